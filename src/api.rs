@@ -0,0 +1,148 @@
+/*!
+HTTP API server
+
+Lightweight JSON API exposing bridge health and capability introspection.
+
+Routes:
+- `GET /health` - component health status
+- `GET /capabilities` - supported networks, tokens, schemes, and features
+- `GET /maintenance` - current or upcoming maintenance window
+- `GET /collateral` - collateral locked on L1 per token
+*/
+
+use crate::bridge::GhostBridge;
+use crate::error::{BridgeError, NetworkError, Result};
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde::Serialize;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tracing::{debug, info, warn};
+
+/// API routes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Route {
+    Health,
+    Capabilities,
+    Maintenance,
+    Collateral,
+    NotFound,
+}
+
+impl Route {
+    fn resolve(method: &Method, path: &str) -> Self {
+        match (method, path.trim_end_matches('/')) {
+            (&Method::GET, "/health") => Route::Health,
+            (&Method::GET, "/capabilities") => Route::Capabilities,
+            (&Method::GET, "/maintenance") => Route::Maintenance,
+            (&Method::GET, "/collateral") => Route::Collateral,
+            _ => Route::NotFound,
+        }
+    }
+}
+
+/// HTTP API server for a bridge instance
+pub struct ApiServer {
+    bridge: Arc<GhostBridge>,
+    bind_address: SocketAddr,
+}
+
+impl ApiServer {
+    pub fn new(bridge: Arc<GhostBridge>, bind_address: SocketAddr) -> Self {
+        Self { bridge, bind_address }
+    }
+
+    /// Accept and serve connections until the task is cancelled
+    pub async fn serve(&self) -> Result<()> {
+        let listener = TcpListener::bind(self.bind_address)
+            .await
+            .map_err(|e| BridgeError::Network(NetworkError::ConnectionFailed {
+                endpoint: self.bind_address.to_string(),
+                source: Box::new(e),
+            }))?;
+
+        info!("API server listening on {}", self.bind_address);
+
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    warn!("Failed to accept API connection: {}", e);
+                    continue;
+                }
+            };
+
+            let bridge = self.bridge.clone();
+            tokio::spawn(async move {
+                let service = service_fn(move |request| handle(bridge.clone(), request));
+                if let Err(e) = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await
+                {
+                    debug!("API connection from {} closed with error: {}", peer, e);
+                }
+            });
+        }
+    }
+}
+
+async fn handle(
+    bridge: Arc<GhostBridge>,
+    request: Request<Incoming>,
+) -> std::result::Result<Response<Full<Bytes>>, Infallible> {
+    let response = match Route::resolve(request.method(), request.uri().path()) {
+        Route::Health => match bridge.health_check().await {
+            Ok(health) => json_response(StatusCode::OK, &health),
+            Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+        },
+        Route::Capabilities => json_response(StatusCode::OK, &bridge.capabilities()),
+        Route::Maintenance => json_response(StatusCode::OK, &bridge.maintenance().status()),
+        Route::Collateral => json_response(StatusCode::OK, &bridge.locked_collateral()),
+        Route::NotFound => error_response(StatusCode::NOT_FOUND, "not found"),
+    };
+
+    Ok(response)
+}
+
+fn json_response<T: Serialize>(status: StatusCode, body: &T) -> Response<Full<Bytes>> {
+    match serde_json::to_vec(body) {
+        Ok(body) => Response::builder()
+            .status(status)
+            .header("content-type", "application/json")
+            .body(Full::new(Bytes::from(body)))
+            .unwrap_or_default(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Full<Bytes>> {
+    let body = serde_json::json!({ "error": message }).to_string();
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(body)))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_resolution() {
+        assert_eq!(Route::resolve(&Method::GET, "/capabilities"), Route::Capabilities);
+        assert_eq!(Route::resolve(&Method::GET, "/capabilities/"), Route::Capabilities);
+        assert_eq!(Route::resolve(&Method::GET, "/health"), Route::Health);
+        assert_eq!(Route::resolve(&Method::GET, "/maintenance"), Route::Maintenance);
+        assert_eq!(Route::resolve(&Method::GET, "/collateral"), Route::Collateral);
+        assert_eq!(Route::resolve(&Method::POST, "/capabilities"), Route::NotFound);
+        assert_eq!(Route::resolve(&Method::GET, "/unknown"), Route::NotFound);
+    }
+}
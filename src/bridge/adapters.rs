@@ -0,0 +1,610 @@
+/*!
+Pluggable source-chain adapters
+
+Everything the bridge does on a chain other than GhostChain and GhostPlane
+goes through a `ChainAdapter`: fetching deposits, confirming that a deposit
+is included, submitting withdrawals, and reading state. Supporting a new
+chain means implementing the trait and registering it with the bridge; the
+routing in `GhostBridge` only asks the registry whether an adapter serves a
+network. The built-in `EvmChainAdapter` serves Ethereum, Polygon, Arbitrum,
+and custom EVM networks it has a client for, confirming deposits and paying
+out withdrawals through the `L1ChainClient` configured for each chain.
+
+Deposits are found by scanning a range of blocks. Each scan reports where
+the next one should start, so a caller that keeps the cursor never sees a
+deposit twice. The EVM deposit event only names the bridge transaction it
+pays for, so the EVM adapter returns the deposits of bridge transactions it
+was told to expect.
+*/
+
+use crate::bridge::config::{BridgeConfig, TokenConfig};
+use crate::bridge::decimals;
+use crate::bridge::l1_client::{DepositEvent, EvmRpcClient, InclusionPolicy, L1ChainClient};
+use crate::bridge::state_reads::CrossChainStateReader;
+use crate::error::{BridgeError, CrossChainError, Result};
+use crate::types::{ChainId, Network, TokenAmount, Transaction, TransactionHash, TransactionReceipt};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Deposit found by a scan, with the block it was made in
+#[derive(Debug, Clone)]
+pub struct ObservedDeposit {
+    pub transaction: Transaction,
+    pub block_number: u64,
+}
+
+/// Deposits found in a range of blocks
+#[derive(Debug, Clone, Default)]
+pub struct DepositScan {
+    pub deposits: Vec<ObservedDeposit>,
+    /// First block the scan did not cover; start the next scan there
+    pub next_block: u64,
+}
+
+/// Source-chain operations the bridge needs
+#[async_trait]
+pub trait ChainAdapter: Send + Sync {
+    /// Adapter name for logging
+    fn name(&self) -> &str;
+
+    /// Whether this adapter serves `network`
+    fn handles(&self, network: &Network) -> bool;
+
+    /// Deposits into the bridge on `network` from `from_block` up to what is
+    /// safe to act on
+    async fn fetch_deposits(&self, network: &Network, from_block: u64) -> Result<DepositScan>;
+
+    /// Receipt of a deposit once it is included on the source chain
+    async fn confirm_inclusion(&self, transaction: &Transaction) -> Result<TransactionReceipt>;
+
+    /// Pay out a withdrawal from L2 on this chain
+    async fn submit_withdrawal(&self, transaction: &Transaction) -> Result<TransactionReceipt>;
+
+    /// Read chain state; `at_head` accepts values that may still be reorged away
+    async fn read_state(&self, network: &Network, _key: &[u8], _at_head: bool) -> Result<Vec<u8>> {
+        Err(unsupported(network))
+    }
+}
+
+fn unsupported(network: &Network) -> BridgeError {
+    BridgeError::CrossChain(CrossChainError::UnsupportedChain {
+        chain_id: network.chain_id().map_or(0, |chain_id| chain_id.0),
+    })
+}
+
+/// Registered adapters; the most recently registered adapter for a network wins
+pub struct ChainAdapterRegistry {
+    adapters: RwLock<Vec<Arc<dyn ChainAdapter>>>,
+}
+
+impl ChainAdapterRegistry {
+    /// Registry without any adapters
+    pub fn empty() -> Self {
+        Self { adapters: RwLock::new(Vec::new()) }
+    }
+
+    /// Registry with the built-in EVM adapter
+    pub fn new() -> Self {
+        let registry = Self::empty();
+        registry.register(Arc::new(EvmChainAdapter::new()));
+        registry
+    }
+
+    pub fn register(&self, adapter: Arc<dyn ChainAdapter>) {
+        info!("Registered chain adapter {}", adapter.name());
+        self.adapters.write().insert(0, adapter);
+    }
+
+    /// Adapter serving `network`, if any
+    pub fn adapter_for(&self, network: &Network) -> Option<Arc<dyn ChainAdapter>> {
+        self.adapters.read().iter().find(|adapter| adapter.handles(network)).cloned()
+    }
+
+    /// Adapter serving `network`, or an unsupported-chain error
+    pub fn require(&self, network: &Network) -> Result<Arc<dyn ChainAdapter>> {
+        self.adapter_for(network).ok_or_else(|| unsupported(network))
+    }
+
+    /// Deposit from an adapter-served chain into GhostPlane
+    pub fn is_deposit(&self, transaction: &Transaction) -> bool {
+        matches!(transaction.to_chain, Network::GhostPlane { .. })
+            && self.adapter_for(&transaction.from_chain).is_some()
+    }
+
+    /// Withdrawal from GhostPlane to an adapter-served chain
+    pub fn is_withdrawal(&self, transaction: &Transaction) -> bool {
+        matches!(transaction.from_chain, Network::GhostPlane { .. })
+            && self.adapter_for(&transaction.to_chain).is_some()
+    }
+
+    /// Names of the registered adapters, highest precedence first
+    pub fn names(&self) -> Vec<String> {
+        self.adapters.read().iter().map(|adapter| adapter.name().to_string()).collect()
+    }
+}
+
+impl Default for ChainAdapterRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Ethereum, Polygon, Arbitrum, and custom EVM networks
+pub struct EvmChainAdapter {
+    state_reader: RwLock<Option<Arc<CrossChainStateReader>>>,
+    /// L1 client and inclusion policy per chain
+    clients: HashMap<ChainId, (Arc<dyn L1ChainClient>, InclusionPolicy)>,
+    /// Per-chain token decimals payouts are denominated in
+    token_config: TokenConfig,
+    /// Bridge transactions waiting for their deposit, by bridge transaction hash
+    expected_deposits: RwLock<HashMap<TransactionHash, Transaction>>,
+}
+
+impl EvmChainAdapter {
+    pub fn new() -> Self {
+        Self {
+            state_reader: RwLock::new(None),
+            clients: HashMap::new(),
+            token_config: TokenConfig::default(),
+            expected_deposits: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Adapter with a JSON-RPC client for each configured Ethereum, Polygon,
+    /// or Arbitrum network. A network whose bridge contract or vault doesn't
+    /// parse is left without a client, so bridging on it is refused.
+    pub fn from_config(config: &BridgeConfig) -> Self {
+        let adapter = Self { token_config: config.token_config.clone(), ..Self::new() };
+        config.networks.values().fold(adapter, |adapter, network| match EvmRpcClient::for_network(network) {
+            Ok(Some(client)) => {
+                adapter.with_client(network.chain_id.clone(), Arc::new(client), InclusionPolicy::for_network(network))
+            }
+            Ok(None) => adapter,
+            Err(e) => {
+                warn!("No L1 client for chain {}: {}", network.chain_id.0, e);
+                adapter
+            }
+        })
+    }
+
+    /// Submit transactions on `chain_id` through `client`
+    pub fn with_client(mut self, chain_id: ChainId, client: Arc<dyn L1ChainClient>, policy: InclusionPolicy) -> Self {
+        self.clients.insert(chain_id, (client, policy));
+        self
+    }
+
+    /// Serve state reads through `reader`
+    pub fn with_state_reader(self, reader: CrossChainStateReader) -> Self {
+        self.set_state_reader(reader);
+        self
+    }
+
+    /// Serve state reads through `reader` from now on
+    pub fn set_state_reader(&self, reader: CrossChainStateReader) {
+        *self.state_reader.write() = Some(Arc::new(reader));
+    }
+
+    /// Report `transaction`'s deposit from `fetch_deposits` once the user makes it
+    pub fn expect_deposit(&self, transaction: Transaction) {
+        self.expected_deposits.write().insert(transaction.hash(), transaction);
+    }
+
+    /// Stop looking for `transaction`'s deposit, e.g. once it is bridged
+    pub fn forget_deposit(&self, transaction: &Transaction) {
+        self.expected_deposits.write().remove(&transaction.hash());
+    }
+
+    /// Expected deposits among `events` made on `network`
+    fn expected(&self, network: &Network, events: &[DepositEvent]) -> Vec<ObservedDeposit> {
+        let expected = self.expected_deposits.read();
+        events.iter()
+            .filter_map(|event| {
+                let transaction = expected.get(&event.bridge_tx_hash)?;
+                (transaction.from_chain.chain_id() == network.chain_id()).then(|| ObservedDeposit {
+                    transaction: transaction.clone(),
+                    block_number: event.block_number,
+                })
+            })
+            .collect()
+    }
+
+    fn client(&self, network: &Network) -> Result<(ChainId, &Arc<dyn L1ChainClient>, &InclusionPolicy)> {
+        let chain_id = network.chain_id().ok_or_else(|| unsupported(network))?;
+        let (client, policy) = self.clients.get(&chain_id).ok_or_else(|| {
+            BridgeError::CrossChain(CrossChainError::ChainUnavailable { chain_id: chain_id.0 })
+        })?;
+        Ok((chain_id, client, policy))
+    }
+}
+
+impl Default for EvmChainAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ChainAdapter for EvmChainAdapter {
+    fn name(&self) -> &str {
+        "evm"
+    }
+
+    fn handles(&self, network: &Network) -> bool {
+        match network {
+            Network::Ethereum { .. } | Network::Polygon { .. } | Network::Arbitrum { .. } => true,
+            // Only custom networks configured with a client; anything else
+            // is not a chain the bridge can deposit from or pay out on
+            Network::Custom { chain_id, .. } => self.clients.contains_key(chain_id),
+            _ => false,
+        }
+    }
+
+    async fn fetch_deposits(&self, network: &Network, from_block: u64) -> Result<DepositScan> {
+        let (_, client, policy) = self.client(network)?;
+
+        // Only blocks already buried under the confirmation depth
+        let head = client.get_block_number().await?;
+        let Some(to_block) = head.checked_sub(policy.confirmations.saturating_sub(1)).filter(|to| *to >= from_block) else {
+            return Ok(DepositScan { deposits: Vec::new(), next_block: from_block });
+        };
+
+        let events = client.deposit_events(from_block, to_block).await?;
+        Ok(DepositScan { deposits: self.expected(network, &events), next_block: to_block + 1 })
+    }
+
+    async fn confirm_inclusion(&self, transaction: &Transaction) -> Result<TransactionReceipt> {
+        // The user made the deposit; only find and check it, never send one
+        let (chain_id, client, policy) = self.client(&transaction.from_chain)?;
+        policy.confirm_deposit(client.as_ref(), &chain_id, transaction).await
+    }
+
+    async fn submit_withdrawal(&self, transaction: &Transaction) -> Result<TransactionReceipt> {
+        let (chain_id, client, policy) = self.client(&transaction.to_chain)?;
+
+        // Pay out in the destination chain's decimals whatever the amount arrived in
+        let token_type = transaction.amount.token_type;
+        let decimals = self.token_config.decimals_on(token_type, &transaction.to_chain);
+        let payout = Transaction {
+            amount: TokenAmount {
+                token_type,
+                amount: decimals::scale_amount(token_type, &transaction.amount.amount, transaction.amount.decimals, decimals)?,
+                decimals,
+            },
+            ..transaction.clone()
+        };
+        policy.submit_and_wait(client.as_ref(), &chain_id, &payout).await
+    }
+
+    async fn read_state(&self, network: &Network, key: &[u8], at_head: bool) -> Result<Vec<u8>> {
+        let reader = self.state_reader.read().clone();
+        match (reader, network.chain_id()) {
+            (Some(reader), Some(chain_id)) => Ok(reader.read(&chain_id, key, at_head).await?.value),
+            _ => Err(unsupported(network)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bridge::l1_client::{deposit_topic, L1TransactionStatus};
+    use crate::types::{fixtures, Address, BitcoinNetwork, LogEntry, TokenType, TransactionHash, U256};
+    use parking_lot::Mutex;
+    use std::time::Duration;
+
+    /// Bitcoin adapter backed by an in-memory deposit list
+    struct StubBitcoinAdapter {
+        deposits: Vec<Transaction>,
+        withdrawals: Mutex<Vec<Transaction>>,
+    }
+
+    #[async_trait]
+    impl ChainAdapter for StubBitcoinAdapter {
+        fn name(&self) -> &str {
+            "bitcoin"
+        }
+
+        fn handles(&self, network: &Network) -> bool {
+            matches!(network, Network::Bitcoin { .. })
+        }
+
+        async fn fetch_deposits(&self, _network: &Network, from_block: u64) -> Result<DepositScan> {
+            let deposits = self.deposits.iter()
+                .map(|transaction| ObservedDeposit { transaction: transaction.clone(), block_number: 840_000 })
+                .filter(|deposit| deposit.block_number >= from_block)
+                .collect();
+            Ok(DepositScan { deposits, next_block: 840_001 })
+        }
+
+        async fn confirm_inclusion(&self, transaction: &Transaction) -> Result<TransactionReceipt> {
+            Ok(TransactionReceipt {
+                transaction_hash: transaction.hash(),
+                block_number: 840_000,
+                block_hash: [1u8; 32],
+                transaction_index: 3,
+                gas_used: 0,
+                success: true,
+                logs: vec![],
+            })
+        }
+
+        async fn submit_withdrawal(&self, transaction: &Transaction) -> Result<TransactionReceipt> {
+            self.withdrawals.lock().push(transaction.clone());
+            self.confirm_inclusion(transaction).await
+        }
+    }
+
+    fn transaction(from_chain: Network, to_chain: Network) -> Transaction {
+        Transaction { from_chain, to_chain, nonce: 1, ..fixtures::transfer(1, 2, 5_000) }
+    }
+
+    #[tokio::test]
+    async fn test_custom_adapter_sources_and_settles_bridges() {
+        let bitcoin = Network::Bitcoin { network: BitcoinNetwork::Mainnet };
+        let ghostplane = Network::GhostPlane { chain_id: ChainId::GHOSTPLANE };
+        let deposit = transaction(bitcoin.clone(), ghostplane.clone());
+
+        let registry = ChainAdapterRegistry::new();
+        assert!(!registry.is_deposit(&deposit));
+        assert!(registry.require(&bitcoin).is_err());
+
+        registry.register(Arc::new(StubBitcoinAdapter {
+            deposits: vec![deposit.clone()],
+            withdrawals: Mutex::new(Vec::new()),
+        }));
+        assert_eq!(registry.names(), vec!["bitcoin", "evm"]);
+
+        // Deposits fetched from the new chain are routed through its adapter
+        let adapter = registry.require(&bitcoin).unwrap();
+        let scan = adapter.fetch_deposits(&bitcoin, 0).await.unwrap();
+        assert_eq!(scan.deposits.len(), 1);
+        let fetched = &scan.deposits[0].transaction;
+        assert!(registry.is_deposit(fetched));
+        let receipt = adapter.confirm_inclusion(fetched).await.unwrap();
+        assert_eq!(receipt.transaction_hash, deposit.hash());
+        assert_eq!(receipt.block_number, 840_000);
+
+        let withdrawal = transaction(ghostplane, bitcoin.clone());
+        assert!(registry.is_withdrawal(&withdrawal));
+        registry.require(&withdrawal.to_chain).unwrap().submit_withdrawal(&withdrawal).await.unwrap();
+
+        // Built-in EVM chains are unaffected
+        let ethereum = Network::Ethereum { chain_id: ChainId::ETHEREUM };
+        assert_eq!(registry.require(&ethereum).unwrap().name(), "evm");
+        assert!(registry.require(&bitcoin).unwrap().read_state(&bitcoin, b"key", false).await.is_err());
+    }
+
+    /// L1 that includes each transaction after `pending_polls` receipt polls, or drops it
+    struct StubL1Client {
+        pending_polls: usize,
+        drop: bool,
+        polls: Mutex<usize>,
+        /// Logs of the user's deposit, if they made one
+        deposit_logs: Option<Vec<LogEntry>>,
+        payouts: Mutex<Vec<Transaction>>,
+        head: u64,
+    }
+
+    impl StubL1Client {
+        fn new(pending_polls: usize, drop: bool) -> Self {
+            Self {
+                pending_polls,
+                drop,
+                polls: Mutex::new(0),
+                deposit_logs: None,
+                payouts: Mutex::new(Vec::new()),
+                head: 19_000_001,
+            }
+        }
+
+        /// The bridge contract's event for a deposit of `amount` from `from` towards `transaction`
+        fn deposited(mut self, transaction: &Transaction, from: &Address, amount: u64) -> Self {
+            let mut from_word = [0u8; 32];
+            from_word[12..].copy_from_slice(&from.0);
+            self.deposit_logs = Some(vec![LogEntry {
+                address: Address(CONTRACT),
+                topics: vec![deposit_topic(), transaction.hash().0, from_word],
+                data: U256::from(amount).0.to_vec(),
+            }]);
+            self
+        }
+    }
+
+    const CONTRACT: [u8; 20] = [0xbb; 20];
+
+    #[async_trait]
+    impl L1ChainClient for StubL1Client {
+        fn name(&self) -> &str {
+            "stub"
+        }
+
+        fn bridge_contract(&self) -> &Address {
+            static BRIDGE: Address = Address(CONTRACT);
+            &BRIDGE
+        }
+
+        async fn find_deposit(&self, _transaction: &Transaction) -> Result<Option<TransactionHash>> {
+            Ok(self.deposit_logs.as_ref().map(|_| TransactionHash([7u8; 32])))
+        }
+
+        async fn submit_transaction(&self, transaction: &Transaction) -> Result<TransactionHash> {
+            self.payouts.lock().push(transaction.clone());
+            Ok(TransactionHash([8u8; 32]))
+        }
+
+        async fn deposit_events(&self, from_block: u64, to_block: u64) -> Result<Vec<DepositEvent>> {
+            let events = self.deposit_logs.iter().flatten().map(|log| DepositEvent {
+                bridge_tx_hash: TransactionHash(log.topics[1]),
+                l1_tx_hash: TransactionHash([7u8; 32]),
+                block_number: 19_000_001,
+            });
+            Ok(events.filter(|event| (from_block..=to_block).contains(&event.block_number)).collect())
+        }
+
+        async fn get_receipt(&self, tx_hash: &TransactionHash) -> Result<L1TransactionStatus> {
+            let mut polls = self.polls.lock();
+            *polls += 1;
+            if *polls <= self.pending_polls {
+                return Ok(L1TransactionStatus::Pending);
+            }
+            if self.drop {
+                return Ok(L1TransactionStatus::Dropped);
+            }
+            Ok(L1TransactionStatus::Included(TransactionReceipt {
+                transaction_hash: tx_hash.clone(),
+                block_number: 19_000_001,
+                block_hash: [9u8; 32],
+                transaction_index: 4,
+                gas_used: 52_000,
+                success: true,
+                logs: self.deposit_logs.clone().unwrap_or_default(),
+            }))
+        }
+
+        async fn get_block_number(&self) -> Result<u64> {
+            Ok(self.head)
+        }
+    }
+
+    fn policy(confirmations: u64) -> InclusionPolicy {
+        InclusionPolicy { poll_interval: Duration::from_millis(1), timeout: Duration::from_millis(50), confirmations }
+    }
+
+    #[tokio::test]
+    async fn test_evm_adapter_confirms_the_users_own_deposit() {
+        let ethereum = Network::Ethereum { chain_id: ChainId::ETHEREUM };
+        let ghostplane = Network::GhostPlane { chain_id: ChainId::GHOSTPLANE };
+        let deposit = transaction(ethereum.clone(), ghostplane.clone());
+        let confirm = |client: StubL1Client, confirmations| {
+            let client = Arc::new(client);
+            let adapter = EvmChainAdapter::new().with_client(ChainId::ETHEREUM, client.clone(), policy(confirmations));
+            let deposit = deposit.clone();
+            async move { (adapter.confirm_inclusion(&deposit).await, client) }
+        };
+
+        // Real block, hash, and gas of the user's transaction; nothing is sent
+        let stub = StubL1Client::new(2, false).deposited(&deposit, &deposit.from_address, 5_000);
+        let (receipt, client) = confirm(stub, 1).await;
+        let receipt = receipt.unwrap();
+        assert_eq!(receipt.transaction_hash, TransactionHash([7u8; 32]));
+        assert_eq!((receipt.block_number, receipt.block_hash, receipt.gas_used), (19_000_001, [9u8; 32], 52_000));
+        assert!(client.payouts.lock().is_empty());
+
+        // Short of the confirmation depth, still waiting when the policy gives up
+        let stub = StubL1Client::new(0, false).deposited(&deposit, &deposit.from_address, 5_000);
+        let (error, _) = confirm(stub, 3).await;
+        assert!(matches!(error, Err(BridgeError::CrossChain(CrossChainError::L1InclusionTimeout { .. }))));
+
+        // Underpaid deposit, or none at all
+        let stub = StubL1Client::new(0, false).deposited(&deposit, &deposit.from_address, 4_999);
+        let (error, _) = confirm(stub, 1).await;
+        assert!(matches!(error, Err(BridgeError::CrossChain(CrossChainError::DepositMismatch { chain_id: 1, .. }))));
+        let (error, _) = confirm(StubL1Client::new(0, false), 1).await;
+        assert!(matches!(error, Err(BridgeError::CrossChain(CrossChainError::DepositNotFound { chain_id: 1, .. }))));
+
+        // No client configured for the chain
+        let adapter = EvmChainAdapter::new();
+        let custom = Network::Custom { chain_id: ChainId(8453), name: "base".to_string(), rpc_url: String::new() };
+        let error = adapter.confirm_inclusion(&transaction(custom, ghostplane)).await.unwrap_err();
+        assert!(matches!(error, BridgeError::CrossChain(CrossChainError::ChainUnavailable { chain_id: 8453 })));
+    }
+
+    #[tokio::test]
+    async fn test_evm_adapter_pays_withdrawals_in_destination_decimals() {
+        let ghostplane = Network::GhostPlane { chain_id: ChainId::GHOSTPLANE };
+        let ethereum = Network::Ethereum { chain_id: ChainId::ETHEREUM };
+        let mut token_config = TokenConfig::default();
+        token_config.gcc.chain_decimals.insert(ChainId::ETHEREUM, 6);
+
+        let ethereum_client = Arc::new(StubL1Client::new(1, false));
+        let adapter = EvmChainAdapter { token_config, ..EvmChainAdapter::new() }
+            .with_client(ChainId::ETHEREUM, ethereum_client.clone(), policy(1))
+            .with_client(ChainId(137), Arc::new(StubL1Client::new(0, true)), policy(1))
+            .with_client(ChainId(42161), Arc::new(StubL1Client::new(usize::MAX, false)), policy(1));
+
+        // 2.5 GCC leaves L2 in 18 decimals and is paid out in 6
+        let mut withdrawal = transaction(ghostplane.clone(), ethereum);
+        withdrawal.amount = TokenAmount { token_type: TokenType::Gcc, amount: U256::from(2_500_000_000_000_000_000u64), decimals: 18 };
+        let receipt = adapter.submit_withdrawal(&withdrawal).await.unwrap();
+        assert_eq!(receipt.transaction_hash, TransactionHash([8u8; 32]));
+        let payout = ethereum_client.payouts.lock()[0].clone();
+        assert_eq!((payout.amount.amount, payout.amount.decimals), (U256::from(2_500_000u64), 6));
+        assert_eq!(payout.id, withdrawal.id);
+
+        let polygon = transaction(ghostplane.clone(), Network::Polygon { chain_id: ChainId(137) });
+        let error = adapter.submit_withdrawal(&polygon).await.unwrap_err();
+        assert!(matches!(error, BridgeError::CrossChain(CrossChainError::L1TransactionDropped { chain_id: 137, .. })));
+
+        let arbitrum = transaction(ghostplane, Network::Arbitrum { chain_id: ChainId(42161) });
+        let error = adapter.submit_withdrawal(&arbitrum).await.unwrap_err();
+        assert!(error.is_l1_outcome_unknown());
+    }
+
+    #[tokio::test]
+    async fn test_evm_adapter_scans_expected_deposits_behind_the_confirmation_depth() {
+        let ethereum = Network::Ethereum { chain_id: ChainId::ETHEREUM };
+        let ghostplane = Network::GhostPlane { chain_id: ChainId::GHOSTPLANE };
+        let deposit = transaction(ethereum.clone(), ghostplane.clone());
+        let stranger = Transaction { nonce: 2, ..deposit.clone() };
+
+        // Both deposits land in block 19_000_001; only one is for a bridge
+        // transaction the adapter expects
+        let logs: Vec<LogEntry> = [&deposit, &stranger].into_iter()
+            .flat_map(|t| StubL1Client::new(0, false).deposited(t, &t.from_address, 5_000).deposit_logs.unwrap())
+            .collect();
+        let scan_at = |head: u64, confirmations: u64| {
+            let client = StubL1Client { head, deposit_logs: Some(logs.clone()), ..StubL1Client::new(0, false) };
+            EvmChainAdapter::new().with_client(ChainId::ETHEREUM, Arc::new(client), policy(confirmations))
+        };
+
+        // The deposit's block is not yet buried deep enough: nothing is
+        // scanned and the cursor stays put
+        let adapter = scan_at(19_000_002, 3);
+        adapter.expect_deposit(deposit.clone());
+        let scan = adapter.fetch_deposits(&ethereum, 19_000_001).await.unwrap();
+        assert!(scan.deposits.is_empty());
+        assert_eq!(scan.next_block, 19_000_001);
+
+        // Once it is, only the expected deposit is reported, and the cursor
+        // moves past the scanned blocks
+        let adapter = scan_at(19_000_003, 3);
+        adapter.expect_deposit(deposit.clone());
+        let scan = adapter.fetch_deposits(&ethereum, scan.next_block).await.unwrap();
+        assert_eq!(scan.deposits.len(), 1);
+        assert_eq!(scan.deposits[0].transaction.hash(), deposit.hash());
+        assert_eq!(scan.deposits[0].block_number, 19_000_001);
+        assert_eq!(scan.next_block, 19_000_002);
+
+        // Scanning on from the cursor does not report it again
+        let scan = adapter.fetch_deposits(&ethereum, scan.next_block).await.unwrap();
+        assert!(scan.deposits.is_empty());
+        assert_eq!(scan.next_block, 19_000_002);
+
+        adapter.forget_deposit(&deposit);
+        assert!(adapter.fetch_deposits(&ethereum, 19_000_000).await.unwrap().deposits.is_empty());
+    }
+
+    #[test]
+    fn test_custom_networks_need_a_configured_client() {
+        let configured = Network::Custom { chain_id: ChainId(7777), name: "ghostnet".to_string(), rpc_url: String::new() };
+        let unknown = Network::Custom { chain_id: ChainId(8888), name: "elsewhere".to_string(), rpc_url: String::new() };
+        let ghostplane = Network::GhostPlane { chain_id: ChainId::GHOSTPLANE };
+
+        let registry = ChainAdapterRegistry::new();
+        registry.register(Arc::new(
+            EvmChainAdapter::new().with_client(ChainId(7777), Arc::new(StubL1Client::new(0, false)), policy(1)),
+        ));
+
+        assert!(registry.require(&configured).is_ok());
+        assert!(registry.is_deposit(&transaction(configured.clone(), ghostplane.clone())));
+        assert!(registry.is_withdrawal(&transaction(ghostplane.clone(), configured)));
+
+        // A custom network nobody configured is not a bridge route
+        assert!(registry.require(&unknown).is_err());
+        assert!(!registry.is_deposit(&transaction(unknown.clone(), ghostplane.clone())));
+        assert!(!registry.is_withdrawal(&transaction(ghostplane, unknown)));
+    }
+}
@@ -0,0 +1,342 @@
+/*!
+Allowances for delegated bridging
+
+A contract or relayer may bridge a user's funds only up to an amount the
+user approved for that spender and token, as with ERC-20 allowances. Each
+delegated bridge draws down the allowance; a bridge that fails before any
+leg moves funds gives the amount back. Approving replaces the previous
+allowance, and revoking removes it.
+
+The spender is never taken on the caller's word: it is the address of the
+secp256k1 key that signed the transaction's delegation message, derived the
+Ethereum way from the Keccak-256 of the public key.
+*/
+
+use crate::error::{BridgeError, CrossChainError, Result, SecurityError};
+use crate::types::{Address, BridgeReceipt, BridgeStatus, TokenAmount, TokenType, Transaction, U256};
+use parking_lot::Mutex;
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use sha3::{Digest, Keccak256};
+use std::collections::{HashMap, HashSet};
+use tracing::debug;
+use uuid::Uuid;
+
+/// Domain tag for the message a spender signs, so the signature cannot be
+/// passed off as one over anything else
+const DELEGATION_DOMAIN: &[u8] = b"GhostBridge delegated bridge v1";
+
+/// Message a spender signs to bridge `transaction` on its owner's behalf.
+/// Binds the transaction id and everything it moves.
+pub fn delegation_message(transaction: &Transaction) -> Vec<u8> {
+    let mut message = DELEGATION_DOMAIN.to_vec();
+    message.extend_from_slice(transaction.id.as_bytes());
+    message.extend_from_slice(&transaction.content_hash().0);
+    message
+}
+
+/// Address that signed `transaction`'s delegation message with a 65-byte
+/// `r || s || v` secp256k1 signature over its Keccak-256 digest
+pub fn recover_spender(transaction: &Transaction, signature: &[u8]) -> Result<Address> {
+    let invalid = || BridgeError::Security(SecurityError::SignatureVerificationFailed);
+    if signature.len() != 65 {
+        return Err(invalid());
+    }
+    let recovery_id = match signature[64] {
+        v @ (0 | 1) => v,
+        v @ (27 | 28) => v - 27,
+        _ => return Err(invalid()),
+    };
+    let recovery_id = RecoveryId::from_i32(recovery_id as i32).map_err(|_| invalid())?;
+    let signature = RecoverableSignature::from_compact(&signature[..64], recovery_id).map_err(|_| invalid())?;
+
+    let digest: [u8; 32] = Keccak256::digest(delegation_message(transaction)).into();
+    let public_key = secp256k1::Secp256k1::verification_only()
+        .recover_ecdsa(&secp256k1::Message::from_digest(digest), &signature)
+        .map_err(|_| invalid())?;
+    Ok(address_of(&public_key))
+}
+
+/// Ethereum address of a secp256k1 public key
+fn address_of(public_key: &secp256k1::PublicKey) -> Address {
+    let digest = Keccak256::digest(&public_key.serialize_uncompressed()[1..]);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&digest[12..]);
+    Address(address)
+}
+
+/// An amount drawn from an allowance for one delegated bridge
+#[derive(Debug, Clone)]
+pub struct DelegatedSpend {
+    transaction_id: Uuid,
+    owner: Address,
+    spender: Address,
+    amount: TokenAmount,
+}
+
+/// Remaining allowances by owner, spender, and token
+#[derive(Debug, Default)]
+pub struct AllowanceRegistry {
+    allowances: Mutex<HashMap<(Address, Address, TokenType), U256>>,
+    /// Delegated transactions already drawn against an allowance, so a
+    /// signed submission cannot be replayed to drain it
+    submitted: Mutex<HashSet<Uuid>>,
+}
+
+impl AllowanceRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Let `spender` bridge up to `amount` of the owner's tokens
+    pub fn approve(&self, owner: &Address, spender: &Address, amount: &TokenAmount) {
+        debug!("{} approved {} to bridge {} {}", owner, spender, amount.amount, amount.token_type);
+        self.allowances.lock().insert(
+            (owner.clone(), spender.clone(), amount.token_type),
+            amount.amount.clone(),
+        );
+    }
+
+    /// Remove a spender's allowance for a token
+    pub fn revoke(&self, owner: &Address, spender: &Address, token_type: TokenType) {
+        debug!("{} revoked {}'s {} allowance", owner, spender, token_type);
+        self.allowances.lock().remove(&(owner.clone(), spender.clone(), token_type));
+    }
+
+    /// Amount `spender` may still bridge for `owner`
+    pub fn allowance(&self, owner: &Address, spender: &Address, token_type: TokenType) -> U256 {
+        self.allowances.lock()
+            .get(&(owner.clone(), spender.clone(), token_type))
+            .cloned()
+            .unwrap_or(U256::ZERO)
+    }
+
+    /// Draw `amount` from the allowance, failing without change if it exceeds it
+    pub fn consume(&self, owner: &Address, spender: &Address, amount: &TokenAmount) -> Result<()> {
+        let mut allowances = self.allowances.lock();
+        let key = (owner.clone(), spender.clone(), amount.token_type);
+        let allowed = allowances.get(&key).cloned().unwrap_or(U256::ZERO);
+
+        let remaining = allowed.checked_sub(&amount.amount).ok_or_else(|| {
+            BridgeError::CrossChain(CrossChainError::AllowanceExceeded {
+                owner: owner.to_string(),
+                spender: spender.to_string(),
+                token: amount.token_type.to_string(),
+                allowed: allowed.to_string(),
+                attempted: amount.amount.to_string(),
+            })
+        })?;
+        allowances.insert(key, remaining);
+        Ok(())
+    }
+
+    /// Give back an amount drawn for a bridge that did not go through
+    pub fn restore(&self, owner: &Address, spender: &Address, amount: &TokenAmount) {
+        let mut allowances = self.allowances.lock();
+        // Revoked in the meantime: the owner no longer wants the spender to bridge
+        if let Some(allowed) = allowances.get_mut(&(owner.clone(), spender.clone(), amount.token_type)) {
+            *allowed = allowed.checked_add(&amount.amount).unwrap_or(U256([0xff; 32]));
+        }
+    }
+
+    /// Authorize a transaction submitted with `spender_signature`. Returns
+    /// `None` when the owner signed it themselves; otherwise the amount is
+    /// drawn from the allowance the owner approved for the signer.
+    pub fn authorize(&self, transaction: &Transaction, spender_signature: &[u8]) -> Result<Option<DelegatedSpend>> {
+        let spender = recover_spender(transaction, spender_signature)?;
+        if spender == transaction.from_address {
+            return Ok(None);
+        }
+
+        let mut submitted = self.submitted.lock();
+        if submitted.contains(&transaction.id) {
+            return Err(BridgeError::Security(SecurityError::DelegationReused {
+                transaction_id: transaction.id.to_string(),
+            }));
+        }
+        self.consume(&transaction.from_address, &spender, &transaction.amount)?;
+        submitted.insert(transaction.id);
+
+        Ok(Some(DelegatedSpend {
+            transaction_id: transaction.id,
+            owner: transaction.from_address.clone(),
+            spender,
+            amount: transaction.amount.clone(),
+        }))
+    }
+
+    /// Settle a delegated spend once its bridge has run. The amount is given
+    /// back only if no leg moved funds; once the L1 or L2 side went through,
+    /// the spender has used it even if a later leg failed.
+    pub fn settle(&self, spend: &DelegatedSpend, result: &Result<BridgeReceipt>) {
+        let nothing_moved = match result {
+            Ok(receipt) => {
+                matches!(receipt.status, BridgeStatus::Failed { .. })
+                    && receipt.l1_transaction.is_none()
+                    && receipt.l2_transaction.is_none()
+            }
+            Err(_) => true,
+        };
+        if nothing_moved {
+            self.restore(&spend.owner, &spend.spender, &spend.amount);
+            // The same signed submission may be retried
+            self.submitted.lock().remove(&spend.transaction_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{fixtures, TransactionHash, TransactionReceipt};
+
+    fn gcc(amount: u64) -> TokenAmount {
+        TokenAmount::new(TokenType::Gcc, U256::from(amount))
+    }
+
+    fn keypair(seed: u8) -> (secp256k1::SecretKey, Address) {
+        let secret_key = secp256k1::SecretKey::from_slice(&[seed; 32]).unwrap();
+        let public_key = secret_key.public_key(&secp256k1::Secp256k1::signing_only());
+        (secret_key, address_of(&public_key))
+    }
+
+    fn sign(secret_key: &secp256k1::SecretKey, transaction: &Transaction) -> Vec<u8> {
+        let digest: [u8; 32] = Keccak256::digest(delegation_message(transaction)).into();
+        let signature = secp256k1::Secp256k1::signing_only()
+            .sign_ecdsa_recoverable(&secp256k1::Message::from_digest(digest), secret_key);
+        let (recovery_id, compact) = signature.serialize_compact();
+        let mut bytes = compact.to_vec();
+        bytes.push(recovery_id.to_i32() as u8);
+        bytes
+    }
+
+    /// A transfer of `amount` GCC out of `owner`
+    fn transfer_from(owner: &Address, amount: u64) -> Transaction {
+        Transaction { from_address: owner.clone(), ..fixtures::transfer(1, 2, amount) }
+    }
+
+    fn receipt(status: BridgeStatus, l1: bool, l2: bool) -> BridgeReceipt {
+        let leg = || TransactionReceipt {
+            transaction_hash: TransactionHash([7u8; 32]),
+            block_number: 1,
+            block_hash: [0u8; 32],
+            transaction_index: 0,
+            gas_used: 21_000,
+            success: true,
+            logs: Vec::new(),
+        };
+        BridgeReceipt {
+            bridge_id: Uuid::new_v4(),
+            l1_transaction: l1.then(leg),
+            l2_transaction: l2.then(leg),
+            status,
+            bridged_at: chrono::Utc::now(),
+            settled_at: None,
+        }
+    }
+
+    fn failed() -> BridgeStatus {
+        BridgeStatus::Failed { reason: "test".to_string() }
+    }
+
+    #[test]
+    fn test_delegated_bridging_draws_down_allowance() {
+        let registry = AllowanceRegistry::new();
+        let owner = Address([1u8; 20]);
+        let relayer = Address([2u8; 20]);
+
+        registry.approve(&owner, &relayer, &gcc(1_000));
+        registry.consume(&owner, &relayer, &gcc(600)).unwrap();
+        assert_eq!(registry.allowance(&owner, &relayer, TokenType::Gcc), U256::from(400));
+
+        // Over the remaining allowance, and for a token never approved
+        assert!(registry.consume(&owner, &relayer, &gcc(401)).is_err());
+        assert!(registry.consume(&owner, &relayer, &TokenAmount::new(TokenType::Mana, U256::from(1))).is_err());
+        assert_eq!(registry.allowance(&owner, &relayer, TokenType::Gcc), U256::from(400));
+
+        // A failed bridge gives its amount back
+        registry.restore(&owner, &relayer, &gcc(600));
+        assert_eq!(registry.allowance(&owner, &relayer, TokenType::Gcc), U256::from(1_000));
+    }
+
+    #[test]
+    fn test_revoked_approval_blocks_bridging() {
+        let registry = AllowanceRegistry::new();
+        let owner = Address([1u8; 20]);
+        let relayer = Address([2u8; 20]);
+
+        registry.approve(&owner, &relayer, &gcc(1_000));
+        registry.revoke(&owner, &relayer, TokenType::Gcc);
+
+        let error = registry.consume(&owner, &relayer, &gcc(1)).unwrap_err();
+        assert!(error.to_string().contains("Allowance exceeded"), "{}", error);
+
+        // Nothing comes back after revocation either
+        registry.restore(&owner, &relayer, &gcc(500));
+        assert!(registry.allowance(&owner, &relayer, TokenType::Gcc).is_zero());
+    }
+
+    #[test]
+    fn test_spender_is_recovered_from_signature() {
+        let registry = AllowanceRegistry::new();
+        let (owner_key, owner) = keypair(1);
+        let (relayer_key, relayer) = keypair(2);
+        registry.approve(&owner, &relayer, &gcc(1_000));
+
+        let transaction = transfer_from(&owner, 600);
+        let spend = registry.authorize(&transaction, &sign(&relayer_key, &transaction)).unwrap();
+        assert!(spend.is_some());
+        assert_eq!(registry.allowance(&owner, &relayer, TokenType::Gcc), U256::from(400));
+
+        // Signed by the owner: no allowance involved
+        let own = transfer_from(&owner, 5_000);
+        assert!(registry.authorize(&own, &sign(&owner_key, &own)).unwrap().is_none());
+
+        // A signature over another transaction recovers some other address,
+        // which has no allowance
+        let other = transfer_from(&owner, 100);
+        let forged = sign(&relayer_key, &transfer_from(&owner, 1));
+        assert!(registry.authorize(&other, &forged).is_err());
+        assert!(registry.authorize(&other, &[0u8; 65]).is_err());
+        assert_eq!(registry.allowance(&owner, &relayer, TokenType::Gcc), U256::from(400));
+    }
+
+    #[test]
+    fn test_signed_submission_cannot_be_replayed() {
+        let registry = AllowanceRegistry::new();
+        let (_, owner) = keypair(1);
+        let (relayer_key, relayer) = keypair(2);
+        registry.approve(&owner, &relayer, &gcc(1_000));
+
+        let transaction = transfer_from(&owner, 300);
+        let signature = sign(&relayer_key, &transaction);
+        let spend = registry.authorize(&transaction, &signature).unwrap().unwrap();
+        registry.settle(&spend, &Ok(receipt(BridgeStatus::L2Confirmed, false, true)));
+
+        let error = registry.authorize(&transaction, &signature).unwrap_err();
+        assert!(matches!(error, BridgeError::Security(SecurityError::DelegationReused { .. })));
+        assert_eq!(registry.allowance(&owner, &relayer, TokenType::Gcc), U256::from(700));
+    }
+
+    #[test]
+    fn test_settle_restores_only_when_nothing_moved() {
+        let registry = AllowanceRegistry::new();
+        let (_, owner) = keypair(1);
+        let (relayer_key, relayer) = keypair(2);
+        registry.approve(&owner, &relayer, &gcc(1_000));
+        let spend = |amount| {
+            let transaction = transfer_from(&owner, amount);
+            registry.authorize(&transaction, &sign(&relayer_key, &transaction)).unwrap().unwrap()
+        };
+
+        // Rejected before any leg ran: the amount comes back
+        registry.settle(&spend(100), &Ok(receipt(failed(), false, false)));
+        registry.settle(&spend(100), &Err(BridgeError::internal("rejected")));
+        assert_eq!(registry.allowance(&owner, &relayer, TokenType::Gcc), U256::from(1_000));
+
+        // L1 deposit went through before L2 failed: the spend stands
+        registry.settle(&spend(100), &Ok(receipt(failed(), true, false)));
+        // L2 went through before the L1 payout failed: the spend stands
+        registry.settle(&spend(200), &Ok(receipt(failed(), false, true)));
+        assert_eq!(registry.allowance(&owner, &relayer, TokenType::Gcc), U256::from(700));
+    }
+}
@@ -0,0 +1,151 @@
+/*!
+Runtime capability introspection
+
+Reports what a bridge instance supports so integrators can discover configured
+networks, tokens, signature schemes, and enabled features at runtime.
+*/
+
+use crate::bridge::BridgeConfig;
+use crate::bridge::maintenance::{MaintenanceSchedule, MaintenanceStatus};
+use crate::security::{CryptoProvider, SignatureScheme};
+use crate::types::{ChainId, Network, TokenType};
+use serde::{Deserialize, Serialize};
+
+/// Capabilities of a bridge instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeCapabilities {
+    /// Bridge version
+    pub version: String,
+    /// Configured networks, ordered by chain id
+    pub networks: Vec<NetworkCapability>,
+    /// Tokens supported on at least one configured network
+    pub tokens: Vec<TokenType>,
+    /// Available signature schemes
+    pub signature_schemes: Vec<SignatureScheme>,
+    /// L2 settlement mode
+    pub settlement_mode: SettlementMode,
+    /// Enabled feature flags
+    pub features: FeatureFlags,
+    /// Current or upcoming maintenance window
+    pub maintenance: MaintenanceStatus,
+}
+
+/// A configured network
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkCapability {
+    pub chain_id: ChainId,
+    pub network: Network,
+    pub is_testnet: bool,
+    pub supported_tokens: Vec<TokenType>,
+}
+
+/// How L2 batches are settled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SettlementMode {
+    /// Optimistic execution with fraud proofs
+    Optimistic,
+    /// Validity proofs only
+    ZkRollup,
+    /// Optimistic execution backed by ZK proofs
+    Hybrid,
+    /// Neither optimistic execution nor ZK proofs
+    Direct,
+}
+
+/// Feature flags enabled for this instance
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeatureFlags {
+    pub ffi: bool,
+    pub metrics: bool,
+    pub zk_proofs: bool,
+    pub guardian_auth: bool,
+}
+
+impl BridgeCapabilities {
+    /// Derive capabilities from a bridge configuration
+    pub fn from_config(config: &BridgeConfig) -> Self {
+        let mut networks: Vec<NetworkCapability> = config
+            .networks
+            .values()
+            .map(|network| NetworkCapability {
+                chain_id: network.chain_id.clone(),
+                network: network.network.clone(),
+                is_testnet: network.is_testnet,
+                supported_tokens: network.supported_tokens.clone(),
+            })
+            .collect();
+        networks.sort_by_key(|network| network.chain_id.0);
+
+        let tokens = [TokenType::Gcc, TokenType::Spirit, TokenType::Mana, TokenType::Ghost]
+            .into_iter()
+            .filter(|token| networks.iter().any(|n| n.supported_tokens.contains(token)))
+            .collect();
+
+        let settlement_mode = match (
+            config.l2_config.enable_optimistic_execution,
+            config.l2_config.enable_zk_proofs,
+        ) {
+            (true, true) => SettlementMode::Hybrid,
+            (true, false) => SettlementMode::Optimistic,
+            (false, true) => SettlementMode::ZkRollup,
+            (false, false) => SettlementMode::Direct,
+        };
+
+        Self {
+            version: crate::version().to_string(),
+            networks,
+            tokens,
+            signature_schemes: CryptoProvider::supported_schemes(),
+            settlement_mode,
+            features: FeatureFlags {
+                ffi: crate::has_ffi_support(),
+                metrics: crate::has_metrics_support() && config.enable_metrics,
+                zk_proofs: config.l2_config.enable_zk_proofs,
+                guardian_auth: config.enable_guardian_auth,
+            },
+            maintenance: MaintenanceSchedule::new(&config.maintenance).status(),
+        }
+    }
+
+    /// Whether a chain is configured
+    pub fn supports_chain(&self, chain_id: &ChainId) -> bool {
+        self.networks.iter().any(|network| &network.chain_id == chain_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities_reflect_config() {
+        let mut config = BridgeConfig::default();
+        config.networks.retain(|chain_id, _| *chain_id == ChainId::ETHEREUM || *chain_id == ChainId::GHOSTPLANE);
+        config.l2_config.enable_zk_proofs = false;
+        config.enable_metrics = false;
+
+        let capabilities = BridgeCapabilities::from_config(&config);
+
+        let chain_ids: Vec<u64> = capabilities.networks.iter().map(|n| n.chain_id.0).collect();
+        assert_eq!(chain_ids, vec![1, 10000]);
+        assert!(capabilities.supports_chain(&ChainId::GHOSTPLANE));
+        assert!(!capabilities.supports_chain(&ChainId::GHOSTCHAIN));
+
+        assert_eq!(capabilities.tokens.len(), 4);
+        assert_eq!(capabilities.settlement_mode, SettlementMode::Optimistic);
+        assert!(!capabilities.features.zk_proofs);
+        assert!(!capabilities.features.metrics);
+        assert_eq!(capabilities.features.ffi, crate::has_ffi_support());
+        assert!(capabilities.signature_schemes.contains(&SignatureScheme::Ed25519));
+        assert_eq!(capabilities.maintenance, MaintenanceStatus::Operational);
+
+        let now = chrono::Utc::now();
+        config.maintenance.windows.push(crate::bridge::MaintenanceWindow {
+            start: now - chrono::Duration::minutes(1),
+            end: now + chrono::Duration::hours(1),
+            message: "L1 contract upgrade".to_string(),
+        });
+        let capabilities = BridgeCapabilities::from_config(&config);
+        assert!(matches!(capabilities.maintenance, MaintenanceStatus::InProgress { ref window } if window.message == "L1 contract upgrade"));
+    }
+}
@@ -0,0 +1,262 @@
+/*!
+Locked collateral accounting
+
+Deposits lock tokens in the bridge on L1 to back the supply minted on L2;
+withdrawals release them. The ledger keeps the running total per chain and
+token, custom networks included, so integrators can check that L2 supply is
+fully backed. Opened with a path, the ledger writes every change to disk
+before acknowledging it, so the totals survive a restart.
+*/
+
+use crate::error::{BridgeError, CrossChainError, Result};
+use crate::types::{Network, TokenType, Transaction, U256};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+/// Collateral locked for one token on one chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedCollateral {
+    pub network: Network,
+    pub token_type: TokenType,
+    pub amount: U256,
+}
+
+/// Running total of collateral locked on L1 per chain and token
+#[derive(Debug, Default)]
+pub struct CollateralLedger {
+    locked: Mutex<HashMap<(Network, TokenType), U256>>,
+    /// Serializes updates so snapshots reach disk in order
+    writes: tokio::sync::Mutex<()>,
+    /// Snapshot rewritten on every change; the ledger is in memory only if unset
+    path: Option<PathBuf>,
+}
+
+impl CollateralLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ledger persisted at `path`, starting from what an earlier run recorded
+    pub async fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let locked = match tokio::fs::read(&path).await {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            bytes => {
+                let entries: Vec<LockedCollateral> = serde_json::from_slice(&bytes?)
+                    .map_err(|e| BridgeError::Serialization(e.into()))?;
+                entries.into_iter()
+                    .map(|entry| ((entry.network, entry.token_type), entry.amount))
+                    .collect()
+            }
+        };
+
+        Ok(Self {
+            locked: Mutex::new(locked),
+            writes: tokio::sync::Mutex::new(()),
+            path: Some(path),
+        })
+    }
+
+    /// Record an L1 lock event for a deposit
+    pub async fn record_deposit(&self, transaction: &Transaction) -> Result<()> {
+        let amount = &transaction.amount;
+        self.update(&transaction.from_chain, amount.token_type, |total| {
+            total.checked_add(&amount.amount).ok_or_else(|| {
+                BridgeError::CrossChain(CrossChainError::BridgeOperationFailed {
+                    operation: format!("lock of {} {} overflows locked collateral", amount.amount, amount.token_type),
+                })
+            })
+        }).await
+    }
+
+    /// Record an L1 release for a withdrawal; fails if more is released than
+    /// is locked on the destination chain
+    pub async fn record_withdrawal(&self, transaction: &Transaction) -> Result<()> {
+        let amount = &transaction.amount;
+        self.update(&transaction.to_chain, amount.token_type, |total| {
+            total.checked_sub(&amount.amount).ok_or_else(|| {
+                warn!("Withdrawal {} releases {} {} but only {} is locked",
+                      transaction.id, amount.amount, amount.token_type, total);
+                BridgeError::CrossChain(CrossChainError::BridgeOperationFailed {
+                    operation: format!("release of {} {} exceeds locked collateral", amount.amount, amount.token_type),
+                })
+            })
+        }).await
+    }
+
+    /// Apply `change` to one total, persisting the result before it takes effect
+    async fn update(
+        &self,
+        network: &Network,
+        token_type: TokenType,
+        change: impl FnOnce(&U256) -> Result<U256>,
+    ) -> Result<()> {
+        let _write = self.writes.lock().await;
+        let key = (ledger_network(network), token_type);
+        let mut updated = self.locked.lock().clone();
+        let total = change(updated.get(&key).unwrap_or(&U256::ZERO))?;
+        updated.insert(key, total);
+
+        self.persist(&updated).await?;
+        *self.locked.lock() = updated;
+        Ok(())
+    }
+
+    async fn persist(&self, locked: &HashMap<(Network, TokenType), U256>) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let entries: Vec<LockedCollateral> = locked.iter()
+            .map(|((network, token_type), amount)| LockedCollateral {
+                network: network.clone(),
+                token_type: *token_type,
+                amount: amount.clone(),
+            })
+            .collect();
+        let bytes = serde_json::to_vec(&entries).map_err(|e| BridgeError::Serialization(e.into()))?;
+
+        // Write a new snapshot beside the old one and swap it in, so a crash
+        // leaves one or the other intact
+        let mut temp_path = path.clone().into_os_string();
+        temp_path.push(".tmp");
+        let mut file = tokio::fs::File::create(&temp_path).await?;
+        file.write_all(&bytes).await?;
+        file.sync_all().await?;
+        tokio::fs::rename(&temp_path, path).await?;
+        Ok(())
+    }
+
+    /// Locked amount per token across all chains; tokens never deposited are omitted
+    pub fn locked(&self) -> HashMap<TokenType, U256> {
+        let mut totals: HashMap<TokenType, U256> = HashMap::new();
+        for ((_, token_type), amount) in self.locked.lock().iter() {
+            let total = totals.entry(*token_type).or_insert(U256::ZERO);
+            // Each chain's total fits in a U256, but their sum need not
+            *total = total.checked_add(amount).unwrap_or(U256([0xff; 32]));
+        }
+        totals
+    }
+
+    /// Locked amount per chain and token
+    pub fn locked_by_chain(&self) -> Vec<LockedCollateral> {
+        self.locked.lock().iter()
+            .map(|((network, token_type), amount)| LockedCollateral {
+                network: network.clone(),
+                token_type: *token_type,
+                amount: amount.clone(),
+            })
+            .collect()
+    }
+
+    /// Whether the locked collateral exactly backs the given L2 supply per token
+    pub fn reconciles_with(&self, l2_supply: &HashMap<TokenType, U256>) -> bool {
+        let locked = self.locked();
+        let amount = |map: &HashMap<TokenType, U256>, token| map.get(token).cloned().unwrap_or(U256::ZERO);
+        locked.keys().chain(l2_supply.keys())
+            .all(|token| amount(&locked, token) == amount(l2_supply, token))
+    }
+}
+
+/// A custom network's RPC endpoint is not part of its identity, so collateral
+/// stays on one total when the endpoint changes
+fn ledger_network(network: &Network) -> Network {
+    match network {
+        Network::Custom { chain_id, name, .. } => Network::Custom {
+            chain_id: chain_id.clone(),
+            name: name.clone(),
+            rpc_url: String::new(),
+        },
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{fixtures, ChainId};
+
+    fn bridge(from_chain: Network, to_chain: Network, token_type: TokenType, amount: u64) -> Transaction {
+        let mut transaction = Transaction { from_chain, to_chain, ..fixtures::transfer(1, 2, amount) };
+        transaction.amount.token_type = token_type;
+        transaction
+    }
+
+    fn custom(rpc_url: &str) -> Network {
+        Network::Custom { chain_id: ChainId(7777), name: "ghostnet".to_string(), rpc_url: rpc_url.to_string() }
+    }
+
+    #[tokio::test]
+    async fn test_locked_collateral_tracks_bridged_amounts_per_token() {
+        let ethereum = Network::Ethereum { chain_id: ChainId::ETHEREUM };
+        let ghostplane = Network::GhostPlane { chain_id: ChainId::GHOSTPLANE };
+        let ledger = CollateralLedger::new();
+
+        ledger.record_deposit(&bridge(ethereum.clone(), ghostplane.clone(), TokenType::Gcc, 1_000)).await.unwrap();
+        ledger.record_deposit(&bridge(ethereum.clone(), ghostplane.clone(), TokenType::Gcc, 250)).await.unwrap();
+        ledger.record_deposit(&bridge(ethereum.clone(), ghostplane.clone(), TokenType::Spirit, 40)).await.unwrap();
+        ledger.record_withdrawal(&bridge(ghostplane.clone(), ethereum.clone(), TokenType::Gcc, 200)).await.unwrap();
+
+        let locked = ledger.locked();
+        assert_eq!(locked[&TokenType::Gcc], U256::from(1_050));
+        assert_eq!(locked[&TokenType::Spirit], U256::from(40));
+        assert!(!locked.contains_key(&TokenType::Mana));
+
+        // Releasing more than is locked is refused and leaves the total untouched
+        assert!(ledger.record_withdrawal(&bridge(ghostplane, ethereum, TokenType::Spirit, 41)).await.is_err());
+        assert_eq!(ledger.locked()[&TokenType::Spirit], U256::from(40));
+
+        let l2_supply = HashMap::from([(TokenType::Gcc, U256::from(1_050)), (TokenType::Spirit, U256::from(40))]);
+        assert!(ledger.reconciles_with(&l2_supply));
+        assert!(!ledger.reconciles_with(&HashMap::from([(TokenType::Gcc, U256::from(1_050))])));
+    }
+
+    #[tokio::test]
+    async fn test_collateral_is_per_chain_and_full_width() {
+        let ethereum = Network::Ethereum { chain_id: ChainId::ETHEREUM };
+        let ghostplane = Network::GhostPlane { chain_id: ChainId::GHOSTPLANE };
+        let ledger = CollateralLedger::new();
+
+        // Amounts beyond u64 are kept exactly
+        let mut large = bridge(custom("https://a.example"), ghostplane.clone(), TokenType::Gcc, 0);
+        large.amount.amount = U256::from(u64::MAX).checked_add(&U256::from(u64::MAX)).unwrap();
+        ledger.record_deposit(&large).await.unwrap();
+        ledger.record_deposit(&bridge(ethereum.clone(), ghostplane.clone(), TokenType::Gcc, 5)).await.unwrap();
+
+        // Collateral locked on the custom chain cannot be released on Ethereum
+        let mut drain = bridge(ghostplane.clone(), ethereum, TokenType::Gcc, 0);
+        drain.amount.amount = large.amount.amount.clone();
+        assert!(ledger.record_withdrawal(&drain).await.is_err());
+
+        // ...but can be on the custom chain, whichever endpoint it is reached through
+        drain.to_chain = custom("https://b.example");
+        ledger.record_withdrawal(&drain).await.unwrap();
+        assert_eq!(ledger.locked()[&TokenType::Gcc], U256::from(5));
+
+        let mut overflow = bridge(ghostplane.clone(), ghostplane, TokenType::Gcc, 0);
+        overflow.from_chain = Network::Ethereum { chain_id: ChainId::ETHEREUM };
+        overflow.amount.amount = U256([0xff; 32]);
+        assert!(ledger.record_deposit(&overflow).await.is_err());
+        assert_eq!(ledger.locked()[&TokenType::Gcc], U256::from(5));
+    }
+
+    #[tokio::test]
+    async fn test_collateral_survives_restart() {
+        let path = std::env::temp_dir().join(format!("ghostbridge-collateral-{}.json", uuid::Uuid::new_v4()));
+        let ethereum = Network::Ethereum { chain_id: ChainId::ETHEREUM };
+        let ghostplane = Network::GhostPlane { chain_id: ChainId::GHOSTPLANE };
+
+        let ledger = CollateralLedger::open(&path).await.unwrap();
+        ledger.record_deposit(&bridge(ethereum.clone(), ghostplane.clone(), TokenType::Mana, 300)).await.unwrap();
+        ledger.record_deposit(&bridge(custom("https://a.example"), ghostplane, TokenType::Mana, 20)).await.unwrap();
+        drop(ledger);
+
+        let reopened = CollateralLedger::open(&path).await.unwrap();
+        tokio::fs::remove_file(&path).await.unwrap();
+        assert_eq!(reopened.locked()[&TokenType::Mana], U256::from(320));
+        assert_eq!(reopened.locked_by_chain().len(), 2);
+    }
+}
@@ -5,9 +5,13 @@ Configuration for GhostBridge supporting Ethereum, Bitcoin, Polygon, Arbitrum,
 and custom chains with the 4-token economy integration.
 */
 
+use crate::bridge::fee_quotes::FeeQuoteConfig;
+use crate::bridge::limits::MinimumBridgeAmount;
+use crate::bridge::maintenance::{MaintenanceConfig, MaintenanceWindow};
 use crate::error::{BridgeError, Result};
-use crate::services::ServiceEndpoint;
-use crate::types::{Network, ChainId, TokenType};
+use crate::health::{Criticality, HealthPolicy};
+use crate::services::{ServiceAuth, ServiceEndpoint, ServiceManager};
+use crate::types::{Network, ChainId, TokenType, U256};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
@@ -38,6 +42,30 @@ pub struct BridgeConfig {
     pub max_retries: u32,
     pub enable_guardian_auth: bool,
     pub enable_metrics: bool,
+
+    /// Scheduled maintenance windows
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+
+    /// Which component failures make the bridge unhealthy rather than degraded
+    #[serde(default = "BridgeConfig::default_health_policy")]
+    pub health: HealthPolicy,
+
+    /// Healthy services required before new transactions are accepted; 0 disables the check
+    #[serde(default)]
+    pub min_healthy_services: usize,
+
+    /// How often the bridge re-checks service health in the background
+    #[serde(default = "BridgeConfig::default_health_check_interval")]
+    pub health_check_interval: Duration,
+
+    /// Signed fee quote settings
+    #[serde(default)]
+    pub fee_quotes: FeeQuoteConfig,
+
+    /// File locked collateral totals are persisted to; kept in memory only if unset
+    #[serde(default)]
+    pub collateral_ledger_path: Option<String>,
 }
 
 /// Service endpoint configurations
@@ -63,8 +91,22 @@ pub struct NetworkConfig {
     pub max_gas_price: u64,
     pub supported_tokens: Vec<TokenType>,
     pub bridge_contract: Option<String>,
+    /// Account holding bridged funds that withdrawals are paid from; the RPC
+    /// node, or a signer proxy in front of it, must hold its key
+    #[serde(default)]
+    pub bridge_vault: Option<String>,
     pub is_testnet: bool,
     pub block_time_ms: u64,
+    /// Blocks behind the head that cross-chain reads target (defaults to `confirmation_blocks`)
+    #[serde(default)]
+    pub read_buffer_blocks: Option<u32>,
+}
+
+impl NetworkConfig {
+    /// Reorg-safety buffer for cross-chain state reads
+    pub fn read_buffer_blocks(&self) -> u64 {
+        self.read_buffer_blocks.unwrap_or(self.confirmation_blocks) as u64
+    }
 }
 
 /// L2 configuration for GhostPlane
@@ -92,6 +134,9 @@ pub struct TokenConfig {
     pub ghost: TokenSettings,
     /// Fee distribution percentages
     pub fee_distribution: FeeDistribution,
+    /// Window over which per-token bridge volume caps are enforced
+    #[serde(default = "TokenConfig::default_bridge_volume_window")]
+    pub bridge_volume_window: Duration,
 }
 
 /// Individual token settings
@@ -102,6 +147,16 @@ pub struct TokenSettings {
     pub burn_rate_bps: u16, // Basis points (100 = 1%)
     pub min_fee_amount: u64,
     pub max_supply: Option<u64>,
+    /// Maximum amount bridged per volume window, in base units at `decimals`
+    /// whichever chain it is bridged from (None = uncapped)
+    pub bridge_volume_cap: Option<U256>,
+    /// Decimals of the token's representation on chains where they differ
+    /// from `decimals`, e.g. a 6-decimal stablecoin on Ethereum
+    #[serde(default)]
+    pub chain_decimals: HashMap<ChainId, u8>,
+    /// Smallest amount accepted for bridging (None = no minimum)
+    #[serde(default)]
+    pub min_bridge_amount: Option<MinimumBridgeAmount>,
 }
 
 /// Fee distribution across the ecosystem
@@ -153,6 +208,12 @@ impl Default for BridgeConfig {
             max_retries: 3,
             enable_guardian_auth: true,
             enable_metrics: true,
+            maintenance: MaintenanceConfig::default(),
+            health: Self::default_health_policy(),
+            min_healthy_services: 0,
+            health_check_interval: Self::default_health_check_interval(),
+            fee_quotes: FeeQuoteConfig::default(),
+            collateral_ledger_path: None,
         }
     }
 }
@@ -165,42 +226,49 @@ impl Default for ServiceEndpoints {
                 port: 8545,
                 use_tls: false,
                 timeout_ms: 5000,
+                auth: ServiceAuth::default(),
             },
             walletd: ServiceEndpoint {
                 host: "localhost".to_string(),
                 port: 8546,
                 use_tls: false,
                 timeout_ms: 5000,
+                auth: ServiceAuth::default(),
             },
             gid: ServiceEndpoint {
                 host: "localhost".to_string(),
                 port: 8547,
                 use_tls: false,
                 timeout_ms: 5000,
+                auth: ServiceAuth::default(),
             },
             cns: ServiceEndpoint {
                 host: "localhost".to_string(),
                 port: 8548,
                 use_tls: false,
                 timeout_ms: 5000,
+                auth: ServiceAuth::default(),
             },
             gledger: ServiceEndpoint {
                 host: "localhost".to_string(),
                 port: 8549,
                 use_tls: false,
                 timeout_ms: 5000,
+                auth: ServiceAuth::default(),
             },
             gsig: ServiceEndpoint {
                 host: "localhost".to_string(),
                 port: 8550,
                 use_tls: false,
                 timeout_ms: 5000,
+                auth: ServiceAuth::default(),
             },
             ghostplane: ServiceEndpoint {
                 host: "localhost".to_string(),
                 port: 9090,
                 use_tls: false,
                 timeout_ms: 10000,
+                auth: ServiceAuth::default(),
             },
         }
     }
@@ -229,6 +297,9 @@ impl Default for TokenConfig {
                 burn_rate_bps: 100, // 1% burn rate
                 min_fee_amount: 1000000000000000, // 0.001 GCC
                 max_supply: Some(21_000_000 * 10u64.pow(18)), // 21M GCC
+                bridge_volume_cap: None,
+                chain_decimals: HashMap::new(),
+                min_bridge_amount: None,
             },
             spirit: TokenSettings {
                 decimals: 18,
@@ -236,6 +307,9 @@ impl Default for TokenConfig {
                 burn_rate_bps: 0,
                 min_fee_amount: 500000000000000, // 0.0005 SPIRIT
                 max_supply: None, // Unlimited for governance
+                bridge_volume_cap: None,
+                chain_decimals: HashMap::new(),
+                min_bridge_amount: None,
             },
             mana: TokenSettings {
                 decimals: 18,
@@ -243,6 +317,9 @@ impl Default for TokenConfig {
                 burn_rate_bps: 50, // 0.5% burn rate
                 min_fee_amount: 750000000000000, // 0.00075 MANA
                 max_supply: Some(100_000_000 * 10u64.pow(18)), // 100M MANA
+                bridge_volume_cap: None,
+                chain_decimals: HashMap::new(),
+                min_bridge_amount: None,
             },
             ghost: TokenSettings {
                 decimals: 0, // NFT-like tokens
@@ -250,6 +327,9 @@ impl Default for TokenConfig {
                 burn_rate_bps: 0,
                 min_fee_amount: 1, // 1 GHOST
                 max_supply: Some(10_000), // Limited collectibles
+                bridge_volume_cap: None,
+                chain_decimals: HashMap::new(),
+                min_bridge_amount: None,
             },
             fee_distribution: FeeDistribution {
                 l2_validators: 40,
@@ -257,8 +337,59 @@ impl Default for TokenConfig {
                 security_fund: 20,
                 protocol_development: 10,
             },
+            bridge_volume_window: Self::default_bridge_volume_window(),
+        }
+    }
+}
+
+impl TokenConfig {
+    pub fn default_bridge_volume_window() -> Duration {
+        Duration::from_secs(24 * 60 * 60)
+    }
+
+    /// Get the configured bridge volume caps for capped tokens
+    pub fn bridge_volume_caps(&self) -> HashMap<TokenType, U256> {
+        [
+            (TokenType::Gcc, &self.gcc),
+            (TokenType::Spirit, &self.spirit),
+            (TokenType::Mana, &self.mana),
+            (TokenType::Ghost, &self.ghost),
+        ]
+        .into_iter()
+        .filter_map(|(token_type, settings)| settings.bridge_volume_cap.clone().map(|cap| (token_type, cap)))
+        .collect()
+    }
+
+    /// Get the configured minimum bridge amounts for tokens that have one
+    pub fn min_bridge_amounts(&self) -> HashMap<TokenType, MinimumBridgeAmount> {
+        [
+            (TokenType::Gcc, &self.gcc),
+            (TokenType::Spirit, &self.spirit),
+            (TokenType::Mana, &self.mana),
+            (TokenType::Ghost, &self.ghost),
+        ]
+        .into_iter()
+        .filter_map(|(token_type, settings)| settings.min_bridge_amount.map(|minimum| (token_type, minimum)))
+        .collect()
+    }
+
+    /// Settings for a token
+    pub fn settings(&self, token_type: TokenType) -> &TokenSettings {
+        match token_type {
+            TokenType::Gcc => &self.gcc,
+            TokenType::Spirit => &self.spirit,
+            TokenType::Mana => &self.mana,
+            TokenType::Ghost => &self.ghost,
         }
     }
+
+    /// Decimals of a token on a network
+    pub fn decimals_on(&self, token_type: TokenType, network: &Network) -> u8 {
+        let settings = self.settings(token_type);
+        network.chain_id()
+            .and_then(|chain_id| settings.chain_decimals.get(&chain_id).copied())
+            .unwrap_or(settings.decimals)
+    }
 }
 
 impl Default for ValidationRules {
@@ -297,6 +428,26 @@ impl BridgeConfig {
         BridgeConfigBuilder::new()
     }
 
+    /// Settlement, GhostPlane, and GHOSTD are critical; the other services
+    /// only back optional features (see `BridgeFeature`)
+    pub fn default_health_policy() -> HealthPolicy {
+        HealthPolicy::new(Criticality::NonCritical)
+            .with_component("settlement", Criticality::Critical)
+            .with_component("ghostplane_ffi", Criticality::Critical)
+            .with_component("GHOSTD", Criticality::Critical)
+            .with_component("GHOSTPLANE", Criticality::Critical)
+    }
+
+    /// A majority of the GhostChain services, for deployments that opt into
+    /// `min_healthy_services`
+    pub fn majority_service_quorum() -> usize {
+        ServiceManager::SERVICE_NAMES.len() / 2 + 1
+    }
+
+    pub fn default_health_check_interval() -> Duration {
+        Duration::from_secs(30)
+    }
+
     /// Get default network configurations
     fn default_networks() -> HashMap<ChainId, NetworkConfig> {
         let mut networks = HashMap::new();
@@ -313,8 +464,10 @@ impl BridgeConfig {
                 max_gas_price: 100_000_000_000, // 100 gwei
                 supported_tokens: vec![TokenType::Gcc, TokenType::Spirit],
                 bridge_contract: Some("0x1234...".to_string()),
+                bridge_vault: None,
                 is_testnet: false,
                 block_time_ms: 12000,
+                read_buffer_blocks: None,
             },
         );
 
@@ -330,8 +483,10 @@ impl BridgeConfig {
                 max_gas_price: 50_000_000_000, // 50 gwei
                 supported_tokens: vec![TokenType::Gcc, TokenType::Spirit, TokenType::Mana, TokenType::Ghost],
                 bridge_contract: Some("ghost1abcd...".to_string()),
+                bridge_vault: None,
                 is_testnet: false,
                 block_time_ms: 3000,
+                read_buffer_blocks: None,
             },
         );
 
@@ -347,8 +502,10 @@ impl BridgeConfig {
                 max_gas_price: 1_000_000_000, // 1 gwei
                 supported_tokens: vec![TokenType::Gcc, TokenType::Spirit, TokenType::Mana, TokenType::Ghost],
                 bridge_contract: None, // Native L2
+                bridge_vault: None,
                 is_testnet: false,
                 block_time_ms: 100, // 100ms for high TPS
+                read_buffer_blocks: None,
             },
         );
 
@@ -364,8 +521,10 @@ impl BridgeConfig {
                 max_gas_price: 200_000_000_000, // 200 gwei
                 supported_tokens: vec![TokenType::Gcc],
                 bridge_contract: Some("0x5678...".to_string()),
+                bridge_vault: None,
                 is_testnet: false,
                 block_time_ms: 2000,
+                read_buffer_blocks: None,
             },
         );
 
@@ -381,8 +540,10 @@ impl BridgeConfig {
                 max_gas_price: 10_000_000_000, // 10 gwei
                 supported_tokens: vec![TokenType::Gcc, TokenType::Spirit],
                 bridge_contract: Some("0x9abc...".to_string()),
+                bridge_vault: None,
                 is_testnet: false,
                 block_time_ms: 250, // ~250ms
+                read_buffer_blocks: None,
             },
         );
 
@@ -413,6 +574,25 @@ impl BridgeConfig {
             return Err(BridgeError::config("L2 target TPS must be greater than 0"));
         }
 
+        if self.min_healthy_services > ServiceManager::SERVICE_NAMES.len() {
+            return Err(BridgeError::config(format!(
+                "Service quorum of {} exceeds the {} services",
+                self.min_healthy_services,
+                ServiceManager::SERVICE_NAMES.len()
+            )));
+        }
+
+        if self.maintenance.windows.iter().any(|window| window.end <= window.start) {
+            return Err(BridgeError::config("Maintenance windows must end after they start"));
+        }
+
+        if self.fee_quotes.validity.is_zero() {
+            return Err(BridgeError::config("Fee quotes must be valid for longer than 0"));
+        }
+        if self.fee_quotes.signing_key.is_some() {
+            self.fee_quotes.signing_key()?;
+        }
+
         // Validate network configurations
         for (chain_id, network_config) in &self.networks {
             if network_config.confirmation_blocks == 0 {
@@ -430,6 +610,10 @@ impl BridgeConfig {
             }
         }
 
+        if self.token_config.bridge_volume_window.is_zero() {
+            return Err(BridgeError::config("Bridge volume window must be greater than 0"));
+        }
+
         // Validate Guardian configuration
         if self.guardian_config.trust_level_threshold > 10 {
             return Err(BridgeError::config(
@@ -488,6 +672,22 @@ impl BridgeConfigBuilder {
         self
     }
 
+    pub fn bridge_volume_cap(mut self, token_type: TokenType, cap: U256) -> Self {
+        let settings = match token_type {
+            TokenType::Gcc => &mut self.config.token_config.gcc,
+            TokenType::Spirit => &mut self.config.token_config.spirit,
+            TokenType::Mana => &mut self.config.token_config.mana,
+            TokenType::Ghost => &mut self.config.token_config.ghost,
+        };
+        settings.bridge_volume_cap = Some(cap);
+        self
+    }
+
+    pub fn maintenance_window(mut self, window: MaintenanceWindow) -> Self {
+        self.config.maintenance.windows.push(window);
+        self
+    }
+
     pub fn add_custom_network(mut self, chain_id: u64, config: NetworkConfig) -> Self {
         self.config.networks.insert(ChainId(chain_id), config);
         self
@@ -549,4 +749,40 @@ mod tests {
         assert!(!config.token_config.spirit.is_deflationary);
         assert_eq!(config.token_config.ghost.decimals, 0); // NFT-like
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_bridge_volume_caps() {
+        let config = BridgeConfig::builder()
+            .bridge_volume_cap(TokenType::Gcc, U256::from(5_000u64))
+            .build()
+            .unwrap();
+
+        let caps = config.token_config.bridge_volume_caps();
+        assert_eq!(caps.get(&TokenType::Gcc), Some(&U256::from(5_000u64)));
+        assert!(!caps.contains_key(&TokenType::Mana));
+    }
+
+    #[test]
+    fn test_bridge_volume_window_defaults_when_absent() {
+        let mut value = serde_json::to_value(TokenConfig::default()).unwrap();
+        value.as_object_mut().unwrap().remove("bridge_volume_window");
+
+        let config: TokenConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.bridge_volume_window, Duration::from_secs(24 * 60 * 60));
+    }
+
+    #[test]
+    fn test_default_health_policy_tiers() {
+        use crate::health::HealthState;
+        let policy = BridgeConfig::default().health;
+        let with_down = |down: &str| {
+            policy.aggregate(["GHOSTD", "CNS", "GLEDGER", "settlement"].map(|component| (component, component != down)))
+        };
+
+        assert_eq!(with_down("none"), HealthState::Healthy);
+        assert_eq!(with_down("CNS"), HealthState::Degraded);
+        assert_eq!(with_down("GLEDGER"), HealthState::Degraded);
+        assert_eq!(with_down("GHOSTD"), HealthState::Unhealthy);
+        assert_eq!(with_down("settlement"), HealthState::Unhealthy);
+    }
+}
@@ -0,0 +1,153 @@
+/*!
+Token decimals conversion across chains
+
+A token can use different decimals on each side of the bridge, e.g. 6 on
+Ethereum and 18 on GhostPlane. Amounts are scaled into the destination
+chain's decimals while bridging. Conversions that would overflow, or that
+would drop a non-zero remainder when scaling down, are rejected rather than
+silently changing the amount.
+*/
+
+use crate::bridge::config::TokenConfig;
+use crate::error::{BridgeError, CrossChainError, Result};
+use crate::types::{Network, TokenAmount, TokenType, U256};
+
+/// Largest power of ten that fits in a u64
+const MAX_STEP: u8 = 19;
+
+/// Scale `amount` from `from_decimals` to `to_decimals` without losing value
+pub fn scale_amount(token_type: TokenType, amount: &U256, from_decimals: u8, to_decimals: u8) -> Result<U256> {
+    let error = |reason: &str| BridgeError::CrossChain(CrossChainError::DecimalConversion {
+        token: token_type.to_string(),
+        from_decimals,
+        to_decimals,
+        reason: reason.to_string(),
+    });
+
+    let mut scaled = amount.clone();
+    let mut remaining = from_decimals.abs_diff(to_decimals);
+    while remaining > 0 {
+        let step = remaining.min(MAX_STEP);
+        let factor = 10u64.pow(step as u32);
+        scaled = if to_decimals > from_decimals {
+            scaled.checked_mul_u64(factor).ok_or_else(|| error("amount overflows"))?
+        } else {
+            let (quotient, remainder) = scaled.div_rem_u64(factor);
+            if remainder != 0 {
+                return Err(error("amount would lose precision"));
+            }
+            quotient
+        };
+        remaining -= step;
+    }
+    Ok(scaled)
+}
+
+/// A source-chain amount in base units at the token's configured decimals,
+/// the unit volume caps are given in. Extra precision is rounded up, so a
+/// transfer never counts for less volume than it moves.
+pub fn standard_amount(config: &TokenConfig, amount: &TokenAmount, from: &Network) -> Result<U256> {
+    let from_decimals = config.decimals_on(amount.token_type, from);
+    let standard_decimals = config.settings(amount.token_type).decimals;
+    if from_decimals <= standard_decimals {
+        return scale_amount(amount.token_type, &amount.amount, from_decimals, standard_decimals);
+    }
+
+    let mut scaled = amount.amount.clone();
+    let mut truncated = false;
+    let mut remaining = from_decimals - standard_decimals;
+    while remaining > 0 {
+        let step = remaining.min(MAX_STEP);
+        let (quotient, remainder) = scaled.div_rem_u64(10u64.pow(step as u32));
+        truncated |= remainder != 0;
+        scaled = quotient;
+        remaining -= step;
+    }
+    // Dividing by at least ten leaves room for the extra unit
+    Ok(if truncated { scaled.checked_add(&U256::ONE).unwrap_or(scaled) } else { scaled })
+}
+
+/// Convert a source-chain amount into the destination chain's decimals
+pub fn convert_amount(config: &TokenConfig, amount: &TokenAmount, from: &Network, to: &Network) -> Result<TokenAmount> {
+    let from_decimals = config.decimals_on(amount.token_type, from);
+    let to_decimals = config.decimals_on(amount.token_type, to);
+
+    Ok(TokenAmount {
+        token_type: amount.token_type,
+        amount: scale_amount(amount.token_type, &amount.amount, from_decimals, to_decimals)?,
+        decimals: to_decimals,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ChainId;
+
+    fn usdc_like_config() -> TokenConfig {
+        let mut config = TokenConfig::default();
+        config.gcc.chain_decimals.insert(ChainId::ETHEREUM, 6);
+        config
+    }
+
+    #[test]
+    fn test_six_decimal_l1_token_scaled_to_eighteen_on_l2() {
+        let config = usdc_like_config();
+        let ethereum = Network::Ethereum { chain_id: ChainId::ETHEREUM };
+        let ghostplane = Network::GhostPlane { chain_id: ChainId::GHOSTPLANE };
+
+        // 2.5 tokens on L1
+        let deposit = TokenAmount { token_type: TokenType::Gcc, amount: U256::from(2_500_000), decimals: 6 };
+        let on_l2 = convert_amount(&config, &deposit, &ethereum, &ghostplane).unwrap();
+        assert_eq!(on_l2.amount, U256::from(2_500_000_000_000_000_000));
+        assert_eq!(on_l2.decimals, 18);
+
+        // And back again
+        let withdrawal = convert_amount(&config, &on_l2, &ghostplane, &ethereum).unwrap();
+        assert_eq!(withdrawal.amount, deposit.amount);
+        assert_eq!(withdrawal.decimals, 6);
+
+        // Amounts past u64 are scaled at full width
+        let large = scale_amount(TokenType::Gcc, &U256::from(u64::MAX), 6, 18).unwrap();
+        assert_eq!(large.div_rem_u64(1_000_000_000_000), (U256::from(u64::MAX), 0));
+    }
+
+    #[test]
+    fn test_lossy_and_overflowing_conversions_rejected() {
+        let config = usdc_like_config();
+        let ethereum = Network::Ethereum { chain_id: ChainId::ETHEREUM };
+        let ghostplane = Network::GhostPlane { chain_id: ChainId::GHOSTPLANE };
+
+        // 1 wei on L2 has no 6-decimal representation
+        let dust = TokenAmount { token_type: TokenType::Gcc, amount: U256::from(1_000_000_000_000 + 1), decimals: 18 };
+        let error = convert_amount(&config, &dust, &ghostplane, &ethereum).unwrap_err();
+        assert!(error.to_string().contains("lose precision"), "{}", error);
+
+        assert!(scale_amount(TokenType::Gcc, &U256([0xff; 32]), 6, 18).is_err());
+    }
+
+    #[test]
+    fn test_volume_counted_at_standard_decimals() {
+        use crate::bridge::limits::VolumeLimiter;
+
+        let mut config = usdc_like_config();
+        config.spirit.chain_decimals.insert(ChainId::ETHEREUM, 24);
+        let ethereum = Network::Ethereum { chain_id: ChainId::ETHEREUM };
+
+        // 2.5 tokens from a 6-decimal L1 count as 2.5 tokens against an 18-decimal cap
+        let deposit = TokenAmount { token_type: TokenType::Gcc, amount: U256::from(2_500_000), decimals: 6 };
+        let volume = standard_amount(&config, &deposit, &ethereum).unwrap();
+        assert_eq!(volume, U256::from(2_500_000_000_000_000_000));
+
+        let limiter = VolumeLimiter::new(
+            [(TokenType::Gcc, U256::from(3_000_000_000_000_000_000u64))].into(),
+            std::time::Duration::from_secs(3600),
+        );
+        limiter.check_and_record(TokenType::Gcc, &volume).unwrap();
+        assert!(limiter.check_and_record(TokenType::Gcc, &volume).is_err());
+
+        // Precision beyond the standard decimals rounds up
+        let fine = TokenAmount { token_type: TokenType::Spirit, amount: U256::from(1_000_001), decimals: 24 };
+        assert_eq!(standard_amount(&config, &fine, &ethereum).unwrap(), U256::from(2));
+    }
+}
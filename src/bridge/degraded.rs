@@ -0,0 +1,211 @@
+/*!
+Degraded-mode operation on partial service health
+
+Most bridge features depend on only some GhostChain services. When a health
+check finds services down, only the features that need them are disabled,
+e.g. CNS name resolution while CNS is unreachable, and core bridging keeps
+running as long as GHOSTD and GhostPlane are up. Disabled features are
+re-enabled by the next health check that finds their services healthy.
+
+Operators can additionally require a quorum of healthy services: new work is
+then refused while fewer are healthy, or before any health check has run,
+since it would only fail later. The quorum is off by default, leaving the
+per-feature rules above in charge.
+*/
+
+use crate::error::{BridgeError, Result, ServiceError};
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use tracing::{info, warn};
+
+/// A bridge feature that can be disabled independently
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum BridgeFeature {
+    /// Address-based bridging and L2 submission
+    CoreBridging,
+    /// Resolving CNS names to recipient addresses
+    NameResolution,
+    /// GID identity verification
+    IdentityVerification,
+    /// GSIG remote signing; local signing via `CryptoProvider` still works
+    RemoteSigning,
+    /// WALLETD wallet operations
+    WalletIntegration,
+    /// GLEDGER balance and fee accounting
+    FeeAccounting,
+}
+
+impl BridgeFeature {
+    pub const ALL: [BridgeFeature; 6] = [
+        BridgeFeature::CoreBridging,
+        BridgeFeature::NameResolution,
+        BridgeFeature::IdentityVerification,
+        BridgeFeature::RemoteSigning,
+        BridgeFeature::WalletIntegration,
+        BridgeFeature::FeeAccounting,
+    ];
+
+    /// Services (see `ServiceManager::SERVICE_NAMES`) the feature needs
+    pub fn required_services(self) -> &'static [&'static str] {
+        match self {
+            BridgeFeature::CoreBridging => &["GHOSTD", "GHOSTPLANE"],
+            BridgeFeature::NameResolution => &["CNS"],
+            BridgeFeature::IdentityVerification => &["GID"],
+            BridgeFeature::RemoteSigning => &["GSIG"],
+            BridgeFeature::WalletIntegration => &["WALLETD"],
+            BridgeFeature::FeeAccounting => &["GLEDGER"],
+        }
+    }
+}
+
+/// Features disabled by the last service health check
+#[derive(Debug, Default)]
+pub struct DegradedMode {
+    /// Disabled features and the first unhealthy service each depends on
+    disabled: RwLock<HashMap<BridgeFeature, &'static str>>,
+    /// Healthy services required before accepting work; 0 disables the check
+    quorum: usize,
+    /// Healthy services at the last check; `None` until the first check
+    healthy_services: RwLock<Option<usize>>,
+}
+
+impl DegradedMode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Refuse work while fewer than `quorum` services are healthy
+    pub fn with_quorum(mut self, quorum: usize) -> Self {
+        self.quorum = quorum;
+        self
+    }
+
+    /// Recompute disabled features from per-service health
+    pub fn update(&self, service_health: &HashMap<String, bool>) {
+        let mut disabled = HashMap::new();
+        for feature in BridgeFeature::ALL {
+            let down = feature.required_services().iter()
+                .find(|service| !service_health.get(**service).copied().unwrap_or(false));
+            if let Some(service) = down {
+                disabled.insert(feature, *service);
+            }
+        }
+
+        let healthy = service_health.values().filter(|healthy| **healthy).count();
+        let previous = self.healthy_services.write().replace(healthy);
+        let had_quorum = previous.map_or(true, |previous| previous >= self.quorum);
+        if had_quorum && healthy < self.quorum {
+            warn!("Refusing new work: {} services healthy, {} required", healthy, self.quorum);
+        } else if !had_quorum && healthy >= self.quorum {
+            info!("Service quorum restored: {} services healthy", healthy);
+        }
+
+        let mut current = self.disabled.write();
+        for (feature, service) in &disabled {
+            if !current.contains_key(feature) {
+                warn!("Disabling {:?}: {} is unavailable", feature, service);
+            }
+        }
+        for feature in current.keys().filter(|feature| !disabled.contains_key(feature)) {
+            info!("Re-enabling {:?}", feature);
+        }
+        *current = disabled;
+    }
+
+    pub fn is_available(&self, feature: BridgeFeature) -> bool {
+        !self.disabled.read().contains_key(&feature)
+    }
+
+    /// Fail with the unavailable service if `feature` is disabled
+    pub fn require(&self, feature: BridgeFeature) -> Result<()> {
+        match self.disabled.read().get(&feature) {
+            Some(service) => Err(BridgeError::Service(ServiceError::ServiceUnavailable {
+                service: service.to_string(),
+            })),
+            None => Ok(()),
+        }
+    }
+
+    /// Fail if the last health check found fewer than the quorum of services
+    /// healthy; with a quorum set, no service counts as healthy before the first check
+    pub fn require_quorum(&self) -> Result<()> {
+        if self.quorum == 0 {
+            return Ok(());
+        }
+        let healthy = self.healthy_services.read().unwrap_or(0);
+        if healthy < self.quorum {
+            return Err(BridgeError::Service(ServiceError::InsufficientServices {
+                healthy,
+                required: self.quorum,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Currently disabled features
+    pub fn degraded_features(&self) -> Vec<BridgeFeature> {
+        self.disabled.read().keys().copied().collect::<BTreeSet<_>>().into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::ServiceManager;
+
+    fn health(down: &[&str]) -> HashMap<String, bool> {
+        ServiceManager::SERVICE_NAMES.iter()
+            .map(|service| (service.to_string(), !down.contains(service)))
+            .collect()
+    }
+
+    #[test]
+    fn test_cns_outage_disables_only_name_resolution() {
+        let mode = DegradedMode::new();
+        mode.update(&health(&["CNS"]));
+
+        // Name-based transfers are rejected, address-based bridging continues
+        let error = mode.require(BridgeFeature::NameResolution).unwrap_err();
+        assert!(error.to_string().contains("CNS"), "{}", error);
+        assert!(mode.require(BridgeFeature::CoreBridging).is_ok());
+        assert_eq!(mode.degraded_features(), vec![BridgeFeature::NameResolution]);
+
+        // Recovered on the next check
+        mode.update(&health(&[]));
+        assert!(mode.is_available(BridgeFeature::NameResolution));
+        assert!(mode.degraded_features().is_empty());
+
+        mode.update(&health(&["GHOSTPLANE", "GSIG"]));
+        assert!(!mode.is_available(BridgeFeature::CoreBridging));
+        assert_eq!(mode.degraded_features(), vec![BridgeFeature::CoreBridging, BridgeFeature::RemoteSigning]);
+    }
+
+    #[test]
+    fn test_work_refused_below_service_quorum() {
+        // Without a quorum, only per-feature degradation applies
+        let unchecked = DegradedMode::new();
+        assert!(unchecked.require_quorum().is_ok());
+        unchecked.update(&health(&["CNS", "GID", "GSIG", "WALLETD"]));
+        assert!(unchecked.require_quorum().is_ok());
+
+        // Nothing is known to be healthy before the first health check
+        let mode = DegradedMode::new().with_quorum(5);
+        assert!(matches!(
+            mode.require_quorum().unwrap_err(),
+            BridgeError::Service(ServiceError::InsufficientServices { healthy: 0, required: 5 })
+        ));
+
+        mode.update(&health(&["CNS", "GID", "GSIG"]));
+        let error = mode.require_quorum().unwrap_err();
+        assert!(matches!(
+            error,
+            BridgeError::Service(ServiceError::InsufficientServices { healthy: 4, required: 5 })
+        ));
+        assert!(error.is_retryable());
+
+        // Accepted again once enough services recover
+        mode.update(&health(&["CNS", "GID"]));
+        assert!(mode.require_quorum().is_ok());
+    }
+}
@@ -0,0 +1,284 @@
+/*!
+Signed fee quotes
+
+`FeeQuoter` prices a bridge transaction and signs the quote with the bridge's
+Ed25519 key, over the fee breakdown, quote id, expiry, and the hash of the
+transaction it was issued for. A client submitting the transaction hands the
+quote back: a quote still within its expiry is honored at the quoted fee once,
+an expired one is replaced by a fresh calculation, and one whose signature no
+longer matches, that was issued for another transaction, or that was already
+used is rejected.
+
+Fees are priced against the `MarketConditions` at the time: L2 congestion
+and demand for the token being bridged.
+
+Ed25519 signatures are deterministic, so configuring the signing key seed
+lets every bridge instance issue and accept the same quotes.
+*/
+
+use crate::economy::FeeCalculator;
+use crate::error::{BridgeError, Result, SecurityError};
+use crate::types::{MultiTokenFee, TokenAmount, Transaction};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// Domain separator so quote signatures can't be replayed as other messages
+const QUOTE_DOMAIN: &[u8] = b"ghostbridge-fee-quote-v1";
+
+/// Gas charged for a bridge transaction before calldata
+pub const BRIDGE_BASE_GAS: u64 = 60_000;
+/// Gas charged per calldata byte
+pub const BRIDGE_GAS_PER_BYTE: u64 = 16;
+
+/// Fee quote settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeQuoteConfig {
+    /// How long a quote is honored after it is issued
+    pub validity: Duration,
+    /// Hex-encoded 32-byte Ed25519 seed quotes are signed with. Without one a
+    /// key is generated at startup and quotes don't survive a restart.
+    #[serde(default)]
+    pub signing_key: Option<String>,
+}
+
+impl Default for FeeQuoteConfig {
+    fn default() -> Self {
+        Self {
+            validity: Duration::from_secs(5 * 60), // 5 minutes
+            signing_key: None,
+        }
+    }
+}
+
+impl FeeQuoteConfig {
+    /// The configured signing key, or a freshly generated one
+    pub fn signing_key(&self) -> Result<SigningKey> {
+        let Some(seed) = &self.signing_key else {
+            return Ok(SigningKey::generate(&mut rand::rngs::OsRng));
+        };
+        let seed: [u8; 32] = hex::decode(seed.trim_start_matches("0x"))
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| BridgeError::config("Fee quote signing key must be 32 hex-encoded bytes"))?;
+        Ok(SigningKey::from_bytes(&seed))
+    }
+}
+
+/// Load a fee is priced at, each from 0.0 (idle) to 1.0 (saturated)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MarketConditions {
+    /// How full recent L2 batches are
+    pub congestion: f64,
+    /// How much of the token's bridge volume cap is used in the current window
+    pub token_demand: f64,
+}
+
+/// Fee quoted for a bridge transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeQuote {
+    pub quote_id: Uuid,
+    /// Hash of the transaction the quote was issued for
+    pub transaction_hash: [u8; 32],
+    pub fee: MultiTokenFee,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl FeeQuote {
+    /// Canonical bytes covered by the signature
+    fn signing_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(QUOTE_DOMAIN.len() + 16 + 32 + 4 * 34 + 8);
+        bytes.extend_from_slice(QUOTE_DOMAIN);
+        bytes.extend_from_slice(self.quote_id.as_bytes());
+        bytes.extend_from_slice(&self.transaction_hash);
+        for amount in [&self.fee.gcc_fee, &self.fee.spirit_fee, &self.fee.mana_fee, &self.fee.ghost_fee] {
+            push_amount(&mut bytes, amount);
+        }
+        bytes.extend_from_slice(&self.expires_at.timestamp_millis().to_be_bytes());
+        bytes
+    }
+}
+
+fn push_amount(bytes: &mut Vec<u8>, amount: &TokenAmount) {
+    bytes.push(amount.token_type as u8);
+    bytes.push(amount.decimals);
+    bytes.extend_from_slice(&amount.amount.0);
+}
+
+/// A fee quote with the bridge's signature over it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedFeeQuote {
+    pub quote: FeeQuote,
+    /// Ed25519 signature over the quote's canonical bytes
+    pub signature: Vec<u8>,
+}
+
+/// Issues and checks signed fee quotes
+pub struct FeeQuoter {
+    signing_key: SigningKey,
+    validity: chrono::Duration,
+    calculator: FeeCalculator,
+    /// Ids of honored quotes, kept until they expire
+    spent: Mutex<HashMap<Uuid, DateTime<Utc>>>,
+}
+
+impl FeeQuoter {
+    pub async fn new(config: &FeeQuoteConfig) -> Result<Self> {
+        let validity = chrono::Duration::from_std(config.validity)
+            .map_err(|_| BridgeError::config("Fee quote validity is out of range"))?;
+        Ok(Self {
+            signing_key: config.signing_key()?,
+            validity,
+            calculator: FeeCalculator::new().await?,
+            spent: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Key clients can verify quotes with
+    pub fn verifying_key(&self) -> VerifyingKey {
+        self.signing_key.verifying_key()
+    }
+
+    /// Fee for `transaction` under `conditions`
+    pub async fn calculate_fee(&self, transaction: &Transaction, conditions: MarketConditions) -> Result<MultiTokenFee> {
+        let gas = BRIDGE_BASE_GAS + transaction.data.len() as u64 * BRIDGE_GAS_PER_BYTE;
+        self.calculator.calculate_dynamic_fees(
+            gas,
+            conditions.congestion.clamp(0.0, 1.0),
+            conditions.token_demand.clamp(0.0, 1.0),
+        ).await
+    }
+
+    /// Price `transaction` under `conditions` and sign the quote
+    pub async fn quote(&self, transaction: &Transaction, conditions: MarketConditions) -> Result<SignedFeeQuote> {
+        let fee = self.calculate_fee(transaction, conditions).await?;
+        Ok(self.sign(fee, transaction, Utc::now() + self.validity))
+    }
+
+    /// Sign a quote for `fee` on `transaction`, honored until `expires_at`
+    pub fn sign(&self, fee: MultiTokenFee, transaction: &Transaction, expires_at: DateTime<Utc>) -> SignedFeeQuote {
+        let quote = FeeQuote { quote_id: Uuid::new_v4(), transaction_hash: transaction.hash().0, fee, expires_at };
+        let signature = self.signing_key.sign(&quote.signing_bytes());
+        SignedFeeQuote { quote, signature: signature.to_bytes().to_vec() }
+    }
+
+    /// The fee to charge for a transaction submitted with `quote`: the quoted
+    /// fee the first time a valid quote is used, a fresh calculation under
+    /// `conditions` once it has expired. A quote whose signature doesn't
+    /// match, that was issued for another transaction, or that was already
+    /// used is rejected.
+    pub async fn resolve_fee(
+        &self,
+        transaction: &Transaction,
+        quote: &SignedFeeQuote,
+        conditions: MarketConditions,
+    ) -> Result<MultiTokenFee> {
+        let quote_id = quote.quote.quote_id;
+        let verified = Signature::from_slice(&quote.signature).ok()
+            .is_some_and(|signature| self.verifying_key().verify(&quote.quote.signing_bytes(), &signature).is_ok());
+        if !verified {
+            warn!("Rejected tampered fee quote {}", quote_id);
+            return Err(BridgeError::Security(SecurityError::InvalidFeeQuote { quote_id: quote_id.to_string() }));
+        }
+        if quote.quote.transaction_hash != transaction.hash().0 {
+            warn!("Rejected fee quote {} presented for transaction {}", quote_id, transaction.id);
+            return Err(BridgeError::Security(SecurityError::FeeQuoteMismatch { quote_id: quote_id.to_string() }));
+        }
+
+        let now = Utc::now();
+        if now >= quote.quote.expires_at {
+            debug!("Fee quote {} expired at {}, recalculating", quote_id, quote.quote.expires_at);
+            return self.calculate_fee(transaction, conditions).await;
+        }
+
+        let mut spent = self.spent.lock();
+        spent.retain(|_, expires_at| *expires_at > now);
+        if spent.insert(quote_id, quote.quote.expires_at).is_some() {
+            warn!("Rejected reused fee quote {}", quote_id);
+            return Err(BridgeError::Security(SecurityError::FeeQuoteReused { quote_id: quote_id.to_string() }));
+        }
+        Ok(quote.quote.fee.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{fixtures, U256};
+
+    #[tokio::test]
+    async fn test_signed_quotes_honored_until_expiry_and_tampering_rejected() {
+        let config = FeeQuoteConfig { signing_key: Some(hex::encode([7u8; 32])), ..Default::default() };
+        let quoter = FeeQuoter::new(&config).await.unwrap();
+        let transaction = fixtures::transfer(1, 2, 1_000);
+        let conditions = MarketConditions { congestion: 0.2, token_demand: 0.5 };
+        let fresh = quoter.calculate_fee(&transaction, conditions).await.unwrap();
+
+        // A quote below current rates is honored while it is valid
+        let mut quoted_fee = fresh.clone();
+        quoted_fee.gcc_fee.amount = U256::from(42u64);
+        let expires_at = Utc::now() + chrono::Duration::minutes(1);
+        let quote = quoter.sign(quoted_fee.clone(), &transaction, expires_at);
+        let fee = quoter.resolve_fee(&transaction, &quote, conditions).await.unwrap();
+        assert_eq!(fee.gcc_fee.amount, U256::from(42u64));
+
+        // Each quote is honored once
+        assert!(matches!(
+            quoter.resolve_fee(&transaction, &quote, conditions).await,
+            Err(BridgeError::Security(SecurityError::FeeQuoteReused { .. }))
+        ));
+
+        // Another instance with the same key accepts quotes it didn't issue
+        let peer = FeeQuoter::new(&config).await.unwrap();
+        let peer_quote = quoter.sign(quoted_fee.clone(), &transaction, expires_at);
+        assert_eq!(peer.resolve_fee(&transaction, &peer_quote, conditions).await.unwrap().gcc_fee.amount, U256::from(42u64));
+
+        // A quote only covers the transaction it was issued for
+        let cheap = quoter.sign(quoted_fee, &fixtures::transfer(1, 2, 1), expires_at);
+        assert!(matches!(
+            quoter.resolve_fee(&transaction, &cheap, conditions).await,
+            Err(BridgeError::Security(SecurityError::FeeQuoteMismatch { .. }))
+        ));
+
+        // Lowering the fee or extending the expiry breaks the signature
+        let mut tampered = quote.clone();
+        tampered.quote.fee.gcc_fee.amount = U256::from(1u64);
+        assert!(matches!(
+            quoter.resolve_fee(&transaction, &tampered, conditions).await,
+            Err(BridgeError::Security(SecurityError::InvalidFeeQuote { .. }))
+        ));
+        let mut extended = quote.clone();
+        extended.quote.expires_at += chrono::Duration::days(1);
+        assert!(quoter.resolve_fee(&transaction, &extended, conditions).await.is_err());
+
+        // Once expired, the fee is recalculated
+        let expired = quoter.sign(quote.quote.fee.clone(), &transaction, Utc::now() - chrono::Duration::seconds(1));
+        let fee = quoter.resolve_fee(&transaction, &expired, conditions).await.unwrap();
+        assert_eq!(fee.gcc_fee.amount, fresh.gcc_fee.amount);
+    }
+
+    #[tokio::test]
+    async fn test_fees_rise_with_congestion_and_demand() {
+        let quoter = FeeQuoter::new(&FeeQuoteConfig::default()).await.unwrap();
+        let transaction = fixtures::transfer(1, 2, 1_000);
+        let gcc = |fee: MultiTokenFee| fee.gcc_fee.amount;
+
+        let idle = gcc(quoter.calculate_fee(&transaction, MarketConditions::default()).await.unwrap());
+        let congested = MarketConditions { congestion: 1.0, token_demand: 0.0 };
+        let in_demand = MarketConditions { congestion: 0.0, token_demand: 1.0 };
+        assert!(gcc(quoter.calculate_fee(&transaction, congested).await.unwrap()) > idle);
+        assert!(gcc(quoter.calculate_fee(&transaction, in_demand).await.unwrap()) > idle);
+
+        // Out-of-range inputs are treated as saturated
+        let overloaded = MarketConditions { congestion: 5.0, token_demand: 0.0 };
+        assert_eq!(
+            gcc(quoter.calculate_fee(&transaction, overloaded).await.unwrap()),
+            gcc(quoter.calculate_fee(&transaction, congested).await.unwrap()),
+        );
+    }
+}
@@ -0,0 +1,666 @@
+/*!
+L1 chain clients
+
+An `L1ChainClient` finds users' deposits into the bridge contract, pays out
+withdrawals from the bridge vault, and reports whether transactions were
+included. `EvmRpcClient` speaks the standard Ethereum JSON-RPC API and serves
+Ethereum, Polygon, and Arbitrum; `EvmChainAdapter` picks the client for a
+transaction's chain and waits for inclusion under an `InclusionPolicy`.
+
+A deposit is the user's own L1 transaction. The bridge contract emits
+`Deposit(bytes32 indexed bridgeTxHash, address indexed from, uint256 amount)`
+naming the bridge transaction's hash, and a deposit only counts once that
+event is buried under the network's confirmation blocks with the expected
+sender and amount. Payouts are sent from the vault under a nonce pinned to
+the bridge transaction, so paying the same transaction out again waits on the
+earlier broadcast instead of paying twice, and a payout is only reported as
+dropped once the vault's nonce has moved past it.
+*/
+
+use crate::bridge::config::NetworkConfig;
+use crate::error::{BridgeError, CrossChainError, NetworkError, Result};
+use crate::types::{Address, ChainId, LogEntry, Network, Transaction, TransactionHash, TransactionReceipt, U256};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use sha3::{Digest, Keccak256};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// Blocks to wait for inclusion before giving up on a transaction
+pub const INCLUSION_TIMEOUT_BLOCKS: u32 = 50;
+/// Shortest inclusion timeout, for chains with sub-second blocks
+pub const MIN_INCLUSION_TIMEOUT: Duration = Duration::from_secs(30);
+/// Upper bound on one JSON-RPC request, including reading the response
+pub const RPC_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+pub const RPC_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Event the bridge contract emits for each deposit
+pub const DEPOSIT_EVENT: &str = "Deposit(bytes32,address,uint256)";
+
+/// Topic of `DEPOSIT_EVENT`
+pub fn deposit_topic() -> [u8; 32] {
+    Keccak256::digest(DEPOSIT_EVENT.as_bytes()).into()
+}
+
+/// Where a submitted L1 transaction stands
+#[derive(Debug, Clone)]
+pub enum L1TransactionStatus {
+    /// Not in a block yet, or not known to be
+    Pending,
+    Included(TransactionReceipt),
+    /// Can no longer be included, e.g. its nonce was used by another transaction
+    Dropped,
+}
+
+/// Bridge contract deposit event, as found by a log scan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositEvent {
+    /// Hash of the bridge transaction the deposit pays for
+    pub bridge_tx_hash: TransactionHash,
+    /// The user's L1 deposit transaction
+    pub l1_tx_hash: TransactionHash,
+    pub block_number: u64,
+}
+
+/// Deposit lookup, payouts, and receipt access for one L1
+#[async_trait]
+pub trait L1ChainClient: Send + Sync {
+    /// Client name for logging
+    fn name(&self) -> &str;
+
+    /// Contract users deposit into
+    fn bridge_contract(&self) -> &Address;
+
+    /// Hash of the user's L1 deposit for `transaction`, once it is in a block
+    async fn find_deposit(&self, transaction: &Transaction) -> Result<Option<TransactionHash>>;
+
+    /// Deposit events of the bridge contract in blocks `from_block..=to_block`
+    async fn deposit_events(&self, from_block: u64, to_block: u64) -> Result<Vec<DepositEvent>>;
+
+    /// Pay `transaction` out from the bridge vault, returning its L1 hash.
+    /// Paying out the same transaction again must not broadcast a second payment.
+    async fn submit_transaction(&self, transaction: &Transaction) -> Result<TransactionHash>;
+
+    async fn get_receipt(&self, tx_hash: &TransactionHash) -> Result<L1TransactionStatus>;
+
+    /// Latest block number
+    async fn get_block_number(&self) -> Result<u64>;
+}
+
+/// 32-byte word holding an address, as in indexed event topics
+fn address_word(address: &Address) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(&address.0);
+    word
+}
+
+/// Check that `receipt` carries the bridge contract's deposit event for
+/// `transaction`, from its sender and for its amount
+pub fn verify_deposit(
+    receipt: &TransactionReceipt,
+    bridge_contract: &Address,
+    transaction: &Transaction,
+) -> std::result::Result<(), &'static str> {
+    let topic = deposit_topic();
+    let bridge_tx = transaction.hash().0;
+    let log = receipt.logs.iter()
+        .find(|log| log.address == *bridge_contract
+            && log.topics.first() == Some(&topic)
+            && log.topics.get(1) == Some(&bridge_tx))
+        .ok_or("no deposit event for the bridge transaction")?;
+
+    if log.topics.get(2) != Some(&address_word(&transaction.from_address)) {
+        return Err("deposited from a different address");
+    }
+    if log.data.as_slice() != transaction.amount.amount.0.as_slice() {
+        return Err("deposited amount differs");
+    }
+    Ok(())
+}
+
+/// How long and how often to poll for inclusion
+#[derive(Debug, Clone)]
+pub struct InclusionPolicy {
+    pub poll_interval: Duration,
+    pub timeout: Duration,
+    /// Blocks, counting its own, a transaction must be buried under
+    pub confirmations: u64,
+}
+
+impl InclusionPolicy {
+    /// Poll once per block, for up to `INCLUSION_TIMEOUT_BLOCKS` blocks past
+    /// the network's confirmation depth
+    pub fn for_network(config: &NetworkConfig) -> Self {
+        let block_time = Duration::from_millis(config.block_time_ms.max(1));
+        Self {
+            poll_interval: block_time,
+            timeout: (block_time * INCLUSION_TIMEOUT_BLOCKS.saturating_add(config.confirmation_blocks))
+                .max(MIN_INCLUSION_TIMEOUT),
+            confirmations: config.confirmation_blocks as u64,
+        }
+    }
+
+    /// Wait for the user's deposit of `transaction` to be confirmed and check
+    /// it against the bridge transaction
+    pub async fn confirm_deposit(
+        &self,
+        client: &dyn L1ChainClient,
+        chain_id: &ChainId,
+        transaction: &Transaction,
+    ) -> Result<TransactionReceipt> {
+        let started = Instant::now();
+        let tx_hash = loop {
+            if let Some(tx_hash) = client.find_deposit(transaction).await? {
+                break tx_hash;
+            }
+            let waited = started.elapsed();
+            if waited >= self.timeout {
+                return Err(BridgeError::CrossChain(CrossChainError::DepositNotFound {
+                    chain_id: chain_id.0,
+                    transaction_id: transaction.id.to_string(),
+                    waited_ms: waited.as_millis() as u64,
+                }));
+            }
+            tokio::time::sleep(self.poll_interval.min(self.timeout - waited)).await;
+        };
+        debug!("Found deposit for {} on {} in {}", transaction.id, client.name(), tx_hash);
+
+        let receipt = self.wait_for_inclusion(client, chain_id, &tx_hash, started).await?;
+        verify_deposit(&receipt, client.bridge_contract(), transaction).map_err(|reason| {
+            BridgeError::CrossChain(CrossChainError::DepositMismatch {
+                chain_id: chain_id.0,
+                tx_hash: tx_hash.to_string(),
+                reason: reason.to_string(),
+            })
+        })?;
+        Ok(receipt)
+    }
+
+    /// Pay `transaction` out through `client` and wait until it is confirmed
+    pub async fn submit_and_wait(
+        &self,
+        client: &dyn L1ChainClient,
+        chain_id: &ChainId,
+        transaction: &Transaction,
+    ) -> Result<TransactionReceipt> {
+        let tx_hash = client.submit_transaction(transaction).await?;
+        debug!("Submitted {} to {} as {}", transaction.id, client.name(), tx_hash);
+        self.wait_for_inclusion(client, chain_id, &tx_hash, Instant::now()).await
+    }
+
+    /// Poll until `tx_hash` is buried under `confirmations` blocks. The
+    /// receipt is fetched again on every poll, so a reorg puts it back to
+    /// pending rather than confirming a block that no longer exists.
+    async fn wait_for_inclusion(
+        &self,
+        client: &dyn L1ChainClient,
+        chain_id: &ChainId,
+        tx_hash: &TransactionHash,
+        started: Instant,
+    ) -> Result<TransactionReceipt> {
+        loop {
+            match client.get_receipt(tx_hash).await? {
+                L1TransactionStatus::Included(receipt) if !receipt.success => {
+                    return Err(BridgeError::CrossChain(CrossChainError::L1TransactionReverted {
+                        chain_id: chain_id.0,
+                        tx_hash: tx_hash.to_string(),
+                    }));
+                }
+                L1TransactionStatus::Included(receipt) => {
+                    let head = client.get_block_number().await?;
+                    if head.saturating_add(1) >= receipt.block_number.saturating_add(self.confirmations) {
+                        return Ok(receipt);
+                    }
+                }
+                L1TransactionStatus::Dropped => {
+                    warn!("L1 transaction {} was dropped by {}", tx_hash, client.name());
+                    return Err(BridgeError::CrossChain(CrossChainError::L1TransactionDropped {
+                        chain_id: chain_id.0,
+                        tx_hash: tx_hash.to_string(),
+                    }));
+                }
+                L1TransactionStatus::Pending => {}
+            }
+
+            let waited = started.elapsed();
+            if waited >= self.timeout {
+                return Err(BridgeError::CrossChain(CrossChainError::L1InclusionTimeout {
+                    chain_id: chain_id.0,
+                    tx_hash: tx_hash.to_string(),
+                    waited_ms: waited.as_millis() as u64,
+                }));
+            }
+            tokio::time::sleep(self.poll_interval.min(self.timeout - waited)).await;
+        }
+    }
+}
+
+/// EVM chains served by `EvmRpcClient`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvmChain {
+    Ethereum,
+    Polygon,
+    Arbitrum,
+}
+
+impl EvmChain {
+    pub fn of(network: &Network) -> Option<Self> {
+        match network {
+            Network::Ethereum { .. } => Some(EvmChain::Ethereum),
+            Network::Polygon { .. } => Some(EvmChain::Polygon),
+            Network::Arbitrum { .. } => Some(EvmChain::Arbitrum),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            EvmChain::Ethereum => "ethereum",
+            EvmChain::Polygon => "polygon",
+            EvmChain::Arbitrum => "arbitrum",
+        }
+    }
+}
+
+/// Vault nonces pinned to the payouts they were used for
+#[derive(Debug, Default)]
+struct PayoutNonces {
+    /// Next unused vault nonce, read from the node when unknown
+    next: Option<u64>,
+    by_transaction: HashMap<Uuid, (u64, TransactionHash)>,
+    by_hash: HashMap<TransactionHash, u64>,
+}
+
+/// Ethereum JSON-RPC client. Payouts are sent with `eth_sendTransaction`
+/// from the configured bridge vault, so the node (or a signer proxy in front
+/// of it) must hold the vault's key.
+pub struct EvmRpcClient {
+    chain: EvmChain,
+    chain_id: ChainId,
+    rpc_url: String,
+    bridge_contract: Address,
+    vault: Option<Address>,
+    http: reqwest::Client,
+    next_id: AtomicU64,
+    payouts: Mutex<PayoutNonces>,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcReceipt {
+    block_number: String,
+    block_hash: String,
+    transaction_index: String,
+    gas_used: String,
+    status: Option<String>,
+    #[serde(default)]
+    logs: Vec<RpcLog>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcLog {
+    address: String,
+    topics: Vec<String>,
+    data: String,
+    #[serde(default)]
+    transaction_hash: Option<String>,
+    #[serde(default)]
+    block_number: Option<String>,
+    /// Set on logs from blocks that were reorged away
+    #[serde(default)]
+    removed: bool,
+}
+
+impl EvmRpcClient {
+    pub fn new(chain: EvmChain, config: &NetworkConfig) -> Result<Self> {
+        let bridge_contract = config.bridge_contract.as_deref()
+            .ok_or_else(|| BridgeError::config(format!("No bridge contract configured for chain {}", config.chain_id.0)))
+            .and_then(Address::from_hex)?;
+        let vault = config.bridge_vault.as_deref().map(Address::from_hex).transpose()?;
+        let http = reqwest::Client::builder()
+            .timeout(RPC_REQUEST_TIMEOUT)
+            .connect_timeout(RPC_CONNECT_TIMEOUT)
+            .build()
+            .map_err(|e| BridgeError::config(format!("L1 RPC client for chain {}: {}", config.chain_id.0, e)))?;
+
+        Ok(Self {
+            chain,
+            chain_id: config.chain_id.clone(),
+            rpc_url: config.rpc_url.clone(),
+            bridge_contract,
+            vault,
+            http,
+            next_id: AtomicU64::new(1),
+            payouts: Mutex::new(PayoutNonces::default()),
+        })
+    }
+
+    /// Client for a configured Ethereum, Polygon, or Arbitrum network; `None`
+    /// for other networks
+    pub fn for_network(config: &NetworkConfig) -> Result<Option<Self>> {
+        EvmChain::of(&config.network).map(|chain| Self::new(chain, config)).transpose()
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, params: serde_json::Value) -> Result<Option<T>> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": self.next_id.fetch_add(1, Ordering::Relaxed),
+            "method": method,
+            "params": params,
+        });
+        let response: RpcResponse<T> = self.http.post(&self.rpc_url)
+            .json(&request)
+            .send().await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| BridgeError::Network(NetworkError::ConnectionFailed {
+                endpoint: self.rpc_url.clone(),
+                source: Box::new(e),
+            }))?
+            .json().await
+            .map_err(|e| self.rpc_error(format!("{} returned a malformed response: {}", method, e)))?;
+
+        match response.error {
+            Some(error) => Err(self.rpc_error(format!("{} failed ({}): {}", method, error.code, error.message))),
+            None => Ok(response.result),
+        }
+    }
+
+    fn rpc_error(&self, message: String) -> BridgeError {
+        BridgeError::CrossChain(CrossChainError::L1RpcError { chain_id: self.chain_id.0, message })
+    }
+
+    fn quantity(&self, value: &str) -> Result<u64> {
+        u64::from_str_radix(value.trim_start_matches("0x"), 16)
+            .map_err(|_| self.rpc_error(format!("Invalid quantity {}", value)))
+    }
+
+    fn bytes(&self, value: &str) -> Result<Vec<u8>> {
+        hex::decode(value.trim_start_matches("0x"))
+            .map_err(|_| self.rpc_error(format!("Invalid hex data {}", value)))
+    }
+
+    fn word(&self, value: &str) -> Result<[u8; 32]> {
+        self.bytes(value)?.try_into()
+            .map_err(|_| self.rpc_error(format!("Expected 32 bytes, got {}", value)))
+    }
+
+    fn receipt(&self, tx_hash: &TransactionHash, receipt: RpcReceipt) -> Result<TransactionReceipt> {
+        let logs = receipt.logs.iter()
+            .map(|log| -> Result<LogEntry> {
+                Ok(LogEntry {
+                    address: Address::from_hex(&log.address)?,
+                    topics: log.topics.iter().map(|topic| self.word(topic)).collect::<Result<_>>()?,
+                    data: self.bytes(&log.data)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(TransactionReceipt {
+            transaction_hash: tx_hash.clone(),
+            block_number: self.quantity(&receipt.block_number)?,
+            block_hash: self.word(&receipt.block_hash)?,
+            transaction_index: self.quantity(&receipt.transaction_index)? as u32,
+            gas_used: self.quantity(&receipt.gas_used)?,
+            // Pre-Byzantium receipts carry no status; they only exist for mined transactions
+            success: receipt.status.as_deref().map_or(true, |status| status == "0x1"),
+            logs,
+        })
+    }
+
+    async fn fetch_receipt(&self, tx_hash: &TransactionHash) -> Result<Option<TransactionReceipt>> {
+        let params = serde_json::json!([tx_hash.to_string()]);
+        match self.call::<RpcReceipt>("eth_getTransactionReceipt", params).await? {
+            Some(receipt) => self.receipt(tx_hash, receipt).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    async fn transaction_count(&self, account: &Address, block: &str) -> Result<u64> {
+        let params = serde_json::json!([account.to_hex(), block]);
+        let count: String = self.call("eth_getTransactionCount", params).await?
+            .ok_or_else(|| self.rpc_error("eth_getTransactionCount returned no count".to_string()))?;
+        self.quantity(&count)
+    }
+
+    fn vault(&self) -> Result<&Address> {
+        self.vault.as_ref().ok_or_else(|| {
+            BridgeError::config(format!("No bridge vault configured for chain {}", self.chain_id.0))
+        })
+    }
+
+    /// Status of `tx_hash`. A transaction without a receipt stays pending
+    /// unless it was sent from the vault under `nonce` and the vault has
+    /// since used that nonce for something else; a missing mempool entry
+    /// alone doesn't mean it can't still be mined.
+    async fn status(&self, tx_hash: &TransactionHash, nonce: Option<u64>) -> Result<L1TransactionStatus> {
+        if let Some(receipt) = self.fetch_receipt(tx_hash).await? {
+            return Ok(L1TransactionStatus::Included(receipt));
+        }
+        let (Some(nonce), Some(vault)) = (nonce, &self.vault) else {
+            return Ok(L1TransactionStatus::Pending);
+        };
+        if self.transaction_count(vault, "latest").await? <= nonce {
+            return Ok(L1TransactionStatus::Pending);
+        }
+
+        // The nonce is used; it may have been by this transaction since the first check
+        match self.fetch_receipt(tx_hash).await? {
+            Some(receipt) => Ok(L1TransactionStatus::Included(receipt)),
+            None => Ok(L1TransactionStatus::Dropped),
+        }
+    }
+}
+
+/// Minimal hex quantity, as JSON-RPC expects
+fn hex_quantity(value: &U256) -> String {
+    let digits = hex::encode(value.0);
+    match digits.trim_start_matches('0') {
+        "" => "0x0".to_string(),
+        digits => format!("0x{}", digits),
+    }
+}
+
+fn hex_word(word: &[u8; 32]) -> String {
+    format!("0x{}", hex::encode(word))
+}
+
+#[async_trait]
+impl L1ChainClient for EvmRpcClient {
+    fn name(&self) -> &str {
+        self.chain.name()
+    }
+
+    fn bridge_contract(&self) -> &Address {
+        &self.bridge_contract
+    }
+
+    async fn find_deposit(&self, transaction: &Transaction) -> Result<Option<TransactionHash>> {
+        let filter = serde_json::json!({
+            "address": self.bridge_contract.to_hex(),
+            "fromBlock": "earliest",
+            "toBlock": "latest",
+            "topics": [hex_word(&deposit_topic()), hex_word(&transaction.hash().0)],
+        });
+        let logs: Vec<RpcLog> = self.call("eth_getLogs", serde_json::json!([filter])).await?.unwrap_or_default();
+        logs.iter()
+            .filter(|log| !log.removed)
+            .find_map(|log| log.transaction_hash.as_deref())
+            .map(|tx_hash| self.word(tx_hash).map(TransactionHash))
+            .transpose()
+    }
+
+    async fn deposit_events(&self, from_block: u64, to_block: u64) -> Result<Vec<DepositEvent>> {
+        let filter = serde_json::json!({
+            "address": self.bridge_contract.to_hex(),
+            "fromBlock": format!("{:#x}", from_block),
+            "toBlock": format!("{:#x}", to_block),
+            "topics": [hex_word(&deposit_topic())],
+        });
+        let logs: Vec<RpcLog> = self.call("eth_getLogs", serde_json::json!([filter])).await?.unwrap_or_default();
+        logs.iter()
+            .filter(|log| !log.removed)
+            .map(|log| {
+                let missing = |field: &str| self.rpc_error(format!("Deposit log without {}", field));
+                Ok(DepositEvent {
+                    bridge_tx_hash: TransactionHash(self.word(log.topics.get(1).ok_or_else(|| missing("a bridge transaction"))?)?),
+                    l1_tx_hash: TransactionHash(self.word(log.transaction_hash.as_deref().ok_or_else(|| missing("a transaction hash"))?)?),
+                    block_number: self.quantity(log.block_number.as_deref().ok_or_else(|| missing("a block number"))?)?,
+                })
+            })
+            .collect()
+    }
+
+    async fn submit_transaction(&self, transaction: &Transaction) -> Result<TransactionHash> {
+        let vault = self.vault()?;
+        let mut payouts = self.payouts.lock().await;
+
+        if let Some((nonce, tx_hash)) = payouts.by_transaction.get(&transaction.id).cloned() {
+            match self.status(&tx_hash, Some(nonce)).await? {
+                L1TransactionStatus::Pending | L1TransactionStatus::Included(_) => {
+                    debug!("Payout for {} already broadcast as {}", transaction.id, tx_hash);
+                    return Ok(tx_hash);
+                }
+                // Someone else used the vault nonce; the payout never happened
+                L1TransactionStatus::Dropped => payouts.next = None,
+            }
+        }
+
+        let nonce = match payouts.next {
+            Some(next) => next,
+            None => self.transaction_count(vault, "pending").await?,
+        };
+        let call = serde_json::json!({
+            "from": vault.to_hex(),
+            "to": transaction.to_address.to_hex(),
+            "value": hex_quantity(&transaction.amount.amount),
+            "data": format!("0x{}", hex::encode(&transaction.data)),
+            "nonce": format!("{:#x}", nonce),
+        });
+        let tx_hash: String = self.call("eth_sendTransaction", serde_json::json!([call])).await?
+            .ok_or_else(|| self.rpc_error("eth_sendTransaction returned no hash".to_string()))?;
+        let tx_hash = TransactionHash(self.word(&tx_hash)?);
+
+        payouts.next = Some(nonce + 1);
+        payouts.by_transaction.insert(transaction.id, (nonce, tx_hash.clone()));
+        payouts.by_hash.insert(tx_hash.clone(), nonce);
+        Ok(tx_hash)
+    }
+
+    async fn get_receipt(&self, tx_hash: &TransactionHash) -> Result<L1TransactionStatus> {
+        let nonce = self.payouts.lock().await.by_hash.get(tx_hash).copied();
+        self.status(tx_hash, nonce).await
+    }
+
+    async fn get_block_number(&self) -> Result<u64> {
+        let block_number: String = self.call("eth_blockNumber", serde_json::json!([])).await?
+            .ok_or_else(|| self.rpc_error("eth_blockNumber returned no block".to_string()))?;
+        self.quantity(&block_number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bridge::config::BridgeConfig;
+    use crate::types::fixtures;
+
+    const CONTRACT: &str = "0x00000000000000000000000000000000000000bb";
+
+    fn client() -> EvmRpcClient {
+        let mut config = BridgeConfig::default().networks[&ChainId::ETHEREUM].clone();
+        config.bridge_contract = Some(CONTRACT.to_string());
+        EvmRpcClient::for_network(&config).unwrap().unwrap()
+    }
+
+    fn rpc_receipt(value: serde_json::Value) -> RpcReceipt {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_hex_quantity_is_minimal() {
+        assert_eq!(hex_quantity(&U256::ZERO), "0x0");
+        assert_eq!(hex_quantity(&U256::from(1u64)), "0x1");
+        assert_eq!(hex_quantity(&U256::from(255u64)), "0xff");
+        assert_eq!(hex_quantity(&U256::from(4096u64)), "0x1000");
+        assert_eq!(hex_quantity(&U256::from(1_000_000_000_000_000_000u64)), "0xde0b6b3a7640000");
+
+        let mut max = [0xffu8; 32];
+        max[0] = 0x01;
+        assert_eq!(hex_quantity(&U256(max)), format!("0x1{}", "f".repeat(62)));
+    }
+
+    #[test]
+    fn test_receipt_parsing_and_deposit_verification() {
+        let client = client();
+        let transaction = fixtures::transfer(1, 2, 5_000);
+        let tx_hash = TransactionHash([3u8; 32]);
+        let deposit_log = |from: &Address, amount: u64| serde_json::json!({
+            "address": CONTRACT,
+            "topics": [
+                hex_word(&deposit_topic()),
+                hex_word(&transaction.hash().0),
+                hex_word(&address_word(from)),
+            ],
+            "data": hex_word(&U256::from(amount).0),
+            "transactionHash": tx_hash.to_string(),
+        });
+        let receipt_json = |status: Option<&str>, logs: serde_json::Value| serde_json::json!({
+            "blockNumber": "0x121eac1",
+            "blockHash": hex_word(&[9u8; 32]),
+            "transactionIndex": "0x4",
+            "gasUsed": "0xcb20",
+            "status": status,
+            "logs": logs,
+        });
+
+        let receipt = client.receipt(&tx_hash, rpc_receipt(receipt_json(
+            Some("0x1"),
+            serde_json::json!([deposit_log(&transaction.from_address, 5_000)]),
+        ))).unwrap();
+        assert_eq!((receipt.block_number, receipt.transaction_index, receipt.gas_used), (19_000_001, 4, 52_000));
+        assert_eq!(receipt.block_hash, [9u8; 32]);
+        assert!(receipt.success);
+        assert_eq!(receipt.logs[0].address, Address::from_hex(CONTRACT).unwrap());
+        assert_eq!(receipt.logs[0].topics[0], deposit_topic());
+        assert!(verify_deposit(&receipt, &Address::from_hex(CONTRACT).unwrap(), &transaction).is_ok());
+
+        // A deposit of another amount, from another sender, or to another contract doesn't count
+        let contract = Address::from_hex(CONTRACT).unwrap();
+        let parse = |logs| client.receipt(&tx_hash, rpc_receipt(receipt_json(Some("0x1"), logs))).unwrap();
+        let short = parse(serde_json::json!([deposit_log(&transaction.from_address, 4_999)]));
+        assert_eq!(verify_deposit(&short, &contract, &transaction), Err("deposited amount differs"));
+        let other = parse(serde_json::json!([deposit_log(&Address([7u8; 20]), 5_000)]));
+        assert_eq!(verify_deposit(&other, &contract, &transaction), Err("deposited from a different address"));
+        assert!(verify_deposit(&receipt, &Address([7u8; 20]), &transaction).is_err());
+
+        // Failed and pre-Byzantium receipts
+        assert!(!client.receipt(&tx_hash, rpc_receipt(receipt_json(Some("0x0"), serde_json::json!([])))).unwrap().success);
+        assert!(client.receipt(&tx_hash, rpc_receipt(receipt_json(None, serde_json::json!([])))).unwrap().success);
+
+        // Malformed fields are errors, not zeroes
+        let mut malformed = receipt_json(Some("0x1"), serde_json::json!([]));
+        malformed["blockHash"] = serde_json::json!("0x1234");
+        assert!(matches!(
+            client.receipt(&tx_hash, rpc_receipt(malformed)),
+            Err(BridgeError::CrossChain(CrossChainError::L1RpcError { chain_id: 1, .. }))
+        ));
+    }
+}
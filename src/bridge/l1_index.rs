@@ -0,0 +1,136 @@
+/*!
+L1 transaction hash index
+
+Maps the hash of an L1 deposit transaction to the bridge it triggered, so an
+operator handling a support request can go from the hash a user sees in
+their wallet to the bridge receipt and its L2 transaction. A deposit is
+indexed as soon as its L1 leg is confirmed; the entry is updated as L2
+processing completes or fails.
+*/
+
+use crate::types::{BridgeReceipt, BridgeStatus, TransactionHash};
+use parking_lot::RwLock;
+use std::collections::HashMap;
+
+/// Latest bridge receipt per L1 transaction hash
+#[derive(Debug, Default)]
+pub struct L1TransactionIndex {
+    receipts: RwLock<HashMap<TransactionHash, BridgeReceipt>>,
+}
+
+impl L1TransactionIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index or update a receipt; receipts without an L1 leg are ignored
+    pub fn record(&self, receipt: &BridgeReceipt) {
+        if let Some(l1) = &receipt.l1_transaction {
+            self.receipts.write().insert(l1.transaction_hash.clone(), receipt.clone());
+        }
+    }
+
+    /// Index a newly confirmed deposit, unless its L1 transaction already
+    /// triggered a bridge that didn't fail. Returns whether it was indexed.
+    pub fn claim(&self, receipt: &BridgeReceipt) -> bool {
+        let Some(l1) = &receipt.l1_transaction else {
+            return false;
+        };
+        let mut receipts = self.receipts.write();
+        if let Some(existing) = receipts.get(&l1.transaction_hash) {
+            if !matches!(existing.status, BridgeStatus::Failed { .. }) {
+                return false;
+            }
+        }
+        receipts.insert(l1.transaction_hash.clone(), receipt.clone());
+        true
+    }
+
+    /// Bridge triggered by an L1 transaction, if it has been seen
+    pub fn find(&self, l1_transaction_hash: &TransactionHash) -> Option<BridgeReceipt> {
+        self.receipts.read().get(l1_transaction_hash).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.receipts.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.receipts.read().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TransactionReceipt;
+
+    fn receipt_for(hash: u8) -> TransactionReceipt {
+        TransactionReceipt {
+            transaction_hash: TransactionHash([hash; 32]),
+            block_number: 12345,
+            block_hash: [0u8; 32],
+            transaction_index: 0,
+            gas_used: 21000,
+            success: true,
+            logs: vec![],
+        }
+    }
+
+    #[test]
+    fn test_l1_hash_resolves_to_bridge_receipt() {
+        let index = L1TransactionIndex::new();
+        let l1_hash = TransactionHash([1u8; 32]);
+        let mut receipt = BridgeReceipt {
+            bridge_id: uuid::Uuid::new_v4(),
+            l1_transaction: Some(receipt_for(1)),
+            l2_transaction: None,
+            status: BridgeStatus::L1Confirmed,
+            bridged_at: chrono::Utc::now(),
+            settled_at: None,
+        };
+
+        // Seen on L1, L2 still in progress
+        index.record(&receipt);
+        let found = index.find(&l1_hash).unwrap();
+        assert_eq!(found.bridge_id, receipt.bridge_id);
+        assert_eq!(found.status, BridgeStatus::L1Confirmed);
+        assert!(found.l2_transaction.is_none());
+
+        // Completed on L2
+        receipt.l2_transaction = Some(receipt_for(2));
+        receipt.status = BridgeStatus::L2Confirmed;
+        index.record(&receipt);
+        let found = index.find(&l1_hash).unwrap();
+        assert_eq!(found.status, BridgeStatus::L2Confirmed);
+        assert_eq!(found.l2_transaction.unwrap().transaction_hash, TransactionHash([2u8; 32]));
+        assert_eq!(index.len(), 1);
+
+        assert!(index.find(&TransactionHash([9u8; 32])).is_none());
+
+        // L2-only bridges have nothing to index
+        index.record(&BridgeReceipt { l1_transaction: None, ..receipt });
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn test_deposit_claimed_once_unless_its_bridge_failed() {
+        let index = L1TransactionIndex::new();
+        let confirmed = |status| BridgeReceipt {
+            bridge_id: uuid::Uuid::new_v4(),
+            l1_transaction: Some(receipt_for(1)),
+            l2_transaction: None,
+            status,
+            bridged_at: chrono::Utc::now(),
+            settled_at: None,
+        };
+
+        assert!(index.claim(&confirmed(BridgeStatus::L1Confirmed)));
+        assert!(!index.claim(&confirmed(BridgeStatus::L1Confirmed)));
+
+        // A bridge that failed after confirming may be retried
+        index.record(&confirmed(BridgeStatus::Failed { reason: "L2 submission failed".to_string() }));
+        assert!(index.claim(&confirmed(BridgeStatus::L1Confirmed)));
+        assert!(!index.claim(&BridgeReceipt { l1_transaction: None, ..confirmed(BridgeStatus::L1Confirmed) }));
+    }
+}
@@ -0,0 +1,419 @@
+/*!
+Per-token bridge volume caps and minimum amounts
+
+Tracks how much of each token has been bridged within a rolling window and
+rejects transactions that would push the total past the configured cap. Used
+to bound the blast radius of an exploit.
+
+Dust transfers, worth less than the fees and gas spent moving them, are
+rejected against a per-token minimum given in base units or in USD. USD
+minimums are priced from the token economy's price cache, so an oracle outage
+falls back to the last known prices rather than blocking transfers.
+*/
+
+use crate::economy::TokenEconomy;
+use crate::error::{BridgeError, CrossChainError, Result, TokenError};
+use crate::types::{TokenAmount, TokenType, U256};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Volume accounting for a single token within the current window
+#[derive(Debug, Clone)]
+struct VolumeWindow {
+    started_at: DateTime<Utc>,
+    volume: U256,
+}
+
+/// Volume recorded against a cap, released if the bridge attempt fails
+#[derive(Debug, Clone)]
+pub struct VolumeReservation {
+    token_type: TokenType,
+    amount: U256,
+    window_started_at: DateTime<Utc>,
+}
+
+/// Per-token rolling volume cap enforcement
+pub struct VolumeLimiter {
+    caps: HashMap<TokenType, U256>,
+    window: chrono::Duration,
+    windows: Mutex<HashMap<TokenType, VolumeWindow>>,
+}
+
+impl VolumeLimiter {
+    /// Create a limiter with the given per-token caps and window length
+    pub fn new(caps: HashMap<TokenType, U256>, window: std::time::Duration) -> Self {
+        Self {
+            caps,
+            window: chrono::Duration::from_std(window).unwrap_or_else(|_| chrono::Duration::hours(24)),
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Check the amount against the token's cap and record it if allowed
+    ///
+    /// Returns the recorded reservation for capped tokens so a failed bridge
+    /// attempt can hand its volume back with [`VolumeLimiter::release`].
+    pub fn check_and_record(
+        &self,
+        token_type: TokenType,
+        amount: &U256,
+    ) -> Result<Option<VolumeReservation>> {
+        self.check_and_record_at(token_type, amount, Utc::now())
+    }
+
+    /// Check and record an amount as of the given timestamp
+    pub fn check_and_record_at(
+        &self,
+        token_type: TokenType,
+        amount: &U256,
+        now: DateTime<Utc>,
+    ) -> Result<Option<VolumeReservation>> {
+        let cap = match self.caps.get(&token_type) {
+            Some(cap) => cap,
+            None => return Ok(None), // Uncapped token
+        };
+
+        let mut windows = self.windows.lock();
+        let window = windows.entry(token_type).or_insert(VolumeWindow {
+            started_at: now,
+            volume: U256::ZERO,
+        });
+
+        // Reset at the window boundary
+        if now - window.started_at >= self.window {
+            window.started_at = now;
+            window.volume = U256::ZERO;
+        }
+
+        // Full width, so an amount above u64 cannot wrap under the cap
+        let attempted = window.volume.checked_add(amount);
+        match attempted {
+            Some(attempted) if attempted <= *cap => window.volume = attempted,
+            attempted => {
+                return Err(BridgeError::CrossChain(CrossChainError::VolumeCapExceeded {
+                    token: token_type.to_string(),
+                    cap: cap.clone(),
+                    attempted: attempted.unwrap_or(U256([0xff; 32])),
+                }));
+            }
+        }
+
+        Ok(Some(VolumeReservation {
+            token_type,
+            amount: amount.clone(),
+            window_started_at: window.started_at,
+        }))
+    }
+
+    /// Hand back volume recorded for a bridge attempt that did not go through
+    ///
+    /// A no-op once the window the reservation was made in has rolled over.
+    pub fn release(&self, reservation: &VolumeReservation) {
+        let mut windows = self.windows.lock();
+        if let Some(window) = windows.get_mut(&reservation.token_type) {
+            if window.started_at == reservation.window_started_at {
+                window.volume = window
+                    .volume
+                    .checked_sub(&reservation.amount)
+                    .unwrap_or(U256::ZERO);
+            }
+        }
+    }
+
+    /// Volume bridged for a token in the current window
+    pub fn current_volume(&self, token_type: TokenType) -> U256 {
+        self.current_volume_at(token_type, Utc::now())
+    }
+
+    /// Volume bridged for a token in the window containing `now`
+    pub fn current_volume_at(&self, token_type: TokenType, now: DateTime<Utc>) -> U256 {
+        let windows = self.windows.lock();
+        match windows.get(&token_type) {
+            Some(window) if now - window.started_at < self.window => window.volume.clone(),
+            _ => U256::ZERO,
+        }
+    }
+
+    /// Configured cap for a token, if any
+    pub fn cap(&self, token_type: TokenType) -> Option<U256> {
+        self.caps.get(&token_type).cloned()
+    }
+
+    /// Share of the token's cap used in the current window, from 0.0 to 1.0;
+    /// uncapped tokens report no demand
+    pub fn utilization(&self, token_type: TokenType) -> f64 {
+        match self.cap(token_type) {
+            Some(cap) if cap.is_zero() => 1.0,
+            Some(cap) => (self.current_volume(token_type).to_f64() / cap.to_f64()).min(1.0),
+            None => 0.0,
+        }
+    }
+}
+
+/// Smallest amount of a token worth bridging
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MinimumBridgeAmount {
+    /// In base units at the token's standard decimals
+    BaseUnits(u64),
+    /// USD value at the oracle's current price
+    Usd(f64),
+}
+
+/// Per-token minimum bridge amount enforcement
+pub struct DustFilter {
+    minimums: HashMap<TokenType, MinimumBridgeAmount>,
+    token_economy: Option<Arc<TokenEconomy>>,
+}
+
+impl DustFilter {
+    pub fn new(minimums: HashMap<TokenType, MinimumBridgeAmount>) -> Self {
+        Self { minimums, token_economy: None }
+    }
+
+    /// Price USD minimums from `economy`'s cached prices; without it they reject everything
+    pub fn with_token_economy(mut self, economy: Arc<TokenEconomy>) -> Self {
+        self.token_economy = Some(economy);
+        self
+    }
+
+    /// Reject `amount` if it is below its token's minimum
+    pub async fn check(&self, amount: &TokenAmount) -> Result<()> {
+        let below = |minimum: String| {
+            Err(BridgeError::CrossChain(CrossChainError::BelowMinimumAmount {
+                token: amount.token_type.to_string(),
+                amount: amount.to_human_readable(),
+                minimum,
+            }))
+        };
+
+        match self.minimums.get(&amount.token_type) {
+            None => Ok(()),
+            Some(MinimumBridgeAmount::BaseUnits(minimum)) => {
+                // An amount too large to express at standard decimals is far from dust
+                let minimum = TokenAmount::new(amount.token_type, U256::from(*minimum));
+                if standard_units(amount).is_some_and(|units| units < minimum.amount) {
+                    return below(minimum.to_human_readable());
+                }
+                Ok(())
+            }
+            Some(MinimumBridgeAmount::Usd(minimum_usd)) => {
+                // Anything past u64 base units is far from dust
+                if amount.amount.0 > U256::from(u64::MAX).0 {
+                    return Ok(());
+                }
+                let price_usd = self.price_usd(amount.token_type).await?;
+                let value_usd = amount.amount.to_u64() as f64 * price_usd / 10f64.powi(amount.decimals as i32);
+                if value_usd < *minimum_usd {
+                    return below(format!("${:.2}", minimum_usd));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    async fn price_usd(&self, token_type: TokenType) -> Result<f64> {
+        let unavailable = || BridgeError::Token(TokenError::PricingUnavailable { token: token_type.to_string() });
+        let economy = self.token_economy.as_ref().ok_or_else(unavailable)?;
+        economy.get_token_pricing().await?
+            .get(&token_type)
+            .map(|price| price.price_usd)
+            .filter(|price| *price > 0.0)
+            .ok_or_else(unavailable)
+    }
+
+    /// Configured minimum for a token, if any
+    pub fn minimum(&self, token_type: TokenType) -> Option<MinimumBridgeAmount> {
+        self.minimums.get(&token_type).copied()
+    }
+}
+
+/// `amount` in base units at its token's standard decimals, rounded down;
+/// None if it overflows a U256 at that precision
+fn standard_units(amount: &TokenAmount) -> Option<U256> {
+    let standard_decimals = TokenAmount::new(amount.token_type, U256::ZERO).decimals;
+    let mut units = amount.amount.clone();
+    for _ in standard_decimals..amount.decimals {
+        units = units.div_rem_u64(10).0;
+    }
+    for _ in amount.decimals..standard_decimals {
+        units = units.checked_mul_u64(10)?;
+    }
+    Some(units)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::oracle::PriceOracle;
+    use crate::services::ServiceManager;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn limiter(cap: u64) -> VolumeLimiter {
+        let mut caps = HashMap::new();
+        caps.insert(TokenType::Gcc, U256::from(cap));
+        VolumeLimiter::new(caps, Duration::from_secs(24 * 60 * 60))
+    }
+
+    #[test]
+    fn test_volume_cap_rejects_until_window_resets() {
+        let limiter = limiter(1000);
+        let start = Utc::now();
+
+        assert!(limiter.check_and_record_at(TokenType::Gcc, &U256::from(600), start).is_ok());
+        assert!(limiter.check_and_record_at(TokenType::Gcc, &U256::from(400), start).is_ok());
+        assert_eq!(limiter.current_volume_at(TokenType::Gcc, start), U256::from(1000));
+
+        // Cap reached - the next transaction is rejected
+        let err = limiter
+            .check_and_record_at(TokenType::Gcc, &U256::from(1), start + chrono::Duration::hours(23))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            BridgeError::CrossChain(CrossChainError::VolumeCapExceeded { .. })
+        ));
+
+        // Window boundary resets the accounting
+        let reset = start + chrono::Duration::hours(24);
+        assert!(limiter.check_and_record_at(TokenType::Gcc, &U256::from(1), reset).is_ok());
+        assert_eq!(limiter.current_volume_at(TokenType::Gcc, reset), U256::from(1));
+    }
+
+    #[test]
+    fn test_uncapped_token_is_unlimited() {
+        let limiter = limiter(1000);
+        assert!(limiter.check_and_record(TokenType::Mana, &U256::from(u64::MAX)).is_ok());
+        assert_eq!(limiter.cap(TokenType::Mana), None);
+        assert_eq!(limiter.utilization(TokenType::Mana), 0.0);
+    }
+
+    #[test]
+    fn test_utilization_is_share_of_cap_used() {
+        let limiter = limiter(1000);
+        assert_eq!(limiter.utilization(TokenType::Gcc), 0.0);
+        limiter.check_and_record(TokenType::Gcc, &U256::from(250)).unwrap();
+        assert_eq!(limiter.utilization(TokenType::Gcc), 0.25);
+        limiter.check_and_record(TokenType::Gcc, &U256::from(750)).unwrap();
+        assert_eq!(limiter.utilization(TokenType::Gcc), 1.0);
+    }
+
+    #[test]
+    fn test_amount_above_u64_is_not_truncated_under_cap() {
+        let limiter = limiter(1000);
+        // Low 64 bits are 1, which a truncating check would let through
+        let mut bytes = [0u8; 32];
+        bytes[23] = 1;
+        bytes[31] = 1;
+        let huge = U256(bytes);
+
+        let err = limiter.check_and_record(TokenType::Gcc, &huge).unwrap_err();
+        assert!(matches!(
+            err,
+            BridgeError::CrossChain(CrossChainError::VolumeCapExceeded { ref attempted, .. }) if *attempted == huge
+        ));
+        assert_eq!(limiter.current_volume(TokenType::Gcc), U256::ZERO);
+    }
+
+    #[test]
+    fn test_release_returns_volume_to_the_window() {
+        let limiter = limiter(1000);
+        let start = Utc::now();
+
+        let reservation = limiter
+            .check_and_record_at(TokenType::Gcc, &U256::from(1000), start)
+            .unwrap()
+            .unwrap();
+        assert!(limiter.check_and_record_at(TokenType::Gcc, &U256::from(1), start).is_err());
+
+        limiter.release(&reservation);
+        assert_eq!(limiter.current_volume_at(TokenType::Gcc, start), U256::ZERO);
+        assert!(limiter.check_and_record_at(TokenType::Gcc, &U256::from(1000), start).is_ok());
+    }
+
+    #[test]
+    fn test_release_after_window_reset_is_noop() {
+        let limiter = limiter(1000);
+        let start = Utc::now();
+
+        let reservation = limiter
+            .check_and_record_at(TokenType::Gcc, &U256::from(600), start)
+            .unwrap()
+            .unwrap();
+        let next = start + chrono::Duration::hours(24);
+        limiter.check_and_record_at(TokenType::Gcc, &U256::from(300), next).unwrap();
+
+        limiter.release(&reservation);
+        assert_eq!(limiter.current_volume_at(TokenType::Gcc, next), U256::from(300));
+    }
+
+    /// Serves one GCC price and counts how often it is asked
+    struct FixedOracle {
+        price_usd: f64,
+        fetches: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl PriceOracle for FixedOracle {
+        async fn fetch_prices(&self) -> Result<HashMap<TokenType, crate::economy::TokenPrice>> {
+            self.fetches.fetch_add(1, Ordering::SeqCst);
+            Ok(HashMap::from([(TokenType::Gcc, crate::economy::TokenPrice {
+                token_type: TokenType::Gcc,
+                price_usd: self.price_usd,
+                market_cap_usd: 0.0,
+                volume_24h_usd: 0.0,
+                change_24h_percent: 0.0,
+                last_updated: Utc::now(),
+            })]))
+        }
+    }
+
+    fn gcc(amount: u64) -> TokenAmount {
+        TokenAmount::new(TokenType::Gcc, U256::from(amount))
+    }
+
+    fn is_dust(result: Result<()>) -> bool {
+        matches!(result, Err(BridgeError::CrossChain(CrossChainError::BelowMinimumAmount { .. })))
+    }
+
+    #[tokio::test]
+    async fn test_dust_rejected_below_base_unit_minimum() {
+        let filter = DustFilter::new(HashMap::from([(TokenType::Gcc, MinimumBridgeAmount::BaseUnits(1_000))]));
+
+        assert!(is_dust(filter.check(&gcc(999)).await));
+        assert!(filter.check(&gcc(1_000)).await.is_ok());
+        // Tokens without a minimum are unaffected
+        assert!(filter.check(&TokenAmount::new(TokenType::Mana, U256::from(1))).await.is_ok());
+
+        // 999 at 6 decimals is 999 * 10^12 at GCC's 18, well above the minimum
+        let six_decimals = TokenAmount { decimals: 6, ..gcc(999) };
+        assert!(filter.check(&six_decimals).await.is_ok());
+        // 1_000 at 20 decimals is 10 at 18
+        let twenty_decimals = TokenAmount { decimals: 20, ..gcc(1_000) };
+        assert!(is_dust(filter.check(&twenty_decimals).await));
+    }
+
+    #[tokio::test]
+    async fn test_dust_rejected_below_usd_minimum() {
+        // $1 at $0.50 per GCC is 2 GCC
+        let minimums = HashMap::from([(TokenType::Gcc, MinimumBridgeAmount::Usd(1.0))]);
+        let oracle = Arc::new(FixedOracle { price_usd: 0.5, fetches: AtomicUsize::new(0) });
+        let services = Arc::new(ServiceManager::new(crate::services::ServiceConfig::default()));
+        services.init_gledger().await.unwrap();
+        let economy = Arc::new(TokenEconomy::new(services, oracle.clone()).await.unwrap());
+        let filter = DustFilter::new(minimums.clone()).with_token_economy(economy);
+
+        assert!(is_dust(filter.check(&gcc(1_900_000_000_000_000_000)).await));
+        assert!(filter.check(&gcc(2_000_000_000_000_000_000)).await.is_ok());
+        // Prices come from the economy's cache, not the oracle per transfer
+        assert_eq!(oracle.fetches.load(Ordering::SeqCst), 1);
+
+        // Without a price the minimum cannot be checked, so the transfer is refused
+        assert!(matches!(
+            DustFilter::new(minimums).check(&gcc(2_000_000_000_000_000_000)).await,
+            Err(BridgeError::Token(TokenError::PricingUnavailable { .. }))
+        ));
+    }
+}
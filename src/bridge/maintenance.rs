@@ -0,0 +1,170 @@
+/*!
+Scheduled maintenance windows
+
+Operators announce maintenance ahead of time so clients can show it and stop
+submitting before it starts. During a window the bridge rejects submissions
+with the window's message; it resumes on its own once the window ends.
+*/
+
+use crate::error::{BridgeError, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::info;
+
+/// A planned maintenance period
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Shown to clients and returned with rejected submissions
+    pub message: String,
+}
+
+impl MaintenanceWindow {
+    fn contains(&self, now: DateTime<Utc>) -> bool {
+        self.start <= now && now < self.end
+    }
+}
+
+/// Maintenance schedule settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceConfig {
+    /// How far ahead an upcoming window is announced
+    pub notice_period: Duration,
+    pub windows: Vec<MaintenanceWindow>,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            notice_period: Duration::from_secs(24 * 60 * 60), // 24 hours
+            windows: Vec::new(),
+        }
+    }
+}
+
+/// Maintenance state reported to clients
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum MaintenanceStatus {
+    #[default]
+    Operational,
+    /// A window starts within the notice period
+    Upcoming { window: MaintenanceWindow },
+    /// Submissions are rejected until the window ends
+    InProgress { window: MaintenanceWindow },
+}
+
+/// Tracks maintenance windows and gates submissions during them
+pub struct MaintenanceSchedule {
+    notice_period: chrono::Duration,
+    windows: RwLock<Vec<MaintenanceWindow>>,
+}
+
+impl MaintenanceSchedule {
+    pub fn new(config: &MaintenanceConfig) -> Self {
+        let mut windows = config.windows.clone();
+        windows.sort_by_key(|window| window.start);
+        Self {
+            notice_period: chrono::Duration::from_std(config.notice_period).unwrap_or(chrono::Duration::zero()),
+            windows: RwLock::new(windows),
+        }
+    }
+
+    /// Announce a new window
+    pub fn schedule(&self, window: MaintenanceWindow) -> Result<()> {
+        if window.end <= window.start {
+            return Err(BridgeError::config("maintenance window must end after it starts"));
+        }
+        info!("Scheduled maintenance from {} to {}: {}", window.start, window.end, window.message);
+        let mut windows = self.windows.write();
+        windows.push(window);
+        windows.sort_by_key(|window| window.start);
+        Ok(())
+    }
+
+    /// Remove a window starting at `start`, returning whether one was removed
+    pub fn cancel(&self, start: DateTime<Utc>) -> bool {
+        let mut windows = self.windows.write();
+        let before = windows.len();
+        windows.retain(|window| window.start != start);
+        windows.len() != before
+    }
+
+    pub fn status(&self) -> MaintenanceStatus {
+        self.status_at(Utc::now())
+    }
+
+    pub fn status_at(&self, now: DateTime<Utc>) -> MaintenanceStatus {
+        let windows = self.windows.read();
+        if let Some(window) = windows.iter().find(|window| window.contains(now)) {
+            return MaintenanceStatus::InProgress { window: window.clone() };
+        }
+        windows.iter()
+            .find(|window| window.start > now && window.start - now <= self.notice_period)
+            .map(|window| MaintenanceStatus::Upcoming { window: window.clone() })
+            .unwrap_or_default()
+    }
+
+    /// Reject submissions during a maintenance window
+    pub fn check(&self) -> Result<()> {
+        self.check_at(Utc::now())
+    }
+
+    pub fn check_at(&self, now: DateTime<Utc>) -> Result<()> {
+        match self.status_at(now) {
+            MaintenanceStatus::InProgress { window } => Err(BridgeError::Maintenance {
+                message: window.message,
+                ends_at: window.end.to_rfc3339(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Drop windows that have ended
+    pub fn prune(&self, now: DateTime<Utc>) {
+        self.windows.write().retain(|window| window.end > now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_submissions_rejected_during_window_and_resume_after() {
+        let start = Utc::now() + chrono::Duration::hours(2);
+        let window = MaintenanceWindow {
+            start,
+            end: start + chrono::Duration::minutes(30),
+            message: "Upgrading settlement contract".to_string(),
+        };
+        let schedule = MaintenanceSchedule::new(&MaintenanceConfig {
+            notice_period: Duration::from_secs(60 * 60),
+            windows: vec![window.clone()],
+        });
+
+        // Announced only once inside the notice period
+        let three_hours_before = start - chrono::Duration::hours(3);
+        assert_eq!(schedule.status_at(three_hours_before), MaintenanceStatus::Operational);
+        let just_before = start - chrono::Duration::minutes(10);
+        assert_eq!(schedule.status_at(just_before), MaintenanceStatus::Upcoming { window: window.clone() });
+        assert!(schedule.check_at(just_before).is_ok());
+
+        // Paused at the start
+        let during = start + chrono::Duration::minutes(5);
+        let error = schedule.check_at(during).unwrap_err();
+        assert!(error.to_string().contains("Upgrading settlement contract"), "{}", error);
+        assert!(matches!(schedule.status_at(start), MaintenanceStatus::InProgress { .. }));
+
+        // Resumed at the end
+        assert!(schedule.check_at(window.end).is_ok());
+        assert_eq!(schedule.status_at(window.end), MaintenanceStatus::Operational);
+
+        schedule.prune(window.end);
+        assert!(!schedule.cancel(start));
+        assert!(schedule.schedule(MaintenanceWindow { end: start, ..window }).is_err());
+    }
+}
@@ -4,13 +4,19 @@ Core GhostBridge implementation
 Main bridge orchestration for cross-chain transactions, L2 settlement, and service coordination.
 */
 
-use crate::error::{BridgeError, Result, CrossChainError};
+use crate::error::{BridgeError, Result, CrossChainError, ServiceError};
+use crate::health::HealthState;
 use crate::types::{
     Transaction, TransactionReceipt, BridgeReceipt, BridgeStatus, Network, ChainId,
-    TokenAmount, MultiTokenFee, L2Batch, SettlementProof,
+    TokenAmount, MultiTokenFee, L2Batch, SettlementProof, TokenType, TransactionHash, U256, Address,
 };
 use crate::services::{ServiceManager, ServiceConfig};
 use crate::ffi::{GhostPlaneFfi, GhostPlaneConfig};
+use crate::transport::GhostPlaneChannel;
+use crate::security::CryptoProvider;
+use crate::settlement::{SettlementConfig, StateManager, ZKProofSystem};
+use crate::economy::TokenEconomy;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, instrument, warn};
@@ -20,18 +26,64 @@ use uuid::Uuid;
 pub mod config;
 pub mod validator;
 pub mod settlement;
+pub mod limits;
+pub mod capabilities;
+pub mod simulation;
+pub mod diagnostics;
+pub mod maintenance;
+pub mod state_reads;
+pub mod collateral;
+pub mod decimals;
+pub mod l1_index;
+pub mod allowances;
+pub mod degraded;
+pub mod adapters;
+pub mod l1_client;
+pub mod fee_quotes;
 
 pub use config::BridgeConfig;
 pub use validator::TransactionValidator;
 pub use settlement::SettlementEngine;
+pub use limits::{DustFilter, MinimumBridgeAmount, VolumeLimiter, VolumeReservation};
+pub use capabilities::{BridgeCapabilities, NetworkCapability, SettlementMode, FeatureFlags};
+pub use simulation::{L1Rpc, L1Simulator, L1Simulation, BridgeSimulation};
+pub use diagnostics::{DiagnosticCheck, DiagnosticReport, SelfDiagnostic, SubsystemResult};
+pub use maintenance::{MaintenanceConfig, MaintenanceSchedule, MaintenanceStatus, MaintenanceWindow};
+pub use state_reads::{CrossChainStateReader, StateRead};
+pub use collateral::CollateralLedger;
+pub use l1_index::L1TransactionIndex;
+pub use allowances::{AllowanceRegistry, DelegatedSpend, delegation_message};
+pub use degraded::{BridgeFeature, DegradedMode};
+pub use adapters::{ChainAdapter, ChainAdapterRegistry, DepositScan, EvmChainAdapter, ObservedDeposit};
+pub use l1_client::{DepositEvent, EvmChain, EvmRpcClient, InclusionPolicy, L1ChainClient, L1TransactionStatus};
+pub use fee_quotes::{FeeQuote, FeeQuoteConfig, FeeQuoter, MarketConditions, SignedFeeQuote};
 
 /// Main GhostBridge instance
 pub struct GhostBridge {
     config: BridgeConfig,
     services: Arc<ServiceManager>,
     ghostplane_ffi: Arc<RwLock<GhostPlaneFfi>>,
+    /// Channel to a GhostPlane on another host; batches go here instead of
+    /// over the FFI when set
+    remote_ghostplane: Option<GhostPlaneChannel>,
     validator: TransactionValidator,
     settlement_engine: Arc<SettlementEngine>,
+    volume_limiter: VolumeLimiter,
+    dust_filter: DustFilter,
+    l1_simulator: Option<L1Simulator>,
+    adapters: ChainAdapterRegistry,
+    /// Built-in adapter for the configured EVM networks
+    evm_adapter: Arc<EvmChainAdapter>,
+    maintenance: MaintenanceSchedule,
+    collateral: CollateralLedger,
+    l1_index: L1TransactionIndex,
+    allowances: AllowanceRegistry,
+    degraded: DegradedMode,
+    fee_quoter: FeeQuoter,
+    /// Fill of the last batch submitted to L2, priced in as congestion
+    l2_utilization: parking_lot::Mutex<f64>,
+    /// Owns the chain of L2 batch state roots, each linked to the one before
+    state_manager: Arc<StateManager>,
     metrics: Arc<BridgeMetrics>,
 }
 
@@ -78,14 +130,44 @@ impl GhostBridge {
             config.l2_config.clone(),
             services.clone(),
         ).await?);
+        let volume_limiter = VolumeLimiter::new(
+            config.token_config.bridge_volume_caps(),
+            config.token_config.bridge_volume_window,
+        );
+        let dust_filter = DustFilter::new(config.token_config.min_bridge_amounts());
         let metrics = Arc::new(BridgeMetrics::new());
+        let maintenance = MaintenanceSchedule::new(&config.maintenance);
+        let degraded = DegradedMode::new().with_quorum(config.min_healthy_services);
+        let state_manager = Arc::new(StateManager::new(SettlementConfig::default()).await?);
+        let collateral = match &config.collateral_ledger_path {
+            Some(path) => CollateralLedger::open(path).await?,
+            None => CollateralLedger::new(),
+        };
+        let fee_quoter = FeeQuoter::new(&config.fee_quotes).await?;
+        let evm_adapter = Arc::new(EvmChainAdapter::from_config(&config));
+        let adapters = ChainAdapterRegistry::empty();
+        adapters.register(evm_adapter.clone());
 
         let bridge = Self {
             config,
             services,
             ghostplane_ffi: Arc::new(RwLock::new(ghostplane_ffi)),
+            remote_ghostplane: None,
             validator,
             settlement_engine,
+            volume_limiter,
+            dust_filter,
+            l1_simulator: None,
+            adapters,
+            evm_adapter,
+            maintenance,
+            collateral,
+            l1_index: L1TransactionIndex::new(),
+            allowances: AllowanceRegistry::new(),
+            degraded,
+            fee_quoter,
+            l2_utilization: parking_lot::Mutex::new(0.0),
+            state_manager,
             metrics,
         };
 
@@ -93,15 +175,259 @@ impl GhostBridge {
         Ok(bridge)
     }
 
-    /// Bridge a transaction from L1 to L2
+    /// Simulate L1 legs against forked L1 state before submission
+    pub fn with_l1_simulator(mut self, simulator: L1Simulator) -> Self {
+        self.l1_simulator = Some(simulator);
+        self
+    }
+
+    /// Submit batches to a remote GhostPlane over a negotiated, compressed
+    /// and encrypted channel instead of the local FFI
+    pub fn with_remote_ghostplane(mut self, channel: GhostPlaneChannel) -> Self {
+        self.remote_ghostplane = Some(channel);
+        self
+    }
+
+    /// Link submitted batches into `state_manager`'s chain of roots, e.g.
+    /// `L2SettlementEngine::state_manager`, which continues from the latest
+    /// finalized batch when the engine starts
+    pub fn with_state_manager(mut self, state_manager: Arc<StateManager>) -> Self {
+        self.state_manager = state_manager;
+        self
+    }
+
+    /// Price USD-denominated minimum bridge amounts from `economy`'s price cache
+    pub fn with_token_economy(mut self, economy: Arc<TokenEconomy>) -> Self {
+        self.dust_filter = self.dust_filter.with_token_economy(economy);
+        self
+    }
+
+    /// Read L1 state for cross-chain queries, buffered behind the head per
+    /// network config. The reader is added to the built-in EVM adapter, so
+    /// its L1 clients and any adapter registered since keep serving.
+    pub fn with_l1_state_reader(self, rpc: Arc<dyn L1Rpc>) -> Self {
+        self.evm_adapter.set_state_reader(CrossChainStateReader::from_config(rpc, &self.config));
+        self
+    }
+
+    /// Bridge from or to another chain through `adapter`
+    pub fn with_chain_adapter(self, adapter: Arc<dyn ChainAdapter>) -> Self {
+        self.adapters.register(adapter);
+        self
+    }
+
+    /// Registered source-chain adapters
+    pub fn chain_adapters(&self) -> &ChainAdapterRegistry {
+        &self.adapters
+    }
+
+    /// Watch for the deposit paying for `transaction` on its source chain,
+    /// so `bridge_deposits` picks it up
+    pub fn expect_deposit(&self, transaction: Transaction) {
+        self.evm_adapter.expect_deposit(transaction);
+    }
+
+    /// Bridge the deposits an adapter has seen on `network` since `from_block`.
+    /// A failing deposit does not stop the rest; the sweep's `next_block`
+    /// stays at the first failed deposit's block so the next sweep retries it.
+    pub async fn bridge_deposits(&self, network: &Network, from_block: u64) -> Result<DepositSweep> {
+        let scan = self.adapters.require(network)?.fetch_deposits(network, from_block).await?;
+        let mut sweep = DepositSweep { receipts: Vec::new(), failures: Vec::new(), next_block: scan.next_block };
+        for deposit in scan.deposits {
+            let transaction = deposit.transaction;
+            // The deposit on the source chain is the owner's authorization
+            match self.process_bridge_transaction(transaction.clone()).await {
+                Ok(receipt) => {
+                    self.evm_adapter.forget_deposit(&transaction);
+                    sweep.receipts.push(receipt);
+                }
+                // Bridged by an earlier sweep; nothing left to do
+                Err(BridgeError::CrossChain(CrossChainError::DepositAlreadyBridged { .. })) => {
+                    self.evm_adapter.forget_deposit(&transaction);
+                }
+                Err(e) => {
+                    warn!("Deposit {} on {:?} not bridged: {}", transaction.id, network, e);
+                    sweep.next_block = sweep.next_block.min(deposit.block_number);
+                    sweep.failures.push((transaction.id, e.to_string()));
+                }
+            }
+        }
+        Ok(sweep)
+    }
+
+    /// Predict whether a bridge transaction will succeed without submitting it
+    #[instrument(skip(self, transaction))]
+    pub async fn simulate_bridge(&self, transaction: &Transaction) -> Result<BridgeSimulation> {
+        self.validator.validate(transaction).await?;
+
+        let l1 = if self.requires_l1_processing(transaction) {
+            self.simulate_l1_leg(transaction).await?
+        } else {
+            None
+        };
+
+        Ok(BridgeSimulation {
+            will_succeed: l1.as_ref().map_or(true, |sim| sim.will_succeed),
+            l1,
+        })
+    }
+
+    /// Let `spender` bridge up to `amount` of the owner's tokens; the caller
+    /// is responsible for having authenticated the owner
+    pub fn approve_bridge_allowance(&self, owner: &Address, spender: &Address, amount: &TokenAmount) {
+        self.allowances.approve(owner, spender, amount);
+    }
+
+    /// Stop `spender` from bridging any more of the owner's `token_type`
+    pub fn revoke_bridge_allowance(&self, owner: &Address, spender: &Address, token_type: TokenType) {
+        self.allowances.revoke(owner, spender, token_type);
+    }
+
+    /// Amount `spender` may still bridge on the owner's behalf
+    pub fn bridge_allowance(&self, owner: &Address, spender: &Address, token_type: TokenType) -> U256 {
+        self.allowances.allowance(owner, spender, token_type)
+    }
+
+    /// Signed fee quote for bridging `transaction`, honored by
+    /// `bridge_transaction_with_quote` until it expires
+    pub async fn estimate_fee(&self, transaction: &Transaction) -> Result<SignedFeeQuote> {
+        self.fee_quoter.quote(transaction, self.market_conditions(transaction)).await
+    }
+
+    /// Load `transaction` is priced at: how full the last L2 batch was and
+    /// how much of its token's volume cap is used
+    fn market_conditions(&self, transaction: &Transaction) -> MarketConditions {
+        MarketConditions {
+            congestion: *self.l2_utilization.lock(),
+            token_demand: self.volume_limiter.utilization(transaction.amount.token_type),
+        }
+    }
+
+    /// Bridge a transaction submitted with the submitter's signature over
+    /// its `allowances::delegation_message`, charging the fee at current
+    /// rates whatever fee it carries; use `bridge_transaction_with_quote` to
+    /// pay a quoted fee. When the signer isn't the owner of the funds, the
+    /// amount is drawn from the allowance the owner approved for it, and
+    /// given back only if the bridge failed before any leg moved funds.
+    pub async fn bridge_transaction(&self, transaction: Transaction, submitter_signature: &[u8]) -> Result<BridgeReceipt> {
+        let spend = self.allowances.authorize(&transaction, submitter_signature)?;
+        self.bridge_authorized(transaction, spend, None).await
+    }
+
+    /// Bridge a transaction at the fee from a quote returned by
+    /// `estimate_fee`. An expired quote is repriced at current rates; one
+    /// that was modified, issued for another transaction, or already used is
+    /// rejected.
+    pub async fn bridge_transaction_with_quote(
+        &self,
+        transaction: Transaction,
+        quote: &SignedFeeQuote,
+        submitter_signature: &[u8],
+    ) -> Result<BridgeReceipt> {
+        let spend = self.allowances.authorize(&transaction, submitter_signature)?;
+        self.bridge_authorized(transaction, spend, Some(quote)).await
+    }
+
+    /// Price and bridge an authorized transaction, at the quoted fee if a
+    /// quote is given and at current rates otherwise; an allowance drawn for
+    /// it is settled once the bridge has run
+    async fn bridge_authorized(
+        &self,
+        mut transaction: Transaction,
+        spend: Option<DelegatedSpend>,
+        quote: Option<&SignedFeeQuote>,
+    ) -> Result<BridgeReceipt> {
+        let conditions = self.market_conditions(&transaction);
+        let fee = match quote {
+            Some(quote) => self.fee_quoter.resolve_fee(&transaction, quote, conditions).await,
+            None => self.fee_quoter.calculate_fee(&transaction, conditions).await,
+        };
+        let result = match fee {
+            Ok(fee) => {
+                transaction.fee = fee;
+                self.process_bridge_transaction(transaction).await
+            }
+            Err(e) => Err(e),
+        };
+        if let Some(spend) = &spend {
+            self.allowances.settle(spend, &result);
+        }
+        result
+    }
+
+    /// Bridge to the address a CNS name resolves to. The submitter signs the
+    /// transaction as addressed to the name's current owner, so a signed
+    /// submission cannot be redirected to another name. Unavailable while
+    /// CNS is down; address-based bridging is unaffected.
+    pub async fn bridge_to_name(
+        &self,
+        mut transaction: Transaction,
+        recipient: &str,
+        submitter_signature: &[u8],
+    ) -> Result<BridgeReceipt> {
+        self.degraded.require(BridgeFeature::NameResolution)?;
+
+        let resolution = {
+            let mut cns_guard = self.services.cns_mut().await?;
+            let cns = cns_guard.as_mut().unwrap();
+            cns.resolve_domain(recipient).await?
+        };
+        transaction.to_address = resolution.owner.ok_or_else(|| {
+            BridgeError::CrossChain(CrossChainError::InvalidTransaction {
+                chain_id: transaction.to_chain.chain_id().map_or(0, |chain_id| chain_id.0),
+                reason: format!("CNS name {} has no owner address", recipient),
+            })
+        })?;
+
+        self.bridge_transaction(transaction, submitter_signature).await
+    }
+
+    /// Features disabled because services they depend on are down
+    pub fn degraded_features(&self) -> Vec<BridgeFeature> {
+        self.degraded.degraded_features()
+    }
+
     #[instrument(skip(self, transaction))]
-    pub async fn bridge_transaction(&self, transaction: Transaction) -> Result<BridgeReceipt> {
+    async fn process_bridge_transaction(&self, transaction: Transaction) -> Result<BridgeReceipt> {
         info!("Processing bridge transaction: {}", transaction.id);
 
+        self.maintenance.check()?;
+        self.degraded.require_quorum()?;
+        self.degraded.require(BridgeFeature::CoreBridging)?;
+
         // Validate transaction
         self.validator.validate(&transaction).await?;
         self.metrics.record_bridge_attempt();
 
+        // Scale the amount into the destination chain's decimals up front so a
+        // lossy conversion is rejected before anything is submitted
+        let destination_amount = decimals::convert_amount(
+            &self.config.token_config,
+            &transaction.amount,
+            &transaction.from_chain,
+            &transaction.to_chain,
+        )?;
+
+        // Reject dust before it counts toward volume caps
+        self.dust_filter.check(&transaction.amount).await?;
+
+        // Enforce per-token volume caps, which are set at the token's standard
+        // decimals; failed attempts hand their volume back
+        let volume = decimals::standard_amount(&self.config.token_config, &transaction.amount, &transaction.from_chain)?;
+        let reservation = match self.volume_limiter.check_and_record(transaction.amount.token_type, &volume) {
+            Ok(reservation) => reservation,
+            Err(e) => {
+                warn!("Bridge volume cap exceeded for transaction {}: {}", transaction.id, e);
+                self.metrics.record_volume_cap_rejection();
+                return Err(e);
+            }
+        };
+        let release_volume = || {
+            if let Some(reservation) = &reservation {
+                self.volume_limiter.release(reservation);
+            }
+        };
+
         // Create bridge receipt
         let bridge_id = Uuid::new_v4();
         let mut receipt = BridgeReceipt {
@@ -117,32 +443,88 @@ impl GhostBridge {
         if self.requires_l1_processing(&transaction) {
             match self.process_l1_transaction(&transaction).await {
                 Ok(l1_receipt) => {
+                    let l1_hash = l1_receipt.transaction_hash.clone();
                     receipt.l1_transaction = Some(l1_receipt);
                     receipt.status = BridgeStatus::L1Confirmed;
+                    // A deposit is credited once, however often it is presented
+                    if !self.l1_index.claim(&receipt) {
+                        warn!("L1 deposit {} for {} was already bridged", l1_hash, transaction.id);
+                        release_volume();
+                        return Err(BridgeError::CrossChain(CrossChainError::DepositAlreadyBridged {
+                            tx_hash: l1_hash.to_string(),
+                        }));
+                    }
+                    if let Err(e) = self.collateral.record_deposit(&transaction).await {
+                        error!("Collateral accounting out of sync for {}: {}", transaction.id, e);
+                    }
                 }
                 Err(e) => {
                     error!("L1 processing failed: {}", e);
                     receipt.status = BridgeStatus::Failed {
                         reason: format!("L1 processing failed: {}", e),
                     };
+                    release_volume();
                     return Ok(receipt);
                 }
             }
         }
 
-        // Submit to L2 (GhostPlane)
-        match self.submit_to_l2(&transaction).await {
+        // Submit to L2 (GhostPlane), denominated in L2 decimals
+        let l2_transaction = if self.requires_l1_processing(&transaction) {
+            Transaction { amount: destination_amount.clone(), ..transaction.clone() }
+        } else {
+            transaction.clone()
+        };
+        match self.submit_to_l2(&l2_transaction).await {
             Ok(l2_receipt) => {
                 receipt.l2_transaction = Some(l2_receipt);
                 receipt.status = BridgeStatus::L2Confirmed;
+                if self.releases_l1_collateral(&transaction) {
+                    // Collateral is tracked in L1 decimals
+                    let release = Transaction { amount: destination_amount, ..transaction.clone() };
+                    match self.submit_l1_withdrawal(&release).await {
+                        Ok(l1_receipt) => {
+                            receipt.l1_transaction = Some(l1_receipt);
+                            if let Err(e) = self.collateral.record_withdrawal(&release).await {
+                                error!("Collateral accounting out of sync for {}: {}", transaction.id, e);
+                            }
+                        }
+                        Err(e) if e.is_l1_outcome_unknown() => {
+                            // The payout may still be mined, so it must not be
+                            // treated as failed and paid again
+                            error!("L1 withdrawal for {} not yet included, needs reconciliation: {}", transaction.id, e);
+                            return Ok(receipt);
+                        }
+                        Err(e) => {
+                            error!("L1 withdrawal failed: {}", e);
+                            // The L2 debit already went through; credit it back
+                            let refund = match self.refund_on_l2(&l2_transaction).await {
+                                Ok(_) => "amount refunded on L2".to_string(),
+                                Err(refund_error) => {
+                                    error!("L2 refund for {} failed, needs reconciliation: {}", transaction.id, refund_error);
+                                    format!("L2 refund failed: {}", refund_error)
+                                }
+                            };
+                            receipt.status = BridgeStatus::Failed {
+                                reason: format!("L1 withdrawal failed: {}; {}", e, refund),
+                            };
+                            release_volume();
+                            self.metrics.record_bridge_failure();
+                            return Ok(receipt);
+                        }
+                    }
+                }
                 self.metrics.record_bridge_success();
+                self.l1_index.record(&receipt);
             }
             Err(e) => {
                 error!("L2 submission failed: {}", e);
                 receipt.status = BridgeStatus::Failed {
                     reason: format!("L2 submission failed: {}", e),
                 };
+                release_volume();
                 self.metrics.record_bridge_failure();
+                self.l1_index.record(&receipt);
                 return Ok(receipt);
             }
         }
@@ -156,38 +538,75 @@ impl GhostBridge {
     pub async fn submit_batch(&self, transactions: Vec<Transaction>) -> Result<L2Batch> {
         info!("Submitting batch of {} transactions to L2", transactions.len());
 
+        self.maintenance.check()?;
+        self.degraded.require_quorum()?;
+
         // Validate all transactions
         for tx in &transactions {
             self.validator.validate(tx).await?;
         }
 
-        // Submit batch to GhostPlane via FFI
-        let ghostplane_ffi = self.ghostplane_ffi.read().await;
-        let batch_result = ghostplane_ffi.submit_batch(&transactions).await?;
+        // Hold the chain tip across submission so concurrent batches link in
+        // the order GhostPlane applied them
+        let batch_chain = self.state_manager.batch_chain();
+        let chain = batch_chain.lock().await;
+
+        // Submit batch to GhostPlane, remote over QUIC or local via FFI
+        let batch_result = match &self.remote_ghostplane {
+            Some(channel) => channel.submit_batch(&transactions).await?,
+            None => self.ghostplane_ffi.read().await.submit_batch(&transactions).await?,
+        };
+        // How full the batch was is the congestion later fees are priced at
+        *self.l2_utilization.lock() =
+            (batch_result.transaction_count as f64 / self.config.l2_config.max_batch_size as f64).min(1.0);
+
+        let link = chain.next_link();
+        let previous_state_root = link.previous_state_root.as_slice().try_into()
+            .map_err(|_| BridgeError::internal("Batch chain holds a state root that is not 32 bytes"))?;
 
         // Create L2 batch record
         let batch = L2Batch {
             batch_id: Uuid::new_v4(),
             transactions,
             state_root: batch_result.state_root,
-            previous_state_root: [0u8; 32], // TODO: Get previous state root
-            block_number: 12345, // TODO: Get actual block number
+            previous_state_root,
+            block_number: link.block_number,
             timestamp: chrono::Utc::now(),
         };
 
-        // Trigger settlement process
+        // Trigger settlement process; the tip only moves once it is accepted,
+        // so later batches never link to a root that won't reach L1
         self.settlement_engine.process_batch(&batch).await?;
+        chain.append(batch.state_root.to_vec());
 
         info!("Batch submitted successfully: {}", batch.batch_id);
         Ok(batch)
     }
 
-    /// Query cross-chain state
-    #[instrument(skip(self))]
+    /// Query cross-chain state; L1 reads target `head - buffer` to avoid reorged values
     pub async fn query_cross_chain_state(
         &self,
         network: &Network,
         key: &[u8],
+    ) -> Result<Vec<u8>> {
+        self.read_cross_chain_state(network, key, false).await
+    }
+
+    /// Query cross-chain state at the chain head, accepting that it may be reorged away
+    pub async fn query_cross_chain_state_at_head(
+        &self,
+        network: &Network,
+        key: &[u8],
+    ) -> Result<Vec<u8>> {
+        self.read_cross_chain_state(network, key, true).await
+    }
+
+    #[instrument(skip(self))]
+    async fn read_cross_chain_state(
+        &self,
+        network: &Network,
+        key: &[u8],
+        at_head: bool,
     ) -> Result<Vec<u8>> {
         debug!("Querying cross-chain state for network: {:?}", network);
 
@@ -196,20 +615,19 @@ impl GhostBridge {
                 let ghostplane_ffi = self.ghostplane_ffi.read().await;
                 ghostplane_ffi.query_state(key).await
             }
-            Network::GhostChain { .. } => {
-                // Query through GHOSTD service
-                // TODO: Implement GHOSTD state query
-                Ok(vec![])
+            Network::GhostChain { chain_id } => {
+                let ghostd = self.services.ghostd().await?;
+                let ghostd = ghostd.as_ref().ok_or_else(|| BridgeError::Service(ServiceError::ServiceUnavailable {
+                    service: "GHOSTD".to_string(),
+                }))?;
+                ghostd.query_state(key).await?.ok_or_else(|| {
+                    BridgeError::CrossChain(CrossChainError::StateKeyNotFound {
+                        chain_id: chain_id.0,
+                        key: format!("0x{}", hex::encode(key)),
+                    })
+                })
             }
-            _ => Err(BridgeError::CrossChain(CrossChainError::UnsupportedChain {
-                chain_id: match network {
-                    Network::Ethereum { chain_id } => chain_id.0,
-                    Network::Polygon { chain_id } => chain_id.0,
-                    Network::Arbitrum { chain_id } => chain_id.0,
-                    Network::Custom { chain_id, .. } => chain_id.0,
-                    _ => 0,
-                },
-            })),
+            _ => self.adapters.require(network)?.read_state(network, key, at_head).await,
         }
     }
 
@@ -219,23 +637,71 @@ impl GhostBridge {
         Ok(BridgeStatus::Pending)
     }
 
+    /// Networks, tokens, signature schemes, and features supported by this bridge
+    pub fn capabilities(&self) -> BridgeCapabilities {
+        let mut capabilities = BridgeCapabilities::from_config(&self.config);
+        capabilities.maintenance = self.maintenance.status();
+        capabilities
+    }
+
+    /// Collateral locked on L1 per token, backing the bridged L2 supply
+    pub fn locked_collateral(&self) -> HashMap<TokenType, U256> {
+        self.collateral.locked()
+    }
+
+    /// Bridge triggered by an L1 deposit transaction. A deposit whose L2 leg
+    /// is still being processed is returned with status `L1Confirmed`.
+    pub fn find_bridge_by_l1_tx(&self, l1_transaction_hash: &TransactionHash) -> Option<BridgeReceipt> {
+        self.l1_index.find(l1_transaction_hash)
+    }
+
+    /// Scheduled maintenance; windows added here are announced to clients
+    pub fn maintenance(&self) -> &MaintenanceSchedule {
+        &self.maintenance
+    }
+
+    /// Probe every subsystem and report which passed. Signing and proving
+    /// are dry runs on scratch instances built from this bridge's
+    /// configuration; the L2 and services are only read.
+    #[instrument(skip(self))]
+    pub async fn self_diagnostic(&self) -> Result<DiagnosticReport> {
+        let crypto = Arc::new(CryptoProvider::new(self.config.guardian_config.clone()).await?);
+        let proof_system = Arc::new(ZKProofSystem::new(SettlementConfig::default()).await?);
+
+        let mut diagnostic = SelfDiagnostic::new()
+            .with_check(diagnostics::CryptoCheck { provider: crypto })
+            .with_check(diagnostics::ZkProofCheck { proof_system })
+            .with_check(diagnostics::L2StateCheck { ghostplane: self.ghostplane_ffi.clone() });
+        for service_name in ServiceManager::SERVICE_NAMES {
+            diagnostic = diagnostic.with_check(diagnostics::ServiceCheck {
+                services: self.services.clone(),
+                service_name,
+            });
+        }
+
+        Ok(diagnostic.run().await)
+    }
+
     /// Health check for all bridge components
     pub async fn health_check(&self) -> Result<BridgeHealthStatus> {
         info!("Performing bridge health check");
 
         let mut status = BridgeHealthStatus::default();
 
-        // Check services
-        match self.services.health_check().await {
+        // Check services; if the check itself fails, count every service as down
+        let service_health = match self.services.health_check().await {
             Ok(service_status) => {
                 status.services_healthy = service_status.all_healthy;
                 status.healthy_services = service_status.healthy_services;
+                service_status.services
             }
             Err(e) => {
                 warn!("Service health check failed: {}", e);
                 status.services_healthy = false;
+                ServiceManager::SERVICE_NAMES.iter().map(|service| (service.to_string(), false)).collect()
             }
-        }
+        };
+        self.degraded.update(&service_health);
 
         // Check GhostPlane FFI
         let ghostplane_ffi = self.ghostplane_ffi.read().await;
@@ -243,38 +709,100 @@ impl GhostBridge {
 
         // Check settlement engine
         status.settlement_healthy = self.settlement_engine.is_healthy().await;
+        status.degraded_features = self.degraded.degraded_features();
 
-        status.overall_healthy = status.services_healthy && status.ffi_healthy && status.settlement_healthy;
+        let components = service_health.iter()
+            .map(|(service, healthy)| (service.as_str(), *healthy))
+            .chain([("ghostplane_ffi", status.ffi_healthy), ("settlement", status.settlement_healthy)]);
+        status.status = self.config.health.aggregate(components);
+        status.overall_healthy = status.status == HealthState::Healthy;
 
-        info!("Bridge health check completed: healthy = {}", status.overall_healthy);
+        info!("Bridge health check completed: {:?}", status.status);
         Ok(status)
     }
 
+    /// Re-run the health check on the configured interval until the bridge is
+    /// dropped, so degraded features and the service quorum track live health
+    pub fn spawn_health_monitor(bridge: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let interval = bridge.config.health_check_interval;
+        let bridge = Arc::downgrade(bridge);
+        tokio::spawn(async move {
+            loop {
+                let Some(bridge) = bridge.upgrade() else { break };
+                if let Err(e) = bridge.health_check().await {
+                    warn!("Background health check failed: {}", e);
+                }
+                drop(bridge);
+                tokio::time::sleep(interval).await;
+            }
+        })
+    }
+
     // Private helper methods
 
-    /// Check if transaction requires L1 processing
+    /// Check if transaction is a deposit from a chain served by an adapter
     fn requires_l1_processing(&self, transaction: &Transaction) -> bool {
-        matches!(
-            (&transaction.from_chain, &transaction.to_chain),
-            (Network::Ethereum { .. }, Network::GhostPlane { .. }) |
-            (Network::Polygon { .. }, Network::GhostPlane { .. }) |
-            (Network::Arbitrum { .. }, Network::GhostPlane { .. })
-        )
+        self.adapters.is_deposit(transaction)
+    }
+
+    /// Check if transaction withdraws from L2, releasing L1 collateral
+    fn releases_l1_collateral(&self, transaction: &Transaction) -> bool {
+        self.adapters.is_withdrawal(transaction)
+    }
+
+    /// Simulate the L1 leg of a transaction, if a simulator is configured
+    async fn simulate_l1_leg(&self, transaction: &Transaction) -> Result<Option<L1Simulation>> {
+        let (Some(simulator), Some(chain_id)) = (&self.l1_simulator, transaction.from_chain.chain_id()) else {
+            return Ok(None);
+        };
+
+        simulator.simulate(&chain_id, transaction).await.map(Some)
     }
 
     /// Process L1 side of transaction
     async fn process_l1_transaction(&self, transaction: &Transaction) -> Result<TransactionReceipt> {
         debug!("Processing L1 transaction");
-        // TODO: Implement actual L1 processing via external chain clients
-        Ok(TransactionReceipt {
-            transaction_hash: transaction.hash(),
-            block_number: 12345,
-            block_hash: [0u8; 32],
-            transaction_index: 0,
-            gas_used: 21000,
-            success: true,
-            logs: vec![],
-        })
+
+        // Predict the outcome before submitting anything
+        let simulation = self.simulate_l1_leg(transaction).await?;
+        if let Some(sim) = &simulation {
+            if !sim.will_succeed {
+                return Err(BridgeError::CrossChain(CrossChainError::SimulatedRevert {
+                    chain_id: sim.chain_id.0,
+                    reason: sim.revert_reason.clone().unwrap_or_default(),
+                }));
+            }
+        }
+
+        // Submit and wait for inclusion; the receipt carries the real block and gas used
+        self.adapters.require(&transaction.from_chain)?
+            .confirm_inclusion(transaction)
+            .await
+    }
+
+    /// Pay out a withdrawal on its destination chain
+    async fn submit_l1_withdrawal(&self, transaction: &Transaction) -> Result<TransactionReceipt> {
+        debug!("Submitting L1 withdrawal");
+        self.adapters.require(&transaction.to_chain)?.submit_withdrawal(transaction).await
+    }
+
+    /// Credit a withdrawal's L2 debit back to its sender after the payout failed
+    async fn refund_on_l2(&self, withdrawal: &Transaction) -> Result<TransactionReceipt> {
+        let refund = Transaction {
+            id: Uuid::new_v4(),
+            from_chain: withdrawal.to_chain.clone(),
+            to_chain: withdrawal.from_chain.clone(),
+            from_address: withdrawal.to_address.clone(),
+            to_address: withdrawal.from_address.clone(),
+            amount: withdrawal.amount.clone(),
+            fee: MultiTokenFee::zero(),
+            nonce: withdrawal.nonce,
+            data: withdrawal.id.as_bytes().to_vec(),
+            signature: None,
+            created_at: chrono::Utc::now(),
+        };
+        warn!("Refunding withdrawal {} on L2 as {}", withdrawal.id, refund.id);
+        self.submit_to_l2(&refund).await
     }
 
     /// Submit transaction to L2
@@ -286,13 +814,18 @@ impl GhostBridge {
 }
 
 /// Bridge health status
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, serde::Serialize)]
 pub struct BridgeHealthStatus {
+    /// True only when every component is healthy
     pub overall_healthy: bool,
+    /// Healthy, degraded by non-critical failures, or unhealthy
+    pub status: HealthState,
     pub services_healthy: bool,
     pub ffi_healthy: bool,
     pub settlement_healthy: bool,
     pub healthy_services: usize,
+    /// Features disabled while the services they need are down
+    pub degraded_features: Vec<BridgeFeature>,
 }
 
 /// Bridge metrics collection
@@ -300,6 +833,7 @@ pub struct BridgeMetrics {
     bridge_attempts: parking_lot::Mutex<u64>,
     bridge_successes: parking_lot::Mutex<u64>,
     bridge_failures: parking_lot::Mutex<u64>,
+    volume_cap_rejections: parking_lot::Mutex<u64>,
 }
 
 impl BridgeMetrics {
@@ -308,6 +842,7 @@ impl BridgeMetrics {
             bridge_attempts: parking_lot::Mutex::new(0),
             bridge_successes: parking_lot::Mutex::new(0),
             bridge_failures: parking_lot::Mutex::new(0),
+            volume_cap_rejections: parking_lot::Mutex::new(0),
         }
     }
 
@@ -323,11 +858,16 @@ impl BridgeMetrics {
         *self.bridge_failures.lock() += 1;
     }
 
+    pub fn record_volume_cap_rejection(&self) {
+        *self.volume_cap_rejections.lock() += 1;
+    }
+
     pub fn get_stats(&self) -> BridgeStats {
         BridgeStats {
             total_attempts: *self.bridge_attempts.lock(),
             successful_bridges: *self.bridge_successes.lock(),
             failed_bridges: *self.bridge_failures.lock(),
+            volume_cap_rejections: *self.volume_cap_rejections.lock(),
         }
     }
 }
@@ -338,6 +878,17 @@ pub struct BridgeStats {
     pub total_attempts: u64,
     pub successful_bridges: u64,
     pub failed_bridges: u64,
+    pub volume_cap_rejections: u64,
+}
+
+/// Outcome of bridging the deposits found in a range of blocks
+#[derive(Debug, Clone)]
+pub struct DepositSweep {
+    pub receipts: Vec<BridgeReceipt>,
+    /// Deposits that could not be bridged, with the reason
+    pub failures: Vec<(Uuid, String)>,
+    /// Block the next sweep should start from
+    pub next_block: u64,
 }
 
 #[cfg(test)]
@@ -357,6 +908,15 @@ mod tests {
         assert_eq!(stats.failed_bridges, 0);
     }
 
+    #[test]
+    fn test_volume_cap_rejection_metrics() {
+        let metrics = BridgeMetrics::new();
+
+        metrics.record_volume_cap_rejection();
+
+        assert_eq!(metrics.get_stats().volume_cap_rejections, 1);
+    }
+
     #[test]
     fn test_bridge_health_status() {
         let status = BridgeHealthStatus {
@@ -365,6 +925,7 @@ mod tests {
             ffi_healthy: true,
             settlement_healthy: true,
             healthy_services: 6,
+            ..BridgeHealthStatus::default()
         };
 
         assert!(status.overall_healthy);
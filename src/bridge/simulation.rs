@@ -0,0 +1,241 @@
+/*!
+L1 transaction simulation
+
+Simulates the L1 portion of a bridge transaction against a forked view of L1
+state (pinned to the latest block via the L1 RPC) to predict success and gas
+usage before anything is submitted.
+*/
+
+use crate::error::Result;
+use crate::types::{Address, ChainId, TokenType, Transaction, U256};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::debug;
+
+/// `Error(string)` selector used by Solidity `revert("...")`
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// `Panic(uint256)` selector used by Solidity assertion failures
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Read-only L1 RPC access used for simulation
+#[async_trait]
+pub trait L1Rpc: Send + Sync {
+    /// Latest block number on the chain
+    async fn block_number(&self, chain_id: &ChainId) -> Result<u64>;
+
+    /// Execute a call against state at `block_number` without submitting it
+    async fn simulate_call(&self, chain_id: &ChainId, call: &L1Call, block_number: u64) -> Result<L1CallOutcome>;
+
+    /// Read raw state for `key` as of `block_number`
+    async fn read_state(&self, chain_id: &ChainId, key: &[u8], block_number: u64) -> Result<Vec<u8>>;
+}
+
+/// Call executed against forked L1 state
+#[derive(Debug, Clone)]
+pub struct L1Call {
+    pub from: Address,
+    pub to: Address,
+    pub token: TokenType,
+    pub value: U256,
+    pub data: Vec<u8>,
+    pub gas_limit: u64,
+}
+
+/// Raw outcome of a simulated call
+#[derive(Debug, Clone)]
+pub enum L1CallOutcome {
+    Success { gas_used: u64 },
+    Reverted { gas_used: u64, revert_data: Vec<u8> },
+}
+
+/// Predicted outcome of the L1 side of a bridge transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct L1Simulation {
+    pub chain_id: ChainId,
+    /// Block the simulation was forked from
+    pub fork_block: u64,
+    pub will_succeed: bool,
+    pub estimated_gas: u64,
+    pub revert_reason: Option<String>,
+}
+
+/// Prediction returned by `GhostBridge::simulate_bridge`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BridgeSimulation {
+    /// L1 prediction, if the transaction has an L1 leg and simulation is configured
+    pub l1: Option<L1Simulation>,
+    pub will_succeed: bool,
+}
+
+/// Simulates L1 transactions via an `L1Rpc`
+pub struct L1Simulator {
+    rpc: Arc<dyn L1Rpc>,
+    gas_limit: u64,
+}
+
+impl L1Simulator {
+    pub fn new(rpc: Arc<dyn L1Rpc>, gas_limit: u64) -> Self {
+        Self { rpc, gas_limit }
+    }
+
+    /// Simulate a transaction's L1 leg on `chain_id`
+    pub async fn simulate(&self, chain_id: &ChainId, transaction: &Transaction) -> Result<L1Simulation> {
+        let fork_block = self.rpc.block_number(chain_id).await?;
+        let call = L1Call {
+            from: transaction.from_address.clone(),
+            to: transaction.to_address.clone(),
+            token: transaction.amount.token_type,
+            value: transaction.amount.amount.clone(),
+            data: transaction.data.clone(),
+            gas_limit: self.gas_limit,
+        };
+
+        let simulation = match self.rpc.simulate_call(chain_id, &call, fork_block).await? {
+            L1CallOutcome::Success { gas_used } => L1Simulation {
+                chain_id: chain_id.clone(),
+                fork_block,
+                will_succeed: true,
+                estimated_gas: gas_used,
+                revert_reason: None,
+            },
+            L1CallOutcome::Reverted { gas_used, revert_data } => L1Simulation {
+                chain_id: chain_id.clone(),
+                fork_block,
+                will_succeed: false,
+                estimated_gas: gas_used,
+                revert_reason: Some(decode_revert_reason(&revert_data)),
+            },
+        };
+
+        debug!("Simulated {} on chain {} at block {}: success = {}",
+               transaction.id, chain_id, fork_block, simulation.will_succeed);
+        Ok(simulation)
+    }
+}
+
+/// Decode an L1 revert payload into a human-readable reason
+pub fn decode_revert_reason(data: &[u8]) -> String {
+    if data.is_empty() {
+        return "execution reverted".to_string();
+    }
+
+    if data.len() >= 4 + 64 && data[..4] == ERROR_SELECTOR {
+        // Error(string): offset word, length word, then UTF-8 bytes
+        let payload = &data[4..];
+        let length = word_to_usize(&payload[32..64]);
+        if let Some(reason) = length
+            .and_then(|len| payload.get(64..64usize.checked_add(len)?))
+            .and_then(|bytes| std::str::from_utf8(bytes).ok())
+        {
+            return reason.to_string();
+        }
+    }
+
+    if data.len() >= 4 + 32 && data[..4] == PANIC_SELECTOR {
+        let code = word_to_usize(&data[4..36]).unwrap_or(usize::MAX);
+        return format!("panic: 0x{:02x}", code);
+    }
+
+    format!("execution reverted: 0x{}", hex::encode(data))
+}
+
+fn word_to_usize(word: &[u8]) -> Option<usize> {
+    if word[..24].iter().any(|&b| b != 0) {
+        return None;
+    }
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&word[24..32]);
+    usize::try_from(u64::from_be_bytes(bytes)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{fixtures, Network};
+    use std::collections::HashMap;
+
+    /// Stub RPC with fixed L1 balances; transfers above balance revert
+    struct StubL1Rpc {
+        balances: HashMap<Address, U256>,
+    }
+
+    #[async_trait]
+    impl L1Rpc for StubL1Rpc {
+        async fn block_number(&self, _chain_id: &ChainId) -> Result<u64> {
+            Ok(19_000_000)
+        }
+
+        async fn simulate_call(&self, _chain_id: &ChainId, call: &L1Call, _block_number: u64) -> Result<L1CallOutcome> {
+            let balance = self.balances.get(&call.from).cloned().unwrap_or(U256::ZERO);
+            if call.value.0 > balance.0 {
+                Ok(L1CallOutcome::Reverted {
+                    gas_used: 24_000,
+                    revert_data: encode_error("ERC20: transfer amount exceeds balance"),
+                })
+            } else {
+                Ok(L1CallOutcome::Success { gas_used: 51_000 })
+            }
+        }
+
+        async fn read_state(&self, _chain_id: &ChainId, _key: &[u8], _block_number: u64) -> Result<Vec<u8>> {
+            Ok(vec![])
+        }
+    }
+
+    fn encode_error(reason: &str) -> Vec<u8> {
+        let mut data = ERROR_SELECTOR.to_vec();
+        data.extend_from_slice(&U256::from(32).0);
+        data.extend_from_slice(&U256::from(reason.len() as u64).0);
+        data.extend_from_slice(reason.as_bytes());
+        let padded = 4 + (data.len() - 4).div_ceil(32) * 32;
+        data.resize(padded, 0);
+        data
+    }
+
+    fn transfer(from: Address, amount: u64) -> Transaction {
+        Transaction {
+            from_chain: Network::Ethereum { chain_id: ChainId::ETHEREUM },
+            from_address: from,
+            ..fixtures::transfer(0, 2, amount)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_simulation_predicts_success_and_revert() {
+        let sender = Address([1u8; 20]);
+        let rpc = StubL1Rpc {
+            balances: HashMap::from([(sender.clone(), U256::from(1_000))]),
+        };
+        let simulator = L1Simulator::new(Arc::new(rpc), 100_000);
+
+        let ok = simulator.simulate(&ChainId::ETHEREUM, &transfer(sender.clone(), 500)).await.unwrap();
+        assert!(ok.will_succeed);
+        assert_eq!(ok.estimated_gas, 51_000);
+        assert_eq!(ok.fork_block, 19_000_000);
+
+        let reverted = simulator.simulate(&ChainId::ETHEREUM, &transfer(sender, 5_000)).await.unwrap();
+        assert!(!reverted.will_succeed);
+        assert_eq!(
+            reverted.revert_reason.as_deref(),
+            Some("ERC20: transfer amount exceeds balance")
+        );
+    }
+
+    #[test]
+    fn test_decode_revert_reason_fallbacks() {
+        assert_eq!(decode_revert_reason(&[]), "execution reverted");
+
+        let mut panic = PANIC_SELECTOR.to_vec();
+        panic.extend_from_slice(&U256::from(0x11).0);
+        assert_eq!(decode_revert_reason(&panic), "panic: 0x11");
+
+        assert_eq!(decode_revert_reason(&[0xde, 0xad]), "execution reverted: 0xdead");
+
+        // A length word that would overflow the slice bound falls back to hex
+        let mut hostile = ERROR_SELECTOR.to_vec();
+        hostile.extend_from_slice(&U256::from(32).0);
+        hostile.extend_from_slice(&U256::from(u64::MAX).0);
+        assert!(decode_revert_reason(&hostile).starts_with("execution reverted: 0x"));
+    }
+}
@@ -0,0 +1,105 @@
+/*!
+Reorg-safe cross-chain state reads
+
+Reading L1 state at the chain head risks returning a value that is reorged
+away moments later. Reads therefore target `head - buffer` blocks, where the
+buffer is configured per chain; callers that accept the reorg risk can read
+at the head explicitly.
+*/
+
+use crate::bridge::config::BridgeConfig;
+use crate::bridge::simulation::L1Rpc;
+use crate::error::{BridgeError, CrossChainError, Result};
+use crate::types::ChainId;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::debug;
+
+/// State read from a chain, with the block it was read at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateRead {
+    pub block_number: u64,
+    pub value: Vec<u8>,
+}
+
+/// Reads L1 state a configured number of blocks behind the head
+pub struct CrossChainStateReader {
+    rpc: Arc<dyn L1Rpc>,
+    /// chain -> blocks behind head that buffered reads target
+    buffers: HashMap<ChainId, u64>,
+}
+
+impl CrossChainStateReader {
+    pub fn new(rpc: Arc<dyn L1Rpc>, buffers: HashMap<ChainId, u64>) -> Self {
+        Self { rpc, buffers }
+    }
+
+    /// Use each configured network's read buffer
+    pub fn from_config(rpc: Arc<dyn L1Rpc>, config: &BridgeConfig) -> Self {
+        let buffers = config.networks.iter()
+            .map(|(chain_id, network)| (chain_id.clone(), network.read_buffer_blocks()))
+            .collect();
+        Self::new(rpc, buffers)
+    }
+
+    /// Block a read targets: the head, or the head minus the chain's buffer
+    pub async fn target_block(&self, chain_id: &ChainId, at_head: bool) -> Result<u64> {
+        let buffer = *self.buffers.get(chain_id).ok_or(BridgeError::CrossChain(
+            CrossChainError::UnsupportedChain { chain_id: chain_id.0 },
+        ))?;
+        let head = self.rpc.block_number(chain_id).await?;
+        Ok(if at_head { head } else { head.saturating_sub(buffer) })
+    }
+
+    /// Read `key` on `chain_id`, `buffer` blocks behind the head unless `at_head`
+    pub async fn read(&self, chain_id: &ChainId, key: &[u8], at_head: bool) -> Result<StateRead> {
+        let block_number = self.target_block(chain_id, at_head).await?;
+        debug!("Reading state on chain {} at block {} (at_head: {})", chain_id.0, block_number, at_head);
+        let value = self.rpc.read_state(chain_id, key, block_number).await?;
+        Ok(StateRead { block_number, value })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bridge::simulation::{L1Call, L1CallOutcome};
+    use async_trait::async_trait;
+
+    const HEAD: u64 = 19_000_000;
+
+    /// Stub RPC whose state value is the block number it was read at
+    struct StubL1Rpc;
+
+    #[async_trait]
+    impl L1Rpc for StubL1Rpc {
+        async fn block_number(&self, _chain_id: &ChainId) -> Result<u64> {
+            Ok(HEAD)
+        }
+
+        async fn simulate_call(&self, _chain_id: &ChainId, _call: &L1Call, _block_number: u64) -> Result<L1CallOutcome> {
+            Ok(L1CallOutcome::Success { gas_used: 21_000 })
+        }
+
+        async fn read_state(&self, _chain_id: &ChainId, _key: &[u8], block_number: u64) -> Result<Vec<u8>> {
+            Ok(block_number.to_be_bytes().to_vec())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reads_target_buffered_block_unless_at_head() {
+        let reader = CrossChainStateReader::from_config(Arc::new(StubL1Rpc), &BridgeConfig::default());
+        let ethereum = ChainId::ETHEREUM;
+        let buffer = BridgeConfig::default().networks[&ethereum].read_buffer_blocks();
+        assert!(buffer > 0);
+
+        let buffered = reader.read(&ethereum, b"balance", false).await.unwrap();
+        assert_eq!(buffered.block_number, HEAD - buffer);
+        assert_eq!(buffered.value, (HEAD - buffer).to_be_bytes());
+
+        let head = reader.read(&ethereum, b"balance", true).await.unwrap();
+        assert_eq!(head.block_number, HEAD);
+
+        assert!(reader.read(&ChainId(999_999), b"balance", false).await.is_err());
+    }
+}
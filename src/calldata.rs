@@ -0,0 +1,349 @@
+/*!
+Calldata decoding for known function selectors
+
+Contract-call transactions carry ABI-encoded `data`. A `CalldataDecoder`
+registered with function signatures turns that data into the function name
+and decoded arguments for traces and audit logs. Data with an unknown
+selector, or that doesn't match its registered signature, is left raw.
+*/
+
+use crate::error::{BridgeError, Result, SerializationError};
+use crate::types::{Address, U256};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::collections::HashMap;
+use std::fmt;
+
+const WORD: usize = 32;
+
+/// Supported ABI parameter types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AbiType {
+    Address,
+    /// Any `uintN`, decoded as a full word
+    Uint(u16),
+    Bool,
+    /// `bytes1` to `bytes32`
+    FixedBytes(u8),
+    Bytes,
+    String,
+}
+
+impl AbiType {
+    fn parse(name: &str) -> Result<Self> {
+        let parsed = match name {
+            "address" => Some(AbiType::Address),
+            "bool" => Some(AbiType::Bool),
+            "bytes" => Some(AbiType::Bytes),
+            "string" => Some(AbiType::String),
+            "uint" => Some(AbiType::Uint(256)),
+            _ => {
+                if let Some(bits) = name.strip_prefix("uint") {
+                    bits.parse().ok().filter(|b| b % 8 == 0 && (8..=256).contains(b)).map(AbiType::Uint)
+                } else if let Some(size) = name.strip_prefix("bytes") {
+                    size.parse().ok().filter(|s| (1..=32).contains(s)).map(AbiType::FixedBytes)
+                } else {
+                    None
+                }
+            }
+        };
+        parsed.ok_or_else(|| invalid(format!("unsupported ABI type '{}'", name)))
+    }
+
+    fn canonical_name(&self) -> String {
+        match self {
+            AbiType::Address => "address".to_string(),
+            AbiType::Uint(bits) => format!("uint{}", bits),
+            AbiType::Bool => "bool".to_string(),
+            AbiType::FixedBytes(size) => format!("bytes{}", size),
+            AbiType::Bytes => "bytes".to_string(),
+            AbiType::String => "string".to_string(),
+        }
+    }
+}
+
+/// A function that can be decoded, e.g. `transfer(address,uint256)`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionAbi {
+    pub name: String,
+    pub inputs: Vec<AbiType>,
+}
+
+impl FunctionAbi {
+    /// Parse a signature such as `transfer(address,uint256)`
+    pub fn parse(signature: &str) -> Result<Self> {
+        let signature = signature.trim();
+        let (name, rest) = signature.split_once('(')
+            .ok_or_else(|| invalid(format!("malformed function signature '{}'", signature)))?;
+        let params = rest.strip_suffix(')')
+            .ok_or_else(|| invalid(format!("malformed function signature '{}'", signature)))?;
+        if name.is_empty() {
+            return Err(invalid(format!("malformed function signature '{}'", signature)));
+        }
+
+        let inputs = params.split(',')
+            .map(str::trim)
+            .filter(|param| !param.is_empty())
+            // Allow named parameters, e.g. `transfer(address to, uint256 amount)`
+            .map(|param| AbiType::parse(param.split_whitespace().next().unwrap_or(param)))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { name: name.to_string(), inputs })
+    }
+
+    /// Canonical signature used for the selector
+    pub fn signature(&self) -> String {
+        let inputs: Vec<_> = self.inputs.iter().map(AbiType::canonical_name).collect();
+        format!("{}({})", self.name, inputs.join(","))
+    }
+
+    /// First four bytes of the keccak256 hash of the signature
+    pub fn selector(&self) -> [u8; 4] {
+        let hash = Keccak256::digest(self.signature().as_bytes());
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
+}
+
+/// A decoded argument
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AbiValue {
+    Address(Address),
+    Uint(U256),
+    Bool(bool),
+    FixedBytes(Vec<u8>),
+    Bytes(Vec<u8>),
+    String(String),
+}
+
+impl fmt::Display for AbiValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AbiValue::Address(address) => write!(f, "0x{}", hex::encode(address.0)),
+            // Values that fit in a u64 read better in decimal
+            AbiValue::Uint(value) if value.0[..24].iter().all(|b| *b == 0) => write!(f, "{}", value.to_u64()),
+            AbiValue::Uint(value) => write!(f, "0x{}", hex::encode(value.0)),
+            AbiValue::Bool(value) => write!(f, "{}", value),
+            AbiValue::FixedBytes(bytes) | AbiValue::Bytes(bytes) => write!(f, "0x{}", hex::encode(bytes)),
+            AbiValue::String(value) => write!(f, "{:?}", value),
+        }
+    }
+}
+
+/// Calldata decoded against a registered function
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedCall {
+    pub function: String,
+    pub signature: String,
+    pub arguments: Vec<AbiValue>,
+}
+
+impl fmt::Display for DecodedCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let arguments: Vec<_> = self.arguments.iter().map(ToString::to_string).collect();
+        write!(f, "{}({})", self.function, arguments.join(", "))
+    }
+}
+
+/// How a transaction's data is shown in traces and audit logs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CalldataAnnotation {
+    Decoded(DecodedCall),
+    Raw { data: String },
+}
+
+impl fmt::Display for CalldataAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalldataAnnotation::Decoded(call) => write!(f, "{}", call),
+            CalldataAnnotation::Raw { data } => write!(f, "{}", data),
+        }
+    }
+}
+
+/// Decodes calldata for registered function selectors
+#[derive(Debug, Clone, Default)]
+pub struct CalldataDecoder {
+    functions: HashMap<[u8; 4], FunctionAbi>,
+}
+
+impl CalldataDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decoder preloaded with the ERC-20 transfer and approval functions
+    pub fn erc20() -> Self {
+        Self::new()
+            .with_function("transfer(address,uint256)")
+            .and_then(|d| d.with_function("transferFrom(address,address,uint256)"))
+            .and_then(|d| d.with_function("approve(address,uint256)"))
+            .expect("ERC-20 signatures are valid")
+    }
+
+    /// Register a function by signature
+    pub fn with_function(mut self, signature: &str) -> Result<Self> {
+        let function = FunctionAbi::parse(signature)?;
+        self.functions.insert(function.selector(), function);
+        Ok(self)
+    }
+
+    /// Registered function for the selector at the start of `data`
+    pub fn function_for(&self, data: &[u8]) -> Option<&FunctionAbi> {
+        let selector: [u8; 4] = data.get(..4)?.try_into().ok()?;
+        self.functions.get(&selector)
+    }
+
+    /// Decode `data`; `None` if its selector isn't registered
+    pub fn decode(&self, data: &[u8]) -> Option<Result<DecodedCall>> {
+        let function = self.function_for(data)?;
+        Some(decode_arguments(&function.inputs, &data[4..]).map(|arguments| DecodedCall {
+            function: function.name.clone(),
+            signature: function.signature(),
+            arguments,
+        }))
+    }
+
+    /// Decoded call, or the raw hex for unknown or malformed data
+    pub fn annotate(&self, data: &[u8]) -> CalldataAnnotation {
+        match self.decode(data) {
+            Some(Ok(call)) => CalldataAnnotation::Decoded(call),
+            _ => CalldataAnnotation::Raw { data: format!("0x{}", hex::encode(data)) },
+        }
+    }
+}
+
+/// Contract methods that may be called, by selector
+#[derive(Debug, Clone, Default)]
+pub struct MethodAllowlist {
+    methods: HashMap<[u8; 4], String>,
+}
+
+impl MethodAllowlist {
+    /// Allow the functions with the given signatures, e.g. `transfer(address,uint256)`
+    pub fn from_signatures<S: AsRef<str>>(signatures: &[S]) -> Result<Self> {
+        let mut methods = HashMap::new();
+        for signature in signatures {
+            let function = FunctionAbi::parse(signature.as_ref())?;
+            methods.insert(function.selector(), function.signature());
+        }
+        Ok(Self { methods })
+    }
+
+    /// Check the selector at the start of contract-call `data`
+    pub fn check(&self, data: &[u8]) -> std::result::Result<(), String> {
+        let Some(selector) = data.get(..4) else {
+            return Err(format!("calldata 0x{} is too short for a method selector", hex::encode(data)));
+        };
+        let selector: [u8; 4] = selector.try_into().expect("four bytes");
+        if self.methods.contains_key(&selector) {
+            Ok(())
+        } else {
+            Err(format!("contract method 0x{} is not allow-listed", hex::encode(selector)))
+        }
+    }
+}
+
+fn decode_arguments(inputs: &[AbiType], args: &[u8]) -> Result<Vec<AbiValue>> {
+    inputs.iter().enumerate().map(|(index, abi_type)| {
+        let head = word(args, index * WORD)?;
+        match abi_type {
+            AbiType::Address => {
+                if head[..12].iter().any(|b| *b != 0) {
+                    return Err(invalid(format!("argument {} is not a valid address", index)));
+                }
+                let mut address = [0u8; 20];
+                address.copy_from_slice(&head[12..]);
+                Ok(AbiValue::Address(Address(address)))
+            }
+            AbiType::Uint(_) => Ok(AbiValue::Uint(U256(*head))),
+            AbiType::Bool => match head[31] {
+                0 | 1 if head[..31].iter().all(|b| *b == 0) => Ok(AbiValue::Bool(head[31] == 1)),
+                _ => Err(invalid(format!("argument {} is not a valid bool", index))),
+            },
+            AbiType::FixedBytes(size) => Ok(AbiValue::FixedBytes(head[..*size as usize].to_vec())),
+            AbiType::Bytes => Ok(AbiValue::Bytes(dynamic(args, head)?.to_vec())),
+            AbiType::String => String::from_utf8(dynamic(args, head)?.to_vec())
+                .map(AbiValue::String)
+                .map_err(|_| invalid(format!("argument {} is not valid UTF-8", index))),
+        }
+    }).collect()
+}
+
+fn word(args: &[u8], offset: usize) -> Result<&[u8; WORD]> {
+    offset.checked_add(WORD)
+        .and_then(|end| args.get(offset..end))
+        .and_then(|slice| slice.try_into().ok())
+        .ok_or_else(|| invalid(format!("calldata truncated at byte {}", offset)))
+}
+
+/// Word value as an offset or length, rejecting values that can't index the calldata
+fn word_usize(word: &[u8; WORD]) -> Result<usize> {
+    if word[..24].iter().any(|b| *b != 0) {
+        return Err(invalid("calldata offset out of range".to_string()));
+    }
+    usize::try_from(U256(*word).to_u64()).map_err(|_| invalid("calldata offset out of range".to_string()))
+}
+
+fn dynamic<'a>(args: &'a [u8], head: &[u8; WORD]) -> Result<&'a [u8]> {
+    let offset = word_usize(head)?;
+    let length = word_usize(word(args, offset)?)?;
+    let start = offset + WORD;
+    start.checked_add(length)
+        .and_then(|end| args.get(start..end))
+        .ok_or_else(|| invalid("dynamic argument runs past end of calldata".to_string()))
+}
+
+fn invalid(message: String) -> BridgeError {
+    BridgeError::Serialization(SerializationError::InvalidFormat(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn erc20_transfer(to: [u8; 20], amount: u64) -> Vec<u8> {
+        let mut data = vec![0xa9, 0x05, 0x9c, 0xbb];
+        data.extend_from_slice(&[0u8; 12]);
+        data.extend_from_slice(&to);
+        data.extend_from_slice(&U256::from(amount).0);
+        data
+    }
+
+    #[test]
+    fn test_decode_erc20_transfer() {
+        let decoder = CalldataDecoder::erc20();
+        assert_eq!(FunctionAbi::parse("transfer(address to, uint256 amount)").unwrap().selector(), [0xa9, 0x05, 0x9c, 0xbb]);
+
+        let data = erc20_transfer([0x11; 20], 1_000_000);
+        let call = decoder.decode(&data).unwrap().unwrap();
+        assert_eq!(call, DecodedCall {
+            function: "transfer".to_string(),
+            signature: "transfer(address,uint256)".to_string(),
+            arguments: vec![AbiValue::Address(Address([0x11; 20])), AbiValue::Uint(U256::from(1_000_000))],
+        });
+        assert_eq!(call.to_string(), format!("transfer(0x{}, 1000000)", "11".repeat(20)));
+
+        // Unknown selectors and truncated calldata stay raw
+        assert!(decoder.decode(&[0xde, 0xad, 0xbe, 0xef]).is_none());
+        assert_eq!(decoder.annotate(&data[..40]), CalldataAnnotation::Raw { data: format!("0x{}", hex::encode(&data[..40])) });
+    }
+
+    #[test]
+    fn test_decode_dynamic_arguments() {
+        let decoder = CalldataDecoder::new().with_function("setName(string,bool)").unwrap();
+        let function = FunctionAbi::parse("setName(string,bool)").unwrap();
+
+        let mut data = function.selector().to_vec();
+        data.extend_from_slice(&U256::from(64).0); // offset of the string
+        data.extend_from_slice(&U256::from(1).0);
+        data.extend_from_slice(&U256::from(5).0);
+        let mut name = [0u8; 32];
+        name[..5].copy_from_slice(b"ghost");
+        data.extend_from_slice(&name);
+
+        let call = decoder.decode(&data).unwrap().unwrap();
+        assert_eq!(call.arguments, vec![AbiValue::String("ghost".to_string()), AbiValue::Bool(true)]);
+        assert!(FunctionAbi::parse("broken(uint7)").is_err());
+    }
+}
@@ -0,0 +1,432 @@
+/*!
+Canonical transaction serialization
+
+A versioned, language-neutral byte encoding of `Transaction` shared by the
+Rust bridge and the Zig GhostPlane side of the FFI boundary. Unlike the
+bincode form, the layout is fixed by this module rather than by serde, so
+both implementations produce identical bytes and hashes for the same
+transaction. The layout is described in `docs/canonical-transaction.md` and
+pinned by the conformance vectors in `docs/vectors/`.
+
+Version 1 layout, all integers big-endian:
+
+| Field          | Encoding                                                   |
+|----------------|------------------------------------------------------------|
+| version        | u8                                                         |
+| id             | 16 bytes (UUID)                                            |
+| from_chain     | network                                                    |
+| to_chain       | network                                                    |
+| from_address   | 20 bytes                                                   |
+| to_address     | 20 bytes                                                   |
+| amount         | token amount                                               |
+| fee            | token amount x4: gcc, spirit, mana, ghost                  |
+| nonce          | u64                                                        |
+| data           | u32 length + bytes                                         |
+| signature      | u8 flag (0 absent, 1 present), then r 32, s 32, v u8, scheme u8 |
+| created_at     | i64 unix seconds + u32 nanoseconds                         |
+
+A network is a u8 tag (Ethereum 0, Bitcoin 1, GhostChain 2, GhostPlane 3,
+Polygon 4, Arbitrum 5, Custom 6) followed by the u64 chain id, or for
+Bitcoin a u8 network (Mainnet 0, Testnet 1, Signet 2, Regtest 3). Custom
+networks add the name and RPC URL as u32 length + UTF-8. A token amount is
+a u8 token tag (GCC 0, SPIRIT 1, MANA 2, GHOST 3), u8 decimals, and the
+32-byte amount. Signature schemes are Ed25519 0, Secp256k1 1, BLS12381 2,
+Dilithium 3.
+*/
+
+use crate::error::{BridgeError, Result, SerializationError};
+use crate::security::SignatureScheme;
+use crate::types::{
+    Address, BitcoinNetwork, ChainId, MultiTokenFee, Network, Signature, TokenAmount, TokenType,
+    Transaction, U256,
+};
+use chrono::{TimeZone, Utc};
+use uuid::Uuid;
+
+/// Version written by `encode`
+pub const CANONICAL_VERSION: u8 = 1;
+
+/// Encode a transaction in the current canonical version
+pub fn encode(transaction: &Transaction) -> Vec<u8> {
+    let mut writer = Writer::default();
+    writer.transaction_v1(transaction);
+    writer.0
+}
+
+/// Encode a transaction in a specific canonical version, for peers that have
+/// not moved to the current one yet
+pub fn encode_version(transaction: &Transaction, version: u8) -> Result<Vec<u8>> {
+    match version {
+        1 => Ok(encode(transaction)),
+        other => Err(unsupported(other)),
+    }
+}
+
+/// Decode a canonically encoded transaction of any supported version
+pub fn decode(bytes: &[u8]) -> Result<Transaction> {
+    let mut reader = Reader { bytes, position: 0 };
+    let transaction = match reader.u8()? {
+        1 => reader.transaction_v1()?,
+        other => return Err(unsupported(other)),
+    };
+    if reader.position != bytes.len() {
+        return Err(invalid(format!("{} trailing bytes", bytes.len() - reader.position)));
+    }
+    Ok(transaction)
+}
+
+fn unsupported(version: u8) -> BridgeError {
+    BridgeError::Serialization(SerializationError::UnsupportedSchemaVersion {
+        version: version as u32,
+        supported: CANONICAL_VERSION as u32,
+    })
+}
+
+fn invalid(reason: impl Into<String>) -> BridgeError {
+    BridgeError::Serialization(SerializationError::InvalidFormat(reason.into()))
+}
+
+#[derive(Default)]
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn transaction_v1(&mut self, tx: &Transaction) {
+        self.0.push(1);
+        self.0.extend_from_slice(tx.id.as_bytes());
+        self.network(&tx.from_chain);
+        self.network(&tx.to_chain);
+        self.0.extend_from_slice(&tx.from_address.0);
+        self.0.extend_from_slice(&tx.to_address.0);
+        self.token_amount(&tx.amount);
+        self.token_amount(&tx.fee.gcc_fee);
+        self.token_amount(&tx.fee.spirit_fee);
+        self.token_amount(&tx.fee.mana_fee);
+        self.token_amount(&tx.fee.ghost_fee);
+        self.0.extend_from_slice(&tx.nonce.to_be_bytes());
+        self.bytes(&tx.data);
+        match &tx.signature {
+            None => self.0.push(0),
+            Some(signature) => {
+                self.0.push(1);
+                self.0.extend_from_slice(&signature.r.0);
+                self.0.extend_from_slice(&signature.s.0);
+                self.0.push(signature.v);
+                self.0.push(scheme_tag(&signature.scheme));
+            }
+        }
+        self.0.extend_from_slice(&tx.created_at.timestamp().to_be_bytes());
+        self.0.extend_from_slice(&tx.created_at.timestamp_subsec_nanos().to_be_bytes());
+    }
+
+    fn network(&mut self, network: &Network) {
+        let (tag, chain_id) = match network {
+            Network::Ethereum { chain_id } => (0, chain_id),
+            Network::Bitcoin { network } => {
+                self.0.extend_from_slice(&[1, bitcoin_tag(network)]);
+                return;
+            }
+            Network::GhostChain { chain_id } => (2, chain_id),
+            Network::GhostPlane { chain_id } => (3, chain_id),
+            Network::Polygon { chain_id } => (4, chain_id),
+            Network::Arbitrum { chain_id } => (5, chain_id),
+            Network::Custom { chain_id, .. } => (6, chain_id),
+        };
+        self.0.push(tag);
+        self.0.extend_from_slice(&chain_id.0.to_be_bytes());
+        if let Network::Custom { name, rpc_url, .. } = network {
+            self.bytes(name.as_bytes());
+            self.bytes(rpc_url.as_bytes());
+        }
+    }
+
+    fn token_amount(&mut self, amount: &TokenAmount) {
+        self.0.push(token_tag(amount.token_type));
+        self.0.push(amount.decimals);
+        self.0.extend_from_slice(&amount.amount.0);
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+        self.0.extend_from_slice(bytes);
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn transaction_v1(&mut self) -> Result<Transaction> {
+        let id = Uuid::from_bytes(self.array()?);
+        let from_chain = self.network()?;
+        let to_chain = self.network()?;
+        let from_address = Address(self.array()?);
+        let to_address = Address(self.array()?);
+        let amount = self.token_amount()?;
+        let fee = MultiTokenFee {
+            gcc_fee: self.token_amount()?,
+            spirit_fee: self.token_amount()?,
+            mana_fee: self.token_amount()?,
+            ghost_fee: self.token_amount()?,
+        };
+        let nonce = u64::from_be_bytes(self.array()?);
+        let data = self.bytes()?.to_vec();
+        let signature = match self.u8()? {
+            0 => None,
+            1 => Some(Signature {
+                r: U256(self.array()?),
+                s: U256(self.array()?),
+                v: self.u8()?,
+                scheme: scheme_from_tag(self.u8()?)?,
+            }),
+            other => return Err(invalid(format!("invalid signature flag {}", other))),
+        };
+        let seconds = i64::from_be_bytes(self.array()?);
+        let nanos = u32::from_be_bytes(self.array()?);
+        let created_at = Utc.timestamp_opt(seconds, nanos).single()
+            .ok_or_else(|| invalid(format!("invalid timestamp {}.{:09}", seconds, nanos)))?;
+
+        Ok(Transaction {
+            id,
+            from_chain,
+            to_chain,
+            from_address,
+            to_address,
+            amount,
+            fee,
+            nonce,
+            data,
+            signature,
+            created_at,
+        })
+    }
+
+    fn network(&mut self) -> Result<Network> {
+        let tag = self.u8()?;
+        if tag == 1 {
+            return Ok(Network::Bitcoin { network: bitcoin_from_tag(self.u8()?)? });
+        }
+        let chain_id = ChainId(u64::from_be_bytes(self.array()?));
+        Ok(match tag {
+            0 => Network::Ethereum { chain_id },
+            2 => Network::GhostChain { chain_id },
+            3 => Network::GhostPlane { chain_id },
+            4 => Network::Polygon { chain_id },
+            5 => Network::Arbitrum { chain_id },
+            6 => Network::Custom { chain_id, name: self.string()?, rpc_url: self.string()? },
+            other => return Err(invalid(format!("unknown network tag {}", other))),
+        })
+    }
+
+    fn token_amount(&mut self) -> Result<TokenAmount> {
+        let token_type = match self.u8()? {
+            0 => TokenType::Gcc,
+            1 => TokenType::Spirit,
+            2 => TokenType::Mana,
+            3 => TokenType::Ghost,
+            other => return Err(invalid(format!("unknown token tag {}", other))),
+        };
+        let decimals = self.u8()?;
+        Ok(TokenAmount { token_type, amount: U256(self.array()?), decimals })
+    }
+
+    fn string(&mut self) -> Result<String> {
+        String::from_utf8(self.bytes()?.to_vec()).map_err(|e| invalid(e.to_string()))
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = u32::from_be_bytes(self.array()?) as usize;
+        self.take(len)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.position.checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| invalid(format!("truncated at byte {}", self.position)))?;
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+}
+
+fn bitcoin_tag(network: &BitcoinNetwork) -> u8 {
+    match network {
+        BitcoinNetwork::Mainnet => 0,
+        BitcoinNetwork::Testnet => 1,
+        BitcoinNetwork::Signet => 2,
+        BitcoinNetwork::Regtest => 3,
+    }
+}
+
+fn bitcoin_from_tag(tag: u8) -> Result<BitcoinNetwork> {
+    Ok(match tag {
+        0 => BitcoinNetwork::Mainnet,
+        1 => BitcoinNetwork::Testnet,
+        2 => BitcoinNetwork::Signet,
+        3 => BitcoinNetwork::Regtest,
+        other => return Err(invalid(format!("unknown bitcoin network tag {}", other))),
+    })
+}
+
+fn token_tag(token_type: TokenType) -> u8 {
+    match token_type {
+        TokenType::Gcc => 0,
+        TokenType::Spirit => 1,
+        TokenType::Mana => 2,
+        TokenType::Ghost => 3,
+    }
+}
+
+fn scheme_tag(scheme: &SignatureScheme) -> u8 {
+    match scheme {
+        SignatureScheme::Ed25519 => 0,
+        SignatureScheme::Secp256k1 => 1,
+        SignatureScheme::BLS12381 => 2,
+        SignatureScheme::Dilithium => 3,
+    }
+}
+
+fn scheme_from_tag(tag: u8) -> Result<SignatureScheme> {
+    Ok(match tag {
+        0 => SignatureScheme::Ed25519,
+        1 => SignatureScheme::Secp256k1,
+        2 => SignatureScheme::BLS12381,
+        3 => SignatureScheme::Dilithium,
+        other => return Err(invalid(format!("unknown signature scheme tag {}", other))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VECTORS: &str = include_str!("../docs/vectors/canonical-transaction-v1.json");
+
+    fn amount(token_type: TokenType, amount: U256) -> TokenAmount {
+        let decimals = if token_type == TokenType::Ghost { 0 } else { 18 };
+        TokenAmount { token_type, amount, decimals }
+    }
+
+    fn fee(gcc: u64, ghost: u64) -> MultiTokenFee {
+        MultiTokenFee {
+            gcc_fee: amount(TokenType::Gcc, U256::from(gcc)),
+            spirit_fee: amount(TokenType::Spirit, U256::ZERO),
+            mana_fee: amount(TokenType::Mana, U256::ZERO),
+            ghost_fee: amount(TokenType::Ghost, U256::from(ghost)),
+        }
+    }
+
+    fn vector_transaction(name: &str) -> Transaction {
+        match name {
+            "deposit" => Transaction {
+                id: Uuid::parse_str("00112233-4455-6677-8899-aabbccddeeff").unwrap(),
+                from_chain: Network::Ethereum { chain_id: ChainId::ETHEREUM },
+                to_chain: Network::GhostPlane { chain_id: ChainId::GHOSTPLANE },
+                from_address: Address([0x11; 20]),
+                to_address: Address([0x22; 20]),
+                amount: amount(TokenType::Gcc, U256::from(1_000_000_000_000_000_000)),
+                fee: fee(21000, 0),
+                nonce: 7,
+                data: vec![],
+                signature: None,
+                created_at: "2024-01-01T00:00:00Z".parse().unwrap(),
+            },
+            "contract_call" => Transaction {
+                id: Uuid::parse_str("0f0e0d0c-0b0a-0908-0706-050403020100").unwrap(),
+                from_chain: Network::GhostPlane { chain_id: ChainId::GHOSTPLANE },
+                to_chain: Network::Custom {
+                    chain_id: ChainId(4242),
+                    name: "devnet".to_string(),
+                    rpc_url: "http://localhost:8545".to_string(),
+                },
+                from_address: Address([0xaa; 20]),
+                to_address: Address([0xbb; 20]),
+                amount: amount(TokenType::Mana, U256::from(5)),
+                fee: fee(1, 0),
+                nonce: 1,
+                data: vec![0xde, 0xad, 0xbe, 0xef],
+                signature: Some(Signature {
+                    r: U256::from(1),
+                    s: U256::from(2),
+                    v: 27,
+                    scheme: SignatureScheme::Ed25519,
+                }),
+                created_at: "2024-06-15T12:30:45.123456789Z".parse().unwrap(),
+            },
+            "bitcoin_withdrawal" => {
+                let mut r = U256::from(5);
+                r.0[0] = 0x80;
+                Transaction {
+                    id: Uuid::parse_str("ffffffff-0000-4000-8000-000000000001").unwrap(),
+                    from_chain: Network::Bitcoin { network: BitcoinNetwork::Testnet },
+                    to_chain: Network::GhostChain { chain_id: ChainId::GHOSTCHAIN },
+                    from_address: Address([0x01; 20]),
+                    to_address: Address([0x02; 20]),
+                    amount: amount(TokenType::Ghost, U256::from(3)),
+                    fee: fee(0, 1),
+                    nonce: 0,
+                    data: vec![],
+                    signature: Some(Signature {
+                        r,
+                        s: U256::from(7),
+                        v: 1,
+                        scheme: SignatureScheme::Secp256k1,
+                    }),
+                    created_at: "2023-11-14T22:13:20Z".parse().unwrap(),
+                }
+            }
+            other => panic!("no transaction for vector {}", other),
+        }
+    }
+
+    #[test]
+    fn test_encoding_matches_published_vectors() {
+        let vectors: serde_json::Value = serde_json::from_str(VECTORS).unwrap();
+        assert_eq!(vectors["version"], CANONICAL_VERSION);
+
+        let vectors = vectors["vectors"].as_array().unwrap();
+        assert_eq!(vectors.len(), 3);
+        for vector in vectors {
+            let name = vector["name"].as_str().unwrap();
+            let expected = vector["encoded"].as_str().unwrap();
+            let transaction = vector_transaction(name);
+
+            let encoded = encode(&transaction);
+            assert_eq!(hex::encode(&encoded), expected, "vector {}", name);
+
+            let decoded = decode(&encoded).unwrap();
+            assert_eq!(encode(&decoded), encoded, "vector {}", name);
+            assert_eq!(decoded.created_at, transaction.created_at);
+        }
+    }
+
+    #[test]
+    fn test_unknown_versions_and_malformed_input_rejected() {
+        let transaction = vector_transaction("contract_call");
+        let mut encoded = encode(&transaction);
+
+        assert!(encode_version(&transaction, 2).is_err());
+        assert!(decode(&encoded[..encoded.len() - 1]).is_err());
+
+        encoded.push(0);
+        assert!(decode(&encoded).unwrap_err().to_string().contains("trailing"));
+
+        encoded[0] = 2;
+        let error = decode(&encoded).unwrap_err();
+        assert!(matches!(
+            error,
+            BridgeError::Serialization(SerializationError::UnsupportedSchemaVersion { version: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,122 @@
+/*!
+Clock-skew tolerant time
+
+Deadlines, challenge periods, and expiries are compared against wall-clock
+time, which differs slightly between nodes. A deadline only counts as passed
+once it is behind the local clock by more than the configured skew
+tolerance, so a transaction that another node considers on time is not
+rejected here. Durations measured by this node use a monotonic basis that is
+unaffected by wall-clock adjustments after startup.
+*/
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant, SystemTime};
+use tracing::warn;
+
+/// Clock-skew settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockConfig {
+    /// Largest clock difference between nodes that is tolerated
+    pub max_skew: Duration,
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        Self {
+            max_skew: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Evaluates deadlines with skew tolerance and tracks drift from a reference
+#[derive(Debug)]
+pub struct SkewTolerantClock {
+    max_skew: Duration,
+    origin: Instant,
+    origin_wall: SystemTime,
+    last_drift: Mutex<Option<Duration>>,
+}
+
+impl SkewTolerantClock {
+    pub fn new(config: &ClockConfig) -> Self {
+        Self {
+            max_skew: config.max_skew,
+            origin: Instant::now(),
+            origin_wall: SystemTime::now(),
+            last_drift: Mutex::new(None),
+        }
+    }
+
+    pub fn max_skew(&self) -> Duration {
+        self.max_skew
+    }
+
+    /// Wall-clock time, for comparing against timestamps from other nodes
+    pub fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    /// Startup wall time advanced by monotonic elapsed time, for measuring
+    /// durations between local events
+    pub fn monotonic_now(&self) -> SystemTime {
+        self.origin_wall + self.origin.elapsed()
+    }
+
+    /// Whether `deadline` has passed at `now` even allowing for clock skew
+    pub fn has_passed(&self, deadline: SystemTime, now: SystemTime) -> bool {
+        now > deadline + self.max_skew
+    }
+
+    /// Whether `timestamp` is ahead of `now` by more than the skew tolerance
+    pub fn is_in_future(&self, timestamp: SystemTime, now: SystemTime) -> bool {
+        timestamp > now + self.max_skew
+    }
+
+    /// Compare the local clock with a trusted reference time, e.g. a recent
+    /// L1 block timestamp, warning when the drift exceeds the tolerance
+    pub fn observe_reference(&self, reference: SystemTime) -> Duration {
+        let now = self.now();
+        let drift = now.duration_since(reference)
+            .unwrap_or_else(|e| e.duration());
+        if drift > self.max_skew {
+            warn!("Local clock drifted {:?} from reference time, beyond the {:?} skew tolerance",
+                  drift, self.max_skew);
+        }
+        *self.last_drift.lock() = Some(drift);
+        drift
+    }
+
+    /// Drift measured at the last reference observation
+    pub fn last_drift(&self) -> Option<Duration> {
+        *self.last_drift.lock()
+    }
+
+    /// Whether the last observed drift is within tolerance
+    pub fn within_tolerance(&self) -> bool {
+        self.last_drift().map_or(true, |drift| drift <= self.max_skew)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadlines_tolerate_skew_and_drift_is_tracked() {
+        let clock = SkewTolerantClock::new(&ClockConfig { max_skew: Duration::from_secs(10) });
+        let deadline = SystemTime::now();
+
+        assert!(!clock.has_passed(deadline, deadline + Duration::from_secs(10)));
+        assert!(clock.has_passed(deadline, deadline + Duration::from_secs(11)));
+        assert!(!clock.is_in_future(deadline + Duration::from_secs(5), deadline));
+        assert!(clock.is_in_future(deadline + Duration::from_secs(15), deadline));
+
+        assert!(clock.within_tolerance());
+        clock.observe_reference(SystemTime::now() - Duration::from_secs(60));
+        assert!(!clock.within_tolerance());
+        clock.observe_reference(SystemTime::now() + Duration::from_secs(2));
+        assert!(clock.within_tolerance());
+        assert!(clock.monotonic_now() >= clock.origin_wall);
+    }
+}
@@ -1,33 +1,200 @@
 /*!
 Fee distribution system for 4-token economy
 
-Distributes fees according to tokenomics:
+Distributes fees according to tokenomics, by default:
 - 40% to L2 validators
 - 30% to L1 validators  
 - 20% to security fund
 - 10% to protocol development
+
+The split and burn rates in effect come from the versioned
+`EconomicParameters`, so changing them is recorded like any other parameter.
+
+Each bucket's share of each token is credited separately, by a GLEDGER
+transfer from the fee collector account to the bucket's account. A share
+whose transfer fails is queued as a pending distribution and retried with
+backoff until it lands, so collected fees always reach their buckets
+eventually. Opened with a path, the distributor keeps the queue on disk, so
+pending shares survive a restart; a share that was being retried when the
+process stopped is credited again on the next start.
 */
 
 use crate::error::{BridgeError, Result};
-use crate::types::{TokenType, TokenAmount, U256, MultiTokenFee};
+use crate::types::{Address, TokenType, TokenAmount, U256, MultiTokenFee};
 use crate::services::ServiceManager;
-use crate::economy::FeeDistributionBreakdown;
-use std::sync::Arc;
-use tracing::{debug, instrument};
+use crate::economy::{EconomicParameterRegistry, EconomicParameters, FeeDistributionBreakdown};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, info, instrument, warn};
 
 /// Fee distribution manager
 pub struct FeeDistributor {
     services: Arc<ServiceManager>,
-    distribution_config: DistributionConfig,
+    /// Source of the splits and burn rates in effect
+    parameters: Arc<EconomicParameterRegistry>,
+    transfer: Arc<dyn BucketTransfer>,
+    retry_config: DistributionRetryConfig,
+    pending: Mutex<Vec<PendingDistribution>>,
+    /// Serializes updates so queue snapshots reach disk in order
+    writes: tokio::sync::Mutex<()>,
+    /// Queue snapshot rewritten on every change; in memory only if unset
+    path: Option<PathBuf>,
 }
 
-/// Distribution configuration
+/// Moves a bucket's share of one token into that bucket
+#[async_trait]
+pub trait BucketTransfer: Send + Sync {
+    async fn credit(&self, bucket: RemainderBucket, amount: &TokenAmount) -> Result<()>;
+}
+
+/// GLEDGER accounts fees are distributed from and to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BucketAccounts {
+    /// Account payments are collected in until they are distributed
+    pub collector: Address,
+    /// Account of each bucket; shares of a bucket without one stay queued
+    pub buckets: HashMap<RemainderBucket, Address>,
+}
+
+impl Default for BucketAccounts {
+    fn default() -> Self {
+        Self {
+            // Where `TokenEconomy::process_payment` sends collected fees
+            collector: Address([0u8; 20]),
+            buckets: HashMap::new(),
+        }
+    }
+}
+
+/// Credits buckets with GLEDGER transfers out of the collector account
+pub struct GledgerBuckets {
+    services: Arc<ServiceManager>,
+    accounts: BucketAccounts,
+}
+
+impl GledgerBuckets {
+    pub fn new(services: Arc<ServiceManager>, accounts: BucketAccounts) -> Self {
+        Self { services, accounts }
+    }
+}
+
+#[async_trait]
+impl BucketTransfer for GledgerBuckets {
+    async fn credit(&self, bucket: RemainderBucket, amount: &TokenAmount) -> Result<()> {
+        let account = self.accounts.buckets.get(&bucket).ok_or_else(|| {
+            BridgeError::config(format!("No GLEDGER account configured for the {:?} bucket", bucket))
+        })?;
+        let gledger_guard = self.services.gledger().await?;
+        let gledger = gledger_guard.as_ref().unwrap();
+        let result = gledger.transfer_tokens(&self.accounts.collector, account, amount).await?;
+        if !result.success {
+            return Err(BridgeError::internal(format!(
+                "GLEDGER transfer {} to the {:?} bucket failed", result.transaction_hash, bucket
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Bucket balances kept in memory, for tests and local setups
+#[derive(Debug, Default)]
+pub struct InMemoryBuckets {
+    balances: Mutex<HashMap<(RemainderBucket, TokenType), U256>>,
+}
+
+impl InMemoryBuckets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Amount of `token_type` credited to a bucket so far
+    pub fn balance(&self, bucket: RemainderBucket, token_type: TokenType) -> U256 {
+        self.balances.lock().get(&(bucket, token_type)).cloned().unwrap_or(U256::ZERO)
+    }
+}
+
+#[async_trait]
+impl BucketTransfer for InMemoryBuckets {
+    async fn credit(&self, bucket: RemainderBucket, amount: &TokenAmount) -> Result<()> {
+        let mut balances = self.balances.lock();
+        let balance = balances.entry((bucket, amount.token_type)).or_insert(U256::ZERO);
+        *balance = balance.checked_add(&amount.amount)
+            .ok_or_else(|| BridgeError::internal(format!("{:?} bucket balance overflows", bucket)))?;
+        Ok(())
+    }
+}
+
+/// Backoff between retries of a failed distribution
 #[derive(Debug, Clone)]
-struct DistributionConfig {
+pub struct DistributionRetryConfig {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for DistributionRetryConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(300),
+        }
+    }
+}
+
+/// A bucket share whose transfer failed and is waiting to be retried
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingDistribution {
+    pub bucket: RemainderBucket,
+    pub amount: TokenAmount,
+    pub attempts: u32,
+    pub last_error: String,
+    /// Due now after a restart
+    #[serde(skip, default = "Instant::now")]
+    next_attempt: Instant,
+    backoff: Duration,
+}
+
+/// Per-token burn rates in basis points, in GCC, SPIRIT, MANA, GHOST order
+pub type BurnRates = [u64; 4];
+
+/// Default burn rates: 1% of GCC, 0.5% of MANA
+pub const DEFAULT_BURN_RATES: BurnRates = [100, 0, 50, 0];
+
+/// Distribution configuration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DistributionConfig {
     l2_validators_percent: u8,
     l1_validators_percent: u8, 
     security_fund_percent: u8,
     protocol_development_percent: u8,
+    remainder_bucket: RemainderBucket,
+}
+
+/// Distribution bucket that absorbs rounding remainders
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RemainderBucket {
+    L2Validators,
+    L1Validators,
+    SecurityFund,
+    ProtocolDevelopment,
+    Burn,
+}
+
+impl RemainderBucket {
+    fn index(self) -> usize {
+        match self {
+            RemainderBucket::L2Validators => 0,
+            RemainderBucket::L1Validators => 1,
+            RemainderBucket::SecurityFund => 2,
+            RemainderBucket::ProtocolDevelopment => 3,
+            RemainderBucket::Burn => 4,
+        }
+    }
 }
 
 impl Default for DistributionConfig {
@@ -37,18 +204,171 @@ impl Default for DistributionConfig {
             l1_validators_percent: 30,
             security_fund_percent: 20,
             protocol_development_percent: 10,
+            remainder_bucket: RemainderBucket::ProtocolDevelopment,
+        }
+    }
+}
+
+impl DistributionConfig {
+    /// Split by percentage of the fee left after burning; the shares must add up to 100
+    pub fn new(
+        l2_validators_percent: u8,
+        l1_validators_percent: u8,
+        security_fund_percent: u8,
+        protocol_development_percent: u8,
+        remainder_bucket: RemainderBucket,
+    ) -> Result<Self> {
+        let total = [l2_validators_percent, l1_validators_percent, security_fund_percent, protocol_development_percent]
+            .iter()
+            .map(|percent| *percent as u32)
+            .sum::<u32>();
+        if total != 100 {
+            return Err(BridgeError::config(format!("fee distribution shares add up to {}%, not 100%", total)));
+        }
+
+        Ok(Self {
+            l2_validators_percent,
+            l1_validators_percent,
+            security_fund_percent,
+            protocol_development_percent,
+            remainder_bucket,
+        })
+    }
+
+    /// Send rounding remainders to `bucket`
+    pub fn with_remainder_bucket(mut self, bucket: RemainderBucket) -> Self {
+        self.remainder_bucket = bucket;
+        self
+    }
+
+    /// Split a fee into the distribution buckets, burning `burn_rates` first
+    pub fn split_fee(&self, total_fee: &MultiTokenFee, burn_rates: &BurnRates) -> FeeDistributionBreakdown {
+        let gcc = self.split_amount(&total_fee.gcc_fee.amount, burn_rates[0]);
+        let spirit = self.split_amount(&total_fee.spirit_fee.amount, burn_rates[1]);
+        let mana = self.split_amount(&total_fee.mana_fee.amount, burn_rates[2]);
+        let ghost = self.split_amount(&total_fee.ghost_fee.amount, burn_rates[3]);
+
+        let bucket = |index: usize| MultiTokenFee {
+            gcc_fee: TokenAmount::new(TokenType::Gcc, gcc[index].clone()),
+            spirit_fee: TokenAmount::new(TokenType::Spirit, spirit[index].clone()),
+            mana_fee: TokenAmount::new(TokenType::Mana, mana[index].clone()),
+            ghost_fee: TokenAmount::new(TokenType::Ghost, ghost[index].clone()),
+        };
+
+        FeeDistributionBreakdown {
+            l2_validators: bucket(RemainderBucket::L2Validators.index()),
+            l1_validators: bucket(RemainderBucket::L1Validators.index()),
+            security_fund: bucket(RemainderBucket::SecurityFund.index()),
+            protocol_development: bucket(RemainderBucket::ProtocolDevelopment.index()),
+            burn_amount: bucket(RemainderBucket::Burn.index()),
+        }
+    }
+
+    /// Split an amount into the five distribution buckets.
+    ///
+    /// The burn is taken first and the rest is split by percentage using
+    /// integer arithmetic. Whatever is left after rounding down goes to the
+    /// configured remainder bucket, so the buckets always sum to `amount`.
+    fn split_amount(&self, amount: &U256, burn_rate_bps: u64) -> [U256; 5] {
+        // Neither fraction exceeds one, so no share can overflow
+        let fraction = |amount: &U256, numerator: u64, denominator: u64| {
+            amount.mul_div_u64(numerator.min(denominator), denominator).unwrap_or(U256::ZERO)
+        };
+        let burn = fraction(amount, burn_rate_bps, 10_000);
+        let distributable = amount.checked_sub(&burn).unwrap_or(U256::ZERO);
+        let share = |percent: u8| fraction(&distributable, percent as u64, 100);
+
+        let mut buckets = [
+            share(self.l2_validators_percent),
+            share(self.l1_validators_percent),
+            share(self.security_fund_percent),
+            share(self.protocol_development_percent),
+            burn,
+        ];
+
+        let allocated = buckets.iter().fold(U256::ZERO, |sum, bucket| sum.checked_add(bucket).unwrap_or(sum));
+        let remainder = amount.checked_sub(&allocated).unwrap_or(U256::ZERO);
+        let bucket = &mut buckets[self.remainder_bucket.index()];
+        if let Some(total) = bucket.checked_add(&remainder) {
+            *bucket = total;
         }
+        buckets
     }
 }
 
 impl FeeDistributor {
+    /// Distributor crediting buckets through GLEDGER, with the queue in memory
     pub async fn new(services: Arc<ServiceManager>) -> Result<Self> {
         Ok(Self {
+            transfer: Arc::new(GledgerBuckets::new(services.clone(), BucketAccounts::default())),
             services,
-            distribution_config: DistributionConfig::default(),
+            parameters: Arc::new(EconomicParameterRegistry::default()),
+            retry_config: DistributionRetryConfig::default(),
+            pending: Mutex::new(Vec::new()),
+            writes: tokio::sync::Mutex::new(()),
+            path: None,
         })
     }
 
+    /// Distributor whose queue is persisted at `path`, resuming the shares an
+    /// earlier run left pending
+    pub async fn open(services: Arc<ServiceManager>, path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let pending = match tokio::fs::read(&path).await {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            bytes => serde_json::from_slice(&bytes?).map_err(|e| BridgeError::Serialization(e.into()))?,
+        };
+
+        Ok(Self {
+            pending: Mutex::new(pending),
+            path: Some(path),
+            ..Self::new(services).await?
+        })
+    }
+
+    /// Credit buckets to these GLEDGER accounts
+    pub fn with_bucket_accounts(self, accounts: BucketAccounts) -> Self {
+        let transfer = Arc::new(GledgerBuckets::new(self.services.clone(), accounts));
+        self.with_bucket_transfer(transfer)
+    }
+
+    /// Credit buckets through a different transfer implementation
+    pub fn with_bucket_transfer(mut self, transfer: Arc<dyn BucketTransfer>) -> Self {
+        self.transfer = transfer;
+        self
+    }
+
+    /// Set the backoff for retrying failed distributions
+    pub fn with_retry_config(mut self, config: DistributionRetryConfig) -> Self {
+        self.retry_config = config;
+        self
+    }
+
+    pub fn retry_config(&self) -> &DistributionRetryConfig {
+        &self.retry_config
+    }
+
+    /// Split fees by the versions recorded in `parameters`
+    pub fn with_economic_parameters(mut self, parameters: Arc<EconomicParameterRegistry>) -> Self {
+        self.parameters = parameters;
+        self
+    }
+
+    /// Set the bucket that receives rounding remainders, as a new parameter version
+    pub fn with_remainder_bucket(self, bucket: RemainderBucket) -> Self {
+        let current = self.parameters.current().parameters;
+        self.parameters.update(EconomicParameters {
+            distribution: current.distribution.clone().with_remainder_bucket(bucket),
+            ..current
+        });
+        self
+    }
+
+    /// Distribution percentages and remainder handling in effect
+    pub fn config(&self) -> DistributionConfig {
+        self.parameters.current().parameters.distribution
+    }
+
     #[instrument(skip(self))]
     pub async fn calculate_distribution(
         &self,
@@ -56,32 +376,8 @@ impl FeeDistributor {
     ) -> Result<FeeDistributionBreakdown> {
         debug!("Calculating fee distribution for total fee: {}", total_fee.total_value());
 
-        let l2_validators = self.calculate_portion(total_fee, self.distribution_config.l2_validators_percent)?;
-        let l1_validators = self.calculate_portion(total_fee, self.distribution_config.l1_validators_percent)?;
-        let security_fund = self.calculate_portion(total_fee, self.distribution_config.security_fund_percent)?;
-        let protocol_development = self.calculate_portion(total_fee, self.distribution_config.protocol_development_percent)?;
-        
-        // Calculate burn amounts (1% of GCC, 0.5% of MANA)
-        let burn_amount = MultiTokenFee {
-            gcc_fee: TokenAmount::new(
-                TokenType::Gcc,
-                U256::from(total_fee.gcc_fee.amount.to_u64() / 100), // 1%
-            ),
-            spirit_fee: TokenAmount::new(TokenType::Spirit, U256::ZERO),
-            mana_fee: TokenAmount::new(
-                TokenType::Mana,
-                U256::from(total_fee.mana_fee.amount.to_u64() / 200), // 0.5%
-            ),
-            ghost_fee: TokenAmount::new(TokenType::Ghost, U256::ZERO),
-        };
-
-        Ok(FeeDistributionBreakdown {
-            l2_validators,
-            l1_validators,
-            security_fund,
-            protocol_development,
-            burn_amount,
-        })
+        let parameters = self.parameters.current().parameters;
+        Ok(parameters.distribution.split_fee(total_fee, &parameters.burn_rates_bps))
     }
 
     #[instrument(skip(self))]
@@ -91,47 +387,353 @@ impl FeeDistributor {
     ) -> Result<()> {
         debug!("Distributing fees to validators and funds");
 
-        // TODO: Implement actual distribution to validator addresses
-        // This would involve:
-        // 1. Get validator addresses from GHOSTD
-        // 2. Calculate individual validator rewards
-        // 3. Transfer tokens via GLEDGER
-        // 4. Update security fund balances
-        // 5. Update protocol development fund
+        let legs = [
+            (RemainderBucket::L2Validators, &distribution.l2_validators),
+            (RemainderBucket::L1Validators, &distribution.l1_validators),
+            (RemainderBucket::SecurityFund, &distribution.security_fund),
+            (RemainderBucket::ProtocolDevelopment, &distribution.protocol_development),
+            (RemainderBucket::Burn, &distribution.burn_amount),
+        ];
+
+        let mut failed = Vec::new();
+        for (bucket, fee) in legs {
+            let tokens = [&fee.gcc_fee, &fee.spirit_fee, &fee.mana_fee, &fee.ghost_fee];
+            for amount in tokens.into_iter().filter(|amount| !amount.amount.is_zero()) {
+                // Fees are already collected at this point, so a failed leg is
+                // queued for retry rather than failing the payment
+                if let Err(e) = self.transfer.credit(bucket, amount).await {
+                    warn!("Distribution of {} to {:?} failed, queued for retry: {}", amount.token_type, bucket, e);
+                    let backoff = self.retry_config.initial_backoff;
+                    failed.push(PendingDistribution {
+                        bucket,
+                        amount: amount.clone(),
+                        attempts: 1,
+                        last_error: e.to_string(),
+                        next_attempt: Instant::now() + backoff,
+                        backoff,
+                    });
+                }
+            }
+        }
+
+        if !failed.is_empty() {
+            let _write = self.writes.lock().await;
+            self.pending.lock().extend(failed);
+            self.persist().await?;
+        }
 
         debug!("Fee distribution completed");
         Ok(())
     }
 
+    /// Retry pending distributions whose backoff has elapsed, returning how
+    /// many are still pending afterwards
+    pub async fn retry_pending(&self) -> usize {
+        // Due shares stay in the snapshot on disk until their retry is
+        // recorded, so a crash mid-retry credits them again after restart
+        let _write = self.writes.lock().await;
+        let now = Instant::now();
+        let due: Vec<PendingDistribution> = {
+            let mut pending = self.pending.lock();
+            let (due, waiting) = pending.drain(..).partition(|p| p.next_attempt <= now);
+            *pending = waiting;
+            due
+        };
+        if due.is_empty() {
+            return self.pending.lock().len();
+        }
+
+        for mut distribution in due {
+            match self.transfer.credit(distribution.bucket, &distribution.amount).await {
+                Ok(()) => info!("Distribution to {:?} succeeded after {} attempts",
+                                distribution.bucket, distribution.attempts + 1),
+                Err(e) => {
+                    distribution.attempts += 1;
+                    distribution.last_error = e.to_string();
+                    distribution.backoff = (distribution.backoff * 2).min(self.retry_config.max_backoff);
+                    distribution.next_attempt = Instant::now() + distribution.backoff;
+                    warn!("Distribution to {:?} failed again (attempt {}): {}",
+                          distribution.bucket, distribution.attempts, e);
+                    self.pending.lock().push(distribution);
+                }
+            }
+        }
+
+        if let Err(e) = self.persist().await {
+            warn!("Could not persist pending fee distributions: {}", e);
+        }
+        self.pending.lock().len()
+    }
+
+    /// Rewrite the queue snapshot; callers hold `writes`
+    async fn persist(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let snapshot = self.pending.lock().clone();
+        let bytes = serde_json::to_vec(&snapshot).map_err(|e| BridgeError::Serialization(e.into()))?;
+
+        // Swap a fully written snapshot in, so a crash leaves the old or the new one
+        let mut temp_path = path.clone().into_os_string();
+        temp_path.push(".tmp");
+        let mut file = tokio::fs::File::create(&temp_path).await?;
+        file.write_all(&bytes).await?;
+        file.sync_all().await?;
+        tokio::fs::rename(&temp_path, path).await?;
+        Ok(())
+    }
+
+    /// Retry pending distributions in the background until the distributor is dropped
+    pub fn spawn_retry_task(distributor: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let distributor: Weak<Self> = Arc::downgrade(distributor);
+        tokio::spawn(async move {
+            loop {
+                let interval = match distributor.upgrade() {
+                    Some(distributor) => {
+                        distributor.retry_pending().await;
+                        distributor.retry_config.initial_backoff
+                    }
+                    None => return,
+                };
+                tokio::time::sleep(interval).await;
+            }
+        })
+    }
+
+    /// Distributions still waiting to be retried
+    pub fn pending_distributions(&self) -> Vec<PendingDistribution> {
+        self.pending.lock().clone()
+    }
+
+    /// Collected `token_type` fees not yet credited to `bucket`
+    pub fn undistributed(&self, bucket: RemainderBucket, token_type: TokenType) -> U256 {
+        self.pending.lock().iter()
+            .filter(|p| p.bucket == bucket && p.amount.token_type == token_type)
+            .fold(U256::ZERO, |total, p| total.checked_add(&p.amount.amount).unwrap_or(total))
+    }
+
     pub async fn is_healthy(&self) -> bool {
         // Verify distribution percentages sum to 100%
-        let total = self.distribution_config.l2_validators_percent +
-                   self.distribution_config.l1_validators_percent +
-                   self.distribution_config.security_fund_percent +
-                   self.distribution_config.protocol_development_percent;
+        let config = self.config();
+        let total = config.l2_validators_percent as u32 +
+                   config.l1_validators_percent as u32 +
+                   config.security_fund_percent as u32 +
+                   config.protocol_development_percent as u32;
         total == 100
     }
+}
 
-    fn calculate_portion(&self, total_fee: &MultiTokenFee, percentage: u8) -> Result<MultiTokenFee> {
-        let multiplier = percentage as f64 / 100.0;
-
-        Ok(MultiTokenFee {
-            gcc_fee: TokenAmount::new(
-                TokenType::Gcc,
-                U256::from((total_fee.gcc_fee.amount.to_u64() as f64 * multiplier) as u64),
-            ),
-            spirit_fee: TokenAmount::new(
-                TokenType::Spirit,
-                U256::from((total_fee.spirit_fee.amount.to_u64() as f64 * multiplier) as u64),
-            ),
-            mana_fee: TokenAmount::new(
-                TokenType::Mana,
-                U256::from((total_fee.mana_fee.amount.to_u64() as f64 * multiplier) as u64),
-            ),
-            ghost_fee: TokenAmount::new(
-                TokenType::Ghost,
-                U256::from((total_fee.ghost_fee.amount.to_u64() as f64 * multiplier) as u64),
-            ),
-        })
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::ServiceConfig;
+
+    fn fee(gcc: u64, spirit: u64, mana: u64, ghost: u64) -> MultiTokenFee {
+        MultiTokenFee {
+            gcc_fee: TokenAmount::new(TokenType::Gcc, U256::from(gcc)),
+            spirit_fee: TokenAmount::new(TokenType::Spirit, U256::from(spirit)),
+            mana_fee: TokenAmount::new(TokenType::Mana, U256::from(mana)),
+            ghost_fee: TokenAmount::new(TokenType::Ghost, U256::from(ghost)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_distribution_conserves_total() {
+        let services = Arc::new(ServiceManager::new(ServiceConfig::default()));
+        let distributor = FeeDistributor::new(services).await.unwrap();
+
+        for total in [fee(1_001, 7, 9_999, 3), fee(123_457, 1, 333, 1), fee(99, 0, 1, 0)] {
+            let breakdown = distributor.calculate_distribution(&total).await.unwrap();
+            let buckets = [
+                &breakdown.l2_validators,
+                &breakdown.l1_validators,
+                &breakdown.security_fund,
+                &breakdown.protocol_development,
+                &breakdown.burn_amount,
+            ];
+
+            let sum = |select: fn(&MultiTokenFee) -> u64| buckets.iter().map(|b| select(b)).sum::<u64>();
+            assert_eq!(sum(|f| f.gcc_fee.amount.to_u64()), total.gcc_fee.amount.to_u64());
+            assert_eq!(sum(|f| f.spirit_fee.amount.to_u64()), total.spirit_fee.amount.to_u64());
+            assert_eq!(sum(|f| f.mana_fee.amount.to_u64()), total.mana_fee.amount.to_u64());
+            assert_eq!(sum(|f| f.ghost_fee.amount.to_u64()), total.ghost_fee.amount.to_u64());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remainder_goes_to_designated_bucket() {
+        let services = Arc::new(ServiceManager::new(ServiceConfig::default()));
+        let distributor = FeeDistributor::new(services).await.unwrap();
+
+        // 7 SPIRIT, no burn: 40% -> 2, 30% -> 2, 20% -> 1, 10% -> 0, remainder 2
+        let breakdown = distributor.calculate_distribution(&fee(0, 7, 0, 0)).await.unwrap();
+        assert_eq!(breakdown.l2_validators.spirit_fee.amount.to_u64(), 2);
+        assert_eq!(breakdown.l1_validators.spirit_fee.amount.to_u64(), 2);
+        assert_eq!(breakdown.security_fund.spirit_fee.amount.to_u64(), 1);
+        assert_eq!(breakdown.protocol_development.spirit_fee.amount.to_u64(), 2);
+    }
+
+    /// Fails the first `failures` credits to the security fund
+    struct FlakySecurityFund {
+        buckets: InMemoryBuckets,
+        failures: Mutex<u32>,
+    }
+
+    #[async_trait]
+    impl BucketTransfer for FlakySecurityFund {
+        async fn credit(&self, bucket: RemainderBucket, amount: &TokenAmount) -> Result<()> {
+            if bucket == RemainderBucket::SecurityFund {
+                let mut failures = self.failures.lock();
+                if *failures > 0 {
+                    *failures -= 1;
+                    return Err(BridgeError::Internal("GLEDGER unavailable".to_string()));
+                }
+            }
+            self.buckets.credit(bucket, amount).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_distribution_is_retried_until_credited() {
+        let services = Arc::new(ServiceManager::new(ServiceConfig::default()));
+        let transfer = Arc::new(FlakySecurityFund { buckets: InMemoryBuckets::new(), failures: Mutex::new(2) });
+        let distributor = FeeDistributor::new(services).await.unwrap()
+            .with_bucket_transfer(transfer.clone())
+            .with_retry_config(DistributionRetryConfig {
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(4),
+            });
+
+        let breakdown = distributor.calculate_distribution(&fee(10_000, 0, 0, 0)).await.unwrap();
+        distributor.distribute_fees(&breakdown).await.unwrap();
+
+        // Other buckets are credited; the security fund share waits
+        let expected = breakdown.security_fund.gcc_fee.amount.clone();
+        assert_eq!(transfer.buckets.balance(RemainderBucket::L2Validators, TokenType::Gcc),
+                   breakdown.l2_validators.gcc_fee.amount);
+        assert!(transfer.buckets.balance(RemainderBucket::SecurityFund, TokenType::Gcc).is_zero());
+        assert_eq!(distributor.undistributed(RemainderBucket::SecurityFund, TokenType::Gcc), expected);
+
+        // Second attempt fails too, third succeeds
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(distributor.retry_pending().await, 1);
+        assert_eq!(distributor.pending_distributions()[0].attempts, 2);
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(distributor.retry_pending().await, 0);
+
+        assert_eq!(transfer.buckets.balance(RemainderBucket::SecurityFund, TokenType::Gcc), expected);
+        assert!(distributor.undistributed(RemainderBucket::SecurityFund, TokenType::Gcc).is_zero());
+    }
+
+    #[tokio::test]
+    async fn test_split_is_exact_beyond_u64() {
+        let services = Arc::new(ServiceManager::new(ServiceConfig::default()));
+        let distributor = FeeDistributor::new(services).await.unwrap();
+
+        // 10^30 GCC base units, far past u64
+        let amount = U256::from(1_000_000_000_000_000).checked_mul_u64(1_000_000_000_000_000).unwrap();
+        let mut total = fee(0, 0, 0, 0);
+        total.gcc_fee.amount = amount.clone();
+        let breakdown = distributor.calculate_distribution(&total).await.unwrap();
+
+        // 1% burned, then 40% of the rest
+        let burn = amount.div_rem_u64(100).0;
+        assert_eq!(breakdown.burn_amount.gcc_fee.amount, burn);
+        let distributable = amount.checked_sub(&burn).unwrap();
+        assert_eq!(breakdown.l2_validators.gcc_fee.amount, distributable.div_rem_u64(10).0.checked_mul_u64(4).unwrap());
+
+        let buckets = [
+            &breakdown.l2_validators,
+            &breakdown.l1_validators,
+            &breakdown.security_fund,
+            &breakdown.protocol_development,
+            &breakdown.burn_amount,
+        ];
+        let sum = buckets.iter().fold(U256::ZERO, |sum, b| sum.checked_add(&b.gcc_fee.amount).unwrap());
+        assert_eq!(sum, amount);
+    }
+
+    /// Always fails, as GLEDGER would while unreachable
+    struct Unreachable;
+
+    #[async_trait]
+    impl BucketTransfer for Unreachable {
+        async fn credit(&self, _bucket: RemainderBucket, _amount: &TokenAmount) -> Result<()> {
+            Err(BridgeError::Internal("GLEDGER unavailable".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pending_distributions_survive_restart() {
+        let path = std::env::temp_dir().join(format!("ghostbridge-distributions-{}.json", uuid::Uuid::new_v4()));
+        let services = Arc::new(ServiceManager::new(ServiceConfig::default()));
+
+        let distributor = FeeDistributor::open(services.clone(), &path).await.unwrap()
+            .with_bucket_transfer(Arc::new(Unreachable));
+        let breakdown = distributor.calculate_distribution(&fee(10_000, 0, 30_000, 0)).await.unwrap();
+        distributor.distribute_fees(&breakdown).await.unwrap();
+        let queued = distributor.pending_distributions().len();
+        assert!(queued > 0);
+        drop(distributor);
+
+        // After a restart the queued shares are due at once and land
+        let buckets = Arc::new(InMemoryBuckets::new());
+        let reopened = FeeDistributor::open(services, &path).await.unwrap()
+            .with_bucket_transfer(buckets.clone());
+        assert_eq!(reopened.pending_distributions().len(), queued);
+        assert_eq!(reopened.undistributed(RemainderBucket::L1Validators, TokenType::Mana),
+                   breakdown.l1_validators.mana_fee.amount);
+        assert_eq!(reopened.retry_pending().await, 0);
+        assert_eq!(buckets.balance(RemainderBucket::SecurityFund, TokenType::Gcc), breakdown.security_fund.gcc_fee.amount);
+        assert_eq!(buckets.balance(RemainderBucket::Burn, TokenType::Mana), breakdown.burn_amount.mana_fee.amount);
+
+        // The emptied queue is what the next start sees
+        drop(reopened);
+        let services = Arc::new(ServiceManager::new(ServiceConfig::default()));
+        assert!(FeeDistributor::open(services, &path).await.unwrap().pending_distributions().is_empty());
+        tokio::fs::remove_file(&path).await.unwrap();
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_shares_without_a_bucket_account_stay_queued() {
+        let services = Arc::new(ServiceManager::new(ServiceConfig::default()));
+        let distributor = FeeDistributor::new(services).await.unwrap();
+
+        let breakdown = distributor.calculate_distribution(&fee(100, 0, 0, 0)).await.unwrap();
+        distributor.distribute_fees(&breakdown).await.unwrap();
+        assert_eq!(distributor.undistributed(RemainderBucket::L2Validators, TokenType::Gcc),
+                   breakdown.l2_validators.gcc_fee.amount);
+    }
+
+    #[tokio::test]
+    async fn test_distribution_follows_active_parameter_version() {
+        let services = Arc::new(ServiceManager::new(ServiceConfig::default()));
+        let parameters = Arc::new(EconomicParameterRegistry::default());
+        let distributor = FeeDistributor::new(services).await.unwrap()
+            .with_economic_parameters(parameters.clone());
+
+        let before = distributor.calculate_distribution(&fee(10_000, 0, 0, 0)).await.unwrap();
+        assert_eq!(before.burn_amount.gcc_fee.amount.to_u64(), 100);
+        assert_eq!(before.l2_validators.gcc_fee.amount.to_u64(), 3_960);
+
+        // Burn 5% of GCC and pay half of the rest to L2 validators
+        let version = parameters.update(EconomicParameters {
+            burn_rates_bps: [500, 0, 50, 0],
+            distribution: DistributionConfig::new(50, 30, 10, 10, RemainderBucket::ProtocolDevelopment).unwrap(),
+            ..EconomicParameters::default()
+        });
+        assert_eq!(version, 2);
+
+        let after = distributor.calculate_distribution(&fee(10_000, 0, 0, 0)).await.unwrap();
+        assert_eq!(after.burn_amount.gcc_fee.amount.to_u64(), 500);
+        assert_eq!(after.l2_validators.gcc_fee.amount.to_u64(), 4_750);
+        assert!(distributor.is_healthy().await);
+
+        // Version 1 still reproduces the earlier split
+        let v1 = parameters.get(1).unwrap().parameters;
+        let replayed = v1.distribution.split_fee(&fee(10_000, 0, 0, 0), &v1.burn_rates_bps);
+        assert_eq!(replayed.l2_validators.gcc_fee.amount, before.l2_validators.gcc_fee.amount);
+        assert_eq!(replayed.burn_amount.gcc_fee.amount, before.burn_amount.gcc_fee.amount);
+        assert!(DistributionConfig::new(50, 30, 20, 10, RemainderBucket::Burn).is_err());
+    }
+}
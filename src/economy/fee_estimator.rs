@@ -0,0 +1,149 @@
+/*!
+Priority fee suggestions for a target inclusion time
+
+Batches are filled highest fee first, so a transaction is included within a
+target time if it outbids all but the transactions the bridge can include in
+that time. The estimator learns that inclusion rate from recent batches and
+ranks the suggested fee against the fees currently waiting in the pool.
+*/
+
+use crate::types::U256;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Fee estimator settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeEstimatorConfig {
+    /// Lowest fee ever suggested
+    pub min_priority_fee: U256,
+    /// How far above the fee to outbid, in basis points
+    pub outbid_bps: u64,
+    /// Inclusion rate assumed until batches have been observed
+    pub default_inclusion_rate: f64,
+    /// Recent batches used to learn the inclusion rate
+    pub history: usize,
+}
+
+impl Default for FeeEstimatorConfig {
+    fn default() -> Self {
+        Self {
+            min_priority_fee: U256::from(1_000_000_000u64), // 1 Gwei
+            outbid_bps: 1_000, // 10%
+            default_inclusion_rate: 10_000.0,
+            history: 100,
+        }
+    }
+}
+
+/// Suggested priority fee for a target inclusion time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeSuggestion {
+    pub priority_fee: U256,
+    pub target: Duration,
+    /// Transactions waiting when the suggestion was made
+    pub pool_depth: usize,
+    /// Transactions expected to be included within the target
+    pub capacity: usize,
+    /// Learned inclusion rate in transactions per second
+    pub inclusion_rate: f64,
+}
+
+/// One observed batch
+#[derive(Debug, Clone, Copy)]
+struct BatchSample {
+    included: usize,
+    interval: Duration,
+}
+
+/// Suggests priority fees from the pool's fee distribution and recent inclusion data
+pub struct InclusionFeeEstimator {
+    config: FeeEstimatorConfig,
+    samples: Mutex<VecDeque<BatchSample>>,
+}
+
+impl InclusionFeeEstimator {
+    pub fn new(config: FeeEstimatorConfig) -> Self {
+        Self { config, samples: Mutex::new(VecDeque::new()) }
+    }
+
+    /// Record a batch that included `included` transactions `interval` after the previous one
+    pub fn record_batch(&self, included: usize, interval: Duration) {
+        let mut samples = self.samples.lock();
+        samples.push_back(BatchSample { included, interval });
+        while samples.len() > self.config.history.max(1) {
+            samples.pop_front();
+        }
+    }
+
+    /// Transactions included per second over recent batches
+    pub fn inclusion_rate(&self) -> f64 {
+        let samples = self.samples.lock();
+        let included: usize = samples.iter().map(|s| s.included).sum();
+        let elapsed: f64 = samples.iter().map(|s| s.interval.as_secs_f64()).sum();
+        if elapsed > 0.0 {
+            included as f64 / elapsed
+        } else {
+            self.config.default_inclusion_rate
+        }
+    }
+
+    /// Fee likely to be included within `target`, given the fees waiting in the pool
+    pub fn suggest(&self, target: Duration, pool_fees: &[U256]) -> FeeSuggestion {
+        let inclusion_rate = self.inclusion_rate();
+        let capacity = (inclusion_rate * target.as_secs_f64()).floor() as usize;
+
+        let mut fees: Vec<u64> = pool_fees.iter().map(U256::to_u64).collect();
+        fees.sort_unstable_by(|a, b| b.cmp(a));
+
+        // Outbid the transaction that would take the last slot within the target
+        let min_fee = self.config.min_priority_fee.to_u64();
+        let priority_fee = match fees.get(capacity.saturating_sub(1)) {
+            Some(&competing) => {
+                let bump = (competing as u128 * self.config.outbid_bps as u128 / 10_000).max(1) as u64;
+                competing.saturating_add(bump).max(min_fee)
+            }
+            _ => min_fee,
+        };
+
+        FeeSuggestion {
+            priority_fee: U256::from(priority_fee),
+            target,
+            pool_depth: fees.len(),
+            capacity,
+            inclusion_rate,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fees(count: u64, step: u64) -> Vec<U256> {
+        (1..=count).map(|i| U256::from(1_000_000_000 + i * step)).collect()
+    }
+
+    #[test]
+    fn test_heavier_pool_load_raises_suggested_fee() {
+        let estimator = InclusionFeeEstimator::new(FeeEstimatorConfig::default());
+        // Learned from recent batches: 100 transactions every 100ms
+        for _ in 0..10 {
+            estimator.record_batch(100, Duration::from_millis(100));
+        }
+        assert!((estimator.inclusion_rate() - 1_000.0).abs() < 1e-6);
+
+        let target = Duration::from_secs(2);
+        let light = estimator.suggest(target, &fees(500, 1_000_000));
+        let heavy = estimator.suggest(target, &fees(20_000, 1_000_000));
+
+        assert_eq!(light.capacity, 2_000);
+        assert_eq!(light.priority_fee, FeeEstimatorConfig::default().min_priority_fee);
+        assert!(heavy.priority_fee.to_u64() > light.priority_fee.to_u64());
+
+        // A longer target under the same load needs a lower fee
+        let relaxed = estimator.suggest(Duration::from_secs(10), &fees(20_000, 1_000_000));
+        assert!(relaxed.priority_fee.to_u64() < heavy.priority_fee.to_u64());
+    }
+}
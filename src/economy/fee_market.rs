@@ -0,0 +1,259 @@
+/*!
+Pluggable fee-market strategies
+
+A `FeeMarketStrategy` turns the GLEDGER base quote for an operation into the
+base and priority fees charged, and decides how the collected fees are split.
+Deployments choose a strategy through the `FeeMarketConfig` in the economy's
+`EconomicParameters`, e.g. with `TokenEconomy::with_fee_market`:
+
+- `Flat`: the quote is the base fee and the priority multiplier scales it
+- `Eip1559`: the base fee tracks block utilization and is burned, while
+  validators and funds are paid from the priority tip
+*/
+
+use crate::economy::distribution::BurnRates;
+use crate::economy::{EconomicParameters, FeeDistributionBreakdown};
+use crate::types::{MultiTokenFee, TokenAmount, TokenType, U256};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// Fee market selection
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeeMarketConfig {
+    /// Flat base fee scaled by the priority multiplier
+    #[default]
+    Flat,
+    /// EIP-1559-style base fee adjustment with burned base fees
+    Eip1559 {
+        /// Block utilization (0.0 to 1.0) at which the base fee holds steady
+        target_utilization: f64,
+        /// Maximum base fee change per block in basis points
+        max_change_bps: u64,
+        /// Priority tip as basis points of the quote, before the priority multiplier
+        tip_bps: u64,
+        /// Floor for the base fee multiplier
+        min_base_multiplier: f64,
+        /// Ceiling for the base fee multiplier
+        max_base_multiplier: f64,
+    },
+}
+
+impl FeeMarketConfig {
+    /// Default EIP-1559 parameters: 50% target, 12.5% max change, 10% tip
+    pub fn eip1559() -> Self {
+        FeeMarketConfig::Eip1559 {
+            target_utilization: 0.5,
+            max_change_bps: 1_250,
+            tip_bps: 1_000,
+            min_base_multiplier: 0.1,
+            max_base_multiplier: 10.0,
+        }
+    }
+
+    /// Build the configured strategy
+    pub fn build(&self) -> Box<dyn FeeMarketStrategy> {
+        match self {
+            FeeMarketConfig::Flat => Box::new(FlatRateMarket),
+            FeeMarketConfig::Eip1559 {
+                target_utilization,
+                max_change_bps,
+                tip_bps,
+                min_base_multiplier,
+                max_base_multiplier,
+            } => Box::new(Eip1559Market {
+                target_utilization: target_utilization.clamp(0.01, 1.0),
+                max_change: *max_change_bps as f64 / 10_000.0,
+                tip_bps: *tip_bps,
+                min_base_multiplier: *min_base_multiplier,
+                max_base_multiplier: *max_base_multiplier,
+                base_multiplier: Mutex::new(1.0),
+            }),
+        }
+    }
+}
+
+/// Computes fees and their distribution for a fee market
+pub trait FeeMarketStrategy: Send + Sync {
+    /// Strategy name for logging and metrics
+    fn name(&self) -> &'static str;
+
+    /// Base fee charged for an operation quoted at `quote`
+    fn base_fee(&self, quote: &MultiTokenFee) -> MultiTokenFee;
+
+    /// Priority fee on top of the base fee
+    fn priority_fee(&self, quote: &MultiTokenFee, priority_multiplier: f64) -> MultiTokenFee;
+
+    /// Split collected fees by the splits and burn rates in `parameters`;
+    /// `priority_fee` includes any cross-chain and security surcharges
+    fn distribution(
+        &self,
+        parameters: &EconomicParameters,
+        base_fee: &MultiTokenFee,
+        priority_fee: &MultiTokenFee,
+    ) -> FeeDistributionBreakdown;
+
+    /// Observe the utilization (0.0 to 1.0) of the latest block
+    fn record_block_utilization(&self, _utilization: f64) {}
+}
+
+/// Flat-rate market: the quote is the base fee, scaled by the priority multiplier
+pub struct FlatRateMarket;
+
+impl FeeMarketStrategy for FlatRateMarket {
+    fn name(&self) -> &'static str {
+        "flat"
+    }
+
+    fn base_fee(&self, quote: &MultiTokenFee) -> MultiTokenFee {
+        quote.clone()
+    }
+
+    fn priority_fee(&self, quote: &MultiTokenFee, priority_multiplier: f64) -> MultiTokenFee {
+        scale_fee(quote, (priority_multiplier - 1.0).max(0.0))
+    }
+
+    fn distribution(
+        &self,
+        parameters: &EconomicParameters,
+        base_fee: &MultiTokenFee,
+        priority_fee: &MultiTokenFee,
+    ) -> FeeDistributionBreakdown {
+        parameters.distribution.split_fee(&add_fees(base_fee, priority_fee), &parameters.burn_rates_bps)
+    }
+}
+
+/// EIP-1559-style market: the base fee moves with block utilization and is
+/// burned in full; the priority tip is distributed
+pub struct Eip1559Market {
+    target_utilization: f64,
+    max_change: f64,
+    tip_bps: u64,
+    min_base_multiplier: f64,
+    max_base_multiplier: f64,
+    base_multiplier: Mutex<f64>,
+}
+
+impl Eip1559Market {
+    /// Current base fee multiplier relative to the GLEDGER quote
+    pub fn base_multiplier(&self) -> f64 {
+        *self.base_multiplier.lock()
+    }
+}
+
+impl FeeMarketStrategy for Eip1559Market {
+    fn name(&self) -> &'static str {
+        "eip1559"
+    }
+
+    fn base_fee(&self, quote: &MultiTokenFee) -> MultiTokenFee {
+        scale_fee(quote, self.base_multiplier())
+    }
+
+    fn priority_fee(&self, quote: &MultiTokenFee, priority_multiplier: f64) -> MultiTokenFee {
+        scale_fee(quote, self.tip_bps as f64 / 10_000.0 * priority_multiplier.max(0.0))
+    }
+
+    fn distribution(
+        &self,
+        parameters: &EconomicParameters,
+        base_fee: &MultiTokenFee,
+        priority_fee: &MultiTokenFee,
+    ) -> FeeDistributionBreakdown {
+        const NO_BURN: BurnRates = [0; 4];
+        let mut breakdown = parameters.distribution.split_fee(priority_fee, &NO_BURN);
+        breakdown.burn_amount = add_fees(&breakdown.burn_amount, base_fee);
+        breakdown
+    }
+
+    fn record_block_utilization(&self, utilization: f64) {
+        let delta = (utilization.clamp(0.0, 1.0) - self.target_utilization) / self.target_utilization;
+        let mut multiplier = self.base_multiplier.lock();
+        *multiplier = (*multiplier * (1.0 + self.max_change * delta.clamp(-1.0, 1.0)))
+            .clamp(self.min_base_multiplier, self.max_base_multiplier);
+    }
+}
+
+fn scale_fee(fee: &MultiTokenFee, factor: f64) -> MultiTokenFee {
+    let scale = |amount: &TokenAmount, token_type| {
+        TokenAmount::new(token_type, U256::from((amount.amount.to_u64() as f64 * factor) as u64))
+    };
+    MultiTokenFee {
+        gcc_fee: scale(&fee.gcc_fee, TokenType::Gcc),
+        spirit_fee: scale(&fee.spirit_fee, TokenType::Spirit),
+        mana_fee: scale(&fee.mana_fee, TokenType::Mana),
+        ghost_fee: scale(&fee.ghost_fee, TokenType::Ghost),
+    }
+}
+
+/// Component-wise sum of two fees
+fn add_fees(a: &MultiTokenFee, b: &MultiTokenFee) -> MultiTokenFee {
+    MultiTokenFee {
+        gcc_fee: TokenAmount::new(TokenType::Gcc, &a.gcc_fee.amount + &b.gcc_fee.amount),
+        spirit_fee: TokenAmount::new(TokenType::Spirit, &a.spirit_fee.amount + &b.spirit_fee.amount),
+        mana_fee: TokenAmount::new(TokenType::Mana, &a.mana_fee.amount + &b.mana_fee.amount),
+        ghost_fee: TokenAmount::new(TokenType::Ghost, &a.ghost_fee.amount + &b.ghost_fee.amount),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fee(gcc: u64, mana: u64) -> MultiTokenFee {
+        MultiTokenFee {
+            gcc_fee: TokenAmount::new(TokenType::Gcc, U256::from(gcc)),
+            spirit_fee: TokenAmount::new(TokenType::Spirit, U256::ZERO),
+            mana_fee: TokenAmount::new(TokenType::Mana, U256::from(mana)),
+            ghost_fee: TokenAmount::new(TokenType::Ghost, U256::ZERO),
+        }
+    }
+
+    fn gcc(fee: &MultiTokenFee) -> u64 {
+        fee.gcc_fee.amount.to_u64()
+    }
+
+    #[test]
+    fn test_strategies_on_identical_inputs() {
+        let quote = fee(10_000, 2_000);
+
+        let parameters = EconomicParameters::default();
+        let flat = FeeMarketConfig::Flat.build();
+        assert_eq!(gcc(&flat.base_fee(&quote)), 10_000);
+        assert_eq!(gcc(&flat.priority_fee(&quote, 1.5)), 5_000);
+        let breakdown = flat.distribution(&parameters, &flat.base_fee(&quote), &flat.priority_fee(&quote, 1.5));
+        assert_eq!(gcc(&breakdown.burn_amount), 150); // 1% of 15,000
+        assert_eq!(gcc(&breakdown.l2_validators), 5_940); // 40% of 14,850
+
+        let eip1559 = FeeMarketConfig::eip1559().build();
+        assert_eq!(gcc(&eip1559.base_fee(&quote)), 10_000);
+        assert_eq!(gcc(&eip1559.priority_fee(&quote, 1.5)), 1_500); // 10% tip x 1.5
+        let breakdown = eip1559.distribution(&parameters, &eip1559.base_fee(&quote), &eip1559.priority_fee(&quote, 1.5));
+        assert_eq!(gcc(&breakdown.burn_amount), 10_000); // whole base fee
+        assert_eq!(gcc(&breakdown.l2_validators), 600); // 40% of the tip
+    }
+
+    #[test]
+    fn test_switching_strategy_changes_fees() {
+        let quote = fee(10_000, 2_000);
+        let flat = FeeMarketConfig::Flat.build();
+        let eip1559 = FeeMarketConfig::eip1559().build();
+
+        // Full blocks raise the EIP-1559 base fee by 12.5% each; flat ignores utilization
+        for _ in 0..2 {
+            flat.record_block_utilization(1.0);
+            eip1559.record_block_utilization(1.0);
+        }
+        assert_eq!(gcc(&flat.base_fee(&quote)), 10_000);
+        assert_eq!(gcc(&eip1559.base_fee(&quote)), 12_656);
+
+        let total = |market: &dyn FeeMarketStrategy| {
+            gcc(&add_fees(&market.base_fee(&quote), &market.priority_fee(&quote, 1.5)))
+        };
+        assert_ne!(total(flat.as_ref()), total(eip1559.as_ref()));
+
+        // Empty blocks bring it back down
+        eip1559.record_block_utilization(0.0);
+        assert_eq!(gcc(&eip1559.base_fee(&quote)), 11_074);
+    }
+}
@@ -10,24 +10,39 @@ Complete implementation of the GhostChain 4-token economy:
 Integrates with GLEDGER service for balance management and fee distribution.
 */
 
-use crate::error::{BridgeError, Result, TokenError};
+use crate::error::{BalanceShortfall, BalanceShortfalls, BridgeError, Result, TokenError};
+use crate::metrics::EconomyMetrics;
 use crate::types::{TokenType, TokenAmount, U256, MultiTokenFee, Address};
-use crate::services::{ServiceManager, gledger::{GasOperation, StateUpdate}};
+use crate::services::{ServiceManager, gledger::{GasOperation, GledgerService, StateUpdate, TransferResult}};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tracing::{debug, info, instrument, warn};
+use tracing::{debug, error, info, instrument, warn};
 
 // Sub-modules
 pub mod fee_calculator;
 pub mod token_manager;
 pub mod economics;
 pub mod distribution;
+pub mod paymaster;
+pub mod fee_market;
+pub mod fee_estimator;
+pub mod parameters;
+pub mod oracle;
 
 pub use fee_calculator::FeeCalculator;
 pub use token_manager::TokenManager;
 pub use economics::TokenEconomics;
-pub use distribution::FeeDistributor;
+pub use distribution::{
+    BucketAccounts, BucketTransfer, DistributionConfig, DistributionRetryConfig, FeeDistributor,
+    GledgerBuckets, InMemoryBuckets, PendingDistribution, RemainderBucket,
+};
+pub use fee_market::{Eip1559Market, FeeMarketConfig, FeeMarketStrategy, FlatRateMarket};
+pub use fee_estimator::{FeeEstimatorConfig, FeeSuggestion, InclusionFeeEstimator};
+pub use paymaster::{Paymaster, PaymasterConfig, PaymasterQuote};
+pub use parameters::{EconomicParameterRegistry, EconomicParameters, ParameterVersion};
+pub use oracle::{HttpPriceOracle, PriceOracle};
+pub use crate::metrics::{DistributedTotals, EconomicSummary, TokenTotals};
 
 /// 4-Token economy manager
 pub struct TokenEconomy {
@@ -37,6 +52,12 @@ pub struct TokenEconomy {
     economics: Arc<TokenEconomics>,
     services: Arc<ServiceManager>,
     pricing_cache: Arc<RwLock<PricingCache>>,
+    price_oracle: Arc<dyn PriceOracle>,
+    paymaster: Arc<Paymaster>,
+    /// Rebuilt whenever the economic parameters change
+    fee_market: parking_lot::RwLock<Arc<dyn FeeMarketStrategy>>,
+    parameters: Arc<EconomicParameterRegistry>,
+    metrics: Arc<EconomyMetrics>,
 }
 
 /// Token pricing cache
@@ -67,6 +88,18 @@ pub struct FeeBreakdown {
     pub bridge_security_fee: TokenAmount,
     pub total_fee: MultiTokenFee,
     pub fee_distribution: FeeDistributionBreakdown,
+    /// Token the payer uses for gas; non-GCC tokens go through the paymaster
+    pub gas_payment_token: Option<TokenType>,
+    /// Economic parameters version the fees were computed with
+    pub parameters_version: u32,
+}
+
+impl FeeBreakdown {
+    /// Pay the GCC gas portion in another token via the paymaster
+    pub fn with_gas_payment_token(mut self, token_type: TokenType) -> Self {
+        self.gas_payment_token = Some(token_type);
+        self
+    }
 }
 
 /// Fee distribution breakdown
@@ -91,14 +124,19 @@ pub struct TokenMetrics {
 
 impl TokenEconomy {
     /// Create a new token economy manager
-    #[instrument(skip(services))]
-    pub async fn new(services: Arc<ServiceManager>) -> Result<Self> {
+    #[instrument(skip(services, price_oracle))]
+    pub async fn new(services: Arc<ServiceManager>, price_oracle: Arc<dyn PriceOracle>) -> Result<Self> {
         info!("Initializing 4-token economy system");
 
         let token_manager = Arc::new(TokenManager::new(services.clone()).await?);
         let fee_calculator = Arc::new(FeeCalculator::new().await?);
-        let fee_distributor = Arc::new(FeeDistributor::new(services.clone()).await?);
+        let parameters = Arc::new(EconomicParameterRegistry::new(EconomicParameters::default()));
+        let fee_distributor = Arc::new(
+            FeeDistributor::new(services.clone()).await?.with_economic_parameters(parameters.clone())
+        );
+        FeeDistributor::spawn_retry_task(&fee_distributor);
         let economics = Arc::new(TokenEconomics::new().await?);
+        let fee_market = Arc::from(parameters.current().parameters.fee_market.build());
 
         let pricing_cache = Arc::new(RwLock::new(PricingCache {
             prices: HashMap::new(),
@@ -113,15 +151,75 @@ impl TokenEconomy {
             economics,
             services,
             pricing_cache,
+            price_oracle,
+            paymaster: Arc::new(Paymaster::new(PaymasterConfig::default())),
+            fee_market: parking_lot::RwLock::new(fee_market),
+            parameters,
+            metrics: Arc::new(EconomyMetrics::new()),
         };
 
-        // Initialize token pricing
-        economy.update_pricing().await?;
+        // Initialize token pricing; fee calculation retries once the oracle is reachable
+        if let Err(e) = economy.update_pricing().await {
+            warn!("Initial token pricing unavailable: {}", e);
+        }
 
         info!("4-token economy system initialized successfully");
         Ok(economy)
     }
 
+    /// Distribute fees to the given GLEDGER accounts, keeping failed shares
+    /// queued at `pending_path` across restarts if one is given
+    pub async fn with_fee_distribution(
+        mut self,
+        accounts: BucketAccounts,
+        pending_path: Option<std::path::PathBuf>,
+    ) -> Result<Self> {
+        let distributor = match pending_path {
+            Some(path) => FeeDistributor::open(self.services.clone(), path).await?,
+            None => FeeDistributor::new(self.services.clone()).await?,
+        };
+        // The retry task of the replaced distributor stops once it is dropped
+        self.fee_distributor = Arc::new(
+            distributor.with_bucket_accounts(accounts).with_economic_parameters(self.parameters.clone())
+        );
+        FeeDistributor::spawn_retry_task(&self.fee_distributor);
+        Ok(self)
+    }
+
+    /// Use a custom paymaster configuration
+    pub fn with_paymaster(mut self, config: PaymasterConfig) -> Self {
+        self.paymaster = Arc::new(Paymaster::new(config));
+        self
+    }
+
+    /// Use a different fee market strategy
+    pub fn with_fee_market(self, config: FeeMarketConfig) -> Self {
+        let parameters = EconomicParameters { fee_market: config, ..self.parameters.current().parameters };
+        self.update_economic_parameters(parameters);
+        self
+    }
+
+    /// Activate a new economic parameter set, returning its version.
+    /// A changed fee market restarts from its initial state.
+    pub fn update_economic_parameters(&self, parameters: EconomicParameters) -> u32 {
+        let previous = self.parameters.current();
+        let version = self.parameters.update(parameters.clone());
+        if version != previous.version && parameters.fee_market != previous.parameters.fee_market {
+            *self.fee_market.write() = Arc::from(parameters.fee_market.build());
+        }
+        version
+    }
+
+    /// History of economic parameter versions
+    pub fn economic_parameters(&self) -> Arc<EconomicParameterRegistry> {
+        self.parameters.clone()
+    }
+
+    /// Feed the latest block utilization (0.0 to 1.0) to the fee market
+    pub fn record_block_utilization(&self, utilization: f64) {
+        self.fee_market.read().record_block_utilization(utilization);
+    }
+
     /// Calculate comprehensive transaction fees
     #[instrument(skip(self))]
     pub async fn calculate_transaction_fees(
@@ -132,28 +230,31 @@ impl TokenEconomy {
     ) -> Result<FeeBreakdown> {
         debug!("Calculating fees for operation: {:?}", operation);
 
-        // Get base fees from GLEDGER
+        // Get the base quote from GLEDGER and price it through the fee market
         let gledger_guard = self.services.gledger().await?;
         let gledger = gledger_guard.as_ref().unwrap();
-        let base_fees = gledger.calculate_gas_fees(operation).await?;
+        let quote = gledger.calculate_gas_fees(operation).await?;
+
+        // Snapshot the parameters so the whole breakdown uses one version
+        let parameters = self.parameters.current();
+        let fee_market = self.fee_market.read().clone();
 
-        // Apply priority multiplier
-        let priority_fees = self.apply_priority_multiplier(&base_fees, priority_multiplier)?;
+        let base_fees = fee_market.base_fee(&quote);
+        let priority_fees = fee_market.priority_fee(&quote, priority_multiplier);
 
         // Calculate cross-chain fees if applicable
         let cross_chain_fee = if cross_chain {
-            Some(self.calculate_cross_chain_fee(&base_fees).await?)
+            Some(parameters.parameters.cross_chain_fee(&base_fees))
         } else {
             None
         };
 
-        // Calculate bridge security fee (0.1% of total)
-        let bridge_security_fee = self.calculate_security_fee(&base_fees)?;
+        let bridge_security_fee = parameters.parameters.security_fee(&base_fees);
 
-        // Calculate total fees
-        let mut total_fee = priority_fees.clone();
+        // Surcharges are paid out alongside the priority fee
+        let mut surcharged_priority = priority_fees.clone();
         if let Some(cc_fee) = &cross_chain_fee {
-            total_fee = self.add_fees(&total_fee, &MultiTokenFee {
+            surcharged_priority = self.add_fees(&surcharged_priority, &MultiTokenFee {
                 gcc_fee: cc_fee.clone(),
                 spirit_fee: TokenAmount::new(TokenType::Spirit, U256::ZERO),
                 mana_fee: TokenAmount::new(TokenType::Mana, U256::ZERO),
@@ -162,15 +263,17 @@ impl TokenEconomy {
         }
 
         // Add security fee
-        total_fee = self.add_fees(&total_fee, &MultiTokenFee {
+        surcharged_priority = self.add_fees(&surcharged_priority, &MultiTokenFee {
             gcc_fee: bridge_security_fee.clone(),
             spirit_fee: TokenAmount::new(TokenType::Spirit, U256::ZERO),
             mana_fee: TokenAmount::new(TokenType::Mana, U256::ZERO),
             ghost_fee: TokenAmount::new(TokenType::Ghost, U256::ZERO),
         })?;
 
+        let total_fee = self.add_fees(&base_fees, &surcharged_priority)?;
+
         // Calculate fee distribution
-        let fee_distribution = self.calculate_fee_distribution(&total_fee).await?;
+        let fee_distribution = fee_market.distribution(&parameters.parameters, &base_fees, &surcharged_priority);
 
         let breakdown = FeeBreakdown {
             base_fee: base_fees.gcc_fee.clone(),
@@ -179,6 +282,8 @@ impl TokenEconomy {
             bridge_security_fee,
             total_fee,
             fee_distribution,
+            gas_payment_token: None,
+            parameters_version: parameters.version,
         };
 
         debug!("Fee calculation completed: total = {}", breakdown.total_fee.total_value());
@@ -199,35 +304,73 @@ impl TokenEconomy {
         let gledger = gledger_guard.as_ref().unwrap();
         let balances = gledger.get_all_balances(payer).await?;
 
-        // Verify sufficient balances
-        self.verify_sufficient_balances(&balances, &fee_breakdown.total_fee)?;
+        // Quote paymaster reimbursement if gas is paid in another token
+        let paymaster_quote = match fee_breakdown.gas_payment_token {
+            Some(gas_token) if gas_token != TokenType::Gcc => {
+                let prices = self.get_token_pricing().await?;
+                Some(self.paymaster.quote(&fee_breakdown.total_fee.gcc_fee, gas_token, &prices)?)
+            }
+            _ => None,
+        };
+
+        // GHOST is NFT-like: only whole tokens can pay fees
+        let total_fee = MultiTokenFee {
+            ghost_fee: whole_ghost_fee(&fee_breakdown.total_fee.ghost_fee)?,
+            ..fee_breakdown.total_fee.clone()
+        };
+
+        let payer_fee = match &paymaster_quote {
+            Some(quote) => self.paymaster.payer_fee(&total_fee, quote),
+            None => total_fee.clone(),
+        };
+
+        // Verify sufficient balances before moving any funds
+        self.verify_sufficient_balances(&balances, &payer_fee)?;
+        if let Some(quote) = &paymaster_quote {
+            self.paymaster.verify_reimbursement(&balances, &payer_fee, quote)?;
+            let float = gledger.get_balance(self.paymaster.address(), TokenType::Gcc).await?;
+            self.paymaster.verify_float(&float, quote)?;
+        }
 
         // Process burns for deflationary tokens
-        let burn_amounts = self.calculate_burn_amounts(&fee_breakdown.total_fee).await?;
+        let burn_amounts = self.calculate_burn_amounts(&total_fee, fee_breakdown.parameters_version);
 
         // Deduct fees from payer
-        let mut payment_results = Vec::new();
-
-        if fee_breakdown.total_fee.gcc_fee.amount.to_u64() > 0 {
-            let transfer_result = gledger.transfer_tokens(
-                payer,
-                &Address([0u8; 20]), // Burn/fee address
-                &fee_breakdown.total_fee.gcc_fee,
-            ).await?;
-            payment_results.push(("GCC".to_string(), transfer_result));
+        let fee_address = Address([0u8; 20]); // Burn/fee address
+        let mut legs = Vec::new();
+
+        if let Some(quote) = &paymaster_quote {
+            // Payer reimburses in the chosen token before the paymaster fronts GCC
+            legs.push(FeeLeg { from: payer.clone(), to: self.paymaster.address().clone(), amount: quote.reimbursement.clone() });
+            legs.push(FeeLeg { from: self.paymaster.address().clone(), to: fee_address.clone(), amount: quote.gcc_fronted.clone() });
+        } else {
+            legs.push(FeeLeg { from: payer.clone(), to: fee_address.clone(), amount: total_fee.gcc_fee.clone() });
+        }
+
+        // SPIRIT, MANA, and GHOST legs are paid directly by the payer
+        for fee in [&total_fee.spirit_fee, &total_fee.mana_fee, &total_fee.ghost_fee] {
+            legs.push(FeeLeg { from: payer.clone(), to: fee_address.clone(), amount: fee.clone() });
         }
+        legs.retain(|leg| !leg.amount.amount.is_zero());
 
-        // Similar for other tokens...
+        // All legs or none: a failed leg reverses those already made
+        let payment_results = transfer_all(gledger, &legs).await?
+            .into_iter()
+            .map(|result| (result.amount.token_type.to_string(), result))
+            .collect();
 
         // Distribute fees to validators and funds
         self.fee_distributor.distribute_fees(&fee_breakdown.fee_distribution).await?;
+        self.metrics.record_payment(&total_fee, &fee_breakdown.fee_distribution, None);
 
         let result = PaymentResult {
             payer: payer.clone(),
-            total_paid: fee_breakdown.total_fee.clone(),
+            total_paid: total_fee,
             payment_breakdown: payment_results,
             burn_amounts,
             fee_distribution: fee_breakdown.fee_distribution.clone(),
+            paymaster_quote,
+            parameters_version: fee_breakdown.parameters_version,
             processed_at: chrono::Utc::now(),
         };
 
@@ -235,12 +378,22 @@ impl TokenEconomy {
         Ok(result)
     }
 
+    /// Fee distributions that failed and are waiting to be retried
+    pub fn pending_fee_distributions(&self) -> Vec<PendingDistribution> {
+        self.fee_distributor.pending_distributions()
+    }
+
     /// Get current token metrics
     pub async fn get_token_metrics(&self) -> Result<TokenMetrics> {
         let economics = &self.economics;
         economics.get_current_metrics().await
     }
 
+    /// Cumulative fees collected, burned, distributed, and refunded
+    pub fn economic_summary(&self) -> EconomicSummary {
+        self.metrics.summary()
+    }
+
     /// Get current token pricing
     pub async fn get_token_pricing(&self) -> Result<HashMap<TokenType, TokenPrice>> {
         // Check if cache is still valid
@@ -304,97 +457,30 @@ impl TokenEconomy {
 
     // Private helper methods
 
+    /// Refresh the cache from the oracle; while the oracle is unavailable the
+    /// last cached prices keep being served
     async fn update_pricing(&self) -> Result<()> {
         debug!("Updating token pricing information");
 
-        // Get pricing from GLEDGER service
-        let gledger_guard = self.services.gledger().await?;
-        let gledger = gledger_guard.as_ref().unwrap();
-        let pricing = gledger.get_token_pricing().await?;
+        let prices = match self.price_oracle.fetch_prices().await {
+            Ok(prices) => prices,
+            Err(e) => {
+                if self.pricing_cache.read().await.prices.is_empty() {
+                    return Err(e);
+                }
+                warn!("Price oracle unavailable, serving cached prices: {}", e);
+                return Ok(());
+            }
+        };
 
         let mut cache = self.pricing_cache.write().await;
-
-        // Update cache with latest prices
-        cache.prices.insert(TokenType::Gcc, TokenPrice {
-            token_type: TokenType::Gcc,
-            price_usd: pricing.gcc_price_usd,
-            market_cap_usd: pricing.gcc_price_usd * 10_000_000.0, // Assuming 10M circulating
-            volume_24h_usd: 1_000_000.0, // Mock volume
-            change_24h_percent: 2.5,
-            last_updated: chrono::Utc::now(),
-        });
-
-        cache.prices.insert(TokenType::Spirit, TokenPrice {
-            token_type: TokenType::Spirit,
-            price_usd: pricing.spirit_price_usd,
-            market_cap_usd: pricing.spirit_price_usd * 50_000_000.0, // Assuming 50M circulating
-            volume_24h_usd: 500_000.0,
-            change_24h_percent: 1.2,
-            last_updated: chrono::Utc::now(),
-        });
-
-        cache.prices.insert(TokenType::Mana, TokenPrice {
-            token_type: TokenType::Mana,
-            price_usd: pricing.mana_price_usd,
-            market_cap_usd: pricing.mana_price_usd * 75_000_000.0, // Assuming 75M circulating
-            volume_24h_usd: 250_000.0,
-            change_24h_percent: -0.8,
-            last_updated: chrono::Utc::now(),
-        });
-
-        cache.prices.insert(TokenType::Ghost, TokenPrice {
-            token_type: TokenType::Ghost,
-            price_usd: pricing.ghost_floor_price_usd,
-            market_cap_usd: pricing.ghost_floor_price_usd * 10_000.0, // 10K max supply
-            volume_24h_usd: 50_000.0,
-            change_24h_percent: 5.0,
-            last_updated: chrono::Utc::now(),
-        });
-
+        cache.prices.extend(prices);
         cache.last_updated = chrono::Utc::now();
 
         debug!("Token pricing updated successfully");
         Ok(())
     }
 
-    fn apply_priority_multiplier(&self, base_fees: &MultiTokenFee, multiplier: f64) -> Result<MultiTokenFee> {
-        let multiply_amount = |amount: &U256, multiplier: f64| -> U256 {
-            let value = amount.to_u64() as f64 * multiplier;
-            U256::from(value as u64)
-        };
-
-        Ok(MultiTokenFee {
-            gcc_fee: TokenAmount::new(
-                TokenType::Gcc,
-                multiply_amount(&base_fees.gcc_fee.amount, multiplier),
-            ),
-            spirit_fee: TokenAmount::new(
-                TokenType::Spirit,
-                multiply_amount(&base_fees.spirit_fee.amount, multiplier),
-            ),
-            mana_fee: TokenAmount::new(
-                TokenType::Mana,
-                multiply_amount(&base_fees.mana_fee.amount, multiplier),
-            ),
-            ghost_fee: TokenAmount::new(
-                TokenType::Ghost,
-                multiply_amount(&base_fees.ghost_fee.amount, multiplier),
-            ),
-        })
-    }
-
-    async fn calculate_cross_chain_fee(&self, base_fees: &MultiTokenFee) -> Result<TokenAmount> {
-        // Cross-chain fee is 10% of base GCC fee + 1 GHOST
-        let cross_chain_gcc = U256::from(base_fees.gcc_fee.amount.to_u64() / 10);
-        Ok(TokenAmount::new(TokenType::Gcc, cross_chain_gcc))
-    }
-
-    fn calculate_security_fee(&self, base_fees: &MultiTokenFee) -> Result<TokenAmount> {
-        // Security fee is 0.1% of base GCC fee
-        let security_fee = U256::from(base_fees.gcc_fee.amount.to_u64() / 1000);
-        Ok(TokenAmount::new(TokenType::Gcc, security_fee))
-    }
-
     fn add_fees(&self, fee1: &MultiTokenFee, fee2: &MultiTokenFee) -> Result<MultiTokenFee> {
         Ok(MultiTokenFee {
             gcc_fee: TokenAmount::new(
@@ -416,45 +502,123 @@ impl TokenEconomy {
         })
     }
 
-    async fn calculate_fee_distribution(&self, total_fee: &MultiTokenFee) -> Result<FeeDistributionBreakdown> {
-        // Use fee distributor to calculate distribution
-        self.fee_distributor.calculate_distribution(total_fee).await
-    }
-
     fn verify_sufficient_balances(
         &self,
         balances: &crate::services::gledger::MultiTokenBalance,
         required_fees: &MultiTokenFee,
     ) -> Result<()> {
-        if balances.gcc.amount.to_u64() < required_fees.gcc_fee.amount.to_u64() {
-            return Err(BridgeError::Token(TokenError::InsufficientBalance {
-                token: "GCC".to_string(),
-                required: required_fees.gcc_fee.to_human_readable(),
-                available: balances.gcc.to_human_readable(),
-            }));
+        let legs = [
+            (&balances.gcc, &required_fees.gcc_fee),
+            (&balances.spirit, &required_fees.spirit_fee),
+            (&balances.mana, &required_fees.mana_fee),
+            (&balances.ghost, &required_fees.ghost_fee),
+        ];
+
+        // Report every shortfall at once so the payer can top up in one go
+        let mut shortfalls: Vec<BalanceShortfall> = legs.into_iter()
+            // U256 is big-endian, so byte order is numeric order
+            .filter(|(balance, required)| balance.amount.0 < required.amount.0)
+            .map(|(balance, required)| BalanceShortfall {
+                token: required.token_type.to_string(),
+                required: required.to_human_readable(),
+                available: balance.to_human_readable(),
+            })
+            .collect();
+
+        match shortfalls.len() {
+            0 => Ok(()),
+            1 => {
+                let BalanceShortfall { token, required, available } = shortfalls.remove(0);
+                Err(BridgeError::Token(TokenError::InsufficientBalance { token, required, available }))
+            }
+            _ => Err(BridgeError::Token(TokenError::InsufficientBalances(BalanceShortfalls(shortfalls)))),
         }
+    }
 
-        // Check other token balances similarly...
+    fn calculate_burn_amounts(&self, fees: &MultiTokenFee, version: u32) -> MultiTokenFee {
+        // Burn with the rates the fees were quoted under
+        let parameters = self.parameters.get(version)
+            .unwrap_or_else(|| {
+                warn!("Unknown economic parameters version {}, burning at current rates", version);
+                self.parameters.current()
+            });
+        parameters.parameters.burn_amounts(fees)
+    }
+}
 
-        Ok(())
+/// One transfer making up a fee payment
+#[derive(Debug, Clone)]
+struct FeeLeg {
+    from: Address,
+    to: Address,
+    amount: TokenAmount,
+}
+
+/// Ledger fee payments are made on
+#[async_trait::async_trait]
+trait FeeLedger: Send + Sync {
+    async fn transfer(&self, from: &Address, to: &Address, amount: &TokenAmount) -> Result<TransferResult>;
+}
+
+#[async_trait::async_trait]
+impl FeeLedger for GledgerService {
+    async fn transfer(&self, from: &Address, to: &Address, amount: &TokenAmount) -> Result<TransferResult> {
+        self.transfer_tokens(from, to, amount).await
     }
+}
 
-    async fn calculate_burn_amounts(&self, fees: &MultiTokenFee) -> Result<MultiTokenFee> {
-        // Calculate burn amounts based on tokenomics
-        // GCC: 1% burn rate
-        // MANA: 0.5% burn rate
-        // Others: no burn
+/// Make every leg in order. If one fails, the legs already made are reversed,
+/// newest first, and the failure is returned; a reversal that itself fails is
+/// logged for manual reconciliation.
+async fn transfer_all(ledger: &dyn FeeLedger, legs: &[FeeLeg]) -> Result<Vec<TransferResult>> {
+    let mut made = Vec::with_capacity(legs.len());
+    for leg in legs {
+        let outcome = match ledger.transfer(&leg.from, &leg.to, &leg.amount).await {
+            Ok(result) if result.success => Ok(result),
+            Ok(result) => Err(BridgeError::Token(TokenError::TransferFailed(format!(
+                "GLEDGER transfer {} of {} {} failed", result.transaction_hash, leg.amount.amount, leg.amount.token_type
+            )))),
+            Err(e) => Err(e),
+        };
+        match outcome {
+            Ok(result) => made.push(result),
+            Err(e) => {
+                warn!("Fee leg of {} {} from {} failed, reversing {} completed legs: {}",
+                      leg.amount.amount, leg.amount.token_type, leg.from, made.len(), e);
+                for (done, _) in legs.iter().zip(&made).rev() {
+                    if let Err(reversal) = ledger.transfer(&done.to, &done.from, &done.amount).await {
+                        error!("Could not reverse fee leg of {} {} from {} to {}: {}",
+                               done.amount.amount, done.amount.token_type, done.from, done.to, reversal);
+                    }
+                }
+                return Err(e);
+            }
+        }
+    }
+    Ok(made)
+}
 
-        let gcc_burn = U256::from(fees.gcc_fee.amount.to_u64() / 100); // 1%
-        let mana_burn = U256::from(fees.mana_fee.amount.to_u64() / 200); // 0.5%
+/// A GHOST fee in whole tokens; amounts carrying decimals must be a whole number of tokens
+fn whole_ghost_fee(fee: &TokenAmount) -> Result<TokenAmount> {
+    // 10^77 is the largest power of ten a U256 holds
+    if fee.decimals > 77 {
+        return Err(BridgeError::Token(TokenError::InvalidAmount {
+            amount: format!("GHOST amount with {} decimals", fee.decimals),
+        }));
+    }
 
-        Ok(MultiTokenFee {
-            gcc_fee: TokenAmount::new(TokenType::Gcc, gcc_burn),
-            spirit_fee: TokenAmount::new(TokenType::Spirit, U256::ZERO),
-            mana_fee: TokenAmount::new(TokenType::Mana, mana_burn),
-            ghost_fee: TokenAmount::new(TokenType::Ghost, U256::ZERO),
-        })
+    let mut whole = fee.amount.clone();
+    for _ in 0..fee.decimals {
+        let (quotient, remainder) = whole.div_rem_u64(10);
+        if remainder != 0 {
+            return Err(BridgeError::Token(TokenError::FractionalAmount {
+                token: TokenType::Ghost.to_string(),
+                amount: fee.to_human_readable(),
+            }));
+        }
+        whole = quotient;
     }
+    Ok(TokenAmount::new(TokenType::Ghost, whole))
 }
 
 /// Payment processing result
@@ -462,9 +626,12 @@ impl TokenEconomy {
 pub struct PaymentResult {
     pub payer: Address,
     pub total_paid: MultiTokenFee,
-    pub payment_breakdown: Vec<(String, crate::services::gledger::TransferResult)>,
+    pub payment_breakdown: Vec<(String, TransferResult)>,
     pub burn_amounts: MultiTokenFee,
     pub fee_distribution: FeeDistributionBreakdown,
+    pub paymaster_quote: Option<PaymasterQuote>,
+    /// Economic parameters version in effect for this payment
+    pub parameters_version: u32,
     pub processed_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -498,6 +665,32 @@ mod tests {
         assert_eq!(price.price_usd, 0.50);
     }
 
+    /// Oracle quoting every token at a fixed price until switched off
+    #[derive(Default)]
+    struct MockOracle {
+        unavailable: std::sync::atomic::AtomicBool,
+    }
+
+    #[async_trait::async_trait]
+    impl PriceOracle for MockOracle {
+        async fn fetch_prices(&self) -> Result<HashMap<TokenType, TokenPrice>> {
+            if self.unavailable.load(std::sync::atomic::Ordering::SeqCst) {
+                return Err(BridgeError::Token(TokenError::PricingUnavailable { token: "GCC".to_string() }));
+            }
+            Ok([(TokenType::Gcc, 0.5), (TokenType::Spirit, 0.1), (TokenType::Mana, 0.05), (TokenType::Ghost, 250.0)]
+                .into_iter()
+                .map(|(token_type, price_usd)| (token_type, TokenPrice {
+                    token_type,
+                    price_usd,
+                    market_cap_usd: price_usd * 1_000_000.0,
+                    volume_24h_usd: 20_000.0,
+                    change_24h_percent: 0.0,
+                    last_updated: chrono::Utc::now(),
+                }))
+                .collect())
+        }
+    }
+
     #[test]
     fn test_fee_breakdown() {
         let base_fee = TokenAmount::new(TokenType::Gcc, U256::from(1000));
@@ -546,9 +739,182 @@ mod tests {
                     ghost_fee: TokenAmount::new(TokenType::Ghost, U256::ZERO),
                 },
             },
+            gas_payment_token: None,
+            parameters_version: 1,
         };
 
         assert_eq!(breakdown.base_fee.amount.to_u64(), 1000);
         assert_eq!(breakdown.priority_fee.amount.to_u64(), 1200);
     }
-}
\ No newline at end of file
+
+    fn all_token_fee(gcc: u64, spirit: u64, mana: u64, ghost: TokenAmount) -> FeeBreakdown {
+        let zero = || MultiTokenFee {
+            gcc_fee: TokenAmount::new(TokenType::Gcc, U256::ZERO),
+            spirit_fee: TokenAmount::new(TokenType::Spirit, U256::ZERO),
+            mana_fee: TokenAmount::new(TokenType::Mana, U256::ZERO),
+            ghost_fee: TokenAmount::new(TokenType::Ghost, U256::ZERO),
+        };
+        FeeBreakdown {
+            base_fee: TokenAmount::new(TokenType::Gcc, U256::from(gcc)),
+            priority_fee: TokenAmount::new(TokenType::Gcc, U256::ZERO),
+            cross_chain_fee: None,
+            bridge_security_fee: TokenAmount::new(TokenType::Gcc, U256::ZERO),
+            total_fee: MultiTokenFee {
+                gcc_fee: TokenAmount::new(TokenType::Gcc, U256::from(gcc)),
+                spirit_fee: TokenAmount::new(TokenType::Spirit, U256::from(spirit)),
+                mana_fee: TokenAmount::new(TokenType::Mana, U256::from(mana)),
+                ghost_fee: ghost,
+            },
+            fee_distribution: FeeDistributionBreakdown {
+                l2_validators: zero(),
+                l1_validators: zero(),
+                security_fund: zero(),
+                protocol_development: zero(),
+                burn_amount: zero(),
+            },
+            gas_payment_token: None,
+            parameters_version: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_payment_collects_every_token() {
+        let services = Arc::new(ServiceManager::new(crate::services::ServiceConfig::default()));
+        services.init_gledger().await.unwrap();
+        let economy = TokenEconomy::new(services, Arc::new(MockOracle::default())).await.unwrap();
+        let payer = Address([7u8; 20]);
+
+        let fee = all_token_fee(1_000, 200, 300, TokenAmount::new(TokenType::Ghost, U256::from(1)));
+        let result = economy.process_payment(&payer, &fee).await.unwrap();
+        let tokens: Vec<&str> = result.payment_breakdown.iter().map(|(token, _)| token.as_str()).collect();
+        assert_eq!(tokens, vec!["GCC", "SPIRIT", "MANA", "GHOST"]);
+        assert_eq!(result.payment_breakdown[3].1.amount.amount.to_u64(), 1);
+
+        // Half a GHOST cannot be paid
+        let mut half = TokenAmount::new(TokenType::Ghost, U256::from(5));
+        half.decimals = 1;
+        let error = economy.process_payment(&payer, &all_token_fee(1_000, 0, 0, half)).await.unwrap_err();
+        assert!(matches!(error, BridgeError::Token(TokenError::FractionalAmount { .. })), "{}", error);
+    }
+
+    #[test]
+    fn test_whole_ghost_fee_at_wide_decimals() {
+        // 3 GHOST at 30 decimals, past what fits in a u64
+        let mut fee = TokenAmount::new(TokenType::Ghost, U256::from(3_000_000_000_000_000).checked_mul_u64(1_000_000_000_000_000).unwrap());
+        fee.decimals = 30;
+        assert_eq!(whole_ghost_fee(&fee).unwrap().amount, U256::from(3));
+
+        fee.amount = fee.amount.checked_add(&U256::ONE).unwrap();
+        assert!(matches!(whole_ghost_fee(&fee), Err(BridgeError::Token(TokenError::FractionalAmount { .. }))));
+
+        fee.decimals = 78;
+        assert!(matches!(whole_ghost_fee(&fee), Err(BridgeError::Token(TokenError::InvalidAmount { .. }))));
+    }
+
+    #[tokio::test]
+    async fn test_all_balance_shortfalls_reported() {
+        let services = Arc::new(ServiceManager::new(crate::services::ServiceConfig::default()));
+        services.init_gledger().await.unwrap();
+        let economy = TokenEconomy::new(services, Arc::new(MockOracle::default())).await.unwrap();
+        let balance = |token_type, amount: u64| TokenAmount::new(token_type, U256::from(amount));
+        let balances = crate::services::gledger::MultiTokenBalance {
+            address: Address([7u8; 20]),
+            gcc: balance(TokenType::Gcc, 1_000),
+            spirit: balance(TokenType::Spirit, 0),
+            mana: balance(TokenType::Mana, 10),
+            ghost: balance(TokenType::Ghost, 0),
+            last_updated: chrono::Utc::now(),
+        };
+        let fee = |gcc, spirit, mana, ghost| MultiTokenFee {
+            gcc_fee: balance(TokenType::Gcc, gcc),
+            spirit_fee: balance(TokenType::Spirit, spirit),
+            mana_fee: balance(TokenType::Mana, mana),
+            ghost_fee: balance(TokenType::Ghost, ghost),
+        };
+
+        assert!(economy.verify_sufficient_balances(&balances, &fee(1_000, 0, 10, 0)).is_ok());
+
+        // Enough GCC but no MANA is caught before any transfer
+        let error = economy.verify_sufficient_balances(&balances, &fee(500, 0, 20, 0)).unwrap_err();
+        assert!(matches!(&error, BridgeError::Token(TokenError::InsufficientBalance { token, .. }) if token == "MANA"));
+
+        let error = economy.verify_sufficient_balances(&balances, &fee(500, 1, 20, 1)).unwrap_err();
+        let BridgeError::Token(TokenError::InsufficientBalances(shortfalls)) = error else {
+            panic!("expected every shortfall, got {}", error);
+        };
+        let tokens: Vec<&str> = shortfalls.0.iter().map(|s| s.token.as_str()).collect();
+        assert_eq!(tokens, vec!["SPIRIT", "MANA", "GHOST"]);
+    }
+
+    #[tokio::test]
+    async fn test_stale_prices_served_when_oracle_fails() {
+        let services = Arc::new(ServiceManager::new(crate::services::ServiceConfig::default()));
+        services.init_gledger().await.unwrap();
+        let oracle = Arc::new(MockOracle::default());
+        let economy = TokenEconomy::new(services, oracle.clone()).await.unwrap();
+
+        let prices = economy.get_token_pricing().await.unwrap();
+        assert_eq!(prices[&TokenType::Ghost].price_usd, 250.0);
+        assert_eq!(prices[&TokenType::Mana].volume_24h_usd, 20_000.0);
+
+        // Expire the cache and take the oracle down: the last prices are kept
+        oracle.unavailable.store(true, std::sync::atomic::Ordering::SeqCst);
+        economy.pricing_cache.write().await.last_updated = chrono::Utc::now() - chrono::Duration::hours(1);
+        let stale = economy.get_token_pricing().await.unwrap();
+        assert_eq!(stale.len(), 4);
+        assert_eq!(stale[&TokenType::Gcc].price_usd, 0.5);
+
+        // Without anything cached there is nothing to fall back on
+        economy.pricing_cache.write().await.prices.clear();
+        assert!(economy.get_token_pricing().await.is_err());
+    }
+
+    /// Ledger that records transfers and fails the `fail_at`th one
+    struct MockLedger {
+        fail_at: usize,
+        transfers: parking_lot::Mutex<Vec<(Address, Address, U256)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl FeeLedger for MockLedger {
+        async fn transfer(&self, from: &Address, to: &Address, amount: &TokenAmount) -> Result<TransferResult> {
+            let mut transfers = self.transfers.lock();
+            if transfers.len() == self.fail_at {
+                transfers.push((from.clone(), to.clone(), U256::ZERO));
+                return Err(BridgeError::Token(TokenError::TransferFailed("ledger unavailable".to_string())));
+            }
+            transfers.push((from.clone(), to.clone(), amount.amount.clone()));
+            Ok(TransferResult {
+                transaction_hash: format!("0x{:02x}", transfers.len()),
+                from: from.clone(),
+                to: to.clone(),
+                amount: amount.clone(),
+                fee: TokenAmount::new(amount.token_type, U256::ZERO),
+                success: true,
+                block_number: 1,
+                timestamp: chrono::Utc::now(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_fee_leg_reverses_completed_legs() {
+        let (payer, paymaster, collector) = (Address([1u8; 20]), Address([2u8; 20]), Address([0u8; 20]));
+        let legs = vec![
+            FeeLeg { from: payer.clone(), to: paymaster.clone(), amount: TokenAmount::new(TokenType::Mana, U256::from(202)) },
+            FeeLeg { from: paymaster.clone(), to: collector.clone(), amount: TokenAmount::new(TokenType::Gcc, U256::from(100)) },
+            FeeLeg { from: payer.clone(), to: collector.clone(), amount: TokenAmount::new(TokenType::Spirit, U256::from(7)) },
+        ];
+
+        let ledger = MockLedger { fail_at: usize::MAX, transfers: Default::default() };
+        assert_eq!(transfer_all(&ledger, &legs).await.unwrap().len(), 3);
+
+        // The third leg fails: the first two are undone, newest first
+        let ledger = MockLedger { fail_at: 2, transfers: Default::default() };
+        assert!(transfer_all(&ledger, &legs).await.is_err());
+        assert_eq!(ledger.transfers.lock()[3..], [
+            (collector, paymaster.clone(), U256::from(100)),
+            (paymaster, payer, U256::from(202)),
+        ]);
+    }
+}
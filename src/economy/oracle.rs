@@ -0,0 +1,155 @@
+/*!
+Token price oracles
+
+`TokenEconomy` prices the four tokens through a `PriceOracle`, caching the
+result for a few minutes. `HttpPriceOracle` reads prices, market caps, and
+volumes from an HTTPS endpoint returning JSON keyed by token symbol:
+
+```json
+{ "GCC": { "price_usd": 0.5, "market_cap_usd": 5000000.0, "volume_24h_usd": 1000000.0, "change_24h_percent": 2.5 }, ... }
+```
+
+Quotes with a non-positive price or negative market data are rejected rather
+than cached, since a zero price would make every transfer look like dust.
+*/
+
+use crate::economy::TokenPrice;
+use crate::error::{BridgeError, NetworkError, Result, TokenError};
+use crate::types::TokenType;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Largest oracle response accepted; a full quote set is well under 1 KiB
+const MAX_ORACLE_RESPONSE_BYTES: usize = 64 * 1024;
+
+/// Source of market data for GCC, SPIRIT, MANA, and GHOST
+#[async_trait]
+pub trait PriceOracle: Send + Sync {
+    /// Current price of every token
+    async fn fetch_prices(&self) -> Result<HashMap<TokenType, TokenPrice>>;
+}
+
+/// One token's market data as served by the oracle endpoint
+#[derive(Debug, Clone, Deserialize)]
+struct OracleQuote {
+    price_usd: f64,
+    market_cap_usd: f64,
+    volume_24h_usd: f64,
+    #[serde(default)]
+    change_24h_percent: f64,
+}
+
+/// HTTPS oracle client
+pub struct HttpPriceOracle {
+    url: String,
+    timeout: Duration,
+    http: reqwest::Client,
+}
+
+impl HttpPriceOracle {
+    /// Oracle at an `https://` URL; prices are never fetched in the clear
+    pub fn new(url: impl Into<String>, timeout: Duration) -> Result<Self> {
+        let url = url.into();
+        if !url.starts_with("https://") {
+            return Err(BridgeError::config(format!("Price oracle {} must use https://", url)));
+        }
+        let http = reqwest::Client::builder()
+            .https_only(true)
+            .timeout(timeout)
+            .build()
+            .map_err(|e| BridgeError::config(format!("Price oracle {}: {}", url, e)))?;
+        Ok(Self { url, timeout, http })
+    }
+
+    async fn get(&self) -> Result<Vec<u8>> {
+        let url = &self.url;
+        let response = self.http.get(url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send().await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| oracle_error(url, &e.to_string()))?;
+        crate::transport::read_bounded_body(response, url, MAX_ORACLE_RESPONSE_BYTES).await
+    }
+}
+
+#[async_trait]
+impl PriceOracle for HttpPriceOracle {
+    async fn fetch_prices(&self) -> Result<HashMap<TokenType, TokenPrice>> {
+        let body = tokio::time::timeout(self.timeout, self.get()).await
+            .map_err(|_| BridgeError::Network(NetworkError::Timeout { duration_ms: self.timeout.as_millis() as u64 }))??;
+        let quotes: HashMap<String, OracleQuote> = serde_json::from_slice(&body)
+            .map_err(|e| BridgeError::Serialization(e.into()))?;
+        parse_quotes(quotes)
+    }
+}
+
+fn parse_quotes(mut quotes: HashMap<String, OracleQuote>) -> Result<HashMap<TokenType, TokenPrice>> {
+    let now = chrono::Utc::now();
+    [TokenType::Gcc, TokenType::Spirit, TokenType::Mana, TokenType::Ghost]
+        .into_iter()
+        .map(|token_type| {
+            let unavailable = || BridgeError::Token(TokenError::PricingUnavailable { token: token_type.to_string() });
+            let quote = quotes.remove(&token_type.to_string()).ok_or_else(unavailable)?;
+            let valid = quote.price_usd.is_finite() && quote.price_usd > 0.0
+                && [quote.market_cap_usd, quote.volume_24h_usd].iter().all(|value| value.is_finite() && *value >= 0.0)
+                && quote.change_24h_percent.is_finite();
+            if !valid {
+                return Err(unavailable());
+            }
+            Ok((token_type, TokenPrice {
+                token_type,
+                price_usd: quote.price_usd,
+                market_cap_usd: quote.market_cap_usd,
+                volume_24h_usd: quote.volume_24h_usd,
+                change_24h_percent: quote.change_24h_percent,
+                last_updated: now,
+            }))
+        })
+        .collect()
+}
+
+fn oracle_error(url: &str, reason: &str) -> BridgeError {
+    BridgeError::Network(NetworkError::ConnectionFailed {
+        endpoint: url.to_string(),
+        source: reason.to_string().into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quotes(price_usd: f64) -> HashMap<String, OracleQuote> {
+        ["GCC", "SPIRIT", "MANA", "GHOST"].into_iter()
+            .map(|symbol| (symbol.to_string(), OracleQuote {
+                price_usd,
+                market_cap_usd: 5_000_000.0,
+                volume_24h_usd: 1_000_000.0,
+                change_24h_percent: 2.5,
+            }))
+            .collect()
+    }
+
+    #[test]
+    fn test_non_positive_prices_are_rejected() {
+        assert_eq!(parse_quotes(quotes(0.5)).unwrap()[&TokenType::Gcc].price_usd, 0.5);
+        for price in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                parse_quotes(quotes(price)),
+                Err(BridgeError::Token(TokenError::PricingUnavailable { .. }))
+            ));
+        }
+
+        let mut negative_volume = quotes(0.5);
+        negative_volume.get_mut("MANA").unwrap().volume_24h_usd = -1.0;
+        assert!(parse_quotes(negative_volume).is_err());
+    }
+
+    #[test]
+    fn test_oracle_requires_https() {
+        assert!(HttpPriceOracle::new("http://oracle.example/prices", Duration::from_secs(5)).is_err());
+        assert!(HttpPriceOracle::new("https://oracle.example/prices", Duration::from_secs(5)).is_ok());
+    }
+}
@@ -0,0 +1,184 @@
+/*!
+Versioned economic parameters
+
+Fees and burns depend on a handful of tunable parameters: the fee market
+strategy, burn rates, how fees are split between validators and funds, and
+the cross-chain and security surcharges. Every
+change to that set is recorded in an `EconomicParameterRegistry` under a new
+version number, and fee breakdowns and payment results carry the version
+they were computed with, so an auditor can reproduce any historical fee from
+the exact parameters that were in effect.
+*/
+
+use crate::economy::distribution::{BurnRates, DistributionConfig, DEFAULT_BURN_RATES};
+use crate::economy::fee_market::FeeMarketConfig;
+use crate::types::{MultiTokenFee, TokenAmount, TokenType, U256};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+use tracing::info;
+
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// The full set of parameters that determine fees and burns
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EconomicParameters {
+    pub fee_market: FeeMarketConfig,
+    /// Burn rates in basis points, indexed GCC, SPIRIT, MANA, GHOST
+    pub burn_rates_bps: BurnRates,
+    /// Split of the fees left after burning between validators and funds
+    #[serde(default)]
+    pub distribution: DistributionConfig,
+    /// Cross-chain surcharge as basis points of the base GCC fee
+    pub cross_chain_fee_bps: u64,
+    /// Bridge security surcharge as basis points of the base GCC fee
+    pub security_fee_bps: u64,
+}
+
+impl Default for EconomicParameters {
+    fn default() -> Self {
+        Self {
+            fee_market: FeeMarketConfig::default(),
+            burn_rates_bps: DEFAULT_BURN_RATES,
+            distribution: DistributionConfig::default(),
+            cross_chain_fee_bps: 1_000, // 10%
+            security_fee_bps: 10,       // 0.1%
+        }
+    }
+}
+
+impl EconomicParameters {
+    pub fn cross_chain_fee(&self, base_fees: &MultiTokenFee) -> TokenAmount {
+        TokenAmount::new(TokenType::Gcc, U256::from(bps_of(base_fees.gcc_fee.amount.to_u64(), self.cross_chain_fee_bps)))
+    }
+
+    pub fn security_fee(&self, base_fees: &MultiTokenFee) -> TokenAmount {
+        TokenAmount::new(TokenType::Gcc, U256::from(bps_of(base_fees.gcc_fee.amount.to_u64(), self.security_fee_bps)))
+    }
+
+    /// Amount of each token burned from a paid fee
+    pub fn burn_amounts(&self, fees: &MultiTokenFee) -> MultiTokenFee {
+        let [gcc, spirit, mana, ghost] = self.burn_rates_bps;
+        let burn = |amount: &TokenAmount, rate: u64| {
+            TokenAmount::new(amount.token_type, U256::from(bps_of(amount.amount.to_u64(), rate)))
+        };
+
+        MultiTokenFee {
+            gcc_fee: burn(&fees.gcc_fee, gcc),
+            spirit_fee: burn(&fees.spirit_fee, spirit),
+            mana_fee: burn(&fees.mana_fee, mana),
+            ghost_fee: burn(&fees.ghost_fee, ghost),
+        }
+    }
+}
+
+fn bps_of(amount: u64, bps: u64) -> u64 {
+    (amount as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+/// One recorded parameter set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParameterVersion {
+    pub version: u32,
+    pub parameters: EconomicParameters,
+    pub activated_at: SystemTime,
+}
+
+/// Append-only history of economic parameter sets
+#[derive(Debug)]
+pub struct EconomicParameterRegistry {
+    versions: RwLock<Vec<ParameterVersion>>,
+}
+
+impl EconomicParameterRegistry {
+    /// Start the history with `initial` as version 1
+    pub fn new(initial: EconomicParameters) -> Self {
+        Self {
+            versions: RwLock::new(vec![ParameterVersion {
+                version: 1,
+                parameters: initial,
+                activated_at: SystemTime::now(),
+            }]),
+        }
+    }
+
+    /// Parameters currently in effect
+    pub fn current(&self) -> ParameterVersion {
+        self.versions.read().last().cloned().expect("registry always holds a version")
+    }
+
+    pub fn current_version(&self) -> u32 {
+        self.versions.read().last().map_or(0, |v| v.version)
+    }
+
+    /// Activate a new parameter set, returning the version in effect afterwards;
+    /// identical parameters keep the current version
+    pub fn update(&self, parameters: EconomicParameters) -> u32 {
+        let mut versions = self.versions.write();
+        let current = versions.last().expect("registry always holds a version");
+        if current.parameters == parameters {
+            return current.version;
+        }
+
+        let version = current.version + 1;
+        info!("Activating economic parameters version {}", version);
+        versions.push(ParameterVersion { version, parameters, activated_at: SystemTime::now() });
+        version
+    }
+
+    /// Parameters recorded under `version`
+    pub fn get(&self, version: u32) -> Option<ParameterVersion> {
+        self.versions.read().iter().find(|v| v.version == version).cloned()
+    }
+
+    /// All versions, oldest first
+    pub fn history(&self) -> Vec<ParameterVersion> {
+        self.versions.read().clone()
+    }
+}
+
+impl Default for EconomicParameterRegistry {
+    fn default() -> Self {
+        Self::new(EconomicParameters::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fee(gcc: u64, mana: u64) -> MultiTokenFee {
+        MultiTokenFee {
+            gcc_fee: TokenAmount::new(TokenType::Gcc, U256::from(gcc)),
+            spirit_fee: TokenAmount::new(TokenType::Spirit, U256::ZERO),
+            mana_fee: TokenAmount::new(TokenType::Mana, U256::from(mana)),
+            ghost_fee: TokenAmount::new(TokenType::Ghost, U256::ZERO),
+        }
+    }
+
+    #[test]
+    fn test_parameter_changes_bump_version_and_history_is_kept() {
+        let registry = EconomicParameterRegistry::default();
+        assert_eq!(registry.current_version(), 1);
+
+        // Re-applying the same parameters is not a new version
+        assert_eq!(registry.update(EconomicParameters::default()), 1);
+
+        let raised = EconomicParameters { security_fee_bps: 25, ..EconomicParameters::default() };
+        assert_eq!(registry.update(raised.clone()), 2);
+        assert_eq!(registry.current().parameters, raised);
+        assert_eq!(registry.history().len(), 2);
+
+        // A fee quoted under version 1 is reproducible from the registry
+        let base = fee(100_000, 0);
+        let v1 = registry.get(1).unwrap().parameters;
+        assert_eq!(v1.security_fee(&base).amount.to_u64(), 100);
+        assert_eq!(v1.cross_chain_fee(&base).amount.to_u64(), 10_000);
+        assert_eq!(registry.get(2).unwrap().parameters.security_fee(&base).amount.to_u64(), 250);
+
+        let burns = v1.burn_amounts(&fee(10_000, 10_000));
+        assert_eq!(burns.gcc_fee.amount.to_u64(), 100);
+        assert_eq!(burns.mana_fee.amount.to_u64(), 50);
+        assert!(registry.get(3).is_none());
+    }
+}
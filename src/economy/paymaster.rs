@@ -0,0 +1,321 @@
+/*!
+Paymaster gas abstraction for the 4-token economy
+
+Lets users pay gas in a token of their choice. The paymaster fronts the GCC
+gas fee to the protocol and is reimbursed in the chosen token at the oracle
+rate plus a spread. Oracle prices are taken to the nano-dollar and the
+reimbursement is computed from there in exact integer arithmetic, rounded
+in the paymaster's favour.
+*/
+
+use crate::error::{BridgeError, Result, TokenError};
+use crate::types::{TokenType, TokenAmount, U256, MultiTokenFee, Address};
+use crate::services::gledger::MultiTokenBalance;
+use crate::economy::TokenPrice;
+use std::collections::HashMap;
+use tracing::debug;
+
+/// Paymaster configuration
+#[derive(Debug, Clone)]
+pub struct PaymasterConfig {
+    /// Address that fronts GCC and receives reimbursements
+    pub address: Address,
+    /// Spread charged on top of the oracle rate, in basis points
+    pub spread_bps: u16,
+    /// Tokens accepted as gas payment
+    pub accepted_tokens: Vec<TokenType>,
+}
+
+impl Default for PaymasterConfig {
+    fn default() -> Self {
+        Self {
+            address: Address([0xfa; 20]),
+            spread_bps: 100, // 1% spread
+            accepted_tokens: vec![TokenType::Spirit, TokenType::Mana, TokenType::Ghost],
+        }
+    }
+}
+
+/// Quote for fronting a GCC gas fee in exchange for another token
+#[derive(Debug, Clone)]
+pub struct PaymasterQuote {
+    pub gas_token: TokenType,
+    /// GCC fronted to the protocol by the paymaster
+    pub gcc_fronted: TokenAmount,
+    /// Amount the payer reimburses in the gas token
+    pub reimbursement: TokenAmount,
+    /// Gas-token units per GCC unit, including the spread; informational,
+    /// the reimbursement is not derived from it
+    pub rate: f64,
+}
+
+/// Paymaster fronting GCC gas on behalf of payers
+pub struct Paymaster {
+    config: PaymasterConfig,
+}
+
+impl Paymaster {
+    pub fn new(config: PaymasterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Paymaster address
+    pub fn address(&self) -> &Address {
+        &self.config.address
+    }
+
+    /// Quote the reimbursement for fronting `gcc_fee` in `gas_token`
+    pub fn quote(
+        &self,
+        gcc_fee: &TokenAmount,
+        gas_token: TokenType,
+        prices: &HashMap<TokenType, TokenPrice>,
+    ) -> Result<PaymasterQuote> {
+        if !self.config.accepted_tokens.contains(&gas_token) {
+            return Err(BridgeError::Token(TokenError::InvalidTokenType {
+                token: gas_token.to_string(),
+            }));
+        }
+
+        let price = |token_type: TokenType| {
+            prices
+                .get(&token_type)
+                .and_then(|p| price_nanos(p.price_usd))
+                .ok_or_else(|| BridgeError::Token(TokenError::PricingUnavailable {
+                    token: token_type.to_string(),
+                }))
+        };
+
+        let gcc_price = price(TokenType::Gcc)?;
+        let token_price = price(gas_token)?;
+        let token_decimals = TokenAmount::new(gas_token, U256::ZERO).decimals;
+        let spread_factor = 10_000 + self.config.spread_bps as u64;
+
+        // amount * gcc_price * spread * 10^token_decimals
+        //     / (token_price * 10_000 * 10^gcc_decimals),
+        // multiplying first and rounding each division up so the paymaster
+        // is never under-reimbursed
+        let overflow = || BridgeError::Token(TokenError::InvalidAmount {
+            amount: format!("{} GCC is too large to quote", gcc_fee.amount),
+        });
+        let mut value = gcc_fee.amount.checked_mul_u64(gcc_price)
+            .and_then(|value| value.checked_mul_u64(spread_factor))
+            .ok_or_else(overflow)?;
+        for _ in gcc_fee.decimals..token_decimals {
+            value = value.checked_mul_u64(10).ok_or_else(overflow)?;
+        }
+        let mut divisors = vec![token_price, 10_000];
+        divisors.extend((token_decimals..gcc_fee.decimals).map(|_| 10));
+        for divisor in divisors {
+            value = div_ceil(&value, divisor).ok_or_else(overflow)?;
+        }
+        let reimbursement = value;
+
+        let rate = gcc_price as f64 / token_price as f64
+            * 10f64.powi(token_decimals as i32 - gcc_fee.decimals as i32)
+            * spread_factor as f64 / 10_000.0;
+
+        debug!(
+            "Paymaster quote: {} GCC -> {} {} (rate {})",
+            gcc_fee.amount, reimbursement, gas_token, rate
+        );
+
+        Ok(PaymasterQuote {
+            gas_token,
+            gcc_fronted: gcc_fee.clone(),
+            reimbursement: TokenAmount::new(gas_token, reimbursement),
+            rate,
+        })
+    }
+
+    /// Fees the payer owes once the paymaster covers the GCC portion
+    pub fn payer_fee(&self, total_fee: &MultiTokenFee, quote: &PaymasterQuote) -> MultiTokenFee {
+        let mut fee = total_fee.clone();
+        fee.gcc_fee = TokenAmount::new(TokenType::Gcc, U256::ZERO);
+
+        let token_fee = match quote.gas_token {
+            TokenType::Gcc => &mut fee.gcc_fee,
+            TokenType::Spirit => &mut fee.spirit_fee,
+            TokenType::Mana => &mut fee.mana_fee,
+            TokenType::Ghost => &mut fee.ghost_fee,
+        };
+        *token_fee = TokenAmount::new(
+            quote.gas_token,
+            &token_fee.amount + &quote.reimbursement.amount,
+        );
+
+        fee
+    }
+
+    /// Reject the quote if the payer cannot cover the reimbursement
+    pub fn verify_reimbursement(
+        &self,
+        balances: &MultiTokenBalance,
+        payer_fee: &MultiTokenFee,
+        quote: &PaymasterQuote,
+    ) -> Result<()> {
+        let (balance, required) = match quote.gas_token {
+            TokenType::Gcc => (&balances.gcc, &payer_fee.gcc_fee),
+            TokenType::Spirit => (&balances.spirit, &payer_fee.spirit_fee),
+            TokenType::Mana => (&balances.mana, &payer_fee.mana_fee),
+            TokenType::Ghost => (&balances.ghost, &payer_fee.ghost_fee),
+        };
+
+        if balance.amount < required.amount {
+            return Err(BridgeError::Token(TokenError::InsufficientBalance {
+                token: quote.gas_token.to_string(),
+                required: required.to_human_readable(),
+                available: balance.to_human_readable(),
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Reject the quote if the paymaster's own GCC `balance` cannot front it
+    pub fn verify_float(&self, balance: &TokenAmount, quote: &PaymasterQuote) -> Result<()> {
+        if balance.amount < quote.gcc_fronted.amount {
+            return Err(BridgeError::Token(TokenError::InsufficientBalance {
+                token: format!("paymaster {}", TokenType::Gcc),
+                required: quote.gcc_fronted.to_human_readable(),
+                available: balance.to_human_readable(),
+            }));
+        }
+        Ok(())
+    }
+}
+
+/// Price as a whole number of nano-dollars, if it is a usable price
+fn price_nanos(price_usd: f64) -> Option<u64> {
+    let nanos = (price_usd * 1e9).round();
+    (nanos.is_finite() && nanos >= 1.0 && nanos < u64::MAX as f64).then_some(nanos as u64)
+}
+
+/// `value / divisor`, rounded up
+fn div_ceil(value: &U256, divisor: u64) -> Option<U256> {
+    match value.div_rem_u64(divisor) {
+        (quotient, 0) => Some(quotient),
+        (quotient, _) => quotient.checked_add(&U256::ONE),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prices() -> HashMap<TokenType, TokenPrice> {
+        [(TokenType::Gcc, 0.50), (TokenType::Mana, 0.25), (TokenType::Spirit, 1.25)]
+            .into_iter()
+            .map(|(token_type, price_usd)| {
+                (token_type, TokenPrice {
+                    token_type,
+                    price_usd,
+                    market_cap_usd: 0.0,
+                    volume_24h_usd: 0.0,
+                    change_24h_percent: 0.0,
+                    last_updated: chrono::Utc::now(),
+                })
+            })
+            .collect()
+    }
+
+    fn balances(mana: u64) -> MultiTokenBalance {
+        MultiTokenBalance {
+            address: Address([1u8; 20]),
+            gcc: TokenAmount::new(TokenType::Gcc, U256::ZERO),
+            spirit: TokenAmount::new(TokenType::Spirit, U256::ZERO),
+            mana: TokenAmount::new(TokenType::Mana, U256::from(mana)),
+            ghost: TokenAmount::new(TokenType::Ghost, U256::ZERO),
+            last_updated: chrono::Utc::now(),
+        }
+    }
+
+    fn gcc_only_fee(gcc: u64) -> MultiTokenFee {
+        MultiTokenFee {
+            gcc_fee: TokenAmount::new(TokenType::Gcc, U256::from(gcc)),
+            spirit_fee: TokenAmount::new(TokenType::Spirit, U256::ZERO),
+            mana_fee: TokenAmount::new(TokenType::Mana, U256::ZERO),
+            ghost_fee: TokenAmount::new(TokenType::Ghost, U256::ZERO),
+        }
+    }
+
+    #[test]
+    fn test_mana_paid_transaction() {
+        let paymaster = Paymaster::new(PaymasterConfig::default());
+        let total_fee = gcc_only_fee(1_000_000);
+
+        let quote = paymaster.quote(&total_fee.gcc_fee, TokenType::Mana, &prices()).unwrap();
+        // 1 GCC = 2 MANA at the oracle rate, plus a 1% spread
+        assert_eq!(quote.reimbursement.amount.to_u64(), 2_020_000);
+
+        let payer_fee = paymaster.payer_fee(&total_fee, &quote);
+        assert!(payer_fee.gcc_fee.amount.is_zero());
+        assert_eq!(payer_fee.mana_fee.amount.to_u64(), 2_020_000);
+
+        assert!(paymaster.verify_reimbursement(&balances(5_000_000), &payer_fee, &quote).is_ok());
+    }
+
+    #[test]
+    fn test_insufficient_reimbursement_rejected() {
+        let paymaster = Paymaster::new(PaymasterConfig::default());
+        let total_fee = gcc_only_fee(1_000_000);
+
+        let quote = paymaster.quote(&total_fee.gcc_fee, TokenType::Mana, &prices()).unwrap();
+        let payer_fee = paymaster.payer_fee(&total_fee, &quote);
+
+        let err = paymaster
+            .verify_reimbursement(&balances(2_000_000), &payer_fee, &quote)
+            .unwrap_err();
+        assert!(matches!(err, BridgeError::Token(TokenError::InsufficientBalance { .. })));
+    }
+
+    #[test]
+    fn test_missing_price_rejected() {
+        let paymaster = Paymaster::new(PaymasterConfig::default());
+        let fee = TokenAmount::new(TokenType::Gcc, U256::from(1_000));
+
+        assert!(matches!(
+            paymaster.quote(&fee, TokenType::Ghost, &prices()),
+            Err(BridgeError::Token(TokenError::PricingUnavailable { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_quote_is_exact_beyond_u64() {
+        let paymaster = Paymaster::new(PaymasterConfig::default());
+
+        // 10^24 GCC base units (a million GCC) is well past u64
+        let amount = U256::from(1_000_000_000_000).checked_mul_u64(1_000_000_000_000).unwrap();
+        let fee = TokenAmount::new(TokenType::Gcc, amount.clone());
+        let quote = paymaster.quote(&fee, TokenType::Spirit, &prices()).unwrap();
+
+        // 1 GCC = 0.4 SPIRIT, plus 1%: exactly 0.404 SPIRIT per GCC
+        let expected = amount.div_rem_u64(1_000).0.checked_mul_u64(404).unwrap();
+        assert_eq!(quote.reimbursement.amount, expected);
+
+        // A remainder is rounded up, never down
+        let quote = paymaster.quote(&TokenAmount::new(TokenType::Gcc, U256::from(1)), TokenType::Spirit, &prices()).unwrap();
+        assert_eq!(quote.reimbursement.amount, U256::from(1));
+
+        // Into a token with fewer decimals: 1,000 GCC at $0.50 is 5 GHOST at
+        // $100, 5.05 with the spread, rounded up to 6
+        let mut prices = prices();
+        let mut ghost = prices[&TokenType::Spirit].clone();
+        ghost.token_type = TokenType::Ghost;
+        ghost.price_usd = 100.0;
+        prices.insert(TokenType::Ghost, ghost);
+        let fee = TokenAmount::new(TokenType::Gcc, U256::from(1_000_000_000_000_000).checked_mul_u64(1_000_000).unwrap());
+        let quote = paymaster.quote(&fee, TokenType::Ghost, &prices).unwrap();
+        assert_eq!(quote.reimbursement.amount, U256::from(6));
+    }
+
+    #[test]
+    fn test_paymaster_float_checked() {
+        let paymaster = Paymaster::new(PaymasterConfig::default());
+        let quote = paymaster.quote(&TokenAmount::new(TokenType::Gcc, U256::from(1_000)), TokenType::Mana, &prices()).unwrap();
+
+        assert!(paymaster.verify_float(&TokenAmount::new(TokenType::Gcc, U256::from(1_000)), &quote).is_ok());
+        let err = paymaster.verify_float(&TokenAmount::new(TokenType::Gcc, U256::from(999)), &quote).unwrap_err();
+        assert!(matches!(err, BridgeError::Token(TokenError::InsufficientBalance { .. })));
+    }
+}
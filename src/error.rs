@@ -54,6 +54,10 @@ pub enum BridgeError {
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// Submissions rejected during a scheduled maintenance window
+    #[error("Bridge under maintenance until {ends_at}: {message}")]
+    Maintenance { message: String, ends_at: String },
+
     /// Internal errors that shouldn't normally occur
     #[error("Internal error: {0}")]
     Internal(String),
@@ -85,6 +89,104 @@ pub enum NetworkError {
 
     #[error("Connection pool exhausted")]
     PoolExhausted,
+
+    #[error("Server is draining and not accepting new connections")]
+    ServerDraining,
+
+    #[error("Connection to {endpoint} closed: {close}")]
+    ConnectionClosed { endpoint: String, close: ConnectionClose },
+
+    #[error("Invalid payload frame: {0}")]
+    InvalidPayload(String),
+
+    #[error("Response from {endpoint} exceeded {limit} bytes")]
+    ResponseTooLarge { endpoint: String, limit: usize },
+
+    #[error("Channel to {endpoint} is not encrypted")]
+    UnencryptedChannel { endpoint: String },
+
+    #[error("Channel negotiation with {endpoint} failed: {reason}")]
+    ChannelNegotiationFailed { endpoint: String, reason: String },
+
+    #[error("Protocol version {version} is no longer supported; upgrade to version {min_supported} through {current}")]
+    UnsupportedProtocolVersion { version: u16, min_supported: u16, current: u16 },
+}
+
+/// Why a QUIC connection was closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseKind {
+    /// No activity within the idle timeout
+    IdleTimeout,
+    /// CONNECTION_CLOSE with an application error code
+    ApplicationClosed,
+    /// CONNECTION_CLOSE with a QUIC transport error code
+    TransportError,
+    /// Peer sent a stateless reset
+    Reset,
+    /// Closed by this endpoint without an error code
+    LocallyClosed,
+    /// No common QUIC version
+    VersionMismatch,
+}
+
+/// Details of a QUIC connection close
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionClose {
+    pub kind: CloseKind,
+    /// Application or transport error code, if one was sent
+    pub code: Option<u64>,
+    /// Reason phrase from the CONNECTION_CLOSE frame
+    pub reason: String,
+    /// Whether the peer initiated the close
+    pub peer_initiated: bool,
+}
+
+impl ConnectionClose {
+    pub fn idle_timeout() -> Self {
+        Self { kind: CloseKind::IdleTimeout, code: None, reason: String::new(), peer_initiated: false }
+    }
+
+    pub fn application(code: u64, reason: impl Into<String>, peer_initiated: bool) -> Self {
+        Self { kind: CloseKind::ApplicationClosed, code: Some(code), reason: reason.into(), peer_initiated }
+    }
+
+    pub fn transport(code: u64, reason: impl Into<String>, peer_initiated: bool) -> Self {
+        Self { kind: CloseKind::TransportError, code: Some(code), reason: reason.into(), peer_initiated }
+    }
+
+    pub fn reset() -> Self {
+        Self { kind: CloseKind::Reset, code: None, reason: String::new(), peer_initiated: true }
+    }
+
+    pub fn locally_closed() -> Self {
+        Self { kind: CloseKind::LocallyClosed, code: None, reason: String::new(), peer_initiated: false }
+    }
+
+    /// Whether reconnecting is likely to succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(self.kind, CloseKind::IdleTimeout | CloseKind::Reset)
+    }
+}
+
+impl fmt::Display for ConnectionClose {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            CloseKind::IdleTimeout => "idle timeout",
+            CloseKind::ApplicationClosed => "application close",
+            CloseKind::TransportError => "transport error",
+            CloseKind::Reset => "stateless reset",
+            CloseKind::LocallyClosed => "locally closed",
+            CloseKind::VersionMismatch => "version mismatch",
+        };
+        write!(f, "{} ({})", kind, if self.peer_initiated { "peer" } else { "local" })?;
+        if let Some(code) = self.code {
+            write!(f, ", code 0x{:x}", code)?;
+        }
+        if !self.reason.is_empty() {
+            write!(f, ": {}", self.reason)?;
+        }
+        Ok(())
+    }
 }
 
 /// FFI boundary and memory safety errors
@@ -142,8 +244,17 @@ pub enum ServiceError {
     #[error("Service authentication failed: {service}")]
     AuthenticationFailed { service: String },
 
+    #[error("{service} requires a credential but none is configured")]
+    MissingCredential { service: String },
+
+    #[error("{service} has a credential configured, but its client cannot present one")]
+    CredentialNotSupported { service: String },
+
     #[error("Etherlink client error: {0}")]
     EtherlinkClient(String),
+
+    #[error("Insufficient services: {healthy} healthy, {required} required")]
+    InsufficientServices { healthy: usize, required: usize },
 }
 
 /// Cross-chain operation errors
@@ -172,6 +283,51 @@ pub enum CrossChainError {
 
     #[error("Cross-chain message timeout")]
     MessageTimeout,
+
+    #[error("Bridge volume cap exceeded for {token}: cap {cap}, attempted {attempted}")]
+    VolumeCapExceeded { token: String, cap: crate::types::U256, attempted: crate::types::U256 },
+
+    #[error("Bridge amount {amount} {token} is below the minimum of {minimum}")]
+    BelowMinimumAmount { token: String, amount: String, minimum: String },
+
+    #[error("L1 simulation on chain {chain_id} predicts revert: {reason}")]
+    SimulatedRevert { chain_id: u64, reason: String },
+
+    #[error("Allowance exceeded: {spender} may bridge {allowed} {token} for {owner}, attempted {attempted}")]
+    AllowanceExceeded {
+        owner: String,
+        spender: String,
+        token: String,
+        allowed: String,
+        attempted: String,
+    },
+
+    #[error("Cannot convert {token} from {from_decimals} to {to_decimals} decimals: {reason}")]
+    DecimalConversion { token: String, from_decimals: u8, to_decimals: u8, reason: String },
+
+    #[error("No state at key {key} on chain {chain_id}")]
+    StateKeyNotFound { chain_id: u64, key: String },
+
+    #[error("L1 transaction {tx_hash} on chain {chain_id} was dropped before inclusion")]
+    L1TransactionDropped { chain_id: u64, tx_hash: String },
+
+    #[error("L1 transaction {tx_hash} on chain {chain_id} not included after {waited_ms}ms")]
+    L1InclusionTimeout { chain_id: u64, tx_hash: String, waited_ms: u64 },
+
+    #[error("L1 transaction {tx_hash} on chain {chain_id} reverted")]
+    L1TransactionReverted { chain_id: u64, tx_hash: String },
+
+    #[error("L1 RPC error on chain {chain_id}: {message}")]
+    L1RpcError { chain_id: u64, message: String },
+
+    #[error("No deposit for transaction {transaction_id} found on chain {chain_id} after {waited_ms}ms")]
+    DepositNotFound { chain_id: u64, transaction_id: String, waited_ms: u64 },
+
+    #[error("L1 deposit {tx_hash} on chain {chain_id} does not match the bridge transaction: {reason}")]
+    DepositMismatch { chain_id: u64, tx_hash: String, reason: String },
+
+    #[error("L1 deposit {tx_hash} was already bridged")]
+    DepositAlreadyBridged { tx_hash: String },
 }
 
 /// L2 settlement specific errors
@@ -200,6 +356,72 @@ pub enum SettlementError {
 
     #[error("Gas estimation failed: {0}")]
     GasEstimationFailed(String),
+
+    #[error("Circuit not active: {circuit_id}")]
+    InactiveCircuit { circuit_id: String },
+
+    #[error("Circuit {circuit_id} takes {capacity} {kind} inputs but the proof needs {required}")]
+    ProofInputsExceedCircuit { circuit_id: String, kind: String, required: u64, capacity: u64 },
+
+    #[error("Invalid trusted setup: {0}")]
+    InvalidTrustedSetup(String),
+
+    #[error("Invalid nonce: expected {expected}, got {actual}")]
+    InvalidNonce { expected: u64, actual: u64 },
+
+    #[error("Duplicate transaction: {content_hash} was already submitted")]
+    DuplicateTransaction { content_hash: String },
+
+    #[error("Snapshot {snapshot_id} is corrupted: stored root {expected}, recomputed {actual}")]
+    CorruptedSnapshot { snapshot_id: String, expected: String, actual: String },
+
+    #[error("No valid snapshot at or before block {block_number}")]
+    NoValidSnapshot { block_number: u64 },
+
+    #[error("Proof queue is full ({capacity} requests); retry later")]
+    ProofQueueFull { capacity: usize },
+
+    #[error("ZK proof {proof_id} has expired")]
+    ProofExpired { proof_id: String },
+
+    #[error("Verification key {key_id} not found or revoked")]
+    UnknownVerificationKey { key_id: String },
+
+    #[error("Deadline for challenge {challenge_id} has passed")]
+    ChallengeDeadlinePassed { challenge_id: String },
+
+    #[error("Cannot defend challenge {challenge_id}: {reason}")]
+    DefenseUnavailable { challenge_id: String, reason: String },
+
+    #[error("Batch {batch_id} is not awaiting finality")]
+    UnknownBatch { batch_id: String },
+
+    #[error("No recorded prior state to replay batch {batch_id}")]
+    BatchNotReplayable { batch_id: String },
+
+    #[error("Batch {batch_id} is already tracked in the settlement queue")]
+    DuplicateBatchId { batch_id: String },
+
+    #[error("Transaction {transaction_id} missed its deadline of {deadline}")]
+    DeadlineExpired { transaction_id: String, deadline: String },
+
+    #[error("Transaction {transaction_id} is timestamped beyond the clock skew tolerance")]
+    TimestampInFuture { transaction_id: String },
+
+    #[error("Emergency exits open only after L2 has been down for {threshold_secs}s")]
+    EmergencyExitInactive { threshold_secs: u64 },
+
+    #[error("Invalid balance proof for {address}: {reason}")]
+    InvalidBalanceProof { address: String, reason: String },
+
+    #[error("Transaction {transaction_id} moves no value: {reason}")]
+    ZeroValueTransaction { transaction_id: String, reason: String },
+
+    #[error("Transaction pool full ({capacity} pending)")]
+    PoolFull { capacity: usize },
+
+    #[error("Transaction {transaction_id} rejected by security: {violations}")]
+    SecurityRejected { transaction_id: String, violations: String },
 }
 
 /// Security and Guardian Framework errors
@@ -231,6 +453,36 @@ pub enum SecurityError {
 
     #[error("Trust level insufficient: required {required}, got {actual}")]
     InsufficientTrustLevel { required: u8, actual: u8 },
+
+    #[error("Unknown Guardian: {guardian_id}")]
+    UnknownGuardian { guardian_id: String },
+
+    #[error("Guardian {guardian_id} already voted on this transaction")]
+    DuplicateGuardianVote { guardian_id: String },
+
+    #[error("No Guardian approval request for transaction {transaction_id}")]
+    ApprovalNotFound { transaction_id: String },
+
+    #[error("Guardian {guardian_id} has no registered signing key")]
+    GuardianKeyNotRegistered { guardian_id: String },
+
+    #[error("Fee quote {quote_id} was not signed by this bridge or has been modified")]
+    InvalidFeeQuote { quote_id: String },
+
+    #[error("Fee quote {quote_id} was issued for a different transaction")]
+    FeeQuoteMismatch { quote_id: String },
+
+    #[error("Fee quote {quote_id} has already been used")]
+    FeeQuoteReused { quote_id: String },
+
+    #[error("Delegated bridge {transaction_id} has already been submitted")]
+    DelegationReused { transaction_id: String },
+
+    #[error("Transaction {transaction_id} is quarantined by a security lockdown")]
+    TransactionQuarantined { transaction_id: String },
+
+    #[error("Lockdown release needs Guardian weight {quorum_weight}, got {approved_weight}")]
+    LockdownQuorumNotReached { approved_weight: u64, quorum_weight: u64 },
 }
 
 /// Token economy specific errors
@@ -260,6 +512,36 @@ pub enum TokenError {
 
     #[error("Invalid token amount: {amount}")]
     InvalidAmount { amount: String },
+
+    #[error("{token} is only transferable in whole units, got {amount}")]
+    FractionalAmount { token: String, amount: String },
+
+    #[error("Insufficient balances: {0}")]
+    InsufficientBalances(BalanceShortfalls),
+}
+
+/// One token a payer does not hold enough of
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceShortfall {
+    pub token: String,
+    pub required: String,
+    pub available: String,
+}
+
+/// Every token a payer is short of, in GCC, SPIRIT, MANA, GHOST order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceShortfalls(pub Vec<BalanceShortfall>);
+
+impl fmt::Display for BalanceShortfalls {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, shortfall) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{} required {}, available {}", shortfall.token, shortfall.required, shortfall.available)?;
+        }
+        Ok(())
+    }
 }
 
 /// Serialization and data conversion errors
@@ -282,6 +564,9 @@ pub enum SerializationError {
 
     #[error("Invalid data format: {0}")]
     InvalidFormat(String),
+
+    #[error("Unsupported schema version {version} (newest supported: {supported})")]
+    UnsupportedSchemaVersion { version: u32, supported: u32 },
 }
 
 impl BridgeError {
@@ -301,13 +586,21 @@ impl BridgeError {
             BridgeError::Network(NetworkError::Timeout { .. }) => true,
             BridgeError::Network(NetworkError::ConnectionFailed { .. }) => true,
             BridgeError::Network(NetworkError::PoolExhausted) => true,
+            BridgeError::Network(NetworkError::ConnectionClosed { close, .. }) => close.is_retryable(),
             BridgeError::Service(ServiceError::ServiceUnavailable { .. }) => true,
+            BridgeError::Service(ServiceError::InsufficientServices { .. }) => true,
             BridgeError::CrossChain(CrossChainError::ChainUnavailable { .. }) => true,
             BridgeError::Settlement(SettlementError::SettlementTimeout { .. }) => true,
             _ => false,
         }
     }
 
+    /// Whether an L1 transaction behind this error may still be mined, so
+    /// its outcome must be reconciled rather than treated as a failure
+    pub fn is_l1_outcome_unknown(&self) -> bool {
+        matches!(self, BridgeError::CrossChain(CrossChainError::L1InclusionTimeout { .. }))
+    }
+
     /// Get the error category for metrics
     pub fn category(&self) -> &'static str {
         match self {
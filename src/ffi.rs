@@ -130,6 +130,20 @@ impl GhostPlaneFfi {
         self.handle.initialize(&self.config).await
     }
 
+    /// Hand the current trace to GhostPlane so its spans join the caller's trace
+    #[cfg(feature = "otel")]
+    fn propagate_trace_context(&self) {
+        let Some(context) = crate::telemetry::TraceContext::current() else {
+            return;
+        };
+        if let Ok(traceparent) = CString::new(context.0) {
+            unsafe { ghostplane_set_trace_context(self.handle.raw_handle(), traceparent.as_ptr()) };
+        }
+    }
+
+    #[cfg(not(feature = "otel"))]
+    fn propagate_trace_context(&self) {}
+
     /// Submit a transaction to GhostPlane L2
     #[instrument(skip(self, transaction))]
     pub async fn submit_transaction(&self, transaction: &Transaction) -> Result<TransactionReceipt> {
@@ -143,6 +157,7 @@ impl GhostPlaneFfi {
         let ffi_tx = self.convert_transaction_to_ffi(transaction)?;
 
         debug!("Submitting transaction to GhostPlane");
+        self.propagate_trace_context();
         let mut result: FfiResult<FfiTransactionReceipt> = FfiResult {
             success: false,
             data: FfiTransactionReceipt::default(),
@@ -185,6 +200,7 @@ impl GhostPlaneFfi {
             .collect();
 
         let ffi_transactions = ffi_transactions?;
+        self.propagate_trace_context();
 
         let mut result: FfiResult<FfiBatchResult> = FfiResult {
             success: false,
@@ -277,8 +293,8 @@ impl GhostPlaneFfi {
         let gas_limit = 21000; // Default gas limit
         let gas_price = tx.fee.gcc_fee.amount.to_u64();
 
-        // Serialize transaction data
-        let data = tx.to_bytes()?;
+        // Canonical encoding, decodable on the Zig side
+        let data = tx.canonical_bytes();
 
         // Create FFI transaction
         let ffi_tx = FfiTransaction {
@@ -353,7 +369,7 @@ impl GhostPlaneFfi {
 }
 
 /// Batch operation result
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct BatchResult {
     pub batch_hash: [u8; 32],
     pub state_root: [u8; 32],
@@ -451,6 +467,12 @@ extern "C" {
     fn ghostplane_cleanup(handle: *mut c_void);
 }
 
+#[cfg(feature = "otel")]
+extern "C" {
+    /// Parent GhostPlane's spans for the next call on a W3C `traceparent`
+    fn ghostplane_set_trace_context(handle: *mut c_void, traceparent: *const c_char);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,101 @@
+/*!
+Criticality-tiered health aggregation
+
+Components are not equally important: the bridge cannot operate with the
+settlement engine down, but it keeps bridging while DNS-over-QUIC or CNS is
+unreachable. A `HealthPolicy` assigns each component a criticality and folds
+per-component health into one `HealthState`: any critical failure makes the
+whole unhealthy, while failures limited to non-critical components only
+degrade it.
+*/
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How much a component's failure matters to the whole
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Criticality {
+    /// Failure makes the whole unhealthy
+    Critical,
+    /// Failure only degrades the whole
+    NonCritical,
+}
+
+/// Aggregated health
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum HealthState {
+    /// Every component is healthy
+    Healthy,
+    /// Only non-critical components are failing
+    Degraded,
+    /// A critical component is failing
+    #[default]
+    Unhealthy,
+}
+
+/// Criticality of each component, by name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthPolicy {
+    pub components: HashMap<String, Criticality>,
+    /// Criticality of components not listed
+    pub default_criticality: Criticality,
+}
+
+impl HealthPolicy {
+    /// Policy treating every unlisted component as `default_criticality`
+    pub fn new(default_criticality: Criticality) -> Self {
+        Self { components: HashMap::new(), default_criticality }
+    }
+
+    pub fn with_component(mut self, name: impl Into<String>, criticality: Criticality) -> Self {
+        self.components.insert(name.into(), criticality);
+        self
+    }
+
+    pub fn criticality(&self, component: &str) -> Criticality {
+        self.components.get(component).copied().unwrap_or(self.default_criticality)
+    }
+
+    /// Fold `(component, healthy)` pairs into a single state
+    pub fn aggregate<'a>(&self, components: impl IntoIterator<Item = (&'a str, bool)>) -> HealthState {
+        components.into_iter()
+            .filter(|(_, healthy)| !healthy)
+            .map(|(component, _)| match self.criticality(component) {
+                Criticality::Critical => HealthState::Unhealthy,
+                Criticality::NonCritical => HealthState::Degraded,
+            })
+            .max()
+            .unwrap_or(HealthState::Healthy)
+    }
+}
+
+impl Default for HealthPolicy {
+    /// Every component critical
+    fn default() -> Self {
+        Self::new(Criticality::Critical)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failures_aggregate_by_criticality() {
+        let policy = HealthPolicy::new(Criticality::Critical)
+            .with_component("dns", Criticality::NonCritical)
+            .with_component("mesh", Criticality::NonCritical);
+
+        assert_eq!(policy.aggregate([("settlement", true), ("dns", true)]), HealthState::Healthy);
+
+        // Non-critical failures only degrade
+        assert_eq!(policy.aggregate([("settlement", true), ("dns", false), ("mesh", false)]), HealthState::Degraded);
+
+        // A critical failure wins over any number of non-critical ones
+        assert_eq!(policy.aggregate([("settlement", false), ("dns", false)]), HealthState::Unhealthy);
+
+        // Unlisted components take the default
+        assert_eq!(policy.aggregate([("unknown", false)]), HealthState::Unhealthy);
+        assert_eq!(HealthPolicy::new(Criticality::NonCritical).aggregate([("unknown", false)]), HealthState::Degraded);
+    }
+}
@@ -0,0 +1,107 @@
+/*!
+Identifier generation
+
+Batch, proof request, and key identifiers are produced through an injectable
+`IdGenerator`. Production code uses the OS-backed generator; tests can swap in
+a `SeededIdGenerator` to get a reproducible sequence of ids.
+*/
+
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Source of randomness for identifiers and nonces
+pub trait IdGenerator: Send + Sync {
+    /// Next random 32-bit value
+    fn next_u32(&self) -> u32;
+
+    /// Next identifier with the given prefix
+    fn next_id(&self, prefix: &str) -> String;
+}
+
+/// Default generator: wall-clock timestamp plus OS randomness
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OsIdGenerator;
+
+impl IdGenerator for OsIdGenerator {
+    fn next_u32(&self) -> u32 {
+        rand::random::<u32>()
+    }
+
+    fn next_id(&self, prefix: &str) -> String {
+        format!("{}-{}-{}",
+                prefix,
+                SystemTime::now().duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default().as_millis(),
+                self.next_u32())
+    }
+}
+
+/// Deterministic generator for tests; the same seed yields the same ids
+#[derive(Debug)]
+pub struct SeededIdGenerator {
+    state: Mutex<u64>,
+}
+
+impl SeededIdGenerator {
+    /// Create a generator from a fixed seed
+    pub fn new(seed: u64) -> Self {
+        Self { state: Mutex::new(seed) }
+    }
+
+    // SplitMix64: small, fast, and fully determined by the seed
+    fn next_u64(&self) -> u64 {
+        let mut state = self.state.lock();
+        *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+impl IdGenerator for SeededIdGenerator {
+    fn next_u32(&self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_id(&self, prefix: &str) -> String {
+        format!("{}-{:016x}", prefix, self.next_u64())
+    }
+}
+
+/// Default id generator used when none is injected
+pub fn default_id_generator() -> Arc<dyn IdGenerator> {
+    Arc::new(OsIdGenerator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_generator_is_reproducible() {
+        let a = SeededIdGenerator::new(42);
+        let b = SeededIdGenerator::new(42);
+
+        let ids_a: Vec<String> = (0..5).map(|_| a.next_id("batch")).collect();
+        let ids_b: Vec<String> = (0..5).map(|_| b.next_id("batch")).collect();
+        assert_eq!(ids_a, ids_b);
+
+        // Ids within a sequence are distinct
+        let mut unique = ids_a.clone();
+        unique.dedup();
+        assert_eq!(unique.len(), ids_a.len());
+
+        // A different seed gives a different sequence
+        let c = SeededIdGenerator::new(7);
+        assert_ne!(c.next_id("batch"), ids_a[0]);
+    }
+
+    #[test]
+    fn test_os_generator_ids_are_prefixed() {
+        let id = OsIdGenerator.next_id("key");
+        assert!(id.starts_with("key-"));
+        assert_ne!(OsIdGenerator.next_id("key"), OsIdGenerator.next_id("key"));
+    }
+}
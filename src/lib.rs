@@ -58,7 +58,8 @@ async fn main() -> Result<()> {
     let bridge = GhostBridge::new(config).await?;
 
     // Bridge a transaction
-    let receipt = bridge.bridge_transaction(tx).await?;
+    // signed by the owner of the funds, or a spender they approved
+    let receipt = bridge.bridge_transaction(tx, &signature).await?;
 
     Ok(())
 }
@@ -82,6 +83,13 @@ pub mod error;
 pub mod config;
 pub mod transport;
 pub mod economy;
+pub mod idgen;
+pub mod clock;
+pub mod calldata;
+pub mod canonical;
+pub mod health;
+pub mod api;
+pub mod telemetry;
 
 // Internal modules
 mod ffi;
@@ -109,6 +117,27 @@ pub fn init_with_tracing(filter: &str) {
     tracing::info!("GhostBridge initialized with tracing filter: {}", filter);
 }
 
+/// Initialize GhostBridge tracing with spans also exported over OTLP
+#[cfg(feature = "otel")]
+pub fn init_with_telemetry(filter: &str, config: &telemetry::TelemetryConfig) -> Result<()> {
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| filter.into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .with(telemetry::otlp_layer(config)?)
+        .init();
+
+    tracing::info!("GhostBridge initialized with tracing filter: {}, exporting to {}", filter, config.otlp_endpoint);
+    Ok(())
+}
+
+/// Check if OpenTelemetry export is enabled
+pub fn has_otel_support() -> bool {
+    cfg!(feature = "otel")
+}
+
 /// Get the library version
 pub fn version() -> &'static str {
     env!("CARGO_PKG_VERSION")
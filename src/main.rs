@@ -5,9 +5,12 @@ Command-line interface for operating the GhostBridge cross-chain infrastructure.
 */
 
 use ghostbridge::{BridgeConfig, GhostBridge, init_with_tracing};
+use ghostbridge::api::ApiServer;
+use ghostbridge::health::HealthState;
 use clap::{Parser, Subcommand};
 use anyhow::Result;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 #[derive(Parser)]
 #[command(name = "ghostbridge")]
@@ -94,7 +97,7 @@ async fn main() -> Result<()> {
 async fn start_bridge_service(config: BridgeConfig, bind_addr: &str) -> Result<()> {
     println!("Initializing GhostBridge with {} networks", config.networks.len());
 
-    let bridge = GhostBridge::new(config).await?;
+    let bridge = Arc::new(GhostBridge::new(config).await?);
 
     println!("✅ GhostBridge initialized successfully");
     println!("🔗 Multi-chain support enabled");
@@ -110,12 +113,19 @@ async fn start_bridge_service(config: BridgeConfig, bind_addr: &str) -> Result<(
                  health.healthy_services, 6);
     }
 
-    // TODO: Start HTTP/gRPC servers for bridge API
+    // Keep degraded features and the service quorum current
+    let _health_monitor = GhostBridge::spawn_health_monitor(&bridge);
+
+    // Start HTTP API server
+    let api_server = ApiServer::new(bridge, bind_addr.parse()?);
     println!("🚀 GhostBridge service running on {}", bind_addr);
     println!("Press Ctrl+C to stop");
 
     // Keep the service running
-    tokio::signal::ctrl_c().await?;
+    tokio::select! {
+        result = api_server.serve() => result?,
+        _ = tokio::signal::ctrl_c() => {}
+    }
     println!("👋 Shutting down GhostBridge...");
 
     Ok(())
@@ -125,16 +135,15 @@ async fn check_health(config: BridgeConfig) -> Result<()> {
     let bridge = GhostBridge::new(config).await?;
     let health = bridge.health_check().await?;
 
-    if health.overall_healthy {
-        println!("✅ Bridge is healthy");
-        println!("   - Services: {}/6 healthy", health.healthy_services);
-        println!("   - FFI: {}", if health.ffi_healthy { "✅" } else { "❌" });
-        println!("   - Settlement: {}", if health.settlement_healthy { "✅" } else { "❌" });
-    } else {
-        println!("❌ Bridge has health issues");
-        println!("   - Services: {}/6 healthy", health.healthy_services);
-        println!("   - FFI: {}", if health.ffi_healthy { "✅" } else { "❌" });
-        println!("   - Settlement: {}", if health.settlement_healthy { "✅" } else { "❌" });
+    match health.status {
+        HealthState::Healthy => println!("✅ Bridge is healthy"),
+        HealthState::Degraded => println!("⚠️  Bridge is degraded: non-critical components are down"),
+        HealthState::Unhealthy => println!("❌ Bridge has health issues"),
+    }
+    println!("   - Services: {}/6 healthy", health.healthy_services);
+    println!("   - FFI: {}", if health.ffi_healthy { "✅" } else { "❌" });
+    println!("   - Settlement: {}", if health.settlement_healthy { "✅" } else { "❌" });
+    if health.status == HealthState::Unhealthy {
         std::process::exit(1);
     }
 
@@ -188,17 +197,20 @@ async fn test_configuration(config: BridgeConfig) -> Result<()> {
         }
     }
 
-    // Test service connections (without initializing full bridge)
-    println!("🔗 Testing service connections...");
-
-    // TODO: Add actual connection tests
-    println!("   - GHOSTD: ✅ (simulated)");
-    println!("   - WALLETD: ✅ (simulated)");
-    println!("   - GID: ✅ (simulated)");
-    println!("   - CNS: ✅ (simulated)");
-    println!("   - GLEDGER: ✅ (simulated)");
-    println!("   - GSIG: ✅ (simulated)");
-    println!("   - GhostPlane: ✅ (simulated)");
+    // Probe each subsystem without changing live state
+    println!("🔗 Running self-diagnostic...");
+    let bridge = GhostBridge::new(config).await?;
+    let report = bridge.self_diagnostic().await?;
+
+    for result in &report.results {
+        let mark = if result.passed { "✅" } else { "❌" };
+        println!("   - {}: {} {} ({:?})", result.subsystem, mark, result.detail, result.duration);
+    }
+
+    if !report.passed() {
+        println!("❌ {} subsystem(s) failed", report.failures().count());
+        std::process::exit(1);
+    }
 
     println!("✅ All tests passed!");
 
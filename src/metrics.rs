@@ -0,0 +1,443 @@
+/*!
+Metrics collection
+
+Structured in-process counters for bridge subsystems. Collectors are cheap to
+update from hot paths and expose plain snapshot structs for the API and
+exporters.
+*/
+
+use crate::economy::FeeDistributionBreakdown;
+use crate::types::{MultiTokenFee, TokenType};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+
+/// Cumulative amounts per token
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenTotals {
+    pub gcc: u128,
+    pub spirit: u128,
+    pub mana: u128,
+    pub ghost: u128,
+}
+
+impl TokenTotals {
+    /// Add every token component of a fee
+    pub fn add_fee(&mut self, fee: &MultiTokenFee) {
+        self.gcc += fee.gcc_fee.amount.to_u64() as u128;
+        self.spirit += fee.spirit_fee.amount.to_u64() as u128;
+        self.mana += fee.mana_fee.amount.to_u64() as u128;
+        self.ghost += fee.ghost_fee.amount.to_u64() as u128;
+    }
+
+    /// Amount for a single token
+    pub fn get(&self, token_type: TokenType) -> u128 {
+        match token_type {
+            TokenType::Gcc => self.gcc,
+            TokenType::Spirit => self.spirit,
+            TokenType::Mana => self.mana,
+            TokenType::Ghost => self.ghost,
+        }
+    }
+
+    fn plus(self, other: TokenTotals) -> TokenTotals {
+        TokenTotals {
+            gcc: self.gcc + other.gcc,
+            spirit: self.spirit + other.spirit,
+            mana: self.mana + other.mana,
+            ghost: self.ghost + other.ghost,
+        }
+    }
+}
+
+/// Cumulative fees paid out per distribution bucket
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DistributedTotals {
+    pub l2_validators: TokenTotals,
+    pub l1_validators: TokenTotals,
+    pub security_fund: TokenTotals,
+    pub protocol_development: TokenTotals,
+}
+
+impl DistributedTotals {
+    /// Sum across all buckets
+    pub fn total(&self) -> TokenTotals {
+        self.l2_validators
+            .plus(self.l1_validators)
+            .plus(self.security_fund)
+            .plus(self.protocol_development)
+    }
+}
+
+/// Snapshot of fee flows through the token economy
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EconomicSummary {
+    pub payments_processed: u64,
+    pub collected: TokenTotals,
+    pub burned: TokenTotals,
+    pub distributed: DistributedTotals,
+    pub refunded: TokenTotals,
+}
+
+impl EconomicSummary {
+    /// Whether every collected fee is accounted for as burned, distributed, or refunded
+    pub fn reconciles(&self) -> bool {
+        self.collected == self.burned.plus(self.distributed.total()).plus(self.refunded)
+    }
+}
+
+/// Fee collection, burn, and distribution counters
+#[derive(Debug, Default)]
+pub struct EconomyMetrics {
+    // Single lock so snapshots never observe a half-recorded payment
+    summary: Mutex<EconomicSummary>,
+}
+
+impl EconomyMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a processed payment and where its fees went
+    pub fn record_payment(
+        &self,
+        collected: &MultiTokenFee,
+        distribution: &FeeDistributionBreakdown,
+        refunded: Option<&MultiTokenFee>,
+    ) {
+        let mut summary = self.summary.lock();
+        summary.payments_processed += 1;
+        summary.collected.add_fee(collected);
+        summary.burned.add_fee(&distribution.burn_amount);
+        summary.distributed.l2_validators.add_fee(&distribution.l2_validators);
+        summary.distributed.l1_validators.add_fee(&distribution.l1_validators);
+        summary.distributed.security_fund.add_fee(&distribution.security_fund);
+        summary.distributed.protocol_development.add_fee(&distribution.protocol_development);
+        if let Some(refunded) = refunded {
+            summary.refunded.add_fee(refunded);
+        }
+    }
+
+    pub fn summary(&self) -> EconomicSummary {
+        self.summary.lock().clone()
+    }
+}
+
+/// Limits that raise a proof generation alert when breached
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofAlertThresholds {
+    /// Failure rate (0.0 to 1.0) over the recent window
+    pub max_failure_rate: f64,
+    /// p99 generation latency for any circuit
+    pub max_p99_latency: Duration,
+    /// Generations needed before alerts are evaluated
+    pub min_samples: usize,
+    /// Recent generations kept for failure rates and percentiles
+    pub window: usize,
+}
+
+impl Default for ProofAlertThresholds {
+    fn default() -> Self {
+        Self {
+            max_failure_rate: 0.05,
+            max_p99_latency: Duration::from_secs(20),
+            min_samples: 20,
+            window: 1000,
+        }
+    }
+}
+
+/// Generation latency distribution for one circuit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyPercentiles {
+    pub samples: usize,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+}
+
+impl LatencyPercentiles {
+    fn from_samples(samples: &VecDeque<Duration>) -> Self {
+        let mut sorted: Vec<Duration> = samples.iter().copied().collect();
+        sorted.sort();
+        // Nearest-rank percentile
+        let at = |p: f64| {
+            let rank = ((p * sorted.len() as f64).ceil() as usize).max(1);
+            sorted.get(rank - 1).copied().unwrap_or_default()
+        };
+        Self { samples: sorted.len(), p50: at(0.50), p95: at(0.95), p99: at(0.99) }
+    }
+}
+
+/// Snapshot of ZK proof generation health
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProofMetricsSnapshot {
+    pub requested: u64,
+    pub generated: u64,
+    pub failed: u64,
+    /// Failure rate over the recent window
+    pub recent_failure_rate: f64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    pub cache_hit_rate: f64,
+    pub latency_by_circuit: HashMap<String, LatencyPercentiles>,
+}
+
+/// Breached proof generation threshold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ProofAlert {
+    FailureRate { rate: f64, threshold: f64 },
+    Latency { circuit_id: String, p99: Duration, threshold: Duration },
+}
+
+impl ProofAlert {
+    fn key(&self) -> String {
+        match self {
+            ProofAlert::FailureRate { .. } => "failure_rate".to_string(),
+            ProofAlert::Latency { circuit_id, .. } => format!("latency:{}", circuit_id),
+        }
+    }
+}
+
+impl std::fmt::Display for ProofAlert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProofAlert::FailureRate { rate, threshold } => {
+                write!(f, "proof failure rate {:.1}% exceeds {:.1}%", rate * 100.0, threshold * 100.0)
+            }
+            ProofAlert::Latency { circuit_id, p99, threshold } => {
+                write!(f, "{} proof p99 latency {:?} exceeds {:?}", circuit_id, p99, threshold)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ProofMetricsState {
+    requested: u64,
+    generated: u64,
+    failed: u64,
+    cache_hits: u64,
+    cache_misses: u64,
+    /// Recent outcomes, true for failures
+    recent_failures: VecDeque<bool>,
+    latencies: HashMap<String, VecDeque<Duration>>,
+    /// Alerts already reported, so each breach is raised once
+    active_alerts: HashSet<String>,
+}
+
+/// ZK proof generation counters, latencies, and alerting
+#[derive(Debug)]
+pub struct ProofMetrics {
+    thresholds: ProofAlertThresholds,
+    state: Mutex<ProofMetricsState>,
+}
+
+impl ProofMetrics {
+    pub fn new(thresholds: ProofAlertThresholds) -> Self {
+        Self { thresholds, state: Mutex::new(ProofMetricsState::default()) }
+    }
+
+    pub fn record_requested(&self) {
+        self.state.lock().requested += 1;
+    }
+
+    pub fn record_cache_hit(&self) {
+        self.state.lock().cache_hits += 1;
+    }
+
+    pub fn record_cache_miss(&self) {
+        self.state.lock().cache_misses += 1;
+    }
+
+    /// Record a successful generation on `circuit_id`
+    pub fn record_generated(&self, circuit_id: &str, latency: Duration) {
+        self.record_outcome(circuit_id, latency, false);
+    }
+
+    /// Record a failed generation on `circuit_id`
+    pub fn record_failed(&self, circuit_id: &str, latency: Duration) {
+        self.record_outcome(circuit_id, latency, true);
+    }
+
+    fn record_outcome(&self, circuit_id: &str, latency: Duration, failed: bool) {
+        let window = self.thresholds.window.max(1);
+        let mut state = self.state.lock();
+        if failed {
+            state.failed += 1;
+        } else {
+            state.generated += 1;
+        }
+
+        state.recent_failures.push_back(failed);
+        if state.recent_failures.len() > window {
+            state.recent_failures.pop_front();
+        }
+
+        let latencies = state.latencies.entry(circuit_id.to_string()).or_default();
+        latencies.push_back(latency);
+        if latencies.len() > window {
+            latencies.pop_front();
+        }
+    }
+
+    pub fn snapshot(&self) -> ProofMetricsSnapshot {
+        let state = self.state.lock();
+        let ratio = |part: u64, total: u64| if total == 0 { 0.0 } else { part as f64 / total as f64 };
+        ProofMetricsSnapshot {
+            requested: state.requested,
+            generated: state.generated,
+            failed: state.failed,
+            recent_failure_rate: Self::failure_rate(&state),
+            cache_hits: state.cache_hits,
+            cache_misses: state.cache_misses,
+            cache_hit_rate: ratio(state.cache_hits, state.cache_hits + state.cache_misses),
+            latency_by_circuit: state.latencies.iter()
+                .map(|(circuit_id, samples)| (circuit_id.clone(), LatencyPercentiles::from_samples(samples)))
+                .collect(),
+        }
+    }
+
+    /// Thresholds currently breached
+    pub fn alerts(&self) -> Vec<ProofAlert> {
+        let state = self.state.lock();
+        self.evaluate(&state)
+    }
+
+    /// Breaches not reported by a previous call; cleared breaches can fire again
+    pub fn take_new_alerts(&self) -> Vec<ProofAlert> {
+        let mut state = self.state.lock();
+        let alerts = self.evaluate(&state);
+        let previous = std::mem::replace(
+            &mut state.active_alerts,
+            alerts.iter().map(ProofAlert::key).collect(),
+        );
+        alerts.into_iter().filter(|alert| !previous.contains(&alert.key())).collect()
+    }
+
+    fn evaluate(&self, state: &ProofMetricsState) -> Vec<ProofAlert> {
+        let mut alerts = Vec::new();
+        let min_samples = self.thresholds.min_samples;
+
+        let rate = Self::failure_rate(state);
+        if state.recent_failures.len() >= min_samples && rate > self.thresholds.max_failure_rate {
+            alerts.push(ProofAlert::FailureRate { rate, threshold: self.thresholds.max_failure_rate });
+        }
+
+        let mut circuits: Vec<_> = state.latencies.iter().collect();
+        circuits.sort_by(|a, b| a.0.cmp(b.0));
+        for (circuit_id, samples) in circuits {
+            let percentiles = LatencyPercentiles::from_samples(samples);
+            if percentiles.samples >= min_samples && percentiles.p99 > self.thresholds.max_p99_latency {
+                alerts.push(ProofAlert::Latency {
+                    circuit_id: circuit_id.clone(),
+                    p99: percentiles.p99,
+                    threshold: self.thresholds.max_p99_latency,
+                });
+            }
+        }
+        alerts
+    }
+
+    fn failure_rate(state: &ProofMetricsState) -> f64 {
+        if state.recent_failures.is_empty() {
+            return 0.0;
+        }
+        state.recent_failures.iter().filter(|failed| **failed).count() as f64 / state.recent_failures.len() as f64
+    }
+}
+
+/// Snapshot of per-address pattern tracking in the threat detector
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PatternTrackingSnapshot {
+    pub tracked_patterns: usize,
+    pub max_tracked_patterns: usize,
+    /// Patterns dropped to stay under the cap
+    pub evicted_patterns: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economy::FeeDistributor;
+    use crate::services::{ServiceConfig, ServiceManager};
+    use crate::types::{TokenAmount, U256};
+    use std::sync::Arc;
+
+    fn fee(gcc: u64, spirit: u64, mana: u64, ghost: u64) -> MultiTokenFee {
+        MultiTokenFee {
+            gcc_fee: TokenAmount::new(TokenType::Gcc, U256::from(gcc)),
+            spirit_fee: TokenAmount::new(TokenType::Spirit, U256::from(spirit)),
+            mana_fee: TokenAmount::new(TokenType::Mana, U256::from(mana)),
+            ghost_fee: TokenAmount::new(TokenType::Ghost, U256::from(ghost)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_economy_totals_reconcile() {
+        let services = Arc::new(ServiceManager::new(ServiceConfig::default()));
+        let distributor = FeeDistributor::new(services).await.unwrap();
+        let metrics = EconomyMetrics::new();
+
+        let payments = [fee(1_001, 7, 9_999, 3), fee(123_457, 1, 333, 1), fee(99, 0, 1, 0)];
+        for total in &payments {
+            let distribution = distributor.calculate_distribution(total).await.unwrap();
+            metrics.record_payment(total, &distribution, None);
+        }
+
+        // Refund the unspent part of a payment: it is collected but neither burned nor distributed
+        let refund = fee(500, 0, 0, 0);
+        let charged = fee(2_000, 0, 0, 0);
+        let distribution = distributor.calculate_distribution(&fee(1_500, 0, 0, 0)).await.unwrap();
+        metrics.record_payment(&charged, &distribution, Some(&refund));
+
+        let summary = metrics.summary();
+        assert_eq!(summary.payments_processed, 4);
+        assert_eq!(summary.collected.get(TokenType::Gcc), 1_001 + 123_457 + 99 + 2_000);
+        assert_eq!(summary.collected.get(TokenType::Mana), 9_999 + 333 + 1);
+        assert_eq!(summary.refunded.gcc, 500);
+        assert!(summary.burned.gcc > 0);
+        assert!(summary.reconciles());
+    }
+
+    #[test]
+    fn test_proof_metrics_alert_on_failure_spike() {
+        let metrics = ProofMetrics::new(ProofAlertThresholds {
+            min_samples: 10,
+            ..ProofAlertThresholds::default()
+        });
+
+        for i in 0..20u64 {
+            metrics.record_requested();
+            if i % 4 == 0 {
+                metrics.record_cache_hit();
+            } else {
+                metrics.record_cache_miss();
+                metrics.record_generated("state_transition", Duration::from_millis(100 + i * 10));
+            }
+        }
+        metrics.record_generated("balance_proof", Duration::from_millis(5));
+
+        let snapshot = metrics.snapshot();
+        assert_eq!((snapshot.requested, snapshot.generated, snapshot.failed), (20, 16, 0));
+        assert_eq!(snapshot.cache_hit_rate, 0.25);
+        let latency = snapshot.latency_by_circuit["state_transition"];
+        assert_eq!(latency.samples, 15);
+        assert_eq!(latency.p50, Duration::from_millis(200));
+        assert_eq!(latency.p99, Duration::from_millis(290));
+        assert!(metrics.take_new_alerts().is_empty());
+
+        // Slow failures spike past both thresholds; each alert is raised once
+        for _ in 0..3 {
+            metrics.record_failed("state_transition", Duration::from_secs(30));
+        }
+        let alerts = metrics.take_new_alerts();
+        assert_eq!(alerts.len(), 2, "{:?}", alerts);
+        assert!(matches!(alerts[0], ProofAlert::FailureRate { rate, .. } if rate > 0.05));
+        assert!(matches!(&alerts[1], ProofAlert::Latency { circuit_id, .. } if circuit_id == "state_transition"));
+        assert!(metrics.take_new_alerts().is_empty());
+        assert_eq!(metrics.alerts().len(), 2);
+        assert_eq!(metrics.snapshot().failed, 3);
+    }
+}
@@ -7,7 +7,13 @@ generation for the Guardian Framework.
 
 use crate::error::{BridgeError, Result, SecurityError};
 use crate::security::{GuardianConfig, SignatureScheme};
-use gcrypt::protocols::{Ed25519, Secp256k1};
+use crate::idgen::{IdGenerator, default_id_generator};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use chacha20poly1305::ChaCha20Poly1305;
+use gcrypt::protocols::Ed25519;
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use sha3::{Digest, Keccak256};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
@@ -22,6 +28,7 @@ pub struct CryptoProvider {
     secure_random: SecureRandom,
     signature_schemes: HashMap<SignatureScheme, Box<dyn SignatureProvider + Send + Sync>>,
     encryption_provider: EncryptionProvider,
+    id_generator: Arc<dyn IdGenerator>,
 }
 
 /// Key management system
@@ -103,7 +110,7 @@ struct AccessPolicy {
 
 /// Key operations
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
-enum KeyOperation {
+pub enum KeyOperation {
     Sign,
     Verify,
     Encrypt,
@@ -123,10 +130,27 @@ struct RateLimit {
 
 /// Time-based access restrictions
 #[derive(Debug, Clone)]
-struct TimeRestriction {
-    start_hour: u8, // 0-23
-    end_hour: u8,   // 0-23
-    allowed_days: Vec<u8>, // 0-6, Sunday = 0
+pub struct TimeRestriction {
+    pub start_hour: u8, // 0-23
+    pub end_hour: u8,   // 0-23
+    pub allowed_days: Vec<u8>, // 0-6, Sunday = 0
+}
+
+impl TimeRestriction {
+    /// Whether `now` (UTC) falls on an allowed day within `[start_hour, end_hour)`.
+    /// A window with `start_hour > end_hour` wraps past midnight; equal hours
+    /// allow the whole day.
+    fn allows(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        use chrono::{Datelike, Timelike};
+        let day = now.weekday().num_days_from_sunday() as u8;
+        let hour = now.hour() as u8;
+        let in_window = match self.start_hour.cmp(&self.end_hour) {
+            std::cmp::Ordering::Less => self.start_hour <= hour && hour < self.end_hour,
+            std::cmp::Ordering::Greater => hour >= self.start_hour || hour < self.end_hour,
+            std::cmp::Ordering::Equal => true,
+        };
+        in_window && self.allowed_days.contains(&day)
+    }
 }
 
 /// Key rotation policy
@@ -145,7 +169,7 @@ struct RotationScheduler {
 }
 
 /// Encryption algorithms
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 enum EncryptionAlgorithm {
     AES256GCM,
     ChaCha20Poly1305,
@@ -157,16 +181,19 @@ struct EncryptionProvider {
     algorithms: HashMap<EncryptionAlgorithm, Box<dyn EncryptionAlgorithmProvider + Send + Sync>>,
 }
 
-/// Secure random number generator
+/// Random bytes from the OS CSPRNG
 pub struct SecureRandom {
     entropy_pool: Arc<RwLock<EntropyPool>>,
 }
 
-/// Entropy pool for random generation
+/// Buffer of OS random bytes, each handed out once; it batches small
+/// requests into fewer OS reads and is refilled when drained or stale
 #[derive(Debug)]
 struct EntropyPool {
     pool: Vec<u8>,
     pool_size: usize,
+    /// Bytes already handed out from the front of `pool`
+    consumed: usize,
     last_refresh: SystemTime,
     refresh_interval: Duration,
 }
@@ -177,6 +204,10 @@ trait SignatureProvider {
     async fn generate_keypair(&self) -> Result<(Vec<u8>, Vec<u8>)>; // (private, public)
     async fn sign(&self, private_key: &[u8], message: &[u8]) -> Result<Vec<u8>>;
     async fn verify(&self, public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<bool>;
+    /// Recover the signer's public key, for schemes with recoverable signatures
+    async fn recover_public_key(&self, _message: &[u8], _signature: &[u8]) -> Result<Vec<u8>> {
+        Err(BridgeError::Security(SecurityError::UnsupportedSignatureScheme))
+    }
     fn key_size(&self) -> usize;
     fn signature_size(&self) -> usize;
 }
@@ -197,8 +228,43 @@ struct Ed25519Provider {
 }
 
 /// Secp256k1 signature provider
+///
+/// Messages are hashed with Keccak-256 before signing, as on Ethereum.
+/// Signatures are 65 bytes, `r || s || v`, where `v` is the recovery id as
+/// 0/1; `v` = 27/28 as produced by Ethereum tooling is accepted too.
+/// Verification also takes bare 64-byte `r || s` signatures, recovery needs
+/// all 65. Public keys are 33-byte compressed points.
+///
+/// Unlike Ed25519 this is backed by the `secp256k1` crate rather than gcrypt:
+/// Ethereum signatures and allowance permits need public key recovery, which
+/// the manifest pulls in through that crate's `recovery` feature, and
+/// libsecp256k1 matches what Ethereum tooling signs and recovers bit for bit.
 struct Secp256k1Provider {
-    secp256k1: Secp256k1,
+    secp256k1: secp256k1::Secp256k1<secp256k1::All>,
+}
+
+impl Secp256k1Provider {
+    const COMPACT_SIGNATURE_SIZE: usize = 64;
+    const RECOVERABLE_SIGNATURE_SIZE: usize = 65;
+
+    fn message_digest(message: &[u8]) -> secp256k1::Message {
+        let digest: [u8; 32] = Keccak256::digest(message).into();
+        secp256k1::Message::from_digest(digest)
+    }
+
+    fn invalid(reason: impl std::fmt::Display) -> BridgeError {
+        BridgeError::Security(SecurityError::CryptographicOperation(format!("secp256k1: {}", reason)))
+    }
+
+    /// Normalise the `v` byte to a recovery id
+    fn recovery_id(v: u8) -> Result<RecoveryId> {
+        let id = match v {
+            0 | 1 => v,
+            27 | 28 => v - 27,
+            _ => return Err(Self::invalid(format!("invalid recovery id {}", v))),
+        };
+        RecoveryId::from_i32(id as i32).map_err(Self::invalid)
+    }
 }
 
 /// AES-256-GCM encryption provider
@@ -228,7 +294,7 @@ impl CryptoProvider {
         signature_schemes.insert(
             SignatureScheme::Secp256k1,
             Box::new(Secp256k1Provider {
-                secp256k1: Secp256k1::new(),
+                secp256k1: secp256k1::Secp256k1::new(),
             })
         );
 
@@ -240,9 +306,21 @@ impl CryptoProvider {
             secure_random,
             signature_schemes,
             encryption_provider,
+            id_generator: default_id_generator(),
         })
     }
 
+    /// Signature schemes registered by every crypto provider
+    pub fn supported_schemes() -> Vec<SignatureScheme> {
+        vec![SignatureScheme::Ed25519, SignatureScheme::Secp256k1]
+    }
+
+    /// Use a custom id generator (e.g. a seeded one in tests)
+    pub fn with_id_generator(mut self, id_generator: Arc<dyn IdGenerator>) -> Self {
+        self.id_generator = id_generator;
+        self
+    }
+
     /// Generate new signing keypair
     #[instrument(skip(self))]
     pub async fn generate_signing_keypair(&self, scheme: SignatureScheme) -> Result<(String, PublicKey)> {
@@ -335,6 +413,16 @@ impl CryptoProvider {
         }
     }
 
+    /// Recover the public key that produced a recoverable signature, e.g. a
+    /// 65-byte Secp256k1 signature on a bridged Ethereum transaction
+    #[instrument(skip(self, message, signature))]
+    pub async fn recover_public_key(&self, scheme: SignatureScheme, message: &[u8], signature: &[u8]) -> Result<Vec<u8>> {
+        match self.signature_schemes.get(&scheme) {
+            Some(provider) => provider.recover_public_key(message, signature).await,
+            None => Err(BridgeError::Security(SecurityError::UnsupportedSignatureScheme)),
+        }
+    }
+
     /// Generate encryption key
     #[instrument(skip(self))]
     pub async fn generate_encryption_key(&self, algorithm: EncryptionAlgorithm) -> Result<String> {
@@ -451,16 +539,36 @@ impl CryptoProvider {
         Ok(new_key_id)
     }
 
+    /// Limit `operation` on a key to `max_operations` per `time_window`
+    pub async fn set_rate_limit(
+        &self,
+        key_id: &str,
+        operation: KeyOperation,
+        max_operations: u32,
+        time_window: Duration,
+    ) -> Result<()> {
+        self.key_manager.update_access_policy(key_id, |policy| {
+            policy.rate_limits.insert(operation, RateLimit {
+                max_operations,
+                time_window,
+                current_count: 0,
+                window_start: SystemTime::now(),
+            });
+        }).await
+    }
+
+    /// Restrict a key to the given hours and days (UTC), or lift the restriction with `None`
+    pub async fn set_time_restriction(&self, key_id: &str, restriction: Option<TimeRestriction>) -> Result<()> {
+        self.key_manager.update_access_policy(key_id, |policy| policy.time_restrictions = restriction).await
+    }
+
     /// Health check
     pub async fn is_healthy(&self) -> bool {
         self.key_manager.is_healthy().await && self.secure_random.is_healthy().await
     }
 
     async fn generate_key_id(&self) -> String {
-        format!("key-{}-{}",
-                SystemTime::now().duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default().as_millis(),
-                rand::random::<u32>())
+        self.id_generator.next_id("key")
     }
 
     async fn check_key_access(&self, key_id: &str, operation: KeyOperation) -> Result<()> {
@@ -471,18 +579,18 @@ impl CryptoProvider {
             return Err(BridgeError::Security(SecurityError::KeyAccessDenied));
         }
 
-        // Check rate limits
-        if let Some(rate_limit) = metadata.access_policy.rate_limits.get(&operation) {
-            // TODO: Implement actual rate limiting
-            debug!("Checking rate limit for operation: {:?}", operation);
-        }
-
-        // Check time restrictions
+        // Check time restrictions before counting against the rate limit, so
+        // refused operations do not use up the budget
         if let Some(time_restriction) = &metadata.access_policy.time_restrictions {
-            // TODO: Implement time-based access control
-            debug!("Checking time restrictions for key: {}", key_id);
+            if !time_restriction.allows(chrono::Utc::now()) {
+                warn!("Key {} used outside its allowed hours", key_id);
+                return Err(BridgeError::Security(SecurityError::KeyAccessDenied));
+            }
         }
 
+        // Check rate limits
+        self.key_manager.consume_rate_limit(key_id, &operation, SystemTime::now()).await?;
+
         Ok(())
     }
 }
@@ -560,6 +668,37 @@ impl KeyManager {
             .ok_or(BridgeError::Security(SecurityError::KeyNotFound))
     }
 
+    async fn update_access_policy(&self, key_id: &str, update: impl FnOnce(&mut AccessPolicy)) -> Result<()> {
+        let mut store = self.key_store.write().await;
+        let metadata = store.key_metadata.get_mut(key_id)
+            .ok_or(BridgeError::Security(SecurityError::KeyNotFound))?;
+        update(&mut metadata.access_policy);
+        Ok(())
+    }
+
+    /// Count one `operation` against the key's rate limit, if it has one.
+    /// Checked and counted under the store lock so concurrent callers cannot
+    /// overshoot the limit.
+    async fn consume_rate_limit(&self, key_id: &str, operation: &KeyOperation, now: SystemTime) -> Result<()> {
+        let mut store = self.key_store.write().await;
+        let metadata = store.key_metadata.get_mut(key_id)
+            .ok_or(BridgeError::Security(SecurityError::KeyNotFound))?;
+        let Some(rate_limit) = metadata.access_policy.rate_limits.get_mut(operation) else {
+            return Ok(());
+        };
+
+        if now.duration_since(rate_limit.window_start).unwrap_or_default() >= rate_limit.time_window {
+            rate_limit.window_start = now;
+            rate_limit.current_count = 0;
+        }
+        if rate_limit.current_count >= rate_limit.max_operations {
+            warn!("Rate limit of {} {:?} operations exceeded for key {}", rate_limit.max_operations, operation, key_id);
+            return Err(BridgeError::Security(SecurityError::KeyAccessDenied));
+        }
+        rate_limit.current_count += 1;
+        Ok(())
+    }
+
     async fn update_key_usage(&self, key_id: &str) -> Result<()> {
         let mut store = self.key_store.write().await;
 
@@ -585,53 +724,67 @@ impl KeyManager {
 
 impl SecureRandom {
     async fn new() -> Result<Self> {
-        let entropy_pool = Arc::new(RwLock::new(EntropyPool {
-            pool: vec![0; 4096], // 4KB pool
-            pool_size: 4096,
+        Self::with_buffer(4096).await // 4KB buffer
+    }
+
+    /// Buffer up to `buffer_size` OS bytes for small requests (0 = read
+    /// every request straight from the OS)
+    async fn with_buffer(buffer_size: usize) -> Result<Self> {
+        let mut pool = EntropyPool {
+            pool: vec![0; buffer_size],
+            pool_size: buffer_size,
+            consumed: 0,
             last_refresh: SystemTime::now(),
             refresh_interval: Duration::from_secs(300), // 5 minutes
-        }));
-
-        let random = Self { entropy_pool };
-        random.refresh_entropy().await?;
+        };
+        Self::refill(&mut pool)?;
 
-        Ok(random)
+        Ok(Self { entropy_pool: Arc::new(RwLock::new(pool)) })
     }
 
     pub async fn generate_bytes(&self, size: usize) -> Result<Vec<u8>> {
-        // Check if entropy needs refresh
-        {
-            let pool = self.entropy_pool.read().await;
-            if pool.last_refresh.elapsed().unwrap_or_default() > pool.refresh_interval {
-                drop(pool);
-                self.refresh_entropy().await?;
-            }
+        let mut bytes = vec![0u8; size];
+
+        let mut pool = self.entropy_pool.write().await;
+        if size > pool.pool_size {
+            drop(pool);
+            Self::fill_from_os(&mut bytes)?;
+            return Ok(bytes);
         }
 
-        // Generate random bytes
-        let mut bytes = vec![0u8; size];
-        for i in 0..size {
-            bytes[i] = rand::random::<u8>();
+        let stale = pool.last_refresh.elapsed().unwrap_or_default() > pool.refresh_interval;
+        if stale || pool.pool_size - pool.consumed < size {
+            Self::refill(&mut pool)?;
         }
 
+        // Wipe what is handed out so no bytes are served twice
+        let start = pool.consumed;
+        let served = &mut pool.pool[start..start + size];
+        bytes.copy_from_slice(served);
+        served.fill(0);
+        pool.consumed += size;
+
         Ok(bytes)
     }
 
-    async fn refresh_entropy(&self) -> Result<()> {
-        let mut pool = self.entropy_pool.write().await;
-
-        // Refresh entropy pool
-        for i in 0..pool.pool_size {
-            pool.pool[i] = rand::random::<u8>();
-        }
-
+    fn refill(pool: &mut EntropyPool) -> Result<()> {
+        Self::fill_from_os(&mut pool.pool)?;
+        pool.consumed = 0;
         pool.last_refresh = SystemTime::now();
         Ok(())
     }
 
+    fn fill_from_os(bytes: &mut [u8]) -> Result<()> {
+        use rand::RngCore;
+
+        rand::rngs::OsRng.try_fill_bytes(bytes).map_err(|e| {
+            BridgeError::Security(SecurityError::CryptographicOperation(format!("OS random source unavailable: {}", e)))
+        })
+    }
+
+    /// Healthy while the OS source can be read
     async fn is_healthy(&self) -> bool {
-        let pool = self.entropy_pool.read().await;
-        pool.last_refresh.elapsed().unwrap_or_default() < Duration::from_secs(600) // 10 minutes
+        Self::fill_from_os(&mut [0u8; 16]).is_ok()
     }
 }
 
@@ -698,40 +851,76 @@ impl SignatureProvider for Ed25519Provider {
 #[async_trait::async_trait]
 impl SignatureProvider for Secp256k1Provider {
     async fn generate_keypair(&self) -> Result<(Vec<u8>, Vec<u8>)> {
-        // TODO: Use actual Secp256k1 implementation
-        Ok((vec![0; 32], vec![0; 33]))
+        let (secret_key, public_key) = self.secp256k1.generate_keypair(&mut secp256k1::rand::rngs::OsRng);
+        Ok((secret_key.secret_bytes().to_vec(), public_key.serialize().to_vec()))
     }
 
-    async fn sign(&self, _private_key: &[u8], _message: &[u8]) -> Result<Vec<u8>> {
-        // TODO: Implement actual Secp256k1 signing
-        Ok(vec![0; 64])
+    async fn sign(&self, private_key: &[u8], message: &[u8]) -> Result<Vec<u8>> {
+        let secret_key = secp256k1::SecretKey::from_slice(private_key).map_err(Self::invalid)?;
+        let signature = self.secp256k1.sign_ecdsa_recoverable(&Self::message_digest(message), &secret_key);
+        let (recovery_id, compact) = signature.serialize_compact();
+
+        let mut bytes = compact.to_vec();
+        bytes.push(recovery_id.to_i32() as u8);
+        Ok(bytes)
     }
 
-    async fn verify(&self, _public_key: &[u8], _message: &[u8], _signature: &[u8]) -> Result<bool> {
-        // TODO: Implement actual Secp256k1 verification
-        Ok(true)
+    async fn verify(&self, public_key: &[u8], message: &[u8], signature: &[u8]) -> Result<bool> {
+        if signature.len() != Self::COMPACT_SIGNATURE_SIZE && signature.len() != Self::RECOVERABLE_SIGNATURE_SIZE {
+            return Err(Self::invalid(format!("signature must be 64 or 65 bytes, got {}", signature.len())));
+        }
+        let public_key = secp256k1::PublicKey::from_slice(public_key).map_err(Self::invalid)?;
+        // The recovery byte is not needed to verify against a known key
+        let signature = secp256k1::ecdsa::Signature::from_compact(&signature[..Self::COMPACT_SIGNATURE_SIZE])
+            .map_err(Self::invalid)?;
+
+        Ok(self.secp256k1.verify_ecdsa(&Self::message_digest(message), &signature, &public_key).is_ok())
+    }
+
+    async fn recover_public_key(&self, message: &[u8], signature: &[u8]) -> Result<Vec<u8>> {
+        if signature.len() != Self::RECOVERABLE_SIGNATURE_SIZE {
+            return Err(Self::invalid(format!("recovery needs a 65-byte signature, got {}", signature.len())));
+        }
+        let recovery_id = Self::recovery_id(signature[Self::COMPACT_SIGNATURE_SIZE])?;
+        let signature = RecoverableSignature::from_compact(&signature[..Self::COMPACT_SIGNATURE_SIZE], recovery_id)
+            .map_err(Self::invalid)?;
+
+        let public_key = self.secp256k1.recover_ecdsa(&Self::message_digest(message), &signature)
+            .map_err(Self::invalid)?;
+        Ok(public_key.serialize().to_vec())
     }
 
     fn key_size(&self) -> usize { 32 }
-    fn signature_size(&self) -> usize { 64 }
+    fn signature_size(&self) -> usize { Self::RECOVERABLE_SIGNATURE_SIZE }
 }
 
 // Implement encryption providers
 #[async_trait::async_trait]
 impl EncryptionAlgorithmProvider for AES256GCMProvider {
     async fn generate_key(&self) -> Result<Vec<u8>> {
-        // TODO: Generate actual AES-256 key
-        Ok(vec![0; 32])
+        Ok(Aes256Gcm::generate_key(aes_gcm::aead::OsRng).to_vec())
     }
 
-    async fn encrypt(&self, _key: &[u8], _nonce: &[u8], plaintext: &[u8], _associated_data: &[u8]) -> Result<Vec<u8>> {
-        // TODO: Implement actual AES-256-GCM encryption
-        Ok(plaintext.to_vec())
+    /// Ciphertext with the 16-byte authentication tag appended
+    async fn encrypt(&self, key: &[u8], nonce: &[u8], plaintext: &[u8], associated_data: &[u8]) -> Result<Vec<u8>> {
+        let fail = |reason: &str| BridgeError::Security(SecurityError::EncryptionFailed(reason.to_string()));
+        let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| fail("AES-256-GCM keys are 32 bytes"))?;
+        if nonce.len() != self.nonce_size() {
+            return Err(fail("AES-256-GCM nonces are 12 bytes"));
+        }
+        cipher.encrypt(Nonce::from_slice(nonce), Payload { msg: plaintext, aad: associated_data })
+            .map_err(|_| fail("AES-256-GCM encryption failed"))
     }
 
-    async fn decrypt(&self, _key: &[u8], _nonce: &[u8], ciphertext: &[u8], _associated_data: &[u8]) -> Result<Vec<u8>> {
-        // TODO: Implement actual AES-256-GCM decryption
-        Ok(ciphertext.to_vec())
+    /// Fails unless the tag authenticates both the ciphertext and `associated_data`
+    async fn decrypt(&self, key: &[u8], nonce: &[u8], ciphertext: &[u8], associated_data: &[u8]) -> Result<Vec<u8>> {
+        let fail = |reason: &str| BridgeError::Security(SecurityError::DecryptionFailed(reason.to_string()));
+        let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| fail("AES-256-GCM keys are 32 bytes"))?;
+        if nonce.len() != self.nonce_size() {
+            return Err(fail("AES-256-GCM nonces are 12 bytes"));
+        }
+        cipher.decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: associated_data })
+            .map_err(|_| fail("authentication tag mismatch"))
     }
 
     fn key_size(&self) -> usize { 32 }
@@ -741,18 +930,29 @@ impl EncryptionAlgorithmProvider for AES256GCMProvider {
 #[async_trait::async_trait]
 impl EncryptionAlgorithmProvider for ChaCha20Poly1305Provider {
     async fn generate_key(&self) -> Result<Vec<u8>> {
-        // TODO: Generate actual ChaCha20 key
-        Ok(vec![0; 32])
+        Ok(ChaCha20Poly1305::generate_key(chacha20poly1305::aead::OsRng).to_vec())
     }
 
-    async fn encrypt(&self, _key: &[u8], _nonce: &[u8], plaintext: &[u8], _associated_data: &[u8]) -> Result<Vec<u8>> {
-        // TODO: Implement actual ChaCha20-Poly1305 encryption
-        Ok(plaintext.to_vec())
+    /// Ciphertext with the 16-byte Poly1305 tag appended
+    async fn encrypt(&self, key: &[u8], nonce: &[u8], plaintext: &[u8], associated_data: &[u8]) -> Result<Vec<u8>> {
+        let fail = |reason: &str| BridgeError::Security(SecurityError::EncryptionFailed(reason.to_string()));
+        let cipher = ChaCha20Poly1305::new_from_slice(key).map_err(|_| fail("ChaCha20-Poly1305 keys are 32 bytes"))?;
+        if nonce.len() != self.nonce_size() {
+            return Err(fail("ChaCha20-Poly1305 nonces are 12 bytes"));
+        }
+        cipher.encrypt(chacha20poly1305::Nonce::from_slice(nonce), Payload { msg: plaintext, aad: associated_data })
+            .map_err(|_| fail("ChaCha20-Poly1305 encryption failed"))
     }
 
-    async fn decrypt(&self, _key: &[u8], _nonce: &[u8], ciphertext: &[u8], _associated_data: &[u8]) -> Result<Vec<u8>> {
-        // TODO: Implement actual ChaCha20-Poly1305 decryption
-        Ok(ciphertext.to_vec())
+    /// Fails unless the tag authenticates both the ciphertext and `associated_data`
+    async fn decrypt(&self, key: &[u8], nonce: &[u8], ciphertext: &[u8], associated_data: &[u8]) -> Result<Vec<u8>> {
+        let fail = |reason: &str| BridgeError::Security(SecurityError::DecryptionFailed(reason.to_string()));
+        let cipher = ChaCha20Poly1305::new_from_slice(key).map_err(|_| fail("ChaCha20-Poly1305 keys are 32 bytes"))?;
+        if nonce.len() != self.nonce_size() {
+            return Err(fail("ChaCha20-Poly1305 nonces are 12 bytes"));
+        }
+        cipher.decrypt(chacha20poly1305::Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: associated_data })
+            .map_err(|_| fail("authentication tag mismatch"))
     }
 
     fn key_size(&self) -> usize { 32 }
@@ -791,4 +991,187 @@ mod tests {
         // Random bytes should be different (with very high probability)
         assert_ne!(bytes1, bytes2);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_secure_random_buffer_serves_each_byte_once() {
+        let random = SecureRandom::with_buffer(64).await.unwrap();
+        assert!(random.is_healthy().await);
+
+        // Draining and refilling the buffer never repeats output
+        let mut chunks = Vec::new();
+        for _ in 0..8 {
+            chunks.push(random.generate_bytes(24).await.unwrap());
+        }
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk.len(), 24);
+            assert!(chunks[i + 1..].iter().all(|other| other != chunk));
+        }
+
+        // Requests larger than the buffer, or with no buffer at all, go straight to the OS
+        assert_eq!(random.generate_bytes(256).await.unwrap().len(), 256);
+        let unbuffered = SecureRandom::with_buffer(0).await.unwrap();
+        assert_ne!(unbuffered.generate_bytes(32).await.unwrap(), vec![0; 32]);
+    }
+
+    #[tokio::test]
+    async fn test_secp256k1_sign_verify_and_recover() {
+        let provider = CryptoProvider::new(GuardianConfig::default()).await.unwrap();
+        let (key_id, public_key) = provider.generate_signing_keypair(SignatureScheme::Secp256k1).await.unwrap();
+        assert_eq!(public_key.key_data.len(), 33);
+
+        let message = b"bridge 100 GCC to ethereum";
+        let signature = provider.sign(&key_id, message).await.unwrap();
+        assert_eq!(signature.len(), 65);
+        assert!(provider.verify(&key_id, message, &signature).await.unwrap());
+        assert!(provider.verify(&key_id, message, &signature[..64]).await.unwrap());
+        assert!(!provider.verify(&key_id, b"bridge 999 GCC to ethereum", &signature).await.unwrap());
+
+        // Recovery accepts raw and Ethereum-style recovery ids
+        let recovered = provider.recover_public_key(SignatureScheme::Secp256k1, message, &signature).await.unwrap();
+        assert_eq!(recovered, public_key.key_data);
+        let mut ethereum_style = signature.clone();
+        ethereum_style[64] += 27;
+        let recovered = provider.recover_public_key(SignatureScheme::Secp256k1, message, &ethereum_style).await.unwrap();
+        assert_eq!(recovered, public_key.key_data);
+
+        // Lengths and recovery ids are checked up front
+        assert!(provider.recover_public_key(SignatureScheme::Secp256k1, message, &signature[..64]).await.is_err());
+        assert!(provider.verify(&key_id, message, &signature[..63]).await.is_err());
+        let mut bad_id = signature.clone();
+        bad_id[64] = 5;
+        assert!(provider.recover_public_key(SignatureScheme::Secp256k1, message, &bad_id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_signing_rate_limited_per_window() {
+        let provider = CryptoProvider::new(GuardianConfig::default()).await.unwrap();
+        let (key_id, _) = provider.generate_signing_keypair(SignatureScheme::Ed25519).await.unwrap();
+        let start = SystemTime::now();
+        provider.set_rate_limit(&key_id, KeyOperation::Sign, 3, Duration::from_secs(60)).await.unwrap();
+
+        for _ in 0..3 {
+            provider.sign(&key_id, b"message").await.unwrap();
+        }
+        let denied = provider.sign(&key_id, b"message").await.unwrap_err();
+        assert!(matches!(denied, BridgeError::Security(SecurityError::KeyAccessDenied)));
+
+        // Other operations are not counted against the signing limit
+        provider.key_manager.consume_rate_limit(&key_id, &KeyOperation::Verify, start).await.unwrap();
+
+        // A new window starts once the old one has passed
+        let later = start + Duration::from_secs(61);
+        provider.key_manager.consume_rate_limit(&key_id, &KeyOperation::Sign, later).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_time_restricted_operations_do_not_use_rate_budget() {
+        let provider = CryptoProvider::new(GuardianConfig::default()).await.unwrap();
+        let (key_id, _) = provider.generate_signing_keypair(SignatureScheme::Ed25519).await.unwrap();
+        provider.set_rate_limit(&key_id, KeyOperation::Sign, 1, Duration::from_secs(3600)).await.unwrap();
+
+        // No day is allowed, so every attempt is refused
+        let closed = TimeRestriction { start_hour: 0, end_hour: 0, allowed_days: Vec::new() };
+        provider.set_time_restriction(&key_id, Some(closed)).await.unwrap();
+        for _ in 0..3 {
+            assert!(provider.sign(&key_id, b"message").await.is_err());
+        }
+
+        // ...and none of those attempts counted against the limit
+        provider.set_time_restriction(&key_id, None).await.unwrap();
+        provider.sign(&key_id, b"message").await.unwrap();
+        assert!(provider.sign(&key_id, b"message").await.is_err());
+
+        assert!(provider.set_time_restriction("missing", None).await.is_err());
+    }
+
+    #[test]
+    fn test_time_restrictions_include_overnight_windows() {
+        use chrono::TimeZone;
+        // 2024-06-03 is a Monday
+        let at = |day: u32, hour: u32| chrono::Utc.with_ymd_and_hms(2024, 6, day, hour, 30, 0).unwrap();
+        let business = TimeRestriction { start_hour: 9, end_hour: 17, allowed_days: vec![1, 2, 3, 4, 5] };
+        assert!(business.allows(at(3, 9)));
+        assert!(business.allows(at(3, 16)));
+        assert!(!business.allows(at(3, 17)));
+        assert!(!business.allows(at(3, 8)));
+        // Sunday
+        assert!(!business.allows(at(2, 12)));
+
+        let overnight = TimeRestriction { start_hour: 22, end_hour: 6, allowed_days: (0..7).collect() };
+        assert!(overnight.allows(at(3, 23)));
+        assert!(overnight.allows(at(4, 5)));
+        assert!(!overnight.allows(at(3, 6)));
+        assert!(!overnight.allows(at(3, 12)));
+    }
+
+    #[tokio::test]
+    async fn test_aes256gcm_authenticates_ciphertext_and_aad() {
+        let aes = AES256GCMProvider;
+        let key = aes.generate_key().await.unwrap();
+        let nonce = [7u8; 12];
+        assert_eq!(key.len(), 32);
+        assert_ne!(key, vec![0; 32]);
+
+        let ciphertext = aes.encrypt(&key, &nonce, b"bridge secret", b"header").await.unwrap();
+        assert_eq!(ciphertext.len(), b"bridge secret".len() + 16);
+        assert_ne!(&ciphertext[..13], b"bridge secret");
+        assert_eq!(aes.decrypt(&key, &nonce, &ciphertext, b"header").await.unwrap(), b"bridge secret");
+
+        let decrypt_error = |result: Result<Vec<u8>>| {
+            matches!(result, Err(BridgeError::Security(SecurityError::DecryptionFailed(_))))
+        };
+        let other_key = aes.generate_key().await.unwrap();
+        assert!(decrypt_error(aes.decrypt(&other_key, &nonce, &ciphertext, b"header").await));
+        assert!(decrypt_error(aes.decrypt(&key, &nonce, &ciphertext, b"other header").await));
+
+        let mut tampered = ciphertext.clone();
+        tampered[0] ^= 1;
+        assert!(decrypt_error(aes.decrypt(&key, &nonce, &tampered, b"header").await));
+
+        // Through the provider, keys are looked up by id and nonces are generated
+        let provider = CryptoProvider::new(GuardianConfig::default()).await.unwrap();
+        let key_id = provider.generate_encryption_key(EncryptionAlgorithm::AES256GCM).await.unwrap();
+        let (sealed, nonce) = provider.encrypt(&key_id, b"payload", b"aad").await.unwrap();
+        assert_eq!(provider.decrypt(&key_id, &sealed, &nonce, b"aad").await.unwrap(), b"payload");
+        assert!(provider.decrypt(&key_id, &sealed, &nonce, b"tampered").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_chacha20poly1305_authenticates_ciphertext_and_aad() {
+        let chacha = ChaCha20Poly1305Provider;
+        let key = chacha.generate_key().await.unwrap();
+        let nonce = [7u8; 12];
+        assert_eq!(key.len(), 32);
+        assert_ne!(key, vec![0; 32]);
+
+        let ciphertext = chacha.encrypt(&key, &nonce, b"bridge secret", b"header").await.unwrap();
+        assert_eq!(ciphertext.len(), b"bridge secret".len() + 16);
+        assert_ne!(&ciphertext[..13], b"bridge secret");
+        assert_eq!(chacha.decrypt(&key, &nonce, &ciphertext, b"header").await.unwrap(), b"bridge secret");
+
+        let decrypt_error = |result: Result<Vec<u8>>| {
+            matches!(result, Err(BridgeError::Security(SecurityError::DecryptionFailed(_))))
+        };
+        let other_key = chacha.generate_key().await.unwrap();
+        assert!(decrypt_error(chacha.decrypt(&other_key, &nonce, &ciphertext, b"header").await));
+        assert!(decrypt_error(chacha.decrypt(&key, &nonce, &ciphertext, b"other header").await));
+
+        let mut tampered = ciphertext.clone();
+        tampered[0] ^= 1;
+        assert!(decrypt_error(chacha.decrypt(&key, &nonce, &tampered, b"header").await));
+
+        // Bad key and nonce lengths are errors, not panics
+        assert!(matches!(
+            chacha.encrypt(&key, &[0u8; 8], b"bridge secret", b"").await,
+            Err(BridgeError::Security(SecurityError::EncryptionFailed(_)))
+        ));
+        assert!(decrypt_error(chacha.decrypt(&key, &[0u8; 24], &ciphertext, b"header").await));
+        assert!(decrypt_error(chacha.decrypt(&key[..16], &nonce, &ciphertext, b"header").await));
+
+        let provider = CryptoProvider::new(GuardianConfig::default()).await.unwrap();
+        let key_id = provider.generate_encryption_key(EncryptionAlgorithm::ChaCha20Poly1305).await.unwrap();
+        let (sealed, nonce) = provider.encrypt(&key_id, b"payload", b"aad").await.unwrap();
+        assert_eq!(provider.decrypt(&key_id, &sealed, &nonce, b"aad").await.unwrap(), b"payload");
+        assert!(provider.decrypt(&key_id, &sealed, &nonce, b"tampered").await.is_err());
+    }
+}
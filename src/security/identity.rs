@@ -3,6 +3,10 @@ Identity management and verification system
 
 Implements DID-based identity verification, trust scoring, and privacy-preserving
 identity attestation for zero-trust security.
+
+Trust decays once a verification is older than the configured grace period,
+halving every half-life, and faster for identities with recent violations.
+A stale identity drifts below the trust threshold and has to re-verify.
 */
 
 use crate::error::{BridgeError, Result, SecurityError};
@@ -125,6 +129,28 @@ struct ViolationRecord {
 /// Trust calculation engine
 struct TrustCalculator {
     base_weights: TrustWeights,
+    decay: TrustDecayConfig,
+}
+
+/// Trust decay over time since last verification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustDecayConfig {
+    /// How long a verification keeps full trust
+    pub grace_period: Duration,
+    /// Time after the grace period for trust to halve
+    pub half_life: Duration,
+    /// Violations this recent shorten the half-life
+    pub violation_window: Duration,
+}
+
+impl Default for TrustDecayConfig {
+    fn default() -> Self {
+        Self {
+            grace_period: Duration::from_secs(7 * 24 * 60 * 60), // 7 days
+            half_life: Duration::from_secs(14 * 24 * 60 * 60),   // 14 days
+            violation_window: Duration::from_secs(30 * 24 * 60 * 60), // 30 days
+        }
+    }
 }
 
 /// Trust scoring weights
@@ -207,6 +233,7 @@ impl IdentityManager {
                 age_factor: 0.1,
                 violation_penalty: 0.1,
             },
+            decay: config.trust_decay.clone(),
         };
 
         Ok(Self {
@@ -322,6 +349,7 @@ impl IdentityManager {
                     identity.verification_methods.push(verification_record);
                     identity.verification_level = (identity.verification_level + 1).min(10);
                     identity.last_verified = SystemTime::now();
+                    store.verification_cache.remove(address);
 
                     // Update status if sufficient verification
                     if identity.verification_level >= self.config.trust_level_threshold {
@@ -440,7 +468,7 @@ impl IdentityManager {
                 last_updated: SystemTime::now(),
             });
 
-        let trust_score = self.trust_calculator.calculate_trust(identity, &reputation);
+        let trust_score = self.trust_calculator.calculate_trust(identity, &reputation, SystemTime::now());
         Ok(trust_score.min(10.0).max(0.0) as u8)
     }
 
@@ -459,7 +487,7 @@ impl IdentityManager {
 }
 
 impl TrustCalculator {
-    fn calculate_trust(&self, identity: &Identity, reputation: &ReputationData) -> f64 {
+    fn calculate_trust(&self, identity: &Identity, reputation: &ReputationData, now: SystemTime) -> f64 {
         let verification_score = (identity.verification_level as f64 / 10.0) * self.base_weights.verification_level;
         let reputation_score = (reputation.base_score / 10.0) * self.base_weights.reputation_score;
         let attestation_score = (reputation.positive_attestations as f64 / 10.0).min(1.0) * self.base_weights.attestation_count;
@@ -474,7 +502,27 @@ impl TrustCalculator {
             .sum::<f64>() * self.base_weights.violation_penalty;
 
         let total_score = verification_score + reputation_score + attestation_score + age_score - violation_penalty;
-        total_score.min(10.0).max(0.0)
+        (total_score * self.decay_factor(identity, reputation, now)).min(10.0).max(0.0)
+    }
+
+    /// Fraction of trust retained given how stale the last verification is
+    fn decay_factor(&self, identity: &Identity, reputation: &ReputationData, now: SystemTime) -> f64 {
+        let since_verified = now.duration_since(identity.last_verified).unwrap_or_default();
+        let Some(stale_for) = since_verified.checked_sub(self.decay.grace_period) else {
+            return 1.0;
+        };
+
+        // Each recent unresolved violation shortens the half-life
+        let recent_violations = reputation.violation_history.iter()
+            .filter(|v| !v.resolved)
+            .filter(|v| now.duration_since(v.timestamp).unwrap_or_default() <= self.decay.violation_window)
+            .count();
+        let half_life = self.decay.half_life.as_secs_f64() / (1 + recent_violations) as f64;
+        if half_life <= 0.0 {
+            return 0.0;
+        }
+
+        0.5f64.powf(stale_for.as_secs_f64() / half_life)
     }
 }
 
@@ -573,4 +621,49 @@ mod tests {
         assert!(result.identity.is_some());
         assert!(result.trust_level <= 10);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_stale_verification_decays_trust() {
+        let config = GuardianConfig::default();
+        let decay = config.trust_decay.clone();
+        let manager = IdentityManager::new(config).await.unwrap();
+        let address = Address::from("0x1234567890123456789012345678901234567890");
+
+        manager.verify_identity(&address).await.unwrap();
+        for _ in 0..10 {
+            manager.add_verification(&address, "did", "guardian1", vec![1]).await.unwrap();
+        }
+        let identity = manager.identity_store.read().await.identities[&address].clone();
+        let reputation = ReputationData {
+            base_score: 10.0,
+            transaction_history: Vec::new(),
+            violation_history: Vec::new(),
+            positive_attestations: 10,
+            last_updated: SystemTime::now(),
+        };
+        let calculator = &manager.trust_calculator;
+        let verified_at = identity.last_verified;
+
+        // Full trust through the grace period
+        let fresh = calculator.calculate_trust(&identity, &reputation, verified_at);
+        assert!(fresh > 0.0);
+        assert_eq!(calculator.calculate_trust(&identity, &reputation, verified_at + decay.grace_period), fresh);
+
+        // Two half-lives past the grace period leave a quarter
+        let stale_at = verified_at + decay.grace_period + decay.half_life * 2;
+        let stale = calculator.calculate_trust(&identity, &reputation, stale_at);
+        assert!((stale - fresh / 4.0).abs() < 1e-9, "fresh {} stale {}", fresh, stale);
+
+        // A recent unresolved violation halves the half-life
+        let mut violated = reputation.clone();
+        violated.violation_history.push(ViolationRecord {
+            violation_type: "double_spend".to_string(),
+            severity: 0.0,
+            description: String::new(),
+            timestamp: stale_at,
+            resolved: false,
+        });
+        let decayed = calculator.calculate_trust(&identity, &violated, stale_at);
+        assert!((decayed - fresh / 16.0).abs() < 1e-9, "fresh {} decayed {}", fresh, decayed);
+    }
+}
@@ -5,10 +5,11 @@ Comprehensive zero-trust security implementation for GhostBridge with identity
 verification, privacy policy enforcement, and audit logging.
 */
 
+use crate::calldata::CalldataDecoder;
 use crate::error::{BridgeError, Result, SecurityError};
 use crate::types::{Address, Transaction, U256};
 use gcrypt::protocols::{Ed25519, Secp256k1};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use tokio::sync::RwLock;
@@ -20,12 +21,15 @@ pub mod identity;
 pub mod policy;
 pub mod audit;
 pub mod crypto;
+pub mod multisig;
 
 pub use guardian::GuardianFramework;
-pub use identity::{IdentityManager, Identity, DID};
+pub use identity::{IdentityManager, Identity, TrustDecayConfig, DID};
 pub use policy::{PolicyEngine, PrivacyPolicy, PolicyRule};
-pub use audit::{AuditLogger, AuditEvent, SecurityAudit};
-pub use crypto::{CryptoProvider, KeyManager, SecureRandom};
+pub use audit::{AuditLogger, AuditEvent, AuditFileSink, AuditFlushConfig, AuditSink, BufferedAuditSink, SecurityAudit, AUDIT_SCHEMA_VERSION};
+pub use crypto::{CryptoProvider, KeyManager, KeyOperation, SecureRandom, TimeRestriction};
+pub use multisig::{ApprovalCertificate, ApprovalStatus, GuardianWeight, MultisigConfig, MultisigTracker};
+pub use crate::metrics::PatternTrackingSnapshot;
 
 /// Guardian Framework configuration
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -58,6 +62,22 @@ pub struct GuardianConfig {
     pub max_transaction_amount: U256,
     pub suspicious_activity_threshold: u32,
     pub automatic_lockdown: bool,
+    /// Addresses whose transaction patterns are kept; the least recently
+    /// active are evicted beyond this
+    pub max_tracked_patterns: usize,
+    /// Destinations remembered per address as its usual counterparties
+    pub max_typical_destinations: usize,
+
+    /// Guardian quorum approvals for high-value transactions
+    pub multisig: MultisigConfig,
+
+    /// How trust fades as a verification goes stale
+    pub trust_decay: TrustDecayConfig,
+
+    /// Batching and fsync policy for the persisted audit log
+    pub audit_flush: AuditFlushConfig,
+    /// JSON-lines file the audit trail is persisted to; kept in memory only if unset
+    pub audit_log_path: Option<String>,
 }
 
 /// Supported signature schemes
@@ -94,6 +114,12 @@ impl Default for GuardianConfig {
             max_transaction_amount: U256::from(1_000_000 * 10u64.pow(18)), // 1M tokens
             suspicious_activity_threshold: 10,
             automatic_lockdown: true,
+            max_tracked_patterns: 100_000,
+            max_typical_destinations: 24,
+            multisig: MultisigConfig::default(),
+            trust_decay: TrustDecayConfig::default(),
+            audit_flush: AuditFlushConfig::default(),
+            audit_log_path: None,
         }
     }
 }
@@ -108,6 +134,7 @@ pub struct GuardianSecurity {
     crypto_provider: Arc<CryptoProvider>,
     threat_detector: Arc<ThreatDetector>,
     security_state: Arc<RwLock<SecurityState>>,
+    calldata_decoder: Option<Arc<CalldataDecoder>>,
 }
 
 /// Current security state
@@ -117,11 +144,15 @@ struct SecurityState {
     active_incidents: HashMap<String, SecurityIncident>,
     locked_addresses: Vec<Address>,
     quarantined_transactions: Vec<String>,
+    /// Lockdowns initiated so far; a release is signed over the current one
+    lockdowns: u64,
     last_security_scan: SystemTime,
+    /// Guardian votes on high-value transactions
+    multisig_approvals: MultisigTracker,
 }
 
 /// Threat levels
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ThreatLevel {
     Low,
     Medium,
@@ -156,14 +187,22 @@ pub enum IncidentType {
 /// Threat detection system
 pub struct ThreatDetector {
     config: GuardianConfig,
-    pattern_analyzer: PatternAnalyzer,
+    pattern_analyzer: parking_lot::Mutex<PatternAnalyzer>,
     risk_assessor: RiskAssessor,
 }
 
 /// Pattern analysis for anomaly detection
 struct PatternAnalyzer {
     transaction_patterns: HashMap<Address, TransactionPattern>,
+    /// Addresses by last activity tick, oldest first
+    recency: BTreeMap<u64, Address>,
+    next_tick: u64,
+    max_tracked_patterns: usize,
+    max_typical_destinations: usize,
+    evicted_patterns: u64,
     global_patterns: GlobalPattern,
+    /// Transactions with threat indicators per sender since the last scan
+    flagged: HashMap<Address, u32>,
 }
 
 /// Risk assessment engine
@@ -177,9 +216,110 @@ struct RiskAssessor {
 struct TransactionPattern {
     average_amount: U256,
     frequency: f64, // transactions per hour
+    /// Most recent distinct destinations, oldest first
     typical_destinations: Vec<Address>,
-    time_patterns: Vec<u8>, // hours of day (0-23)
+    /// Transactions per hour of day (UTC)
+    hour_histogram: [u64; 24],
+    first_seen: SystemTime,
     last_updated: SystemTime,
+    transaction_count: u64,
+    /// Recency tick of the last transaction, keyed in `PatternAnalyzer::recency`
+    last_active: u64,
+}
+
+/// Approved transactions an address needs before its pattern is a baseline
+const MIN_BASELINE_TRANSACTIONS: u64 = 5;
+
+/// Hour of day (UTC) of `time`
+fn hour_of_day(time: SystemTime) -> usize {
+    (time.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs() / 3600 % 24) as usize
+}
+
+impl PatternAnalyzer {
+    /// Fold an approved transaction into its sender's pattern, evicting the
+    /// least recently active addresses once the cap is exceeded
+    fn record(&mut self, transaction: &Transaction) {
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        let now = SystemTime::now();
+        let amount = transaction.amount.amount.to_u64() as u128;
+
+        let pattern = self.transaction_patterns.entry(transaction.from_address.clone()).or_insert_with(|| TransactionPattern {
+            average_amount: U256::ZERO,
+            frequency: 0.0,
+            typical_destinations: Vec::new(),
+            hour_histogram: [0; 24],
+            first_seen: now,
+            last_updated: now,
+            transaction_count: 0,
+            last_active: tick,
+        });
+        self.recency.remove(&pattern.last_active);
+
+        let count = pattern.transaction_count as u128;
+        let average = (pattern.average_amount.to_u64() as u128 * count + amount) / (count + 1);
+        pattern.average_amount = U256::from(average as u64);
+        pattern.transaction_count += 1;
+
+        // Keep the most recently used destinations, refreshing repeat ones
+        pattern.typical_destinations.retain(|destination| *destination != transaction.to_address);
+        pattern.typical_destinations.push(transaction.to_address.clone());
+        if pattern.typical_destinations.len() > self.max_typical_destinations {
+            pattern.typical_destinations.remove(0);
+        }
+
+        pattern.hour_histogram[hour_of_day(now)] += 1;
+        // Over at least an hour, so a burst of first transactions isn't extrapolated
+        let hours_active = (now.duration_since(pattern.first_seen).unwrap_or_default().as_secs_f64() / 3600.0).max(1.0);
+        pattern.frequency = pattern.transaction_count as f64 / hours_active;
+        pattern.last_updated = now;
+        pattern.last_active = tick;
+        self.recency.insert(tick, transaction.from_address.clone());
+
+        while self.transaction_patterns.len() > self.max_tracked_patterns {
+            let Some((_, address)) = self.recency.pop_first() else { break };
+            self.transaction_patterns.remove(&address);
+            self.evicted_patterns += 1;
+            debug!("Evicted transaction pattern for inactive address {:?}", address);
+        }
+    }
+
+    /// The sender's pattern, once it has enough transactions to be a baseline
+    fn baseline(&self, address: &Address) -> Option<&TransactionPattern> {
+        self.transaction_patterns.get(address)
+            .filter(|pattern| pattern.transaction_count >= MIN_BASELINE_TRANSACTIONS)
+    }
+
+    /// Whether the sender has a baseline and `transaction` goes somewhere outside it
+    fn is_unusual_destination(&self, transaction: &Transaction) -> bool {
+        self.baseline(&transaction.from_address)
+            .is_some_and(|pattern| !pattern.typical_destinations.contains(&transaction.to_address))
+    }
+
+    /// `transaction`'s amount as a multiple of the sender's average; 0 without a baseline
+    fn amount_ratio(&self, transaction: &Transaction) -> f64 {
+        self.baseline(&transaction.from_address).map_or(0.0, |pattern| {
+            transaction.amount.amount.to_f64() / pattern.average_amount.to_f64().max(1.0)
+        })
+    }
+
+    /// How unusual `now`'s hour of day is for the sender, from 0.0 at their
+    /// busiest hour to 1.0 at an hour they have never transacted in; 0
+    /// without a baseline
+    fn off_hours(&self, transaction: &Transaction, now: SystemTime) -> f64 {
+        self.baseline(&transaction.from_address).map_or(0.0, |pattern| {
+            let busiest = pattern.hour_histogram.iter().copied().max().unwrap_or(0).max(1);
+            1.0 - pattern.hour_histogram[hour_of_day(now)] as f64 / busiest as f64
+        })
+    }
+
+    fn snapshot(&self) -> PatternTrackingSnapshot {
+        PatternTrackingSnapshot {
+            tracked_patterns: self.transaction_patterns.len(),
+            max_tracked_patterns: self.max_tracked_patterns,
+            evicted_patterns: self.evicted_patterns,
+        }
+    }
 }
 
 /// Global transaction patterns
@@ -210,7 +350,10 @@ impl GuardianSecurity {
         let guardian_framework = Arc::new(GuardianFramework::new(config.clone()).await?);
         let identity_manager = Arc::new(IdentityManager::new(config.clone()).await?);
         let policy_engine = Arc::new(PolicyEngine::new(config.clone()).await?);
-        let audit_logger = Arc::new(AuditLogger::new(config.clone()).await?);
+        let audit_sink = config.audit_log_path.as_ref().map(|path| {
+            Arc::new(BufferedAuditSink::new(AuditFileSink::new(path), config.audit_flush.clone())) as Arc<dyn AuditSink>
+        });
+        let audit_logger = Arc::new(AuditLogger::new(config.clone(), audit_sink).await?);
         let crypto_provider = Arc::new(CryptoProvider::new(config.clone()).await?);
         let threat_detector = Arc::new(ThreatDetector::new(config.clone()).await?);
 
@@ -219,7 +362,9 @@ impl GuardianSecurity {
             active_incidents: HashMap::new(),
             locked_addresses: Vec::new(),
             quarantined_transactions: Vec::new(),
+            lockdowns: 0,
             last_security_scan: SystemTime::now(),
+            multisig_approvals: MultisigTracker::new(config.multisig.clone()),
         }));
 
         let security = Self {
@@ -231,17 +376,36 @@ impl GuardianSecurity {
             crypto_provider,
             threat_detector,
             security_state,
+            calldata_decoder: None,
         };
 
         info!("Guardian Framework security initialized successfully");
         Ok(security)
     }
 
+    /// Annotate audited transactions with their decoded contract call
+    pub fn with_calldata_decoder(mut self, decoder: Arc<CalldataDecoder>) -> Self {
+        self.calldata_decoder = Some(decoder);
+        self
+    }
+
     /// Perform comprehensive security check on transaction
     #[instrument(skip(self, transaction))]
     pub async fn security_check(&self, transaction: &Transaction) -> Result<SecurityResult> {
         debug!("Performing security check for transaction: {}", transaction.id);
 
+        if self.security_state.read().await.locked_addresses.contains(&transaction.from_address) {
+            warn!("Rejecting transaction {} from locked address {:?}", transaction.id, transaction.from_address);
+            return Ok(SecurityResult {
+                approved: false,
+                trust_score: 0,
+                risk_score: 1.0,
+                violations: vec!["Sender address is locked down".to_string()],
+                required_actions: vec!["Wait for the security lockdown to be released".to_string()],
+                audit_trail: Vec::new(),
+            });
+        }
+
         let mut result = SecurityResult {
             approved: false,
             trust_score: 0,
@@ -271,6 +435,9 @@ impl GuardianSecurity {
         let threat_assessment = self.assess_threat(transaction).await?;
         result.risk_score = threat_assessment.risk_score;
 
+        if !threat_assessment.threat_indicators.is_empty() {
+            self.threat_detector.flag(&transaction.from_address);
+        }
         if threat_assessment.risk_score > 0.8 {
             result.violations.push("High risk transaction detected".to_string());
             result.required_actions.push("Manual review required".to_string());
@@ -290,19 +457,44 @@ impl GuardianSecurity {
             result.required_actions.push("Reduce transaction amount or get approval".to_string());
         }
 
+        // 6. Guardian quorum for high-value transactions
+        if let Some(violation) = self.check_multisig_approval(transaction).await {
+            result.violations.push(violation);
+            result.required_actions.push("Obtain Guardian quorum approval".to_string());
+        }
+
         // Final approval decision
         result.approved = result.violations.is_empty() &&
                           result.trust_score >= self.config.trust_level_threshold &&
                           result.risk_score < 0.8;
 
+        // Only approved activity shapes the sender's baseline
+        if result.approved {
+            self.threat_detector.learn(transaction);
+        }
+
         // Log audit event
+        let mut metadata = HashMap::new();
+        if let Some(decoder) = self.calldata_decoder.as_ref().filter(|_| !transaction.data.is_empty()) {
+            let annotation = decoder.annotate(&transaction.data);
+            metadata.insert("calldata".to_string(), serde_json::to_value(&annotation)
+                .map_err(|e| BridgeError::Serialization(e.into()))?);
+        }
         let audit_event = AuditEvent {
+            schema_version: AUDIT_SCHEMA_VERSION,
+            event_id: String::new(),
             event_type: "security_check".to_string(),
+            category: audit::AuditCategory::SecurityEvent,
+            severity: audit::AuditSeverity::Info,
             transaction_id: Some(transaction.id.to_string()),
             address: Some(transaction.from_address.clone()),
+            user_id: None,
             result: result.approved,
             details: format!("Trust: {}, Risk: {:.2}", result.trust_score, result.risk_score),
+            metadata,
             timestamp: SystemTime::now(),
+            source_system: "guardian".to_string(),
+            correlation_id: None,
         };
 
         self.audit_logger.log_event(audit_event).await?;
@@ -312,6 +504,84 @@ impl GuardianSecurity {
         Ok(result)
     }
 
+    /// Record a Guardian's approval of a high-value transaction
+    #[instrument(skip(self, signature))]
+    pub async fn approve_transaction(
+        &self,
+        transaction_id: &str,
+        guardian_id: &str,
+        signature: Vec<u8>,
+    ) -> Result<ApprovalStatus> {
+        let mut state = self.security_state.write().await;
+        // Held until the lockdown is released; rejecting stays possible
+        if state.quarantined_transactions.iter().any(|id| id == transaction_id) {
+            return Err(BridgeError::Security(SecurityError::TransactionQuarantined {
+                transaction_id: transaction_id.to_string(),
+            }));
+        }
+        let tracker = &mut state.multisig_approvals;
+
+        // Every vote must carry a valid signature from the Guardian's registered key
+        let (key_id, message) = tracker.vote_key(transaction_id, guardian_id)?;
+        if !self.crypto_provider.verify(&key_id, &message, &signature).await? {
+            return Err(BridgeError::Security(SecurityError::SignatureVerificationFailed));
+        }
+
+        let status = tracker.approve(transaction_id, guardian_id, signature, SystemTime::now())?;
+        info!("Guardian {} approved transaction {}: {:?}", guardian_id, transaction_id, status);
+        Ok(status)
+    }
+
+    /// Record a Guardian's rejection of a high-value transaction, signed over
+    /// `MultisigTracker::rejection_message` of the transaction hash
+    #[instrument(skip(self, signature))]
+    pub async fn reject_transaction(
+        &self,
+        transaction_id: &str,
+        guardian_id: &str,
+        signature: Vec<u8>,
+    ) -> Result<ApprovalStatus> {
+        let mut state = self.security_state.write().await;
+        let tracker = &mut state.multisig_approvals;
+
+        let (key_id, message) = tracker.vote_key(transaction_id, guardian_id)?;
+        let message = MultisigTracker::rejection_message(&message);
+        if !self.crypto_provider.verify(&key_id, &message, &signature).await? {
+            return Err(BridgeError::Security(SecurityError::SignatureVerificationFailed));
+        }
+
+        let status = tracker.reject(transaction_id, guardian_id, SystemTime::now())?;
+        info!("Guardian {} rejected transaction {}: {:?}", guardian_id, transaction_id, status);
+        Ok(status)
+    }
+
+    /// Guardian approval status of a transaction, if it required one
+    pub async fn approval_status(&self, transaction_id: &str) -> Option<ApprovalStatus> {
+        let mut state = self.security_state.write().await;
+        state.multisig_approvals.status(transaction_id, SystemTime::now())
+    }
+
+    /// Open or check the Guardian approval request for a high-value transaction,
+    /// returning a violation unless quorum has been reached
+    async fn check_multisig_approval(&self, transaction: &Transaction) -> Option<String> {
+        let mut state = self.security_state.write().await;
+        let tracker = &mut state.multisig_approvals;
+        if !tracker.requires_approval(transaction) {
+            return None;
+        }
+
+        let now = SystemTime::now();
+        tracker.open(transaction, now);
+        match tracker.status(&transaction.id.to_string(), now)? {
+            ApprovalStatus::Approved(_) => None,
+            ApprovalStatus::Pending { approved_weight, quorum_weight } => Some(format!(
+                "Guardian approval pending: weight {} of {}", approved_weight, quorum_weight
+            )),
+            ApprovalStatus::Rejected => Some("Rejected by Guardians".to_string()),
+            ApprovalStatus::Expired => Some("Guardian approval expired".to_string()),
+        }
+    }
+
     /// Verify identity of an address
     async fn verify_identity(&self, address: &Address) -> Result<IdentityResult> {
         self.identity_manager.verify_identity(address).await
@@ -345,33 +615,129 @@ impl GuardianSecurity {
 
         state.threat_level = max_threat.clone();
         state.last_security_scan = SystemTime::now();
+        let critical = state.threat_level == ThreatLevel::Critical;
+        drop(state);
 
         // Handle critical threats
-        if state.threat_level == ThreatLevel::Critical && self.config.automatic_lockdown {
+        if critical && self.config.automatic_lockdown {
             warn!("Critical threat detected - initiating automatic lockdown");
-            self.initiate_lockdown().await?;
+            self.initiate_lockdown(&current_threats).await?;
         }
 
-        drop(state);
-
         debug!("Security monitoring completed");
         Ok(())
     }
 
-    /// Initiate security lockdown
-    async fn initiate_lockdown(&self) -> Result<()> {
+    /// Initiate security lockdown: freeze the addresses implicated in
+    /// `incidents` or flagged by the threat detector, quarantine their
+    /// transactions awaiting Guardian approval, and raise a critical audit event
+    async fn initiate_lockdown(&self, incidents: &[SecurityIncident]) -> Result<()> {
         warn!("Initiating security lockdown");
 
-        // TODO: Implement lockdown procedures:
-        // 1. Freeze suspicious addresses
-        // 2. Quarantine pending transactions
-        // 3. Alert security team
-        // 4. Activate backup systems
+        let mut state = self.security_state.write().await;
+        state.lockdowns += 1;
+        let suspicious = incidents.iter()
+            .flat_map(|incident| incident.affected_addresses.iter().cloned())
+            .chain(self.threat_detector.suspicious_addresses());
+        for address in suspicious {
+            if !state.locked_addresses.contains(&address) {
+                state.locked_addresses.push(address);
+            }
+        }
+
+        let quarantined = state.multisig_approvals.pending_from(&state.locked_addresses);
+        for transaction_id in quarantined {
+            if !state.quarantined_transactions.contains(&transaction_id) {
+                state.quarantined_transactions.push(transaction_id);
+            }
+        }
+
+        for incident in incidents {
+            state.active_incidents.insert(incident.id.clone(), incident.clone());
+        }
+
+        let details = format!(
+            "Lockdown: {} addresses locked, {} transactions quarantined, {} incidents",
+            state.locked_addresses.len(), state.quarantined_transactions.len(), incidents.len(),
+        );
+        drop(state);
 
+        self.log_lockdown_event("security_lockdown", audit::AuditSeverity::Critical, details).await?;
         info!("Security lockdown activated");
         Ok(())
     }
 
+    /// Message Guardians sign, with their registered keys, to release the
+    /// current lockdown
+    pub async fn lockdown_release_message(&self) -> [u8; 32] {
+        MultisigTracker::lockdown_release_message(self.security_state.read().await.lockdowns)
+    }
+
+    /// Lift a lockdown, unlocking addresses and releasing quarantined
+    /// transactions. Needs `(guardian_id, signature)` pairs over
+    /// `lockdown_release_message` from Guardians holding quorum weight.
+    #[instrument(skip(self, approvals))]
+    pub async fn release_lockdown(&self, approvals: &[(String, Vec<u8>)]) -> Result<()> {
+        let mut state = self.security_state.write().await;
+
+        let message = MultisigTracker::lockdown_release_message(state.lockdowns);
+        let mut signers = BTreeSet::new();
+        for (guardian_id, signature) in approvals {
+            let key_id = state.multisig_approvals.guardian_key(guardian_id).ok_or_else(|| {
+                BridgeError::Security(SecurityError::GuardianKeyNotRegistered { guardian_id: guardian_id.clone() })
+            })?;
+            if !self.crypto_provider.verify(key_id, &message, signature).await? {
+                return Err(BridgeError::Security(SecurityError::SignatureVerificationFailed));
+            }
+            signers.insert(guardian_id.clone());
+        }
+        let (approved_weight, quorum_weight) = state.multisig_approvals.weigh(&signers);
+        if approved_weight < quorum_weight {
+            return Err(BridgeError::Security(SecurityError::LockdownQuorumNotReached {
+                approved_weight,
+                quorum_weight,
+            }));
+        }
+
+        let details = format!(
+            "Lockdown released by {:?}: {} addresses unlocked, {} transactions released",
+            signers, state.locked_addresses.len(), state.quarantined_transactions.len(),
+        );
+        state.locked_addresses.clear();
+        state.quarantined_transactions.clear();
+        let now = SystemTime::now();
+        for incident in state.active_incidents.values_mut() {
+            incident.resolved_at.get_or_insert(now);
+        }
+        state.threat_level = ThreatLevel::Low;
+        drop(state);
+        // Reviewed as part of the release; they are flagged afresh if it recurs
+        self.threat_detector.clear_suspicious();
+
+        self.log_lockdown_event("security_lockdown_released", audit::AuditSeverity::Warning, details).await?;
+        info!("Security lockdown released");
+        Ok(())
+    }
+
+    async fn log_lockdown_event(&self, event_type: &str, severity: audit::AuditSeverity, details: String) -> Result<()> {
+        self.audit_logger.log_event(AuditEvent {
+            schema_version: AUDIT_SCHEMA_VERSION,
+            event_id: String::new(),
+            event_type: event_type.to_string(),
+            category: audit::AuditCategory::SecurityEvent,
+            severity,
+            transaction_id: None,
+            address: None,
+            user_id: None,
+            result: true,
+            details,
+            metadata: HashMap::new(),
+            timestamp: SystemTime::now(),
+            source_system: "guardian".to_string(),
+            correlation_id: None,
+        }).await
+    }
+
     /// Get current security status
     pub async fn get_security_status(&self) -> SecurityStatus {
         let state = self.security_state.read().await;
@@ -389,6 +755,26 @@ impl GuardianSecurity {
         }
     }
 
+    /// Senders frozen by the current lockdown
+    pub async fn locked_addresses(&self) -> HashSet<Address> {
+        self.security_state.read().await.locked_addresses.iter().cloned().collect()
+    }
+
+    /// Whether `monitor_activity` should run continuously
+    pub fn real_time_monitoring(&self) -> bool {
+        self.config.real_time_monitoring
+    }
+
+    /// Whether Guardian data minimization is on, so logs should omit personal data
+    pub fn data_minimization(&self) -> bool {
+        self.config.data_minimization
+    }
+
+    /// Per-address pattern tracking and eviction counts
+    pub fn pattern_metrics(&self) -> PatternTrackingSnapshot {
+        self.threat_detector.pattern_metrics()
+    }
+
     /// Health check for security systems
     pub async fn health_check(&self) -> Result<SecurityHealth> {
         let mut health = SecurityHealth::default();
@@ -485,22 +871,28 @@ pub struct SecurityHealth {
 impl ThreatDetector {
     async fn new(config: GuardianConfig) -> Result<Self> {
         Ok(Self {
-            config,
-            pattern_analyzer: PatternAnalyzer {
+            pattern_analyzer: parking_lot::Mutex::new(PatternAnalyzer {
                 transaction_patterns: HashMap::new(),
+                recency: BTreeMap::new(),
+                next_tick: 0,
+                max_tracked_patterns: config.max_tracked_patterns,
+                max_typical_destinations: config.max_typical_destinations.max(1),
+                evicted_patterns: 0,
                 global_patterns: GlobalPattern {
                     daily_volume: U256::ZERO,
                     peak_hours: vec![9, 10, 11, 14, 15, 16], // Business hours
                     common_amounts: vec![],
                     suspicious_addresses: vec![],
                 },
-            },
+                flagged: HashMap::new(),
+            }),
+            config,
             risk_assessor: RiskAssessor {
                 risk_factors: vec![
                     RiskFactor {
                         name: "large_amount".to_string(),
                         weight: 0.3,
-                        threshold: 100_000.0,
+                        threshold: 10.0, // Times the sender's average amount
                         current_value: 0.0,
                     },
                     RiskFactor {
@@ -522,26 +914,100 @@ impl ThreatDetector {
     }
 
     async fn assess_transaction(&self, transaction: &Transaction) -> Result<ThreatAssessment> {
-        // TODO: Implement sophisticated threat assessment
-        let risk_score = 0.1; // Low risk by default
+        self.assess_transaction_at(transaction, SystemTime::now()).await
+    }
+
+    /// Score `transaction` against its sender's learned pattern as of `now`
+    async fn assess_transaction_at(&self, transaction: &Transaction, now: SystemTime) -> Result<ThreatAssessment> {
+        let (unusual_destination, amount_ratio, off_hours) = {
+            let analyzer = self.pattern_analyzer.lock();
+            (
+                analyzer.is_unusual_destination(transaction),
+                analyzer.amount_ratio(transaction),
+                analyzer.off_hours(transaction, now),
+            )
+        };
+
+        let mut risk_score = 0.1; // Low risk by default
+        let mut threat_indicators = Vec::new();
+        let mut mitigation_suggestions = Vec::new();
+        for factor in &self.risk_assessor.risk_factors {
+            let (value, suggestion) = match factor.name.as_str() {
+                "large_amount" => (amount_ratio, "Confirm the amount with the sender"),
+                "unusual_destination" if unusual_destination => (1.0, "Confirm the new destination with the sender"),
+                "off_hours" => (off_hours, "Confirm the sender is active at this hour"),
+                _ => (0.0, ""),
+            };
+            if value >= factor.threshold {
+                risk_score += factor.weight;
+                threat_indicators.push(factor.name.clone());
+                mitigation_suggestions.push(suggestion.to_string());
+            }
+        }
 
         Ok(ThreatAssessment {
             risk_score,
-            threat_indicators: vec![],
-            mitigation_suggestions: vec![],
+            threat_indicators,
+            mitigation_suggestions,
             confidence_level: 0.8,
         })
     }
 
+    /// Learn from an approved transaction
+    fn learn(&self, transaction: &Transaction) {
+        self.pattern_analyzer.lock().record(transaction);
+    }
+
+    /// Count a transaction with threat indicators against its sender
+    fn flag(&self, address: &Address) {
+        *self.pattern_analyzer.lock().flagged.entry(address.clone()).or_insert(0) += 1;
+    }
+
+    fn suspicious_addresses(&self) -> Vec<Address> {
+        self.pattern_analyzer.lock().global_patterns.suspicious_addresses.clone()
+    }
+
+    fn clear_suspicious(&self) {
+        self.pattern_analyzer.lock().global_patterns.suspicious_addresses.clear();
+    }
+
+    fn pattern_metrics(&self) -> PatternTrackingSnapshot {
+        self.pattern_analyzer.lock().snapshot()
+    }
+
+    /// Raise a critical incident for each sender with at least
+    /// `suspicious_activity_threshold` flagged transactions since the last
+    /// scan, marking it suspicious. Counts restart with every scan.
     async fn scan_for_threats(&self) -> Result<Vec<SecurityIncident>> {
-        // TODO: Implement threat scanning
-        Ok(vec![])
+        let threshold = self.config.suspicious_activity_threshold.max(1);
+        let now = SystemTime::now();
+        let mut analyzer = self.pattern_analyzer.lock();
+        let flagged = std::mem::take(&mut analyzer.flagged);
+
+        let mut incidents = Vec::new();
+        for (address, count) in flagged.into_iter().filter(|(_, count)| *count >= threshold) {
+            if !analyzer.global_patterns.suspicious_addresses.contains(&address) {
+                analyzer.global_patterns.suspicious_addresses.push(address.clone());
+            }
+            incidents.push(SecurityIncident {
+                id: format!("suspicious-activity-{}-{}", address, now.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs()),
+                incident_type: IncidentType::AnomalousPattern,
+                severity: ThreatLevel::Critical,
+                affected_addresses: vec![address],
+                description: format!("{} transactions with threat indicators since the last scan", count),
+                detected_at: now,
+                resolved_at: None,
+                mitigation_actions: vec!["Lock down the address".to_string()],
+            });
+        }
+        Ok(incidents)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::fixtures::transfer;
 
     #[test]
     fn test_guardian_config_default() {
@@ -562,4 +1028,275 @@ mod tests {
         let scheme = SignatureScheme::Ed25519;
         assert_eq!(scheme, SignatureScheme::Ed25519);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_pattern_cap_evicts_least_recently_active() {
+        let config = GuardianConfig { max_tracked_patterns: 2, ..GuardianConfig::default() };
+        let detector = ThreatDetector::new(config).await.unwrap();
+
+        detector.learn(&transfer(1, 9, 1_000));
+        detector.learn(&transfer(2, 9, 1_000));
+        // Address 1 stays active, so address 2 is now the oldest
+        detector.learn(&transfer(1, 8, 1_000));
+        detector.learn(&transfer(3, 9, 1_000));
+
+        let metrics = detector.pattern_metrics();
+        assert_eq!(metrics.tracked_patterns, 2);
+        assert_eq!(metrics.evicted_patterns, 1);
+
+        let analyzer = detector.pattern_analyzer.lock();
+        assert!(!analyzer.transaction_patterns.contains_key(&Address([2; 20])));
+        let active = &analyzer.transaction_patterns[&Address([1; 20])];
+        assert_eq!(active.transaction_count, 2);
+        assert_eq!(active.typical_destinations.len(), 2);
+        assert!(analyzer.transaction_patterns.contains_key(&Address([3; 20])));
+        assert_eq!(analyzer.recency.len(), 2);
+    }
+
+    /// Security whose two Guardians, weight 1 each with quorum 2, have
+    /// registered Secp256k1 keys, returned by Guardian id
+    async fn keyed_guardians() -> (GuardianSecurity, Vec<(String, String)>) {
+        let security = GuardianSecurity::new(GuardianConfig::default()).await.unwrap();
+        let mut guardians = Vec::new();
+        for id in ["alpha", "beta"] {
+            let (key_id, _) = security.crypto_provider.generate_signing_keypair(SignatureScheme::Secp256k1).await.unwrap();
+            guardians.push((id.to_string(), key_id));
+        }
+        let config = MultisigConfig {
+            quorum_weight: 2,
+            guardians: guardians.iter()
+                .map(|(id, key_id)| (id.clone(), GuardianWeight { weight: 1, key_id: Some(key_id.clone()) }))
+                .collect(),
+            ..MultisigConfig::default()
+        };
+        security.security_state.write().await.multisig_approvals = MultisigTracker::new(config);
+        (security, guardians)
+    }
+
+    /// Release signatures from each of `guardians` over the current lockdown
+    async fn release_votes(security: &GuardianSecurity, guardians: &[(String, String)]) -> Vec<(String, Vec<u8>)> {
+        let message = security.lockdown_release_message().await;
+        let mut votes = Vec::new();
+        for (id, key_id) in guardians {
+            votes.push((id.clone(), security.crypto_provider.sign(key_id, &message).await.unwrap()));
+        }
+        votes
+    }
+
+    fn critical_incident(address: Address) -> SecurityIncident {
+        SecurityIncident {
+            id: "incident-1".to_string(),
+            incident_type: IncidentType::PotentialAttack,
+            severity: ThreatLevel::Critical,
+            affected_addresses: vec![address],
+            description: "drain attempt".to_string(),
+            detected_at: SystemTime::now(),
+            resolved_at: None,
+            mitigation_actions: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lockdown_rejects_locked_senders_until_released() {
+        let (security, guardians) = keyed_guardians().await;
+        let mut pending = transfer(1, 9, 1_000);
+        pending.amount.amount = U256::from(100 * 10u64.pow(18));
+        security.security_state.write().await.multisig_approvals.open(&pending, SystemTime::now());
+
+        security.initiate_lockdown(&[critical_incident(Address([1; 20]))]).await.unwrap();
+
+        let status = security.get_security_status().await;
+        assert_eq!(status.locked_addresses, 1);
+        assert_eq!(status.quarantined_transactions, 1);
+        assert_eq!(security.security_state.read().await.quarantined_transactions, vec![pending.id.to_string()]);
+
+        let rejected = security.security_check(&transfer(1, 9, 1_000)).await.unwrap();
+        assert!(!rejected.approved);
+        assert_eq!(rejected.violations, vec!["Sender address is locked down"]);
+
+        let alerts = security.audit_logger.query_events(audit::AuditQuery {
+            start_time: None,
+            end_time: None,
+            event_types: Some(vec!["security_lockdown".to_string()]),
+            categories: None,
+            severities: Some(vec![audit::AuditSeverity::Critical]),
+            addresses: None,
+            transaction_ids: None,
+            limit: None,
+            offset: None,
+        }).await.unwrap();
+        assert_eq!(alerts.len(), 1);
+
+        // Quarantined transactions can't be approved into settlement
+        let (alpha_id, alpha_key) = &guardians[0];
+        let approval = security.crypto_provider.sign(alpha_key, &pending.hash().0).await.unwrap();
+        assert!(matches!(
+            security.approve_transaction(&pending.id.to_string(), alpha_id, approval).await,
+            Err(BridgeError::Security(SecurityError::TransactionQuarantined { .. }))
+        ));
+
+        security.release_lockdown(&release_votes(&security, &guardians).await).await.unwrap();
+        assert_eq!(security.get_security_status().await.locked_addresses, 0);
+        let checked = security.security_check(&transfer(1, 9, 1_000)).await.unwrap();
+        assert!(!checked.violations.iter().any(|violation| violation.contains("locked")));
+    }
+
+    #[tokio::test]
+    async fn test_learned_pattern_flags_new_destination() {
+        let detector = ThreatDetector::new(GuardianConfig::default()).await.unwrap();
+
+        // No baseline yet, so nothing is unusual
+        let first = detector.assess_transaction(&transfer(1, 9, 1_000)).await.unwrap();
+        assert!(first.threat_indicators.is_empty());
+
+        for _ in 0..10 {
+            detector.learn(&transfer(1, 9, 1_000));
+        }
+        {
+            let analyzer = detector.pattern_analyzer.lock();
+            let pattern = &analyzer.transaction_patterns[&Address([1; 20])];
+            assert_eq!(pattern.transaction_count, 10);
+            assert_eq!(pattern.average_amount, U256::from(1_000));
+            assert_eq!(pattern.typical_destinations, vec![Address([9; 20])]);
+            assert_eq!(pattern.hour_histogram.iter().sum::<u64>(), 10);
+            assert!(pattern.frequency > 0.0);
+        }
+
+        let usual = detector.assess_transaction(&transfer(1, 9, 1_000)).await.unwrap();
+        assert!(usual.threat_indicators.is_empty());
+
+        let unusual = detector.assess_transaction(&transfer(1, 0xb, 1_000)).await.unwrap();
+        assert_eq!(unusual.threat_indicators, vec!["unusual_destination"]);
+        assert!(unusual.risk_score > usual.risk_score);
+    }
+
+    #[tokio::test]
+    async fn test_learned_pattern_flags_large_amounts_and_off_hours() {
+        let detector = ThreatDetector::new(GuardianConfig::default()).await.unwrap();
+        let now = SystemTime::now();
+
+        // Without a baseline neither amount nor hour is unusual
+        let first = detector.assess_transaction_at(&transfer(1, 9, 1_000_000), now).await.unwrap();
+        assert!(first.threat_indicators.is_empty());
+
+        for _ in 0..10 {
+            detector.learn(&transfer(1, 9, 1_000));
+        }
+
+        let usual = detector.assess_transaction_at(&transfer(1, 9, 9_000), now).await.unwrap();
+        assert!(usual.threat_indicators.is_empty());
+
+        let large = detector.assess_transaction_at(&transfer(1, 9, 10_000), now).await.unwrap();
+        assert_eq!(large.threat_indicators, vec!["large_amount"]);
+        assert_eq!(large.mitigation_suggestions.len(), 1);
+
+        // Every learned transaction fell in the current hour, so twelve hours on is unusual
+        let later = now + Duration::from_secs(12 * 3600);
+        let off_hours = detector.assess_transaction_at(&transfer(1, 9, 1_000), later).await.unwrap();
+        assert_eq!(off_hours.threat_indicators, vec!["off_hours"]);
+        assert!(off_hours.risk_score > usual.risk_score);
+    }
+
+    #[tokio::test]
+    async fn test_guardian_votes_require_registered_key_signatures() {
+        let security = GuardianSecurity::new(GuardianConfig::default()).await.unwrap();
+        let crypto = &security.crypto_provider;
+        let (alpha_key, _) = crypto.generate_signing_keypair(SignatureScheme::Secp256k1).await.unwrap();
+        let (beta_key, _) = crypto.generate_signing_keypair(SignatureScheme::Secp256k1).await.unwrap();
+
+        let guardian = |weight, key_id: Option<&String>| GuardianWeight { weight, key_id: key_id.cloned() };
+        let config = MultisigConfig {
+            quorum_weight: 2,
+            guardians: HashMap::from([
+                ("alpha".to_string(), guardian(1, Some(&alpha_key))),
+                ("beta".to_string(), guardian(1, Some(&beta_key))),
+                ("keyless".to_string(), guardian(1, None)),
+            ]),
+            ..MultisigConfig::default()
+        };
+        let mut pending = transfer(1, 9, 1_000);
+        pending.amount.amount = U256::from(100 * 10u64.pow(18));
+        let id = pending.id.to_string();
+        {
+            let mut state = security.security_state.write().await;
+            state.multisig_approvals = MultisigTracker::new(config);
+            state.multisig_approvals.open(&pending, SystemTime::now());
+        }
+        let hash = pending.hash().0;
+
+        // A Guardian without a key can't vote, whatever bytes it sends
+        assert!(matches!(
+            security.approve_transaction(&id, "keyless", vec![1; 65]).await,
+            Err(BridgeError::Security(SecurityError::GuardianKeyNotRegistered { .. }))
+        ));
+        // Nor can a signature over the wrong message, or for an unknown transaction
+        let forged = crypto.sign(&alpha_key, b"something else").await.unwrap();
+        assert!(security.approve_transaction(&id, "alpha", forged).await.is_err());
+        let signature = crypto.sign(&alpha_key, &hash).await.unwrap();
+        assert!(security.approve_transaction("unknown", "alpha", signature.clone()).await.is_err());
+
+        // An approval signature can't be replayed as a rejection
+        assert!(security.reject_transaction(&id, "alpha", signature.clone()).await.is_err());
+
+        let status = security.approve_transaction(&id, "alpha", signature).await.unwrap();
+        assert_eq!(status, ApprovalStatus::Pending { approved_weight: 1, quorum_weight: 2 });
+
+        let rejection = crypto.sign(&beta_key, &MultisigTracker::rejection_message(&hash)).await.unwrap();
+        assert_eq!(security.reject_transaction(&id, "beta", rejection).await.unwrap(), ApprovalStatus::Rejected);
+    }
+
+    #[tokio::test]
+    async fn test_lockdown_release_needs_signed_guardian_quorum() {
+        let (security, guardians) = keyed_guardians().await;
+        security.initiate_lockdown(&[critical_incident(Address([1; 20]))]).await.unwrap();
+
+        // No votes, one vote, a forged vote, and an unknown Guardian all fail
+        assert!(matches!(
+            security.release_lockdown(&[]).await,
+            Err(BridgeError::Security(SecurityError::LockdownQuorumNotReached { approved_weight: 0, quorum_weight: 2 }))
+        ));
+        let votes = release_votes(&security, &guardians).await;
+        assert!(security.release_lockdown(&votes[..1]).await.is_err());
+        let forged = security.crypto_provider.sign(&guardians[1].1, b"release").await.unwrap();
+        assert!(security.release_lockdown(&[votes[0].clone(), (guardians[1].0.clone(), forged)]).await.is_err());
+        assert!(security.release_lockdown(&[votes[0].clone(), ("mallory".to_string(), vec![1; 65])]).await.is_err());
+        // The same Guardian twice counts once
+        assert!(security.release_lockdown(&[votes[0].clone(), votes[0].clone()]).await.is_err());
+        assert_eq!(security.get_security_status().await.locked_addresses, 1);
+
+        security.release_lockdown(&votes).await.unwrap();
+        assert_eq!(security.get_security_status().await.locked_addresses, 0);
+
+        // Votes for one lockdown don't release the next
+        security.initiate_lockdown(&[critical_incident(Address([2; 20]))]).await.unwrap();
+        assert!(security.release_lockdown(&votes).await.is_err());
+        assert_eq!(security.get_security_status().await.locked_addresses, 1);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_threat_indicators_trigger_lockdown() {
+        let config = GuardianConfig { suspicious_activity_threshold: 3, ..GuardianConfig::default() };
+        let security = GuardianSecurity::new(config).await.unwrap();
+
+        // A baseline, then transfers to destinations outside it
+        for _ in 0..5 {
+            security.threat_detector.learn(&transfer(1, 9, 1_000));
+        }
+        for destination in [0xa, 0xb] {
+            security.security_check(&transfer(1, destination, 1_000)).await.unwrap();
+        }
+        security.monitor_activity().await.unwrap();
+        assert_eq!(security.get_security_status().await.locked_addresses, 0);
+
+        // Counts restart with each scan, so the threshold must be reached within one
+        for destination in [0xa, 0xb, 0xc] {
+            security.security_check(&transfer(1, destination, 1_000)).await.unwrap();
+        }
+        security.monitor_activity().await.unwrap();
+
+        let status = security.get_security_status().await;
+        assert_eq!(status.threat_level, ThreatLevel::Critical);
+        assert_eq!(security.locked_addresses().await, HashSet::from([Address([1; 20])]));
+        assert!(!security.security_check(&transfer(1, 9, 1_000)).await.unwrap().approved);
+    }
+}
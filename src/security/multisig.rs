@@ -0,0 +1,400 @@
+/*!
+Weighted multi-signature Guardian approvals
+
+High-value transactions are held until Guardians holding a quorum of voting
+weight have signed off on the transaction hash. Approvals are collected per
+transaction and, once quorum is reached, compacted into an
+`ApprovalCertificate` that settlement can carry alongside the batch.
+*/
+
+use crate::error::{BridgeError, Result, SecurityError};
+use crate::types::{Address, Transaction, U256};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::{Duration, SystemTime};
+
+/// Multisig approval configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultisigConfig {
+    /// Transactions above this amount need Guardian quorum approval
+    pub threshold_amount: U256,
+    /// Combined weight of approving Guardians required
+    pub quorum_weight: u64,
+    /// Voting Guardians by id
+    pub guardians: HashMap<String, GuardianWeight>,
+    /// How long an approval request stays open
+    pub approval_timeout: Duration,
+}
+
+/// A Guardian's voting weight and signing key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuardianWeight {
+    pub weight: u64,
+    /// Key used to verify this Guardian's votes; a Guardian without one
+    /// can't vote
+    pub key_id: Option<String>,
+}
+
+impl Default for MultisigConfig {
+    fn default() -> Self {
+        let guardian = |weight| GuardianWeight { weight, key_id: None };
+        Self {
+            threshold_amount: U256::from(10 * 10u64.pow(18)), // 10 tokens
+            quorum_weight: 2,
+            guardians: HashMap::from([
+                ("guardian-0".to_string(), guardian(1)),
+                ("guardian-1".to_string(), guardian(1)),
+            ]),
+            approval_timeout: Duration::from_secs(60 * 60), // 1 hour
+        }
+    }
+}
+
+/// State of a multisig approval request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApprovalStatus {
+    Pending { approved_weight: u64, quorum_weight: u64 },
+    Approved(ApprovalCertificate),
+    Rejected,
+    Expired,
+}
+
+/// Compact record of the Guardian approvals that reached quorum
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalCertificate {
+    pub transaction_id: String,
+    /// Transaction hash the Guardians signed
+    pub message: [u8; 32],
+    /// Approving Guardians, ordered by id
+    pub signers: Vec<String>,
+    /// Signatures in the same order as `signers`
+    pub signatures: Vec<Vec<u8>>,
+    pub total_weight: u64,
+}
+
+/// Open approval request for one transaction
+#[derive(Debug, Clone)]
+struct PendingApproval {
+    message: [u8; 32],
+    sender: Address,
+    approvals: BTreeMap<String, Vec<u8>>,
+    rejections: BTreeSet<String>,
+    created_at: SystemTime,
+    /// When the request was approved, rejected or expired
+    decided_at: Option<SystemTime>,
+    status: ApprovalStatus,
+}
+
+/// Tracks Guardian votes on high-value transactions
+#[derive(Debug, Clone)]
+pub struct MultisigTracker {
+    config: MultisigConfig,
+    requests: HashMap<String, PendingApproval>,
+}
+
+impl MultisigTracker {
+    pub fn new(config: MultisigConfig) -> Self {
+        Self { config, requests: HashMap::new() }
+    }
+
+    /// Whether a transaction needs Guardian quorum approval
+    pub fn requires_approval(&self, transaction: &Transaction) -> bool {
+        transaction.amount.amount > self.config.threshold_amount
+    }
+
+    /// Signing key registered for a Guardian
+    pub fn guardian_key(&self, guardian_id: &str) -> Option<&str> {
+        self.config.guardians.get(guardian_id)?.key_id.as_deref()
+    }
+
+    /// Key a Guardian's vote must be signed with and the transaction hash it
+    /// votes on. Fails for unknown Guardians, Guardians without a registered
+    /// key, and transactions with no open request.
+    pub fn vote_key(&self, transaction_id: &str, guardian_id: &str) -> Result<(String, [u8; 32])> {
+        let guardian = self.config.guardians.get(guardian_id).ok_or_else(|| {
+            BridgeError::Security(SecurityError::UnknownGuardian { guardian_id: guardian_id.to_string() })
+        })?;
+        let key_id = guardian.key_id.clone().ok_or_else(|| {
+            BridgeError::Security(SecurityError::GuardianKeyNotRegistered { guardian_id: guardian_id.to_string() })
+        })?;
+        let message = self.message(transaction_id).ok_or_else(|| {
+            BridgeError::Security(SecurityError::ApprovalNotFound { transaction_id: transaction_id.to_string() })
+        })?;
+        Ok((key_id, message))
+    }
+
+    /// Open an approval request, returning the current status if one exists
+    pub fn open(&mut self, transaction: &Transaction, now: SystemTime) -> ApprovalStatus {
+        self.prune(now);
+        let quorum_weight = self.config.quorum_weight;
+        self.requests
+            .entry(transaction.id.to_string())
+            .or_insert_with(|| PendingApproval {
+                message: transaction.hash().0,
+                sender: transaction.from_address.clone(),
+                approvals: BTreeMap::new(),
+                rejections: BTreeSet::new(),
+                created_at: now,
+                decided_at: None,
+                status: ApprovalStatus::Pending { approved_weight: 0, quorum_weight },
+            })
+            .status
+            .clone()
+    }
+
+    /// Message Guardians sign for a transaction
+    pub fn message(&self, transaction_id: &str) -> Option<[u8; 32]> {
+        self.requests.get(transaction_id).map(|request| request.message)
+    }
+
+    /// Drop requests decided more than an approval timeout ago
+    pub fn prune(&mut self, now: SystemTime) {
+        let timeout = self.config.approval_timeout;
+        for request in self.requests.values_mut() {
+            Self::expire(request, timeout, now);
+        }
+        self.requests.retain(|_, request| {
+            request.decided_at.map_or(true, |decided_at| now.duration_since(decided_at).unwrap_or_default() < timeout)
+        });
+    }
+
+    fn expire(request: &mut PendingApproval, timeout: Duration, now: SystemTime) {
+        if matches!(request.status, ApprovalStatus::Pending { .. })
+            && now.duration_since(request.created_at).unwrap_or_default() >= timeout
+        {
+            request.status = ApprovalStatus::Expired;
+            request.decided_at = Some(now);
+        }
+    }
+
+    /// Still-pending requests for transactions sent by any of `senders`
+    pub fn pending_from(&self, senders: &[Address]) -> Vec<String> {
+        self.requests.iter()
+            .filter(|(_, request)| matches!(request.status, ApprovalStatus::Pending { .. }))
+            .filter(|(_, request)| senders.contains(&request.sender))
+            .map(|(transaction_id, _)| transaction_id.clone())
+            .collect()
+    }
+
+    /// Current status, marking the request expired if its timeout has passed
+    pub fn status(&mut self, transaction_id: &str, now: SystemTime) -> Option<ApprovalStatus> {
+        let timeout = self.config.approval_timeout;
+        let request = self.requests.get_mut(transaction_id)?;
+        Self::expire(request, timeout, now);
+        Some(request.status.clone())
+    }
+
+    /// Record a Guardian's approval signature
+    pub fn approve(
+        &mut self,
+        transaction_id: &str,
+        guardian_id: &str,
+        signature: Vec<u8>,
+        now: SystemTime,
+    ) -> Result<ApprovalStatus> {
+        if signature.is_empty() {
+            return Err(BridgeError::Security(SecurityError::SignatureVerificationFailed));
+        }
+        self.record_vote(transaction_id, guardian_id, now, |request| {
+            request.approvals.insert(guardian_id.to_string(), signature);
+        })
+    }
+
+    /// Message a Guardian signs to reject a transaction, distinct from the
+    /// transaction hash signed to approve it
+    pub fn rejection_message(message: &[u8; 32]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(b"ghostbridge-guardian-reject");
+        hasher.update(message);
+        hasher.finalize().into()
+    }
+
+    /// Message Guardians sign to lift lockdown number `lockdown`, so a
+    /// release can't be replayed against a later lockdown
+    pub fn lockdown_release_message(lockdown: u64) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(b"ghostbridge-guardian-release-lockdown");
+        hasher.update(lockdown.to_be_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Combined weight of the given Guardians and the quorum it must reach
+    pub fn weigh<'a>(&self, guardian_ids: impl IntoIterator<Item = &'a String>) -> (u64, u64) {
+        let weight = guardian_ids.into_iter()
+            .filter_map(|id| self.config.guardians.get(id))
+            .map(|guardian| guardian.weight)
+            .sum();
+        (weight, self.config.quorum_weight)
+    }
+
+    /// Record a Guardian's rejection
+    pub fn reject(&mut self, transaction_id: &str, guardian_id: &str, now: SystemTime) -> Result<ApprovalStatus> {
+        self.record_vote(transaction_id, guardian_id, now, |request| {
+            request.rejections.insert(guardian_id.to_string());
+        })
+    }
+
+    fn record_vote(
+        &mut self,
+        transaction_id: &str,
+        guardian_id: &str,
+        now: SystemTime,
+        vote: impl FnOnce(&mut PendingApproval),
+    ) -> Result<ApprovalStatus> {
+        if !self.config.guardians.contains_key(guardian_id) {
+            return Err(BridgeError::Security(SecurityError::UnknownGuardian {
+                guardian_id: guardian_id.to_string(),
+            }));
+        }
+
+        let status = self.status(transaction_id, now).ok_or_else(|| {
+            BridgeError::Security(SecurityError::ApprovalNotFound {
+                transaction_id: transaction_id.to_string(),
+            })
+        })?;
+        if !matches!(status, ApprovalStatus::Pending { .. }) {
+            return Ok(status);
+        }
+
+        let request = self.requests.get_mut(transaction_id).expect("status checked above");
+        if request.approvals.contains_key(guardian_id) || request.rejections.contains(guardian_id) {
+            return Err(BridgeError::Security(SecurityError::DuplicateGuardianVote {
+                guardian_id: guardian_id.to_string(),
+            }));
+        }
+        vote(request);
+
+        let guardians = &self.config.guardians;
+        let weight_of = |id: &String| guardians.get(id).map_or(0, |g| g.weight);
+        let approved_weight: u64 = request.approvals.keys().map(weight_of).sum();
+        let rejected_weight: u64 = request.rejections.iter().map(weight_of).sum();
+        let total_weight: u64 = guardians.values().map(|g| g.weight).sum();
+        let quorum_weight = self.config.quorum_weight;
+
+        request.status = if approved_weight >= quorum_weight {
+            ApprovalStatus::Approved(ApprovalCertificate {
+                transaction_id: transaction_id.to_string(),
+                message: request.message,
+                signers: request.approvals.keys().cloned().collect(),
+                signatures: request.approvals.values().cloned().collect(),
+                total_weight: approved_weight,
+            })
+        } else if total_weight.saturating_sub(rejected_weight) < quorum_weight {
+            // Quorum can no longer be reached
+            ApprovalStatus::Rejected
+        } else {
+            ApprovalStatus::Pending { approved_weight, quorum_weight }
+        };
+        if !matches!(request.status, ApprovalStatus::Pending { .. }) {
+            request.decided_at = Some(now);
+        }
+
+        Ok(request.status.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::fixtures;
+
+    fn config() -> MultisigConfig {
+        let guardian = |weight| GuardianWeight { weight, key_id: None };
+        MultisigConfig {
+            threshold_amount: U256::from(1_000),
+            quorum_weight: 3,
+            guardians: HashMap::from([
+                ("alpha".to_string(), guardian(2)),
+                ("beta".to_string(), guardian(1)),
+                ("gamma".to_string(), guardian(1)),
+            ]),
+            approval_timeout: Duration::from_secs(60),
+        }
+    }
+
+    fn transaction(amount: u64) -> Transaction {
+        Transaction { nonce: 1, ..fixtures::transfer(1, 2, amount) }
+    }
+
+    #[test]
+    fn test_quorum_approves_transaction() {
+        let mut tracker = MultisigTracker::new(config());
+        let tx = transaction(5_000);
+        let id = tx.id.to_string();
+        let now = SystemTime::now();
+
+        assert!(tracker.requires_approval(&tx));
+        assert!(!tracker.requires_approval(&transaction(500)));
+        tracker.open(&tx, now);
+
+        let status = tracker.approve(&id, "alpha", vec![1; 64], now).unwrap();
+        assert_eq!(status, ApprovalStatus::Pending { approved_weight: 2, quorum_weight: 3 });
+
+        let ApprovalStatus::Approved(certificate) = tracker.approve(&id, "gamma", vec![3; 64], now).unwrap() else {
+            panic!("expected quorum to be reached");
+        };
+        assert_eq!(certificate.signers, vec!["alpha", "gamma"]);
+        assert_eq!(certificate.signatures, vec![vec![1; 64], vec![3; 64]]);
+        assert_eq!(certificate.total_weight, 3);
+        assert_eq!(certificate.message, tx.hash().0);
+
+        // Late votes don't change the outcome
+        assert!(matches!(tracker.approve(&id, "beta", vec![2; 64], now).unwrap(), ApprovalStatus::Approved(_)));
+    }
+
+    #[test]
+    fn test_short_of_quorum_stays_blocked() {
+        let mut tracker = MultisigTracker::new(config());
+        let tx = transaction(5_000);
+        let id = tx.id.to_string();
+        let now = SystemTime::now();
+        tracker.open(&tx, now);
+
+        tracker.approve(&id, "beta", vec![2; 64], now).unwrap();
+        let status = tracker.approve(&id, "gamma", vec![3; 64], now).unwrap();
+        assert_eq!(status, ApprovalStatus::Pending { approved_weight: 2, quorum_weight: 3 });
+
+        // A Guardian can only vote once
+        assert!(tracker.approve(&id, "beta", vec![2; 64], now).is_err());
+        assert!(tracker.approve(&id, "mallory", vec![9; 64], now).is_err());
+
+        // Still pending until it times out
+        assert!(matches!(tracker.status(&id, now), Some(ApprovalStatus::Pending { .. })));
+        assert_eq!(tracker.status(&id, now + Duration::from_secs(61)), Some(ApprovalStatus::Expired));
+    }
+
+    #[test]
+    fn test_rejection_when_quorum_unreachable() {
+        let mut tracker = MultisigTracker::new(config());
+        let tx = transaction(5_000);
+        let id = tx.id.to_string();
+        let now = SystemTime::now();
+        tracker.open(&tx, now);
+
+        // Remaining weight (2) falls below quorum (3)
+        assert_eq!(tracker.reject(&id, "alpha", now).unwrap(), ApprovalStatus::Rejected);
+    }
+
+    #[test]
+    fn test_threshold_compares_full_width_and_decided_requests_pruned() {
+        let mut tracker = MultisigTracker::new(config());
+
+        // 2^64 truncates to 0 in u64 but is far above the threshold
+        let mut huge = transaction(0);
+        huge.amount.amount.0[23] = 1;
+        assert!(tracker.requires_approval(&huge));
+
+        let now = SystemTime::now();
+        let tx = transaction(5_000);
+        let id = tx.id.to_string();
+        tracker.open(&tx, now);
+        assert_eq!(tracker.reject(&id, "alpha", now).unwrap(), ApprovalStatus::Rejected);
+
+        // Kept for a timeout after the decision, then dropped
+        tracker.prune(now + Duration::from_secs(30));
+        assert_eq!(tracker.status(&id, now), Some(ApprovalStatus::Rejected));
+        tracker.prune(now + Duration::from_secs(61));
+        assert_eq!(tracker.status(&id, now), None);
+    }
+}
@@ -22,6 +22,8 @@ impl CnsService {
     #[instrument(skip(endpoint))]
     pub async fn new(endpoint: &ServiceEndpoint) -> Result<Self> {
         debug!("Connecting to CNS service at {}", endpoint.grpc_endpoint());
+        // The etherlink client connects with the endpoint alone
+        endpoint.require_no_credential("CNS")?;
 
         let client = CNSClient::connect(endpoint.grpc_endpoint()).await
             .map_err(|e| BridgeError::Service(ServiceError::Cns(format!(
@@ -156,7 +158,6 @@ pub struct DomainRegistration {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::services::ServiceEndpoint;
 
     #[test]
     fn test_domain_resolution_creation() {
@@ -5,25 +5,222 @@ Integration with the main blockchain daemon for transaction processing and block
 */
 
 use crate::error::{BridgeError, Result, ServiceError};
-use crate::services::ServiceEndpoint;
+use crate::services::{ServiceCredential, ServiceEndpoint};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
 use tracing::{debug, instrument};
 
+/// Reads raw chain state from GHOSTD
+#[async_trait]
+pub trait GhostdStateClient: Send + Sync {
+    /// Value stored at `key`, or `None` if nothing is stored there
+    async fn get_state(&self, key: &[u8]) -> Result<Option<Vec<u8>>>;
+}
+
+/// Largest JSON-RPC response accepted from GHOSTD
+const MAX_STATE_RESPONSE_BYTES: usize = 4 * 1024 * 1024;
+
+/// GHOSTD's JSON-RPC interface: `ghostd_getState` takes a hex key and
+/// returns the hex value, or `null` for an absent key
+pub struct JsonRpcStateClient {
+    endpoint: ServiceEndpoint,
+    url: String,
+    http: reqwest::Client,
+}
+
+#[derive(Deserialize)]
+struct JsonRpcResponse {
+    #[serde(default)]
+    result: Option<String>,
+    #[serde(default)]
+    error: Option<JsonRpcError>,
+}
+
+#[derive(Deserialize)]
+struct JsonRpcError {
+    code: i64,
+    message: String,
+}
+
+impl JsonRpcStateClient {
+    pub fn new(endpoint: &ServiceEndpoint) -> Result<Self> {
+        let mut builder = reqwest::Client::builder().https_only(endpoint.use_tls);
+        if let Some(ServiceCredential::ClientCertificate { cert_path, key_path }) = &endpoint.auth.credential {
+            if !endpoint.use_tls {
+                return Err(BridgeError::config("GHOSTD client certificate requires use_tls"));
+            }
+            // rustls reads the certificate chain and private key from one PEM bundle
+            let mut pem = std::fs::read(cert_path)
+                .map_err(|e| BridgeError::config(format!("GHOSTD client certificate {}: {}", cert_path.display(), e)))?;
+            pem.extend(std::fs::read(key_path)
+                .map_err(|e| BridgeError::config(format!("GHOSTD client key {}: {}", key_path.display(), e)))?);
+            let identity = reqwest::Identity::from_pem(&pem)
+                .map_err(|e| BridgeError::config(format!("GHOSTD client identity: {}", e)))?;
+            builder = builder.identity(identity);
+        }
+        let http = builder.build()
+            .map_err(|e| BridgeError::config(format!("GHOSTD JSON-RPC client: {}", e)))?;
+
+        Ok(Self { endpoint: endpoint.clone(), url: endpoint.url(), http })
+    }
+
+    async fn call(&self, body: &serde_json::Value) -> Result<JsonRpcResponse> {
+        let mut request = self.http.post(&self.url).json(body);
+        if let Some(authorization) = self.endpoint.auth.credential.as_ref().and_then(ServiceCredential::authorization_header) {
+            request = request.header(reqwest::header::AUTHORIZATION, authorization);
+        }
+
+        let response = request.send().await
+            .map_err(|e| ghostd_error(format!("Request to {} failed: {}", self.url, e)))?;
+        if !response.status().is_success() {
+            return Err(ghostd_error(format!("GHOSTD returned HTTP {}", response.status())));
+        }
+        let body = crate::transport::read_bounded_body(response, &self.url, MAX_STATE_RESPONSE_BYTES).await?;
+
+        serde_json::from_slice(&body).map_err(|e| BridgeError::Serialization(e.into()))
+    }
+}
+
+#[async_trait]
+impl GhostdStateClient for JsonRpcStateClient {
+    async fn get_state(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "ghostd_getState",
+            "params": [format!("0x{}", hex::encode(key))],
+        });
+
+        let timeout = Duration::from_millis(self.endpoint.timeout_ms);
+        let response = tokio::time::timeout(timeout, self.call(&body)).await
+            .map_err(|_| ghostd_error(format!("ghostd_getState timed out after {}ms", self.endpoint.timeout_ms)))??;
+
+        if let Some(error) = response.error {
+            return Err(ghostd_error(format!("ghostd_getState failed ({}): {}", error.code, error.message)));
+        }
+        response.result
+            .map(|value| hex::decode(value.trim_start_matches("0x"))
+                .map_err(|e| ghostd_error(format!("Malformed state value: {}", e))))
+            .transpose()
+    }
+}
+
+fn ghostd_error(message: impl Into<String>) -> BridgeError {
+    BridgeError::Service(ServiceError::Ghostd(message.into()))
+}
+
 /// GHOSTD service wrapper
 pub struct GhostdService {
     endpoint: ServiceEndpoint,
+    state: Arc<dyn GhostdStateClient>,
 }
 
 impl GhostdService {
     #[instrument(skip(endpoint))]
     pub async fn new(endpoint: &ServiceEndpoint) -> Result<Self> {
         debug!("Connecting to GHOSTD service at {}", endpoint.grpc_endpoint());
+        // Fail fast when a required credential is missing
+        endpoint.credential("GHOSTD")?;
         Ok(Self {
             endpoint: endpoint.clone(),
+            state: Arc::new(JsonRpcStateClient::new(endpoint)?),
         })
     }
 
+    /// Use a custom state client
+    pub fn with_state_client(mut self, state: Arc<dyn GhostdStateClient>) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Credential attached to this service's connection
+    pub fn credential(&self) -> Option<&ServiceCredential> {
+        self.endpoint.auth.credential.as_ref()
+    }
+
+    /// Chain state stored at `key`, or `None` if the key is absent
+    #[instrument(skip(self, key))]
+    pub async fn query_state(&self, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        debug!("Querying GHOSTD state for key 0x{}", hex::encode(key));
+        self.state.get_state(key).await
+    }
+
     pub async fn health_check(&self) -> Result<()> {
         debug!("Performing GHOSTD health check");
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::ServiceAuth;
+    use bytes::Bytes;
+    use http_body_util::{BodyExt, Full};
+    use hyper::body::Incoming;
+    use hyper_util::rt::TokioIo;
+
+    #[tokio::test]
+    async fn test_query_state_distinguishes_empty_from_absent() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let service = hyper::service::service_fn(|request: hyper::Request<Incoming>| async move {
+                    if request.headers().get(hyper::header::AUTHORIZATION).map(|value| value.as_bytes()) != Some(b"Bearer ghostd-token") {
+                        let mut response = hyper::Response::new(Full::new(Bytes::new()));
+                        *response.status_mut() = hyper::StatusCode::UNAUTHORIZED;
+                        return Ok(response);
+                    }
+                    let body = request.into_body().collect().await?.to_bytes();
+                    let call: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                    let result = match call["params"][0].as_str().unwrap() {
+                        "0x01" => serde_json::json!("0xbeef"),
+                        "0x02" => serde_json::json!("0x"),
+                        _ => serde_json::Value::Null,
+                    };
+                    let response = serde_json::json!({ "jsonrpc": "2.0", "id": call["id"], "result": result });
+                    Ok::<_, hyper::Error>(hyper::Response::new(Full::new(Bytes::from(response.to_string()))))
+                });
+                tokio::spawn(hyper::server::conn::http1::Builder::new().serve_connection(TokioIo::new(stream), service));
+            }
+        });
+
+        let endpoint = ServiceEndpoint {
+            host: "127.0.0.1".to_string(),
+            port,
+            use_tls: false,
+            timeout_ms: 5000,
+            auth: ServiceAuth {
+                credential: Some(ServiceCredential::BearerToken { token: "ghostd-token".to_string() }),
+                required: true,
+            },
+        };
+        let ghostd = GhostdService::new(&endpoint).await.unwrap();
+
+        assert_eq!(ghostd.query_state(&[1]).await.unwrap(), Some(vec![0xbe, 0xef]));
+        assert_eq!(ghostd.query_state(&[2]).await.unwrap(), Some(vec![]));
+        assert_eq!(ghostd.query_state(&[3]).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_client_certificate_requires_tls() {
+        let endpoint = ServiceEndpoint {
+            host: "127.0.0.1".to_string(),
+            port: 8545,
+            use_tls: false,
+            timeout_ms: 5000,
+            auth: ServiceAuth {
+                credential: Some(ServiceCredential::ClientCertificate {
+                    cert_path: "ghostd.crt".into(),
+                    key_path: "ghostd.key".into(),
+                }),
+                required: true,
+            },
+        };
+        assert!(matches!(JsonRpcStateClient::new(&endpoint), Err(BridgeError::Config(_))));
+    }
+}
@@ -5,7 +5,7 @@ Integration with identity management service for authentication and DID operatio
 */
 
 use crate::error::{BridgeError, Result, ServiceError};
-use crate::services::ServiceEndpoint;
+use crate::services::{ServiceCredential, ServiceEndpoint};
 use tracing::{debug, instrument};
 
 /// GID service wrapper
@@ -17,11 +17,18 @@ impl GidService {
     #[instrument(skip(endpoint))]
     pub async fn new(endpoint: &ServiceEndpoint) -> Result<Self> {
         debug!("Connecting to GID service at {}", endpoint.grpc_endpoint());
+        // Fail fast when a required credential is missing
+        endpoint.credential("GID")?;
         Ok(Self {
             endpoint: endpoint.clone(),
         })
     }
 
+    /// Credential attached to this service's connection
+    pub fn credential(&self) -> Option<&ServiceCredential> {
+        self.endpoint.auth.credential.as_ref()
+    }
+
     pub async fn health_check(&self) -> Result<()> {
         debug!("Performing GID health check");
         Ok(())
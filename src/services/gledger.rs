@@ -6,7 +6,7 @@ Integration with GLEDGER for token management and 4-token economy operations.
 
 use crate::error::{BridgeError, Result, ServiceError, TokenError};
 use crate::types::{Address, TokenAmount, TokenType, U256, MultiTokenFee};
-use crate::services::ServiceEndpoint;
+use crate::services::{ServiceCredential, ServiceEndpoint};
 use std::collections::HashMap;
 use tracing::{debug, instrument};
 use serde::{Deserialize, Serialize};
@@ -23,6 +23,8 @@ impl GledgerService {
     #[instrument(skip(endpoint))]
     pub async fn new(endpoint: &ServiceEndpoint) -> Result<Self> {
         debug!("Connecting to GLEDGER service at {}", endpoint.grpc_endpoint());
+        // Fail fast when a required credential is missing
+        endpoint.credential("GLEDGER")?;
 
         // TODO: Replace with actual etherlink GLEDGER client when available
         // let client = GledgerClient::connect(endpoint.grpc_endpoint()).await
@@ -36,6 +38,11 @@ impl GledgerService {
         })
     }
 
+    /// Credential attached to this service's connection
+    pub fn credential(&self) -> Option<&ServiceCredential> {
+        self.endpoint.auth.credential.as_ref()
+    }
+
     /// Get token balance for an address
     #[instrument(skip(self))]
     pub async fn get_balance(&self, address: &Address, token_type: TokenType) -> Result<TokenAmount> {
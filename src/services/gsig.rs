@@ -5,7 +5,7 @@ Integration with signature verification and multi-signature management service.
 */
 
 use crate::error::{BridgeError, Result, ServiceError};
-use crate::services::ServiceEndpoint;
+use crate::services::{ServiceCredential, ServiceEndpoint};
 use tracing::{debug, instrument};
 
 /// GSIG service wrapper
@@ -17,11 +17,18 @@ impl GsigService {
     #[instrument(skip(endpoint))]
     pub async fn new(endpoint: &ServiceEndpoint) -> Result<Self> {
         debug!("Connecting to GSIG service at {}", endpoint.grpc_endpoint());
+        // Fail fast when a required credential is missing
+        endpoint.credential("GSIG")?;
         Ok(Self {
             endpoint: endpoint.clone(),
         })
     }
 
+    /// Credential attached to this service's connection
+    pub fn credential(&self) -> Option<&ServiceCredential> {
+        self.endpoint.auth.credential.as_ref()
+    }
+
     pub async fn health_check(&self) -> Result<()> {
         debug!("Performing GSIG health check");
         Ok(())
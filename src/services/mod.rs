@@ -27,7 +27,7 @@ pub mod gledger;
 pub mod gsig;
 
 pub use self::{
-    ghostd::GhostdService,
+    ghostd::{GhostdService, GhostdStateClient, JsonRpcStateClient},
     walletd::WalletdService,
     gid::GidService,
     cns::CnsService,
@@ -57,6 +57,52 @@ pub struct ServiceEndpoint {
     pub port: u16,
     pub use_tls: bool,
     pub timeout_ms: u64,
+    /// Credential presented when connecting to this service
+    #[serde(default)]
+    pub auth: ServiceAuth,
+}
+
+/// Per-service authentication
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ServiceAuth {
+    pub credential: Option<ServiceCredential>,
+    /// Fail initialization if no credential is configured
+    pub required: bool,
+}
+
+/// Credential attached to a service connection. Its `Debug` output leaves
+/// tokens out, so configs can be logged.
+#[derive(Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServiceCredential {
+    /// Sent as an `authorization: Bearer` header
+    BearerToken { token: String },
+    /// mTLS client identity
+    ClientCertificate { cert_path: std::path::PathBuf, key_path: std::path::PathBuf },
+}
+
+impl ServiceCredential {
+    /// Value of the `authorization` header, for token credentials
+    pub fn authorization_header(&self) -> Option<String> {
+        match self {
+            ServiceCredential::BearerToken { token } => Some(format!("Bearer {}", token)),
+            ServiceCredential::ClientCertificate { .. } => None,
+        }
+    }
+}
+
+impl std::fmt::Debug for ServiceCredential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServiceCredential::BearerToken { .. } => f.debug_struct("BearerToken")
+                .field("token", &"<redacted>")
+                .finish(),
+            ServiceCredential::ClientCertificate { cert_path, key_path } => f.debug_struct("ClientCertificate")
+                .field("cert_path", cert_path)
+                .field("key_path", key_path)
+                .finish(),
+        }
+    }
 }
 
 impl ServiceEndpoint {
@@ -70,6 +116,28 @@ impl ServiceEndpoint {
     pub fn grpc_endpoint(&self) -> String {
         format!("{}:{}", self.host, self.port)
     }
+
+    /// Credential to attach when connecting to `service`, failing if one is required but missing
+    pub fn credential(&self, service: &str) -> Result<Option<&ServiceCredential>> {
+        match &self.auth.credential {
+            None if self.auth.required => Err(BridgeError::Service(ServiceError::MissingCredential {
+                service: service.to_string(),
+            })),
+            credential => Ok(credential.as_ref()),
+        }
+    }
+
+    /// Check `service` can be reached by a client with no way to present a
+    /// credential. A configured one is refused rather than left off the
+    /// connection without notice.
+    pub fn require_no_credential(&self, service: &str) -> Result<()> {
+        match self.credential(service)? {
+            Some(_) => Err(BridgeError::Service(ServiceError::CredentialNotSupported {
+                service: service.to_string(),
+            })),
+            None => Ok(()),
+        }
+    }
 }
 
 impl Default for ServiceConfig {
@@ -80,42 +148,49 @@ impl Default for ServiceConfig {
                 port: 8545,
                 use_tls: false,
                 timeout_ms: 5000,
+                auth: ServiceAuth::default(),
             },
             walletd: ServiceEndpoint {
                 host: "localhost".to_string(),
                 port: 8546,
                 use_tls: false,
                 timeout_ms: 5000,
+                auth: ServiceAuth::default(),
             },
             gid: ServiceEndpoint {
                 host: "localhost".to_string(),
                 port: 8547,
                 use_tls: false,
                 timeout_ms: 5000,
+                auth: ServiceAuth::default(),
             },
             cns: ServiceEndpoint {
                 host: "localhost".to_string(),
                 port: 8548,
                 use_tls: false,
                 timeout_ms: 5000,
+                auth: ServiceAuth::default(),
             },
             gledger: ServiceEndpoint {
                 host: "localhost".to_string(),
                 port: 8549,
                 use_tls: false,
                 timeout_ms: 5000,
+                auth: ServiceAuth::default(),
             },
             gsig: ServiceEndpoint {
                 host: "localhost".to_string(),
                 port: 8550,
                 use_tls: false,
                 timeout_ms: 5000,
+                auth: ServiceAuth::default(),
             },
             ghostplane: ServiceEndpoint {
                 host: "localhost".to_string(),
                 port: 9090,
                 use_tls: false,
                 timeout_ms: 10000, // L2 operations may take longer
+                auth: ServiceAuth::default(),
             },
             default_timeout: Duration::from_secs(5),
             max_retries: 3,
@@ -238,6 +313,17 @@ impl ServiceManager {
         Ok(guard)
     }
 
+    /// Get CNS service for operations that need exclusive access
+    pub async fn cns_mut(&self) -> Result<tokio::sync::RwLockWriteGuard<'_, Option<CnsService>>> {
+        let guard = self.cns.write().await;
+        if guard.is_none() {
+            return Err(BridgeError::Service(ServiceError::ServiceUnavailable {
+                service: "CNS".to_string(),
+            }));
+        }
+        Ok(guard)
+    }
+
     /// Get GLEDGER service
     pub async fn gledger(&self) -> Result<tokio::sync::RwLockReadGuard<'_, Option<GledgerService>>> {
         let guard = self.gledger.read().await;
@@ -271,23 +357,32 @@ impl ServiceManager {
         Ok(guard)
     }
 
+    /// Services checked by `health_check` and `ping`
+    pub const SERVICE_NAMES: [&'static str; 7] = ["GHOSTD", "WALLETD", "GID", "CNS", "GLEDGER", "GSIG", "GHOSTPLANE"];
+
+    /// Check a single service by name (see `SERVICE_NAMES`)
+    pub async fn ping(&self, service_name: &str) -> Result<()> {
+        match service_name {
+            "GHOSTD" => self.check_ghostd_health().await,
+            "WALLETD" => self.check_walletd_health().await,
+            "GID" => self.check_gid_health().await,
+            "CNS" => self.check_cns_health().await,
+            "GLEDGER" => self.check_gledger_health().await,
+            "GSIG" => self.check_gsig_health().await,
+            "GHOSTPLANE" => self.check_ghostplane_health().await,
+            other => Err(BridgeError::Service(ServiceError::ServiceUnavailable {
+                service: other.to_string(),
+            })),
+        }
+    }
+
     /// Health check for all services
     pub async fn health_check(&self) -> Result<ServiceHealthStatus> {
         let mut status = ServiceHealthStatus::default();
 
         // Check each service
-        let checks = vec![
-            ("GHOSTD", self.check_ghostd_health()),
-            ("WALLETD", self.check_walletd_health()),
-            ("GID", self.check_gid_health()),
-            ("CNS", self.check_cns_health()),
-            ("GLEDGER", self.check_gledger_health()),
-            ("GSIG", self.check_gsig_health()),
-            ("GHOSTPLANE", self.check_ghostplane_health()),
-        ];
-
-        for (service_name, health_check) in checks {
-            let is_healthy = health_check.await.is_ok();
+        for service_name in Self::SERVICE_NAMES {
+            let is_healthy = self.ping(service_name).await.is_ok();
             status.services.insert(service_name.to_string(), is_healthy);
 
             if is_healthy {
@@ -328,7 +423,7 @@ impl ServiceManager {
         Ok(())
     }
 
-    async fn init_gledger(&self) -> Result<()> {
+    pub(crate) async fn init_gledger(&self) -> Result<()> {
         debug!("Initializing GLEDGER service");
         let service = GledgerService::new(&self.config.gledger).await?;
         *self.gledger.write().await = Some(service);
@@ -344,6 +439,8 @@ impl ServiceManager {
 
     async fn init_ghostplane(&self) -> Result<()> {
         debug!("Initializing GhostPlane client");
+        // The etherlink client connects with the endpoint alone
+        self.config.ghostplane.require_no_credential("GHOSTPLANE")?;
         let client = GhostPlaneClient::connect(self.config.ghostplane.grpc_endpoint()).await
             .map_err(|e| BridgeError::Service(ServiceError::EtherlinkClient(e.to_string())))?;
         *self.ghostplane_client.write().await = Some(client);
@@ -461,6 +558,7 @@ mod tests {
             port: 8545,
             use_tls: false,
             timeout_ms: 5000,
+            auth: ServiceAuth::default(),
         };
         assert_eq!(endpoint.url(), "http://localhost:8545");
 
@@ -469,6 +567,7 @@ mod tests {
             port: 443,
             use_tls: true,
             timeout_ms: 5000,
+            auth: ServiceAuth::default(),
         };
         assert_eq!(tls_endpoint.url(), "https://example.com:443");
     }
@@ -480,4 +579,48 @@ mod tests {
         // Should not panic
         assert!(true);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_per_service_credentials() {
+        let mut config = ServiceConfig::default();
+        config.gledger.auth = ServiceAuth {
+            credential: Some(ServiceCredential::BearerToken { token: "gledger-token".to_string() }),
+            required: true,
+        };
+        config.gid.auth.credential = Some(ServiceCredential::ClientCertificate {
+            cert_path: "/etc/ghostbridge/gid.crt".into(),
+            key_path: "/etc/ghostbridge/gid.key".into(),
+        });
+
+        let gledger = GledgerService::new(&config.gledger).await.unwrap();
+        assert_eq!(
+            gledger.credential().and_then(ServiceCredential::authorization_header).as_deref(),
+            Some("Bearer gledger-token")
+        );
+        let gid = GidService::new(&config.gid).await.unwrap();
+        assert!(matches!(gid.credential(), Some(ServiceCredential::ClientCertificate { .. })));
+        assert!(GsigService::new(&config.gsig).await.unwrap().credential().is_none());
+
+        // A required credential that is missing fails initialization
+        config.gsig.auth.required = true;
+        let error = GsigService::new(&config.gsig).await.err().unwrap();
+        assert!(matches!(error, BridgeError::Service(ServiceError::MissingCredential { .. })));
+        assert_eq!(error.to_string(), "Service error: GSIG requires a credential but none is configured");
+    }
+
+    #[test]
+    fn test_credentials_are_redacted_and_never_dropped() {
+        let token = ServiceCredential::BearerToken { token: "s3cret-token".to_string() };
+        let mut endpoint = ServiceConfig::default().ghostplane;
+        endpoint.auth.credential = Some(token.clone());
+
+        assert!(!format!("{:?}", endpoint).contains("s3cret-token"));
+        assert!(format!("{:?}", token).contains("<redacted>"));
+
+        // A client that cannot send the credential refuses to connect without it
+        let error = endpoint.require_no_credential("GHOSTPLANE").unwrap_err();
+        assert!(matches!(error, BridgeError::Service(ServiceError::CredentialNotSupported { .. })));
+        endpoint.auth.credential = None;
+        endpoint.require_no_credential("GHOSTPLANE").unwrap();
+    }
+}
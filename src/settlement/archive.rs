@@ -0,0 +1,187 @@
+/*!
+Archival storage for finalized settlement batches
+
+Finalized batches are pruned from the in-memory settlement queue once they age
+past the retention window and moved to a `BatchArchive`, where they remain
+queryable for status lookups.
+*/
+
+use crate::error::Result;
+use crate::settlement::FinalizedBatch;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+use tokio::sync::RwLock;
+
+/// Persistence backend for pruned finalized batches
+#[async_trait]
+pub trait BatchArchive: Send + Sync {
+    /// Store a finalized batch
+    async fn archive_batch(&self, batch: FinalizedBatch) -> Result<()>;
+
+    /// Look up a finalized batch by id
+    async fn get_batch(&self, batch_id: &str) -> Result<Option<FinalizedBatch>>;
+
+    /// Find the archived batch containing a transaction
+    async fn find_transaction(&self, transaction_id: &str) -> Result<Option<FinalizedBatch>>;
+
+    /// Number of archived batches
+    async fn len(&self) -> usize;
+
+    /// Archived batch furthest along the chain of batch roots
+    async fn latest(&self) -> Result<Option<FinalizedBatch>>;
+}
+
+/// In-memory archive, used when no external backend is configured
+#[derive(Default)]
+pub struct InMemoryBatchArchive {
+    batches: RwLock<HashMap<String, FinalizedBatch>>,
+}
+
+impl InMemoryBatchArchive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl BatchArchive for InMemoryBatchArchive {
+    async fn archive_batch(&self, batch: FinalizedBatch) -> Result<()> {
+        self.batches.write().await.insert(batch.batch.batch_id.clone(), batch);
+        Ok(())
+    }
+
+    async fn get_batch(&self, batch_id: &str) -> Result<Option<FinalizedBatch>> {
+        Ok(self.batches.read().await.get(batch_id).cloned())
+    }
+
+    async fn find_transaction(&self, transaction_id: &str) -> Result<Option<FinalizedBatch>> {
+        let batches = self.batches.read().await;
+        Ok(batches
+            .values()
+            .find(|b| b.batch.transactions.iter().any(|tx| tx.id.to_string() == transaction_id))
+            .cloned())
+    }
+
+    async fn len(&self) -> usize {
+        self.batches.read().await.len()
+    }
+
+    async fn latest(&self) -> Result<Option<FinalizedBatch>> {
+        Ok(self.batches.read().await.values().max_by_key(|b| b.batch.block_number).cloned())
+    }
+}
+
+/// Move finalized batches older than `retention` into the archive.
+///
+/// The `min_retained` most recently finalized batches are always kept in
+/// memory regardless of age. Returns the number of batches pruned.
+pub async fn prune_finalized_batches(
+    finalized: &mut HashMap<String, FinalizedBatch>,
+    archive: &dyn BatchArchive,
+    retention: Duration,
+    min_retained: usize,
+    now: SystemTime,
+) -> Result<usize> {
+    if finalized.len() <= min_retained {
+        return Ok(0);
+    }
+
+    // Oldest first; never prune into the most recent `min_retained`
+    let mut candidates: Vec<(String, SystemTime)> = finalized
+        .iter()
+        .map(|(id, batch)| (id.clone(), batch.finalized_at))
+        .collect();
+    candidates.sort_by_key(|(_, finalized_at)| *finalized_at);
+    candidates.truncate(finalized.len() - min_retained);
+
+    let mut pruned = 0;
+    for (batch_id, finalized_at) in candidates {
+        if now.duration_since(finalized_at).unwrap_or_default() < retention {
+            break;
+        }
+
+        if let Some(batch) = finalized.remove(&batch_id) {
+            // Put the batch back if archival fails so it is never lost
+            if let Err(e) = archive.archive_batch(batch.clone()).await {
+                finalized.insert(batch_id, batch);
+                return Err(e);
+            }
+            pruned += 1;
+        }
+    }
+
+    Ok(pruned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settlement::SettlementBatch;
+    use crate::types::{TokenAmount, TokenType, U256};
+
+    fn finalized_batch(batch_id: &str, finalized_at: SystemTime) -> FinalizedBatch {
+        FinalizedBatch {
+            batch: SettlementBatch {
+                batch_id: batch_id.to_string(),
+                transactions: vec![],
+                state_root: vec![0u8; 32],
+                previous_state_root: vec![0u8; 32],
+                block_number: 1,
+                merkle_proof: vec![],
+                zk_proof: None,
+                created_at: finalized_at,
+                gas_used: 0,
+                fee_paid: TokenAmount::new(TokenType::Gcc, U256::ZERO),
+            },
+            finalized_at,
+            l1_block_number: 1,
+            final_gas_used: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prune_moves_old_batches_to_archive() {
+        let now = SystemTime::now();
+        let hour = Duration::from_secs(3600);
+        let archive = InMemoryBatchArchive::new();
+
+        let mut finalized = HashMap::new();
+        finalized.insert("old-1".to_string(), finalized_batch("old-1", now - 3 * hour));
+        finalized.insert("old-2".to_string(), finalized_batch("old-2", now - 2 * hour));
+        finalized.insert("recent".to_string(), finalized_batch("recent", now));
+
+        let pruned = prune_finalized_batches(&mut finalized, &archive, hour, 0, now)
+            .await
+            .unwrap();
+
+        assert_eq!(pruned, 2);
+        assert!(!finalized.contains_key("old-1"));
+        assert!(!finalized.contains_key("old-2"));
+        assert!(finalized.contains_key("recent"));
+
+        // Pruned batches remain retrievable from the archive
+        assert_eq!(archive.len().await, 2);
+        assert!(archive.get_batch("old-1").await.unwrap().is_some());
+        assert!(archive.get_batch("recent").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_prune_keeps_minimum_recent_batches() {
+        let now = SystemTime::now();
+        let hour = Duration::from_secs(3600);
+        let archive = InMemoryBatchArchive::new();
+
+        let mut finalized = HashMap::new();
+        finalized.insert("a".to_string(), finalized_batch("a", now - 5 * hour));
+        finalized.insert("b".to_string(), finalized_batch("b", now - 4 * hour));
+
+        let pruned = prune_finalized_batches(&mut finalized, &archive, hour, 1, now)
+            .await
+            .unwrap();
+
+        assert_eq!(pruned, 1);
+        assert!(finalized.contains_key("b"));
+        assert!(archive.get_batch("a").await.unwrap().is_some());
+    }
+}
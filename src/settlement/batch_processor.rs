@@ -5,10 +5,20 @@ Handles transaction batching, validation, execution, and preparation for L1 sett
 Optimized for 50,000+ TPS throughput with parallel processing.
 */
 
-use crate::error::{BridgeError, Result};
-use crate::types::{Transaction, Address, U256, TokenAmount};
+use crate::error::{BridgeError, Result, SettlementError};
+use crate::types::{Transaction, Address, ChainId, U256, TokenAmount, TokenType};
+use crate::services::ServiceManager;
+use crate::security::SignatureScheme;
 use crate::settlement::{SettlementConfig, SettlementBatch};
-use std::collections::{HashMap, VecDeque};
+use crate::settlement::dependency_graph::DependencyGraph;
+use crate::settlement::dead_letter::DeadLetterQueue;
+use crate::settlement::state_manager::{BatchLink, BatchRootChain};
+use crate::settlement::challenge_monitor::{ReexecutionTrace, TraceStep};
+use crate::settlement::emergency_exit::EmergencyWithdrawal;
+use crate::settlement::merkle::{HasherType, MerkleTree};
+use crate::settlement::state_tree::{self, prove_balance, BalanceProof};
+use crate::calldata::{CalldataDecoder, MethodAllowlist};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use tokio::sync::{RwLock, Semaphore, mpsc};
@@ -25,6 +35,51 @@ pub struct BatchProcessor {
     batch_assembler: BatchAssembler,
     parallelism_limiter: Arc<Semaphore>,
     processing_metrics: Arc<RwLock<ProcessingMetrics>>,
+    calldata_decoder: Option<Arc<CalldataDecoder>>,
+    dead_letters: Arc<DeadLetterQueue>,
+    /// Contract methods that may execute; None allows all
+    method_allowlist: Option<MethodAllowlist>,
+    /// Recent batches with the state they executed against, oldest first
+    replay_records: parking_lot::Mutex<VecDeque<ReplayRecord>>,
+    /// Roots of previous batches, which each new batch links to
+    batch_chain: Arc<BatchRootChain>,
+    /// Scheme of each sender's registered signing key
+    signing_keys: SigningKeys,
+    /// Balances already burned for emergency exits, by account and token
+    burned_exits: parking_lot::Mutex<HashSet<(Address, String)>>,
+}
+
+/// Scheme of each sender's registered signing key, shared with the signature validator
+type SigningKeys = Arc<parking_lot::RwLock<HashMap<Address, SignatureScheme>>>;
+
+/// Result of executing a batch's transactions against the current state
+struct Execution {
+    transactions: Vec<Transaction>,
+    state_root: Vec<u8>,
+    gas_used: u64,
+    /// State before execution, kept when batches are recorded for replay
+    prior_state: Option<GlobalState>,
+    /// Where the batch sits in the chain of roots
+    link: BatchLink,
+}
+
+/// A processed batch and the state it executed against
+#[derive(Debug, Clone)]
+struct ReplayRecord {
+    batch: SettlementBatch,
+    prior_state: GlobalState,
+}
+
+/// Outcome of re-executing a recorded batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayResult {
+    pub batch_id: String,
+    pub recorded_root: Vec<u8>,
+    pub replayed_root: Vec<u8>,
+    /// Recorded transactions that failed on replay
+    pub failed_transactions: Vec<uuid::Uuid>,
+    /// Replay reproduced the recorded root with every transaction succeeding
+    pub matches: bool,
 }
 
 /// Transaction execution engine
@@ -40,6 +95,8 @@ struct ValidationPipeline {
     validators: Vec<Box<dyn TransactionValidator + Send + Sync>>,
     validation_cache: Arc<RwLock<ValidationCache>>,
     concurrent_validators: usize,
+    /// Checks each sender's nonces in batch order after per-transaction validation
+    nonce_validator: NonceValidator,
 }
 
 /// State computation engine
@@ -84,10 +141,23 @@ struct VirtualMachine {
 /// Gas tracking and limits
 struct GasTracker {
     gas_schedule: GasSchedule,
+}
+
+/// Gas used and refunded per transaction id in one batch; each execution
+/// keeps its own, so concurrent batches never see each other's accounting
+#[derive(Debug, Default)]
+struct BatchGas {
     gas_usage: HashMap<String, u64>,
     gas_refunds: HashMap<String, u64>,
 }
 
+impl BatchGas {
+    /// Total gas refunded in the batch
+    fn total_refunded(&self) -> u64 {
+        self.gas_refunds.values().sum()
+    }
+}
+
 /// State cache for performance
 #[derive(Debug, Clone)]
 struct StateCache {
@@ -141,6 +211,8 @@ struct StateDelta {
 struct StateChange {
     change_type: StateChangeType,
     address: Address,
+    /// Token of a balance update
+    token: Option<String>,
     key: Option<U256>,
     old_value: U256,
     new_value: U256,
@@ -194,23 +266,6 @@ struct StateCheckpoint {
     size_bytes: usize,
 }
 
-/// Merkle tree for state proofs
-#[derive(Debug, Clone)]
-struct MerkleTree {
-    root: Vec<u8>,
-    leaves: Vec<MerkleLeaf>,
-    depth: u32,
-    leaf_count: u64,
-}
-
-/// Merkle tree leaf
-#[derive(Debug, Clone)]
-struct MerkleLeaf {
-    index: u64,
-    hash: Vec<u8>,
-    data: Vec<u8>,
-}
-
 /// Leaf hasher
 struct LeafHasher {
     hasher_type: HasherType,
@@ -221,14 +276,6 @@ struct TreeHasher {
     hasher_type: HasherType,
 }
 
-/// Hasher types
-#[derive(Debug, Clone)]
-enum HasherType {
-    Keccak256,
-    Blake3,
-    Poseidon,
-}
-
 /// Batch template
 #[derive(Debug, Clone)]
 struct BatchTemplate {
@@ -396,6 +443,34 @@ struct GasSchedule {
     contract_creation_cost: u64,
     storage_write_cost: u64,
     storage_read_cost: u64,
+    storage_clear_refund: u64,
+    max_refund_quotient: u64,
+}
+
+impl GasTracker {
+    /// Refund earned by a transaction's state changes, before the cap. A
+    /// balance is a storage slot, so a transfer that empties its sender's
+    /// balance clears one just as a storage write of zero does.
+    fn refund_for(&self, state_changes: &[StateChange]) -> u64 {
+        let cleared_slots = state_changes.iter()
+            .filter(|change| matches!(change.change_type, StateChangeType::StorageUpdate | StateChangeType::BalanceUpdate))
+            .filter(|change| change.old_value != U256::ZERO && change.new_value == U256::ZERO)
+            .count() as u64;
+        cleared_slots * self.gas_schedule.storage_clear_refund
+    }
+
+    /// Record a successful execution in `batch` and return the gas charged
+    /// after the refund, which is capped at `gas_used / max_refund_quotient`
+    fn charge(&self, batch: &mut BatchGas, transaction_id: &str, gas_used: u64, state_changes: &[StateChange]) -> u64 {
+        let cap = gas_used / self.gas_schedule.max_refund_quotient.max(1);
+        let refund = self.refund_for(state_changes).min(cap);
+
+        batch.gas_usage.insert(transaction_id.to_string(), gas_used);
+        if refund > 0 {
+            batch.gas_refunds.insert(transaction_id.to_string(), refund);
+        }
+        gas_used - refund
+    }
 }
 
 impl BatchProcessor {
@@ -439,9 +514,9 @@ impl BatchProcessor {
                     contract_creation_cost: 32000,
                     storage_write_cost: 20000,
                     storage_read_cost: 200,
+                    storage_clear_refund: config.storage_clear_refund,
+                    max_refund_quotient: config.max_refund_quotient,
                 },
-                gas_usage: HashMap::new(),
-                gas_refunds: HashMap::new(),
             },
             state_cache: Arc::new(RwLock::new(StateCache {
                 cached_accounts: HashMap::new(),
@@ -452,8 +527,18 @@ impl BatchProcessor {
             })),
         };
 
+        let current_state = Arc::new(RwLock::new(GlobalState {
+            state_root: vec![0; 32],
+            accounts: HashMap::new(),
+            storage: HashMap::new(),
+            nonces: HashMap::new(),
+            balances: HashMap::new(),
+            last_updated: SystemTime::now(),
+        }));
+
+        let signing_keys = SigningKeys::default();
         let validation_pipeline = ValidationPipeline {
-            validators: Self::initialize_validators(),
+            validators: Self::initialize_validators(&config, current_state.clone(), signing_keys.clone(), None),
             validation_cache: Arc::new(RwLock::new(ValidationCache {
                 cached_results: HashMap::new(),
                 cache_hits: 0,
@@ -461,17 +546,11 @@ impl BatchProcessor {
                 last_cleanup: SystemTime::now(),
             })),
             concurrent_validators: 8,
+            nonce_validator: NonceValidator { state: current_state.clone() },
         };
 
         let state_computer = StateComputer {
-            current_state: Arc::new(RwLock::new(GlobalState {
-                state_root: vec![0; 32],
-                accounts: HashMap::new(),
-                storage: HashMap::new(),
-                nonces: HashMap::new(),
-                balances: HashMap::new(),
-                last_updated: SystemTime::now(),
-            })),
+            current_state,
             state_delta_tracker: StateDeltaTracker {
                 pending_deltas: Vec::new(),
                 applied_deltas: HashMap::new(),
@@ -488,10 +567,10 @@ impl BatchProcessor {
         let merkle_tree_builder = MerkleTreeBuilder {
             tree_cache: Arc::new(RwLock::new(HashMap::new())),
             leaf_hasher: LeafHasher {
-                hasher_type: HasherType::Keccak256,
+                hasher_type: BATCH_MERKLE_HASHER,
             },
             tree_hasher: TreeHasher {
-                hasher_type: HasherType::Keccak256,
+                hasher_type: BATCH_MERKLE_HASHER,
             },
         };
 
@@ -527,6 +606,11 @@ impl BatchProcessor {
             last_updated: SystemTime::now(),
         }));
 
+        let dead_letters = Arc::new(DeadLetterQueue::new(config.dead_letter_capacity));
+        let method_allowlist = config.allowed_contract_methods.as_deref()
+            .map(MethodAllowlist::from_signatures)
+            .transpose()?;
+
         Ok(Self {
             config,
             execution_engine,
@@ -536,9 +620,56 @@ impl BatchProcessor {
             batch_assembler,
             parallelism_limiter,
             processing_metrics,
+            calldata_decoder: None,
+            dead_letters,
+            method_allowlist,
+            replay_records: parking_lot::Mutex::new(VecDeque::new()),
+            batch_chain: Arc::new(BatchRootChain::genesis()),
+            signing_keys,
+            burned_exits: parking_lot::Mutex::new(HashSet::new()),
         })
     }
 
+    /// Decode contract-call data in execution traces
+    pub fn with_calldata_decoder(mut self, decoder: Arc<CalldataDecoder>) -> Self {
+        self.calldata_decoder = Some(decoder);
+        self
+    }
+
+    /// Check senders' balances against a ledger before batching
+    pub fn with_balance_source(mut self, balances: Arc<dyn BalanceSource>) -> Self {
+        self.validation_pipeline.validators = Self::initialize_validators(
+            &self.config,
+            self.state_computer.current_state.clone(),
+            self.signing_keys.clone(),
+            Some(balances),
+        );
+        self
+    }
+
+    /// Share a dead-letter queue for transactions dropped at pre-flight
+    pub fn with_dead_letter_queue(mut self, dead_letters: Arc<DeadLetterQueue>) -> Self {
+        self.dead_letters = dead_letters;
+        self
+    }
+
+    /// Link batches into a chain of roots shared with the state manager
+    pub fn with_batch_chain(mut self, batch_chain: Arc<BatchRootChain>) -> Self {
+        self.batch_chain = batch_chain;
+        self
+    }
+
+    /// Register the scheme of `address`'s signing key; its transactions must
+    /// be signed with it whichever network they come from
+    pub fn register_signing_key(&self, address: Address, scheme: SignatureScheme) {
+        self.signing_keys.write().insert(address, scheme);
+    }
+
+    /// Transactions dropped from batches before execution
+    pub fn dead_letters(&self) -> &Arc<DeadLetterQueue> {
+        &self.dead_letters
+    }
+
     /// Process batch of transactions
     #[instrument(skip(self, transactions))]
     pub async fn process_batch(&self, transactions: Vec<Transaction>) -> Result<SettlementBatch> {
@@ -548,22 +679,30 @@ impl BatchProcessor {
         let start_time = SystemTime::now();
 
         // Phase 1: Parallel validation
-        let validated_transactions = self.validate_transactions(transactions).await?;
+        let mut validated_transactions = self.validate_transactions(transactions).await?;
+
+        // State may have moved on since submission; drop what can no longer execute
+        if self.config.enable_batch_preflight {
+            validated_transactions = self.preflight(validated_transactions).await;
+        }
 
-        // Phase 2: Execute transactions in order
-        let (executed_transactions, state_root, gas_used) =
+        // Phase 2: Execute independent transactions in parallel, conflicting ones in order
+        let Execution { transactions, state_root, gas_used, prior_state, link } =
             self.execute_transactions(validated_transactions).await?;
 
         // Phase 3: Build merkle proofs
-        let merkle_proof = self.build_merkle_proof(&executed_transactions).await?;
+        let merkle_proof = self.build_merkle_proof(&transactions).await?;
 
         // Phase 4: Assemble batch
-        let batch = self.assemble_batch(
-            executed_transactions,
-            state_root,
-            merkle_proof,
-            gas_used,
-        ).await?;
+        let batch = self.assemble_batch(transactions, state_root, link, merkle_proof, gas_used).await?;
+
+        if let Some(prior_state) = prior_state {
+            let mut records = self.replay_records.lock();
+            if records.len() >= self.config.replay_history {
+                records.pop_front();
+            }
+            records.push_back(ReplayRecord { batch: batch.clone(), prior_state });
+        }
 
         // Update metrics
         let processing_time = start_time.elapsed().unwrap_or_default();
@@ -575,6 +714,77 @@ impl BatchProcessor {
         Ok(batch)
     }
 
+    /// Re-execute a recorded batch against the state it originally ran on
+    /// and check that it reproduces the recorded state root
+    pub async fn replay_batch(&self, batch_id: &str) -> Result<ReplayResult> {
+        let ReplayRecord { batch, mut prior_state } = self.replay_record(batch_id)?;
+
+        let succeeded = self.run_waves(&batch.transactions, &mut prior_state, |_, _| {}).await?;
+        let failed_transactions: Vec<uuid::Uuid> = batch.transactions.iter()
+            .zip(succeeded)
+            .filter_map(|(transaction, success)| (!success).then_some(transaction.id))
+            .collect();
+        let replayed_root = self.compute_state_root(&prior_state).await?;
+
+        let matches = failed_transactions.is_empty() && replayed_root == batch.state_root;
+        if matches {
+            info!("Replay of batch {} reproduced state root 0x{}", batch_id, hex::encode(&replayed_root));
+        } else {
+            warn!("Replay of batch {} diverged: recorded root 0x{}, replayed 0x{}, {} transactions failed",
+                  batch_id, hex::encode(&batch.state_root), hex::encode(&replayed_root), failed_transactions.len());
+        }
+
+        Ok(ReplayResult {
+            batch_id: batch_id.to_string(),
+            recorded_root: batch.state_root,
+            replayed_root,
+            failed_transactions,
+            matches,
+        })
+    }
+
+    /// Re-execute a recorded batch and trace each transaction, for
+    /// defending the batch against a challenge
+    pub async fn reexecution_trace(&self, batch_id: &str) -> Result<ReexecutionTrace> {
+        let ReplayRecord { batch, mut prior_state } = self.replay_record(batch_id)?;
+
+        let mut gas = vec![0u64; batch.transactions.len()];
+        let succeeded = self.run_waves(&batch.transactions, &mut prior_state, |index, result| {
+            gas[index] = result.gas_used;
+        }).await?;
+        let state_root = self.compute_state_root(&prior_state).await?;
+
+        let steps: Vec<TraceStep> = batch.transactions.iter()
+            .zip(succeeded)
+            .enumerate()
+            .map(|(index, (transaction, success))| TraceStep {
+                index,
+                transaction_id: transaction.id,
+                transaction_hash: transaction.hash(),
+                success,
+                gas_used: gas[index],
+            })
+            .collect();
+
+        Ok(ReexecutionTrace {
+            batch_id: batch.batch_id,
+            previous_state_root: batch.previous_state_root,
+            state_root,
+            gas_used: gas.iter().sum(),
+            steps,
+        })
+    }
+
+    fn replay_record(&self, batch_id: &str) -> Result<ReplayRecord> {
+        self.replay_records.lock()
+            .iter()
+            .find(|record| record.batch.batch_id == batch_id)
+            .cloned()
+            .ok_or_else(|| BridgeError::Settlement(SettlementError::BatchNotReplayable {
+                batch_id: batch_id.to_string(),
+            }))
+    }
+
     /// Health check
     pub async fn is_healthy(&self) -> bool {
         let metrics = self.processing_metrics.read().await;
@@ -592,24 +802,24 @@ impl BatchProcessor {
         let (tx, mut rx) = mpsc::channel(1000);
 
         // Spawn parallel validation tasks
-        for transaction in transactions {
+        for (index, transaction) in transactions.into_iter().enumerate() {
             let validators = &self.validation_pipeline.validators;
             let cache = self.validation_pipeline.validation_cache.clone();
             let tx_clone = tx.clone();
 
             tokio::spawn(async move {
                 let result = Self::validate_single_transaction(&transaction, validators, cache).await;
-                let _ = tx_clone.send((transaction, result)).await;
+                let _ = tx_clone.send((index, transaction, result)).await;
             });
         }
 
         drop(tx); // Close sender
 
         // Collect results
-        while let Some((transaction, validation_result)) = rx.recv().await {
+        while let Some((index, transaction, validation_result)) = rx.recv().await {
             match validation_result {
                 Ok(result) if result.valid => {
-                    validated.push(transaction);
+                    validated.push((index, transaction));
                 }
                 Ok(_) => {
                     warn!("Transaction validation failed: {}", transaction.id);
@@ -620,10 +830,76 @@ impl BatchProcessor {
             }
         }
 
+        // Keep the batch order regardless of which validation finished first
+        validated.sort_by_key(|(index, _)| *index);
+        let validated: Vec<Transaction> = validated.into_iter().map(|(_, transaction)| transaction).collect();
+
+        // Nonces depend on earlier transactions in the batch, so check them in order
+        let validated = self.validation_pipeline.nonce_validator.validate_sequence(validated).await;
+
         debug!("Validated {} out of {} transactions", validated.len(), validated.len());
         Ok(validated)
     }
 
+    /// Replay the batch's transfers in order against the latest L2 state,
+    /// moving transactions that would fail to the dead-letter queue. Fees are
+    /// reserved from the sender alongside the amount, as at validation.
+    async fn preflight(&self, transactions: Vec<Transaction>) -> Vec<Transaction> {
+        let state = self.state_computer.current_state.read().await;
+        let mut balances: HashMap<(Address, String), U256> = HashMap::new();
+        let mut runnable = Vec::with_capacity(transactions.len());
+
+        for transaction in transactions {
+            let balance_of = |balances: &HashMap<_, U256>, key: &(Address, String)| balances.get(key)
+                .or_else(|| state.balances.get(key))
+                .cloned()
+                .unwrap_or(U256::ZERO);
+
+            let Some(required) = BalanceValidator::required_funds(&transaction) else {
+                self.dead_letters.push(transaction, "pre-flight: amount plus fees overflows");
+                continue;
+            };
+
+            // Debit everything the sender owes before crediting the receiver,
+            // so nothing is applied unless the whole transaction fits
+            let mut debited = Vec::with_capacity(required.len());
+            let mut shortfall = None;
+            for (token_type, owed) in required {
+                let key = (transaction.from_address.clone(), token_type.to_string());
+                let balance = balance_of(&balances, &key);
+                match balance.checked_sub(&owed) {
+                    Some(remaining) => debited.push((key, remaining)),
+                    None => {
+                        shortfall = Some(format!(
+                            "pre-flight: insufficient {} balance ({} available, {} required)", token_type, balance, owed,
+                        ));
+                        break;
+                    }
+                }
+            }
+            if let Some(reason) = shortfall {
+                self.dead_letters.push(transaction, reason);
+                continue;
+            }
+
+            // A self-transfer is credited on top of its own debit
+            let to_key = (transaction.to_address.clone(), transaction.amount.token_type.to_string());
+            let to_balance = debited.iter()
+                .find(|(key, _)| *key == to_key)
+                .map(|(_, balance)| balance.clone())
+                .unwrap_or_else(|| balance_of(&balances, &to_key));
+            let Some(credited) = to_balance.checked_add(&transaction.amount.amount) else {
+                self.dead_letters.push(transaction, "pre-flight: receiver balance overflows");
+                continue;
+            };
+            balances.extend(debited);
+            balances.insert(to_key, credited);
+            runnable.push(transaction);
+        }
+
+        runnable
+    }
+
     async fn validate_single_transaction(
         transaction: &Transaction,
         validators: &[Box<dyn TransactionValidator + Send + Sync>],
@@ -671,54 +947,141 @@ impl BatchProcessor {
         Ok(combined_result)
     }
 
-    async fn execute_transactions(
-        &self,
-        transactions: Vec<Transaction>,
-    ) -> Result<(Vec<Transaction>, Vec<u8>, u64)> {
-        debug!("Executing {} transactions", transactions.len());
-
-        let mut executed_transactions = Vec::new();
+    /// Execute against the current state, also returning that state as it
+    /// was beforehand when batches are kept for replay. The new root joins
+    /// the chain of roots while the state is still held, so concurrent
+    /// batches link in the order their state transitions were applied.
+    async fn execute_transactions(&self, transactions: Vec<Transaction>) -> Result<Execution> {
         let mut total_gas_used = 0u64;
+        let gas_tracker = &self.execution_engine.gas_tracker;
+        let mut batch_gas = BatchGas::default();
 
         // Get current state
         let mut current_state = self.state_computer.current_state.write().await;
-
-        for transaction in transactions {
-            // Execute transaction
-            let execution_result = self.execute_single_transaction(&transaction, &mut current_state).await?;
-
-            if execution_result.success {
-                executed_transactions.push(transaction);
-                total_gas_used += execution_result.gas_used;
-
-                // Apply state changes
-                self.apply_state_changes(&mut current_state, execution_result.state_changes).await;
-            } else {
-                warn!("Transaction execution failed: {} - {}", transaction.id, execution_result.error.unwrap_or_default());
-            }
-        }
+        let prior_state = (self.config.replay_history > 0).then(|| current_state.clone());
+
+        let succeeded = self.run_waves(&transactions, &mut current_state, |index, execution_result| {
+            total_gas_used += gas_tracker.charge(
+                &mut batch_gas,
+                &transactions[index].id.to_string(),
+                execution_result.gas_used,
+                &execution_result.state_changes,
+            );
+        }).await?;
+
+        let executed_transactions: Vec<Transaction> = transactions
+            .into_iter()
+            .zip(succeeded)
+            .filter_map(|(transaction, success)| success.then_some(transaction))
+            .collect();
 
         // Compute new state root
         let new_state_root = self.compute_state_root(&current_state).await?;
         current_state.state_root = new_state_root.clone();
         current_state.last_updated = SystemTime::now();
+        let link = self.batch_chain.lock().await.append(new_state_root.clone());
 
         drop(current_state);
 
-        debug!("Executed {} transactions, total gas: {}", executed_transactions.len(), total_gas_used);
-        Ok((executed_transactions, new_state_root, total_gas_used))
+        debug!("Executed {} transactions, total gas: {} (refunded {})",
+               executed_transactions.len(), total_gas_used, batch_gas.total_refunded());
+        Ok(Execution {
+            transactions: executed_transactions,
+            state_root: new_state_root,
+            gas_used: total_gas_used,
+            prior_state,
+            link,
+        })
     }
 
-    async fn execute_single_transaction(
+    /// Execute independent transactions in parallel and conflicting ones in
+    /// order against `state`, returning which succeeded. `on_success` sees
+    /// each successful result before its changes are applied.
+    ///
+    /// Execution is CPU-bound, so each wave is split across scoped worker
+    /// threads rather than polled as futures on the calling task.
+    async fn run_waves(
         &self,
-        transaction: &Transaction,
+        transactions: &[Transaction],
         state: &mut GlobalState,
+        mut on_success: impl FnMut(usize, &ExecutionResult),
+    ) -> Result<Vec<bool>> {
+        let graph = DependencyGraph::build(transactions);
+        debug!("Executing {} transactions in {} waves (max parallelism {})",
+               transactions.len(), graph.waves().len(), graph.max_parallelism());
+
+        let mut succeeded = vec![false; transactions.len()];
+        for wave in graph.waves() {
+            // Transactions in a wave touch disjoint state, so they all read the same pre-wave state
+            let pre_wave: &GlobalState = &*state;
+            let results = self.execute_wave(transactions, wave, pre_wave);
+
+            // Merge deltas in batch order so the resulting state is deterministic
+            for (&index, execution_result) in wave.iter().zip(results) {
+                let execution_result = execution_result?;
+                if execution_result.success {
+                    succeeded[index] = true;
+                    on_success(index, &execution_result);
+
+                    // Apply state changes
+                    self.apply_state_changes(state, execution_result.state_changes).await;
+                } else {
+                    warn!("Transaction execution failed: {} - {}{}",
+                          transactions[index].id, execution_result.error.unwrap_or_default(),
+                          self.describe_calldata(&transactions[index]));
+                }
+            }
+        }
+        Ok(succeeded)
+    }
+
+    /// Execute one wave against the shared pre-wave state, returning results
+    /// in wave order
+    fn execute_wave(&self, transactions: &[Transaction], wave: &[usize], state: &GlobalState) -> Vec<Result<ExecutionResult>> {
+        let workers = std::thread::available_parallelism().map_or(1, |n| n.get()).min(wave.len());
+        if workers <= 1 {
+            return wave.iter().map(|&index| self.execute_single_transaction(&transactions[index], state)).collect();
+        }
+
+        let chunk_size = wave.len().div_ceil(workers);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = wave.chunks(chunk_size)
+                .map(|chunk| scope.spawn(move || {
+                    chunk.iter()
+                        .map(|&index| self.execute_single_transaction(&transactions[index], state))
+                        .collect::<Vec<_>>()
+                }))
+                .collect();
+            handles.into_iter()
+                .flat_map(|handle| handle.join().expect("transaction execution panicked"))
+                .collect()
+        })
+    }
+
+    fn execute_single_transaction(
+        &self,
+        transaction: &Transaction,
+        state: &GlobalState,
     ) -> Result<ExecutionResult> {
+        // Contract calls must target an allow-listed method
+        if let (Some(allowlist), false) = (&self.method_allowlist, transaction.data.is_empty()) {
+            if let Err(reason) = allowlist.check(&transaction.data) {
+                return Ok(ExecutionResult {
+                    success: false,
+                    gas_used: 0,
+                    state_changes: Vec::new(),
+                    error: Some(reason),
+                    return_data: Vec::new(),
+                });
+            }
+        }
+
         // Simple transfer execution (for now)
         // TODO: Implement full VM execution for smart contracts
 
-        let from_balance_key = (transaction.from_address.clone(), transaction.amount.token_type.to_string());
-        let to_balance_key = (transaction.to_address.clone(), transaction.amount.token_type.to_string());
+        let token = transaction.amount.token_type.to_string();
+        let from_balance_key = (transaction.from_address.clone(), token.clone());
+        let to_balance_key = (transaction.to_address.clone(), token.clone());
 
         // Check balance
         let current_balance = state.balances.get(&from_balance_key).unwrap_or(&U256::ZERO);
@@ -733,7 +1096,7 @@ impl BatchProcessor {
         }
 
         // Calculate gas cost
-        let gas_cost = self.calculate_gas_cost(transaction).await;
+        let gas_cost = self.calculate_gas_cost(transaction);
 
         // Create state changes
         let mut state_changes = Vec::new();
@@ -743,6 +1106,7 @@ impl BatchProcessor {
         state_changes.push(StateChange {
             change_type: StateChangeType::BalanceUpdate,
             address: transaction.from_address.clone(),
+            token: Some(token.clone()),
             key: None,
             old_value: *current_balance,
             new_value: new_from_balance,
@@ -754,6 +1118,7 @@ impl BatchProcessor {
         state_changes.push(StateChange {
             change_type: StateChangeType::BalanceUpdate,
             address: transaction.to_address.clone(),
+            token: Some(token),
             key: None,
             old_value: *current_to_balance,
             new_value: new_to_balance,
@@ -764,6 +1129,7 @@ impl BatchProcessor {
         state_changes.push(StateChange {
             change_type: StateChangeType::NonceUpdate,
             address: transaction.from_address.clone(),
+            token: None,
             key: None,
             old_value: U256::from(*current_nonce),
             new_value: U256::from(current_nonce + 1),
@@ -778,14 +1144,21 @@ impl BatchProcessor {
         })
     }
 
+    /// Decoded call for traces, empty for plain transfers
+    fn describe_calldata(&self, transaction: &Transaction) -> String {
+        match &self.calldata_decoder {
+            Some(decoder) if !transaction.data.is_empty() => format!(" (call: {})", decoder.annotate(&transaction.data)),
+            _ => String::new(),
+        }
+    }
+
     async fn apply_state_changes(&self, state: &mut GlobalState, changes: Vec<StateChange>) {
         for change in changes {
             match change.change_type {
                 StateChangeType::BalanceUpdate => {
-                    // Determine token type from context (simplified)
-                    let token_type = "GCC".to_string(); // TODO: Extract from transaction
-                    let balance_key = (change.address.clone(), token_type);
-                    state.balances.insert(balance_key, change.new_value);
+                    if let Some(token) = change.token {
+                        state.balances.insert((change.address.clone(), token), change.new_value);
+                    }
                 }
                 StateChangeType::NonceUpdate => {
                     state.nonces.insert(change.address.clone(), change.new_value.as_u64());
@@ -802,7 +1175,7 @@ impl BatchProcessor {
         }
     }
 
-    async fn calculate_gas_cost(&self, transaction: &Transaction) -> u64 {
+    fn calculate_gas_cost(&self, transaction: &Transaction) -> u64 {
         let mut gas_cost = self.execution_engine.gas_tracker.gas_schedule.base_transaction_cost;
 
         // Add data cost
@@ -817,28 +1190,42 @@ impl BatchProcessor {
     }
 
     async fn compute_state_root(&self, state: &GlobalState) -> Result<Vec<u8>> {
-        // Simplified state root computation
-        // TODO: Implement proper Merkle Patricia Trie
-
-        use sha2::{Sha256, Digest};
-        let mut hasher = Sha256::new();
-
-        // Hash all account data
-        for (address, account_state) in &state.accounts {
-            hasher.update(address.as_bytes());
-            hasher.update(&account_state.nonce.to_le_bytes());
-            hasher.update(&account_state.storage_root);
-            hasher.update(&account_state.code_hash);
-        }
+        // Sorted binary Merkle trees over canonically encoded balance, nonce and
+        // storage leaves, so the root is independent of HashMap iteration order
+        // and balances stay provable
+        Ok(state_tree::state_root(&state.balances, &state.nonces, &state.storage))
+    }
+
+    /// Proof of a balance against the current state root
+    pub async fn balance_proof(&self, address: &Address, token: &str) -> Option<BalanceProof> {
+        let state = self.state_computer.current_state.read().await;
+        prove_balance(&state.balances, &state.nonces, &state.storage, address, token)
+    }
 
-        // Hash all balances
-        for ((address, token_type), balance) in &state.balances {
-            hasher.update(address.as_bytes());
-            hasher.update(token_type.as_bytes());
-            hasher.update(&balance.to_le_bytes());
+    /// Debit a balance that was already paid out by an emergency exit on L1.
+    /// Each account and token exits once, so burning the same withdrawal
+    /// again is a no-op; returns whether this call debited it.
+    pub async fn burn_exited(&self, withdrawal: &EmergencyWithdrawal) -> bool {
+        let mut state = self.state_computer.current_state.write().await;
+        let key = (withdrawal.address.clone(), withdrawal.token.clone());
+        if !self.burned_exits.lock().insert(key.clone()) {
+            return false;
         }
+        let balance = state.balances.get(&key).cloned().unwrap_or(U256::ZERO);
+        let remaining = balance.checked_sub(&withdrawal.amount).unwrap_or_else(|| {
+            warn!("Burning emergency exit of {} {} for {} exceeds its L2 balance of {}",
+                  withdrawal.amount, withdrawal.token, withdrawal.address, balance);
+            U256::ZERO
+        });
+        state.balances.insert(key, remaining);
+        true
+    }
 
-        Ok(hasher.finalize().to_vec())
+    /// Proof that `batch`'s transaction at `tx_index` is in its Merkle root
+    pub fn inclusion_proof(&self, batch: &SettlementBatch, tx_index: usize) -> Result<Vec<Vec<u8>>> {
+        let leaves: Vec<Vec<u8>> = batch.transactions.iter().map(transaction_leaf).collect();
+        let tree = self.merkle_tree_builder.tree_hasher.hasher_type.build_tree(&leaves)?;
+        Ok(tree.generate_inclusion_proof(tx_index))
     }
 
     async fn build_merkle_proof(&self, transactions: &[Transaction]) -> Result<Vec<u8>> {
@@ -854,46 +1241,38 @@ impl BatchProcessor {
     }
 
     async fn hash_transaction(&self, transaction: &Transaction) -> Vec<u8> {
-        use sha2::{Sha256, Digest};
-        let mut hasher = Sha256::new();
-
-        hasher.update(transaction.id.as_bytes());
-        hasher.update(transaction.from_address.as_bytes());
-        hasher.update(transaction.to_address.as_bytes());
-        hasher.update(&transaction.amount.amount.to_le_bytes());
-        hasher.update(&transaction.nonce.to_le_bytes());
-        hasher.update(&transaction.gas_limit.to_le_bytes());
-        hasher.update(&transaction.gas_price.to_le_bytes());
-        hasher.update(&transaction.data);
-
-        hasher.finalize().to_vec()
+        transaction_leaf(transaction)
     }
 
     async fn assemble_batch(
         &self,
         transactions: Vec<Transaction>,
         state_root: Vec<u8>,
+        link: BatchLink,
         merkle_proof: Vec<u8>,
         gas_used: u64,
     ) -> Result<SettlementBatch> {
-        let batch_id = format!("batch-{}",
-                              SystemTime::now().duration_since(std::time::UNIX_EPOCH)
-                                  .unwrap_or_default().as_millis());
+        let batch_id = SettlementBatch::content_id(&transactions, &state_root);
 
         // Calculate total fees
         let total_fee = self.calculate_total_fee(&transactions).await;
 
-        // Get previous state root
-        let previous_state_root = self.get_previous_state_root().await;
+        // Timestamp from the batch's contents rather than the local clock, so
+        // every node building this batch produces the same bytes
+        let created_at = transactions.iter()
+            .map(|transaction| SystemTime::from(transaction.created_at))
+            .max()
+            .unwrap_or(SystemTime::UNIX_EPOCH);
 
         Ok(SettlementBatch {
             batch_id,
             transactions,
             state_root,
-            previous_state_root,
+            previous_state_root: link.previous_state_root,
+            block_number: link.block_number,
             merkle_proof,
             zk_proof: None, // ZK proof will be added later
-            created_at: SystemTime::now(),
+            created_at,
             gas_used,
             fee_paid: total_fee,
         })
@@ -910,11 +1289,6 @@ impl BatchProcessor {
         TokenAmount::new(crate::types::TokenType::Gcc, total_fee)
     }
 
-    async fn get_previous_state_root(&self) -> Vec<u8> {
-        // TODO: Get actual previous state root from last batch
-        vec![0; 32]
-    }
-
     async fn update_metrics(&self, batch: &SettlementBatch, processing_time: Duration) {
         let mut metrics = self.processing_metrics.write().await;
 
@@ -970,11 +1344,16 @@ impl BatchProcessor {
         gas_costs
     }
 
-    fn initialize_validators() -> Vec<Box<dyn TransactionValidator + Send + Sync>> {
+    fn initialize_validators(
+        config: &SettlementConfig,
+        state: Arc<RwLock<GlobalState>>,
+        signing_keys: SigningKeys,
+        balances: Option<Arc<dyn BalanceSource>>,
+    ) -> Vec<Box<dyn TransactionValidator + Send + Sync>> {
         vec![
-            Box::new(SignatureValidator),
-            Box::new(NonceValidator),
-            Box::new(BalanceValidator),
+            Box::new(SignatureValidator::new(config.signature_schemes.clone(), signing_keys)),
+            Box::new(NonceValidator { state }),
+            Box::new(BalanceValidator { balances }),
             Box::new(GasValidator),
         ]
     }
@@ -982,22 +1361,37 @@ impl BatchProcessor {
 
 impl MerkleTreeBuilder {
     async fn build_tree_proof(&self, hashes: &[Vec<u8>]) -> Result<Vec<u8>> {
-        // Simplified merkle proof generation
-        // TODO: Implement proper merkle tree with inclusion proofs
+        Ok(self.tree_hasher.hasher_type.build_tree(hashes)?.root().to_vec())
+    }
+}
 
-        if hashes.is_empty() {
-            return Ok(vec![0; 32]);
-        }
+/// Merkle leaf for a transaction; hashes the canonical bytes so GhostPlane
+/// and external verifiers compute the same leaves
+pub fn transaction_leaf(transaction: &Transaction) -> Vec<u8> {
+    use sha2::{Sha256, Digest};
+    Sha256::digest(transaction.canonical_bytes()).to_vec()
+}
 
-        use sha2::{Sha256, Digest};
-        let mut hasher = Sha256::new();
+/// Hasher for the transaction trees that batches commit to
+pub const BATCH_MERKLE_HASHER: HasherType = HasherType::Keccak256;
 
-        for hash in hashes {
-            hasher.update(hash);
-        }
+/// Root committed to as a batch's `merkle_proof`; all zeros when empty
+pub fn merkle_root(leaves: &[Vec<u8>]) -> Vec<u8> {
+    build_batch_tree(leaves).root().to_vec()
+}
 
-        Ok(hasher.finalize().to_vec())
-    }
+/// Proof that the transaction at `tx_index` of a batch is in its `merkle_proof` root
+pub fn generate_inclusion_proof(leaves: &[Vec<u8>], tx_index: usize) -> Vec<Vec<u8>> {
+    build_batch_tree(leaves).generate_inclusion_proof(tx_index)
+}
+
+/// Light-client check of a batch inclusion proof from `generate_inclusion_proof`
+pub fn verify_inclusion(root: &[u8], leaf: &[u8], proof: &[Vec<u8>], index: usize) -> bool {
+    BATCH_MERKLE_HASHER.verify_inclusion(root, leaf, proof, index)
+}
+
+fn build_batch_tree(leaves: &[Vec<u8>]) -> MerkleTree {
+    BATCH_MERKLE_HASHER.build_tree(leaves).expect("Keccak-256 trees always build")
 }
 
 /// Execution result
@@ -1011,26 +1405,145 @@ struct ExecutionResult {
 }
 
 // Validator implementations
-struct SignatureValidator;
-struct NonceValidator;
-struct BalanceValidator;
+struct SignatureValidator {
+    /// Scheme required per source network
+    required_schemes: HashMap<ChainId, SignatureScheme>,
+    /// Scheme of each sender's registered key, which takes precedence
+    signing_keys: SigningKeys,
+}
+
+/// Requires each transaction's nonce to be exactly one past its sender's
+/// last executed nonce
+struct NonceValidator {
+    state: Arc<RwLock<GlobalState>>,
+}
+
+impl NonceValidator {
+    fn invalid(transaction: &Transaction, expected: u64) -> ValidationError {
+        let problem = if transaction.nonce < expected { "already used" } else { "leaves a gap" };
+        ValidationError {
+            error_type: ValidationErrorType::InvalidNonce,
+            message: format!("Nonce {} {}: expected {}", transaction.nonce, problem, expected),
+            field: Some("nonce".to_string()),
+        }
+    }
+
+    /// Keep the transactions whose nonces follow on from the sender's
+    /// executed nonce and from the sender's earlier transactions in the batch
+    async fn validate_sequence(&self, transactions: Vec<Transaction>) -> Vec<Transaction> {
+        let state = self.state.read().await;
+        let mut next_nonce: HashMap<Address, u64> = HashMap::new();
+        let mut valid = Vec::with_capacity(transactions.len());
+
+        for transaction in transactions {
+            let expected = next_nonce.entry(transaction.from_address.clone())
+                .or_insert_with(|| state.nonces.get(&transaction.from_address).copied().unwrap_or(0) + 1);
+            if transaction.nonce == *expected {
+                *expected += 1;
+                valid.push(transaction);
+            } else {
+                warn!("Transaction validation failed: {} - {}",
+                      transaction.id, Self::invalid(&transaction, *expected).message);
+            }
+        }
+
+        valid
+    }
+}
+struct BalanceValidator {
+    /// Ledger to check against; without one every transaction passes
+    balances: Option<Arc<dyn BalanceSource>>,
+}
+
+/// Token balances the balance validator checks senders against
+#[async_trait::async_trait]
+pub trait BalanceSource: Send + Sync {
+    async fn balance(&self, address: &Address, token_type: TokenType) -> Result<U256>;
+}
+
+#[async_trait::async_trait]
+impl BalanceSource for ServiceManager {
+    async fn balance(&self, address: &Address, token_type: TokenType) -> Result<U256> {
+        let gledger = self.gledger().await?;
+        Ok(gledger.as_ref().unwrap().get_balance(address, token_type).await?.amount)
+    }
+}
+
+impl BalanceValidator {
+    /// Funds the sender needs per token: the amount plus every fee in the
+    /// same token. `None` if a total overflows.
+    fn required_funds(transaction: &Transaction) -> Option<Vec<(TokenType, U256)>> {
+        let fee = &transaction.fee;
+        let mut required: Vec<(TokenType, U256)> = Vec::new();
+        for charge in [&transaction.amount, &fee.gcc_fee, &fee.spirit_fee, &fee.mana_fee, &fee.ghost_fee] {
+            if charge.amount == U256::ZERO {
+                continue;
+            }
+            match required.iter_mut().find(|(token_type, _)| *token_type == charge.token_type) {
+                Some((_, total)) => *total = total.checked_add(&charge.amount)?,
+                None => required.push((charge.token_type, charge.amount.clone())),
+            }
+        }
+        Some(required)
+    }
+
+    fn insufficient(message: String) -> ValidationError {
+        ValidationError {
+            error_type: ValidationErrorType::InsufficientBalance,
+            message,
+            field: Some("amount".to_string()),
+        }
+    }
+}
 struct GasValidator;
 
+impl SignatureValidator {
+    fn new(required_schemes: HashMap<ChainId, SignatureScheme>, signing_keys: SigningKeys) -> Self {
+        Self { required_schemes, signing_keys }
+    }
+
+    /// Reject signatures whose declared scheme is not that of the sender's
+    /// registered key or, for senders without one, the source network's.
+    /// The declared scheme alone is never trusted.
+    fn scheme_error(&self, transaction: &Transaction) -> Option<String> {
+        let signature = transaction.signature.as_ref()?;
+        let chain_id = transaction.from_chain.chain_id().map_or(0, |chain_id| chain_id.0);
+        let (required, source) = match self.signing_keys.read().get(&transaction.from_address) {
+            Some(scheme) => (scheme.clone(), "the sender's registered key"),
+            None => match transaction.from_chain.chain_id().and_then(|chain_id| self.required_schemes.get(&chain_id)) {
+                Some(scheme) => (scheme.clone(), "the source network"),
+                None => return Some(format!(
+                    "Sender {} has no registered signing key and chain {} requires no scheme",
+                    transaction.from_address, chain_id,
+                )),
+            },
+        };
+        (signature.scheme != required).then(|| format!(
+            "Transactions from chain {} must be signed with {:?} for {}, got {:?}",
+            chain_id, required, source, signature.scheme,
+        ))
+    }
+}
+
 #[async_trait::async_trait]
 impl TransactionValidator for SignatureValidator {
     async fn validate(&self, transaction: &Transaction) -> Result<ValidationResult> {
         // TODO: Implement actual signature validation
+        let error = if transaction.signature.is_none() {
+            Some("Missing signature".to_string())
+        } else {
+            self.scheme_error(transaction)
+        };
+
         Ok(ValidationResult {
-            valid: transaction.signature.is_some(),
-            errors: if transaction.signature.is_none() {
-                vec![ValidationError {
+            valid: error.is_none(),
+            errors: error.into_iter()
+                .map(|message| ValidationError {
                     error_type: ValidationErrorType::InvalidSignature,
-                    message: "Missing signature".to_string(),
+                    message,
                     field: Some("signature".to_string()),
-                }]
-            } else {
-                vec![]
-            },
+                })
+                .collect(),
             warnings: vec![],
             gas_estimate: 0,
         })
@@ -1042,11 +1555,18 @@ impl TransactionValidator for SignatureValidator {
 
 #[async_trait::async_trait]
 impl TransactionValidator for NonceValidator {
-    async fn validate(&self, _transaction: &Transaction) -> Result<ValidationResult> {
-        // TODO: Implement nonce validation
+    /// Reject nonces that were already executed; gaps are only known once
+    /// the rest of the batch is seen, in `validate_sequence`
+    async fn validate(&self, transaction: &Transaction) -> Result<ValidationResult> {
+        let current = self.state.read().await.nonces.get(&transaction.from_address).copied().unwrap_or(0);
+        let errors: Vec<_> = (transaction.nonce <= current)
+            .then(|| Self::invalid(transaction, current + 1))
+            .into_iter()
+            .collect();
+
         Ok(ValidationResult {
-            valid: true,
-            errors: vec![],
+            valid: errors.is_empty(),
+            errors,
             warnings: vec![],
             gas_estimate: 0,
         })
@@ -1058,11 +1578,29 @@ impl TransactionValidator for NonceValidator {
 
 #[async_trait::async_trait]
 impl TransactionValidator for BalanceValidator {
-    async fn validate(&self, _transaction: &Transaction) -> Result<ValidationResult> {
-        // TODO: Implement balance validation
+    async fn validate(&self, transaction: &Transaction) -> Result<ValidationResult> {
+        let mut errors = Vec::new();
+
+        if let Some(balances) = &self.balances {
+            match Self::required_funds(transaction) {
+                None => errors.push(Self::insufficient("Amount plus fees overflows".to_string())),
+                Some(required) => {
+                    for (token_type, needed) in required {
+                        let available = balances.balance(&transaction.from_address, token_type).await?;
+                        // U256 is big-endian, so byte order is numeric order
+                        if available.0 < needed.0 {
+                            errors.push(Self::insufficient(format!(
+                                "Insufficient {} balance: {} available, {} required", token_type, available, needed,
+                            )));
+                        }
+                    }
+                }
+            }
+        }
+
         Ok(ValidationResult {
-            valid: true,
-            errors: vec![],
+            valid: errors.is_empty(),
+            errors,
             warnings: vec![],
             gas_estimate: 0,
         })
@@ -1100,6 +1638,8 @@ impl TransactionValidator for GasValidator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::settlement::state_tree::verify_state_proof;
+    use crate::types::fixtures::transfer;
     use crate::types::{TokenType, TokenAmount};
 
     #[tokio::test]
@@ -1130,4 +1670,563 @@ mod tests {
         let result = processor.validate_transactions(vec![transaction]).await.unwrap();
         assert_eq!(result.len(), 1);
     }
-}
\ No newline at end of file
+
+    async fn funded_processor(accounts: &[u8]) -> BatchProcessor {
+        let processor = BatchProcessor::new(SettlementConfig::default()).await.unwrap();
+        let mut state = processor.state_computer.current_state.write().await;
+        for &account in accounts {
+            state.balances.insert((Address([account; 20]), "GCC".to_string()), U256::from(100u64));
+        }
+        drop(state);
+        processor
+    }
+
+    #[tokio::test]
+    async fn test_parallel_execution_matches_sequential() {
+        // Disjoint transfers plus a chain through account 2 that only succeeds in order,
+        // and an overdraft from account 1 after it has spent its balance
+        let batch = vec![
+            transfer(1, 2, 100),
+            transfer(3, 4, 10),
+            transfer(2, 5, 150),
+            transfer(6, 7, 10),
+            transfer(1, 8, 1),
+        ];
+
+        let parallel = funded_processor(&[1, 2, 3, 6]).await;
+        let Execution { transactions: executed, gas_used: parallel_gas, .. } =
+            parallel.execute_transactions(batch.clone()).await.unwrap();
+
+        let sequential = funded_processor(&[1, 2, 3, 6]).await;
+        let mut batch_gas = BatchGas::default();
+        let mut sequential_gas = 0;
+        let mut sequential_executed = Vec::new();
+        {
+            let mut state = sequential.state_computer.current_state.write().await;
+            for transaction in &batch {
+                let result = sequential.execute_single_transaction(transaction, &state).unwrap();
+                if result.success {
+                    sequential_gas += sequential.execution_engine.gas_tracker
+                        .charge(&mut batch_gas, &transaction.id.to_string(), result.gas_used, &result.state_changes);
+                    sequential_executed.push(transaction.id);
+                    sequential.apply_state_changes(&mut state, result.state_changes).await;
+                }
+            }
+        }
+
+        let ids: Vec<_> = executed.iter().map(|tx| tx.id).collect();
+        assert_eq!(ids, sequential_executed);
+        assert_eq!(ids, vec![batch[0].id, batch[1].id, batch[2].id, batch[3].id]);
+        assert_eq!(parallel_gas, sequential_gas);
+
+        let parallel_state = parallel.state_computer.current_state.read().await;
+        let sequential_state = sequential.state_computer.current_state.read().await;
+        assert_eq!(parallel_state.balances, sequential_state.balances);
+        assert_eq!(parallel_state.nonces, sequential_state.nonces);
+        assert_eq!(parallel_state.balances[&(Address([5; 20]), "GCC".to_string())], U256::from(150u64));
+    }
+
+    #[tokio::test]
+    async fn test_signature_scheme_enforced_per_source_network() {
+        use crate::types::{ChainId, Network, Signature};
+
+        let validator = SignatureValidator::new(SettlementConfig::default().signature_schemes, SigningKeys::default());
+        let signed = |scheme| Signature { r: U256::from(1), s: U256::from(2), v: 27, scheme };
+        let mut transaction = transfer(1, 2, 10);
+        transaction.from_chain = Network::Ethereum { chain_id: ChainId::ETHEREUM };
+
+        transaction.signature = Some(signed(SignatureScheme::Ed25519));
+        let result = validator.validate(&transaction).await.unwrap();
+        assert!(!result.valid);
+        assert!(result.errors[0].message.contains("Secp256k1"), "{}", result.errors[0].message);
+
+        transaction.signature = Some(signed(SignatureScheme::Secp256k1));
+        assert!(validator.validate(&transaction).await.unwrap().valid);
+
+        // Native GhostChain transactions are the other way round
+        transaction.from_chain = Network::GhostChain { chain_id: ChainId::GHOSTCHAIN };
+        assert!(!validator.validate(&transaction).await.unwrap().valid);
+        transaction.signature = Some(signed(SignatureScheme::Ed25519));
+        assert!(validator.validate(&transaction).await.unwrap().valid);
+
+        // A network without a required scheme does not take the declared one on trust
+        transaction.from_chain = Network::Custom { chain_id: ChainId(7777), name: "ghostnet".to_string(), rpc_url: String::new() };
+        assert!(!validator.validate(&transaction).await.unwrap().valid);
+
+        // ...but a registered key fixes the scheme, over the network's
+        validator.signing_keys.write().insert(transaction.from_address.clone(), SignatureScheme::Dilithium);
+        assert!(!validator.validate(&transaction).await.unwrap().valid);
+        transaction.signature = Some(signed(SignatureScheme::Dilithium));
+        assert!(validator.validate(&transaction).await.unwrap().valid);
+        transaction.from_chain = Network::Ethereum { chain_id: ChainId::ETHEREUM };
+        assert!(validator.validate(&transaction).await.unwrap().valid);
+    }
+
+    #[tokio::test]
+    async fn test_preflight_drops_transaction_spent_by_earlier_batch() {
+        let processor = funded_processor(&[1]).await;
+        let first = transfer(1, 2, 80);
+        let second = transfer(1, 3, 50);
+
+        // Both fit the balance on their own; the first batch executes and spends it
+        assert_eq!(processor.preflight(vec![first.clone()]).await.len(), 1);
+        processor.execute_transactions(vec![first]).await.unwrap();
+
+        // The second is caught at pre-flight instead of failing during execution
+        let runnable = processor.preflight(vec![second.clone(), transfer(2, 4, 30)]).await;
+        assert_eq!(runnable.len(), 1);
+        assert_eq!(runnable[0].from_address, Address([2u8; 20]));
+
+        let dead = processor.dead_letters().entries();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].transaction.id, second.id);
+        assert!(dead[0].reason.contains("insufficient GCC balance"), "{}", dead[0].reason);
+    }
+
+    #[tokio::test]
+    async fn test_preflight_reserves_fees() {
+        let processor = funded_processor(&[1]).await;
+        processor.state_computer.current_state.write().await
+            .balances.insert((Address([1; 20]), "SPIRIT".to_string()), U256::from(5u64));
+
+        // 90 GCC + 10 GCC fee fits; the next transfer finds the fee already spent
+        let mut first = transfer(1, 2, 90);
+        first.fee.gcc_fee.amount = U256::from(10u64);
+        let second = transfer(1, 3, 1);
+
+        // A fee in another token must be covered by that token's balance
+        let mut third = transfer(1, 4, 0);
+        third.fee.spirit_fee.amount = U256::from(6u64);
+
+        let runnable = processor.preflight(vec![first.clone(), second.clone(), third.clone()]).await;
+        assert_eq!(runnable.len(), 1);
+        assert_eq!(runnable[0].id, first.id);
+
+        let dead: Vec<_> = processor.dead_letters().entries().into_iter().map(|letter| letter.transaction.id).collect();
+        assert_eq!(dead, vec![second.id, third.id]);
+    }
+
+    #[tokio::test]
+    async fn test_balances_are_kept_per_token() {
+        let processor = funded_processor(&[1]).await;
+        processor.state_computer.current_state.write().await.balances
+            .insert((Address([3; 20]), "MANA".to_string()), U256::from(50u64));
+
+        // Different tokens into the same account are independent, so these share a wave
+        let mut mana = transfer(3, 2, 20);
+        mana.amount = TokenAmount::new(TokenType::Mana, U256::from(20u64));
+        processor.execute_transactions(vec![transfer(1, 2, 30), mana]).await.unwrap();
+
+        let state = processor.state_computer.current_state.read().await;
+        let balance = |account: u8, token: &str| state.balances[&(Address([account; 20]), token.to_string())];
+        assert_eq!(balance(1, "GCC"), U256::from(70u64));
+        assert_eq!(balance(2, "GCC"), U256::from(30u64));
+        assert_eq!(balance(3, "MANA"), U256::from(30u64));
+        assert_eq!(balance(2, "MANA"), U256::from(20u64));
+        assert!(!state.balances.contains_key(&(Address([3; 20]), "GCC".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_storage_clear_refund_is_capped() {
+        let processor = funded_processor(&[1]).await;
+        let gas_tracker = &processor.execution_engine.gas_tracker;
+        let mut batch_gas = BatchGas::default();
+        let clear_slot = |slot: u64| StateChange {
+            change_type: StateChangeType::StorageUpdate,
+            address: Address([1u8; 20]),
+            token: None,
+            key: Some(U256::from(slot)),
+            old_value: U256::from(7u64),
+            new_value: U256::ZERO,
+        };
+
+        // 100 bytes of calldata: 21000 + 1600 + 2300 gas, cap 4980
+        let mut transaction = transfer(1, 2, 10);
+        transaction.data = vec![1u8; 100];
+        let state = processor.state_computer.current_state.read().await;
+        let mut result = processor.execute_single_transaction(&transaction, &state).unwrap();
+        drop(state);
+        assert_eq!(result.gas_used, 24_900);
+
+        // One cleared slot earns the full refund
+        result.state_changes.push(clear_slot(0));
+        let charged = gas_tracker.charge(&mut batch_gas, &transaction.id.to_string(), result.gas_used, &result.state_changes);
+        assert_eq!(charged, 24_900 - 4_800);
+
+        // Two cleared slots earn 9600, capped at a fifth of gas used
+        result.state_changes.push(clear_slot(1));
+        let charged = gas_tracker.charge(&mut batch_gas, &transaction.id.to_string(), result.gas_used, &result.state_changes);
+        assert_eq!(charged, 24_900 - 4_980);
+        assert_eq!(batch_gas.total_refunded(), 4_980);
+
+        // Writing a non-zero value earns nothing
+        let write = StateChange { new_value: U256::from(1u64), ..clear_slot(2) };
+        assert_eq!(gas_tracker.charge(&mut batch_gas, "other", 24_900, &[write]), 24_900);
+    }
+
+    #[tokio::test]
+    async fn test_emptying_a_balance_refunds_gas() {
+        // 21000 + 2300 gas, cap 4660
+        let processor = funded_processor(&[1]).await;
+        let Execution { gas_used, .. } = processor.execute_transactions(vec![transfer(1, 2, 40)]).await.unwrap();
+        assert_eq!(gas_used, 23_300);
+
+        // Spending the rest clears the sender's balance slot
+        let Execution { gas_used, .. } = processor.execute_transactions(vec![transfer(1, 3, 60)]).await.unwrap();
+        assert_eq!(gas_used, 23_300 - 4_660);
+    }
+
+    #[tokio::test]
+    async fn test_nodes_build_identical_batches_from_same_pool() {
+        use crate::settlement::{ReplayWindow, SettlementPriority, TieBreakPolicy, TransactionPool};
+        use std::collections::HashSet;
+        use crate::types::Signature;
+
+        let transactions: Vec<Transaction> = (1..=6u8)
+            .map(|sender| {
+                let mut transaction = transfer(sender, 9, 10 + sender as u64);
+                transaction.nonce = 1;
+                transaction.signature = Some(Signature {
+                    r: U256::from(1), s: U256::from(2), v: 27, scheme: SignatureScheme::Ed25519,
+                });
+                transaction
+            })
+            .collect();
+
+        // Each node receives the same transactions in a different order
+        let build = |arrival: Vec<Transaction>| async move {
+            let mut pool = TransactionPool {
+                pending: VecDeque::new(),
+                processing: HashMap::new(),
+                priority_queue: Vec::new(),
+                nonce_tracker: HashMap::new(),
+                staged: HashMap::new(),
+                recent_hashes: ReplayWindow::default(),
+                total_size: 0,
+                last_cleanup: SystemTime::now(),
+                last_batch_at: None,
+                executing_senders: HashSet::new(),
+                quarantined_senders: HashSet::new(),
+            };
+            for transaction in arrival {
+                pool.enqueue(transaction, false);
+            }
+            let adapters = crate::bridge::ChainAdapterRegistry::new();
+            let selected = pool.take_batch(TieBreakPolicy::default(), SettlementPriority::default(), &adapters, Some(42), 4, true);
+            funded_processor(&[1, 2, 3, 4, 5, 6]).await.process_batch(selected).await.unwrap()
+        };
+
+        let mut reversed = transactions.clone();
+        reversed.reverse();
+        let first = build(transactions).await;
+        let second = build(reversed).await;
+
+        let ids = |batch: &SettlementBatch| batch.transactions.iter().map(|tx| tx.id).collect::<Vec<_>>();
+        assert_eq!(first.transactions.len(), 4);
+        assert_eq!(ids(&first), ids(&second));
+        assert_eq!(first.batch_id, second.batch_id);
+        assert_eq!(first.state_root, second.state_root);
+        assert_eq!(first.merkle_proof, second.merkle_proof);
+        assert_eq!(bincode::serialize(&first).unwrap(), bincode::serialize(&second).unwrap());
+    }
+
+    struct FixedBalances(HashMap<(Address, TokenType), U256>);
+
+    #[async_trait::async_trait]
+    impl BalanceSource for FixedBalances {
+        async fn balance(&self, address: &Address, token_type: TokenType) -> Result<U256> {
+            Ok(self.0.get(&(address.clone(), token_type)).cloned().unwrap_or(U256::ZERO))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_balance_validator_checks_amount_plus_fees() {
+        let validator = BalanceValidator {
+            balances: Some(Arc::new(FixedBalances(HashMap::from([
+                ((Address([1; 20]), TokenType::Gcc), U256::from(100u64)),
+                ((Address([1; 20]), TokenType::Spirit), U256::from(5u64)),
+            ])))),
+        };
+
+        // 90 GCC + 10 GCC fee fits exactly
+        let mut transaction = transfer(1, 2, 90);
+        transaction.fee.gcc_fee.amount = U256::from(10u64);
+        assert!(validator.validate(&transaction).await.unwrap().valid);
+
+        // One more GCC of fee overdraws
+        transaction.fee.gcc_fee.amount = U256::from(11u64);
+        let result = validator.validate(&transaction).await.unwrap();
+        assert!(!result.valid);
+        assert!(matches!(result.errors[0].error_type, ValidationErrorType::InsufficientBalance));
+        assert!(result.errors[0].message.contains("GCC"), "{}", result.errors[0].message);
+
+        // Fees in other tokens are checked against those balances
+        transaction.fee.gcc_fee.amount = U256::ZERO;
+        transaction.fee.spirit_fee.amount = U256::from(6u64);
+        assert!(!validator.validate(&transaction).await.unwrap().valid);
+
+        // An amount crafted to wrap when the fee is added is rejected, not wrapped
+        transaction.fee.spirit_fee.amount = U256::ZERO;
+        transaction.amount.amount = U256([0xff; 32]);
+        transaction.fee.gcc_fee.amount = U256::from(101u64);
+        let result = validator.validate(&transaction).await.unwrap();
+        assert!(!result.valid);
+        assert!(result.errors[0].message.contains("overflows"), "{}", result.errors[0].message);
+    }
+
+    #[tokio::test]
+    async fn test_only_allowlisted_contract_methods_execute() {
+        let processor = BatchProcessor::new(SettlementConfig {
+            allowed_contract_methods: Some(vec!["transfer(address,uint256)".to_string()]),
+            ..SettlementConfig::default()
+        }).await.unwrap();
+        processor.state_computer.current_state.write().await
+            .balances.insert((Address([1; 20]), "GCC".to_string()), U256::from(100u64));
+        let call = |signature: &str| {
+            let mut transaction = transfer(1, 2, 10);
+            transaction.data = crate::calldata::FunctionAbi::parse(signature).unwrap().selector().to_vec();
+            transaction.data.extend_from_slice(&[0u8; 64]);
+            transaction
+        };
+
+        let state = processor.state_computer.current_state.read().await;
+        let allowed = processor.execute_single_transaction(&call("transfer(address,uint256)"), &state).unwrap();
+        assert!(allowed.success);
+        assert!(!allowed.state_changes.is_empty());
+
+        // Rejected before touching state or charging gas
+        let rejected = processor.execute_single_transaction(&call("approve(address,uint256)"), &state).unwrap();
+        assert!(!rejected.success);
+        assert!(rejected.state_changes.is_empty());
+        assert_eq!(rejected.gas_used, 0);
+        assert!(rejected.error.unwrap().contains("not allow-listed"));
+
+        // Plain transfers carry no calldata and are unaffected
+        assert!(processor.execute_single_transaction(&transfer(1, 2, 10), &state).unwrap().success);
+    }
+
+    #[tokio::test]
+    async fn test_nonces_checked_sequentially_per_sender() {
+        let processor = funded_processor(&[1, 2]).await;
+        processor.state_computer.current_state.write().await.nonces.insert(Address([1; 20]), 4);
+        let nonce_validator = &processor.validation_pipeline.nonce_validator;
+        let with_nonce = |from: u8, nonce: u64| {
+            let mut transaction = transfer(from, 9, 1);
+            transaction.nonce = nonce;
+            transaction
+        };
+
+        // Sender 1 has executed nonce 4: 5 and 6 follow on within the batch,
+        // a second 6 is a duplicate and 8 leaves a gap
+        let batch = vec![
+            with_nonce(1, 5),
+            with_nonce(2, 1),
+            with_nonce(1, 6),
+            with_nonce(1, 6),
+            with_nonce(1, 8),
+            with_nonce(2, 2),
+        ];
+        let valid = nonce_validator.validate_sequence(batch.clone()).await;
+        let ids: Vec<_> = valid.iter().map(|tx| tx.id).collect();
+        assert_eq!(ids, vec![batch[0].id, batch[1].id, batch[2].id, batch[5].id]);
+
+        // Per transaction, only nonces already executed are known to be invalid
+        let stale = NonceValidator { state: processor.state_computer.current_state.clone() }
+            .validate(&with_nonce(1, 4)).await.unwrap();
+        assert!(!stale.valid);
+        assert!(matches!(stale.errors[0].error_type, ValidationErrorType::InvalidNonce));
+        assert!(stale.errors[0].message.contains("already used"), "{}", stale.errors[0].message);
+        assert!(NonceValidator { state: processor.state_computer.current_state.clone() }
+            .validate(&with_nonce(1, 8)).await.unwrap().valid);
+    }
+
+    #[tokio::test]
+    async fn test_state_root_independent_of_insertion_order() {
+        let processor = BatchProcessor::new(SettlementConfig::default()).await.unwrap();
+        let accounts: Vec<((Address, String), U256)> = (1..=9u8)
+            .flat_map(|i| ["GCC", "MANA"].map(|token| ((Address([i; 20]), token.to_string()), U256::from(i as u64 * 100))))
+            .collect();
+
+        let mut forward = processor.state_computer.current_state.read().await.clone();
+        forward.balances = HashMap::new();
+        forward.nonces = HashMap::new();
+        forward.storage = HashMap::new();
+        let mut reverse = forward.clone();
+        reverse.balances = HashMap::with_capacity(64);
+        reverse.nonces = HashMap::with_capacity(64);
+        reverse.storage = HashMap::with_capacity(64);
+        for (key, balance) in &accounts {
+            forward.balances.insert(key.clone(), balance.clone());
+            forward.nonces.insert(key.0.clone(), balance.to_u64());
+            forward.storage.insert((key.0.clone(), U256::from(key.1.len() as u64)), balance.clone());
+        }
+        for (key, balance) in accounts.iter().rev() {
+            reverse.balances.insert(key.clone(), balance.clone());
+            reverse.nonces.insert(key.0.clone(), balance.to_u64());
+            reverse.storage.insert((key.0.clone(), U256::from(key.1.len() as u64)), balance.clone());
+        }
+
+        let root = processor.compute_state_root(&forward).await.unwrap();
+        assert_eq!(root, processor.compute_state_root(&reverse).await.unwrap());
+
+        // Nonces and storage are committed alongside balances
+        let mut bumped = reverse.clone();
+        *bumped.nonces.get_mut(&Address([4; 20])).unwrap() += 1;
+        assert_ne!(root, processor.compute_state_root(&bumped).await.unwrap());
+        let mut written = reverse.clone();
+        written.storage.insert((Address([4; 20]), U256::from(99)), U256::ONE);
+        assert_ne!(root, processor.compute_state_root(&written).await.unwrap());
+
+        let address = Address([4; 20]);
+        let proof = prove_balance(&reverse.balances, &reverse.nonces, &reverse.storage, &address, "MANA").unwrap();
+        assert!(verify_state_proof(&root, (&address, "MANA"), &proof));
+        assert!(!verify_state_proof(&root, (&address, "GCC"), &proof));
+        assert!(!verify_state_proof(&[0; 32], (&address, "MANA"), &proof));
+    }
+
+    #[tokio::test]
+    async fn test_inclusion_proofs_for_every_transaction() {
+        let processor = BatchProcessor::new(SettlementConfig::default()).await.unwrap();
+        let transactions: Vec<Transaction> = (1..=5u8).map(|sender| transfer(sender, 9, 10)).collect();
+        let merkle_proof = processor.build_merkle_proof(&transactions).await.unwrap();
+        let batch = SettlementBatch {
+            batch_id: SettlementBatch::content_id(&transactions, &[0; 32]),
+            transactions,
+            state_root: vec![0; 32],
+            previous_state_root: vec![0; 32],
+            block_number: 1,
+            merkle_proof,
+            zk_proof: None,
+            created_at: SystemTime::now(),
+            gas_used: 0,
+            fee_paid: TokenAmount::new(TokenType::Gcc, U256::ZERO),
+        };
+
+        // Five leaves: the odd node at each level is paired with the empty node
+        for (index, transaction) in batch.transactions.iter().enumerate() {
+            let leaf = transaction_leaf(transaction);
+            let proof = processor.inclusion_proof(&batch, index).unwrap();
+            assert_eq!(proof.len(), 4);
+            assert!(verify_inclusion(&batch.merkle_proof, &leaf, &proof, index));
+            assert!(!verify_inclusion(&batch.merkle_proof, &leaf, &proof, (index + 1) % 5));
+        }
+
+        let outsider = transaction_leaf(&transfer(7, 9, 10));
+        assert!(!verify_inclusion(&batch.merkle_proof, &outsider, &processor.inclusion_proof(&batch, 0).unwrap(), 0));
+        assert!(processor.inclusion_proof(&batch, 5).unwrap().is_empty());
+
+        // Other hashers build different but equally verifiable trees
+        let leaves: Vec<Vec<u8>> = batch.transactions.iter().map(transaction_leaf).collect();
+        let tree = HasherType::Blake3.build_tree(&leaves).unwrap();
+        assert_ne!(tree.root(), &batch.merkle_proof[..]);
+        assert!(HasherType::Blake3.verify_inclusion(tree.root(), &leaves[4], &tree.generate_inclusion_proof(4), 4));
+        assert!(HasherType::Poseidon.build_tree(&leaves).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replay_reproduces_recorded_root_and_flags_tampering() {
+        use crate::types::Signature;
+
+        let config = SettlementConfig { replay_history: 4, ..SettlementConfig::default() };
+        let processor = BatchProcessor::new(config).await.unwrap();
+        {
+            let mut state = processor.state_computer.current_state.write().await;
+            for account in 1..=3u8 {
+                state.balances.insert((Address([account; 20]), "GCC".to_string()), U256::from(100u64));
+            }
+        }
+
+        let transactions: Vec<Transaction> = (1..=3u8)
+            .map(|sender| {
+                let mut transaction = transfer(sender, 9, 10 * sender as u64);
+                transaction.nonce = 1;
+                transaction.signature = Some(Signature {
+                    r: U256::from(1), s: U256::from(2), v: 27, scheme: SignatureScheme::Ed25519,
+                });
+                transaction
+            })
+            .collect();
+        let batch = processor.process_batch(transactions).await.unwrap();
+        assert_eq!(batch.transactions.len(), 3);
+
+        // Later batches move the live state on; replay uses the recorded prior state
+        processor.state_computer.current_state.write().await.balances.clear();
+        let replay = processor.replay_batch(&batch.batch_id).await.unwrap();
+        assert!(replay.matches);
+        assert_eq!(replay.replayed_root, batch.state_root);
+
+        // The defense trace comes from the same replay
+        let trace = processor.reexecution_trace(&batch.batch_id).await.unwrap();
+        assert!(trace.reproduces(&batch));
+        assert_eq!(trace.steps.len(), 3);
+        assert_eq!(trace.steps[0].transaction_hash, batch.transactions[0].hash());
+
+        // A record whose root was altered no longer matches
+        processor.replay_records.lock()[0].batch.state_root = vec![0xff; 32];
+        let replay = processor.replay_batch(&batch.batch_id).await.unwrap();
+        assert!(!replay.matches);
+        assert_eq!(replay.replayed_root, batch.state_root);
+
+        // Neither does one whose transactions were altered
+        processor.replay_records.lock()[0].batch.state_root = batch.state_root.clone();
+        processor.replay_records.lock()[0].batch.transactions[0].amount = TokenAmount::new(TokenType::Gcc, U256::from(1_000u64));
+        let replay = processor.replay_batch(&batch.batch_id).await.unwrap();
+        assert!(!replay.matches);
+        assert_eq!(replay.failed_transactions, vec![batch.transactions[0].id]);
+
+        assert!(matches!(
+            processor.replay_batch("unknown").await,
+            Err(BridgeError::Settlement(SettlementError::BatchNotReplayable { .. }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_batches_link_to_previous_state_root() {
+        use crate::settlement::state_manager::GENESIS_STATE_ROOT;
+        use crate::types::Signature;
+
+        let signed = |sender| {
+            let mut transaction = transfer(sender, 9, 10);
+            transaction.nonce = 1;
+            transaction.signature = Some(Signature {
+                r: U256::from(1), s: U256::from(2), v: 27, scheme: SignatureScheme::Ed25519,
+            });
+            transaction
+        };
+        let processor = funded_processor(&[1, 2]).await;
+        let first = processor.process_batch(vec![signed(1)]).await.unwrap();
+        let second = processor.process_batch(vec![signed(2)]).await.unwrap();
+
+        assert_eq!(first.previous_state_root, GENESIS_STATE_ROOT.to_vec());
+        assert_eq!(second.previous_state_root, first.state_root);
+        assert_eq!((first.block_number, second.block_number), (1, 2));
+        assert_eq!(processor.batch_chain.tip().await.block_number, 2);
+    }
+
+    #[tokio::test]
+    async fn test_burn_exited_debits_l2_balance() {
+        let processor = BatchProcessor::new(SettlementConfig::default()).await.unwrap();
+        let key = (Address([1; 20]), "GCC".to_string());
+        processor.state_computer.current_state.write().await.balances.insert(key.clone(), U256::from(100u64));
+
+        let withdrawal = EmergencyWithdrawal {
+            address: key.0.clone(),
+            token: key.1.clone(),
+            amount: U256::from(60u64),
+            state_root: vec![0; 32],
+            requested_at: SystemTime::now(),
+        };
+        assert!(processor.burn_exited(&withdrawal).await);
+        assert_eq!(processor.state_computer.current_state.read().await.balances[&key], U256::from(40u64));
+
+        // Retrying a burn whose log entry failed doesn't debit twice
+        assert!(!processor.burn_exited(&withdrawal).await);
+        assert_eq!(processor.state_computer.current_state.read().await.balances[&key], U256::from(40u64));
+
+        // Spent on L2 after the finalized root: burn what is left
+        let spent = (Address([2; 20]), "GCC".to_string());
+        processor.state_computer.current_state.write().await.balances.insert(spent.clone(), U256::from(10u64));
+        assert!(processor.burn_exited(&EmergencyWithdrawal { address: spent.0.clone(), ..withdrawal }).await);
+        assert_eq!(processor.state_computer.current_state.read().await.balances[&spent], U256::ZERO);
+    }
+}
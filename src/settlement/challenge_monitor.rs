@@ -0,0 +1,232 @@
+/*!
+Automatic defense of this node's batches
+
+An unanswered challenge is lost by default once its deadline passes. The
+`ChallengeMonitor` remembers the batches this node proposed; when a
+challenge is registered against one of them, it re-executes the batch from
+its recorded prior state and submits the resulting trace to the dispute
+contract, giving up only if the challenge deadline passes first.
+
+Defending needs a proposer address, a dispute contract client and a
+re-executor (the batch processor, with `replay_history` enabled). Without
+them a challenge is left open rather than reported as answered.
+*/
+
+use crate::error::{BridgeError, Result, SettlementError};
+use crate::settlement::SettlementBatch;
+use crate::settlement::batch_processor::BatchProcessor;
+use crate::types::{Address, TransactionHash};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Automatic challenge defense settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengeDefenseConfig {
+    pub enabled: bool,
+    /// Address this node proposes batches and responds to challenges as;
+    /// challenges cannot be defended until it is set
+    #[serde(default)]
+    pub proposer: Option<Address>,
+}
+
+impl Default for ChallengeDefenseConfig {
+    fn default() -> Self {
+        Self { enabled: true, proposer: None }
+    }
+}
+
+/// One transaction as re-executed for a defense
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceStep {
+    pub index: usize,
+    pub transaction_id: Uuid,
+    pub transaction_hash: TransactionHash,
+    pub success: bool,
+    pub gas_used: u64,
+}
+
+/// Re-execution of a batch from its previous state root; `state_root` is
+/// the root the replay produced, not the one the batch claims
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReexecutionTrace {
+    pub batch_id: String,
+    pub previous_state_root: Vec<u8>,
+    pub state_root: Vec<u8>,
+    pub gas_used: u64,
+    pub steps: Vec<TraceStep>,
+}
+
+impl ReexecutionTrace {
+    /// Whether the replay reproduced `batch` exactly
+    pub fn reproduces(&self, batch: &SettlementBatch) -> bool {
+        self.batch_id == batch.batch_id
+            && self.previous_state_root == batch.previous_state_root
+            && self.state_root == batch.state_root
+            && self.steps.len() == batch.transactions.len()
+            && self.steps.iter().all(|step| step.success)
+    }
+}
+
+/// Re-executes recorded batches to build defense traces
+#[async_trait]
+pub trait BatchReexecutor: Send + Sync {
+    async fn reexecute(&self, batch_id: &str) -> Result<ReexecutionTrace>;
+}
+
+#[async_trait]
+impl BatchReexecutor for BatchProcessor {
+    async fn reexecute(&self, batch_id: &str) -> Result<ReexecutionTrace> {
+        self.reexecution_trace(batch_id).await
+    }
+}
+
+/// Defense submitted against a challenge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengeDefense {
+    pub challenge_id: String,
+    pub batch_id: String,
+    pub responder: Address,
+    pub trace: ReexecutionTrace,
+    pub deadline: SystemTime,
+    pub submitted_at: SystemTime,
+    /// L1 transaction that carried the defense, once submitted
+    pub l1_transaction: Option<TransactionHash>,
+}
+
+impl ChallengeDefense {
+    /// Serialized trace carried as the response's evidence
+    pub fn evidence(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(&self.trace).map_err(|e| BridgeError::Serialization(e.into()))
+    }
+}
+
+/// Sends defenses to the dispute contract
+#[async_trait]
+pub trait DefenseSubmitter: Send + Sync {
+    /// Submit `defense` on L1, returning the hash of the transaction that
+    /// carried it
+    async fn submit_defense(&self, defense: &ChallengeDefense) -> Result<TransactionHash>;
+}
+
+/// Batches this node proposed and the defenses submitted for them
+pub struct ChallengeMonitor {
+    config: ChallengeDefenseConfig,
+    submitter: RwLock<Option<Arc<dyn DefenseSubmitter>>>,
+    reexecutor: RwLock<Option<Arc<dyn BatchReexecutor>>>,
+    /// batch_id -> batch, until the batch finalizes
+    own_batches: RwLock<HashMap<String, SettlementBatch>>,
+    defenses_submitted: AtomicU64,
+}
+
+impl ChallengeMonitor {
+    pub fn new(config: ChallengeDefenseConfig) -> Self {
+        if config.enabled && config.proposer.is_none() {
+            warn!("Challenge defense enabled without a proposer address; challenges will go unanswered");
+        }
+        Self {
+            config,
+            submitter: RwLock::new(None),
+            reexecutor: RwLock::new(None),
+            own_batches: RwLock::new(HashMap::new()),
+            defenses_submitted: AtomicU64::new(0),
+        }
+    }
+
+    /// Dispute contract client defenses are submitted through
+    pub fn set_submitter(&self, submitter: Arc<dyn DefenseSubmitter>) {
+        *self.submitter.write() = Some(submitter);
+    }
+
+    /// Source of re-execution traces for recorded batches
+    pub fn set_reexecutor(&self, reexecutor: Arc<dyn BatchReexecutor>) {
+        *self.reexecutor.write() = Some(reexecutor);
+    }
+
+    /// Remember a batch this node submitted so challenges to it are defended
+    pub fn record_proposal(&self, batch: &SettlementBatch) {
+        self.own_batches.write().insert(batch.batch_id.clone(), batch.clone());
+    }
+
+    /// Stop watching a batch, e.g. once it finalizes
+    pub fn forget(&self, batch_id: &str) {
+        self.own_batches.write().remove(batch_id);
+    }
+
+    pub fn is_own_batch(&self, batch_id: &str) -> bool {
+        self.own_batches.read().contains_key(batch_id)
+    }
+
+    /// Defenses that reached L1 so far
+    pub fn defenses_submitted(&self) -> u64 {
+        self.defenses_submitted.load(Ordering::Relaxed)
+    }
+
+    /// Whether a challenge against `batch_id` should be defended
+    pub fn should_defend(&self, batch_id: &str) -> bool {
+        self.config.enabled && self.is_own_batch(batch_id)
+    }
+
+    /// Defend a challenge if it targets one of this node's batches; returns
+    /// the defense once it reached L1, or `None` if the batch is not ours
+    pub async fn on_challenge(&self, challenge_id: &str, batch_id: &str, deadline: SystemTime) -> Result<Option<ChallengeDefense>> {
+        if !self.config.enabled {
+            return Ok(None);
+        }
+        let Some(batch) = self.own_batches.read().get(batch_id).cloned() else {
+            return Ok(None);
+        };
+
+        let unavailable = |reason: &str| BridgeError::Settlement(SettlementError::DefenseUnavailable {
+            challenge_id: challenge_id.to_string(),
+            reason: reason.to_string(),
+        });
+        let responder = self.config.proposer.clone()
+            .ok_or_else(|| unavailable("no proposer address configured"))?;
+        let submitter = self.submitter.read().clone()
+            .ok_or_else(|| unavailable("no dispute contract client configured"))?;
+        let reexecutor = self.reexecutor.read().clone()
+            .ok_or_else(|| unavailable("no batch re-executor configured"))?;
+
+        let deadline_passed = || {
+            BridgeError::Settlement(SettlementError::ChallengeDeadlinePassed { challenge_id: challenge_id.to_string() })
+        };
+        let remaining = deadline.duration_since(SystemTime::now()).map_err(|_| deadline_passed())?;
+
+        let defend = async {
+            let trace = reexecutor.reexecute(batch_id).await?;
+            // Submitting a trace that contradicts the batch would concede the challenge
+            if !trace.reproduces(&batch) {
+                return Err(unavailable("re-execution does not reproduce the batch"));
+            }
+
+            let mut defense = ChallengeDefense {
+                challenge_id: challenge_id.to_string(),
+                batch_id: batch_id.to_string(),
+                responder,
+                trace,
+                deadline,
+                submitted_at: SystemTime::now(),
+                l1_transaction: None,
+            };
+            let l1_transaction = submitter.submit_defense(&defense).await?;
+            defense.submitted_at = SystemTime::now();
+            defense.l1_transaction = Some(l1_transaction);
+            Ok(defense)
+        };
+
+        // A defense landing after the deadline is worthless, so stop trying then
+        let defense = tokio::time::timeout(remaining, defend).await.map_err(|_| deadline_passed())??;
+
+        self.defenses_submitted.fetch_add(1, Ordering::Relaxed);
+        warn!("Defended challenge {} against own batch {} in L1 transaction {:?}",
+              challenge_id, batch_id, defense.l1_transaction);
+        Ok(Some(defense))
+    }
+}
@@ -0,0 +1,133 @@
+/*!
+Versioned L1 settlement contract addresses
+
+The settlement contract can be upgraded on L1 while the bridge is running.
+Switching to a new address creates a new contract version: batches submitted
+afterwards target the new contract, while batches already in flight keep the
+version they were submitted to until they finalize.
+*/
+
+use crate::types::Address;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::SystemTime;
+use tracing::info;
+
+/// One deployed version of the settlement contract
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettlementContract {
+    pub version: u32,
+    pub address: Address,
+    pub activated_at: SystemTime,
+}
+
+struct ContractsState {
+    versions: Vec<SettlementContract>,
+    /// batch_id -> version it was submitted to
+    in_flight: HashMap<String, u32>,
+}
+
+/// Routes batch submissions to the active settlement contract
+pub struct SettlementContracts {
+    state: RwLock<ContractsState>,
+}
+
+impl SettlementContracts {
+    pub fn new(address: Address) -> Self {
+        Self {
+            state: RwLock::new(ContractsState {
+                versions: vec![SettlementContract { version: 1, address, activated_at: SystemTime::now() }],
+                in_flight: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Contract new batches are submitted to
+    pub fn active(&self) -> SettlementContract {
+        self.state.read().versions.last().cloned().expect("at least one contract version")
+    }
+
+    /// Route new batches to `address`; in-flight batches keep their contract
+    pub fn switch_to(&self, address: Address) -> SettlementContract {
+        let mut state = self.state.write();
+        let current = state.versions.last().expect("at least one contract version");
+        if current.address == address {
+            return current.clone();
+        }
+
+        let contract = SettlementContract {
+            version: current.version + 1,
+            address,
+            activated_at: SystemTime::now(),
+        };
+        info!("Settlement contract switched to v{} at {:?} ({} batches in flight on older versions)",
+              contract.version, contract.address, state.in_flight.len());
+        state.versions.push(contract.clone());
+        contract
+    }
+
+    /// Contract version by number
+    pub fn version(&self, version: u32) -> Option<SettlementContract> {
+        self.state.read().versions.iter().find(|c| c.version == version).cloned()
+    }
+
+    /// Record that `batch_id` is being submitted, returning the contract it targets
+    pub fn assign_batch(&self, batch_id: &str) -> SettlementContract {
+        let mut state = self.state.write();
+        let contract = state.versions.last().cloned().expect("at least one contract version");
+        let version = *state.in_flight.entry(batch_id.to_string()).or_insert(contract.version);
+        state.versions.iter().find(|c| c.version == version).cloned().unwrap_or(contract)
+    }
+
+    /// Contract an in-flight batch was submitted to
+    pub fn contract_for_batch(&self, batch_id: &str) -> Option<SettlementContract> {
+        let state = self.state.read();
+        let version = state.in_flight.get(batch_id)?;
+        state.versions.iter().find(|c| c.version == *version).cloned()
+    }
+
+    /// Stop tracking a finalized batch, returning the contract it finalized on
+    pub fn release_batch(&self, batch_id: &str) -> Option<SettlementContract> {
+        let mut state = self.state.write();
+        let version = state.in_flight.remove(batch_id)?;
+        state.versions.iter().find(|c| c.version == version).cloned()
+    }
+
+    /// Batches still in flight on a contract version
+    pub fn in_flight_on(&self, version: u32) -> usize {
+        self.state.read().in_flight.values().filter(|v| **v == version).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_switchover_routes_new_batches_and_keeps_in_flight() {
+        let old = Address([1u8; 20]);
+        let new = Address([2u8; 20]);
+        let contracts = SettlementContracts::new(old.clone());
+
+        assert_eq!(contracts.assign_batch("batch-1").address, old);
+        assert_eq!(contracts.switch_to(new.clone()).version, 2);
+        // Switching to the active address is a no-op
+        assert_eq!(contracts.switch_to(new.clone()).version, 2);
+
+        let second = contracts.assign_batch("batch-2");
+        assert_eq!((second.version, second.address), (2, new.clone()));
+
+        // Resubmitting an in-flight batch keeps its original contract
+        assert_eq!(contracts.assign_batch("batch-1").address, old);
+        assert_eq!(contracts.contract_for_batch("batch-1").unwrap().version, 1);
+        assert_eq!(contracts.in_flight_on(1), 1);
+
+        // The old batch finalizes against the old contract
+        assert_eq!(contracts.release_batch("batch-1").unwrap().address, old);
+        assert_eq!(contracts.in_flight_on(1), 0);
+        assert!(contracts.contract_for_batch("batch-1").is_none());
+        assert_eq!(contracts.release_batch("batch-2").unwrap().address, new);
+        assert_eq!(contracts.version(1).unwrap().address, old);
+    }
+}
@@ -0,0 +1,92 @@
+/*!
+Dead-letter queue for transactions dropped before execution
+
+Transactions that passed validation at submission but can no longer execute,
+for example because an earlier batch spent the sender's balance, are moved
+here with the reason instead of being silently discarded. Operators can
+inspect the queue and drain it for resubmission or client notification.
+*/
+
+use crate::types::Transaction;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::time::SystemTime;
+use tracing::warn;
+
+/// A transaction removed from a batch, with why
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    pub transaction: Transaction,
+    pub reason: String,
+    pub dead_lettered_at: SystemTime,
+}
+
+/// Bounded queue of dropped transactions; the oldest entries are evicted first.
+/// With a capacity of zero, dropped transactions are only logged.
+#[derive(Debug)]
+pub struct DeadLetterQueue {
+    capacity: usize,
+    entries: Mutex<VecDeque<DeadLetter>>,
+}
+
+impl DeadLetterQueue {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn push(&self, transaction: Transaction, reason: impl Into<String>) {
+        let reason = reason.into();
+        warn!("Dead-lettered transaction {}: {}", transaction.id, reason);
+
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock();
+        while entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(DeadLetter { transaction, reason, dead_lettered_at: SystemTime::now() });
+    }
+
+    /// Current entries, oldest first
+    pub fn entries(&self) -> Vec<DeadLetter> {
+        self.entries.lock().iter().cloned().collect()
+    }
+
+    /// Remove and return all entries
+    pub fn drain(&self) -> Vec<DeadLetter> {
+        self.entries.lock().drain(..).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.lock().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::fixtures;
+
+    #[test]
+    fn test_capacity_bounds_entries() {
+        let queue = DeadLetterQueue::new(2);
+        let transactions: Vec<_> = (1..=3).map(|amount| fixtures::transfer(1, 2, amount)).collect();
+        for transaction in &transactions {
+            queue.push(transaction.clone(), "stale");
+        }
+        let kept: Vec<_> = queue.entries().into_iter().map(|letter| letter.transaction.id).collect();
+        assert_eq!(kept, vec![transactions[1].id, transactions[2].id]);
+
+        let disabled = DeadLetterQueue::new(0);
+        disabled.push(fixtures::transfer(1, 2, 1), "stale");
+        assert!(disabled.is_empty());
+    }
+}
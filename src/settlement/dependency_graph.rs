@@ -0,0 +1,147 @@
+/*!
+Transaction dependency graph for parallel batch execution
+
+Each transaction's account and storage accesses are collected into an
+`AccessSet`. Transactions that conflict (one writes what the other reads or
+writes) keep their batch order; everything else is grouped into waves of
+mutually independent transactions that can execute concurrently. Executing
+the waves in order produces the same state as executing the batch
+sequentially.
+*/
+
+use crate::types::{Address, Transaction};
+use std::collections::{HashMap, HashSet};
+
+/// A piece of state a transaction touches
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AccessKey {
+    /// Balance of one token for an account
+    Balance(Address, String),
+    Nonce(Address),
+    /// Contract storage of an account; keys aren't known before execution
+    Storage(Address),
+}
+
+/// State read and written by one transaction
+#[derive(Debug, Clone, Default)]
+pub struct AccessSet {
+    pub reads: HashSet<AccessKey>,
+    pub writes: HashSet<AccessKey>,
+}
+
+impl AccessSet {
+    /// Accesses of a transfer, plus the recipient's storage for contract calls
+    pub fn for_transaction(transaction: &Transaction) -> Self {
+        let token = transaction.amount.token_type.to_string();
+        let mut set = Self::default();
+        for key in [
+            AccessKey::Balance(transaction.from_address.clone(), token.clone()),
+            AccessKey::Balance(transaction.to_address.clone(), token),
+            AccessKey::Nonce(transaction.from_address.clone()),
+        ] {
+            set.reads.insert(key.clone());
+            set.writes.insert(key);
+        }
+        if !transaction.data.is_empty() {
+            let storage = AccessKey::Storage(transaction.to_address.clone());
+            set.reads.insert(storage.clone());
+            set.writes.insert(storage);
+        }
+        set
+    }
+
+    /// Whether the two transactions must keep their relative order
+    pub fn conflicts_with(&self, other: &AccessSet) -> bool {
+        !self.writes.is_disjoint(&other.writes)
+            || !self.writes.is_disjoint(&other.reads)
+            || !self.reads.is_disjoint(&other.writes)
+    }
+}
+
+/// Batch transactions grouped into waves of independent transactions
+#[derive(Debug, Clone, Default)]
+pub struct DependencyGraph {
+    waves: Vec<Vec<usize>>,
+}
+
+impl DependencyGraph {
+    /// Build the graph for a batch in execution order
+    pub fn build(transactions: &[Transaction]) -> Self {
+        let access_sets: Vec<_> = transactions.iter().map(AccessSet::for_transaction).collect();
+        Self::from_access_sets(&access_sets)
+    }
+
+    /// Build the graph from precomputed access sets in execution order
+    pub fn from_access_sets(access_sets: &[AccessSet]) -> Self {
+        // Latest wave that wrote / accessed each key so far
+        let mut last_write: HashMap<&AccessKey, usize> = HashMap::new();
+        let mut last_access: HashMap<&AccessKey, usize> = HashMap::new();
+        let mut waves: Vec<Vec<usize>> = Vec::new();
+
+        for (index, access) in access_sets.iter().enumerate() {
+            // A write waits for every earlier access; a read only for earlier writes
+            let after_writes = access.writes.iter().filter_map(|key| last_access.get(key));
+            let after_reads = access.reads.iter().filter_map(|key| last_write.get(key));
+            let wave = after_writes.chain(after_reads).map(|wave| wave + 1).max().unwrap_or(0);
+
+            for key in &access.writes {
+                last_write.insert(key, wave.max(last_write.get(key).copied().unwrap_or(0)));
+            }
+            for key in access.reads.iter().chain(&access.writes) {
+                last_access.insert(key, wave.max(last_access.get(key).copied().unwrap_or(0)));
+            }
+
+            if waves.len() <= wave {
+                waves.resize_with(wave + 1, Vec::new);
+            }
+            waves[wave].push(index);
+        }
+
+        Self { waves }
+    }
+
+    /// Waves in execution order; indices within a wave are ascending
+    pub fn waves(&self) -> &[Vec<usize>] {
+        &self.waves
+    }
+
+    /// Size of the largest wave
+    pub fn max_parallelism(&self) -> usize {
+        self.waves.iter().map(Vec::len).max().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::fixtures::transfer;
+
+    #[test]
+    fn test_disjoint_transfers_share_a_wave() {
+        let batch = vec![transfer(1, 2, 10), transfer(3, 4, 10), transfer(5, 6, 10), transfer(7, 8, 10)];
+        let graph = DependencyGraph::build(&batch);
+
+        assert_eq!(graph.waves(), &[vec![0, 1, 2, 3]]);
+        assert_eq!(graph.max_parallelism(), 4);
+    }
+
+    #[test]
+    fn test_conflicting_transfers_keep_batch_order() {
+        // 1 -> 2 and 2 -> 3 share account 2; 4 -> 5 is independent; 1 -> 6 reuses the sender
+        let batch = vec![transfer(1, 2, 10), transfer(2, 3, 10), transfer(4, 5, 10), transfer(1, 6, 10), transfer(3, 7, 10)];
+        let graph = DependencyGraph::build(&batch);
+
+        assert_eq!(graph.waves(), &[vec![0, 2], vec![1, 3], vec![4]]);
+
+        // Every conflicting pair lands in increasing waves
+        let wave_of = |index: usize| graph.waves().iter().position(|wave| wave.contains(&index)).unwrap();
+        let access: Vec<_> = batch.iter().map(AccessSet::for_transaction).collect();
+        for later in 0..batch.len() {
+            for earlier in 0..later {
+                if access[earlier].conflicts_with(&access[later]) {
+                    assert!(wave_of(earlier) < wave_of(later), "{} must run before {}", earlier, later);
+                }
+            }
+        }
+    }
+}
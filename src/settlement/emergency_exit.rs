@@ -0,0 +1,352 @@
+/*!
+Emergency exits when GhostPlane L2 is down
+
+Batch state roots commit to Merkle roots over the L2 balance, nonce and
+storage tables (see `state_tree`), so a user can prove their balance against
+a finalized root without L2 being reachable. Once L2 has been continuously
+unhealthy for the configured duration, users may withdraw on L1 by presenting
+a `BalanceProof` against the last finalized state root. Each account and token can exit once,
+recorded in an append-only exit log so a restart cannot reopen it; proofs
+against older roots are rejected as stale. Exited balances are burned from L2
+state once L2 recovers.
+*/
+
+use crate::error::{BridgeError, Result, SettlementError};
+use crate::settlement::state_tree::BalanceProof;
+use crate::types::{Address, U256};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{info, warn};
+
+/// Emergency exit settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmergencyExitConfig {
+    /// Allow emergency exits at all
+    pub enabled: bool,
+    /// How long L2 must be continuously down before exits open
+    pub l2_downtime_threshold: Duration,
+    /// Append-only log of accepted exits and burns; without one, exits are
+    /// only remembered until restart
+    #[serde(default)]
+    pub exit_log: Option<PathBuf>,
+}
+
+impl Default for EmergencyExitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            l2_downtime_threshold: Duration::from_secs(3 * 24 * 60 * 60), // 3 days
+            exit_log: None,
+        }
+    }
+}
+
+/// An accepted emergency withdrawal, to be paid out on L1
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmergencyWithdrawal {
+    pub address: Address,
+    pub token: String,
+    pub amount: U256,
+    pub state_root: Vec<u8>,
+    pub requested_at: SystemTime,
+}
+
+/// One line of the exit log
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum ExitLogEntry {
+    Withdrawn(EmergencyWithdrawal),
+    Burned { address: Address, token: String },
+}
+
+/// Accepted exits, and those not yet burned from L2 state
+#[derive(Debug, Default)]
+struct ExitLedger {
+    exited: HashSet<(Address, String)>,
+    unburned: Vec<EmergencyWithdrawal>,
+}
+
+impl ExitLedger {
+    fn apply(&mut self, entry: ExitLogEntry) {
+        match entry {
+            ExitLogEntry::Withdrawn(withdrawal) => {
+                self.exited.insert((withdrawal.address.clone(), withdrawal.token.clone()));
+                self.unburned.push(withdrawal);
+            }
+            ExitLogEntry::Burned { address, token } => {
+                self.unburned.retain(|w| w.address != address || w.token != token);
+            }
+        }
+    }
+}
+
+/// Replay the exit log, truncating a torn final line left by a crash
+fn load_exit_log(path: &Path) -> Result<ExitLedger> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(ExitLedger::default()),
+        Err(e) => return Err(BridgeError::internal(format!("reading exit log {}: {}", path.display(), e))),
+    };
+
+    let complete = contents.rfind('\n').map_or(0, |end| end + 1);
+    if complete < contents.len() {
+        warn!("Truncating torn final line of exit log {}", path.display());
+        OpenOptions::new().write(true).open(path)
+            .and_then(|file| file.set_len(complete as u64))
+            .map_err(|e| BridgeError::internal(format!("truncating exit log {}: {}", path.display(), e)))?;
+    }
+
+    let mut ledger = ExitLedger::default();
+    for (number, line) in contents[..complete].lines().enumerate() {
+        let entry = serde_json::from_str(line).map_err(|e| BridgeError::internal(format!(
+            "exit log {} line {}: {}", path.display(), number + 1, e
+        )))?;
+        ledger.apply(entry);
+    }
+    Ok(ledger)
+}
+
+/// Append one entry and fsync it before the caller acts on it
+fn append_exit_log(path: &Path, entry: &ExitLogEntry) -> Result<()> {
+    let line = serde_json::to_string(entry)
+        .map_err(|e| BridgeError::internal(format!("encoding exit log entry: {}", e)))?;
+    let write = |mut file: File| {
+        writeln!(file, "{}", line)?;
+        file.sync_data()
+    };
+    OpenOptions::new().create(true).append(true).open(path)
+        .and_then(write)
+        .map_err(|e| BridgeError::internal(format!("writing exit log {}: {}", path.display(), e)))
+}
+
+/// Tracks L2 downtime and the last finalized root, and accepts exits
+#[derive(Debug)]
+pub struct EmergencyExit {
+    config: EmergencyExitConfig,
+    l2_down_since: Mutex<Option<SystemTime>>,
+    finalized_root: Mutex<Option<Vec<u8>>>,
+    ledger: Mutex<ExitLedger>,
+}
+
+impl EmergencyExit {
+    /// Create the tracker, replaying the exit log if one is configured
+    pub fn new(config: EmergencyExitConfig) -> Result<Self> {
+        let ledger = match &config.exit_log {
+            Some(path) => load_exit_log(path)?,
+            None => ExitLedger::default(),
+        };
+        if !ledger.unburned.is_empty() {
+            info!("{} emergency withdrawals awaiting L2 burn", ledger.unburned.len());
+        }
+        Ok(Self {
+            config,
+            l2_down_since: Mutex::new(None),
+            finalized_root: Mutex::new(None),
+            ledger: Mutex::new(ledger),
+        })
+    }
+
+    fn log(&self, entry: &ExitLogEntry) -> Result<()> {
+        match &self.config.exit_log {
+            Some(path) => append_exit_log(path, entry),
+            None => Ok(()),
+        }
+    }
+
+    /// Record an L2 health observation; downtime restarts after any healthy one
+    pub fn record_l2_health(&self, healthy: bool, now: SystemTime) {
+        let mut down_since = self.l2_down_since.lock();
+        match (healthy, *down_since) {
+            (true, Some(_)) => {
+                info!("GhostPlane L2 recovered; emergency exits closed");
+                *down_since = None;
+            }
+            (false, None) => {
+                warn!("GhostPlane L2 unavailable; emergency exits open after {:?}", self.config.l2_downtime_threshold);
+                *down_since = Some(now);
+            }
+            _ => {}
+        }
+    }
+
+    /// Record the state root of the latest finalized batch
+    pub fn record_finalized_root(&self, state_root: Vec<u8>) {
+        *self.finalized_root.lock() = Some(state_root);
+    }
+
+    pub fn finalized_root(&self) -> Option<Vec<u8>> {
+        self.finalized_root.lock().clone()
+    }
+
+    /// Whether L2 has been down long enough for exits to open
+    pub fn is_active(&self, now: SystemTime) -> bool {
+        self.config.enabled && self.l2_down_since.lock().map_or(false, |since| {
+            now.duration_since(since).unwrap_or_default() >= self.config.l2_downtime_threshold
+        })
+    }
+
+    /// Accept a withdrawal of the proven balance against the last finalized root
+    pub fn withdraw(&self, proof: &BalanceProof, now: SystemTime) -> Result<EmergencyWithdrawal> {
+        if !self.is_active(now) {
+            return Err(BridgeError::Settlement(SettlementError::EmergencyExitInactive {
+                threshold_secs: self.config.l2_downtime_threshold.as_secs(),
+            }));
+        }
+
+        let invalid = |reason: &str| BridgeError::Settlement(SettlementError::InvalidBalanceProof {
+            address: proof.address.to_string(),
+            reason: reason.to_string(),
+        });
+        match self.finalized_root() {
+            None => return Err(invalid("no finalized state root")),
+            Some(root) if root != proof.state_root => return Err(invalid("proof is not against the last finalized state root")),
+            Some(_) => {}
+        }
+        if !proof.verify() {
+            return Err(invalid("balance does not hash to the state root"));
+        }
+
+        let mut ledger = self.ledger.lock();
+        if ledger.exited.contains(&(proof.address.clone(), proof.token.clone())) {
+            return Err(invalid("balance was already withdrawn"));
+        }
+
+        let withdrawal = EmergencyWithdrawal {
+            address: proof.address.clone(),
+            token: proof.token.clone(),
+            amount: proof.balance.clone(),
+            state_root: proof.state_root.clone(),
+            requested_at: now,
+        };
+        // Durable before it is paid out, so a restart cannot pay it twice
+        let entry = ExitLogEntry::Withdrawn(withdrawal.clone());
+        self.log(&entry)?;
+        ledger.apply(entry);
+
+        info!("Emergency withdrawal of {} {} for {}", proof.balance, proof.token, proof.address);
+        Ok(withdrawal)
+    }
+
+    /// Accepted withdrawals whose balances have not been burned on L2 yet
+    pub fn unburned(&self) -> Vec<EmergencyWithdrawal> {
+        self.ledger.lock().unburned.clone()
+    }
+
+    /// Record that `withdrawal`'s balance was burned from L2 state
+    pub fn mark_burned(&self, withdrawal: &EmergencyWithdrawal) -> Result<()> {
+        let entry = ExitLogEntry::Burned { address: withdrawal.address.clone(), token: withdrawal.token.clone() };
+        self.log(&entry)?;
+        self.ledger.lock().apply(entry);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settlement::state_tree::{prove_balance, state_root};
+    use std::collections::HashMap;
+
+    fn balances(entries: &[(u8, u64)]) -> HashMap<(Address, String), U256> {
+        entries.iter()
+            .map(|&(account, amount)| ((Address([account; 20]), "GCC".to_string()), U256::from(amount)))
+            .collect()
+    }
+
+    fn root(table: &HashMap<(Address, String), U256>) -> Vec<u8> {
+        state_root(table, &HashMap::new(), &HashMap::new())
+    }
+
+    fn prove(table: &HashMap<(Address, String), U256>, account: u8) -> BalanceProof {
+        prove_balance(table, &HashMap::new(), &HashMap::new(), &Address([account; 20]), "GCC").unwrap()
+    }
+
+    fn down_exit(root: Vec<u8>) -> (EmergencyExit, SystemTime) {
+        down_exit_logged(root, None)
+    }
+
+    fn down_exit_logged(root: Vec<u8>, exit_log: Option<PathBuf>) -> (EmergencyExit, SystemTime) {
+        let exit = EmergencyExit::new(EmergencyExitConfig {
+            enabled: true,
+            l2_downtime_threshold: Duration::from_secs(3600),
+            exit_log,
+        }).unwrap();
+        let down_at = SystemTime::now();
+        exit.record_finalized_root(root);
+        exit.record_l2_health(false, down_at);
+        (exit, down_at + Duration::from_secs(3601))
+    }
+
+    #[test]
+    fn test_valid_balance_proof_exits_once_l2_is_down() {
+        let table = balances(&[(1, 100), (2, 250), (3, 5)]);
+        let (exit, now) = down_exit(root(&table));
+        let proof = prove(&table, 2);
+        assert!(proof.verify());
+
+        // Not yet down long enough
+        assert!(exit.withdraw(&proof, now - Duration::from_secs(2)).is_err());
+
+        let withdrawal = exit.withdraw(&proof, now).unwrap();
+        assert_eq!(withdrawal.amount, U256::from(250u64));
+        assert_eq!(withdrawal.address, Address([2; 20]));
+
+        // Each balance exits once
+        assert!(exit.withdraw(&proof, now).is_err());
+        let other = prove(&table, 3);
+        assert!(exit.withdraw(&other, now).is_ok());
+
+        // Recovery closes exits again
+        exit.record_l2_health(true, now);
+        assert!(!exit.is_active(now));
+    }
+
+    #[test]
+    fn test_stale_or_forged_balance_proof_is_rejected() {
+        let old = balances(&[(1, 100), (2, 250)]);
+        let latest = balances(&[(1, 100), (2, 10)]);
+        let (exit, now) = down_exit(root(&latest));
+
+        // Proof of a balance that has since been spent
+        let stale = prove(&old, 2);
+        assert!(stale.verify());
+        let error = exit.withdraw(&stale, now).unwrap_err();
+        assert!(error.to_string().contains("last finalized state root"), "{}", error);
+
+        // Inflated balance against the right root
+        let mut forged = prove(&latest, 2);
+        forged.balance = U256::from(250u64);
+        assert!(!forged.verify());
+        assert!(exit.withdraw(&forged, now).is_err());
+    }
+
+    #[test]
+    fn test_exit_log_survives_restart_and_tracks_burns() {
+        let path = std::env::temp_dir().join(format!("ghostbridge-exits-{}.jsonl", uuid::Uuid::new_v4()));
+        let table = balances(&[(1, 100), (2, 250)]);
+        let (exit, now) = down_exit_logged(root(&table), Some(path.clone()));
+        let withdrawal = exit.withdraw(&prove(&table, 2), now).unwrap();
+
+        // A torn write after the accepted exit is dropped on reload
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"event\":\"withdr").unwrap();
+        drop(file);
+
+        let (restarted, now) = down_exit_logged(root(&table), Some(path.clone()));
+        let error = restarted.withdraw(&prove(&table, 2), now).unwrap_err();
+        assert!(error.to_string().contains("already withdrawn"), "{}", error);
+        assert_eq!(restarted.unburned().len(), 1);
+
+        restarted.mark_burned(&withdrawal).unwrap();
+        assert!(restarted.unburned().is_empty());
+        let (reloaded, _) = down_exit_logged(root(&table), Some(path.clone()));
+        assert!(reloaded.unburned().is_empty());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -2,28 +2,42 @@
 Finality engine for L2 settlement
 
 Handles L1 confirmation monitoring, challenge period management, and finality
-determination for optimistic rollup batches.
+determination for optimistic rollup batches. Confirmation counts are turned
+into wall-clock estimates using the settlement network's block time, since
+the same count means minutes on Ethereum but seconds on a fast L1. The block
+time also bounds how soon a batch can be final: the required depth can't be
+reached faster than the network produces blocks.
 */
 
-use crate::error::{BridgeError, Result};
+use crate::bridge::config::NetworkConfig;
+use crate::error::{BridgeError, Result, SettlementError};
 use crate::types::{Address, U256};
 use crate::settlement::{SettlementConfig, SettlementBatch};
+use crate::settlement::finality_callbacks::FinalityCallbacks;
+use crate::settlement::challenge_monitor::{ChallengeMonitor, DefenseSubmitter};
+use crate::clock::SkewTolerantClock;
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, instrument, warn};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 /// Finality engine for L2 batches
 pub struct FinalityEngine {
     config: SettlementConfig,
     l1_monitor: L1Monitor,
-    challenge_tracker: ChallengeTracker,
-    finality_tracker: FinalityTracker,
+    challenge_tracker: Arc<RwLock<ChallengeTracker>>,
+    finality_tracker: parking_lot::RwLock<FinalityTracker>,
     confirmation_manager: ConfirmationManager,
     reorg_detector: ReorgDetector,
     finality_cache: Arc<RwLock<FinalityCache>>,
+    callbacks: Arc<FinalityCallbacks>,
+    challenge_monitor: Arc<ChallengeMonitor>,
+    clock: SkewTolerantClock,
+    /// Block interval of the network batches settle on
+    l1_block_time: parking_lot::RwLock<Duration>,
 }
 
 /// L1 blockchain monitor
@@ -36,7 +50,7 @@ struct L1Monitor {
 
 /// Challenge tracking system
 struct ChallengeTracker {
-    active_challenges: HashMap<String, ActiveChallenge>, // batch_id -> challenge
+    active_challenges: HashMap<String, ActiveChallenge>, // challenge_id -> challenge
     challenge_periods: HashMap<String, ChallengePeriod>,
     challenge_outcomes: HashMap<String, ChallengeOutcome>,
     dispute_resolution: DisputeResolution,
@@ -232,10 +246,16 @@ enum ResolutionMethod {
 struct PendingFinality {
     batch_id: String,
     submitted_at: SystemTime,
+    /// L1 transaction that submitted the batch
+    l1_transaction_hash: String,
+    /// Block the submission was included in, once confirmations are reported
+    l1_block_number: Option<u64>,
     l1_confirmations: u32,
     challenge_period_end: SystemTime,
     finality_requirements: Vec<FinalityRequirement>,
     finality_progress: f64, // 0.0 to 1.0
+    /// Transactions that asked for more confirmations than the batch needs
+    transaction_confirmations: HashMap<Uuid, u32>,
 }
 
 /// Finality requirement
@@ -266,10 +286,13 @@ pub struct FinalizedBatch {
     pub finalized_at: SystemTime,
     pub finality_type: FinalityType,
     pub confirmation_count: u32,
+    /// Transactions still waiting on their elevated confirmation requirement
+    #[serde(default)]
+    pub held_transactions: Vec<Uuid>,
 }
 
 /// Types of finality
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FinalityType {
     Probabilistic, // High probability of finality
     Economic,      // Economic finality through stakes
@@ -353,6 +376,92 @@ enum ReorgHandling {
     Alert,
 }
 
+/// Block interval assumed for networks without a configured block time
+pub const DEFAULT_L1_BLOCK_TIME: Duration = Duration::from_secs(12);
+
+/// Expected wall-clock time until a batch is final: the later of the
+/// remaining confirmations at `block_time` each and the end of the challenge period
+pub fn estimate_time_to_finality(
+    block_time: Duration,
+    confirmations: u32,
+    required_confirmations: u32,
+    challenge_remaining: Duration,
+) -> Duration {
+    let remaining_blocks = required_confirmations.saturating_sub(confirmations);
+    (block_time * remaining_blocks).max(challenge_remaining)
+}
+
+/// Observed L1 reorg history used to estimate finality confidence
+#[derive(Debug, Clone, Default)]
+pub struct ReorgStatistics {
+    /// Blocks observed by the reorg detector
+    pub observed_blocks: u64,
+    /// Depth of each reorg seen over those blocks
+    pub reorg_depths: Vec<u32>,
+}
+
+/// Estimates the probability that a transaction with a given number of
+/// confirmations will not be reorged out.
+///
+/// Reorgs are modelled as occurring at the observed per-block rate with
+/// geometrically distributed depth, so the chance of a reorg reaching `k`
+/// blocks deep is `rate * decay^(k - 1)`. With no history the estimator
+/// assumes a reorg every block with a mean depth of 2 blocks.
+#[derive(Debug, Clone)]
+pub struct ProbabilisticFinalityEstimator {
+    threshold: f64,
+}
+
+impl ProbabilisticFinalityEstimator {
+    pub fn new(threshold: f64) -> Self {
+        Self { threshold: threshold.clamp(0.0, 1.0) }
+    }
+
+    /// Finality confidence (0.0 to 1.0) after `confirmations` blocks
+    pub fn confidence(&self, confirmations: u32, stats: &ReorgStatistics) -> f64 {
+        if confirmations == 0 {
+            return 0.0;
+        }
+
+        let (rate, mean_depth) = if stats.observed_blocks == 0 {
+            (1.0, 2.0)
+        } else {
+            let reorgs = stats.reorg_depths.len() as f64;
+            let mean_depth = if stats.reorg_depths.is_empty() {
+                1.0
+            } else {
+                stats.reorg_depths.iter().map(|&d| d.max(1) as f64).sum::<f64>() / reorgs
+            };
+            ((reorgs / stats.observed_blocks as f64).min(1.0), mean_depth)
+        };
+
+        let decay = 1.0 - 1.0 / mean_depth;
+        let reversal_probability = rate * decay.powi(confirmations as i32 - 1);
+        (1.0 - reversal_probability).clamp(0.0, 1.0)
+    }
+
+    /// Whether `confirmations` reach the probabilistic finality threshold
+    pub fn is_final(&self, confirmations: u32, stats: &ReorgStatistics) -> bool {
+        self.confidence(confirmations, stats) >= self.threshold
+    }
+
+    /// Fewest confirmations reaching the threshold, capped at `max_confirmations`
+    pub fn confirmations_required(&self, stats: &ReorgStatistics, max_confirmations: u32) -> u32 {
+        (1..=max_confirmations)
+            .find(|&k| self.is_final(k, stats))
+            .unwrap_or(max_confirmations)
+    }
+}
+
+impl ReorgDetector {
+    fn statistics(&self) -> ReorgStatistics {
+        ReorgStatistics {
+            observed_blocks: self.monitored_blocks.len() as u64,
+            reorg_depths: self.recent_reorgs.iter().map(|r| r.depth).collect(),
+        }
+    }
+}
+
 /// Finality cache
 #[derive(Debug, Clone)]
 struct FinalityCache {
@@ -392,7 +501,7 @@ impl FinalityEngine {
                 minimum_confirmations: 12,     // ~3 minutes on Ethereum
                 fast_finality_threshold: 6,    // ~1.5 minutes
                 deep_finality_threshold: 64,   // ~16 minutes
-                probabilistic_threshold: 0.99, // 99% confidence
+                probabilistic_threshold: config.probabilistic_finality_threshold,
             },
         };
 
@@ -424,7 +533,7 @@ impl FinalityEngine {
             required_confirmations: 12,
             confirmation_tracking: HashMap::new(),
             fast_finality_enabled: true,
-            probabilistic_finality: true,
+            probabilistic_finality: config.enable_probabilistic_finality,
         };
 
         let reorg_detector = ReorgDetector {
@@ -443,38 +552,63 @@ impl FinalityEngine {
             },
         }));
 
+        let callbacks = Arc::new(FinalityCallbacks::new(config.finality_callbacks.clone()));
+        let challenge_monitor = Arc::new(ChallengeMonitor::new(config.challenge_defense.clone()));
+        let clock = SkewTolerantClock::new(&config.clock);
+
         Ok(Self {
             config,
             l1_monitor,
-            challenge_tracker,
-            finality_tracker,
+            challenge_tracker: Arc::new(RwLock::new(challenge_tracker)),
+            finality_tracker: parking_lot::RwLock::new(finality_tracker),
             confirmation_manager,
             reorg_detector,
             finality_cache,
+            callbacks,
+            challenge_monitor,
+            clock,
+            l1_block_time: parking_lot::RwLock::new(DEFAULT_L1_BLOCK_TIME),
         })
     }
 
+    /// Use a custom dispute contract client for automatic defenses
+    pub fn with_defense_submitter(self, submitter: Arc<dyn DefenseSubmitter>) -> Self {
+        self.challenge_monitor.set_submitter(submitter);
+        self
+    }
+
+    /// Per-transaction callbacks fired when a batch finalizes
+    pub fn callbacks(&self) -> &Arc<FinalityCallbacks> {
+        &self.callbacks
+    }
+
+    /// Watches challenges against batches this node proposed
+    pub fn challenge_monitor(&self) -> &Arc<ChallengeMonitor> {
+        &self.challenge_monitor
+    }
+
     /// Check finalized batches
     #[instrument(skip(self))]
     pub async fn check_finalized_batches(&self) -> Result<Vec<FinalizedBatch>> {
         debug!("Checking for finalized batches");
 
+        let redelivered = self.callbacks.redeliver();
+        if redelivered > 0 {
+            debug!("Redelivering {} settlement receipts", redelivered);
+        }
+
         let mut finalized = Vec::new();
 
         // Check each pending batch for finality
-        let mut to_finalize = Vec::new();
-        {
-            let pending = &self.finality_tracker.pending_finality;
-            for (batch_id, pending_finality) in pending {
-                if self.is_batch_finalized(pending_finality).await? {
-                    to_finalize.push(batch_id.clone());
-                }
-            }
-        }
+        let now = self.clock.now();
+        let to_finalize: Vec<(String, Vec<Uuid>)> = self.finality_tracker.read().pending_finality.iter()
+            .filter(|(_, pending)| self.is_batch_finalized(pending, now))
+            .map(|(batch_id, pending)| (batch_id.clone(), Self::held_transactions(pending)))
+            .collect();
 
         // Process finalized batches
-        for batch_id in to_finalize {
-            if let Some(finalized_batch) = self.finalize_batch(&batch_id).await? {
+        for (batch_id, held) in to_finalize {
+            if let Some(finalized_batch) = self.finalize_batch(&batch_id, held).await? {
                 finalized.push(finalized_batch);
             }
         }
@@ -483,6 +617,160 @@ impl FinalityEngine {
         Ok(finalized)
     }
 
+    /// Batches that have reached probabilistic finality but not yet economic
+    /// finality, for clients that accept probabilistic guarantees
+    #[instrument(skip(self))]
+    pub async fn check_probabilistic_finality(&self) -> Result<Vec<FinalizedBatch>> {
+        if !self.confirmation_manager.probabilistic_finality {
+            return Ok(Vec::new());
+        }
+
+        let estimator = self.probabilistic_estimator();
+        let stats = self.reorg_detector.statistics();
+        let now = self.clock.now();
+
+        let fast_path: Vec<FinalizedBatch> = self.finality_tracker.read().pending_finality.iter()
+            .filter(|(_, pending)| {
+                !self.is_batch_finalized(pending, now) && estimator.is_final(pending.l1_confirmations, &stats)
+            })
+            .map(|(batch_id, pending)| {
+                debug!("Batch {} probabilistically final at {} confirmations (confidence {:.4})",
+                       batch_id, pending.l1_confirmations,
+                       estimator.confidence(pending.l1_confirmations, &stats));
+                FinalizedBatch {
+                    batch_id: batch_id.clone(),
+                    l1_block_number: pending.l1_block_number.unwrap_or_default(),
+                    l1_transaction_hash: pending.l1_transaction_hash.clone(),
+                    gas_used: 0, // TODO: Get actual gas used
+                    finalized_at: now,
+                    finality_type: FinalityType::Probabilistic,
+                    confirmation_count: pending.l1_confirmations,
+                    held_transactions: Self::held_transactions(pending),
+                }
+            })
+            .collect();
+
+        for batch in &fast_path {
+            self.cache_finality_result(&batch.batch_id, true, Some(FinalityType::Probabilistic)).await;
+        }
+        Ok(fast_path)
+    }
+
+    /// Whether a pending batch has reached probabilistic finality; always
+    /// false when the fast path is disabled
+    pub fn is_probabilistically_final(&self, batch_id: &str) -> bool {
+        if !self.confirmation_manager.probabilistic_finality {
+            return false;
+        }
+        let tracker = self.finality_tracker.read();
+        tracker.pending_finality.get(batch_id).is_some_and(|pending| {
+            self.probabilistic_estimator().is_final(pending.l1_confirmations, &self.reorg_detector.statistics())
+        })
+    }
+
+    /// Expected time until a pending batch is final
+    pub fn finality_eta(&self, batch_id: &str) -> Option<Duration> {
+        let tracker = self.finality_tracker.read();
+        let pending = tracker.pending_finality.get(batch_id)?;
+        let now = self.clock.now();
+        // Deadlines only count as passed once the skew tolerance has passed too
+        let remaining_until = |deadline: SystemTime| (deadline + self.clock.max_skew())
+            .duration_since(now)
+            .unwrap_or_default();
+        let eta = estimate_time_to_finality(
+            self.l1_block_time(),
+            pending.l1_confirmations,
+            self.confirmation_manager.required_confirmations,
+            remaining_until(pending.challenge_period_end),
+        );
+        Some(eta.max(remaining_until(pending.submitted_at + self.min_time_to_depth())))
+    }
+
+    /// Batches submitted to L1 and not yet final, with their L1 transaction hashes
+    pub fn pending_submissions(&self) -> Vec<(String, String)> {
+        self.finality_tracker.read().pending_finality.values()
+            .map(|pending| (pending.batch_id.clone(), pending.l1_transaction_hash.clone()))
+            .collect()
+    }
+
+    /// L1 confirmations a batch needs before it is final
+    pub fn required_confirmations(&self) -> u32 {
+        self.confirmation_manager.required_confirmations
+    }
+
+    /// Block time of the network batches settle on
+    pub fn l1_block_time(&self) -> Duration {
+        *self.l1_block_time.read()
+    }
+
+    /// Take the block time from the settlement network's config, as L1 inclusion waits do
+    pub fn set_l1_network(&self, network: &NetworkConfig) {
+        *self.l1_block_time.write() = Duration::from_millis(network.block_time_ms.max(1));
+    }
+
+    /// Shortest time in which the required confirmation depth can be built
+    fn min_time_to_depth(&self) -> Duration {
+        self.l1_block_time() * self.confirmation_manager.required_confirmations
+    }
+
+    /// Current finality confidence for a pending batch
+    pub fn finality_confidence(&self, batch_id: &str) -> Option<f64> {
+        let tracker = self.finality_tracker.read();
+        let pending = tracker.pending_finality.get(batch_id)?;
+        Some(self.probabilistic_estimator()
+            .confidence(pending.l1_confirmations, &self.reorg_detector.statistics()))
+    }
+
+    /// Require more confirmations than the batch default before a transaction
+    /// in `batch_id` is considered final, e.g. for high-value transfers
+    pub fn require_transaction_confirmations(
+        &self,
+        batch_id: &str,
+        transaction_id: Uuid,
+        confirmations: u32,
+    ) -> Result<()> {
+        let mut tracker = self.finality_tracker.write();
+        let pending = tracker.pending_finality.get_mut(batch_id)
+            .ok_or_else(|| BridgeError::Settlement(SettlementError::UnknownBatch {
+                batch_id: batch_id.to_string(),
+            }))?;
+
+        // Overrides can only raise the requirement
+        if confirmations > self.confirmation_manager.required_confirmations {
+            let required = pending.transaction_confirmations.entry(transaction_id).or_insert(0);
+            *required = (*required).max(confirmations);
+        }
+        Ok(())
+    }
+
+    /// Whether a transaction is final: its batch is final and any elevated
+    /// confirmation requirement it asked for is met
+    pub async fn is_transaction_finalized(&self, batch_id: &str, transaction_id: &Uuid) -> Result<bool> {
+        let tracker = self.finality_tracker.read();
+        let Some(pending) = tracker.pending_finality.get(batch_id) else {
+            return Ok(tracker.finalized_batches.contains_key(batch_id));
+        };
+
+        let required = pending.transaction_confirmations.get(transaction_id).copied().unwrap_or(0);
+        Ok(self.is_batch_finalized(pending, self.clock.now()) && pending.l1_confirmations >= required)
+    }
+
+    /// Transactions whose elevated requirement exceeds the batch's confirmations
+    fn held_transactions(pending: &PendingFinality) -> Vec<Uuid> {
+        let mut held: Vec<Uuid> = pending.transaction_confirmations.iter()
+            .filter(|(_, &required)| pending.l1_confirmations < required)
+            .map(|(transaction_id, _)| *transaction_id)
+            .collect();
+        held.sort();
+        held
+    }
+
+    fn probabilistic_estimator(&self) -> ProbabilisticFinalityEstimator {
+        ProbabilisticFinalityEstimator::new(
+            self.l1_monitor.confirmation_requirements.probabilistic_threshold,
+        )
+    }
+
     /// Track L1 submission
     #[instrument(skip(self))]
     pub async fn track_l1_submission(
@@ -493,23 +781,15 @@ impl FinalityEngine {
     ) -> Result<()> {
         debug!("Tracking L1 submission: batch {}, tx {}", batch_id, transaction_hash);
 
-        // Add to monitored transactions
-        let monitored_tx = MonitoredTransaction {
-            tx_hash: transaction_hash.clone(),
-            batch_id: batch_id.clone(),
-            submitted_at,
-            block_number: None,
-            block_hash: None,
-            confirmations: 0,
-            status: TransactionStatus::Pending,
-        };
-
         // Add to pending finality
         let pending_finality = PendingFinality {
             batch_id: batch_id.clone(),
             submitted_at,
+            l1_transaction_hash: transaction_hash,
+            l1_block_number: None,
             l1_confirmations: 0,
             challenge_period_end: submitted_at + self.config.challenge_period,
+            transaction_confirmations: HashMap::new(),
             finality_requirements: vec![
                 FinalityRequirement {
                     requirement_type: RequirementType::L1Confirmations,
@@ -542,6 +822,9 @@ impl FinalityEngine {
             period_status: PeriodStatus::Active,
         };
 
+        self.challenge_tracker.write().await.challenge_periods.insert(batch_id.clone(), challenge_period);
+        self.finality_tracker.write().pending_finality.insert(batch_id.clone(), pending_finality);
+
         info!("Started tracking L1 submission for batch: {}", batch_id);
         Ok(())
     }
@@ -555,21 +838,12 @@ impl FinalityEngine {
         block_hash: String,
         confirmations: u32,
     ) -> Result<()> {
-        debug!("Updating L1 confirmation: tx {}, block {}, confirmations {}",
-               transaction_hash, block_number, confirmations);
-
-        // Update block confirmation
-        let block_confirmation = BlockConfirmation {
-            block_hash: block_hash.clone(),
-            block_number,
-            transaction_hash: transaction_hash.clone(),
-            confirmations,
-            timestamp: SystemTime::now(),
-            finalized: confirmations >= self.confirmation_manager.required_confirmations,
-        };
+        let remaining = self.confirmation_manager.required_confirmations.saturating_sub(confirmations);
+        debug!("Updating L1 confirmation: tx {}, block {} ({}), confirmations {} (~{:?} to required depth)",
+               transaction_hash, block_number, block_hash, confirmations, self.l1_block_time() * remaining);
 
         // Check for finality progress
-        self.update_finality_progress(&transaction_hash, confirmations).await?;
+        self.update_finality_progress(&transaction_hash, block_number, confirmations).await?;
 
         debug!("L1 confirmation updated: {} confirmations", confirmations);
         Ok(())
@@ -602,10 +876,55 @@ impl FinalityEngine {
             responses: Vec::new(),
         };
 
-        // Update challenge period status
-        if let Some(period) = self.challenge_tracker.challenge_periods.get_mut(&batch_id) {
-            period.challenge_count += 1;
-            period.period_status = PeriodStatus::Challenged;
+        let deadline = challenge.deadline;
+        {
+            let mut tracker = self.challenge_tracker.write().await;
+            // Update challenge period status
+            if let Some(period) = tracker.challenge_periods.get_mut(&batch_id) {
+                period.challenge_count += 1;
+                period.period_status = PeriodStatus::Challenged;
+            }
+            tracker.active_challenges.insert(challenge_id.clone(), challenge);
+        }
+
+        // Defend our own batches in the background so registration is not held
+        // up by re-execution and L1 submission; the challenge stays open unless
+        // the defense reaches L1
+        if self.challenge_monitor.should_defend(&batch_id) {
+            let monitor = self.challenge_monitor.clone();
+            let tracker = self.challenge_tracker.clone();
+            let challenge_id = challenge_id.clone();
+            let batch_id = batch_id.clone();
+            tokio::spawn(async move {
+                let defense = match monitor.on_challenge(&challenge_id, &batch_id, deadline).await {
+                    Ok(Some(defense)) => defense,
+                    Ok(None) => return,
+                    Err(e) => {
+                        error!("Failed to defend batch {} against challenge {}: {}", batch_id, challenge_id, e);
+                        return;
+                    }
+                };
+                let response_data = match defense.evidence() {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error!("Failed to record defense for challenge {}: {}", challenge_id, e);
+                        return;
+                    }
+                };
+
+                let mut tracker = tracker.write().await;
+                if let Some(challenge) = tracker.active_challenges.get_mut(&challenge_id) {
+                    challenge.responses.push(ChallengeResponse {
+                        responder: defense.responder,
+                        response_data,
+                        submitted_at: defense.submitted_at,
+                        response_type: ResponseType::Defense,
+                    });
+                    if challenge.status == ChallengeStatus::Open {
+                        challenge.status = ChallengeStatus::UnderReview;
+                    }
+                }
+            });
         }
 
         // Update finality requirements
@@ -644,8 +963,8 @@ impl FinalityEngine {
     /// Health check
     pub async fn is_healthy(&self) -> bool {
         let current_block = *self.l1_monitor.current_block.read().await;
-        let pending_count = self.finality_tracker.pending_finality.len();
-        let active_challenges = self.challenge_tracker.active_challenges.len();
+        let pending_count = self.finality_tracker.read().pending_finality.len();
+        let active_challenges = self.challenge_tracker.read().await.active_challenges.len();
 
         // System is healthy if:
         // - We're tracking recent blocks
@@ -656,55 +975,106 @@ impl FinalityEngine {
         active_challenges < 100
     }
 
-    async fn is_batch_finalized(&self, pending: &PendingFinality) -> Result<bool> {
-        // Check all finality requirements
-        let mut all_satisfied = true;
-
-        for requirement in &pending.finality_requirements {
-            if !requirement.satisfied {
-                all_satisfied = false;
-                break;
-            }
-        }
+    fn is_batch_finalized(&self, pending: &PendingFinality, now: SystemTime) -> bool {
+        // Confirmations and the challenge period are checked directly below;
+        // every other requirement must have been marked satisfied
+        let all_satisfied = pending.finality_requirements.iter()
+            .filter(|requirement| !matches!(
+                requirement.requirement_type,
+                RequirementType::L1Confirmations | RequirementType::ChallengePeriod
+            ))
+            .all(|requirement| requirement.satisfied);
 
-        // Check challenge period
-        let challenge_period_expired = SystemTime::now() > pending.challenge_period_end;
+        // Check challenge period, which must be over even on a node whose clock runs behind
+        let challenge_period_expired = self.clock.has_passed(pending.challenge_period_end, now);
 
         // Check confirmations
         let sufficient_confirmations = pending.l1_confirmations >=
             self.confirmation_manager.required_confirmations;
 
-        Ok(all_satisfied && challenge_period_expired && sufficient_confirmations)
+        // The required depth can't have been built faster than the L1 produces
+        // blocks; a count that arrives sooner is not trusted
+        let depth_time_elapsed = self.clock.has_passed(pending.submitted_at + self.min_time_to_depth(), now);
+
+        all_satisfied && challenge_period_expired && sufficient_confirmations && depth_time_elapsed
     }
 
-    async fn finalize_batch(&self, batch_id: &str) -> Result<Option<FinalizedBatch>> {
+    async fn finalize_batch(&self, batch_id: &str, held_transactions: Vec<Uuid>) -> Result<Option<FinalizedBatch>> {
         debug!("Finalizing batch: {}", batch_id);
 
-        // Get pending finality info
-        // TODO: Implement actual finalization logic
-
-        let finalized_batch = FinalizedBatch {
-            batch_id: batch_id.to_string(),
-            l1_block_number: 0, // TODO: Get actual block number
-            l1_transaction_hash: "".to_string(), // TODO: Get actual tx hash
-            gas_used: 0, // TODO: Get actual gas used
-            finalized_at: SystemTime::now(),
-            finality_type: FinalityType::Economic,
-            confirmation_count: self.confirmation_manager.required_confirmations,
+        let finalized_batch = {
+            let mut tracker = self.finality_tracker.write();
+            let Some(pending) = tracker.pending_finality.get(batch_id) else {
+                return Ok(None);
+            };
+            let finalized_batch = FinalizedBatch {
+                batch_id: batch_id.to_string(),
+                l1_block_number: pending.l1_block_number.unwrap_or_default(),
+                l1_transaction_hash: pending.l1_transaction_hash.clone(),
+                gas_used: 0, // TODO: Get actual gas used
+                finalized_at: SystemTime::now(),
+                finality_type: FinalityType::Economic,
+                confirmation_count: pending.l1_confirmations,
+                held_transactions,
+            };
+            // A batch holding transactions back stays pending, and is reported
+            // again, until their elevated confirmations arrive
+            if finalized_batch.held_transactions.is_empty() {
+                tracker.pending_finality.remove(batch_id);
+                tracker.finalized_batches.insert(batch_id.to_string(), finalized_batch.clone());
+            }
+            finalized_batch
         };
+        if finalized_batch.held_transactions.is_empty() {
+            self.challenge_tracker.write().await.challenge_periods.remove(batch_id);
+        }
 
         // Cache finality result
         self.cache_finality_result(batch_id, true, Some(FinalityType::Economic)).await;
+        self.callbacks.notify(&finalized_batch);
 
-        info!("Batch finalized: {}", batch_id);
+        if finalized_batch.held_transactions.is_empty() {
+            info!("Batch finalized: {}", batch_id);
+        } else {
+            info!("Batch finalized: {} ({} transactions awaiting elevated confirmations)",
+                  batch_id, finalized_batch.held_transactions.len());
+        }
         Ok(Some(finalized_batch))
     }
 
-    async fn update_finality_progress(&self, transaction_hash: &str, confirmations: u32) -> Result<()> {
+    async fn update_finality_progress(&self, transaction_hash: &str, block_number: u64, confirmations: u32) -> Result<()> {
+        let required = self.confirmation_manager.required_confirmations;
+        let now = self.clock.now();
+
         // Update progress for batches associated with this transaction
-        // TODO: Implement actual progress tracking
-        debug!("Updated finality progress for tx: {} ({} confirmations)",
-               transaction_hash, confirmations);
+        let mut tracker = self.finality_tracker.write();
+        let Some(pending) = tracker.pending_finality.values_mut()
+            .find(|pending| pending.l1_transaction_hash == transaction_hash)
+        else {
+            debug!("No pending batch for L1 transaction {}", transaction_hash);
+            return Ok(());
+        };
+
+        pending.l1_block_number = Some(block_number);
+        pending.l1_confirmations = confirmations;
+        pending.finality_progress = if required == 0 {
+            1.0
+        } else {
+            f64::from(confirmations.min(required)) / f64::from(required)
+        };
+        let challenge_period_expired = self.clock.has_passed(pending.challenge_period_end, now);
+        for requirement in &mut pending.finality_requirements {
+            let satisfied = match requirement.requirement_type {
+                RequirementType::L1Confirmations => confirmations >= required,
+                RequirementType::ChallengePeriod => challenge_period_expired,
+                _ => continue,
+            };
+            requirement.satisfied = satisfied;
+            requirement.checked_at = now;
+        }
+
+        debug!("Updated finality progress for batch {} (tx {}): {} confirmations",
+               pending.batch_id, transaction_hash, confirmations);
         Ok(())
     }
 
@@ -808,6 +1178,9 @@ impl FinalityEngine {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bridge::config::BridgeConfig;
+    use crate::settlement::challenge_monitor::{BatchReexecutor, ChallengeDefense, ChallengeDefenseConfig, ReexecutionTrace};
+    use crate::types::{fixtures, ChainId, TransactionHash};
 
     #[tokio::test]
     async fn test_finality_engine_creation() {
@@ -830,6 +1203,100 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_confidence_rises_with_confirmations() {
+        let estimator = ProbabilisticFinalityEstimator::new(0.99);
+        let stats = ReorgStatistics {
+            observed_blocks: 1000,
+            reorg_depths: vec![2; 100], // 10% reorg rate, mean depth 2
+        };
+
+        let confidences: Vec<f64> = (0..=8).map(|k| estimator.confidence(k, &stats)).collect();
+        assert_eq!(confidences[0], 0.0);
+        assert!(confidences.windows(2).all(|w| w[1] > w[0]));
+
+        // 1 - 0.1 * 0.5^(k-1): 0.9875 at 4 confirmations, 0.99375 at 5
+        assert!(!estimator.is_final(4, &stats));
+        assert!(estimator.is_final(5, &stats));
+        assert_eq!(estimator.confirmations_required(&stats, 64), 5);
+    }
+
+    #[test]
+    fn test_confidence_without_history_is_conservative() {
+        let estimator = ProbabilisticFinalityEstimator::new(0.99);
+        let stats = ReorgStatistics::default();
+
+        assert_eq!(estimator.confirmations_required(&stats, 64), 8);
+    }
+
+    #[tokio::test]
+    async fn test_probabilistic_fast_path_marks_batch() {
+        let engine = FinalityEngine::new(SettlementConfig::default()).await.unwrap();
+        let now = SystemTime::now();
+        let pending = |confirmations| PendingFinality {
+            batch_id: String::new(),
+            submitted_at: now,
+            l1_transaction_hash: String::new(),
+            l1_block_number: None,
+            l1_confirmations: confirmations,
+            challenge_period_end: now + Duration::from_secs(3600),
+            finality_requirements: Vec::new(),
+            finality_progress: 0.0,
+            transaction_confirmations: HashMap::new(),
+        };
+        engine.finality_tracker.write().pending_finality.insert("shallow".to_string(), pending(7));
+        engine.finality_tracker.write().pending_finality.insert("deep".to_string(), pending(8));
+
+        let fast_path = engine.check_probabilistic_finality().await.unwrap();
+        assert_eq!(fast_path.len(), 1);
+        assert_eq!(fast_path[0].batch_id, "deep");
+        assert_eq!(fast_path[0].finality_type, FinalityType::Probabilistic);
+        assert!(engine.finality_confidence("shallow").unwrap() < 0.99);
+        assert!(engine.is_probabilistically_final("deep"));
+        assert!(!engine.is_probabilistically_final("shallow"));
+
+        let config = SettlementConfig { enable_probabilistic_finality: false, ..SettlementConfig::default() };
+        let disabled = FinalityEngine::new(config).await.unwrap();
+        disabled.finality_tracker.write().pending_finality.insert("deep".to_string(), pending(8));
+        assert!(!disabled.is_probabilistically_final("deep"));
+    }
+
+    #[tokio::test]
+    async fn test_elevated_confirmations_hold_transaction() {
+        let engine = FinalityEngine::new(SettlementConfig::default()).await.unwrap();
+        let now = SystemTime::now();
+        engine.finality_tracker.write().pending_finality.insert("batch".to_string(), PendingFinality {
+            batch_id: "batch".to_string(),
+            submitted_at: now - Duration::from_secs(7200),
+            l1_transaction_hash: String::new(),
+            l1_block_number: None,
+            l1_confirmations: 12,
+            challenge_period_end: now - Duration::from_secs(3600),
+            finality_requirements: Vec::new(),
+            finality_progress: 1.0,
+            transaction_confirmations: HashMap::new(),
+        });
+
+        let standard = Uuid::new_v4();
+        let high_value = Uuid::new_v4();
+        engine.require_transaction_confirmations("batch", high_value, 64).unwrap();
+        // Lowering below the batch default is ignored
+        engine.require_transaction_confirmations("batch", standard, 3).unwrap();
+        assert!(engine.require_transaction_confirmations("unknown", high_value, 64).is_err());
+
+        // The batch finalizes at the standard threshold, holding back the high-value transaction
+        let finalized = engine.check_finalized_batches().await.unwrap();
+        assert_eq!(finalized.len(), 1);
+        assert_eq!(finalized[0].held_transactions, vec![high_value]);
+        assert!(engine.is_transaction_finalized("batch", &standard).await.unwrap());
+        assert!(!engine.is_transaction_finalized("batch", &high_value).await.unwrap());
+
+        engine.finality_tracker.write().pending_finality.get_mut("batch").unwrap().l1_confirmations = 64;
+        assert!(engine.is_transaction_finalized("batch", &high_value).await.unwrap());
+        let finalized = engine.check_finalized_batches().await.unwrap();
+        assert!(finalized[0].held_transactions.is_empty());
+    }
+
     #[tokio::test]
     async fn test_challenge_registration() {
         let config = SettlementConfig::default();
@@ -844,4 +1311,202 @@ mod tests {
 
         assert!(!challenge_id.is_empty());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_finality_callback_receives_receipt() {
+        use crate::settlement::finality_callbacks::FinalityCallback;
+
+        let engine = FinalityEngine::new(SettlementConfig::default()).await.unwrap();
+        let now = SystemTime::now();
+        engine.finality_tracker.write().pending_finality.insert("batch".to_string(), PendingFinality {
+            batch_id: "batch".to_string(),
+            submitted_at: now - Duration::from_secs(7200),
+            l1_transaction_hash: String::new(),
+            l1_block_number: None,
+            l1_confirmations: 12,
+            challenge_period_end: now - Duration::from_secs(3600),
+            finality_requirements: Vec::new(),
+            finality_progress: 1.0,
+            transaction_confirmations: HashMap::new(),
+        });
+
+        let transaction_id = Uuid::new_v4();
+        let (sender, mut receipts) = tokio::sync::mpsc::unbounded_channel();
+        engine.callbacks().register(transaction_id, FinalityCallback::Channel(sender));
+        engine.callbacks().assign_batch("batch", [transaction_id, Uuid::new_v4()]);
+        assert_eq!(engine.callbacks().pending(), 1);
+
+        let finalized = engine.check_finalized_batches().await.unwrap();
+        assert_eq!(finalized.len(), 1);
+
+        let receipt = tokio::time::timeout(Duration::from_secs(1), receipts.recv()).await.unwrap().unwrap();
+        assert_eq!(receipt.transaction_id, transaction_id);
+        assert_eq!(receipt.batch_id, "batch");
+        assert_eq!(receipt.finality_type, FinalityType::Economic);
+        assert_eq!(engine.callbacks().pending(), 0);
+    }
+
+    struct RecordingSubmitter(parking_lot::Mutex<Vec<ChallengeDefense>>);
+
+    #[async_trait::async_trait]
+    impl DefenseSubmitter for RecordingSubmitter {
+        async fn submit_defense(&self, defense: &ChallengeDefense) -> Result<TransactionHash> {
+            self.0.lock().push(defense.clone());
+            Ok(TransactionHash([7; 32]))
+        }
+    }
+
+    /// Replays every batch to `state_root`
+    struct FixedReexecutor { state_root: Vec<u8> }
+
+    #[async_trait::async_trait]
+    impl BatchReexecutor for FixedReexecutor {
+        async fn reexecute(&self, batch_id: &str) -> Result<ReexecutionTrace> {
+            Ok(ReexecutionTrace {
+                batch_id: batch_id.to_string(),
+                previous_state_root: vec![0; 32],
+                state_root: self.state_root.clone(),
+                gas_used: 0,
+                steps: Vec::new(),
+            })
+        }
+    }
+
+    async fn defending_engine(state_root: Vec<u8>) -> (FinalityEngine, Arc<RecordingSubmitter>) {
+        let config = SettlementConfig {
+            challenge_defense: ChallengeDefenseConfig { enabled: true, proposer: Some(Address([5; 20])) },
+            ..SettlementConfig::default()
+        };
+        let submitter = Arc::new(RecordingSubmitter(parking_lot::Mutex::new(Vec::new())));
+        let engine = FinalityEngine::new(config).await.unwrap()
+            .with_defense_submitter(submitter.clone());
+        engine.challenge_monitor().set_reexecutor(Arc::new(FixedReexecutor { state_root }));
+        engine.challenge_monitor().record_proposal(&fixtures::batch("own-batch"));
+        (engine, submitter)
+    }
+
+    async fn challenge_status(engine: &FinalityEngine, challenge_id: &str) -> ChallengeStatus {
+        engine.challenge_tracker.read().await.active_challenges[challenge_id].status.clone()
+    }
+
+    #[tokio::test]
+    async fn test_challenge_to_own_batch_is_defended() {
+        let batch = fixtures::batch("own-batch");
+        let (engine, submitter) = defending_engine(batch.state_root.clone()).await;
+
+        // Challenges against other nodes' batches are left alone
+        engine.register_challenge("other-batch".to_string(), Address::from("0x1234"),
+                                  ChallengeType::StateTransition, vec![1]).await.unwrap();
+
+        let challenge_id = engine.register_challenge("own-batch".to_string(), Address::from("0x1234"),
+                                                     ChallengeType::StateTransition, vec![1]).await.unwrap();
+
+        // The defense is submitted in the background
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while challenge_status(&engine, &challenge_id).await != ChallengeStatus::UnderReview {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }).await.unwrap();
+
+        let defenses = submitter.0.lock().clone();
+        assert_eq!(defenses.len(), 1);
+        assert_eq!(defenses[0].challenge_id, challenge_id);
+        assert_eq!(defenses[0].responder, Address([5; 20]));
+        assert_eq!(defenses[0].trace.state_root, batch.state_root);
+        assert!(defenses[0].submitted_at < defenses[0].deadline);
+        assert_eq!(engine.challenge_monitor().defenses_submitted(), 1);
+
+        let tracker = engine.challenge_tracker.read().await;
+        let challenge = &tracker.active_challenges[&challenge_id];
+        assert!(matches!(challenge.responses[..], [ChallengeResponse { response_type: ResponseType::Defense, .. }]));
+    }
+
+    #[tokio::test]
+    async fn test_challenge_stays_open_when_defense_cannot_reach_l1() {
+        // A replay that does not reproduce the batch is never submitted
+        let (engine, submitter) = defending_engine(vec![0xff; 32]).await;
+        let diverged = engine.register_challenge("own-batch".to_string(), Address::from("0x1234"),
+                                                 ChallengeType::StateTransition, vec![1]).await.unwrap();
+
+        // Neither is anything without a dispute contract client or proposer
+        let unconfigured = FinalityEngine::new(SettlementConfig::default()).await.unwrap();
+        unconfigured.challenge_monitor().record_proposal(&fixtures::batch("own-batch"));
+        let unsubmitted = unconfigured.register_challenge("own-batch".to_string(), Address::from("0x1234"),
+                                                          ChallengeType::StateTransition, vec![1]).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(submitter.0.lock().is_empty());
+        assert_eq!(engine.challenge_monitor().defenses_submitted(), 0);
+        assert_eq!(challenge_status(&engine, &diverged).await, ChallengeStatus::Open);
+        assert_eq!(unconfigured.challenge_monitor().defenses_submitted(), 0);
+        assert_eq!(challenge_status(&unconfigured, &unsubmitted).await, ChallengeStatus::Open);
+
+        let monitor = ChallengeMonitor::new(ChallengeDefenseConfig::default());
+        monitor.record_proposal(&fixtures::batch("own-batch"));
+        let deadline = SystemTime::now() + Duration::from_secs(60);
+        assert!(matches!(
+            monitor.on_challenge("challenge", "own-batch", deadline).await,
+            Err(BridgeError::Settlement(SettlementError::DefenseUnavailable { .. }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_finality_eta_scales_with_block_time() {
+        let slow = FinalityEngine::new(SettlementConfig::default()).await.unwrap();
+        let fast = FinalityEngine::new(SettlementConfig::default()).await.unwrap();
+        fast.set_l1_network(&BridgeConfig::default().networks[&ChainId(42161)]);
+        assert_eq!(slow.l1_block_time(), Duration::from_secs(12));
+        assert_eq!(fast.l1_block_time(), Duration::from_millis(250));
+
+        // Same confirmation count, challenge period already over
+        let now = SystemTime::now();
+        let pending = PendingFinality {
+            batch_id: "batch".to_string(),
+            submitted_at: now - Duration::from_secs(7200),
+            l1_transaction_hash: String::new(),
+            l1_block_number: None,
+            l1_confirmations: 4,
+            challenge_period_end: now - Duration::from_secs(3600),
+            finality_requirements: Vec::new(),
+            finality_progress: 0.0,
+            transaction_confirmations: HashMap::new(),
+        };
+        slow.finality_tracker.write().pending_finality.insert("batch".to_string(), pending.clone());
+        fast.finality_tracker.write().pending_finality.insert("batch".to_string(), pending);
+
+        // 8 blocks to go: ~96s on Ethereum, 2s on Arbitrum
+        assert_eq!(slow.finality_eta("batch"), Some(Duration::from_secs(96)));
+        assert_eq!(fast.finality_eta("batch"), Some(Duration::from_secs(2)));
+        assert!(slow.finality_eta("batch") > fast.finality_eta("batch"));
+        assert_eq!(slow.finality_eta("unknown"), None);
+
+        // An open challenge period dominates the confirmation wait
+        let eta = estimate_time_to_finality(Duration::from_secs(12), 4, 12, Duration::from_secs(3600));
+        assert_eq!(eta, Duration::from_secs(3600));
+    }
+
+    #[tokio::test]
+    async fn test_confirmations_faster_than_block_time_do_not_finalize() {
+        let engine = FinalityEngine::new(SettlementConfig {
+            challenge_period: Duration::ZERO,
+            ..SettlementConfig::default()
+        }).await.unwrap();
+        let submitted_at = SystemTime::now() - Duration::from_secs(60);
+        engine.track_l1_submission("batch".to_string(), "0xabc".to_string(), submitted_at).await.unwrap();
+
+        // 12 confirmations a minute after submission can't be real at 12s blocks
+        engine.update_l1_confirmation("0xabc".to_string(), 100, "0xdef".to_string(), 12).await.unwrap();
+        assert!(engine.check_finalized_batches().await.unwrap().is_empty());
+        assert!(engine.finality_eta("batch").unwrap() >= Duration::from_secs(80));
+
+        // Backdated past the depth time, the same count finalizes with the L1 details
+        engine.finality_tracker.write().pending_finality.get_mut("batch").unwrap().submitted_at =
+            SystemTime::now() - Duration::from_secs(3600);
+        let finalized = engine.check_finalized_batches().await.unwrap();
+        assert_eq!(finalized.len(), 1);
+        assert_eq!(finalized[0].l1_block_number, 100);
+        assert_eq!(finalized[0].l1_transaction_hash, "0xabc");
+        assert_eq!(engine.finality_eta("batch"), None);
+        assert!(engine.check_finalized_batches().await.unwrap().is_empty());
+    }
+}
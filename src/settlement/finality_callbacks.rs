@@ -0,0 +1,385 @@
+/*!
+Per-transaction finality callbacks
+
+Clients register a callback for a transaction instead of polling its status.
+When the batch containing the transaction finalizes, a `SettlementReceipt` is
+delivered to an in-process channel or POSTed to a webhook. Delivery is
+at-least-once: failed deliveries are retried with backoff, and a receipt
+whose retries are exhausted is kept and redelivered on the next finality
+check. Receivers should deduplicate on `transaction_id`. At most
+`max_undelivered` receipts are kept; past that the oldest is dropped.
+*/
+
+use crate::error::{BridgeError, NetworkError, Result};
+use crate::settlement::finality::{FinalityType, FinalizedBatch};
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// Proof that a transaction's batch finalized on L1
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SettlementReceipt {
+    pub transaction_id: Uuid,
+    pub batch_id: String,
+    pub l1_block_number: u64,
+    pub l1_transaction_hash: String,
+    pub finality_type: FinalityType,
+    pub confirmation_count: u32,
+    pub finalized_at: SystemTime,
+}
+
+impl SettlementReceipt {
+    fn new(transaction_id: Uuid, batch: &FinalizedBatch) -> Self {
+        Self {
+            transaction_id,
+            batch_id: batch.batch_id.clone(),
+            l1_block_number: batch.l1_block_number,
+            l1_transaction_hash: batch.l1_transaction_hash.clone(),
+            finality_type: batch.finality_type.clone(),
+            confirmation_count: batch.confirmation_count,
+            finalized_at: batch.finalized_at,
+        }
+    }
+}
+
+/// Where a transaction's receipt is delivered
+#[derive(Debug, Clone)]
+pub enum FinalityCallback {
+    /// In-process receiver
+    Channel(mpsc::UnboundedSender<SettlementReceipt>),
+    /// JSON receipt POSTed to the URL
+    Webhook { url: String },
+}
+
+/// Delivery retry settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FinalityCallbackConfig {
+    /// Attempts per finality check before the callback waits for the next one
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub webhook_timeout: Duration,
+    /// Receipts kept for redelivery; the oldest is dropped beyond this
+    #[serde(default = "FinalityCallbackConfig::default_max_undelivered")]
+    pub max_undelivered: usize,
+}
+
+impl FinalityCallbackConfig {
+    pub fn default_max_undelivered() -> usize {
+        10_000
+    }
+}
+
+impl Default for FinalityCallbackConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            webhook_timeout: Duration::from_secs(10),
+            max_undelivered: Self::default_max_undelivered(),
+        }
+    }
+}
+
+/// Sends receipts to webhook URLs
+#[async_trait]
+pub trait WebhookDelivery: Send + Sync {
+    async fn deliver(&self, url: &str, receipt: &SettlementReceipt) -> Result<()>;
+}
+
+/// Plain HTTP/1.1 webhook client; a 2xx response acknowledges the receipt
+pub struct HttpWebhookDelivery {
+    timeout: Duration,
+}
+
+impl HttpWebhookDelivery {
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+
+    async fn post(url: &str, body: &[u8]) -> Result<()> {
+        let rest = url.strip_prefix("http://")
+            .ok_or_else(|| webhook_error(url, "only http:// webhooks are supported"))?;
+        let (authority, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, "/"),
+        };
+        let address = if authority.contains(':') { authority.to_string() } else { format!("{}:80", authority) };
+
+        let mut stream = tokio::net::TcpStream::connect(&address).await
+            .map_err(|e| webhook_error(url, &e.to_string()))?;
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            path, authority, body.len(),
+        );
+        stream.write_all(request.as_bytes()).await.map_err(|e| webhook_error(url, &e.to_string()))?;
+        stream.write_all(body).await.map_err(|e| webhook_error(url, &e.to_string()))?;
+
+        let mut status_line = [0u8; 12];
+        stream.read_exact(&mut status_line).await.map_err(|e| webhook_error(url, &e.to_string()))?;
+        // "HTTP/1.1 2xx"
+        if status_line[9] == b'2' {
+            Ok(())
+        } else {
+            Err(webhook_error(url, &format!("unexpected response {}", String::from_utf8_lossy(&status_line))))
+        }
+    }
+}
+
+#[async_trait]
+impl WebhookDelivery for HttpWebhookDelivery {
+    async fn deliver(&self, url: &str, receipt: &SettlementReceipt) -> Result<()> {
+        let body = serde_json::to_vec(receipt).map_err(|e| BridgeError::Serialization(e.into()))?;
+        tokio::time::timeout(self.timeout, Self::post(url, &body)).await
+            .map_err(|_| BridgeError::Network(NetworkError::Timeout { duration_ms: self.timeout.as_millis() as u64 }))?
+    }
+}
+
+fn webhook_error(url: &str, reason: &str) -> BridgeError {
+    BridgeError::Network(NetworkError::ConnectionFailed {
+        endpoint: url.to_string(),
+        source: reason.to_string().into(),
+    })
+}
+
+/// Callback registrations and the batches their transactions settle in
+pub struct FinalityCallbacks {
+    config: FinalityCallbackConfig,
+    webhooks: Arc<dyn WebhookDelivery>,
+    registrations: RwLock<HashMap<Uuid, Vec<FinalityCallback>>>,
+    /// batch_id -> transactions in the batch
+    batches: RwLock<HashMap<String, Vec<Uuid>>>,
+    /// Receipts whose retries ran out, waiting for the next check
+    undelivered: Arc<RwLock<VecDeque<(FinalityCallback, SettlementReceipt)>>>,
+}
+
+impl FinalityCallbacks {
+    pub fn new(config: FinalityCallbackConfig) -> Self {
+        let webhooks = Arc::new(HttpWebhookDelivery::new(config.webhook_timeout));
+        Self {
+            config,
+            webhooks,
+            registrations: RwLock::new(HashMap::new()),
+            batches: RwLock::new(HashMap::new()),
+            undelivered: Arc::new(RwLock::new(VecDeque::new())),
+        }
+    }
+
+    /// Use a custom webhook client
+    pub fn with_webhook_delivery(mut self, webhooks: Arc<dyn WebhookDelivery>) -> Self {
+        self.webhooks = webhooks;
+        self
+    }
+
+    /// Deliver a receipt to `callback` once the transaction's batch finalizes
+    pub fn register(&self, transaction_id: Uuid, callback: FinalityCallback) {
+        self.registrations.write().entry(transaction_id).or_default().push(callback);
+    }
+
+    /// Record which transactions a batch contains
+    pub fn assign_batch(&self, batch_id: &str, transaction_ids: impl IntoIterator<Item = Uuid>) {
+        self.batches.write().insert(batch_id.to_string(), transaction_ids.into_iter().collect());
+    }
+
+    /// Callbacks not yet delivered
+    pub fn pending(&self) -> usize {
+        self.registrations.read().values().map(Vec::len).sum::<usize>() + self.undelivered.read().len()
+    }
+
+    /// Start delivering receipts for a finalized batch's transactions, skipping
+    /// transactions still held for elevated confirmations. Returns the number
+    /// of deliveries started.
+    pub fn notify(&self, batch: &FinalizedBatch) -> usize {
+        let Some(transaction_ids) = self.batches.read().get(&batch.batch_id).cloned() else {
+            return 0;
+        };
+
+        let mut started = 0;
+        for transaction_id in transaction_ids.iter().filter(|id| !batch.held_transactions.contains(id)) {
+            let Some(callbacks) = self.registrations.write().remove(transaction_id) else {
+                continue;
+            };
+            let receipt = SettlementReceipt::new(*transaction_id, batch);
+            for callback in callbacks {
+                started += 1;
+                self.spawn_delivery(callback, receipt.clone());
+            }
+        }
+
+        // Held transactions are delivered when the batch is checked again
+        if batch.held_transactions.is_empty() {
+            self.batches.write().remove(&batch.batch_id);
+        }
+        started
+    }
+
+    /// Retry receipts whose earlier deliveries ran out of attempts
+    pub fn redeliver(&self) -> usize {
+        let undelivered = std::mem::take(&mut *self.undelivered.write());
+        let count = undelivered.len();
+        for (callback, receipt) in undelivered {
+            self.spawn_delivery(callback, receipt);
+        }
+        count
+    }
+
+    fn spawn_delivery(&self, callback: FinalityCallback, receipt: SettlementReceipt) {
+        tokio::spawn(Self::deliver(
+            self.config.clone(),
+            self.webhooks.clone(),
+            self.undelivered.clone(),
+            callback,
+            receipt,
+        ));
+    }
+
+    async fn deliver(
+        config: FinalityCallbackConfig,
+        webhooks: Arc<dyn WebhookDelivery>,
+        undelivered: Arc<RwLock<VecDeque<(FinalityCallback, SettlementReceipt)>>>,
+        callback: FinalityCallback,
+        receipt: SettlementReceipt,
+    ) {
+        let mut backoff = config.initial_backoff;
+        for attempt in 1..=config.max_attempts.max(1) {
+            let result = match &callback {
+                FinalityCallback::Channel(sender) => match sender.send(receipt.clone()) {
+                    Ok(()) => Ok(()),
+                    Err(_) => {
+                        // Nobody is listening any more; retrying can't help
+                        debug!("Finality receiver for {} dropped", receipt.transaction_id);
+                        return;
+                    }
+                },
+                FinalityCallback::Webhook { url } => webhooks.deliver(url, &receipt).await,
+            };
+
+            match result {
+                Ok(()) => {
+                    debug!("Delivered settlement receipt for {} (attempt {})", receipt.transaction_id, attempt);
+                    return;
+                }
+                Err(e) if attempt < config.max_attempts => {
+                    debug!("Receipt delivery for {} failed (attempt {}): {}", receipt.transaction_id, attempt, e);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(config.max_backoff);
+                }
+                Err(e) => warn!("Receipt delivery for {} failed after {} attempts, will redeliver: {}",
+                                receipt.transaction_id, attempt, e),
+            }
+        }
+
+        let mut undelivered = undelivered.write();
+        if undelivered.len() >= config.max_undelivered {
+            let dropped = match undelivered.pop_front() {
+                Some((_, oldest)) => oldest.transaction_id,
+                None => receipt.transaction_id,
+            };
+            warn!("Undelivered receipt queue is full; dropping the receipt for {}", dropped);
+            if config.max_undelivered == 0 {
+                return;
+            }
+        }
+        undelivered.push_back((callback, receipt));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Fails the first `failures` deliveries
+    struct FlakyWebhook {
+        failures: u32,
+        attempts: AtomicU32,
+        delivered: mpsc::UnboundedSender<SettlementReceipt>,
+    }
+
+    #[async_trait]
+    impl WebhookDelivery for FlakyWebhook {
+        async fn deliver(&self, url: &str, receipt: &SettlementReceipt) -> Result<()> {
+            if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(webhook_error(url, "unavailable"));
+            }
+            self.delivered.send(receipt.clone()).unwrap();
+            Ok(())
+        }
+    }
+
+    fn finalized(batch_id: &str) -> FinalizedBatch {
+        FinalizedBatch {
+            batch_id: batch_id.to_string(),
+            l1_block_number: 100,
+            l1_transaction_hash: "0xabc".to_string(),
+            gas_used: 21_000,
+            finalized_at: SystemTime::now(),
+            finality_type: FinalityType::Economic,
+            confirmation_count: 12,
+            held_transactions: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_webhook_retried_until_delivered() {
+        let (sender, mut delivered) = mpsc::unbounded_channel();
+        let webhook = Arc::new(FlakyWebhook { failures: 2, attempts: AtomicU32::new(0), delivered: sender });
+        let config = FinalityCallbackConfig {
+            max_attempts: 2,
+            initial_backoff: Duration::from_millis(1),
+            ..FinalityCallbackConfig::default()
+        };
+        let callbacks = FinalityCallbacks::new(config).with_webhook_delivery(webhook.clone());
+
+        let transaction_id = Uuid::new_v4();
+        callbacks.register(transaction_id, FinalityCallback::Webhook { url: "http://client/hook".to_string() });
+        callbacks.assign_batch("batch-1", [transaction_id]);
+        assert_eq!(callbacks.notify(&finalized("batch-1")), 1);
+
+        // Both attempts fail; the receipt is kept for the next check
+        while callbacks.pending() == 0 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(webhook.attempts.load(Ordering::SeqCst), 2);
+
+        assert_eq!(callbacks.redeliver(), 1);
+        let receipt = delivered.recv().await.unwrap();
+        assert_eq!(receipt.transaction_id, transaction_id);
+        assert_eq!(receipt.l1_block_number, 100);
+        assert_eq!(callbacks.pending(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_undelivered_receipts_are_bounded() {
+        let (sender, _delivered) = mpsc::unbounded_channel();
+        let webhook = Arc::new(FlakyWebhook { failures: u32::MAX, attempts: AtomicU32::new(0), delivered: sender });
+        let config = FinalityCallbackConfig { max_attempts: 1, max_undelivered: 2, ..FinalityCallbackConfig::default() };
+        let callbacks = FinalityCallbacks::new(config).with_webhook_delivery(webhook);
+
+        let transaction_ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        for (index, transaction_id) in transaction_ids.iter().enumerate() {
+            callbacks.register(*transaction_id, FinalityCallback::Webhook { url: "http://client/hook".to_string() });
+            let batch_id = format!("batch-{}", index);
+            callbacks.assign_batch(&batch_id, [*transaction_id]);
+            callbacks.notify(&finalized(&batch_id));
+            while callbacks.pending() < (index + 1).min(2) {
+                tokio::task::yield_now().await;
+            }
+        }
+        // Give the last failed delivery time to evict the oldest receipt
+        while callbacks.undelivered.read().front().map(|(_, r)| r.transaction_id) == Some(transaction_ids[0]) {
+            tokio::task::yield_now().await;
+        }
+
+        let kept: Vec<Uuid> = callbacks.undelivered.read().iter().map(|(_, receipt)| receipt.transaction_id).collect();
+        assert_eq!(kept, transaction_ids[1..]);
+    }
+}
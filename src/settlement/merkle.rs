@@ -0,0 +1,239 @@
+/*!
+Binary Merkle trees for batch transactions and L2 state
+
+Every tree in settlement is built the same way, so one verifier checks them
+all:
+
+- Leaves are hashed as H(0x00 || leaf) and parents as H(0x01 || left || right),
+  so an interior node can never be passed off as a leaf.
+- A level with an odd node count pairs its last node with itself.
+- The root commits to the leaf count as H(0x02 || count as 8 big-endian bytes
+  || top node). Duplicating the last node makes `[a, b, c]` and `[a, b, c, c]`
+  share a top node; the count tells them apart.
+- The tree over no leaves has an all-zero root.
+
+An inclusion proof lists the sibling hashes from the leaf upwards, followed by
+the leaf count as 8 big-endian bytes. Callers that commit to several roots at
+once hash them under their own domain prefix from 0x03 up.
+*/
+
+use crate::error::{BridgeError, Result, SettlementError};
+
+/// Domain prefix of a leaf node hash
+const LEAF_DOMAIN: u8 = 0x00;
+/// Domain prefix of an interior node hash
+const NODE_DOMAIN: u8 = 0x01;
+/// Domain prefix of the root, committing to the leaf count
+const LEAF_COUNT_DOMAIN: u8 = 0x02;
+
+/// Binary Merkle tree over a list of leaves
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    root: Vec<u8>,
+    /// Every level from the leaves up to the root
+    levels: Vec<Vec<Vec<u8>>>,
+    depth: u32,
+    leaf_count: u64,
+}
+
+impl MerkleTree {
+    pub fn root(&self) -> &[u8] {
+        &self.root
+    }
+
+    /// Sibling hashes from leaf `tx_index` up to the top node, then the leaf
+    /// count; empty when the index is out of range
+    pub fn generate_inclusion_proof(&self, tx_index: usize) -> Vec<Vec<u8>> {
+        if tx_index as u64 >= self.leaf_count {
+            return Vec::new();
+        }
+
+        let mut index = tx_index;
+        let mut proof = Vec::with_capacity(self.depth as usize + 1);
+        for level in &self.levels[..self.levels.len() - 1] {
+            // The last node of an odd level is paired with itself
+            proof.push(level.get(index ^ 1).unwrap_or(&level[index]).clone());
+            index /= 2;
+        }
+        proof.push(self.leaf_count.to_be_bytes().to_vec());
+        proof
+    }
+}
+
+/// Hash functions for Merkle trees
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HasherType {
+    Keccak256,
+    Blake3,
+    /// Not available natively; trees using it fail to build
+    Poseidon,
+}
+
+impl HasherType {
+    /// Leaf node hash: H(0x00 || leaf)
+    pub fn hash_leaf(self, leaf: &[u8]) -> Result<Vec<u8>> {
+        self.hash(&[&[LEAF_DOMAIN], leaf])
+    }
+
+    /// Parent node hash: H(0x01 || left || right)
+    pub fn hash_pair(self, left: &[u8], right: &[u8]) -> Result<Vec<u8>> {
+        self.hash(&[&[NODE_DOMAIN], left, right])
+    }
+
+    /// Root over a top node: H(0x02 || leaf_count || top)
+    fn hash_root(self, leaf_count: u64, top: &[u8]) -> Result<Vec<u8>> {
+        self.hash(&[&[LEAF_COUNT_DOMAIN], &leaf_count.to_be_bytes(), top])
+    }
+
+    pub(crate) fn hash(self, parts: &[&[u8]]) -> Result<Vec<u8>> {
+        match self {
+            HasherType::Keccak256 => Ok(keccak(parts)),
+            HasherType::Blake3 => {
+                let mut hasher = blake3::Hasher::new();
+                for part in parts {
+                    hasher.update(part);
+                }
+                Ok(hasher.finalize().as_bytes().to_vec())
+            }
+            HasherType::Poseidon => Err(BridgeError::Settlement(SettlementError::BatchProcessingFailed(
+                "Poseidon Merkle hashing is not supported".to_string(),
+            ))),
+        }
+    }
+
+    /// Build a tree over `leaves` in the order given
+    pub fn build_tree(self, leaves: &[Vec<u8>]) -> Result<MerkleTree> {
+        if leaves.is_empty() {
+            return Ok(MerkleTree { root: vec![0; 32], levels: vec![Vec::new()], depth: 0, leaf_count: 0 });
+        }
+
+        let leaf_hashes = leaves.iter().map(|leaf| self.hash_leaf(leaf)).collect::<Result<Vec<_>>>()?;
+        let mut levels = vec![leaf_hashes];
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| self.hash_pair(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+                .collect::<Result<Vec<_>>>()?;
+            levels.push(next);
+        }
+
+        Ok(MerkleTree {
+            root: self.hash_root(leaves.len() as u64, &levels[levels.len() - 1][0])?,
+            depth: (levels.len() - 1) as u32,
+            leaf_count: leaves.len() as u64,
+            levels,
+        })
+    }
+
+    /// Root that `proof` hashes `leaf` at `index` up to; None if the hasher
+    /// is unavailable or the proof doesn't fit the tree shape its leaf count
+    /// implies
+    pub fn root_from_proof(self, leaf: &[u8], proof: &[Vec<u8>], index: usize) -> Option<Vec<u8>> {
+        let (leaf_count, siblings) = proof.split_last()?;
+        let leaf_count = u64::from_be_bytes(leaf_count.as_slice().try_into().ok()?);
+        let mut index = index as u64;
+        if index >= leaf_count {
+            return None;
+        }
+
+        let mut node = self.hash_leaf(leaf).ok()?;
+        let mut width = leaf_count;
+        let mut siblings = siblings.iter();
+        while width > 1 {
+            let sibling = siblings.next()?;
+            // The last node of an odd level can only be paired with itself
+            if index == width - 1 && width % 2 == 1 && *sibling != node {
+                return None;
+            }
+            node = if index % 2 == 0 {
+                self.hash_pair(&node, sibling)
+            } else {
+                self.hash_pair(sibling, &node)
+            }.ok()?;
+            index /= 2;
+            width = width.div_ceil(2);
+        }
+        if siblings.next().is_some() {
+            return None;
+        }
+        self.hash_root(leaf_count, &node).ok()
+    }
+
+    /// Whether `proof` places `leaf` at `index` under `root`
+    pub fn verify_inclusion(self, root: &[u8], leaf: &[u8], proof: &[Vec<u8>], index: usize) -> bool {
+        self.root_from_proof(leaf, proof, index).is_some_and(|node| node == root)
+    }
+}
+
+fn keccak(parts: &[&[u8]]) -> Vec<u8> {
+    use sha3::{Digest, Keccak256};
+    let mut hasher = Keccak256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASHER: HasherType = HasherType::Keccak256;
+
+    fn leaves(count: u8) -> Vec<Vec<u8>> {
+        (1..=count).map(|leaf| vec![leaf; 32]).collect()
+    }
+
+    fn root_of(leaves: &[Vec<u8>]) -> Vec<u8> {
+        HASHER.build_tree(leaves).unwrap().root
+    }
+
+    #[test]
+    fn test_merkle_tree_resists_padding_and_node_forgery() {
+        let leaves = leaves(3);
+        let root = root_of(&leaves);
+
+        // Repeating the last leaf changes the root, so no fourth leaf verifies
+        let repeated = [leaves.clone(), vec![leaves[2].clone()]].concat();
+        assert_ne!(root_of(&repeated), root);
+        let proof = HASHER.build_tree(&repeated).unwrap().generate_inclusion_proof(3);
+        assert!(!HASHER.verify_inclusion(&root, &leaves[2], &proof, 3));
+        let proof = HASHER.build_tree(&leaves).unwrap().generate_inclusion_proof(2);
+        assert!(!HASHER.verify_inclusion(&root, &leaves[2], &proof, 3));
+
+        // An interior node cannot be passed off as a leaf
+        let tree = HASHER.build_tree(&leaves).unwrap();
+        let interior = tree.levels[1][0].clone();
+        let proof = [tree.levels[1][1].clone(), 2u64.to_be_bytes().to_vec()];
+        assert!(!HASHER.verify_inclusion(&root, &interior, &proof, 0));
+    }
+
+    #[test]
+    fn test_odd_levels_duplicate_their_last_node_and_root_commits_leaf_count() {
+        let leaves = leaves(3);
+        let tree = HASHER.build_tree(&leaves).unwrap();
+
+        let hashed: Vec<_> = leaves.iter().map(|leaf| HASHER.hash_leaf(leaf).unwrap()).collect();
+        let top = HASHER.hash_pair(
+            &HASHER.hash_pair(&hashed[0], &hashed[1]).unwrap(),
+            &HASHER.hash_pair(&hashed[2], &hashed[2]).unwrap(),
+        ).unwrap();
+        assert_eq!(tree.root(), &HASHER.hash_root(3, &top).unwrap()[..]);
+
+        // The unpaired leaf proves with itself as its sibling, and only at its index
+        let proof = tree.generate_inclusion_proof(2);
+        assert_eq!(proof, vec![hashed[2].clone(), tree.levels[1][0].clone(), 3u64.to_be_bytes().to_vec()]);
+        assert!(HASHER.verify_inclusion(tree.root(), &leaves[2], &proof, 2));
+        assert!(!HASHER.verify_inclusion(tree.root(), &leaves[2], &proof, 3));
+
+        // Claiming a different leaf count or a foreign sibling for the unpaired leaf fails
+        let mut recounted = proof.clone();
+        *recounted.last_mut().unwrap() = 4u64.to_be_bytes().to_vec();
+        assert!(!HASHER.verify_inclusion(tree.root(), &leaves[2], &recounted, 2));
+        let mut swapped = proof.clone();
+        swapped[0] = hashed[1].clone();
+        assert!(!HASHER.verify_inclusion(tree.root(), &leaves[2], &swapped, 2));
+
+        assert!(HASHER.build_tree(&[]).unwrap().root().iter().all(|byte| *byte == 0));
+    }
+}
@@ -5,29 +5,63 @@ Implements optimistic rollups, ZK-proofs, and batched settlement to achieve
 50,000+ TPS target with secure finality on L1.
 */
 
-use crate::error::{BridgeError, Result};
-use crate::types::{Transaction, Address, U256, TokenAmount};
+use crate::error::{BridgeError, Result, SettlementError};
+use crate::types::{Transaction, TransactionHash, Address, ChainId, U256, TokenAmount};
 use crate::services::ServiceManager;
 use crate::economy::FeeCalculator;
-use crate::security::GuardianSecurity;
-use std::collections::{HashMap, VecDeque};
+use crate::bridge::{ChainAdapterRegistry, L1ChainClient, L1TransactionStatus};
+use crate::bridge::config::NetworkConfig;
+use crate::economy::fee_estimator::{FeeEstimatorConfig, FeeSuggestion, InclusionFeeEstimator};
+use crate::security::{GuardianSecurity, SignatureScheme};
+use crate::idgen::{IdGenerator, default_id_generator};
+use crate::clock::{ClockConfig, SkewTolerantClock};
+use crate::metrics::ProofAlertThresholds;
+use crate::telemetry::BatchTraces;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use tokio::sync::{RwLock, Semaphore};
-use tracing::{debug, error, info, instrument, warn};
+use tracing::{debug, error, info, info_span, instrument, warn, Instrument};
 use serde::{Deserialize, Serialize};
 
 pub mod optimistic;
 pub mod zk_proofs;
 pub mod batch_processor;
+pub mod dependency_graph;
 pub mod state_manager;
 pub mod finality;
+pub mod finality_callbacks;
+pub mod archive;
+pub mod contracts;
+pub mod proof_workers;
+pub mod dead_letter;
+pub mod proof_export;
+pub mod emergency_exit;
+pub mod merkle;
+pub mod state_tree;
+pub mod rejections;
+pub mod challenge_monitor;
 
 pub use optimistic::OptimisticRollup;
-pub use zk_proofs::ZKProofSystem;
-pub use batch_processor::BatchProcessor;
-pub use state_manager::{StateManager, StateUpdate};
-pub use finality::FinalityEngine;
+pub use zk_proofs::{OversizedInputPolicy, ProofGasModel, ZKProof, ZKProofSystem};
+pub use batch_processor::{BatchProcessor, ReplayResult};
+pub use dependency_graph::{AccessSet, DependencyGraph};
+pub use state_manager::{BatchChainGuard, BatchLink, BatchRootChain, ChainTip, StateManager, StateUpdate, GENESIS_STATE_ROOT};
+pub use finality::{estimate_time_to_finality, FinalityEngine};
+pub use finality_callbacks::{FinalityCallback, FinalityCallbackConfig, FinalityCallbacks, SettlementReceipt, WebhookDelivery};
+pub use archive::{BatchArchive, InMemoryBatchArchive};
+pub use contracts::{SettlementContract, SettlementContracts};
+pub use dead_letter::{DeadLetter, DeadLetterQueue};
+pub use proof_export::{verify_settlement_package, L1BatchCommitment, ProofVerifier, SettlementPackage};
+pub use emergency_exit::{EmergencyExit, EmergencyExitConfig, EmergencyWithdrawal};
+pub use state_tree::{verify_state_proof, BalanceProof};
+pub use rejections::{RejectionLogConfig, RejectionReason, RejectionTracker};
+pub use challenge_monitor::{BatchReexecutor, ChallengeDefense, ChallengeDefenseConfig, ChallengeMonitor, DefenseSubmitter, ReexecutionTrace, TraceStep};
+pub use proof_workers::{ProofWorker, QuicProofWorker, RemoteProofRequest, RemoteProofResponse};
+
+/// How often Guardian security scans for threats while the engine runs
+const SECURITY_SCAN_INTERVAL: Duration = Duration::from_secs(60);
 
 /// L2 Settlement Engine
 pub struct L2SettlementEngine {
@@ -44,6 +78,19 @@ pub struct L2SettlementEngine {
     services: Arc<ServiceManager>,
     fee_calculator: Arc<FeeCalculator>,
     security: Arc<GuardianSecurity>,
+    batch_archive: Arc<dyn BatchArchive>,
+    fee_estimator: Arc<InclusionFeeEstimator>,
+    id_generator: Arc<dyn IdGenerator>,
+    /// Decides which transactions are withdrawals for `settlement_priority`
+    chain_adapters: Arc<ChainAdapterRegistry>,
+    clock: Arc<SkewTolerantClock>,
+    emergency_exit: Arc<EmergencyExit>,
+    rejections: Arc<RejectionTracker>,
+    /// Trace contexts of submitted transactions, continued by batch spans
+    traces: Arc<BatchTraces>,
+    /// Settlement network client polled for confirmations of submitted
+    /// batches; without one, batches never gather confirmations
+    l1_client: Option<Arc<dyn L1ChainClient>>,
 }
 
 /// Settlement configuration
@@ -84,6 +131,267 @@ pub struct SettlementConfig {
 
     /// Priority fee for L1 transactions
     pub priority_fee: U256,
+
+    /// How long finalized batches stay in memory before being archived
+    pub finalized_batch_retention: Duration,
+
+    /// Most recent finalized batches always kept in memory
+    pub min_retained_finalized_batches: usize,
+
+    /// Maximum batches submitted to L1 per settlement cycle
+    pub max_submissions_per_cycle: usize,
+
+    /// Maximum submitted-but-not-finalized batches on L1
+    pub max_in_flight_submissions: usize,
+
+    /// Pending proofs that trigger an automatic aggregation (0 = disabled)
+    pub proof_aggregation_threshold: usize,
+
+    /// Report probabilistic finality before economic finality is reached
+    pub enable_probabilistic_finality: bool,
+
+    /// Confidence (0.0 to 1.0) at which a batch is probabilistically final
+    pub probabilistic_finality_threshold: f64,
+
+    /// How long out-of-order transactions wait for their nonce gap to fill (zero = strict ordering)
+    pub nonce_grace_period: Duration,
+
+    /// Maximum out-of-order transactions staged per sender
+    pub max_staged_nonces_per_sender: usize,
+
+    /// How long a transaction's content hash is remembered for replay detection
+    pub replay_window: Duration,
+
+    /// Maximum content hashes kept for replay detection
+    pub replay_cache_capacity: usize,
+
+    /// Maximum concurrent ZK proof generations (defaults to available parallelism)
+    pub max_concurrent_proof_generations: usize,
+
+    /// Per-circuit concurrency limits, applied within the global limit
+    pub circuit_proof_concurrency: HashMap<String, usize>,
+
+    /// Maximum queued and generating proof requests; submissions beyond it
+    /// are rejected or wait for a slot
+    pub proof_queue_capacity: usize,
+
+    /// How long a generated ZK proof stays valid before it must be regenerated
+    pub max_proof_age: Duration,
+
+    /// Initial L1 settlement contract; can be switched at runtime
+    pub settlement_contract: Address,
+
+    /// Proof failure rate and latency limits that raise an alert
+    pub proof_alert_thresholds: ProofAlertThresholds,
+
+    /// How transactions paying the same fee are ordered within a batch
+    pub tie_break_policy: TieBreakPolicy,
+
+    /// Whether withdrawals are batched ahead of other transactions under congestion
+    pub settlement_priority: SettlementPriority,
+
+    /// Generate proofs locally when the configured proof worker fails
+    pub local_proof_fallback: bool,
+
+    /// On-chain verification cost model behind proofs' gas estimates
+    pub proof_gas_model: ProofGasModel,
+
+    /// Priority fee suggestions for a target inclusion time
+    pub fee_estimator: FeeEstimatorConfig,
+
+    /// Retry policy for per-transaction finality callbacks
+    pub finality_callbacks: FinalityCallbackConfig,
+
+    /// Clock-skew tolerance applied to deadlines and challenge periods
+    pub clock: ClockConfig,
+
+    /// Transactions created longer ago than this are rejected (None = no deadline)
+    pub transaction_deadline: Option<Duration>,
+
+    /// Signature scheme required of transactions from each source network.
+    /// Senders with a registered signing key must use its scheme instead;
+    /// other senders from networks not listed are rejected.
+    pub signature_schemes: HashMap<ChainId, SignatureScheme>,
+
+    /// Revalidate batches against the latest L2 state just before execution
+    pub enable_batch_preflight: bool,
+
+    /// Maximum transactions kept in the dead-letter queue
+    pub dead_letter_capacity: usize,
+
+    /// Gas refunded for clearing a non-zero storage slot
+    pub storage_clear_refund: u64,
+
+    /// Refunds are capped at gas used divided by this quotient (EIP-3529 uses 5)
+    pub max_refund_quotient: u64,
+
+    /// When users may withdraw on L1 against the last finalized state root
+    pub emergency_exit: EmergencyExitConfig,
+
+    /// Seed mixed into hash tie-breaks; nodes that must build identical
+    /// batches share it (None = plain signing hash order)
+    pub ordering_seed: Option<u64>,
+
+    /// Signatures of the only contract methods L2 will execute, e.g.
+    /// `transfer(address,uint256)` (None = any method)
+    pub allowed_contract_methods: Option<Vec<String>>,
+
+    /// Keep a sender's transactions out of new batches while one of its
+    /// batches is executing, so concurrent batches cannot reorder its nonces
+    pub pin_senders_to_batch: bool,
+
+    /// Accept zero-amount transactions that carry calldata; zero-amount
+    /// transfers without calldata are always rejected
+    pub allow_zero_value_calls: bool,
+
+    /// Sampled structured logging of rejected submissions
+    pub rejection_log: RejectionLogConfig,
+
+    /// Automatic defense of challenges against batches this node proposed
+    pub challenge_defense: ChallengeDefenseConfig,
+
+    /// Recent batches whose prior state is kept so they can be replayed
+    /// for debugging and audit (0 = disabled)
+    pub replay_history: usize,
+
+    /// Whether batches too large for the state transition circuit are split
+    /// when assembled or rejected at proof generation
+    pub oversized_proof_inputs: OversizedInputPolicy,
+
+    /// Published trusted-setup ceremony output (JSON) to load verification
+    /// keys from; the built-in development setup is used when unset
+    pub trusted_setup_path: Option<PathBuf>,
+}
+
+/// Ordering between transactions paying the same effective fee
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TieBreakPolicy {
+    /// Earlier submission first, then lower signing hash
+    #[default]
+    SubmissionTimeThenHash,
+    /// Lower signing hash first, ignoring submission time
+    HashOnly,
+}
+
+impl TieBreakPolicy {
+    /// Compare two transactions for batch order: higher fee first, then the tie-break
+    pub fn compare(self, a: &Transaction, b: &Transaction) -> std::cmp::Ordering {
+        self.compare_with_seed(a, b, None)
+    }
+
+    /// Like `compare`, with final ties broken by the signing hash mixed with `seed`
+    pub fn compare_with_seed(self, a: &Transaction, b: &Transaction, seed: Option<u64>) -> std::cmp::Ordering {
+        self.batch_key(a, seed).cmp(&self.batch_key(b, seed))
+    }
+
+    /// Key that sorts transactions the way `compare_with_seed` does
+    fn batch_key(self, transaction: &Transaction, seed: Option<u64>) -> BatchKey {
+        let submitted_at = match self {
+            TieBreakPolicy::SubmissionTimeThenHash => Some(transaction.created_at),
+            TieBreakPolicy::HashOnly => None,
+        };
+        // U256 is big-endian, so byte order is numeric order
+        (std::cmp::Reverse(transaction.fee.total_value().0), submitted_at, Self::hash_key(transaction, seed))
+    }
+
+    fn hash_key(transaction: &Transaction, seed: Option<u64>) -> [u8; 32] {
+        let hash = transaction.hash().0;
+        match seed {
+            None => hash,
+            Some(seed) => {
+                use sha2::{Digest, Sha256};
+                let mut hasher = Sha256::new();
+                hasher.update(seed.to_be_bytes());
+                hasher.update(hash);
+                hasher.finalize().into()
+            }
+        }
+    }
+
+    /// Sort transactions into batch order, keeping each sender's transactions in nonce order
+    pub fn order(self, transactions: &mut [Transaction]) {
+        self.order_with_seed(transactions, None);
+    }
+
+    /// `order` using `compare_with_seed`
+    pub fn order_with_seed(self, transactions: &mut [Transaction], seed: Option<u64>) {
+        order_by_key(transactions, |tx| self.batch_key(tx, seed));
+    }
+}
+
+/// Higher fee first, then submission time if the policy uses it, then the seeded hash
+type BatchKey = (std::cmp::Reverse<[u8; 32]>, Option<chrono::DateTime<chrono::Utc>>, [u8; 32]);
+
+/// Sort transactions by `key`, computed once each, keeping each sender's
+/// transactions in nonce order. A transaction ranks at the best key among
+/// itself and the sender's later nonces, so a sender's earlier transactions
+/// move up with a better-ranked later one, and every prefix of the result
+/// holds a nonce prefix of each sender.
+fn order_by_key<K: Ord + Clone>(transactions: &mut [Transaction], key: impl Fn(&Transaction) -> K) {
+    let mut ranks: Vec<K> = transactions.iter().map(&key).collect();
+
+    let mut by_sender: HashMap<&Address, Vec<usize>> = HashMap::new();
+    for (index, tx) in transactions.iter().enumerate() {
+        by_sender.entry(&tx.from_address).or_default().push(index);
+    }
+    for mut indices in by_sender.into_values() {
+        indices.sort_by_key(|&i| std::cmp::Reverse(transactions[i].nonce));
+        let mut best: Option<K> = None;
+        for i in indices {
+            let rank = match best.take() {
+                Some(later) if later < ranks[i] => later,
+                _ => ranks[i].clone(),
+            };
+            ranks[i] = rank.clone();
+            best = Some(rank);
+        }
+    }
+
+    let mut order: Vec<usize> = (0..transactions.len()).collect();
+    order.sort_by(|&a, &b| ranks[a].cmp(&ranks[b]).then(transactions[a].nonce.cmp(&transactions[b].nonce)));
+    let ordered: Vec<Transaction> = order.into_iter().map(|i| transactions[i].clone()).collect();
+    transactions.clone_from_slice(&ordered);
+}
+
+/// Which transactions claim batch slots first when more are pending than fit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SettlementPriority {
+    /// Fee order only
+    #[default]
+    FeeOrder,
+    /// Withdrawals from GhostPlane ahead of deposits and L2 transfers, fee
+    /// order within each group
+    WithdrawalsFirst,
+}
+
+impl SettlementPriority {
+    /// Whether `transaction` falls behind the favoured group. A withdrawal
+    /// queued behind the sender's own deposit pulls that deposit forward
+    /// with it rather than jumping it.
+    fn deferred(self, transaction: &Transaction, adapters: &ChainAdapterRegistry) -> bool {
+        match self {
+            SettlementPriority::FeeOrder => false,
+            SettlementPriority::WithdrawalsFirst => !adapters.is_withdrawal(transaction),
+        }
+    }
+}
+
+/// Reject zero-amount transactions unless they are contract calls and those are allowed
+fn check_transaction_value(transaction: &Transaction, allow_zero_value_calls: bool) -> Result<()> {
+    if !transaction.amount.amount.is_zero() {
+        return Ok(());
+    }
+    let reason = if transaction.data.is_empty() {
+        "zero amount transfer without calldata"
+    } else if !allow_zero_value_calls {
+        "zero amount contract calls are disabled"
+    } else {
+        return Ok(());
+    };
+    Err(BridgeError::Settlement(SettlementError::ZeroValueTransaction {
+        transaction_id: transaction.id.to_string(),
+        reason: reason.to_string(),
+    }))
 }
 
 /// Transaction pool for pending transactions
@@ -93,8 +401,239 @@ struct TransactionPool {
     processing: HashMap<String, ProcessingTransaction>,
     priority_queue: Vec<Transaction>, // High priority transactions
     nonce_tracker: HashMap<Address, u64>,
+    /// Future-nonce transactions per sender, keyed by nonce
+    staged: HashMap<Address, BTreeMap<u64, StagedTransaction>>,
+    /// Content hashes of recently admitted transactions
+    recent_hashes: ReplayWindow,
     total_size: usize,
     last_cleanup: SystemTime,
+    /// When the last batch was assembled
+    last_batch_at: Option<SystemTime>,
+    /// Senders with transactions in a batch that is still executing
+    executing_senders: HashSet<Address>,
+    /// Senders frozen by a security lockdown; their transactions stay
+    /// pooled but are kept out of batches until it is released
+    quarantined_senders: HashSet<Address>,
+}
+
+/// Bounded set of recently seen transaction content hashes
+#[derive(Debug, Clone, Default)]
+struct ReplayWindow {
+    seen: HashSet<[u8; 32]>,
+    order: VecDeque<([u8; 32], SystemTime)>,
+}
+
+impl ReplayWindow {
+    fn contains(&self, hash: &[u8; 32]) -> bool {
+        self.seen.contains(hash)
+    }
+
+    fn insert(&mut self, hash: [u8; 32], now: SystemTime) {
+        if self.seen.insert(hash) {
+            self.order.push_back((hash, now));
+        }
+    }
+
+    /// Forget hashes older than `window` and the oldest beyond `capacity`
+    fn evict(&mut self, window: Duration, capacity: usize, now: SystemTime) {
+        while let Some(&(hash, seen_at)) = self.order.front() {
+            let expired = now.duration_since(seen_at).unwrap_or_default() >= window;
+            if !expired && self.order.len() <= capacity {
+                break;
+            }
+            self.order.pop_front();
+            self.seen.remove(&hash);
+        }
+    }
+}
+
+/// Out-of-order transaction waiting for the preceding nonces to arrive
+#[derive(Debug, Clone)]
+struct StagedTransaction {
+    transaction: Transaction,
+    high_priority: bool,
+    staged_at: SystemTime,
+}
+
+/// How a transaction was admitted to the pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NonceAdmission {
+    /// Queued for batching, along with `promoted` staged successors
+    Queued { promoted: usize },
+    /// Held until the nonce gap fills
+    Staged,
+}
+
+impl TransactionPool {
+    /// Queue a transaction if its nonce is next for the sender, or stage it if
+    /// it is slightly ahead and staging is enabled
+    fn admit(
+        &mut self,
+        transaction: Transaction,
+        high_priority: bool,
+        grace_period: Duration,
+        max_staged: usize,
+        now: SystemTime,
+    ) -> Result<NonceAdmission> {
+        let sender = transaction.from_address.clone();
+        let expected = self.nonce_tracker.get(&sender).copied().unwrap_or(0) + 1;
+
+        if transaction.nonce == expected {
+            self.enqueue(transaction, high_priority);
+            return Ok(NonceAdmission::Queued { promoted: self.promote_staged(&sender) });
+        }
+
+        let invalid = SettlementError::InvalidNonce { expected, actual: transaction.nonce };
+        let staged = self.staged.get(&sender);
+        let stageable = !grace_period.is_zero()
+            && transaction.nonce > expected
+            && transaction.nonce - expected <= max_staged as u64
+            && staged.map_or(0, |s| s.len()) < max_staged
+            && !staged.is_some_and(|s| s.contains_key(&transaction.nonce));
+        if !stageable {
+            return Err(BridgeError::Settlement(invalid));
+        }
+
+        self.staged.entry(sender).or_default().insert(transaction.nonce, StagedTransaction {
+            transaction,
+            high_priority,
+            staged_at: now,
+        });
+        Ok(NonceAdmission::Staged)
+    }
+
+    fn enqueue(&mut self, transaction: Transaction, high_priority: bool) {
+        self.nonce_tracker.insert(transaction.from_address.clone(), transaction.nonce);
+        if high_priority {
+            self.priority_queue.push(transaction);
+        } else {
+            self.pending.push_back(transaction);
+        }
+        self.total_size += 1;
+    }
+
+    /// Take the next batch. Selection and order depend only on the queued
+    /// transactions, `policy`, and `seed`, not on arrival order, so nodes
+    /// holding the same pool build the same batch.
+    ///
+    /// A sender's transactions are taken as a nonce-ordered prefix of what it
+    /// has queued. With `pin_senders`, senders whose previous batch is still
+    /// executing are skipped until `release_senders` is called for it.
+    /// `priority` picks which groups fill the batch first; a sender's
+    /// transactions still leave in nonce order. Quarantined senders are
+    /// always skipped.
+    fn take_batch(
+        &mut self,
+        policy: TieBreakPolicy,
+        priority: SettlementPriority,
+        adapters: &ChainAdapterRegistry,
+        seed: Option<u64>,
+        batch_size: usize,
+        pin_senders: bool,
+    ) -> Vec<Transaction> {
+        let executing = &self.executing_senders;
+        let quarantined = &self.quarantined_senders;
+        let ready = |tx: &Transaction| {
+            !quarantined.contains(&tx.from_address) && (!pin_senders || !executing.contains(&tx.from_address))
+        };
+
+        let (high, held): (Vec<_>, Vec<_>) = self.priority_queue.drain(..).partition(&ready);
+        self.priority_queue = held;
+        let (regular, held): (VecDeque<_>, VecDeque<_>) = self.pending.drain(..).partition(&ready);
+        self.pending = held;
+
+        // Rank both queues together: high priority first, then the priority
+        // group, then fee. A sender split across the queues keeps its nonce
+        // order, its high-priority transactions pulling the earlier ones along.
+        let high_ids: HashSet<uuid::Uuid> = high.iter().map(|tx| tx.id).collect();
+        let mut batch: Vec<_> = high.into_iter().chain(regular).collect();
+        order_by_key(&mut batch, |tx| {
+            (!high_ids.contains(&tx.id), priority.deferred(tx, adapters), policy.batch_key(tx, seed))
+        });
+
+        for tx in batch.split_off(batch.len().min(batch_size)) {
+            if high_ids.contains(&tx.id) {
+                self.priority_queue.push(tx);
+            } else {
+                self.pending.push_back(tx);
+            }
+        }
+
+        if pin_senders {
+            self.executing_senders.extend(batch.iter().map(|tx| tx.from_address.clone()));
+        }
+        self.total_size -= batch.len();
+        batch
+    }
+
+    /// Let the senders of a batch that finished executing into new batches
+    fn release_senders(&mut self, batch: &[Transaction]) {
+        for tx in batch {
+            self.executing_senders.remove(&tx.from_address);
+        }
+    }
+
+    /// Move staged transactions whose gap has filled into the pool
+    fn promote_staged(&mut self, sender: &Address) -> usize {
+        let mut promoted = 0;
+        loop {
+            let next = self.nonce_tracker.get(sender).copied().unwrap_or(0) + 1;
+            let Some(staged) = self.staged.get_mut(sender).and_then(|s| s.remove(&next)) else {
+                break;
+            };
+            self.enqueue(staged.transaction, staged.high_priority);
+            promoted += 1;
+        }
+
+        if self.staged.get(sender).is_some_and(|s| s.is_empty()) {
+            self.staged.remove(sender);
+        }
+        promoted
+    }
+
+    /// Drop staged transactions whose gap did not fill within the grace period
+    fn expire_staged(&mut self, grace_period: Duration, now: SystemTime) -> Vec<Transaction> {
+        let mut expired = Vec::new();
+        self.staged.retain(|_, staged| {
+            staged.retain(|_, entry| {
+                let waited = now.duration_since(entry.staged_at).unwrap_or_default();
+                if waited >= grace_period {
+                    expired.push(entry.transaction.clone());
+                    false
+                } else {
+                    true
+                }
+            });
+            !staged.is_empty()
+        });
+        expired
+    }
+
+    /// Reject a transaction whose content was already admitted within the replay window
+    fn check_replay(&mut self, transaction: &Transaction, config: &SettlementConfig, now: SystemTime) -> Result<()> {
+        self.recent_hashes.evict(config.replay_window, config.replay_cache_capacity, now);
+
+        let content_hash = transaction.content_hash();
+        if self.recent_hashes.contains(&content_hash.0) {
+            return Err(BridgeError::Settlement(SettlementError::DuplicateTransaction {
+                content_hash: content_hash.to_string(),
+            }));
+        }
+        Ok(())
+    }
+
+    /// Remember an admitted transaction for replay detection
+    fn record_seen(&mut self, transaction: &Transaction, config: &SettlementConfig, now: SystemTime) {
+        self.recent_hashes.insert(transaction.content_hash().0, now);
+        self.recent_hashes.evict(config.replay_window, config.replay_cache_capacity, now);
+    }
+
+    fn is_staged(&self, transaction_id: &str) -> bool {
+        self.staged
+            .values()
+            .flat_map(|staged| staged.values())
+            .any(|entry| entry.transaction.id.to_string() == transaction_id)
+    }
 }
 
 /// Transaction being processed
@@ -125,6 +664,60 @@ struct SettlementQueue {
     next_batch_id: u64,
 }
 
+impl SettlementQueue {
+    /// Reject a batch id already pending, submitted, or finalized
+    fn ensure_untracked(&self, batch_id: &str) -> Result<()> {
+        let tracked = self.submitted_batches.contains_key(batch_id)
+            || self.finalized_batches.contains_key(batch_id)
+            || self.pending_batches.iter().any(|batch| batch.batch_id == batch_id);
+        if tracked {
+            return Err(BridgeError::Settlement(SettlementError::DuplicateBatchId {
+                batch_id: batch_id.to_string(),
+            }));
+        }
+        Ok(())
+    }
+
+    /// Queue a batch for L1 submission
+    fn enqueue(&mut self, batch: SettlementBatch) -> Result<()> {
+        self.ensure_untracked(&batch.batch_id)?;
+        self.pending_batches.push_back(batch);
+        Ok(())
+    }
+
+    /// Track a batch submitted to L1 until it finalizes
+    fn track_submitted(&mut self, submitted: SubmittedBatch) -> Result<()> {
+        self.ensure_untracked(&submitted.batch.batch_id)?;
+        self.submitted_batches.insert(submitted.batch.batch_id.clone(), submitted);
+        Ok(())
+    }
+
+    /// Track a finalized batch; it must no longer be tracked as submitted
+    fn track_finalized(&mut self, finalized: FinalizedBatch) -> Result<()> {
+        self.ensure_untracked(&finalized.batch.batch_id)?;
+        self.finalized_batches.insert(finalized.batch.batch_id.clone(), finalized);
+        Ok(())
+    }
+
+    /// Put batches whose L1 submission failed back at the head of the queue,
+    /// in order; any that reached L1 regardless are already tracked and skipped
+    fn requeue(&mut self, batches: impl DoubleEndedIterator<Item = SettlementBatch>) {
+        for batch in batches.rev() {
+            if self.ensure_untracked(&batch.batch_id).is_ok() {
+                self.pending_batches.push_front(batch);
+            }
+        }
+    }
+
+    /// Pop pending batches that may be submitted now, bounded by the per-cycle
+    /// limit and the remaining in-flight capacity
+    fn take_submittable(&mut self, per_cycle: usize, max_in_flight: usize) -> Vec<SettlementBatch> {
+        let capacity = max_in_flight.saturating_sub(self.submitted_batches.len());
+        let count = per_cycle.min(capacity).min(self.pending_batches.len());
+        self.pending_batches.drain(..count).collect()
+    }
+}
+
 /// Batch ready for L1 settlement
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SettlementBatch {
@@ -132,6 +725,9 @@ pub struct SettlementBatch {
     pub transactions: Vec<Transaction>,
     pub state_root: Vec<u8>,
     pub previous_state_root: Vec<u8>,
+    /// Position in the chain of batch roots; the first batch is block 1
+    #[serde(default)]
+    pub block_number: u64,
     pub merkle_proof: Vec<u8>,
     pub zk_proof: Option<Vec<u8>>,
     pub created_at: SystemTime,
@@ -139,23 +735,38 @@ pub struct SettlementBatch {
     pub fee_paid: TokenAmount,
 }
 
+impl SettlementBatch {
+    /// Content-addressed batch id: the same transactions and resulting state
+    /// always get the same id, so a resubmitted batch is caught as a duplicate
+    pub fn content_id(transactions: &[Transaction], state_root: &[u8]) -> String {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        hasher.update(state_root);
+        for transaction in transactions {
+            hasher.update(transaction.hash().0);
+        }
+        format!("batch-{}", hex::encode(hasher.finalize()))
+    }
+}
+
 /// Batch submitted to L1
 #[derive(Debug, Clone)]
 struct SubmittedBatch {
     batch: SettlementBatch,
     l1_transaction_hash: String,
+    settlement_contract: SettlementContract,
     submitted_at: SystemTime,
-    confirmation_count: u32,
     challenge_period_end: SystemTime,
 }
 
 /// Finalized batch
 #[derive(Debug, Clone)]
-struct FinalizedBatch {
-    batch: SettlementBatch,
-    finalized_at: SystemTime,
-    l1_block_number: u64,
-    final_gas_used: u64,
+pub struct FinalizedBatch {
+    pub batch: SettlementBatch,
+    pub finalized_at: SystemTime,
+    pub l1_block_number: u64,
+    pub final_gas_used: u64,
 }
 
 /// Performance metrics
@@ -182,6 +793,38 @@ struct ThroughputMeasurement {
     tps: f64,
 }
 
+/// 0x-prefixed hex hash of an L1 transaction, as returned on submission
+fn parse_transaction_hash(hash: &str) -> Option<TransactionHash> {
+    let bytes = hex::decode(hash.strip_prefix("0x").unwrap_or(hash)).ok()?;
+    bytes.try_into().ok().map(TransactionHash)
+}
+
+/// Reject transactions past their deadline or timestamped in the future,
+/// allowing for clock skew between the submitting node and this one
+fn check_transaction_deadline(
+    transaction: &Transaction,
+    config: &SettlementConfig,
+    clock: &SkewTolerantClock,
+    now: SystemTime,
+) -> Result<()> {
+    let created_at = SystemTime::from(transaction.created_at);
+    if clock.is_in_future(created_at, now) {
+        return Err(BridgeError::Settlement(SettlementError::TimestampInFuture {
+            transaction_id: transaction.id.to_string(),
+        }));
+    }
+
+    if let Some(deadline) = config.transaction_deadline.map(|age| created_at + age) {
+        if clock.has_passed(deadline, now) {
+            return Err(BridgeError::Settlement(SettlementError::DeadlineExpired {
+                transaction_id: transaction.id.to_string(),
+                deadline: chrono::DateTime::<chrono::Utc>::from(deadline).to_rfc3339(),
+            }));
+        }
+    }
+    Ok(())
+}
+
 /// Settlement result
 #[derive(Debug, Clone)]
 pub struct SettlementResult {
@@ -202,10 +845,21 @@ pub enum SettlementStatus {
     BatchedForSettlement,
     SubmittedToL1,
     ChallengePhase,
+    /// Deep enough on L1 to be final with high probability, for clients that
+    /// accept that guarantee; economic finality is still pending
+    ProbabilisticallyFinal,
     Finalized,
     Failed(String),
 }
 
+/// Settlement status with the expected time until finality
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettlementProgress {
+    pub status: SettlementStatus,
+    /// None once settlement has failed
+    pub finality_eta: Option<Duration>,
+}
+
 impl Default for SettlementConfig {
     fn default() -> Self {
         Self {
@@ -221,6 +875,52 @@ impl Default for SettlementConfig {
             fraud_proof_window: Duration::from_secs(24 * 60 * 60), // 24 hours
             l1_gas_limit: 15_000_000, // 15M gas
             priority_fee: U256::from(2_000_000_000u64), // 2 Gwei
+            finalized_batch_retention: Duration::from_secs(24 * 60 * 60), // 24 hours
+            min_retained_finalized_batches: 100,
+            max_submissions_per_cycle: 5,
+            max_in_flight_submissions: 20,
+            proof_aggregation_threshold: 100,
+            enable_probabilistic_finality: true,
+            probabilistic_finality_threshold: 0.99, // 99% confidence
+            nonce_grace_period: Duration::from_secs(5),
+            max_staged_nonces_per_sender: 16,
+            replay_window: Duration::from_secs(10 * 60), // 10 minutes
+            replay_cache_capacity: 100_000,
+            max_concurrent_proof_generations: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(4),
+            circuit_proof_concurrency: HashMap::new(),
+            proof_queue_capacity: 1000,
+            max_proof_age: Duration::from_secs(24 * 60 * 60), // 24 hours
+            settlement_contract: Address([0u8; 20]),
+            proof_alert_thresholds: ProofAlertThresholds::default(),
+            tie_break_policy: TieBreakPolicy::default(),
+            settlement_priority: SettlementPriority::default(),
+            local_proof_fallback: true,
+            proof_gas_model: ProofGasModel::default(),
+            fee_estimator: FeeEstimatorConfig::default(),
+            finality_callbacks: FinalityCallbackConfig::default(),
+            clock: ClockConfig::default(),
+            transaction_deadline: Some(Duration::from_secs(60 * 60)), // 1 hour
+            signature_schemes: HashMap::from([
+                (ChainId::ETHEREUM, SignatureScheme::Secp256k1),
+                (ChainId::GHOSTCHAIN, SignatureScheme::Ed25519),
+                (ChainId::GHOSTPLANE, SignatureScheme::Ed25519),
+            ]),
+            enable_batch_preflight: true,
+            dead_letter_capacity: 10_000,
+            storage_clear_refund: 4_800,
+            max_refund_quotient: 5,
+            emergency_exit: EmergencyExitConfig::default(),
+            ordering_seed: None,
+            pin_senders_to_batch: true,
+            allow_zero_value_calls: true,
+            rejection_log: RejectionLogConfig::default(),
+            challenge_defense: ChallengeDefenseConfig::default(),
+            replay_history: 0,
+            oversized_proof_inputs: OversizedInputPolicy::default(),
+            trusted_setup_path: None,
+            allowed_contract_methods: None,
         }
     }
 }
@@ -239,9 +939,14 @@ impl L2SettlementEngine {
         // Initialize core components
         let optimistic_rollup = Arc::new(OptimisticRollup::new(config.clone()).await?);
         let zk_proof_system = Arc::new(ZKProofSystem::new(config.clone()).await?);
-        let batch_processor = Arc::new(BatchProcessor::new(config.clone()).await?);
         let state_manager = Arc::new(StateManager::new(config.clone()).await?);
+        let batch_processor = Arc::new(
+            BatchProcessor::new(config.clone()).await?
+                .with_balance_source(services.clone())
+                .with_batch_chain(state_manager.batch_chain())
+        );
         let finality_engine = Arc::new(FinalityEngine::new(config.clone()).await?);
+        finality_engine.challenge_monitor().set_reexecutor(batch_processor.clone());
 
         // Initialize data structures
         let transaction_pool = Arc::new(RwLock::new(TransactionPool {
@@ -249,8 +954,13 @@ impl L2SettlementEngine {
             processing: HashMap::new(),
             priority_queue: Vec::new(),
             nonce_tracker: HashMap::new(),
+            staged: HashMap::new(),
+            recent_hashes: ReplayWindow::default(),
             total_size: 0,
             last_cleanup: SystemTime::now(),
+            last_batch_at: None,
+            executing_senders: HashSet::new(),
+            quarantined_senders: HashSet::new(),
         }));
 
         let settlement_queue = Arc::new(RwLock::new(SettlementQueue {
@@ -274,6 +984,10 @@ impl L2SettlementEngine {
         }));
 
         let concurrency_limiter = Arc::new(Semaphore::new(config.max_concurrent_batches));
+        let fee_estimator = Arc::new(InclusionFeeEstimator::new(config.fee_estimator.clone()));
+        let clock = Arc::new(SkewTolerantClock::new(&config.clock));
+        let emergency_exit = Arc::new(EmergencyExit::new(config.emergency_exit.clone())?);
+        let rejections = Arc::new(RejectionTracker::new(config.rejection_log.clone()));
 
         Ok(Self {
             config,
@@ -289,13 +1003,114 @@ impl L2SettlementEngine {
             services,
             fee_calculator,
             security,
+            batch_archive: Arc::new(InMemoryBatchArchive::new()),
+            fee_estimator,
+            id_generator: default_id_generator(),
+            chain_adapters: Arc::new(ChainAdapterRegistry::new()),
+            clock,
+            emergency_exit,
+            rejections,
+            traces: Arc::new(BatchTraces::default()),
+            l1_client: None,
         })
     }
 
+    /// State manager whose chain of batch roots the engine's batches extend;
+    /// share it with `GhostBridge::with_state_manager` so both link to one chain
+    pub fn state_manager(&self) -> &Arc<StateManager> {
+        &self.state_manager
+    }
+
+    /// Continue the chain of batch roots from the latest finalized or
+    /// archived batch, so a restarted engine doesn't link back to genesis,
+    /// and restore that batch's root as the one emergency exits prove against
+    async fn restore_batch_chain(&self) -> Result<()> {
+        let finalized = self.settlement_queue.read().await.finalized_batches.values()
+            .max_by_key(|finalized| finalized.batch.block_number)
+            .map(|finalized| finalized.batch.clone());
+        let archived = self.batch_archive.latest().await?.map(|finalized| finalized.batch);
+
+        if let Some(latest) = finalized.into_iter().chain(archived).max_by_key(|batch| batch.block_number) {
+            self.emergency_exit.record_finalized_root(latest.state_root.clone());
+            self.state_manager.batch_chain().restore(ChainTip {
+                state_root: latest.state_root,
+                block_number: latest.block_number,
+            }).await;
+        }
+        Ok(())
+    }
+
+    /// Rejected submissions so far, by reason
+    pub fn rejection_counts(&self) -> BTreeMap<RejectionReason, u64> {
+        self.rejections.counts()
+    }
+
+    /// L2 downtime and finalized-root tracking for emergency exits
+    pub fn emergency_exit(&self) -> &Arc<EmergencyExit> {
+        &self.emergency_exit
+    }
+
+    /// Re-execute a recent batch against its recorded prior state and check
+    /// it reproduces the recorded state root (see `replay_history`)
+    pub async fn replay_batch(&self, batch_id: &str) -> Result<ReplayResult> {
+        self.batch_processor.replay_batch(batch_id).await
+    }
+
+    /// Proof of an account's balance in the current L2 state, for users to
+    /// keep in case they later need an emergency exit
+    pub async fn balance_proof(&self, address: &Address, token: &str) -> Option<BalanceProof> {
+        self.batch_processor.balance_proof(address, token).await
+    }
+
+    /// Withdraw on L1 against the last finalized state root while L2 is down
+    pub fn emergency_withdraw(&self, proof: &BalanceProof) -> Result<EmergencyWithdrawal> {
+        self.emergency_exit.withdraw(proof, self.clock.now())
+    }
+
+    /// Use a custom archive backend for pruned finalized batches
+    pub fn with_batch_archive(mut self, archive: Arc<dyn BatchArchive>) -> Self {
+        self.batch_archive = archive;
+        self
+    }
+
+    /// Use a custom id generator for batch ids (e.g. a seeded one in tests)
+    pub fn with_id_generator(mut self, id_generator: Arc<dyn IdGenerator>) -> Self {
+        self.id_generator = id_generator;
+        self
+    }
+
+    /// Chain adapters that decide which transactions are withdrawals, e.g.
+    /// the bridge's own registry
+    pub fn with_chain_adapters(mut self, chain_adapters: Arc<ChainAdapterRegistry>) -> Self {
+        self.chain_adapters = chain_adapters;
+        self
+    }
+
+    /// Network batches settle on, and a client to watch their confirmations
+    /// there; its configured block time paces finality
+    pub fn with_settlement_network(mut self, network: &NetworkConfig, client: Arc<dyn L1ChainClient>) -> Self {
+        self.finality_engine.set_l1_network(network);
+        self.l1_client = Some(client);
+        self
+    }
+
+    /// Generate batch proofs on an external worker, e.g. a `QuicProofWorker`
+    pub fn with_proof_worker(self, worker: Arc<dyn ProofWorker>) -> Self {
+        self.zk_proof_system.set_proof_worker(worker);
+        self
+    }
+
+    /// Dispute contract client used to defend this node's batches
+    pub fn with_defense_submitter(self, submitter: Arc<dyn DefenseSubmitter>) -> Self {
+        self.finality_engine.challenge_monitor().set_submitter(submitter);
+        self
+    }
+
     /// Start the settlement engine
     #[instrument(skip(self))]
     pub async fn start(&self) -> Result<()> {
         info!("Starting L2 settlement engine");
+        self.restore_batch_chain().await?;
 
         // Start background processing tasks
         self.start_batch_processor().await?;
@@ -303,6 +1118,7 @@ impl L2SettlementEngine {
         self.start_finality_monitor().await?;
         self.start_metrics_collector().await?;
         self.start_cleanup_task().await?;
+        self.start_security_monitor().await?;
 
         info!("L2 settlement engine started successfully");
         Ok(())
@@ -313,45 +1129,67 @@ impl L2SettlementEngine {
     pub async fn submit_transaction(&self, transaction: Transaction) -> Result<String> {
         debug!("Submitting transaction for settlement: {}", transaction.id);
 
+        let result = self.admit_transaction(&transaction).await;
+        match &result {
+            Ok(_) => self.traces.record_current(transaction.id),
+            Err(e) => {
+                self.rejections.record(&transaction, e, self.security.data_minimization());
+            }
+        }
+        result
+    }
+
+    async fn admit_transaction(&self, transaction: &Transaction) -> Result<String> {
         // Validate transaction
-        self.validate_transaction(&transaction).await?;
+        self.validate_transaction(transaction).await?;
+        check_transaction_deadline(transaction, &self.config, &self.clock, self.clock.now())?;
 
         // Security check
-        let security_result = self.security.security_check(&transaction).await?;
+        let security_result = self.security.security_check(transaction).await?;
         if !security_result.approved {
-            return Err(BridgeError::Settlement(format!(
-                "Transaction rejected by security: {:?}", security_result.violations
-            )));
+            return Err(BridgeError::Settlement(SettlementError::SecurityRejected {
+                transaction_id: transaction.id.to_string(),
+                violations: security_result.violations.join("; "),
+            }));
         }
 
+        // Determine priority
+        let is_high_priority = self.is_high_priority_transaction(transaction).await;
+
         // Add to transaction pool
         {
             let mut pool = self.transaction_pool.write().await;
 
             // Check pool capacity
             if pool.total_size >= self.config.max_pending_transactions {
-                return Err(BridgeError::Settlement("Transaction pool full".to_string()));
-            }
-
-            // Check nonce ordering
-            let expected_nonce = pool.nonce_tracker.get(&transaction.from_address).unwrap_or(&0) + 1;
-            if transaction.nonce != expected_nonce {
-                return Err(BridgeError::Settlement(format!(
-                    "Invalid nonce: expected {}, got {}", expected_nonce, transaction.nonce
-                )));
+                return Err(BridgeError::Settlement(SettlementError::PoolFull {
+                    capacity: self.config.max_pending_transactions,
+                }));
             }
 
-            // Determine priority
-            let is_high_priority = self.is_high_priority_transaction(&transaction).await;
-
-            if is_high_priority {
-                pool.priority_queue.push(transaction.clone());
-            } else {
-                pool.pending.push_back(transaction.clone());
+            // Reject replays of recently submitted content
+            let now = self.clock.monotonic_now();
+            pool.check_replay(transaction, &self.config, now)?;
+
+            // Queue in nonce order, staging transactions that arrive slightly early
+            let admission = pool.admit(
+                transaction.clone(),
+                is_high_priority,
+                self.config.nonce_grace_period,
+                self.config.max_staged_nonces_per_sender,
+                now,
+            )?;
+            pool.record_seen(transaction, &self.config, now);
+
+            match admission {
+                NonceAdmission::Queued { promoted } if promoted > 0 => {
+                    debug!("Promoted {} staged transactions from {}", promoted, transaction.from_address);
+                }
+                NonceAdmission::Staged => {
+                    debug!("Staged out-of-order transaction {} (nonce {})", transaction.id, transaction.nonce);
+                }
+                NonceAdmission::Queued { .. } => {}
             }
-
-            pool.nonce_tracker.insert(transaction.from_address.clone(), transaction.nonce);
-            pool.total_size += 1;
         }
 
         // Update metrics
@@ -379,7 +1217,8 @@ impl L2SettlementEngine {
 
             // Check if in pending queue
             if pool.pending.iter().any(|tx| tx.id == transaction_id) ||
-               pool.priority_queue.iter().any(|tx| tx.id == transaction_id) {
+               pool.priority_queue.iter().any(|tx| tx.id == transaction_id) ||
+               pool.is_staged(transaction_id) {
                 return Ok(SettlementStatus::Pending);
             }
         }
@@ -398,7 +1237,9 @@ impl L2SettlementEngine {
             // Check submitted batches
             for submitted in queue.submitted_batches.values() {
                 if submitted.batch.transactions.iter().any(|tx| tx.id == transaction_id) {
-                    if SystemTime::now() < submitted.challenge_period_end {
+                    if self.finality_engine.is_probabilistically_final(&submitted.batch.batch_id) {
+                        return Ok(SettlementStatus::ProbabilisticallyFinal);
+                    } else if !self.clock.has_passed(submitted.challenge_period_end, self.clock.now()) {
                         return Ok(SettlementStatus::ChallengePhase);
                     } else {
                         return Ok(SettlementStatus::SubmittedToL1);
@@ -414,15 +1255,112 @@ impl L2SettlementEngine {
             }
         }
 
+        // Check archived finalized batches
+        if self.batch_archive.find_transaction(transaction_id).await?.is_some() {
+            return Ok(SettlementStatus::Finalized);
+        }
+
         // Transaction not found
         Err(BridgeError::Settlement("Transaction not found".to_string()))
     }
 
+    /// Settlement status with an ETA to finality from the settlement network's block time
+    pub async fn get_settlement_progress(&self, transaction_id: &str) -> Result<SettlementProgress> {
+        let status = self.get_settlement_status(transaction_id).await?;
+
+        let finality_eta = match status {
+            SettlementStatus::Finalized => Some(Duration::ZERO),
+            SettlementStatus::Failed(_) => None,
+            _ => {
+                let queue = self.settlement_queue.read().await;
+                let on_l1 = queue.submitted_batches.values()
+                    .find(|submitted| submitted.batch.transactions.iter().any(|tx| tx.id.to_string() == transaction_id))
+                    .and_then(|submitted| self.finality_engine.finality_eta(&submitted.batch.batch_id));
+                // Not on L1 yet: every confirmation and the full challenge period are still ahead
+                on_l1.or_else(|| Some(finality::estimate_time_to_finality(
+                    self.finality_engine.l1_block_time(),
+                    0,
+                    self.finality_engine.required_confirmations(),
+                    self.config.challenge_period,
+                )))
+            }
+        };
+
+        Ok(SettlementProgress { status, finality_eta })
+    }
+
+    /// Get a finalized batch, falling back to the archive for pruned batches
+    pub async fn get_finalized_batch(&self, batch_id: &str) -> Result<Option<FinalizedBatch>> {
+        if let Some(batch) = self.settlement_queue.read().await.finalized_batches.get(batch_id) {
+            return Ok(Some(batch.clone()));
+        }
+
+        self.batch_archive.get_batch(batch_id).await
+    }
+
+    /// Export a finalized batch as a package external verifiers can check
+    /// with `verify_settlement_package`
+    pub async fn export_settlement_package(&self, batch_id: &str) -> Result<Option<SettlementPackage>> {
+        match self.get_finalized_batch(batch_id).await? {
+            Some(finalized) => SettlementPackage::from_finalized(&finalized).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Route new batches to an upgraded settlement contract; batches already
+    /// submitted keep finalizing against the contract they were sent to
+    pub fn switch_settlement_contract(&self, address: Address) -> SettlementContract {
+        self.optimistic_rollup.contracts().switch_to(address)
+    }
+
     /// Get current performance metrics
     pub async fn get_performance_metrics(&self) -> PerformanceMetrics {
         self.performance_metrics.read().await.clone()
     }
 
+    /// Priority fee likely to get a transaction into a batch within `target`
+    pub async fn suggest_priority_fee(&self, target: Duration) -> FeeSuggestion {
+        let pool = self.transaction_pool.read().await;
+        let pool_fees: Vec<U256> = pool.priority_queue.iter()
+            .chain(pool.pending.iter())
+            .map(|tx| tx.fee.total_value())
+            .collect();
+        self.fee_estimator.suggest(target, &pool_fees)
+    }
+
+    /// Transactions dropped from batches at pre-flight
+    pub fn dead_letters(&self) -> &Arc<DeadLetterQueue> {
+        self.batch_processor.dead_letters()
+    }
+
+    /// Compare the local clock with a trusted reference, e.g. the latest L1
+    /// block timestamp; drift beyond the skew tolerance is logged
+    pub fn observe_reference_time(&self, reference: SystemTime) -> Duration {
+        self.clock.observe_reference(reference)
+    }
+
+    /// Deliver a `SettlementReceipt` to `callback` once the batch containing
+    /// `transaction_id` finalizes
+    pub fn register_finality_callback(&self, transaction_id: uuid::Uuid, callback: FinalityCallback) {
+        self.finality_engine.callbacks().register(transaction_id, callback);
+    }
+
+    /// Hold `transaction_id` until its batch has at least `confirmations` L1
+    /// confirmations, e.g. for high-value transfers
+    pub fn require_transaction_confirmations(
+        &self,
+        batch_id: &str,
+        transaction_id: uuid::Uuid,
+        confirmations: u32,
+    ) -> Result<()> {
+        self.finality_engine.require_transaction_confirmations(batch_id, transaction_id, confirmations)
+    }
+
+    /// Whether `transaction_id` is final, including any elevated confirmation requirement
+    pub async fn is_transaction_finalized(&self, batch_id: &str, transaction_id: &uuid::Uuid) -> Result<bool> {
+        self.finality_engine.is_transaction_finalized(batch_id, transaction_id).await
+    }
+
     /// Get settlement statistics
     pub async fn get_settlement_statistics(&self) -> SettlementStatistics {
         let pool = self.transaction_pool.read().await;
@@ -460,9 +1398,7 @@ impl L2SettlementEngine {
 
     async fn validate_transaction(&self, transaction: &Transaction) -> Result<()> {
         // Basic validation
-        if transaction.amount.amount == U256::ZERO {
-            return Err(BridgeError::Settlement("Zero amount transaction".to_string()));
-        }
+        check_transaction_value(transaction, self.config.allow_zero_value_calls)?;
 
         if transaction.gas_limit == 0 {
             return Err(BridgeError::Settlement("Zero gas limit".to_string()));
@@ -546,6 +1482,7 @@ impl L2SettlementEngine {
                 if let Err(e) = engine.monitor_finality().await {
                     error!("Finality monitoring error: {}", e);
                 }
+                engine.probe_l2_health().await;
 
                 tokio::time::sleep(Duration::from_secs(30)).await;
             }
@@ -567,6 +1504,28 @@ impl L2SettlementEngine {
         Ok(())
     }
 
+    /// Scan for threats on an interval so critical ones lock down the
+    /// bridge without waiting for a caller
+    async fn start_security_monitor(&self) -> Result<()> {
+        if !self.security.real_time_monitoring() {
+            return Ok(());
+        }
+        let security = Arc::downgrade(&self.security);
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SECURITY_SCAN_INTERVAL);
+            loop {
+                interval.tick().await;
+                let Some(security) = security.upgrade() else { break };
+                if let Err(e) = security.monitor_activity().await {
+                    warn!("Security monitoring scan failed: {}", e);
+                }
+            }
+        });
+
+        Ok(())
+    }
+
     async fn start_cleanup_task(&self) -> Result<()> {
         let engine = Arc::downgrade(&Arc::new(self.clone()));
 
@@ -581,106 +1540,175 @@ impl L2SettlementEngine {
     }
 
     async fn process_pending_transactions(&self) -> Result<()> {
+        self.expire_staged_transactions().await;
+
         let _permit = self.concurrency_limiter.acquire().await.unwrap();
 
-        // Get transactions to process
+        // Keep batches small enough to prove unless oversized ones should fail loudly
+        let batch_size = match self.config.oversized_proof_inputs {
+            OversizedInputPolicy::Split => self.config.batch_size.min(self.zk_proof_system.max_batch_transactions().await?),
+            OversizedInputPolicy::Reject => self.config.batch_size,
+        };
+
+        // Get transactions to process, holding back senders under lockdown
+        let locked = self.security.locked_addresses().await;
         let transactions = {
             let mut pool = self.transaction_pool.write().await;
-            let mut batch_transactions = Vec::new();
-
-            // First, process high priority transactions
-            while let Some(tx) = pool.priority_queue.pop() {
-                batch_transactions.push(tx);
-                if batch_transactions.len() >= self.config.batch_size {
-                    break;
-                }
-            }
-
-            // Fill remaining slots with regular transactions
-            while batch_transactions.len() < self.config.batch_size {
-                if let Some(tx) = pool.pending.pop_front() {
-                    batch_transactions.push(tx);
-                } else {
-                    break;
-                }
-            }
+            pool.quarantined_senders = locked;
+            let batch_transactions = pool.take_batch(
+                self.config.tie_break_policy,
+                self.config.settlement_priority,
+                &self.chain_adapters,
+                self.config.ordering_seed,
+                batch_size,
+                self.config.pin_senders_to_batch,
+            );
 
             if batch_transactions.is_empty() {
                 return Ok(());
             }
 
-            // Move to processing
+            // Learn the inclusion rate from the batch cadence
+            let now = SystemTime::now();
+            if let Some(interval) = pool.last_batch_at.and_then(|last| now.duration_since(last).ok()) {
+                self.fee_estimator.record_batch(batch_transactions.len(), interval);
+            }
+            pool.last_batch_at = Some(now);
+
+            // Move to processing under a provisional id until the batch's content id is known
+            let batch_id = self.id_generator.next_id("batch");
             for tx in &batch_transactions {
                 pool.processing.insert(tx.id.to_string(), ProcessingTransaction {
                     transaction: tx.clone(),
-                    batch_id: format!("batch-{}", SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis()),
+                    batch_id: batch_id.clone(),
                     started_at: SystemTime::now(),
                     stage: ProcessingStage::Validation,
                 });
-                pool.total_size -= 1;
             }
 
             batch_transactions
         };
 
         if !transactions.is_empty() {
-            // Process the batch
-            self.batch_processor.process_batch(transactions).await?;
+            // Process the batch and queue it for L1 settlement; its senders
+            // may join new batches once execution is over, even if it failed
+            let span = self.traces.batch_span(info_span!("process_batch", transactions = transactions.len()), &transactions);
+            let result = self.batch_processor.process_batch(transactions.clone()).instrument(span).await;
+            self.transaction_pool.write().await.release_senders(&transactions);
+            let batch = match result {
+                Ok(batch) => batch,
+                Err(e) => {
+                    self.traces.forget(&transactions);
+                    return Err(e);
+                }
+            };
+            // Transactions left out of the batch never reach finality
+            let included: HashSet<uuid::Uuid> = batch.transactions.iter().map(|tx| tx.id).collect();
+            let dropped: Vec<Transaction> = transactions.into_iter()
+                .filter(|tx| !included.contains(&tx.id))
+                .collect();
+            self.traces.forget(&dropped);
+            {
+                let mut pool = self.transaction_pool.write().await;
+                for tx in &batch.transactions {
+                    if let Some(processing) = pool.processing.get_mut(&tx.id.to_string()) {
+                        processing.batch_id = batch.batch_id.clone();
+                    }
+                }
+            }
+            self.settlement_queue.write().await.enqueue(batch)?;
         }
 
         Ok(())
     }
 
     async fn process_settlement_queue(&self) -> Result<()> {
-        // Get pending batches
+        // Get pending batches, backing off while too many are in flight
         let batches_to_settle = {
             let mut queue = self.settlement_queue.write().await;
-            let mut batches = Vec::new();
-
-            while let Some(batch) = queue.pending_batches.pop_front() {
-                batches.push(batch);
-                if batches.len() >= 5 { // Process up to 5 batches at once
-                    break;
-                }
+            let batches = queue.take_submittable(
+                self.config.max_submissions_per_cycle,
+                self.config.max_in_flight_submissions,
+            );
+
+            if batches.is_empty() && !queue.pending_batches.is_empty() {
+                debug!("Deferring L1 submission: {} batches in flight (max {})",
+                       queue.submitted_batches.len(), self.config.max_in_flight_submissions);
             }
 
             batches
         };
 
-        for batch in batches_to_settle {
-            if let Err(e) = self.submit_batch_to_l1(batch).await {
-                error!("Failed to submit batch to L1: {}", e);
+        for (index, batch) in batches_to_settle.iter().enumerate() {
+            let span = self.traces.batch_span(info_span!("submit_batch_to_l1", batch_id = %batch.batch_id), &batch.transactions);
+            if let Err(e) = self.submit_batch_to_l1(batch.clone()).instrument(span).await {
+                // Later batches link to this one's root, so none may land
+                // before it does; retry them all in order next cycle
+                error!("Failed to submit batch {} to L1, retrying: {}", batch.batch_id, e);
+                self.settlement_queue.write().await.requeue(batches_to_settle[index..].iter().cloned());
+                break;
             }
         }
 
         Ok(())
     }
 
-    async fn submit_batch_to_l1(&self, batch: SettlementBatch) -> Result<()> {
+    async fn submit_batch_to_l1(&self, mut batch: SettlementBatch) -> Result<()> {
         debug!("Submitting batch {} to L1", batch.batch_id);
 
+        // Attach a fresh, verified proof; one that expired while the batch
+        // waited (e.g. across retries) is regenerated
+        let proof = self.zk_proof_system.proof_for_submission(&batch).await?;
+        batch.zk_proof = Some(bincode::serialize(&proof).map_err(|e| BridgeError::Serialization(e.into()))?);
+
         // Submit via optimistic rollup
         let l1_tx_hash = self.optimistic_rollup.submit_batch(&batch).await?;
+        self.finality_engine.challenge_monitor().record_proposal(&batch);
+        self.finality_engine.callbacks()
+            .assign_batch(&batch.batch_id, batch.transactions.iter().map(|tx| tx.id));
+        let settlement_contract = self.optimistic_rollup.contracts()
+            .contract_for_batch(&batch.batch_id)
+            .unwrap_or_else(|| self.optimistic_rollup.contracts().active());
 
         // Track submission
+        let submitted_at = SystemTime::now();
         {
             let mut queue = self.settlement_queue.write().await;
             let submitted_batch = SubmittedBatch {
                 batch: batch.clone(),
                 l1_transaction_hash: l1_tx_hash.clone(),
-                submitted_at: SystemTime::now(),
-                confirmation_count: 0,
-                challenge_period_end: SystemTime::now() + self.config.challenge_period,
+                settlement_contract,
+                submitted_at,
+                challenge_period_end: submitted_at + self.config.challenge_period,
             };
 
-            queue.submitted_batches.insert(batch.batch_id.clone(), submitted_batch);
+            queue.track_submitted(submitted_batch)?;
         }
+        self.finality_engine
+            .track_l1_submission(batch.batch_id.clone(), l1_tx_hash.clone(), submitted_at)
+            .await?;
 
         info!("Batch {} submitted to L1 with transaction hash: {}", batch.batch_id, l1_tx_hash);
+        self.queue_for_aggregation(proof);
         Ok(())
     }
 
+    /// Queue a submitted batch's proof for aggregation in the background,
+    /// so reaching the threshold never holds up the next submission
+    fn queue_for_aggregation(&self, proof: ZKProof) {
+        let zk_proof_system = self.zk_proof_system.clone();
+        tokio::spawn(async move {
+            match zk_proof_system.queue_for_aggregation(proof).await {
+                Ok(Some(aggregated)) => info!("Aggregated batch proofs into {}", aggregated.proof_id),
+                Ok(None) => {}
+                Err(e) => warn!("Failed to aggregate batch proofs: {}", e),
+            }
+        });
+    }
+
     async fn monitor_finality(&self) -> Result<()> {
+        self.poll_l1_confirmations().await;
+
         // Check submitted batches for finality
         let finalized_batches = self.finality_engine.check_finalized_batches().await?;
 
@@ -689,6 +1717,14 @@ impl L2SettlementEngine {
 
             for finalized_batch in finalized_batches {
                 if let Some(submitted) = queue.submitted_batches.remove(&finalized_batch.batch_id) {
+                    let _span = self.traces.batch_span(
+                        info_span!("finalize_batch", batch_id = %finalized_batch.batch_id),
+                        &submitted.batch.transactions,
+                    ).entered();
+                    self.traces.forget(&submitted.batch.transactions);
+                    self.optimistic_rollup.contracts().release_batch(&finalized_batch.batch_id);
+                    self.finality_engine.challenge_monitor().forget(&finalized_batch.batch_id);
+                    let state_root = submitted.batch.state_root.clone();
                     let finalized = FinalizedBatch {
                         batch: submitted.batch,
                         finalized_at: SystemTime::now(),
@@ -696,8 +1732,14 @@ impl L2SettlementEngine {
                         final_gas_used: finalized_batch.gas_used,
                     };
 
-                    queue.finalized_batches.insert(finalized_batch.batch_id.clone(), finalized);
-                    info!("Batch {} finalized at block {}", finalized_batch.batch_id, finalized_batch.l1_block_number);
+                    if let Err(e) = queue.track_finalized(finalized) {
+                        error!("Finalized batch {} not tracked: {}", finalized_batch.batch_id, e);
+                        continue;
+                    }
+                    self.emergency_exit.record_finalized_root(state_root);
+                    info!("Batch {} finalized at block {} on settlement contract v{}",
+                          finalized_batch.batch_id, finalized_batch.l1_block_number,
+                          submitted.settlement_contract.version);
                 }
             }
         }
@@ -705,6 +1747,70 @@ impl L2SettlementEngine {
         Ok(())
     }
 
+    /// Report how deep each submitted batch's L1 transaction is buried
+    async fn poll_l1_confirmations(&self) {
+        let Some(client) = &self.l1_client else {
+            return;
+        };
+        let head = match client.get_block_number().await {
+            Ok(head) => head,
+            Err(e) => {
+                warn!("Settlement network head unavailable, confirmations not updated: {}", e);
+                return;
+            }
+        };
+
+        for (batch_id, l1_tx_hash) in self.finality_engine.pending_submissions() {
+            let Some(hash) = parse_transaction_hash(&l1_tx_hash) else {
+                debug!("Batch {} has no L1 transaction hash to poll ({})", batch_id, l1_tx_hash);
+                continue;
+            };
+            match client.get_receipt(&hash).await {
+                Ok(L1TransactionStatus::Included(receipt)) => {
+                    let depth = head.saturating_sub(receipt.block_number).saturating_add(1);
+                    let confirmations = u32::try_from(depth).unwrap_or(u32::MAX);
+                    if let Err(e) = self.finality_engine.update_l1_confirmation(
+                        l1_tx_hash,
+                        receipt.block_number,
+                        format!("0x{}", hex::encode(receipt.block_hash)),
+                        confirmations,
+                    ).await {
+                        warn!("Confirmations for batch {} not recorded: {}", batch_id, e);
+                    }
+                }
+                Ok(L1TransactionStatus::Pending) => {}
+                Ok(L1TransactionStatus::Dropped) => {
+                    warn!("L1 submission {} of batch {} was dropped", l1_tx_hash, batch_id);
+                }
+                Err(e) => warn!("Receipt for batch {} unavailable: {}", batch_id, e),
+            }
+        }
+    }
+
+    /// Feed GhostPlane availability to the emergency exit tracker, and burn
+    /// balances already paid out on L1 once L2 is reachable again
+    async fn probe_l2_health(&self) {
+        let healthy = self.services.ping("GHOSTPLANE").await.is_ok();
+        self.emergency_exit.record_l2_health(healthy, self.clock.now());
+        if healthy {
+            self.burn_exited_balances().await;
+        }
+    }
+
+    async fn burn_exited_balances(&self) {
+        for withdrawal in self.emergency_exit.unburned() {
+            // Burning is idempotent per withdrawal, so if recording it fails
+            // the next probe burns again as a no-op and retries the record
+            if self.batch_processor.burn_exited(&withdrawal).await {
+                info!("Burned {} {} exited by {} on L1", withdrawal.amount, withdrawal.token, withdrawal.address);
+            }
+            if let Err(e) = self.emergency_exit.mark_burned(&withdrawal) {
+                error!("Burn of emergency exit for {} not recorded: {}", withdrawal.address, e);
+                return;
+            }
+        }
+    }
+
     async fn update_performance_metrics(&self) -> Result<()> {
         let mut metrics = self.performance_metrics.write().await;
         let now = SystemTime::now();
@@ -761,18 +1867,56 @@ impl L2SettlementEngine {
         // This is called when new transactions are added
     }
 
+    async fn expire_staged_transactions(&self) {
+        let expired = self.transaction_pool
+            .write()
+            .await
+            .expire_staged(self.config.nonce_grace_period, self.clock.monotonic_now());
+        if expired.is_empty() {
+            return;
+        }
+
+        for transaction in &expired {
+            warn!("Rejected transaction {} from {}: nonce gap before {} did not fill within {:?}",
+                  transaction.id, transaction.from_address, transaction.nonce, self.config.nonce_grace_period);
+        }
+        self.traces.forget(&expired);
+        self.performance_metrics.write().await.failed_transactions += expired.len() as u64;
+    }
+
     async fn cleanup_expired_data(&self) {
         // Cleanup expired transactions and old data
         let mut pool = self.transaction_pool.write().await;
         let now = SystemTime::now();
 
         // Remove old processing transactions
-        pool.processing.retain(|_, tx| {
-            now.duration_since(tx.started_at).unwrap_or_default() < Duration::from_secs(3600) // 1 hour
-        });
+        let stale: Vec<String> = pool.processing.iter()
+            .filter(|(_, tx)| now.duration_since(tx.started_at).unwrap_or_default() >= Duration::from_secs(3600)) // 1 hour
+            .map(|(id, _)| id.clone())
+            .collect();
+        let stale: Vec<Transaction> = stale.iter()
+            .filter_map(|id| pool.processing.remove(id))
+            .map(|processing| processing.transaction)
+            .collect();
+        self.traces.forget(&stale);
 
         // Update last cleanup time
         pool.last_cleanup = now;
+        drop(pool);
+
+        // Archive finalized batches past the retention window
+        let mut queue = self.settlement_queue.write().await;
+        match archive::prune_finalized_batches(
+            &mut queue.finalized_batches,
+            self.batch_archive.as_ref(),
+            self.config.finalized_batch_retention,
+            self.config.min_retained_finalized_batches,
+            now,
+        ).await {
+            Ok(pruned) if pruned > 0 => debug!("Archived {} finalized batches", pruned),
+            Ok(_) => {}
+            Err(e) => warn!("Failed to archive finalized batches: {}", e),
+        }
     }
 }
 
@@ -793,6 +1937,15 @@ impl Clone for L2SettlementEngine {
             services: self.services.clone(),
             fee_calculator: self.fee_calculator.clone(),
             security: self.security.clone(),
+            batch_archive: self.batch_archive.clone(),
+            fee_estimator: self.fee_estimator.clone(),
+            id_generator: self.id_generator.clone(),
+            chain_adapters: self.chain_adapters.clone(),
+            clock: self.clock.clone(),
+            emergency_exit: self.emergency_exit.clone(),
+            rejections: self.rejections.clone(),
+            traces: self.traces.clone(),
+            l1_client: self.l1_client.clone(),
         }
     }
 }
@@ -815,7 +1968,7 @@ pub struct SettlementStatistics {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::{TokenType, TokenAmount};
+    use crate::types::{fixtures, Network, TokenType};
 
     #[tokio::test]
     async fn test_settlement_engine_creation() {
@@ -833,4 +1986,768 @@ mod tests {
         assert_eq!(config.batch_size, 1000);
         assert_eq!(config.max_concurrent_batches, 20);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_in_flight_cap_defers_submissions() {
+        let mut queue = SettlementQueue {
+            pending_batches: (0..10).map(|i| fixtures::batch(&format!("batch-{}", i))).collect(),
+            submitted_batches: HashMap::new(),
+            finalized_batches: HashMap::new(),
+            next_batch_id: 1,
+        };
+
+        let submit = |queue: &mut SettlementQueue, batches: Vec<SettlementBatch>| {
+            for batch in batches {
+                queue.track_submitted(submitted(batch)).unwrap();
+            }
+        };
+
+        // Per-cycle limit applies first
+        let batches = queue.take_submittable(3, 4);
+        assert_eq!(batches.len(), 3);
+        submit(&mut queue, batches);
+
+        // Only one slot of in-flight capacity left
+        let batches = queue.take_submittable(3, 4);
+        assert_eq!(batches.len(), 1);
+        submit(&mut queue, batches);
+
+        // At the cap: nothing more is submitted
+        assert!(queue.take_submittable(3, 4).is_empty());
+        assert_eq!(queue.pending_batches.len(), 6);
+
+        // Finalizing two batches frees capacity again
+        queue.submitted_batches.remove("batch-0");
+        queue.submitted_batches.remove("batch-1");
+        let batches = queue.take_submittable(3, 4);
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].batch_id, "batch-4");
+    }
+
+    #[test]
+    fn test_failed_submissions_requeued_in_order() {
+        let mut queue = SettlementQueue {
+            pending_batches: (0..4).map(|i| fixtures::batch(&format!("batch-{}", i))).collect(),
+            submitted_batches: HashMap::new(),
+            finalized_batches: HashMap::new(),
+            next_batch_id: 1,
+        };
+
+        // batch-0 lands, batch-1 fails, so batch-2 must wait behind it
+        let batches = queue.take_submittable(3, 10);
+        queue.track_submitted(submitted(batches[0].clone())).unwrap();
+        queue.requeue(batches.into_iter());
+
+        let pending: Vec<_> = queue.pending_batches.iter().map(|batch| batch.batch_id.as_str()).collect();
+        assert_eq!(pending, vec!["batch-1", "batch-2", "batch-3"]);
+    }
+
+    fn submitted(batch: SettlementBatch) -> SubmittedBatch {
+        SubmittedBatch {
+            batch,
+            l1_transaction_hash: String::new(),
+            settlement_contract: SettlementContract {
+                version: 1,
+                address: Address([0u8; 20]),
+                activated_at: SystemTime::now(),
+            },
+            submitted_at: SystemTime::now(),
+            challenge_period_end: SystemTime::now(),
+        }
+    }
+
+    #[test]
+    fn test_duplicate_batch_ids_rejected() {
+        let mut queue = SettlementQueue {
+            pending_batches: VecDeque::new(),
+            submitted_batches: HashMap::new(),
+            finalized_batches: HashMap::new(),
+            next_batch_id: 1,
+        };
+
+        queue.track_submitted(submitted(fixtures::batch("batch-1"))).unwrap();
+        let mut replacement = submitted(fixtures::batch("batch-1"));
+        replacement.l1_transaction_hash = "0xreplacement".to_string();
+        let error = queue.track_submitted(replacement).unwrap_err();
+        assert!(matches!(error, BridgeError::Settlement(SettlementError::DuplicateBatchId { .. })));
+
+        // The tracked batch keeps its finality tracking
+        assert_eq!(queue.submitted_batches.len(), 1);
+        assert_eq!(queue.submitted_batches["batch-1"].l1_transaction_hash, "");
+
+        // Pending ids collide with submitted and other pending ones too
+        assert!(queue.enqueue(fixtures::batch("batch-1")).is_err());
+        queue.enqueue(fixtures::batch("batch-2")).unwrap();
+        assert!(queue.enqueue(fixtures::batch("batch-2")).is_err());
+        assert_eq!(queue.pending_batches.len(), 1);
+
+        // Content-addressed ids only collide for identical content
+        let root = [7u8; 32];
+        assert_eq!(SettlementBatch::content_id(&[], &root), SettlementBatch::content_id(&[], &root));
+        assert_ne!(SettlementBatch::content_id(&[], &root), SettlementBatch::content_id(&[], &[8u8; 32]));
+    }
+
+    fn empty_pool() -> TransactionPool {
+        TransactionPool {
+            pending: VecDeque::new(),
+            processing: HashMap::new(),
+            priority_queue: Vec::new(),
+            nonce_tracker: HashMap::new(),
+            staged: HashMap::new(),
+            recent_hashes: ReplayWindow::default(),
+            total_size: 0,
+            last_cleanup: SystemTime::now(),
+            last_batch_at: None,
+            executing_senders: HashSet::new(),
+            quarantined_senders: HashSet::new(),
+        }
+    }
+
+    fn nonce_tx(sender: &Address, nonce: u64) -> Transaction {
+        Transaction {
+            to_chain: Network::Ethereum { chain_id: ChainId::ETHEREUM },
+            from_address: sender.clone(),
+            nonce,
+            ..fixtures::transfer(0, 2, 100)
+        }
+    }
+
+    #[test]
+    fn test_out_of_order_nonces_reconcile() {
+        let grace = Duration::from_secs(5);
+        let sender = Address([1u8; 20]);
+        let now = SystemTime::now();
+        let mut pool = empty_pool();
+
+        // Nonce 2 arrives before nonce 1 and is held back
+        let admission = pool.admit(nonce_tx(&sender, 2), false, grace, 16, now).unwrap();
+        assert_eq!(admission, NonceAdmission::Staged);
+        assert!(pool.pending.is_empty());
+
+        // Nonce 1 fills the gap and promotes nonce 2 behind it
+        let admission = pool.admit(nonce_tx(&sender, 1), false, grace, 16, now).unwrap();
+        assert_eq!(admission, NonceAdmission::Queued { promoted: 1 });
+
+        let nonces: Vec<u64> = pool.pending.iter().map(|tx| tx.nonce).collect();
+        assert_eq!(nonces, vec![1, 2]);
+        assert!(pool.staged.is_empty());
+        assert_eq!(pool.nonce_tracker[&sender], 2);
+        assert_eq!(pool.total_size, 2);
+    }
+
+    #[test]
+    fn test_nonce_gap_times_out() {
+        let grace = Duration::from_secs(5);
+        let sender = Address([1u8; 20]);
+        let start = SystemTime::now();
+        let mut pool = empty_pool();
+
+        assert_eq!(pool.admit(nonce_tx(&sender, 3), false, grace, 16, start).unwrap(), NonceAdmission::Staged);
+
+        // Still within the grace period
+        assert!(pool.expire_staged(grace, start + Duration::from_secs(1)).is_empty());
+
+        // Gap never filled: the staged transaction is rejected
+        let expired = pool.expire_staged(grace, start + Duration::from_secs(6));
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].nonce, 3);
+        assert!(pool.staged.is_empty());
+
+        // Later arrivals no longer promote the expired transaction
+        assert_eq!(
+            pool.admit(nonce_tx(&sender, 1), false, grace, 16, start).unwrap(),
+            NonceAdmission::Queued { promoted: 0 }
+        );
+        assert_eq!(pool.pending.len(), 1);
+    }
+
+    #[test]
+    fn test_nonce_staging_limits() {
+        let sender = Address([1u8; 20]);
+        let now = SystemTime::now();
+        let mut pool = empty_pool();
+
+        // Too far ahead of the expected nonce
+        assert!(pool.admit(nonce_tx(&sender, 10), false, Duration::from_secs(5), 4, now).is_err());
+
+        // Strict ordering when the grace period is zero
+        assert!(pool.admit(nonce_tx(&sender, 2), false, Duration::ZERO, 4, now).is_err());
+
+        // Stale nonces are always rejected
+        pool.admit(nonce_tx(&sender, 1), false, Duration::ZERO, 4, now).unwrap();
+        assert!(pool.admit(nonce_tx(&sender, 1), false, Duration::from_secs(5), 4, now).is_err());
+    }
+
+    #[test]
+    fn test_replayed_transaction_is_rejected() {
+        let config = SettlementConfig::default();
+        let sender = Address([1u8; 20]);
+        let now = SystemTime::now();
+        let mut pool = empty_pool();
+
+        let original = nonce_tx(&sender, 1);
+        pool.check_replay(&original, &config, now).unwrap();
+        pool.admit(original.clone(), false, config.nonce_grace_period, 16, now).unwrap();
+        pool.record_seen(&original, &config, now);
+
+        // Same content resubmitted, even under a fresh id, is a replay
+        let mut replay = original.clone();
+        replay.id = uuid::Uuid::new_v4();
+        let err = pool.check_replay(&replay, &config, now + Duration::from_secs(1)).unwrap_err();
+        assert!(matches!(err, BridgeError::Settlement(SettlementError::DuplicateTransaction { .. })));
+
+        // A different transaction from the same sender is accepted
+        let next = nonce_tx(&sender, 2);
+        pool.check_replay(&next, &config, now).unwrap();
+        pool.admit(next, false, config.nonce_grace_period, 16, now).unwrap();
+
+        // Outside the window the hash is forgotten
+        let later = now + config.replay_window;
+        assert!(pool.check_replay(&original, &config, later).is_ok());
+    }
+
+    #[test]
+    fn test_quarantined_senders_stay_pooled_until_released() {
+        let locked = Address([1u8; 20]);
+        let other = Address([3u8; 20]);
+        let adapters = ChainAdapterRegistry::new();
+        let mut pool = empty_pool();
+        pool.enqueue(nonce_tx(&locked, 1), true);
+        pool.enqueue(nonce_tx(&locked, 2), false);
+        pool.enqueue(nonce_tx(&other, 1), false);
+
+        pool.quarantined_senders = HashSet::from([locked.clone()]);
+        let batch = pool.take_batch(TieBreakPolicy::default(), SettlementPriority::default(), &adapters, None, 4, false);
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].from_address, other);
+        assert_eq!(pool.total_size, 2);
+
+        pool.quarantined_senders.clear();
+        let released = pool.take_batch(TieBreakPolicy::default(), SettlementPriority::default(), &adapters, None, 4, false);
+        assert_eq!(released.iter().map(|tx| tx.nonce).collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_batch_slots_go_to_sender_nonce_prefixes() {
+        let sender = Address([1u8; 20]);
+        let other = Address([3u8; 20]);
+        let adapters = ChainAdapterRegistry::new();
+        let paying = |address: &Address, nonce, fee: u64| {
+            let mut tx = nonce_tx(address, nonce);
+            tx.fee.gcc_fee.amount = U256::from(fee);
+            tx
+        };
+        let taken = |batch: &[Transaction]| batch.iter().map(|tx| (tx.from_address.0[0], tx.nonce)).collect::<Vec<_>>();
+
+        // The sender's best-paying transaction sits behind a cheap nonce, which
+        // it carries into the batch ahead of the other sender
+        let mut pool = empty_pool();
+        pool.enqueue(paying(&sender, 1, 1), false);
+        pool.enqueue(paying(&sender, 2, 500), false);
+        pool.enqueue(paying(&other, 1, 100), false);
+        let batch = pool.take_batch(TieBreakPolicy::default(), SettlementPriority::default(), &adapters, None, 2, false);
+        assert_eq!(taken(&batch), vec![(1, 1), (1, 2)]);
+
+        // Split across the queues, the high-priority nonce brings its
+        // predecessor along instead of leaving its slot empty
+        let mut pool = empty_pool();
+        pool.enqueue(paying(&sender, 1, 1), false);
+        pool.enqueue(paying(&sender, 2, 1), true);
+        pool.enqueue(paying(&other, 1, 100), true);
+        pool.enqueue(paying(&other, 2, 100), false);
+        let batch = pool.take_batch(TieBreakPolicy::default(), SettlementPriority::default(), &adapters, None, 3, false);
+        assert_eq!(taken(&batch), vec![(3, 1), (1, 1), (1, 2)]);
+        assert_eq!(pool.total_size, 1);
+    }
+
+    #[test]
+    fn test_sender_nonces_stay_ordered_across_concurrent_batches() {
+        let sender = Address([1u8; 20]);
+        let other = Address([3u8; 20]);
+        let adapters = ChainAdapterRegistry::new();
+        let mut pool = empty_pool();
+        for nonce in 1..=3 {
+            pool.enqueue(nonce_tx(&sender, nonce), false);
+        }
+        pool.enqueue(nonce_tx(&sender, 4), true);
+        pool.enqueue(nonce_tx(&other, 1), false);
+        let nonces = |batch: &[Transaction]| {
+            batch.iter().filter(|tx| tx.from_address == sender).map(|tx| tx.nonce).collect::<Vec<_>>()
+        };
+
+        // Nonce 4 is high priority but cannot run ahead of 2 and 3 at the boundary
+        let first = pool.take_batch(TieBreakPolicy::default(), SettlementPriority::default(), &adapters, None, 2, true);
+        assert!(!nonces(&first).contains(&4));
+
+        // While the first batch executes, its senders wait for it
+        let second = pool.take_batch(TieBreakPolicy::default(), SettlementPriority::default(), &adapters, None, 2, true);
+        assert!(second.iter().all(|tx| first.iter().all(|f| f.from_address != tx.from_address)));
+
+        pool.release_senders(&first);
+        pool.release_senders(&second);
+        let mut executed = nonces(&first);
+        executed.extend(nonces(&second));
+        loop {
+            let batch = pool.take_batch(TieBreakPolicy::default(), SettlementPriority::default(), &adapters, None, 2, true);
+            if batch.is_empty() {
+                break;
+            }
+            executed.extend(nonces(&batch));
+            pool.release_senders(&batch);
+        }
+        assert_eq!(executed, vec![1, 2, 3, 4]);
+        assert_eq!(pool.total_size, 0);
+    }
+
+    #[test]
+    fn test_zero_value_contract_calls_accepted() {
+        let sender = Address([1u8; 20]);
+        let mut call = nonce_tx(&sender, 1);
+        call.amount = TokenAmount::new(TokenType::Gcc, U256::ZERO);
+        call.data = vec![0xa9, 0x05, 0x9c, 0xbb, 0, 0, 0, 1];
+        assert!(check_transaction_value(&call, true).is_ok());
+        assert!(check_transaction_value(&call, false).is_err());
+
+        let empty_transfer = Transaction { data: vec![], ..call };
+        let error = check_transaction_value(&empty_transfer, true).unwrap_err();
+        assert!(matches!(error, BridgeError::Settlement(SettlementError::ZeroValueTransaction { .. })), "{}", error);
+
+        assert!(check_transaction_value(&nonce_tx(&sender, 2), false).is_ok());
+    }
+
+    #[test]
+    fn test_equal_fee_transactions_ordered_deterministically() {
+        let created_at = chrono::Utc::now();
+        let transaction = |sender: u8, fee: u64, offset_ms: i64| {
+            let mut transaction = fixtures::transfer(sender, 0xee, 10);
+            transaction.id = uuid::Uuid::from_u128(sender as u128);
+            transaction.fee.gcc_fee.amount = U256::from(fee);
+            transaction.nonce = 1;
+            transaction.created_at = created_at + chrono::Duration::milliseconds(offset_ms);
+            transaction
+        };
+
+        // Two equal-fee transactions submitted at the same instant
+        let a = transaction(1, 100, 0);
+        let b = transaction(2, 100, 0);
+        let expected = if a.hash().0 < b.hash().0 { [a.id, b.id] } else { [b.id, a.id] };
+
+        for policy in [TieBreakPolicy::SubmissionTimeThenHash, TieBreakPolicy::HashOnly] {
+            for run in 0..10 {
+                let mut batch = if run % 2 == 0 { vec![a.clone(), b.clone()] } else { vec![b.clone(), a.clone()] };
+                policy.order(&mut batch);
+                assert_eq!([batch[0].id, batch[1].id], expected, "{:?} run {}", policy, run);
+            }
+        }
+
+        // Fee wins over submission time, then earlier submission wins
+        let rich = transaction(3, 200, 50);
+        let early = transaction(4, 100, -50);
+        let mut batch = vec![a.clone(), early.clone(), rich.clone()];
+        TieBreakPolicy::SubmissionTimeThenHash.order(&mut batch);
+        assert_eq!(batch.iter().map(|tx| tx.id).collect::<Vec<_>>(), vec![rich.id, early.id, a.id]);
+    }
+
+    #[test]
+    fn test_deadline_accepts_within_clock_skew() {
+        let config = SettlementConfig {
+            clock: ClockConfig { max_skew: Duration::from_secs(30) },
+            transaction_deadline: Some(Duration::from_secs(60)),
+            ..SettlementConfig::default()
+        };
+        let clock = SkewTolerantClock::new(&config.clock);
+        let transaction = nonce_tx(&Address([1u8; 20]), 1);
+        let deadline = SystemTime::from(transaction.created_at) + Duration::from_secs(60);
+
+        // Just past the deadline, but within the skew tolerance
+        check_transaction_deadline(&transaction, &config, &clock, deadline + Duration::from_secs(10)).unwrap();
+
+        let err = check_transaction_deadline(&transaction, &config, &clock, deadline + Duration::from_secs(31)).unwrap_err();
+        assert!(matches!(err, BridgeError::Settlement(SettlementError::DeadlineExpired { .. })));
+
+        // Timestamps from a node running slightly ahead are fine; far ahead is not
+        let created_at = SystemTime::from(transaction.created_at);
+        check_transaction_deadline(&transaction, &config, &clock, created_at - Duration::from_secs(20)).unwrap();
+        let err = check_transaction_deadline(&transaction, &config, &clock, created_at - Duration::from_secs(60)).unwrap_err();
+        assert!(matches!(err, BridgeError::Settlement(SettlementError::TimestampInFuture { .. })));
+    }
+
+    #[test]
+    fn test_withdrawals_batched_ahead_of_deposits() {
+        let deposit = |sender: u8, nonce| {
+            let mut tx = nonce_tx(&Address([sender; 20]), nonce);
+            tx.from_chain = Network::Ethereum { chain_id: ChainId::ETHEREUM };
+            tx.to_chain = Network::GhostPlane { chain_id: ChainId::GHOSTPLANE };
+            tx.fee.gcc_fee = TokenAmount::new(TokenType::Gcc, U256::from(1_000));
+            tx
+        };
+        let fill = || {
+            let mut pool = empty_pool();
+            pool.enqueue(deposit(10, 1), false);
+            pool.enqueue(deposit(11, 1), false);
+            pool.enqueue(nonce_tx(&Address([20; 20]), 1), false);
+            pool.enqueue(nonce_tx(&Address([21; 20]), 1), false);
+            // A withdrawal queued behind its sender's own deposit
+            pool.enqueue(deposit(30, 1), false);
+            pool.enqueue(nonce_tx(&Address([30; 20]), 2), false);
+            pool
+        };
+        let adapters = ChainAdapterRegistry::new();
+        let is_withdrawal = |tx: &Transaction| adapters.is_withdrawal(tx);
+
+        // Fee order alone fills the batch with the better-paying deposits
+        let batch = fill().take_batch(TieBreakPolicy::default(), SettlementPriority::FeeOrder, &adapters, None, 3, false);
+        assert!(batch.iter().all(|tx| !is_withdrawal(tx)));
+
+        let mut pool = fill();
+        let batch = pool.take_batch(TieBreakPolicy::default(), SettlementPriority::WithdrawalsFirst, &adapters, None, 3, false);
+        let senders: Vec<u8> = batch.iter().map(|tx| tx.from_address.0[0]).collect();
+        assert!(senders.contains(&20) && senders.contains(&21));
+        assert!(!senders.contains(&10) && !senders.contains(&11));
+        // Sender 30's withdrawal pulls its earlier deposit ahead of the other deposits
+        let sender_30: Vec<u64> = batch.iter().filter(|tx| tx.from_address.0[0] == 30).map(|tx| tx.nonce).collect();
+        assert_eq!(sender_30, vec![1]);
+
+        // ...and leads the next batch itself
+        let next = pool.take_batch(TieBreakPolicy::default(), SettlementPriority::WithdrawalsFirst, &adapters, None, 4, false);
+        assert_eq!(next.len(), 3);
+        assert!(is_withdrawal(&next[0]));
+        assert_eq!((next[0].from_address.0[0], next[0].nonce), (30, 2));
+    }
+
+    #[test]
+    fn test_rejections_counted_by_reason() {
+        let config = SettlementConfig::default();
+        let tracker = RejectionTracker::new(RejectionLogConfig { enabled: true, sample_one_in: 2 });
+        let sender = Address([1u8; 20]);
+        let now = SystemTime::now();
+        let mut pool = empty_pool();
+
+        let first = nonce_tx(&sender, 1);
+        pool.check_replay(&first, &config, now).unwrap();
+        pool.admit(first.clone(), false, Duration::ZERO, 4, now).unwrap();
+        pool.record_seen(&first, &config, now);
+
+        // Bad nonce, replay, and zero value, each from the path that produces it
+        let stale = nonce_tx(&sender, 1);
+        let error = pool.admit(stale.clone(), false, Duration::ZERO, 4, now).unwrap_err();
+        assert_eq!(tracker.record(&stale, &error, true), RejectionReason::InvalidNonce);
+        let error = pool.check_replay(&first, &config, now).unwrap_err();
+        tracker.record(&first, &error, true);
+        let mut empty = nonce_tx(&sender, 2);
+        empty.amount.amount = U256::ZERO;
+        let error = check_transaction_value(&empty, true).unwrap_err();
+        tracker.record(&empty, &error, false);
+
+        let pool_full = BridgeError::Settlement(SettlementError::PoolFull { capacity: 1 });
+        tracker.record(&empty, &pool_full, true);
+        tracker.record(&empty, &pool_full, true);
+        let security = BridgeError::Settlement(SettlementError::SecurityRejected {
+            transaction_id: empty.id.to_string(),
+            violations: "Trust level 0 below threshold 7".to_string(),
+        });
+        tracker.record(&empty, &security, true);
+        tracker.record(&empty, &BridgeError::Internal("unexpected".to_string()), true);
+
+        let counts = tracker.counts();
+        assert_eq!(counts[&RejectionReason::InvalidNonce], 1);
+        assert_eq!(counts[&RejectionReason::Duplicate], 1);
+        assert_eq!(counts[&RejectionReason::ZeroValue], 1);
+        assert_eq!(counts[&RejectionReason::PoolFull], 2);
+        assert_eq!(counts[&RejectionReason::SecurityRejected], 1);
+        assert_eq!(counts[&RejectionReason::Invalid], 1);
+        assert!(!counts.contains_key(&RejectionReason::DeadlineExpired));
+
+        // Every second rejection is sampled into the log
+        assert_eq!(tracker.logged(), 3);
+    }
+
+    /// Formatted log output captured for assertions
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<parking_lot::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_transaction_records_redacted_rejections() {
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        let _default = tracing::subscriber::set_default(subscriber);
+
+        let security = GuardianSecurity::new(crate::security::GuardianConfig {
+            data_minimization: true,
+            ..Default::default()
+        }).await.unwrap();
+        let engine = L2SettlementEngine::new(
+            SettlementConfig {
+                rejection_log: RejectionLogConfig { enabled: true, sample_one_in: 1 },
+                ..SettlementConfig::default()
+            },
+            Arc::new(ServiceManager::new(crate::services::ServiceConfig::default())),
+            Arc::new(FeeCalculator::new().await.unwrap()),
+            Arc::new(security),
+        ).await.unwrap();
+
+        let empty = fixtures::transfer(1, 2, 0);
+        assert!(engine.submit_transaction(empty.clone()).await.is_err());
+        assert_eq!(engine.rejection_counts()[&RejectionReason::ZeroValue], 1);
+
+        // The sampled record keeps the reason but neither addresses nor the
+        // error text, which can embed them
+        let output = String::from_utf8(logs.0.lock().clone()).unwrap();
+        let record = output.lines().find(|line| line.contains("Transaction rejected")).unwrap();
+        assert!(record.contains("ZeroValue"), "{}", record);
+        assert!(!record.contains(&empty.from_address.to_string()), "{}", record);
+        assert!(!record.contains("moves no value"), "{}", record);
+    }
+
+    #[tokio::test]
+    async fn test_restart_continues_batch_chain_from_archive() {
+        let archive = Arc::new(InMemoryBatchArchive::new());
+        for block_number in [3, 5, 4] {
+            let batch = SettlementBatch {
+                state_root: vec![block_number as u8; 32],
+                block_number,
+                ..fixtures::batch(&format!("batch-{}", block_number))
+            };
+            archive.archive_batch(FinalizedBatch {
+                batch,
+                finalized_at: SystemTime::now(),
+                l1_block_number: 100,
+                final_gas_used: 0,
+            }).await.unwrap();
+        }
+
+        let engine = L2SettlementEngine::new(
+            SettlementConfig::default(),
+            Arc::new(ServiceManager::new(crate::services::ServiceConfig::default())),
+            Arc::new(FeeCalculator::new().await.unwrap()),
+            Arc::new(GuardianSecurity::new(crate::security::GuardianConfig::default()).await.unwrap()),
+        ).await.unwrap().with_batch_archive(archive);
+        engine.restore_batch_chain().await.unwrap();
+
+        let next = engine.state_manager().batch_chain().lock().await.next_link();
+        assert_eq!(next, BatchLink { previous_state_root: vec![5; 32], block_number: 6 });
+        assert_eq!(engine.emergency_exit().finalized_root(), Some(vec![5; 32]));
+    }
+
+    struct ReceiptClient {
+        head: u64,
+        included_at: u64,
+    }
+
+    #[async_trait::async_trait]
+    impl L1ChainClient for ReceiptClient {
+        fn name(&self) -> &str {
+            "receipts"
+        }
+
+        fn bridge_contract(&self) -> &Address {
+            static BRIDGE: Address = Address([0u8; 20]);
+            &BRIDGE
+        }
+
+        async fn find_deposit(&self, _transaction: &Transaction) -> Result<Option<TransactionHash>> {
+            Ok(None)
+        }
+
+        async fn deposit_events(&self, _from_block: u64, _to_block: u64) -> Result<Vec<crate::bridge::DepositEvent>> {
+            Ok(Vec::new())
+        }
+
+        async fn submit_transaction(&self, _transaction: &Transaction) -> Result<TransactionHash> {
+            Err(BridgeError::internal("not a payout client"))
+        }
+
+        async fn get_receipt(&self, tx_hash: &TransactionHash) -> Result<L1TransactionStatus> {
+            Ok(L1TransactionStatus::Included(crate::types::TransactionReceipt {
+                transaction_hash: tx_hash.clone(),
+                block_number: self.included_at,
+                block_hash: [9u8; 32],
+                transaction_index: 0,
+                gas_used: 0,
+                success: true,
+                logs: Vec::new(),
+            }))
+        }
+
+        async fn get_block_number(&self) -> Result<u64> {
+            Ok(self.head)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_l1_confirmations_drive_finality() {
+        let mut settlement_network = crate::bridge::config::BridgeConfig::default().networks[&ChainId::ETHEREUM].clone();
+        settlement_network.block_time_ms = 1_000;
+        let engine = L2SettlementEngine::new(
+            SettlementConfig { challenge_period: Duration::ZERO, ..SettlementConfig::default() },
+            Arc::new(ServiceManager::new(crate::services::ServiceConfig::default())),
+            Arc::new(FeeCalculator::new().await.unwrap()),
+            Arc::new(GuardianSecurity::new(crate::security::GuardianConfig::default()).await.unwrap()),
+        ).await.unwrap().with_settlement_network(
+            &settlement_network,
+            Arc::new(ReceiptClient { head: 111, included_at: 100 }),
+        );
+        assert_eq!(engine.finality_engine.l1_block_time(), Duration::from_secs(1));
+
+        let l1_tx_hash = format!("0x{}", hex::encode([7u8; 32]));
+        engine.finality_engine.track_l1_submission(
+            "batch".to_string(),
+            l1_tx_hash.clone(),
+            SystemTime::now() - Duration::from_secs(3600),
+        ).await.unwrap();
+        assert!(engine.finality_engine.finality_eta("batch").unwrap() > Duration::ZERO);
+        let high_value = uuid::Uuid::new_v4();
+        engine.require_transaction_confirmations("batch", high_value, 64).unwrap();
+
+        // Head 111 over inclusion at 100 is the 12 confirmations required
+        engine.poll_l1_confirmations().await;
+        assert_eq!(engine.finality_engine.finality_eta("batch"), Some(Duration::ZERO));
+        let finalized = engine.finality_engine.check_finalized_batches().await.unwrap();
+        assert_eq!(finalized.len(), 1);
+        assert_eq!(finalized[0].l1_block_number, 100);
+        assert_eq!(finalized[0].l1_transaction_hash, l1_tx_hash);
+        assert_eq!(finalized[0].held_transactions, vec![high_value]);
+        assert!(!engine.is_transaction_finalized("batch", &high_value).await.unwrap());
+    }
+
+    /// Export spans to memory for the rest of the test
+    #[cfg(feature = "otel")]
+    fn in_memory_tracing() -> (opentelemetry_sdk::testing::trace::InMemorySpanExporter, tracing::subscriber::DefaultGuard) {
+        use opentelemetry::trace::TracerProvider as _;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let exporter = opentelemetry_sdk::testing::trace::InMemorySpanExporter::default();
+        let provider = opentelemetry_sdk::trace::TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("ghostbridge")));
+        (exporter, tracing::subscriber::set_default(subscriber))
+    }
+
+    #[cfg(feature = "otel")]
+    #[tokio::test]
+    async fn test_engine_continues_bridge_traces_through_settlement_and_finality() {
+        let (exporter, _default) = in_memory_tracing();
+        let mut settlement_network = crate::bridge::config::BridgeConfig::default().networks[&ChainId::ETHEREUM].clone();
+        settlement_network.block_time_ms = 1;
+        let engine = L2SettlementEngine::new(
+            SettlementConfig {
+                challenge_period: Duration::ZERO,
+                clock: ClockConfig { max_skew: Duration::ZERO },
+                ..SettlementConfig::default()
+            },
+            Arc::new(ServiceManager::new(crate::services::ServiceConfig::default())),
+            Arc::new(FeeCalculator::new().await.unwrap()),
+            Arc::new(GuardianSecurity::new(crate::security::GuardianConfig::default()).await.unwrap()),
+        ).await.unwrap().with_settlement_network(
+            &settlement_network,
+            Arc::new(ReceiptClient { head: 111, included_at: 100 }),
+        );
+
+        // Each transaction is admitted under its own bridge request, which is
+        // where submit_transaction records its context
+        let batch = SettlementBatch {
+            transactions: vec![fixtures::transfer(1, 2, 10), fixtures::transfer(3, 4, 10)],
+            ..fixtures::batch("batch")
+        };
+        for transaction in &batch.transactions {
+            let _bridge = info_span!("process_bridge_transaction").entered();
+            engine.traces.record_current(transaction.id);
+        }
+
+        // Settlement and finality run later, outside any request
+        engine.settlement_queue.write().await.enqueue(batch).unwrap();
+        engine.process_settlement_queue().await.unwrap();
+        let (_, l1_tx_hash) = engine.finality_engine.pending_submissions().pop().unwrap();
+        engine.finality_engine.update_l1_confirmation(l1_tx_hash, 100, String::new(), 12).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        engine.monitor_finality().await.unwrap();
+        assert!(engine.traces.is_empty());
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let span = |name: &str| spans.iter().find(|span| span.name == name).unwrap();
+        let bridge = span("process_bridge_transaction");
+        for stage in [span("submit_batch_to_l1"), span("finalize_batch")] {
+            assert_eq!(stage.span_context.trace_id(), bridge.span_context.trace_id(), "{} left the trace", stage.name);
+            assert_eq!(stage.parent_span_id, bridge.span_context.span_id());
+            // The second transaction's trace is linked to the batch it joined
+            assert_eq!(stage.links.len(), 1);
+        }
+    }
+
+    #[cfg(feature = "otel")]
+    #[tokio::test]
+    async fn test_expired_transactions_drop_their_trace_context() {
+        let (_exporter, _default) = in_memory_tracing();
+        let engine = L2SettlementEngine::new(
+            SettlementConfig::default(),
+            Arc::new(ServiceManager::new(crate::services::ServiceConfig::default())),
+            Arc::new(FeeCalculator::new().await.unwrap()),
+            Arc::new(GuardianSecurity::new(crate::security::GuardianConfig::default()).await.unwrap()),
+        ).await.unwrap();
+
+        // Staged behind a nonce gap that never fills
+        let staged = nonce_tx(&Address([1u8; 20]), 3);
+        let long_ago = SystemTime::now() - Duration::from_secs(60);
+        engine.transaction_pool.write().await
+            .admit(staged.clone(), false, engine.config.nonce_grace_period, 16, long_ago)
+            .unwrap();
+        {
+            let _bridge = info_span!("process_bridge_transaction").entered();
+            engine.traces.record_current(staged.id);
+        }
+        assert_eq!(engine.traces.len(), 1);
+
+        engine.expire_staged_transactions().await;
+        assert!(engine.traces.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_submitted_batches_carry_proofs_and_trigger_aggregation() {
+        let engine = L2SettlementEngine::new(
+            SettlementConfig { proof_aggregation_threshold: 2, ..SettlementConfig::default() },
+            Arc::new(ServiceManager::new(crate::services::ServiceConfig::default())),
+            Arc::new(FeeCalculator::new().await.unwrap()),
+            Arc::new(GuardianSecurity::new(crate::security::GuardianConfig::default()).await.unwrap()),
+        ).await.unwrap();
+
+        for batch_id in ["batch-1", "batch-2"] {
+            engine.settlement_queue.write().await.enqueue(fixtures::batch(batch_id)).unwrap();
+            engine.process_settlement_queue().await.unwrap();
+        }
+
+        // Each submitted batch carries the proof it was submitted with
+        for submitted in engine.settlement_queue.read().await.submitted_batches.values() {
+            let encoded = submitted.batch.zk_proof.as_deref().expect("batch submitted without a proof");
+            let proof: ZKProof = bincode::deserialize(encoded).unwrap();
+            assert!(engine.zk_proof_system.verify_proof(&proof).await.unwrap());
+        }
+
+        // ...and the second reaches the aggregation threshold
+        let aggregated = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let Some(proof) = engine.zk_proof_system.aggregated_proofs().await.pop() {
+                    break proof;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await.unwrap();
+        assert_eq!(aggregated.proof_type, zk_proofs::ProofType::AggregatedProof);
+    }
+}
@@ -8,6 +8,7 @@ Batches transactions optimistically and allows challenges during dispute period.
 use crate::error::{BridgeError, Result};
 use crate::types::{Transaction, Address, U256};
 use crate::settlement::{SettlementConfig, SettlementBatch};
+use crate::settlement::contracts::SettlementContracts;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
@@ -23,6 +24,7 @@ pub struct OptimisticRollup {
     challenge_tracker: Arc<RwLock<ChallengeTracker>>,
     batch_history: Arc<RwLock<BatchHistory>>,
     validator_set: Arc<RwLock<ValidatorSet>>,
+    contracts: Arc<SettlementContracts>,
 }
 
 /// State management for optimistic rollup
@@ -400,6 +402,8 @@ impl OptimisticRollup {
             slash_conditions: Vec::new(),
         }));
 
+        let contracts = Arc::new(SettlementContracts::new(config.settlement_contract.clone()));
+
         Ok(Self {
             config,
             state_manager,
@@ -407,9 +411,15 @@ impl OptimisticRollup {
             challenge_tracker,
             batch_history,
             validator_set,
+            contracts,
         })
     }
 
+    /// Settlement contract versions and the batches in flight on each
+    pub fn contracts(&self) -> &Arc<SettlementContracts> {
+        &self.contracts
+    }
+
     /// Submit batch to L1 optimistically
     #[instrument(skip(self, batch))]
     pub async fn submit_batch(&self, batch: &SettlementBatch) -> Result<String> {
@@ -471,11 +481,13 @@ impl OptimisticRollup {
             *current_root = batch.state_root.clone();
         }
 
+        let contract = self.contracts.assign_batch(&batch.batch_id);
+
         // TODO: Submit to actual L1 contract
         let l1_tx_hash = format!("0x{}", hex::encode(&batch.state_root[..8]));
 
-        info!("Batch {} submitted optimistically with L1 transaction: {}",
-              batch.batch_id, l1_tx_hash);
+        info!("Batch {} submitted optimistically to settlement contract v{} with L1 transaction: {}",
+              batch.batch_id, contract.version, l1_tx_hash);
 
         Ok(l1_tx_hash)
     }
@@ -801,6 +813,7 @@ mod tests {
             }],
             state_root: vec![0; 32],
             previous_state_root: vec![0; 32],
+            block_number: 1,
             merkle_proof: vec![],
             zk_proof: None,
             created_at: SystemTime::now(),
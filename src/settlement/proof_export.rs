@@ -0,0 +1,294 @@
+/*!
+Settlement proof packages for external verifiers
+
+A `SettlementPackage` bundles everything needed to check a finalized batch
+without running a node: the previous and new state roots, the canonically
+encoded transactions, the Merkle root committed to on L1, and the batch's
+ZK proof if it has one.
+
+A package alone proves nothing: anyone can build a self-consistent one.
+`verify_settlement_package` therefore checks it against an
+`L1BatchCommitment` the verifier read from the settlement contract itself,
+recomputes the Merkle root and content-addressed batch id from the
+transactions, and has a `ProofVerifier` check the ZK proof, whose public
+inputs must match the package.
+*/
+
+use crate::canonical;
+use crate::error::{BridgeError, Result};
+use crate::settlement::batch_processor::{merkle_root, transaction_leaf};
+use crate::settlement::zk_proofs::{batch_public_inputs, ProofType, ZKProof, ZKProofSystem};
+use crate::settlement::{FinalizedBatch, SettlementBatch};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+use tracing::debug;
+
+/// Package format version
+pub const SETTLEMENT_PACKAGE_VERSION: u32 = 1;
+
+const ROOT_LEN: usize = 32;
+
+/// Self-contained, verifiable record of one finalized batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettlementPackage {
+    pub version: u32,
+    pub batch_id: String,
+    pub l1_block_number: u64,
+    pub finalized_at: SystemTime,
+    pub previous_state_root: Vec<u8>,
+    pub state_root: Vec<u8>,
+    pub gas_used: u64,
+    /// Transactions in batch order, canonically encoded
+    pub transactions: Vec<Vec<u8>>,
+    /// Merkle root over the transaction leaves
+    pub merkle_root: Vec<u8>,
+    pub zk_proof: Option<ZKProof>,
+}
+
+impl SettlementPackage {
+    /// Package a finalized batch; the batch's `zk_proof` holds a bincode-encoded `ZKProof`
+    pub fn from_finalized(finalized: &FinalizedBatch) -> Result<Self> {
+        let batch = &finalized.batch;
+        let zk_proof = batch.zk_proof.as_deref()
+            .map(bincode::deserialize::<ZKProof>)
+            .transpose()
+            .map_err(|e| BridgeError::Serialization(e.into()))?;
+
+        Ok(Self {
+            version: SETTLEMENT_PACKAGE_VERSION,
+            batch_id: batch.batch_id.clone(),
+            l1_block_number: finalized.l1_block_number,
+            finalized_at: finalized.finalized_at,
+            previous_state_root: batch.previous_state_root.clone(),
+            state_root: batch.state_root.clone(),
+            gas_used: batch.gas_used,
+            transactions: batch.transactions.iter().map(canonical::encode).collect(),
+            merkle_root: batch.merkle_proof.clone(),
+            zk_proof,
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| BridgeError::Serialization(e.into()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| BridgeError::Serialization(e.into()))
+    }
+}
+
+/// What the settlement contract recorded for a batch. Verifiers must read
+/// this from L1 themselves; taking it from the package would defeat the check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct L1BatchCommitment {
+    pub batch_id: String,
+    pub state_root: Vec<u8>,
+    pub merkle_root: Vec<u8>,
+    pub l1_block_number: u64,
+}
+
+/// Checks state transition proofs, e.g. with the settlement contract's
+/// verifying key
+#[async_trait]
+pub trait ProofVerifier: Send + Sync {
+    async fn verify_proof(&self, proof: &ZKProof) -> Result<bool>;
+}
+
+#[async_trait]
+impl ProofVerifier for ZKProofSystem {
+    async fn verify_proof(&self, proof: &ZKProof) -> Result<bool> {
+        ZKProofSystem::verify_proof(self, proof).await
+    }
+}
+
+/// Check a package against the batch commitment on L1 and, if it carries
+/// one, its ZK proof
+pub async fn verify_settlement_package(
+    package: &SettlementPackage,
+    commitment: &L1BatchCommitment,
+    verifier: &dyn ProofVerifier,
+) -> bool {
+    let reason = match package_error(package, commitment) {
+        Some(reason) => Some(reason),
+        None => match &package.zk_proof {
+            Some(proof) => match verifier.verify_proof(proof).await {
+                Ok(true) => None,
+                Ok(false) => Some("ZK proof is invalid".to_string()),
+                Err(e) => Some(format!("ZK proof could not be verified: {}", e)),
+            },
+            None => None,
+        },
+    };
+    match reason {
+        None => true,
+        Some(reason) => {
+            debug!("Settlement package {} failed verification: {}", package.batch_id, reason);
+            false
+        }
+    }
+}
+
+fn package_error(package: &SettlementPackage, commitment: &L1BatchCommitment) -> Option<String> {
+    let anchored = package.batch_id == commitment.batch_id
+        && package.state_root == commitment.state_root
+        && package.merkle_root == commitment.merkle_root
+        && package.l1_block_number == commitment.l1_block_number;
+    if !anchored {
+        return Some("package does not match the batch committed on L1".to_string());
+    }
+
+    if package.version != SETTLEMENT_PACKAGE_VERSION {
+        return Some(format!("unsupported package version {}", package.version));
+    }
+    if package.previous_state_root.len() != ROOT_LEN || package.state_root.len() != ROOT_LEN {
+        return Some("state roots must be 32 bytes".to_string());
+    }
+
+    let transactions = match package.transactions.iter()
+        .map(|bytes| canonical::decode(bytes))
+        .collect::<Result<Vec<_>>>()
+    {
+        Ok(transactions) => transactions,
+        Err(e) => return Some(format!("undecodable transaction: {}", e)),
+    };
+
+    let leaves: Vec<Vec<u8>> = transactions.iter().map(transaction_leaf).collect();
+    if merkle_root(&leaves) != package.merkle_root {
+        return Some("Merkle root does not match the transactions".to_string());
+    }
+
+    // The batch id commits to the transactions and the resulting state root
+    if SettlementBatch::content_id(&transactions, &package.state_root) != package.batch_id {
+        return Some("batch id does not match the state root and transactions".to_string());
+    }
+
+    if let Some(proof) = &package.zk_proof {
+        if proof.proof_type != ProofType::StateTransition {
+            return Some(format!("expected a state transition proof, got {:?}", proof.proof_type));
+        }
+        if proof.proof_data.is_empty() {
+            return Some("ZK proof has no proof data".to_string());
+        }
+        let expected = batch_public_inputs(
+            &package.previous_state_root,
+            &package.state_root,
+            transactions.len(),
+            package.gas_used,
+        );
+        if proof.public_inputs != expected {
+            return Some("ZK proof public inputs do not match the package".to_string());
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settlement::zk_proofs::{PrivacyLevel, ProofMetadata};
+    use crate::types::{fixtures, Transaction};
+    use std::time::Duration;
+
+    fn transaction(nonce: u64) -> Transaction {
+        Transaction { nonce, ..fixtures::transfer(1, 2, 1_000) }
+    }
+
+    fn finalized_batch() -> FinalizedBatch {
+        let transactions = vec![transaction(0), transaction(1)];
+        let state_root = vec![7u8; 32];
+        let previous_state_root = vec![6u8; 32];
+        let leaves: Vec<Vec<u8>> = transactions.iter().map(transaction_leaf).collect();
+        let proof = ZKProof {
+            proof_id: "proof-1".to_string(),
+            proof_type: ProofType::StateTransition,
+            proof_data: vec![1; 256],
+            public_inputs: batch_public_inputs(&previous_state_root, &state_root, transactions.len(), 42_000),
+            verification_key_id: "state_transition_vk".to_string(),
+            created_at: SystemTime::now(),
+            expires_at: None,
+            metadata: ProofMetadata {
+                circuit_name: "state_transition".to_string(),
+                proof_size: 256,
+                generation_time: Duration::from_millis(100),
+                verification_time: None,
+                gas_cost_estimate: 100_000,
+                privacy_level: PrivacyLevel::Pseudonymous,
+            },
+        };
+
+        FinalizedBatch {
+            batch: SettlementBatch {
+                batch_id: SettlementBatch::content_id(&transactions, &state_root),
+                merkle_proof: merkle_root(&leaves),
+                transactions,
+                state_root,
+                previous_state_root,
+                zk_proof: Some(bincode::serialize(&proof).unwrap()),
+                gas_used: 42_000,
+                ..fixtures::batch("")
+            },
+            finalized_at: SystemTime::now(),
+            l1_block_number: 1_000,
+            final_gas_used: 42_000,
+        }
+    }
+
+    struct FixedVerifier(bool);
+
+    #[async_trait]
+    impl ProofVerifier for FixedVerifier {
+        async fn verify_proof(&self, _proof: &ZKProof) -> Result<bool> {
+            Ok(self.0)
+        }
+    }
+
+    fn commitment_for(batch: &FinalizedBatch) -> L1BatchCommitment {
+        L1BatchCommitment {
+            batch_id: batch.batch.batch_id.clone(),
+            state_root: batch.batch.state_root.clone(),
+            merkle_root: batch.batch.merkle_proof.clone(),
+            l1_block_number: batch.l1_block_number,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exported_package_verifies_independently() {
+        let finalized = finalized_batch();
+        let commitment = commitment_for(&finalized);
+        let package = SettlementPackage::from_finalized(&finalized).unwrap();
+        assert!(package.zk_proof.is_some());
+
+        // Round-trip through JSON as an external auditor would receive it
+        let received = SettlementPackage::from_json(&package.to_json().unwrap()).unwrap();
+        assert!(verify_settlement_package(&received, &commitment, &FixedVerifier(true)).await);
+
+        let mut tampered = received.clone();
+        tampered.state_root[0] ^= 1;
+        assert!(!verify_settlement_package(&tampered, &commitment, &FixedVerifier(true)).await);
+
+        let mut dropped = received.clone();
+        dropped.transactions.pop();
+        assert!(!verify_settlement_package(&dropped, &commitment, &FixedVerifier(true)).await);
+
+        // Consistent public inputs do not stand in for a valid proof
+        assert!(!verify_settlement_package(&received, &commitment, &FixedVerifier(false)).await);
+    }
+
+    #[tokio::test]
+    async fn test_self_consistent_forgery_rejected_against_l1() {
+        let genuine = finalized_batch();
+        let commitment = commitment_for(&genuine);
+
+        // A forger rebuilds every derived field around a different state root
+        let mut forged = genuine.clone();
+        forged.batch.state_root = vec![9u8; 32];
+        forged.batch.batch_id = SettlementBatch::content_id(&forged.batch.transactions, &forged.batch.state_root);
+        forged.batch.zk_proof = None;
+        let package = SettlementPackage::from_finalized(&forged).unwrap();
+        assert!(verify_settlement_package(&package, &commitment_for(&forged), &FixedVerifier(true)).await);
+
+        assert!(!verify_settlement_package(&package, &commitment, &FixedVerifier(true)).await);
+    }
+}
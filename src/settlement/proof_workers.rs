@@ -0,0 +1,78 @@
+/*!
+Remote proof generation workers
+
+Proof generation can be offloaded to dedicated prover hardware. A
+`ProofWorker` receives the circuit id and inputs and returns the raw proof;
+`ZKProofSystem` wraps the result into a `ZKProof`, accepts it only once it
+verifies, and falls back to local generation when the worker fails, unless
+configured otherwise. `L2SettlementEngine::with_proof_worker` routes batch
+proofs through a worker.
+*/
+
+use crate::error::Result;
+use crate::settlement::zk_proofs::{ProofInputs, ProofType};
+use crate::transport::{EndpointClass, GQuicTransport, TrafficClass, WireEnvelope};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+/// Proof request sent to a worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteProofRequest {
+    pub request_id: String,
+    pub circuit_id: String,
+    pub proof_type: ProofType,
+    pub inputs: ProofInputs,
+}
+
+/// Proof returned by a worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteProofResponse {
+    pub proof_data: Vec<u8>,
+    pub generation_time: Duration,
+}
+
+/// Generates proofs outside the bridge process
+#[async_trait]
+pub trait ProofWorker: Send + Sync {
+    /// Name used in logs
+    fn name(&self) -> &str;
+
+    async fn prove(&self, request: &RemoteProofRequest) -> Result<RemoteProofResponse>;
+}
+
+/// Worker reached over the QUIC transport with versioned JSON request/response frames
+pub struct QuicProofWorker {
+    transport: Arc<GQuicTransport>,
+    endpoint: String,
+}
+
+impl QuicProofWorker {
+    /// Worker at `endpoint`, whose connections are kept open as a service's
+    pub fn new(transport: Arc<GQuicTransport>, endpoint: impl Into<String>) -> Self {
+        let endpoint = endpoint.into();
+        transport.set_endpoint_class(&endpoint, EndpointClass::Service);
+        Self { transport, endpoint }
+    }
+}
+
+#[async_trait]
+impl ProofWorker for QuicProofWorker {
+    fn name(&self) -> &str {
+        &self.endpoint
+    }
+
+    async fn prove(&self, request: &RemoteProofRequest) -> Result<RemoteProofResponse> {
+        let payload = WireEnvelope::new(request).encode()?;
+        let response = self.transport
+            .send_data_with_class(&self.endpoint, &payload, TrafficClass::Settlement)
+            .await?;
+        let response = WireEnvelope::<RemoteProofResponse>::decode(&response)?;
+        if let Some(deprecation) = &response.deprecation {
+            warn!("Proof worker {}: {}", self.endpoint, deprecation);
+        }
+        Ok(response.payload)
+    }
+}
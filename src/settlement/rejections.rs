@@ -0,0 +1,134 @@
+/*!
+Rejected transaction accounting
+
+Every transaction `submit_transaction` turns away is counted under a
+`RejectionReason`, giving operators an aggregate view of why submissions fail.
+Optionally, a sample of rejections is written as structured log records under
+the `ghostbridge::rejections` target for debugging spikes; with Guardian data
+minimization enabled, addresses and amounts are left out of those records.
+*/
+
+use crate::error::{BridgeError, SettlementError};
+use crate::types::Transaction;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::info;
+
+/// Why a submission was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum RejectionReason {
+    PoolFull,
+    InvalidNonce,
+    Duplicate,
+    SecurityRejected,
+    DeadlineExpired,
+    ZeroValue,
+    /// Any other validation failure
+    Invalid,
+}
+
+impl RejectionReason {
+    pub fn from_error(error: &BridgeError) -> Self {
+        match error {
+            BridgeError::Settlement(SettlementError::PoolFull { .. }) => RejectionReason::PoolFull,
+            BridgeError::Settlement(SettlementError::InvalidNonce { .. }) => RejectionReason::InvalidNonce,
+            BridgeError::Settlement(SettlementError::DuplicateTransaction { .. }) => RejectionReason::Duplicate,
+            BridgeError::Settlement(SettlementError::SecurityRejected { .. }) => RejectionReason::SecurityRejected,
+            BridgeError::Settlement(
+                SettlementError::DeadlineExpired { .. } | SettlementError::TimestampInFuture { .. },
+            ) => RejectionReason::DeadlineExpired,
+            BridgeError::Settlement(SettlementError::ZeroValueTransaction { .. }) => RejectionReason::ZeroValue,
+            _ => RejectionReason::Invalid,
+        }
+    }
+}
+
+/// Sampled structured logging of rejected transactions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectionLogConfig {
+    pub enabled: bool,
+    /// Log one in this many rejections (1 = every rejection)
+    pub sample_one_in: u64,
+}
+
+impl Default for RejectionLogConfig {
+    fn default() -> Self {
+        Self { enabled: false, sample_one_in: 100 }
+    }
+}
+
+#[derive(Debug, Default)]
+struct RejectionState {
+    counts: BTreeMap<RejectionReason, u64>,
+    total: u64,
+    logged: u64,
+}
+
+/// Rejection counters and the sampled rejection log
+#[derive(Debug)]
+pub struct RejectionTracker {
+    config: RejectionLogConfig,
+    state: Mutex<RejectionState>,
+}
+
+impl RejectionTracker {
+    pub fn new(config: RejectionLogConfig) -> Self {
+        Self { config, state: Mutex::new(RejectionState::default()) }
+    }
+
+    /// Count a rejection and log it if it is sampled; `redact` drops
+    /// addresses and amounts from the log record
+    pub fn record(&self, transaction: &Transaction, error: &BridgeError, redact: bool) -> RejectionReason {
+        let reason = RejectionReason::from_error(error);
+        let sampled = {
+            let mut state = self.state.lock();
+            *state.counts.entry(reason).or_default() += 1;
+            state.total += 1;
+            let sampled = self.config.enabled && state.total % self.config.sample_one_in.max(1) == 0;
+            if sampled {
+                state.logged += 1;
+            }
+            sampled
+        };
+
+        if sampled {
+            if redact {
+                info!(
+                    target: "ghostbridge::rejections",
+                    transaction_id = %transaction.id,
+                    reason = ?reason,
+                    from_chain = ?transaction.from_chain,
+                    to_chain = ?transaction.to_chain,
+                    nonce = transaction.nonce,
+                    "Transaction rejected"
+                );
+            } else {
+                info!(
+                    target: "ghostbridge::rejections",
+                    transaction_id = %transaction.id,
+                    reason = ?reason,
+                    from_chain = ?transaction.from_chain,
+                    to_chain = ?transaction.to_chain,
+                    nonce = transaction.nonce,
+                    from_address = %transaction.from_address,
+                    to_address = %transaction.to_address,
+                    amount = %transaction.amount.to_human_readable(),
+                    error = %error,
+                    "Transaction rejected"
+                );
+            }
+        }
+        reason
+    }
+
+    /// Rejections so far, by reason
+    pub fn counts(&self) -> BTreeMap<RejectionReason, u64> {
+        self.state.lock().counts.clone()
+    }
+
+    /// Rejections written to the sampled log
+    pub fn logged(&self) -> u64 {
+        self.state.lock().logged
+    }
+}
@@ -5,9 +5,10 @@ Handles state transitions, snapshots, and rollback capabilities for the
 high-performance settlement engine.
 */
 
-use crate::error::{BridgeError, Result};
+use crate::error::{BridgeError, Result, SettlementError};
 use crate::types::{Address, U256, Transaction};
 use crate::settlement::SettlementConfig;
+use crate::settlement::state_tree;
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
@@ -15,9 +16,94 @@ use tokio::sync::RwLock;
 use tracing::{debug, error, info, instrument, warn};
 use serde::{Deserialize, Serialize};
 
+/// State root of the empty chain, which the first batch builds on
+pub const GENESIS_STATE_ROOT: [u8; 32] = [0; 32];
+
+/// Root and block number of the latest batch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainTip {
+    pub state_root: Vec<u8>,
+    pub block_number: u64,
+}
+
+/// Where a new batch sits in the chain of roots
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchLink {
+    pub previous_state_root: Vec<u8>,
+    pub block_number: u64,
+}
+
+/// Chain of batch state roots: each batch records the root of the batch
+/// before it and the next block number. Builders append while their state
+/// transition is still exclusive, so concurrent batches link in the order
+/// the transitions were applied.
+#[derive(Debug)]
+pub struct BatchRootChain {
+    tip: tokio::sync::Mutex<ChainTip>,
+}
+
+impl BatchRootChain {
+    /// Empty chain at the genesis root and block 0
+    pub fn genesis() -> Self {
+        Self {
+            tip: tokio::sync::Mutex::new(ChainTip {
+                state_root: GENESIS_STATE_ROOT.to_vec(),
+                block_number: 0,
+            }),
+        }
+    }
+
+    /// Hold the tip until the next batch is appended or the guard is dropped
+    pub async fn lock(&self) -> BatchChainGuard<'_> {
+        BatchChainGuard { tip: self.tip.lock().await }
+    }
+
+    pub async fn tip(&self) -> ChainTip {
+        self.tip.lock().await.clone()
+    }
+
+    /// Continue from `tip` after a restart; a tip behind the current one is ignored
+    pub async fn restore(&self, tip: ChainTip) {
+        let mut current = self.tip.lock().await;
+        if tip.block_number > current.block_number {
+            info!("Batch chain restored at block {} (root 0x{})", tip.block_number, hex::encode(&tip.state_root));
+            *current = tip;
+        }
+    }
+}
+
+impl Default for BatchRootChain {
+    fn default() -> Self {
+        Self::genesis()
+    }
+}
+
+/// Exclusive hold on the chain tip; dropping it without appending leaves the chain unchanged
+pub struct BatchChainGuard<'a> {
+    tip: tokio::sync::MutexGuard<'a, ChainTip>,
+}
+
+impl BatchChainGuard<'_> {
+    /// Link the next batch will carry
+    pub fn next_link(&self) -> BatchLink {
+        BatchLink {
+            previous_state_root: self.tip.state_root.clone(),
+            block_number: self.tip.block_number + 1,
+        }
+    }
+
+    /// Make the batch with `state_root` the new tip
+    pub fn append(mut self, state_root: Vec<u8>) -> BatchLink {
+        let link = self.next_link();
+        *self.tip = ChainTip { state_root, block_number: link.block_number };
+        link
+    }
+}
+
 /// State manager for L2 settlement
 pub struct StateManager {
     config: SettlementConfig,
+    batch_chain: Arc<BatchRootChain>,
     current_state: Arc<RwLock<L2State>>,
     state_history: Arc<RwLock<StateHistory>>,
     snapshot_manager: SnapshotManager,
@@ -115,6 +201,7 @@ struct StateHistory {
 pub struct StateSnapshot {
     pub snapshot_id: String,
     pub block_number: u64,
+    /// Root of the snapshotted state, recomputed and checked on restore
     pub state_root: Vec<u8>,
     pub compressed_state: Vec<u8>,
     pub created_at: SystemTime,
@@ -343,6 +430,7 @@ impl StateManager {
 
         Ok(Self {
             config,
+            batch_chain: Arc::new(BatchRootChain::genesis()),
             current_state,
             state_history,
             snapshot_manager,
@@ -352,6 +440,11 @@ impl StateManager {
         })
     }
 
+    /// Chain of batch state roots built on this state
+    pub fn batch_chain(&self) -> Arc<BatchRootChain> {
+        self.batch_chain.clone()
+    }
+
     /// Apply state update
     #[instrument(skip(self, update))]
     pub async fn apply_state_update(&self, update: StateUpdate) -> Result<()> {
@@ -466,6 +559,7 @@ impl StateManager {
         };
 
         let compression_ratio = compressed_state.len() as f64 / serialized_state.len() as f64;
+        let size_bytes = compressed_state.len();
 
         let snapshot = StateSnapshot {
             snapshot_id: snapshot_id.clone(),
@@ -473,7 +567,7 @@ impl StateManager {
             state_root: state.state_root.clone(),
             compressed_state,
             created_at: SystemTime::now(),
-            size_bytes: compressed_state.len(),
+            size_bytes,
             compression_ratio,
         };
 
@@ -513,6 +607,9 @@ impl StateManager {
         // Deserialize state
         let restored_state: L2State = serde_json::from_slice(&serialized_state)?;
 
+        // Refuse to load data that doesn't hash to the stored root
+        Self::verify_restored_state(snapshot, &restored_state)?;
+
         // Replace current state
         {
             let mut current_state = self.current_state.write().await;
@@ -539,27 +636,14 @@ impl StateManager {
             ));
         }
 
-        // Find appropriate snapshot
-        let snapshot = {
-            let history = self.state_history.read().await;
-
-            // Find the latest snapshot <= target_block
-            let mut best_snapshot = None;
-            for (block_num, snapshot) in &history.snapshots {
-                if *block_num <= target_block {
-                    if best_snapshot.is_none() || *block_num > best_snapshot.unwrap().0 {
-                        best_snapshot = Some((*block_num, snapshot.clone()));
-                    }
-                }
-            }
-
-            best_snapshot.map(|(_, snapshot)| snapshot)
+        // Restore the latest intact snapshot <= target_block
+        let snapshot = match self.restore_latest_valid_snapshot(target_block).await {
+            Ok(snapshot) => Some(snapshot),
+            Err(BridgeError::Settlement(SettlementError::NoValidSnapshot { .. })) => None,
+            Err(e) => return Err(e),
         };
 
         if let Some(snapshot) = snapshot {
-            // Restore from snapshot
-            self.restore_from_snapshot(&snapshot).await?;
-
             // Create rollback point
             let rollback_point = RollbackPoint {
                 point_id: format!("rollback-{}", SystemTime::now()
@@ -586,6 +670,43 @@ impl StateManager {
         Ok(())
     }
 
+    /// Restore the newest snapshot at or before `max_block` whose data matches
+    /// its stored root, falling back to earlier snapshots past corrupted ones
+    #[instrument(skip(self))]
+    pub async fn restore_latest_valid_snapshot(&self, max_block: u64) -> Result<StateSnapshot> {
+        let mut candidates: Vec<StateSnapshot> = {
+            let history = self.state_history.read().await;
+            history.snapshots.values()
+                .filter(|snapshot| snapshot.block_number <= max_block)
+                .cloned()
+                .collect()
+        };
+        candidates.sort_by(|a, b| b.block_number.cmp(&a.block_number));
+
+        for snapshot in candidates {
+            match self.restore_from_snapshot(&snapshot).await {
+                Ok(()) => return Ok(snapshot),
+                Err(e) => error!("Skipping unusable snapshot {}: {}", snapshot.snapshot_id, e),
+            }
+        }
+
+        Err(BridgeError::Settlement(SettlementError::NoValidSnapshot { block_number: max_block }))
+    }
+
+    /// Recompute the state root over the restored balances, nonces and
+    /// storage, and check it against the root the snapshot was taken at
+    fn verify_restored_state(snapshot: &StateSnapshot, restored: &L2State) -> Result<()> {
+        let actual = state_tree::state_root(&restored.balances, &restored.nonces, &restored.storage);
+        if actual != snapshot.state_root || restored.state_root != snapshot.state_root {
+            return Err(BridgeError::Settlement(SettlementError::CorruptedSnapshot {
+                snapshot_id: snapshot.snapshot_id.clone(),
+                expected: hex::encode(&snapshot.state_root),
+                actual: hex::encode(&actual),
+            }));
+        }
+        Ok(())
+    }
+
     /// Generate merkle proof for state
     #[instrument(skip(self))]
     pub async fn generate_merkle_proof(&self, address: &Address, key: Option<U256>) -> Result<Vec<u8>> {
@@ -775,4 +896,114 @@ mod tests {
         let balance = manager.get_balance(&address, "GCC").await.unwrap();
         assert_eq!(balance, U256::ZERO);
     }
-}
\ No newline at end of file
+
+    fn gcc_balance() -> (Address, String) {
+        (Address([1; 20]), "GCC".to_string())
+    }
+
+    async fn snapshot_at(manager: &StateManager, block_number: u64, balance: u64) -> StateSnapshot {
+        {
+            let mut state = manager.current_state.write().await;
+            state.block_number = block_number;
+            state.balances.insert(gcc_balance(), U256::from(balance));
+            state.state_root = state_tree::state_root(&state.balances, &state.nonces, &state.storage);
+        }
+        manager.create_snapshot().await.unwrap()
+    }
+
+    /// Rewrite the snapshot payload without updating its stored root
+    fn corrupt(snapshot: &mut StateSnapshot) {
+        let mut state: L2State = serde_json::from_slice(&snapshot.compressed_state).unwrap();
+        state.balances.insert(gcc_balance(), U256::from(1_000_000));
+        snapshot.compressed_state = serde_json::to_vec(&state).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restore_verifies_state_root() {
+        let manager = StateManager::new(SettlementConfig::default()).await.unwrap();
+        let snapshot = snapshot_at(&manager, 1, 500).await;
+
+        let mut corrupted = snapshot.clone();
+        corrupt(&mut corrupted);
+        let result = manager.restore_from_snapshot(&corrupted).await;
+        assert!(matches!(
+            result,
+            Err(BridgeError::Settlement(SettlementError::CorruptedSnapshot { .. }))
+        ));
+
+        manager.current_state.write().await.balances.clear();
+        manager.restore_from_snapshot(&snapshot).await.unwrap();
+        let state = manager.current_state.read().await;
+        assert_eq!(state.balances.get(&gcc_balance()), Some(&U256::from(500)));
+    }
+
+    #[tokio::test]
+    async fn test_restore_falls_back_past_corrupted_snapshot() {
+        let manager = StateManager::new(SettlementConfig::default()).await.unwrap();
+        snapshot_at(&manager, 1, 500).await;
+        snapshot_at(&manager, 2, 700).await;
+        corrupt(manager.state_history.write().await.snapshots.get_mut(&2).unwrap());
+
+        let restored = manager.restore_latest_valid_snapshot(2).await.unwrap();
+        assert_eq!(restored.block_number, 1);
+        assert_eq!(manager.current_state.read().await.block_number, 1);
+
+        corrupt(manager.state_history.write().await.snapshots.get_mut(&1).unwrap());
+        assert!(matches!(
+            manager.restore_latest_valid_snapshot(2).await,
+            Err(BridgeError::Settlement(SettlementError::NoValidSnapshot { block_number: 2 }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_batch_roots_form_a_chain_under_concurrency() {
+        let manager = StateManager::new(SettlementConfig::default()).await.unwrap();
+        let chain = manager.batch_chain();
+
+        // The first batch builds on the zero root
+        assert_eq!(chain.lock().await.next_link(), BatchLink {
+            previous_state_root: GENESIS_STATE_ROOT.to_vec(),
+            block_number: 1,
+        });
+
+        // An abandoned batch leaves the tip unchanged
+        drop(chain.lock().await);
+        assert_eq!(chain.tip().await.block_number, 0);
+
+        let tasks: Vec<_> = (1..=16u8)
+            .map(|root| {
+                let chain = chain.clone();
+                tokio::spawn(async move {
+                    let guard = chain.lock().await;
+                    tokio::task::yield_now().await;
+                    (root, guard.append(vec![root; 32]))
+                })
+            })
+            .collect();
+        let mut links = Vec::new();
+        for task in tasks {
+            links.push(task.await.unwrap());
+        }
+        links.sort_by_key(|(_, link)| link.block_number);
+
+        // Block numbers are consecutive and each batch links to the one before it
+        let mut previous = GENESIS_STATE_ROOT.to_vec();
+        for (expected_block, (root, link)) in (1..).zip(&links) {
+            assert_eq!(link.block_number, expected_block);
+            assert_eq!(link.previous_state_root, previous);
+            previous = vec![*root; 32];
+        }
+        assert_eq!(chain.tip().await, ChainTip { state_root: previous, block_number: 16 });
+    }
+
+    #[tokio::test]
+    async fn test_batch_chain_restores_forward_only() {
+        let chain = BatchRootChain::genesis();
+        chain.restore(ChainTip { state_root: vec![7; 32], block_number: 41 }).await;
+        assert_eq!(chain.lock().await.next_link(), BatchLink { previous_state_root: vec![7; 32], block_number: 42 });
+
+        // An older batch never moves the tip back
+        chain.restore(ChainTip { state_root: vec![3; 32], block_number: 12 }).await;
+        assert_eq!(chain.tip().await.block_number, 41);
+    }
+}
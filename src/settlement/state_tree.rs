@@ -0,0 +1,206 @@
+/*!
+Merkle commitment to L2 state
+
+A state root commits to one Merkle tree (see `merkle`) per state table:
+balances, nonces and contract storage. Each table's leaves are sorted by key,
+so the same state always yields the same root whatever the `HashMap`
+iteration order, and the three table roots are combined as
+H(0x03 || balances || nonces || storage). Empty state has the all-zero
+`GENESIS_STATE_ROOT`.
+
+The batch processor commits to this root, the state manager checks restored
+snapshots against it, and emergency exits accept `BalanceProof`s against it
+while L2 is down.
+*/
+
+use crate::settlement::merkle::{HasherType, MerkleTree};
+use crate::settlement::state_manager::GENESIS_STATE_ROOT;
+use crate::types::{Address, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Hasher for the state tables' trees, matching L1 verifiers
+pub const STATE_TREE_HASHER: HasherType = HasherType::Keccak256;
+
+/// Domain prefix of the hash combining the table roots
+const STATE_DOMAIN: u8 = 0x03;
+
+/// Inclusion proof of an L2 balance in a state root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceProof {
+    pub address: Address,
+    pub token: String,
+    pub balance: U256,
+    /// State root the proof was generated against
+    pub state_root: Vec<u8>,
+    /// Roots of the nonce and storage tables committed alongside balances
+    pub nonce_root: Vec<u8>,
+    pub storage_root: Vec<u8>,
+    /// Position of the balance among the sorted balance leaves
+    pub index: u64,
+    /// Inclusion proof of the balance leaf in the balance tree
+    pub path: Vec<Vec<u8>>,
+}
+
+impl BalanceProof {
+    /// Whether the proof's balance hashes up to its state root
+    pub fn verify(&self) -> bool {
+        let leaf = balance_leaf(&self.address, &self.token, &self.balance);
+        usize::try_from(self.index).ok()
+            .and_then(|index| STATE_TREE_HASHER.root_from_proof(&leaf, &self.path, index))
+            .is_some_and(|balance_root| {
+                combine_roots(&balance_root, &self.nonce_root, &self.storage_root) == self.state_root
+            })
+    }
+}
+
+/// Leaf committing to one account's balance of one token
+pub fn balance_leaf(address: &Address, token: &str, balance: &U256) -> Vec<u8> {
+    let mut leaf = Vec::with_capacity(20 + 4 + token.len() + 32);
+    leaf.extend_from_slice(&address.0);
+    leaf.extend_from_slice(&(token.len() as u32).to_be_bytes());
+    leaf.extend_from_slice(token.as_bytes());
+    leaf.extend_from_slice(&balance.0);
+    leaf
+}
+
+/// Leaf committing to one account's nonce
+pub fn nonce_leaf(address: &Address, nonce: u64) -> Vec<u8> {
+    [&address.0[..], &nonce.to_be_bytes()].concat()
+}
+
+/// Leaf committing to one storage slot of one account
+pub fn storage_leaf(address: &Address, slot: &U256, value: &U256) -> Vec<u8> {
+    [&address.0[..], &slot.0, &value.0].concat()
+}
+
+fn build_tree(leaves: Vec<Vec<u8>>) -> MerkleTree {
+    STATE_TREE_HASHER.build_tree(&leaves).expect("Keccak-256 trees always build")
+}
+
+/// Balance entries in address then token order
+fn sorted_balances(balances: &HashMap<(Address, String), U256>) -> Vec<(&(Address, String), &U256)> {
+    let mut entries: Vec<_> = balances.iter().collect();
+    entries.sort_by(|(a, _), (b, _)| a.0.0.cmp(&b.0.0).then_with(|| a.1.cmp(&b.1)));
+    entries
+}
+
+fn balance_tree(balances: &HashMap<(Address, String), U256>) -> MerkleTree {
+    build_tree(sorted_balances(balances).into_iter()
+        .map(|((address, token), balance)| balance_leaf(address, token, balance))
+        .collect())
+}
+
+/// Merkle root over a balance table; all zeros when empty
+pub fn balance_root(balances: &HashMap<(Address, String), U256>) -> Vec<u8> {
+    balance_tree(balances).root().to_vec()
+}
+
+/// Merkle root over a nonce table, in address order
+pub fn nonce_root(nonces: &HashMap<Address, u64>) -> Vec<u8> {
+    let mut entries: Vec<_> = nonces.iter().collect();
+    entries.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0));
+    build_tree(entries.into_iter().map(|(address, nonce)| nonce_leaf(address, *nonce)).collect())
+        .root().to_vec()
+}
+
+/// Merkle root over a storage table, in address then slot order
+pub fn storage_root(storage: &HashMap<(Address, U256), U256>) -> Vec<u8> {
+    let mut entries: Vec<_> = storage.iter().collect();
+    entries.sort_by(|((a, a_slot), _), ((b, b_slot), _)| a.0.cmp(&b.0).then_with(|| a_slot.cmp(b_slot)));
+    build_tree(entries.into_iter().map(|((address, slot), value)| storage_leaf(address, slot, value)).collect())
+        .root().to_vec()
+}
+
+fn combine_roots(balance_root: &[u8], nonce_root: &[u8], storage_root: &[u8]) -> Vec<u8> {
+    STATE_TREE_HASHER.hash(&[&[STATE_DOMAIN], balance_root, nonce_root, storage_root])
+        .expect("Keccak-256 always hashes")
+}
+
+/// State root committing to the balance, nonce and storage tables
+pub fn state_root(
+    balances: &HashMap<(Address, String), U256>,
+    nonces: &HashMap<Address, u64>,
+    storage: &HashMap<(Address, U256), U256>,
+) -> Vec<u8> {
+    if balances.is_empty() && nonces.is_empty() && storage.is_empty() {
+        return GENESIS_STATE_ROOT.to_vec();
+    }
+    combine_roots(&balance_root(balances), &nonce_root(nonces), &storage_root(storage))
+}
+
+/// Prove `address`'s balance of `token` against the state's root
+pub fn prove_balance(
+    balances: &HashMap<(Address, String), U256>,
+    nonces: &HashMap<Address, u64>,
+    storage: &HashMap<(Address, U256), U256>,
+    address: &Address,
+    token: &str,
+) -> Option<BalanceProof> {
+    let key = (address.clone(), token.to_string());
+    let index = sorted_balances(balances).iter().position(|(entry, _)| **entry == key)?;
+    let tree = balance_tree(balances);
+
+    let nonce_root = nonce_root(nonces);
+    let storage_root = storage_root(storage);
+    Some(BalanceProof {
+        address: address.clone(),
+        token: token.to_string(),
+        balance: balances[&key].clone(),
+        state_root: combine_roots(tree.root(), &nonce_root, &storage_root),
+        nonce_root,
+        storage_root,
+        index: index as u64,
+        path: tree.generate_inclusion_proof(index),
+    })
+}
+
+/// Light-client check that `proof` proves the balance at `key` under `root`
+pub fn verify_state_proof(root: &[u8], key: (&Address, &str), proof: &BalanceProof) -> bool {
+    let (address, token) = key;
+    proof.address == *address
+        && proof.token == token
+        && proof.state_root == root
+        && proof.verify()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balances(entries: &[(u8, u64)]) -> HashMap<(Address, String), U256> {
+        entries.iter()
+            .map(|&(account, amount)| ((Address([account; 20]), "GCC".to_string()), U256::from(amount)))
+            .collect()
+    }
+
+    #[test]
+    fn test_state_root_commits_to_nonces_and_storage() {
+        let table = balances(&[(1, 100), (2, 250), (3, 5)]);
+        let nonces: HashMap<Address, u64> = [(Address([1; 20]), 3)].into_iter().collect();
+        let storage: HashMap<(Address, U256), U256> =
+            [((Address([2; 20]), U256::from(7u64)), U256::from(9u64))].into_iter().collect();
+        let full = state_root(&table, &nonces, &storage);
+
+        assert_ne!(full, state_root(&table, &HashMap::new(), &HashMap::new()));
+        let bumped: HashMap<Address, u64> = [(Address([1; 20]), 4)].into_iter().collect();
+        assert_ne!(full, state_root(&table, &bumped, &storage));
+        assert_eq!(state_root(&HashMap::new(), &HashMap::new(), &HashMap::new()), GENESIS_STATE_ROOT.to_vec());
+
+        // Every balance proves against the root, including the unpaired last leaf
+        for account in 1..=3u8 {
+            let proof = prove_balance(&table, &nonces, &storage, &Address([account; 20]), "GCC").unwrap();
+            assert_eq!(proof.state_root, full);
+            assert!(verify_state_proof(&full, (&Address([account; 20]), "GCC"), &proof));
+        }
+
+        // Claiming different nonce or storage roots, or another position, breaks the proof
+        let proof = prove_balance(&table, &nonces, &storage, &Address([2; 20]), "GCC").unwrap();
+        let mut forged = proof.clone();
+        forged.nonce_root = nonce_root(&bumped);
+        assert!(!forged.verify());
+        let mut moved = proof.clone();
+        moved.index = 0;
+        assert!(!moved.verify());
+    }
+}
@@ -5,13 +5,16 @@ Implements ZK-SNARKs for privacy-preserving and efficient settlement proofs.
 Provides proof generation, verification, and batched proof aggregation.
 */
 
-use crate::error::{BridgeError, Result};
+use crate::error::{BridgeError, Result, SettlementError};
 use crate::types::{Transaction, Address, U256};
 use crate::settlement::{SettlementConfig, SettlementBatch};
+use crate::idgen::{IdGenerator, default_id_generator};
+use crate::metrics::ProofMetrics;
+use crate::settlement::proof_workers::{ProofWorker, RemoteProofRequest};
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::{Duration, SystemTime};
-use tokio::sync::{RwLock, Semaphore};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{OwnedSemaphorePermit, RwLock, Semaphore, SemaphorePermit};
 use tracing::{debug, error, info, instrument, warn};
 use serde::{Deserialize, Serialize};
 
@@ -25,7 +28,13 @@ pub struct ZKProofSystem {
     aggregation_engine: AggregationEngine,
     trusted_setup: TrustedSetup,
     proof_queue: Arc<RwLock<ProofQueue>>,
+    /// One permit per free slot in the proof queue
+    proof_queue_slots: Arc<Semaphore>,
     generation_limiter: Arc<Semaphore>,
+    circuit_limiters: HashMap<String, Arc<Semaphore>>,
+    proof_metrics: Arc<ProofMetrics>,
+    proof_worker: parking_lot::RwLock<Option<Arc<dyn ProofWorker>>>,
+    id_generator: Arc<dyn IdGenerator>,
 }
 
 /// Proof generation engine
@@ -37,7 +46,7 @@ struct ProofGenerator {
 
 /// Proof verification engine
 struct ProofVerifier {
-    verification_keys: HashMap<String, VerificationKey>,
+    verification_keys: Arc<RwLock<HashMap<String, VerificationKey>>>,
     verification_cache: Arc<RwLock<HashMap<String, VerificationResult>>>,
 }
 
@@ -60,7 +69,7 @@ struct ProofCache {
 /// Proof aggregation engine
 struct AggregationEngine {
     aggregation_circuit: Circuit,
-    batch_aggregator: BatchAggregator,
+    batch_aggregator: Arc<RwLock<BatchAggregator>>,
     recursive_proofs: Vec<RecursiveProof>,
 }
 
@@ -70,6 +79,37 @@ struct TrustedSetup {
     parameters: SetupParameters,
     ceremony_data: CeremonyData,
     verification_transcript: Vec<SetupVerification>,
+    /// Verification key per circuit id from the ceremony output; empty for
+    /// the built-in development setup, whose keys are derived instead
+    verification_keys: HashMap<String, Vec<u8>>,
+}
+
+/// Published output of a trusted-setup ceremony, loaded with
+/// `ZKProofSystem::with_ceremony` or from `SettlementConfig::trusted_setup_path`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CeremonyOutput {
+    pub setup_id: String,
+    pub ceremony_id: String,
+    /// Public randomness the contribution chain starts from
+    pub randomness_beacon: Vec<u8>,
+    /// Contributions in ceremony order
+    pub contributions: Vec<CeremonyContribution>,
+    /// Parameters produced by the last contribution
+    pub final_parameters: Vec<u8>,
+    /// Commitment to the final parameters and verification keys
+    pub verification_transcript: Vec<u8>,
+    /// Verification key for every circuit, by circuit id
+    pub verification_keys: HashMap<String, Vec<u8>>,
+}
+
+/// One participant's contribution to a ceremony
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CeremonyContribution {
+    pub participant_id: String,
+    pub public_key: Vec<u8>,
+    pub contribution_data: Vec<u8>,
+    pub previous_hash: Vec<u8>,
+    pub new_hash: Vec<u8>,
 }
 
 /// Proof generation queue
@@ -94,6 +134,13 @@ pub struct ZKProof {
     pub metadata: ProofMetadata,
 }
 
+impl ZKProof {
+    /// Whether the proof is past its `expires_at`
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires_at.is_some_and(|expiry| now >= expiry)
+    }
+}
+
 /// Types of ZK proofs
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ProofType {
@@ -106,6 +153,18 @@ pub enum ProofType {
     RecursiveProof,
 }
 
+impl ProofType {
+    /// Circuit that proves this type of statement
+    pub fn circuit_id(&self) -> &'static str {
+        match self {
+            ProofType::TransactionValidity | ProofType::StateTransition => "state_transition",
+            ProofType::BalanceProof | ProofType::RangeProof => "balance_proof",
+            ProofType::MembershipProof => "membership_proof",
+            ProofType::AggregatedProof | ProofType::RecursiveProof => "aggregation",
+        }
+    }
+}
+
 /// Proof metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProofMetadata {
@@ -117,6 +176,38 @@ pub struct ProofMetadata {
     pub privacy_level: PrivacyLevel,
 }
 
+/// Gas cost of verifying a Groth16-style proof on L1
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofGasModel {
+    /// Fixed cost of the pairing check
+    pub base_verification_gas: u64,
+    /// Per public input: one scalar multiplication and addition with the
+    /// verification key's input points
+    pub gas_per_public_input: u64,
+    /// Calldata cost per proof byte
+    pub calldata_gas_per_byte: u64,
+}
+
+impl Default for ProofGasModel {
+    fn default() -> Self {
+        // EIP-1108 BN254 precompile prices
+        Self {
+            base_verification_gas: 181_000,
+            gas_per_public_input: 6_150,
+            calldata_gas_per_byte: 16,
+        }
+    }
+}
+
+impl ProofGasModel {
+    /// Estimated verification gas for a proof of `proof_size` bytes
+    pub fn estimate(&self, public_input_count: u64, proof_size: usize) -> u64 {
+        self.base_verification_gas
+            + self.gas_per_public_input * public_input_count
+            + self.calldata_gas_per_byte * proof_size as u64
+    }
+}
+
 /// Privacy levels
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum PrivacyLevel {
@@ -135,11 +226,60 @@ struct Circuit {
     constraint_count: u64,
     variable_count: u64,
     public_input_count: u64,
+    /// Witness capacity, in field elements
+    private_input_count: u64,
     proving_key_size: usize,
     verification_key_size: usize,
     trusted_setup_required: bool,
 }
 
+impl Circuit {
+    /// Size and estimated verification gas of a proof from this circuit
+    fn proof_costs(&self, model: &ProofGasModel, proof_data: &[u8]) -> (usize, u64) {
+        (proof_data.len(), model.estimate(self.public_input_count, proof_data.len()))
+    }
+
+    /// Refuse inputs the circuit can't take; proving them would silently
+    /// truncate the statement rather than fail
+    fn check_inputs(&self, inputs: &ProofInputs) -> Result<()> {
+        for (kind, bytes, capacity) in [
+            ("public", &inputs.public_inputs, self.public_input_count),
+            ("private", &inputs.private_inputs, self.private_input_count),
+        ] {
+            let required = field_elements(bytes.len());
+            if required > capacity {
+                return Err(BridgeError::Settlement(SettlementError::ProofInputsExceedCircuit {
+                    circuit_id: self.circuit_id.clone(),
+                    kind: kind.to_string(),
+                    required,
+                    capacity,
+                }));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Bytes packed into each circuit input
+pub const FIELD_ELEMENT_BYTES: usize = 32;
+
+/// Private input bytes contributed by each transaction in a batch
+const PRIVATE_INPUT_BYTES_PER_TRANSACTION: usize = 16 + 20 + 20 + 32; // id, from, to, amount
+
+fn field_elements(bytes: usize) -> u64 {
+    bytes.div_ceil(FIELD_ELEMENT_BYTES) as u64
+}
+
+/// What to do with batches too large for the state transition circuit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OversizedInputPolicy {
+    /// Fail proof generation for the batch
+    Reject,
+    /// Cap batches at the circuit's capacity when they are assembled
+    #[default]
+    Split,
+}
+
 /// Circuit performance metrics
 #[derive(Debug, Clone)]
 struct CircuitMetrics {
@@ -315,11 +455,11 @@ struct ProofRequest {
 }
 
 /// Proof inputs
-#[derive(Debug, Clone)]
-struct ProofInputs {
-    public_inputs: Vec<u8>,
-    private_inputs: Vec<u8>,
-    auxiliary_data: Vec<u8>,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofInputs {
+    pub public_inputs: Vec<u8>,
+    pub private_inputs: Vec<u8>,
+    pub auxiliary_data: Vec<u8>,
 }
 
 /// Proof priority
@@ -366,6 +506,11 @@ struct VerificationResult {
     valid: bool,
     verification_time: Duration,
     verified_at: SystemTime,
+    /// Key the proof was checked against; the result is discarded when it
+    /// is rotated or revoked
+    verification_key_id: String,
+    /// Expiry of the verified proof; the result is discarded with it
+    expires_at: Option<SystemTime>,
     error: Option<String>,
 }
 
@@ -373,8 +518,29 @@ impl ZKProofSystem {
     /// Initialize ZK proof system
     #[instrument(skip(config))]
     pub async fn new(config: SettlementConfig) -> Result<Self> {
+        let Some(path) = config.trusted_setup_path.clone() else {
+            warn!("No trusted setup configured; using the built-in development setup");
+            return Self::with_trusted_setup(config, TrustedSetup::genesis()).await;
+        };
+        let bytes = tokio::fs::read(&path).await?;
+        let ceremony: CeremonyOutput = serde_json::from_slice(&bytes)
+            .map_err(|e| BridgeError::Serialization(e.into()))?;
+        info!("Loading trusted setup {} from {}", ceremony.setup_id, path.display());
+        Self::with_ceremony(config, ceremony).await
+    }
+
+    /// Initialize from a ceremony's published output, refusing it unless its
+    /// contribution chain verifies and it has a key for every circuit
+    pub async fn with_ceremony(config: SettlementConfig, ceremony: CeremonyOutput) -> Result<Self> {
+        Self::with_trusted_setup(config, TrustedSetup::from_ceremony(ceremony)).await
+    }
+
+    /// Initialize from a trusted setup, loading its verification keys
+    async fn with_trusted_setup(config: SettlementConfig, mut trusted_setup: TrustedSetup) -> Result<Self> {
         info!("Initializing ZK proof system");
 
+        trusted_setup.validate()?;
+
         let proof_generator = ProofGenerator {
             proving_keys: HashMap::new(),
             witness_generators: HashMap::new(),
@@ -396,13 +562,9 @@ impl ZKProofSystem {
             ],
         };
 
-        let verifier = ProofVerifier {
-            verification_keys: HashMap::new(),
-            verification_cache: Arc::new(RwLock::new(HashMap::new())),
-        };
-
+        let circuits = Self::initialize_circuits();
         let circuit_registry = Arc::new(RwLock::new(CircuitRegistry {
-            circuits: Self::initialize_circuits(),
+            circuits: circuits.clone(),
             active_circuits: vec![
                 "state_transition".to_string(),
                 "balance_proof".to_string(),
@@ -431,34 +593,31 @@ impl ZKProofSystem {
                 constraint_count: 1_000_000,
                 variable_count: 100_000,
                 public_input_count: 100,
+                private_input_count: 10_000,
                 proving_key_size: 10_000_000, // 10MB
                 verification_key_size: 1_000, // 1KB
                 trusted_setup_required: true,
             },
-            batch_aggregator: BatchAggregator {
+            batch_aggregator: Arc::new(RwLock::new(BatchAggregator {
                 max_batch_size: 1000,
-                aggregation_threshold: 100,
+                aggregation_threshold: config.proof_aggregation_threshold.min(1000),
                 pending_aggregations: Vec::new(),
-            },
+            })),
             recursive_proofs: Vec::new(),
         };
 
-        let trusted_setup = TrustedSetup {
-            setup_id: "ghostbridge_setup_v1".to_string(),
-            parameters: SetupParameters {
-                curve_type: CurveType::BN254,
-                security_level: 128,
-                randomness_beacon: vec![0; 32], // TODO: Use actual randomness beacon
-                parameter_size: 100_000_000, // 100MB
-            },
-            ceremony_data: CeremonyData {
-                ceremony_id: "ghostbridge_ceremony_2024".to_string(),
-                participants: Vec::new(),
-                contributions: Vec::new(),
-                final_parameters: Vec::new(),
-                verification_transcript: Vec::new(),
-            },
-            verification_transcript: Vec::new(),
+        // Load verification keys for every circuit from the setup output
+        let mut verification_keys = HashMap::new();
+        for circuit in circuits.values().chain(std::iter::once(&aggregation_engine.aggregation_circuit)) {
+            let key = trusted_setup.verification_key(circuit)?;
+            verification_keys.insert(key.key_id.clone(), key);
+        }
+        info!("Loaded {} verification keys from trusted setup {}",
+              verification_keys.len(), trusted_setup.setup_id);
+
+        let verifier = ProofVerifier {
+            verification_keys: Arc::new(RwLock::new(verification_keys)),
+            verification_cache: Arc::new(RwLock::new(HashMap::new())),
         };
 
         let proof_queue = Arc::new(RwLock::new(ProofQueue {
@@ -468,7 +627,12 @@ impl ZKProofSystem {
             failed_proofs: HashMap::new(),
         }));
 
-        let generation_limiter = Arc::new(Semaphore::new(10)); // Max 10 concurrent proof generations
+        let proof_queue_slots = Arc::new(Semaphore::new(config.proof_queue_capacity));
+        let generation_limiter = Arc::new(Semaphore::new(config.max_concurrent_proof_generations.max(1)));
+        let circuit_limiters = config.circuit_proof_concurrency.iter()
+            .map(|(circuit_id, limit)| (circuit_id.clone(), Arc::new(Semaphore::new((*limit).max(1)))))
+            .collect();
+        let proof_metrics = Arc::new(ProofMetrics::new(config.proof_alert_thresholds.clone()));
 
         Ok(Self {
             config,
@@ -479,29 +643,69 @@ impl ZKProofSystem {
             aggregation_engine,
             trusted_setup,
             proof_queue,
+            proof_queue_slots,
             generation_limiter,
+            circuit_limiters,
+            proof_metrics,
+            proof_worker: parking_lot::RwLock::new(None),
+            id_generator: default_id_generator(),
         })
     }
 
+    /// Use a custom id generator (e.g. a seeded one in tests)
+    pub fn with_id_generator(mut self, id_generator: Arc<dyn IdGenerator>) -> Self {
+        self.id_generator = id_generator;
+        self
+    }
+
+    /// Offload proof generation to an external worker
+    pub fn with_proof_worker(self, worker: Arc<dyn ProofWorker>) -> Self {
+        self.set_proof_worker(worker);
+        self
+    }
+
+    /// Offload proof generation to an external worker from now on
+    pub fn set_proof_worker(&self, worker: Arc<dyn ProofWorker>) {
+        *self.proof_worker.write() = Some(worker);
+    }
+
+    /// Proof generation counters, latencies, and alerts
+    pub fn proof_metrics(&self) -> Arc<ProofMetrics> {
+        self.proof_metrics.clone()
+    }
+
+    /// Most transactions a batch may hold and still fit the state transition circuit
+    pub async fn max_batch_transactions(&self) -> Result<usize> {
+        let circuit = self.select_circuit(&ProofType::StateTransition).await?;
+        Ok(circuit.private_input_count as usize * FIELD_ELEMENT_BYTES / PRIVATE_INPUT_BYTES_PER_TRANSACTION)
+    }
+
     /// Generate proof for settlement batch
     #[instrument(skip(self, batch))]
     pub async fn generate_batch_proof(&self, batch: &SettlementBatch) -> Result<ZKProof> {
         debug!("Generating ZK proof for batch: {}", batch.batch_id);
 
-        let _permit = self.generation_limiter.acquire().await.unwrap();
+        let circuit_id = ProofType::StateTransition.circuit_id();
+        self.proof_metrics.record_requested();
+        let _permits = self.acquire_generation_permits(circuit_id).await;
 
         // Check cache first
         let cache_key = self.compute_batch_cache_key(batch);
         if let Some(cached_proof) = self.get_cached_proof(&cache_key).await {
             debug!("Using cached proof for batch: {}", batch.batch_id);
+            self.proof_metrics.record_cache_hit();
             return Ok(cached_proof);
         }
+        self.proof_metrics.record_cache_miss();
 
-        // Prepare proof inputs
-        let inputs = self.prepare_batch_inputs(batch).await?;
-
-        // Generate proof for state transition
-        let proof = self.generate_state_transition_proof(inputs).await?;
+        // Prepare inputs and generate proof for state transition
+        let started = Instant::now();
+        let result = match self.prepare_batch_inputs(batch).await {
+            Ok(inputs) => self.generate_state_transition_proof(inputs).await,
+            Err(e) => Err(e),
+        };
+        self.record_generation(circuit_id, started, &result);
+        let proof = result?;
 
         // Cache the proof
         self.cache_proof(&cache_key, &proof).await;
@@ -512,23 +716,51 @@ impl ZKProofSystem {
         Ok(proof)
     }
 
+    /// Proof for submitting a batch to L1: a fresh, verified proof,
+    /// regenerated if the cached one has expired
+    #[instrument(skip(self, batch))]
+    pub async fn proof_for_submission(&self, batch: &SettlementBatch) -> Result<ZKProof> {
+        let proof = self.generate_batch_proof(batch).await?;
+        if !self.verify_proof(&proof).await? {
+            return Err(BridgeError::Settlement(SettlementError::ZkProofVerificationFailed));
+        }
+        Ok(proof)
+    }
+
     /// Verify ZK proof
     #[instrument(skip(self, proof))]
     pub async fn verify_proof(&self, proof: &ZKProof) -> Result<bool> {
         debug!("Verifying ZK proof: {}", proof.proof_id);
 
+        // Expired proofs are never accepted, even if previously verified
+        if proof.is_expired(SystemTime::now()) {
+            self.verifier.verification_cache.write().await.remove(&proof.proof_id);
+            return Err(BridgeError::Settlement(SettlementError::ProofExpired {
+                proof_id: proof.proof_id.clone(),
+            }));
+        }
+
         // Check verification cache
         {
             let cache = self.verifier.verification_cache.read().await;
+            // A result only vouches for the proof under the key it was checked against
             if let Some(result) = cache.get(&proof.proof_id) {
-                debug!("Using cached verification result for proof: {}", proof.proof_id);
-                return Ok(result.valid);
+                if result.verification_key_id == proof.verification_key_id
+                    && result.expires_at.is_none_or(|expiry| SystemTime::now() < expiry)
+                {
+                    debug!("Using cached verification result for proof: {}", proof.proof_id);
+                    return Ok(result.valid);
+                }
             }
         }
 
-        // Get verification key
-        let verification_key = self.verifier.verification_keys.get(&proof.verification_key_id)
-            .ok_or_else(|| BridgeError::Settlement("Verification key not found".to_string()))?;
+        // Get verification key; holding the read lock until the result is
+        // cached keeps a concurrent rotation from being outrun by a stale result
+        let verification_keys = self.verifier.verification_keys.read().await;
+        let verification_key = verification_keys.get(&proof.verification_key_id)
+            .ok_or_else(|| BridgeError::Settlement(SettlementError::UnknownVerificationKey {
+                key_id: proof.verification_key_id.clone(),
+            }))?;
 
         // Perform verification
         let start_time = SystemTime::now();
@@ -543,14 +775,54 @@ impl ZKProofSystem {
                 valid,
                 verification_time,
                 verified_at: SystemTime::now(),
+                verification_key_id: proof.verification_key_id.clone(),
+                expires_at: proof.expires_at,
                 error: None,
             });
         }
+        drop(verification_keys);
 
         debug!("Proof verification completed: {} (valid: {})", proof.proof_id, valid);
         Ok(valid)
     }
 
+    /// Replace a verification key's material, e.g. after a new setup ceremony
+    pub async fn rotate_verification_key(&self, key_id: &str, key_data: Vec<u8>) -> Result<()> {
+        let mut verification_keys = self.verifier.verification_keys.write().await;
+        let key = verification_keys.get_mut(key_id)
+            .ok_or_else(|| BridgeError::Settlement(SettlementError::UnknownVerificationKey {
+                key_id: key_id.to_string(),
+            }))?;
+        key.size_bytes = key_data.len();
+        key.key_data = key_data;
+        key.created_at = SystemTime::now();
+
+        let invalidated = self.invalidate_cached_results(key_id).await;
+        info!("Rotated verification key {}; invalidated {} cached results", key_id, invalidated);
+        Ok(())
+    }
+
+    /// Remove a verification key; proofs against it no longer verify
+    pub async fn revoke_verification_key(&self, key_id: &str) -> Result<()> {
+        let mut verification_keys = self.verifier.verification_keys.write().await;
+        if verification_keys.remove(key_id).is_none() {
+            return Err(BridgeError::Settlement(SettlementError::UnknownVerificationKey {
+                key_id: key_id.to_string(),
+            }));
+        }
+        let invalidated = self.invalidate_cached_results(key_id).await;
+        warn!("Revoked verification key {}; invalidated {} cached results", key_id, invalidated);
+        Ok(())
+    }
+
+    /// Drop cached results checked against `key_id`, returning how many
+    async fn invalidate_cached_results(&self, key_id: &str) -> usize {
+        let mut cache = self.verifier.verification_cache.write().await;
+        let before = cache.len();
+        cache.retain(|_, result| result.verification_key_id != key_id);
+        before - cache.len()
+    }
+
     /// Generate aggregated proof for multiple batches
     #[instrument(skip(self, proofs))]
     pub async fn aggregate_proofs(&self, proofs: Vec<ZKProof>) -> Result<ZKProof> {
@@ -581,19 +853,126 @@ impl ZKProofSystem {
         Ok(aggregated_proof)
     }
 
-    /// Submit proof generation request
+    /// Queue a proof for aggregation.
+    ///
+    /// Once `aggregation_threshold` proofs are pending they are aggregated into
+    /// a single recursive proof, which is returned.
+    #[instrument(skip(self, proof))]
+    pub async fn queue_for_aggregation(&self, proof: ZKProof) -> Result<Option<ZKProof>> {
+        let (batch_id, proofs) = {
+            let mut aggregator = self.aggregation_engine.batch_aggregator.write().await;
+            let threshold = aggregator.aggregation_threshold;
+
+            if threshold == 0 {
+                return Ok(None);
+            }
+
+            let batch = match aggregator.pending_aggregations.iter_mut()
+                .position(|b| b.status == AggregationStatus::Pending)
+            {
+                Some(index) => &mut aggregator.pending_aggregations[index],
+                None => {
+                    aggregator.pending_aggregations.push(AggregationBatch {
+                        batch_id: self.id_generator.next_id("agg-batch"),
+                        proofs: Vec::new(),
+                        aggregated_proof: None,
+                        created_at: SystemTime::now(),
+                        status: AggregationStatus::Pending,
+                    });
+                    aggregator.pending_aggregations.last_mut().unwrap()
+                }
+            };
+
+            batch.proofs.push(proof);
+            if batch.proofs.len() < threshold {
+                return Ok(None);
+            }
+
+            batch.status = AggregationStatus::Aggregating;
+            (batch.batch_id.clone(), batch.proofs.clone())
+        };
+
+        debug!("Aggregation threshold reached for {}: aggregating {} proofs", batch_id, proofs.len());
+        let result = self.aggregate_proofs(proofs).await;
+
+        let mut aggregator = self.aggregation_engine.batch_aggregator.write().await;
+        if let Some(batch) = aggregator.pending_aggregations.iter_mut().find(|b| b.batch_id == batch_id) {
+            match &result {
+                Ok(aggregated) => {
+                    batch.aggregated_proof = Some(aggregated.clone());
+                    batch.status = AggregationStatus::Completed;
+                }
+                Err(e) => {
+                    warn!("Automatic aggregation of {} failed: {}", batch_id, e);
+                    batch.status = AggregationStatus::Failed;
+                }
+            }
+        }
+
+        result.map(Some)
+    }
+
+    /// Recursive proofs produced by automatic aggregation, oldest first
+    pub async fn aggregated_proofs(&self) -> Vec<ZKProof> {
+        let aggregator = self.aggregation_engine.batch_aggregator.read().await;
+        aggregator.pending_aggregations.iter()
+            .filter_map(|b| b.aggregated_proof.clone())
+            .collect()
+    }
+
+    /// Number of proofs waiting for the aggregation threshold
+    pub async fn pending_aggregation_count(&self) -> usize {
+        let aggregator = self.aggregation_engine.batch_aggregator.read().await;
+        aggregator.pending_aggregations.iter()
+            .filter(|b| b.status == AggregationStatus::Pending)
+            .map(|b| b.proofs.len())
+            .sum()
+    }
+
+    /// Submit proof generation request, rejecting it if the queue is full
     #[instrument(skip(self, inputs))]
     pub async fn submit_proof_request(
         &self,
         proof_type: ProofType,
-        circuit_id: String,
         inputs: ProofInputs,
         priority: ProofPriority,
     ) -> Result<String> {
-        let request_id = format!("req-{}-{}",
-                                SystemTime::now().duration_since(std::time::UNIX_EPOCH)
-                                    .unwrap_or_default().as_millis(),
-                                rand::random::<u32>());
+        let slot = self.proof_queue_slots.clone().try_acquire_owned()
+            .map_err(|_| BridgeError::Settlement(SettlementError::ProofQueueFull {
+                capacity: self.config.proof_queue_capacity,
+            }))?;
+        self.enqueue_proof_request(slot, proof_type, inputs, priority).await
+    }
+
+    /// Submit proof generation request, waiting for a free queue slot
+    #[instrument(skip(self, inputs))]
+    pub async fn submit_proof_request_when_ready(
+        &self,
+        proof_type: ProofType,
+        inputs: ProofInputs,
+        priority: ProofPriority,
+    ) -> Result<String> {
+        let slot = self.proof_queue_slots.clone().acquire_owned().await
+            .map_err(|_| BridgeError::Settlement(SettlementError::ProofQueueFull {
+                capacity: self.config.proof_queue_capacity,
+            }))?;
+        self.enqueue_proof_request(slot, proof_type, inputs, priority).await
+    }
+
+    /// Free queue slots
+    pub fn proof_queue_available(&self) -> usize {
+        self.proof_queue_slots.available_permits()
+    }
+
+    async fn enqueue_proof_request(
+        &self,
+        slot: OwnedSemaphorePermit,
+        proof_type: ProofType,
+        inputs: ProofInputs,
+        priority: ProofPriority,
+    ) -> Result<String> {
+        let circuit_id = self.select_circuit(&proof_type).await?.circuit_id;
+        let request_id = self.id_generator.next_id("req");
 
         let request = ProofRequest {
             request_id: request_id.clone(),
@@ -610,11 +989,65 @@ impl ZKProofSystem {
             queue.pending_proofs.push(request);
             queue.pending_proofs.sort_by(|a, b| b.priority.cmp(&a.priority)); // Higher priority first
         }
+        // The slot stays taken until the request completes or fails
+        slot.forget();
 
         debug!("Submitted proof request: {}", request_id);
         Ok(request_id)
     }
 
+    /// Generate the highest-priority queued proof, freeing its queue slot when
+    /// done. Returns the request id, or `None` if the queue is empty.
+    pub async fn process_next_proof_request(&self) -> Option<String> {
+        let request = {
+            let mut queue = self.proof_queue.write().await;
+            if queue.pending_proofs.is_empty() {
+                return None;
+            }
+            let request = queue.pending_proofs.remove(0);
+            let now = SystemTime::now();
+            queue.generating_proofs.insert(request.request_id.clone(), GeneratingProof {
+                request: request.clone(),
+                started_at: now,
+                progress: 0.0,
+                estimated_completion: now,
+                worker_id: "local".to_string(),
+            });
+            request
+        };
+
+        let request_id = request.request_id.clone();
+        let started = Instant::now();
+        let result = self.generate_proof(request.proof_type.clone(), request.inputs.clone()).await;
+
+        {
+            let mut queue = self.proof_queue.write().await;
+            queue.generating_proofs.remove(&request_id);
+            match result {
+                Ok(proof) => {
+                    queue.completed_proofs.insert(request_id.clone(), CompletedProof {
+                        request,
+                        proof,
+                        completed_at: SystemTime::now(),
+                        generation_time: started.elapsed(),
+                    });
+                }
+                Err(e) => {
+                    warn!("Queued proof request {} failed: {}", request_id, e);
+                    queue.failed_proofs.insert(request_id.clone(), FailedProof {
+                        request,
+                        error: e.to_string(),
+                        failed_at: SystemTime::now(),
+                        retry_count: 0,
+                    });
+                }
+            }
+        }
+        self.proof_queue_slots.add_permits(1);
+
+        Some(request_id)
+    }
+
     /// Get proof request status
     pub async fn get_proof_status(&self, request_id: &str) -> Result<ProofStatus> {
         let queue = self.proof_queue.read().await;
@@ -644,11 +1077,75 @@ impl ZKProofSystem {
         let cache = self.proof_cache.read().await;
 
         // System is healthy if queues are manageable
-        queue.pending_proofs.len() < 1000 &&
+        self.proof_queue_slots.available_permits() > 0 &&
         queue.generating_proofs.len() < 100 &&
         cache.cached_proofs.len() < 10000
     }
 
+    /// Generate a proof using the circuit for `proof_type`
+    #[instrument(skip(self, inputs))]
+    pub async fn generate_proof(&self, proof_type: ProofType, inputs: ProofInputs) -> Result<ZKProof> {
+        let circuit_id = proof_type.circuit_id();
+        self.proof_metrics.record_requested();
+        let circuit = match self.select_circuit(&proof_type).await {
+            Ok(circuit) => circuit,
+            Err(e) => {
+                self.proof_metrics.record_failed(circuit_id, Duration::ZERO);
+                return Err(e);
+            }
+        };
+        let _permits = self.acquire_generation_permits(&circuit.circuit_id).await;
+
+        let started = Instant::now();
+        let result = self.generate_circuit_proof(&circuit, proof_type, inputs).await;
+        self.record_generation(circuit_id, started, &result);
+        result
+    }
+
+    /// Record a generation outcome and report newly breached alert thresholds
+    fn record_generation(&self, circuit_id: &str, started: Instant, result: &Result<ZKProof>) {
+        match result {
+            Ok(_) => self.proof_metrics.record_generated(circuit_id, started.elapsed()),
+            Err(_) => self.proof_metrics.record_failed(circuit_id, started.elapsed()),
+        }
+        for alert in self.proof_metrics.take_new_alerts() {
+            warn!("Proof generation alert: {}", alert);
+        }
+    }
+
+    /// Wait for a generation slot on `circuit_id` and then a global one.
+    /// Callers beyond either limit queue here rather than over-subscribing.
+    async fn acquire_generation_permits(&self, circuit_id: &str) -> (Option<SemaphorePermit<'_>>, SemaphorePermit<'_>) {
+        // Take the circuit slot first so a throttled circuit never holds global slots while waiting
+        let circuit_permit = match self.circuit_limiters.get(circuit_id) {
+            Some(limiter) => Some(limiter.acquire().await.expect("proof limiter is never closed")),
+            None => None,
+        };
+        let global_permit = self.generation_limiter.acquire().await.expect("proof limiter is never closed");
+        (circuit_permit, global_permit)
+    }
+
+    /// Resolve the active circuit for a proof type
+    async fn select_circuit(&self, proof_type: &ProofType) -> Result<Circuit> {
+        let circuit_id = proof_type.circuit_id();
+        if circuit_id == self.aggregation_engine.aggregation_circuit.circuit_id {
+            return Ok(self.aggregation_engine.aggregation_circuit.clone());
+        }
+
+        let registry = self.circuit_registry.read().await;
+        if !registry.active_circuits.iter().any(|id| id == circuit_id) {
+            return Err(BridgeError::Settlement(SettlementError::InactiveCircuit {
+                circuit_id: circuit_id.to_string(),
+            }));
+        }
+
+        registry.circuits.get(circuit_id)
+            .cloned()
+            .ok_or_else(|| BridgeError::Settlement(SettlementError::InactiveCircuit {
+                circuit_id: circuit_id.to_string(),
+            }))
+    }
+
     fn initialize_circuits() -> HashMap<String, Circuit> {
         let mut circuits = HashMap::new();
 
@@ -659,6 +1156,7 @@ impl ZKProofSystem {
             constraint_count: 1_000_000,
             variable_count: 100_000,
             public_input_count: 10,
+            private_input_count: 50_000,
             proving_key_size: 50_000_000, // 50MB
             verification_key_size: 2_000, // 2KB
             trusted_setup_required: true,
@@ -671,6 +1169,7 @@ impl ZKProofSystem {
             constraint_count: 100_000,
             variable_count: 10_000,
             public_input_count: 5,
+            private_input_count: 5_000,
             proving_key_size: 5_000_000, // 5MB
             verification_key_size: 1_000, // 1KB
             trusted_setup_required: true,
@@ -683,6 +1182,7 @@ impl ZKProofSystem {
             constraint_count: 500_000,
             variable_count: 50_000,
             public_input_count: 3,
+            private_input_count: 25_000,
             proving_key_size: 25_000_000, // 25MB
             verification_key_size: 1_500, // 1.5KB
             trusted_setup_required: true,
@@ -697,20 +1197,23 @@ impl ZKProofSystem {
     }
 
     async fn get_cached_proof(&self, cache_key: &str) -> Option<ZKProof> {
-        let cache = self.proof_cache.read().await;
-
-        if let Some(cached) = cache.cached_proofs.get(cache_key) {
-            // Check if expired
-            if let Some(expiry) = cache.expiry_times.get(cache_key) {
-                if SystemTime::now() > *expiry {
-                    return None;
-                }
+        let now = SystemTime::now();
+        {
+            let cache = self.proof_cache.read().await;
+            let cached = cache.cached_proofs.get(cache_key)?;
+            let entry_expired = cache.expiry_times.get(cache_key).is_some_and(|expiry| now > *expiry);
+            if !entry_expired && !cached.proof.is_expired(now) {
+                return Some(cached.proof.clone());
             }
-
-            Some(cached.proof.clone())
-        } else {
-            None
         }
+
+        // Drop the stale entry so the caller regenerates
+        debug!("Cached proof for {} expired; regenerating", cache_key);
+        let mut cache = self.proof_cache.write().await;
+        cache.cached_proofs.remove(cache_key);
+        cache.expiry_times.remove(cache_key);
+        cache.cache_statistics.total_entries = cache.cached_proofs.len();
+        None
     }
 
     async fn cache_proof(&self, cache_key: &str, proof: &ZKProof) {
@@ -725,10 +1228,10 @@ impl ZKProofSystem {
 
         cache.cached_proofs.insert(cache_key.to_string(), cached_proof);
 
-        // Set expiry time (24 hours)
+        // Cache entries expire with the proof
         cache.expiry_times.insert(
             cache_key.to_string(),
-            SystemTime::now() + Duration::from_secs(24 * 60 * 60)
+            proof.expires_at.unwrap_or_else(|| SystemTime::now() + self.config.max_proof_age)
         );
 
         // Update statistics
@@ -748,14 +1251,7 @@ impl ZKProofSystem {
     }
 
     async fn serialize_public_inputs(&self, batch: &SettlementBatch) -> Result<Vec<u8>> {
-        // Serialize public inputs (state roots, transaction count, etc.)
-        let mut inputs = Vec::new();
-        inputs.extend_from_slice(&batch.previous_state_root);
-        inputs.extend_from_slice(&batch.state_root);
-        inputs.extend_from_slice(&(batch.transactions.len() as u64).to_le_bytes());
-        inputs.extend_from_slice(&batch.gas_used.to_le_bytes());
-
-        Ok(inputs)
+        Ok(batch_public_inputs(&batch.previous_state_root, &batch.state_root, batch.transactions.len(), batch.gas_used))
     }
 
     async fn serialize_private_inputs(&self, batch: &SettlementBatch) -> Result<Vec<u8>> {
@@ -773,12 +1269,37 @@ impl ZKProofSystem {
     }
 
     async fn generate_state_transition_proof(&self, inputs: ProofInputs) -> Result<ZKProof> {
+        let circuit = self.select_circuit(&ProofType::StateTransition).await?;
+        self.generate_circuit_proof(&circuit, ProofType::StateTransition, inputs).await
+    }
+
+    async fn generate_circuit_proof(
+        &self,
+        circuit: &Circuit,
+        proof_type: ProofType,
+        inputs: ProofInputs,
+    ) -> Result<ZKProof> {
+        circuit.check_inputs(&inputs)?;
+
+        let worker = self.proof_worker.read().clone();
+        if let Some(worker) = worker {
+            match self.generate_remote_proof(worker.as_ref(), circuit, proof_type.clone(), &inputs).await {
+                Ok(proof) => return Ok(proof),
+                Err(e) if self.config.local_proof_fallback => {
+                    warn!("Proof worker {} failed, generating locally: {}", worker.name(), e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
         // TODO: Implement actual ZK proof generation
         // This would use a ZK library like arkworks, bellman, or circom
 
-        let proof_id = format!("proof-{}",
-                              SystemTime::now().duration_since(std::time::UNIX_EPOCH)
-                                  .unwrap_or_default().as_millis());
+        if !self.proof_generator.proving_keys.contains_key(&circuit.circuit_id) {
+            debug!("No proving key loaded for circuit {}", circuit.circuit_id);
+        }
+
+        let proof_id = self.id_generator.next_id("proof");
 
         let start_time = SystemTime::now();
 
@@ -787,25 +1308,78 @@ impl ZKProofSystem {
 
         let generation_time = start_time.elapsed().unwrap_or_default();
 
+        let proof_data = vec![0; 256]; // Placeholder proof data
+        let (proof_size, gas_cost_estimate) = circuit.proof_costs(&self.config.proof_gas_model, &proof_data);
+
         Ok(ZKProof {
             proof_id,
-            proof_type: ProofType::StateTransition,
-            proof_data: vec![0; 256], // Placeholder proof data
+            proof_type,
+            proof_data,
             public_inputs: inputs.public_inputs,
-            verification_key_id: "state_transition_vk".to_string(),
+            verification_key_id: format!("{}_vk", circuit.circuit_id),
             created_at: SystemTime::now(),
-            expires_at: Some(SystemTime::now() + Duration::from_secs(24 * 60 * 60)),
+            expires_at: Some(SystemTime::now() + self.config.max_proof_age),
             metadata: ProofMetadata {
-                circuit_name: "state_transition".to_string(),
-                proof_size: 256,
+                circuit_name: circuit.circuit_id.clone(),
+                proof_size,
                 generation_time,
                 verification_time: None,
-                gas_cost_estimate: 100_000,
+                gas_cost_estimate,
                 privacy_level: PrivacyLevel::Pseudonymous,
             },
         })
     }
 
+    async fn generate_remote_proof(
+        &self,
+        worker: &dyn ProofWorker,
+        circuit: &Circuit,
+        proof_type: ProofType,
+        inputs: &ProofInputs,
+    ) -> Result<ZKProof> {
+        let request = RemoteProofRequest {
+            request_id: self.id_generator.next_id("proof"),
+            circuit_id: circuit.circuit_id.clone(),
+            proof_type,
+            inputs: inputs.clone(),
+        };
+        debug!("Dispatching proof {} for circuit {} to worker {}", request.request_id, request.circuit_id, worker.name());
+
+        let response = worker.prove(&request).await?;
+        if response.proof_data.is_empty() {
+            return Err(BridgeError::Settlement(SettlementError::ZkProofFailed(format!(
+                "worker {} returned an empty proof", worker.name()
+            ))));
+        }
+
+        let (proof_size, gas_cost_estimate) = circuit.proof_costs(&self.config.proof_gas_model, &response.proof_data);
+        let proof = ZKProof {
+            proof_id: request.request_id,
+            proof_type: request.proof_type,
+            proof_data: response.proof_data,
+            public_inputs: request.inputs.public_inputs,
+            verification_key_id: format!("{}_vk", circuit.circuit_id),
+            created_at: SystemTime::now(),
+            expires_at: Some(SystemTime::now() + self.config.max_proof_age),
+            metadata: ProofMetadata {
+                circuit_name: circuit.circuit_id.clone(),
+                proof_size,
+                generation_time: response.generation_time,
+                verification_time: None,
+                gas_cost_estimate,
+                privacy_level: PrivacyLevel::Pseudonymous,
+            },
+        };
+
+        // The worker is outside the bridge's trust boundary
+        if !self.verify_proof(&proof).await? {
+            return Err(BridgeError::Settlement(SettlementError::ZkProofFailed(format!(
+                "worker {} returned a proof that does not verify", worker.name()
+            ))));
+        }
+        Ok(proof)
+    }
+
     async fn verify_proof_with_key(&self, _proof: &ZKProof, _key: &VerificationKey) -> Result<bool> {
         // TODO: Implement actual proof verification
         // This would use the verification key to verify the proof
@@ -836,9 +1410,7 @@ impl ZKProofSystem {
 
     async fn generate_aggregated_proof(&self, inputs: ProofInputs) -> Result<ZKProof> {
         // TODO: Implement actual proof aggregation
-        let proof_id = format!("agg-proof-{}",
-                              SystemTime::now().duration_since(std::time::UNIX_EPOCH)
-                                  .unwrap_or_default().as_millis());
+        let proof_id = self.id_generator.next_id("agg-proof");
 
         let start_time = SystemTime::now();
 
@@ -847,26 +1419,248 @@ impl ZKProofSystem {
 
         let generation_time = start_time.elapsed().unwrap_or_default();
 
+        let circuit = &self.aggregation_engine.aggregation_circuit;
+        let proof_data = vec![0; 512]; // Placeholder aggregated proof
+        let (proof_size, gas_cost_estimate) = circuit.proof_costs(&self.config.proof_gas_model, &proof_data);
+
         Ok(ZKProof {
             proof_id,
             proof_type: ProofType::AggregatedProof,
-            proof_data: vec![0; 512], // Placeholder aggregated proof
+            proof_data,
             public_inputs: inputs.public_inputs,
-            verification_key_id: "aggregation_vk".to_string(),
+            verification_key_id: format!("{}_vk", circuit.circuit_id),
             created_at: SystemTime::now(),
-            expires_at: Some(SystemTime::now() + Duration::from_secs(24 * 60 * 60)),
+            expires_at: Some(SystemTime::now() + self.config.max_proof_age),
             metadata: ProofMetadata {
-                circuit_name: "aggregation".to_string(),
-                proof_size: 512,
+                circuit_name: circuit.circuit_id.clone(),
+                proof_size,
                 generation_time,
                 verification_time: None,
-                gas_cost_estimate: 200_000,
+                gas_cost_estimate,
                 privacy_level: PrivacyLevel::Pseudonymous,
             },
         })
     }
 }
 
+/// Id of the built-in development setup
+const GENESIS_SETUP_ID: &str = "ghostbridge_setup_v1";
+
+impl TrustedSetup {
+    /// Built-in development setup, used when no ceremony output is configured;
+    /// its beacon and keys are public, so it secures nothing
+    fn genesis() -> Self {
+        let randomness_beacon = vec![0; 32];
+
+        let mut setup = Self {
+            setup_id: GENESIS_SETUP_ID.to_string(),
+            parameters: SetupParameters {
+                curve_type: CurveType::BN254,
+                security_level: 128,
+                randomness_beacon: randomness_beacon.clone(),
+                parameter_size: 100_000_000, // 100MB
+            },
+            ceremony_data: CeremonyData {
+                ceremony_id: "ghostbridge_ceremony_2024".to_string(),
+                participants: Vec::new(),
+                contributions: Vec::new(),
+                final_parameters: Vec::new(),
+                verification_transcript: Vec::new(),
+            },
+            verification_transcript: Vec::new(),
+            verification_keys: HashMap::new(),
+        };
+        setup.contribute("ghostbridge-genesis", vec![], randomness_beacon);
+        setup
+    }
+
+    /// Setup from a ceremony's published output; checked by `validate`
+    fn from_ceremony(ceremony: CeremonyOutput) -> Self {
+        let participants = ceremony.contributions.iter()
+            .map(|contribution| Participant {
+                participant_id: contribution.participant_id.clone(),
+                public_key: contribution.public_key.clone(),
+                contribution_hash: contribution.new_hash.clone(),
+                timestamp: SystemTime::now(),
+            })
+            .collect();
+        let contributions = ceremony.contributions.into_iter().enumerate()
+            .map(|(index, contribution)| Contribution {
+                contribution_id: format!("{}-{}", ceremony.ceremony_id, index),
+                participant_id: contribution.participant_id,
+                contribution_data: contribution.contribution_data,
+                previous_hash: contribution.previous_hash,
+                new_hash: contribution.new_hash,
+                verified: false,
+            })
+            .collect();
+
+        Self {
+            setup_id: ceremony.setup_id,
+            parameters: SetupParameters {
+                curve_type: CurveType::BN254,
+                security_level: 128,
+                randomness_beacon: ceremony.randomness_beacon,
+                parameter_size: ceremony.final_parameters.len(),
+            },
+            ceremony_data: CeremonyData {
+                ceremony_id: ceremony.ceremony_id,
+                participants,
+                contributions,
+                final_parameters: ceremony.final_parameters,
+                verification_transcript: ceremony.verification_transcript,
+            },
+            verification_transcript: Vec::new(),
+            verification_keys: ceremony.verification_keys,
+        }
+    }
+
+    /// Commitment to the final parameters and every verification key, in circuit id order
+    fn transcript_hash(final_parameters: &[u8], verification_keys: &HashMap<String, Vec<u8>>) -> Vec<u8> {
+        let mut keys: Vec<_> = verification_keys.iter().collect();
+        keys.sort();
+        let mut parts: Vec<&[u8]> = vec![final_parameters];
+        for (circuit_id, key) in keys {
+            parts.push(circuit_id.as_bytes());
+            parts.push(key);
+        }
+        setup_hash(&parts)
+    }
+
+    /// Hash the contribution chain starts from
+    fn genesis_hash(&self) -> Vec<u8> {
+        setup_hash(&[self.setup_id.as_bytes(), &self.parameters.randomness_beacon])
+    }
+
+    /// Append a contribution on top of the current chain head
+    fn contribute(&mut self, participant_id: &str, public_key: Vec<u8>, contribution_data: Vec<u8>) {
+        let previous_hash = self.ceremony_data.contributions.last()
+            .map(|c| c.new_hash.clone())
+            .unwrap_or_else(|| self.genesis_hash());
+        let new_hash = setup_hash(&[&previous_hash, &contribution_data]);
+
+        self.ceremony_data.participants.push(Participant {
+            participant_id: participant_id.to_string(),
+            public_key,
+            contribution_hash: new_hash.clone(),
+            timestamp: SystemTime::now(),
+        });
+        self.ceremony_data.contributions.push(Contribution {
+            contribution_id: format!("{}-{}", self.ceremony_data.ceremony_id, self.ceremony_data.contributions.len()),
+            participant_id: participant_id.to_string(),
+            contribution_data,
+            previous_hash,
+            new_hash: new_hash.clone(),
+            verified: false,
+        });
+
+        // The final parameters are the output of the latest contribution
+        self.ceremony_data.verification_transcript = Self::transcript_hash(&new_hash, &self.verification_keys);
+        self.ceremony_data.final_parameters = new_hash;
+    }
+
+    /// Verify the contribution chain and transcript before trusting the parameters
+    fn validate(&mut self) -> Result<()> {
+        let setup_id = self.setup_id.clone();
+        let invalid = |reason: String| {
+            BridgeError::Settlement(SettlementError::InvalidTrustedSetup(format!("{}: {}", setup_id, reason)))
+        };
+
+        let mut expected_previous = self.genesis_hash();
+        let ceremony = &self.ceremony_data;
+        if ceremony.contributions.is_empty() {
+            return Err(invalid("ceremony has no contributions".to_string()));
+        }
+
+        for contribution in &ceremony.contributions {
+            if contribution.previous_hash != expected_previous {
+                return Err(invalid(format!(
+                    "contribution {} does not chain to the previous contribution",
+                    contribution.contribution_id
+                )));
+            }
+            if setup_hash(&[&contribution.previous_hash, &contribution.contribution_data]) != contribution.new_hash {
+                return Err(invalid(format!("contribution {} hash mismatch", contribution.contribution_id)));
+            }
+
+            // A participant may contribute more than once; each contribution
+            // needs its own matching transcript entry
+            let known = ceremony.participants.iter()
+                .any(|p| p.participant_id == contribution.participant_id);
+            if !known {
+                return Err(invalid(format!(
+                    "contribution {} from unknown participant {}",
+                    contribution.contribution_id, contribution.participant_id
+                )));
+            }
+            let recorded = ceremony.participants.iter().any(|p| {
+                p.participant_id == contribution.participant_id && p.contribution_hash == contribution.new_hash
+            });
+            if !recorded {
+                return Err(invalid(format!(
+                    "participant {} transcript does not match contribution {}",
+                    contribution.participant_id, contribution.contribution_id
+                )));
+            }
+
+            expected_previous = contribution.new_hash.clone();
+        }
+
+        if ceremony.final_parameters != expected_previous {
+            return Err(invalid("final parameters do not match the last contribution".to_string()));
+        }
+        if Self::transcript_hash(&ceremony.final_parameters, &self.verification_keys) != ceremony.verification_transcript {
+            return Err(invalid("final parameters or verification keys do not match verification transcript".to_string()));
+        }
+
+        for contribution in &mut self.ceremony_data.contributions {
+            contribution.verified = true;
+        }
+        self.verification_transcript.push(SetupVerification {
+            verifier_id: "ghostbridge".to_string(),
+            verification_result: true,
+            verification_proof: expected_previous,
+            verified_at: SystemTime::now(),
+        });
+
+        debug!("Verified {} ceremony contributions for {}",
+               self.ceremony_data.contributions.len(), self.setup_id);
+        Ok(())
+    }
+
+    /// Verification key for a circuit from the ceremony output. The
+    /// development setup carries no keys and derives them from its final
+    /// parameters instead; those keys secure nothing.
+    fn verification_key(&self, circuit: &Circuit) -> Result<VerificationKey> {
+        let key_data = if self.verification_keys.is_empty() && self.setup_id == GENESIS_SETUP_ID {
+            setup_hash(&[&self.ceremony_data.final_parameters, circuit.circuit_id.as_bytes()])
+        } else {
+            self.verification_keys.get(&circuit.circuit_id).cloned().ok_or_else(|| {
+                BridgeError::Settlement(SettlementError::InvalidTrustedSetup(format!(
+                    "{}: no verification key for circuit {}", self.setup_id, circuit.circuit_id
+                )))
+            })?
+        };
+
+        Ok(VerificationKey {
+            key_id: format!("{}_vk", circuit.circuit_id),
+            circuit_id: circuit.circuit_id.clone(),
+            size_bytes: key_data.len(),
+            key_data,
+            created_at: SystemTime::now(),
+        })
+    }
+}
+
+fn setup_hash(parts: &[&[u8]]) -> Vec<u8> {
+    use sha2::{Sha256, Digest};
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().to_vec()
+}
+
 /// Proof status
 #[derive(Debug, Clone)]
 pub enum ProofStatus {
@@ -876,9 +1670,20 @@ pub enum ProofStatus {
     Failed(String),
 }
 
+/// Public inputs of a batch state-transition proof: state roots, transaction count, gas used
+pub fn batch_public_inputs(previous_state_root: &[u8], state_root: &[u8], transaction_count: usize, gas_used: u64) -> Vec<u8> {
+    let mut inputs = Vec::new();
+    inputs.extend_from_slice(previous_state_root);
+    inputs.extend_from_slice(state_root);
+    inputs.extend_from_slice(&(transaction_count as u64).to_le_bytes());
+    inputs.extend_from_slice(&gas_used.to_le_bytes());
+    inputs
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::fixtures;
 
     #[tokio::test]
     async fn test_zk_proof_system_creation() {
@@ -887,6 +1692,398 @@ mod tests {
         assert!(zk_system.is_healthy().await);
     }
 
+    #[tokio::test]
+    async fn test_seeded_request_ids_are_reproducible() {
+        use crate::idgen::SeededIdGenerator;
+
+        async fn request_ids(seed: u64) -> Vec<String> {
+            let zk_system = ZKProofSystem::new(SettlementConfig::default())
+                .await
+                .unwrap()
+                .with_id_generator(Arc::new(SeededIdGenerator::new(seed)));
+
+            let mut ids = Vec::new();
+            for _ in 0..3 {
+                let inputs = ProofInputs {
+                    public_inputs: vec![],
+                    private_inputs: vec![],
+                    auxiliary_data: vec![],
+                };
+                ids.push(zk_system
+                    .submit_proof_request(
+                        ProofType::StateTransition,
+                        inputs,
+                        ProofPriority::Normal,
+                    )
+                    .await
+                    .unwrap());
+            }
+            ids
+        }
+
+        let first = request_ids(1234).await;
+        assert_eq!(first, request_ids(1234).await);
+        assert!(first.iter().all(|id| id.starts_with("req-")));
+    }
+
+    fn test_proof(proof_id: &str) -> ZKProof {
+        ZKProof {
+            proof_id: proof_id.to_string(),
+            proof_type: ProofType::StateTransition,
+            proof_data: vec![0; 256],
+            public_inputs: vec![1, 2, 3],
+            verification_key_id: "state_transition_vk".to_string(),
+            created_at: SystemTime::now(),
+            expires_at: None,
+            metadata: ProofMetadata {
+                circuit_name: "state_transition".to_string(),
+                proof_size: 256,
+                generation_time: Duration::default(),
+                verification_time: None,
+                gas_cost_estimate: 100_000,
+                privacy_level: PrivacyLevel::Pseudonymous,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_aggregation_triggers_at_threshold() {
+        let config = SettlementConfig {
+            proof_aggregation_threshold: 3,
+            ..SettlementConfig::default()
+        };
+        let zk_system = ZKProofSystem::new(config).await.unwrap();
+
+        assert!(zk_system.queue_for_aggregation(test_proof("p1")).await.unwrap().is_none());
+        assert!(zk_system.queue_for_aggregation(test_proof("p2")).await.unwrap().is_none());
+        assert_eq!(zk_system.pending_aggregation_count().await, 2);
+
+        let aggregated = zk_system.queue_for_aggregation(test_proof("p3")).await.unwrap()
+            .expect("threshold reached");
+        assert_eq!(aggregated.proof_type, ProofType::AggregatedProof);
+        assert_eq!(aggregated.public_inputs.len(), 9);
+        assert_eq!(zk_system.pending_aggregation_count().await, 0);
+        assert_eq!(zk_system.aggregated_proofs().await.len(), 1);
+    }
+
+    fn empty_inputs() -> ProofInputs {
+        ProofInputs {
+            public_inputs: vec![],
+            private_inputs: vec![],
+            auxiliary_data: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verification_keys_loaded_from_setup() {
+        let zk_system = ZKProofSystem::new(SettlementConfig::default()).await.unwrap();
+
+        for key_id in ["state_transition_vk", "balance_proof_vk", "membership_proof_vk", "aggregation_vk"] {
+            assert!(zk_system.verifier.verification_keys.read().await.contains_key(key_id), "missing {}", key_id);
+        }
+
+        let proof = zk_system.generate_proof(ProofType::StateTransition, empty_inputs()).await.unwrap();
+        assert!(zk_system.verify_proof(&proof).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_key_rotation_invalidates_cached_results() {
+        let zk_system = ZKProofSystem::new(SettlementConfig::default()).await.unwrap();
+        let proof = zk_system.generate_proof(ProofType::StateTransition, empty_inputs()).await.unwrap();
+        let other = zk_system.generate_proof(ProofType::BalanceProof, empty_inputs()).await.unwrap();
+        assert!(zk_system.verify_proof(&proof).await.unwrap());
+        assert!(zk_system.verify_proof(&other).await.unwrap());
+
+        let cached_at = |proof_id: String| {
+            let cache = zk_system.verifier.verification_cache.clone();
+            async move { cache.read().await.get(&proof_id).map(|result| result.verified_at) }
+        };
+        let first_verified_at = cached_at(proof.proof_id.clone()).await.expect("result cached");
+
+        zk_system.rotate_verification_key(&proof.verification_key_id, vec![7; 32]).await.unwrap();
+        assert!(cached_at(proof.proof_id.clone()).await.is_none());
+        // Results against other keys are kept
+        assert!(cached_at(other.proof_id.clone()).await.is_some());
+
+        // The next check re-verifies against the new key
+        assert!(zk_system.verify_proof(&proof).await.unwrap());
+        assert!(cached_at(proof.proof_id.clone()).await.expect("re-cached") > first_verified_at);
+
+        // A revoked key cannot vouch for the proof through the cache
+        zk_system.revoke_verification_key(&proof.verification_key_id).await.unwrap();
+        assert!(matches!(
+            zk_system.verify_proof(&proof).await,
+            Err(BridgeError::Settlement(SettlementError::UnknownVerificationKey { .. }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cached_result_is_bound_to_its_verification_key() {
+        let zk_system = ZKProofSystem::new(SettlementConfig::default()).await.unwrap();
+        let proof = zk_system.generate_proof(ProofType::StateTransition, empty_inputs()).await.unwrap();
+        assert!(zk_system.verify_proof(&proof).await.unwrap());
+
+        // The same proof id claiming a key that does not exist is not
+        // answered from the cache
+        let relabelled = ZKProof { verification_key_id: "unregistered".to_string(), ..proof.clone() };
+        assert!(matches!(
+            zk_system.verify_proof(&relabelled).await,
+            Err(BridgeError::Settlement(SettlementError::UnknownVerificationKey { .. }))
+        ));
+    }
+
+    async fn generate_concurrently(zk_system: &Arc<ZKProofSystem>, proof_type: ProofType, count: usize) -> Duration {
+        let started = std::time::Instant::now();
+        let tasks: Vec<_> = (0..count)
+            .map(|_| {
+                let zk_system = zk_system.clone();
+                let proof_type = proof_type.clone();
+                tokio::spawn(async move { zk_system.generate_proof(proof_type, empty_inputs()).await })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().expect("queued generations complete");
+        }
+        started.elapsed()
+    }
+
+    #[tokio::test]
+    async fn test_generation_limit_queues_excess_requests() {
+        let config = SettlementConfig {
+            max_concurrent_proof_generations: 2,
+            ..SettlementConfig::default()
+        };
+        let zk_system = Arc::new(ZKProofSystem::new(config).await.unwrap());
+
+        // Five 100ms generations through two slots take at least three rounds
+        let observer = {
+            let zk_system = zk_system.clone();
+            tokio::spawn(async move {
+                let mut saturated = false;
+                for _ in 0..20 {
+                    saturated |= zk_system.generation_limiter.available_permits() == 0;
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+                saturated
+            })
+        };
+        let elapsed = generate_concurrently(&zk_system, ProofType::StateTransition, 5).await;
+
+        assert!(elapsed >= Duration::from_millis(300), "ran in {:?}", elapsed);
+        assert!(observer.await.unwrap(), "limit was never reached");
+        assert_eq!(zk_system.generation_limiter.available_permits(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_per_circuit_limit_overrides_global() {
+        let config = SettlementConfig {
+            max_concurrent_proof_generations: 8,
+            circuit_proof_concurrency: HashMap::from([("membership_proof".to_string(), 1)]),
+            ..SettlementConfig::default()
+        };
+        let zk_system = Arc::new(ZKProofSystem::new(config).await.unwrap());
+
+        // Membership proofs run one at a time; other circuits use the global limit
+        let membership = generate_concurrently(&zk_system, ProofType::MembershipProof, 3).await;
+        assert!(membership >= Duration::from_millis(300), "ran in {:?}", membership);
+
+        let balance = generate_concurrently(&zk_system, ProofType::BalanceProof, 3).await;
+        assert!(balance < Duration::from_millis(300), "ran in {:?}", balance);
+    }
+
+    #[tokio::test]
+    async fn test_expired_proof_rejected_and_regenerated() {
+        let config = SettlementConfig {
+            max_proof_age: Duration::from_millis(200),
+            ..SettlementConfig::default()
+        };
+        let zk_system = ZKProofSystem::new(config).await.unwrap();
+        let batch = fixtures::batch("batch-1");
+
+        let proof = zk_system.proof_for_submission(&batch).await.unwrap();
+        assert_eq!(zk_system.proof_for_submission(&batch).await.unwrap().proof_id, proof.proof_id);
+
+        tokio::time::sleep(Duration::from_millis(250)).await;
+
+        // The cached verification result doesn't outlive the proof
+        assert!(matches!(
+            zk_system.verify_proof(&proof).await,
+            Err(BridgeError::Settlement(SettlementError::ProofExpired { .. }))
+        ));
+
+        let regenerated = zk_system.proof_for_submission(&batch).await.unwrap();
+        assert_ne!(regenerated.proof_id, proof.proof_id);
+        assert!(!regenerated.is_expired(SystemTime::now()));
+    }
+
+    #[tokio::test]
+    async fn test_setup_with_mismatched_transcript_rejected() {
+        let mut setup = TrustedSetup::genesis();
+        setup.ceremony_data.final_parameters[0] ^= 0xff;
+
+        let result = ZKProofSystem::with_trusted_setup(SettlementConfig::default(), setup).await;
+        assert!(matches!(
+            result,
+            Err(BridgeError::Settlement(SettlementError::InvalidTrustedSetup(_)))
+        ));
+    }
+
+    fn multi_party_setup() -> TrustedSetup {
+        let mut setup = TrustedSetup::genesis();
+        setup.contribute("alice", vec![1; 32], vec![0xa1; 64]);
+        setup.contribute("bob", vec![2; 32], vec![0xb0; 64]);
+        setup
+    }
+
+    #[tokio::test]
+    async fn test_valid_ceremony_chain_loads() {
+        let zk_system = ZKProofSystem::with_trusted_setup(SettlementConfig::default(), multi_party_setup())
+            .await
+            .unwrap();
+
+        assert_eq!(zk_system.trusted_setup.ceremony_data.contributions.len(), 3);
+        assert!(zk_system.trusted_setup.ceremony_data.contributions.iter().all(|c| c.verified));
+        assert_eq!(zk_system.trusted_setup.verification_transcript.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tampered_contribution_rejected() {
+        let mut setup = multi_party_setup();
+        setup.ceremony_data.contributions[1].contribution_data[0] ^= 0xff;
+
+        let result = ZKProofSystem::with_trusted_setup(SettlementConfig::default(), setup).await;
+        assert!(matches!(
+            result,
+            Err(BridgeError::Settlement(SettlementError::InvalidTrustedSetup(_)))
+        ));
+    }
+
+    /// Ceremony output for a three-contribution chain run under its own setup id
+    fn ceremony_output(verification_keys: HashMap<String, Vec<u8>>) -> CeremonyOutput {
+        let mut setup = TrustedSetup::from_ceremony(CeremonyOutput {
+            setup_id: "production_setup".to_string(),
+            ceremony_id: "production_ceremony".to_string(),
+            randomness_beacon: vec![7; 32],
+            contributions: vec![],
+            final_parameters: vec![],
+            verification_transcript: vec![],
+            verification_keys,
+        });
+        // Alice contributes twice
+        setup.contribute("alice", vec![1; 32], vec![0xa1; 64]);
+        setup.contribute("bob", vec![2; 32], vec![0xb0; 64]);
+        setup.contribute("alice", vec![1; 32], vec![0xa2; 64]);
+
+        CeremonyOutput {
+            setup_id: setup.setup_id,
+            ceremony_id: setup.ceremony_data.ceremony_id,
+            randomness_beacon: setup.parameters.randomness_beacon,
+            contributions: setup.ceremony_data.contributions.into_iter()
+                .map(|c| CeremonyContribution {
+                    participant_id: c.participant_id,
+                    public_key: vec![],
+                    contribution_data: c.contribution_data,
+                    previous_hash: c.previous_hash,
+                    new_hash: c.new_hash,
+                })
+                .collect(),
+            final_parameters: setup.ceremony_data.final_parameters,
+            verification_transcript: setup.ceremony_data.verification_transcript,
+            verification_keys: setup.verification_keys,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ceremony_output_loads_its_keys() {
+        let mut verification_keys: HashMap<String, Vec<u8>> =
+            ["state_transition", "balance_proof", "membership_proof", "aggregation"].iter()
+                .map(|circuit_id| (circuit_id.to_string(), circuit_id.as_bytes().to_vec()))
+                .collect();
+        let ceremony = ceremony_output(verification_keys.clone());
+
+        let zk_system = ZKProofSystem::with_ceremony(SettlementConfig::default(), ceremony.clone()).await.unwrap();
+        assert_eq!(zk_system.verifier.verification_keys.read().await["balance_proof_vk"].key_data, b"balance_proof");
+
+        // Swapping a key breaks the transcript
+        let mut swapped = ceremony;
+        swapped.verification_keys.insert("balance_proof".to_string(), vec![0; 32]);
+        assert!(ZKProofSystem::with_ceremony(SettlementConfig::default(), swapped).await.is_err());
+
+        // A ceremony without a key for every circuit is refused
+        verification_keys.remove("membership_proof");
+        let missing = ceremony_output(verification_keys);
+        assert!(ZKProofSystem::with_ceremony(SettlementConfig::default(), missing).await.is_err());
+    }
+
+    #[test]
+    fn test_broken_contribution_chain_rejected() {
+        let mut setup = multi_party_setup();
+        setup.ceremony_data.contributions[2].previous_hash = vec![0; 32];
+
+        assert!(setup.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_balance_proof_uses_balance_circuit() {
+        let zk_system = ZKProofSystem::new(SettlementConfig::default()).await.unwrap();
+
+        let proof = zk_system.generate_proof(ProofType::BalanceProof, empty_inputs()).await.unwrap();
+        assert_eq!(proof.proof_type, ProofType::BalanceProof);
+        assert_eq!(proof.metadata.circuit_name, "balance_proof");
+        assert_eq!(proof.verification_key_id, "balance_proof_vk");
+
+        let request_id = zk_system
+            .submit_proof_request(ProofType::BalanceProof, empty_inputs(), ProofPriority::Normal)
+            .await
+            .unwrap();
+        let queue = zk_system.proof_queue.read().await;
+        let request = queue.pending_proofs.iter().find(|r| r.request_id == request_id).unwrap();
+        assert_eq!(request.circuit_id, "balance_proof");
+    }
+
+    #[tokio::test]
+    async fn test_proof_costs_reported_per_circuit() {
+        let model = ProofGasModel { base_verification_gas: 100_000, gas_per_public_input: 1_000, calldata_gas_per_byte: 10 };
+        let config = SettlementConfig { proof_gas_model: model.clone(), ..SettlementConfig::default() };
+        let zk_system = ZKProofSystem::new(config).await.unwrap();
+
+        let state = zk_system.generate_proof(ProofType::StateTransition, empty_inputs()).await.unwrap();
+        let balance = zk_system.generate_proof(ProofType::BalanceProof, empty_inputs()).await.unwrap();
+        let aggregated = zk_system.aggregate_proofs(vec![state.clone(), balance.clone()]).await.unwrap();
+
+        // Sizes come from the proof bytes; gas from each circuit's public inputs
+        for proof in [&state, &balance, &aggregated] {
+            assert_eq!(proof.metadata.proof_size, proof.proof_data.len());
+        }
+        assert_ne!(state.metadata.proof_size, aggregated.metadata.proof_size);
+        assert_eq!(state.metadata.gas_cost_estimate, model.estimate(10, 256));
+        assert_eq!(balance.metadata.gas_cost_estimate, model.estimate(5, 256));
+        assert_eq!(aggregated.metadata.gas_cost_estimate, model.estimate(100, 512));
+        assert!(balance.metadata.gas_cost_estimate < state.metadata.gas_cost_estimate);
+        assert!(state.metadata.gas_cost_estimate < aggregated.metadata.gas_cost_estimate);
+    }
+
+    #[tokio::test]
+    async fn test_membership_proof_uses_membership_circuit() {
+        let zk_system = ZKProofSystem::new(SettlementConfig::default()).await.unwrap();
+
+        let proof = zk_system.generate_proof(ProofType::MembershipProof, empty_inputs()).await.unwrap();
+        assert_eq!(proof.metadata.circuit_name, "membership_proof");
+        assert_eq!(proof.verification_key_id, "membership_proof_vk");
+    }
+
+    #[tokio::test]
+    async fn test_inactive_circuit_rejected() {
+        let zk_system = ZKProofSystem::new(SettlementConfig::default()).await.unwrap();
+        zk_system.circuit_registry.write().await.active_circuits.retain(|id| id != "membership_proof");
+
+        let result = zk_system.generate_proof(ProofType::MembershipProof, empty_inputs()).await;
+        assert!(matches!(
+            result,
+            Err(BridgeError::Settlement(SettlementError::InactiveCircuit { .. }))
+        ));
+    }
+
     #[test]
     fn test_proof_type_serialization() {
         let proof_type = ProofType::StateTransition;
@@ -894,4 +2091,123 @@ mod tests {
         let deserialized: ProofType = serde_json::from_str(&serialized).unwrap();
         assert_eq!(proof_type, deserialized);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_proofs_dispatched_to_remote_worker() {
+        use crate::settlement::proof_workers::RemoteProofResponse;
+        use async_trait::async_trait;
+
+        /// Records requests; fails every request when `fail` is set
+        struct StubWorker {
+            requests: parking_lot::Mutex<Vec<RemoteProofRequest>>,
+            fail: bool,
+        }
+
+        #[async_trait]
+        impl ProofWorker for StubWorker {
+            fn name(&self) -> &str {
+                "stub"
+            }
+
+            async fn prove(&self, request: &RemoteProofRequest) -> Result<RemoteProofResponse> {
+                self.requests.lock().push(request.clone());
+                if self.fail {
+                    return Err(BridgeError::Settlement(SettlementError::ZkProofFailed("worker offline".to_string())));
+                }
+                Ok(RemoteProofResponse { proof_data: vec![0xab; 128], generation_time: Duration::from_millis(5) })
+            }
+        }
+
+        let inputs = ProofInputs { public_inputs: vec![1, 2, 3], private_inputs: vec![4], auxiliary_data: vec![] };
+        let worker = Arc::new(StubWorker { requests: parking_lot::Mutex::new(Vec::new()), fail: false });
+        let zk_system = ZKProofSystem::new(SettlementConfig::default()).await.unwrap()
+            .with_proof_worker(worker.clone());
+
+        let proof = zk_system.generate_proof(ProofType::StateTransition, inputs.clone()).await.unwrap();
+        assert_eq!(proof.proof_data, vec![0xab; 128]);
+        assert_eq!(proof.public_inputs, vec![1, 2, 3]);
+        assert!(zk_system.verify_proof(&proof).await.unwrap());
+
+        let requests = worker.requests.lock().clone();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].circuit_id, "state_transition");
+        assert_eq!(requests[0].inputs.private_inputs, vec![4]);
+
+        // A failing worker falls back to local generation unless disabled
+        let offline = Arc::new(StubWorker { requests: parking_lot::Mutex::new(Vec::new()), fail: true });
+        let zk_system = ZKProofSystem::new(SettlementConfig::default()).await.unwrap()
+            .with_proof_worker(offline.clone());
+        assert!(zk_system.generate_proof(ProofType::StateTransition, inputs.clone()).await.is_ok());
+
+        let config = SettlementConfig { local_proof_fallback: false, ..SettlementConfig::default() };
+        let zk_system = ZKProofSystem::new(config).await.unwrap().with_proof_worker(offline.clone());
+        assert!(zk_system.generate_proof(ProofType::StateTransition, inputs.clone()).await.is_err());
+        assert_eq!(offline.requests.lock().len(), 2);
+
+        // A worker's proof is only accepted once it verifies
+        let config = SettlementConfig { local_proof_fallback: false, ..SettlementConfig::default() };
+        let zk_system = ZKProofSystem::new(config).await.unwrap();
+        zk_system.set_proof_worker(worker.clone());
+        zk_system.revoke_verification_key("state_transition_vk").await.unwrap();
+        assert!(zk_system.generate_proof(ProofType::StateTransition, inputs).await.is_err());
+        assert_eq!(worker.requests.lock().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_proof_queue_applies_backpressure() {
+        let config = SettlementConfig { proof_queue_capacity: 2, ..SettlementConfig::default() };
+        let zk_system = Arc::new(ZKProofSystem::new(config).await.unwrap());
+        let submit = || zk_system.submit_proof_request(ProofType::BalanceProof, empty_inputs(), ProofPriority::Normal);
+
+        let first = submit().await.unwrap();
+        submit().await.unwrap();
+        assert_eq!(zk_system.proof_queue_available(), 0);
+        assert!(!zk_system.is_healthy().await);
+
+        // Full: rejected outright, or parked until a slot frees
+        let err = submit().await.unwrap_err();
+        assert!(matches!(err, BridgeError::Settlement(SettlementError::ProofQueueFull { capacity: 2 })));
+
+        let waiting = tokio::spawn({
+            let zk_system = zk_system.clone();
+            async move {
+                zk_system.submit_proof_request_when_ready(ProofType::BalanceProof, empty_inputs(), ProofPriority::Normal).await
+            }
+        });
+        tokio::task::yield_now().await;
+        assert!(!waiting.is_finished());
+
+        // Completing a proof frees its slot for the waiting submission
+        assert_eq!(zk_system.process_next_proof_request().await, Some(first.clone()));
+        assert!(matches!(zk_system.get_proof_status(&first).await.unwrap(), ProofStatus::Completed));
+        let admitted = tokio::time::timeout(Duration::from_secs(1), waiting).await.unwrap().unwrap().unwrap();
+        assert!(matches!(zk_system.get_proof_status(&admitted).await.unwrap(), ProofStatus::Pending));
+        assert_eq!(zk_system.proof_queue_available(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_batch_exceeding_circuit_inputs_rejected() {
+        let transfer = fixtures::transfer(1, 2, 10);
+
+        let zk_system = ZKProofSystem::new(SettlementConfig::default()).await.unwrap();
+        let capacity = zk_system.max_batch_transactions().await.unwrap();
+        assert!(capacity >= SettlementConfig::default().batch_size);
+
+        let full = SettlementBatch { transactions: vec![transfer.clone(); capacity], ..fixtures::batch("batch-1") };
+        assert!(zk_system.generate_batch_proof(&full).await.is_ok());
+
+        // One more transaction no longer fits the witness
+        let oversized = SettlementBatch {
+            batch_id: "batch-2".to_string(),
+            transactions: vec![transfer; capacity + 1],
+            ..fixtures::batch("batch-1")
+        };
+        let error = zk_system.generate_batch_proof(&oversized).await.unwrap_err();
+        assert!(matches!(
+            &error,
+            BridgeError::Settlement(SettlementError::ProofInputsExceedCircuit { kind, required, capacity: 50_000, .. })
+                if kind == "private" && *required > 50_000
+        ), "{}", error);
+        assert!(error.to_string().contains("state_transition"));
+    }
+}
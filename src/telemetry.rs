@@ -0,0 +1,240 @@
+/*!
+Distributed tracing export
+
+With the `otel` feature, spans are exported over OTLP so a bridge request can
+be followed from the bridge through settlement to finality, and into the Zig
+L2. The trace context crosses process boundaries as a W3C `traceparent`:
+QUIC requests carry it in their `WireEnvelope`, and the FFI hands it to
+GhostPlane before each submission. Settlement batches transactions from many
+traces and finalizes them much later on background tasks, so `BatchTraces`
+remembers each transaction's context and parents batch spans on it, until
+the transaction finalizes or leaves settlement some other way.
+
+Without the feature no context is captured and every call here is a no-op.
+*/
+
+use crate::types::Transaction;
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{warn, Span};
+use uuid::Uuid;
+
+/// Transactions whose trace context is remembered until their batch finalizes
+pub const MAX_TRACKED_TRANSACTIONS: usize = 100_000;
+
+/// OTLP export settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    /// OTLP/gRPC collector endpoint
+    pub otlp_endpoint: String,
+    /// `service.name` reported with every span
+    pub service_name: String,
+    /// Fraction of new traces sampled; traces started upstream follow the caller's decision
+    pub sample_ratio: f64,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: "http://localhost:4317".to_string(),
+            service_name: "ghostbridge".to_string(),
+            sample_ratio: 1.0,
+        }
+    }
+}
+
+/// W3C `traceparent` of a span, carried across process boundaries
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceContext(pub String);
+
+#[cfg(feature = "otel")]
+impl TraceContext {
+    /// Context of the current span, if it is being traced
+    pub fn current() -> Option<Self> {
+        use opentelemetry::propagation::TextMapPropagator;
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        let mut carrier = std::collections::HashMap::new();
+        opentelemetry_sdk::propagation::TraceContextPropagator::new()
+            .inject_context(&Span::current().context(), &mut carrier);
+        carrier.remove("traceparent").map(TraceContext)
+    }
+
+    /// Make `span` a child of this context; call before entering it
+    pub fn attach(&self, span: &Span) {
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+        span.set_parent(self.extract());
+    }
+
+    /// Link `span` to this context without making it the parent
+    pub fn link(&self, span: &Span) {
+        use opentelemetry::trace::TraceContextExt;
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+        span.add_link(self.extract().span().span_context().clone());
+    }
+
+    fn extract(&self) -> opentelemetry::Context {
+        use opentelemetry::propagation::TextMapPropagator;
+
+        let carrier = std::collections::HashMap::from([("traceparent".to_string(), self.0.clone())]);
+        opentelemetry_sdk::propagation::TraceContextPropagator::new().extract(&carrier)
+    }
+}
+
+#[cfg(not(feature = "otel"))]
+impl TraceContext {
+    /// Context of the current span; never captured without the `otel` feature
+    pub fn current() -> Option<Self> {
+        None
+    }
+
+    pub fn attach(&self, _span: &Span) {}
+
+    pub fn link(&self, _span: &Span) {}
+}
+
+/// Trace contexts of transactions waiting in settlement, by transaction id
+#[derive(Debug, Default)]
+pub struct BatchTraces {
+    contexts: DashMap<Uuid, TraceContext>,
+    /// Set while new contexts are dropped for lack of room, so it's logged once
+    saturated: AtomicBool,
+}
+
+impl BatchTraces {
+    /// Remember the current trace context for a transaction
+    pub fn record_current(&self, transaction_id: Uuid) {
+        let Some(context) = TraceContext::current() else {
+            return;
+        };
+        if self.contexts.len() < MAX_TRACKED_TRANSACTIONS {
+            self.contexts.insert(transaction_id, context);
+            self.saturated.store(false, Ordering::Relaxed);
+        } else if !self.saturated.swap(true, Ordering::Relaxed) {
+            warn!("Tracking {} transaction trace contexts; new transactions won't be traced through settlement until some finalize",
+                  MAX_TRACKED_TRANSACTIONS);
+        }
+    }
+
+    /// Parent `span` on the first traced transaction and link it to the
+    /// rest, so each bridged transaction's trace reaches the batch
+    pub fn batch_span(&self, span: Span, transactions: &[Transaction]) -> Span {
+        let mut contexts = transactions.iter()
+            .filter_map(|transaction| self.contexts.get(&transaction.id).map(|context| context.clone()));
+        if let Some(parent) = contexts.next() {
+            parent.attach(&span);
+        }
+        for context in contexts {
+            context.link(&span);
+        }
+        span
+    }
+
+    /// Drop the contexts of transactions that finalized or left settlement
+    pub fn forget(&self, transactions: &[Transaction]) {
+        for transaction in transactions {
+            self.contexts.remove(&transaction.id);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.contexts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contexts.is_empty()
+    }
+}
+
+/// Tracing layer exporting spans to the configured OTLP collector
+#[cfg(feature = "otel")]
+pub fn otlp_layer<S>(config: &TelemetryConfig) -> crate::error::Result<impl tracing_subscriber::Layer<S>>
+where
+    S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+{
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::trace::{self, Sampler};
+
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(config.sample_ratio)));
+    let resource = opentelemetry_sdk::Resource::new(vec![
+        opentelemetry::KeyValue::new("service.name", config.service_name.clone()),
+    ]);
+
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(&config.otlp_endpoint))
+        .with_trace_config(trace::config().with_sampler(sampler).with_resource(resource))
+        .install_batch(opentelemetry_sdk::runtime::Tokio)
+        .map_err(|e| crate::error::BridgeError::Config(format!("OTLP exporter: {}", e)))?;
+
+    Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+#[cfg(all(test, feature = "otel"))]
+mod tests {
+    use super::*;
+    use crate::transport::{ProtocolConfig, WireEnvelope};
+    use crate::types::fixtures::transfer;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+    use tracing::info_span;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_bridged_transaction_traced_through_settlement_and_finality() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = opentelemetry_sdk::trace::TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("ghostbridge")));
+        let _default = tracing::subscriber::set_default(subscriber);
+
+        let traces = BatchTraces::default();
+        let batch = vec![transfer(1, 2, 10), transfer(3, 4, 10)];
+
+        // Bridge stage: each transaction is submitted under its own request
+        for transaction in &batch {
+            let _bridge = info_span!("process_bridge_transaction").entered();
+            traces.record_current(transaction.id);
+        }
+        assert_eq!(traces.len(), 2);
+
+        // Settlement runs later on a background task, outside any request
+        let frame = {
+            let _settle = traces.batch_span(info_span!("process_batch"), &batch).entered();
+            WireEnvelope::new("submit_batch".to_string()).encode().unwrap()
+        };
+
+        // The remote end of the QUIC hop continues the trace
+        let (negotiated, _) = ProtocolConfig::default().decode_request::<String>(&frame).unwrap();
+        let remote = info_span!("remote_submit_batch");
+        negotiated.trace_context.as_ref().unwrap().attach(&remote);
+        drop(remote);
+
+        // Finality: the batch finalizes long after, again on a background task
+        drop(traces.batch_span(info_span!("finalize_batch"), &batch).entered());
+        traces.forget(&batch);
+        assert!(traces.is_empty());
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let span = |name: &str| spans.iter().find(|span| span.name == name).unwrap();
+        let bridge = span("process_bridge_transaction");
+        let settle = span("process_batch");
+        let remote = span("remote_submit_batch");
+        let finalize = span("finalize_batch");
+
+        // One connected trace: bridge -> settlement -> remote GhostPlane, bridge -> finality
+        let trace_id = bridge.span_context.trace_id();
+        for stage in [settle, remote, finalize] {
+            assert_eq!(stage.span_context.trace_id(), trace_id, "{} left the trace", stage.name);
+        }
+        assert_eq!(settle.parent_span_id, bridge.span_context.span_id());
+        assert_eq!(remote.parent_span_id, settle.span_context.span_id());
+        assert_eq!(finalize.parent_span_id, bridge.span_context.span_id());
+
+        // The second transaction's trace is linked to the batch it joined
+        assert_eq!(settle.links.len(), 1);
+    }
+}
@@ -0,0 +1,199 @@
+/*!
+Payload compression for QUIC streams
+
+When enabled, every payload is framed with a one-byte header naming the
+codec used for the rest of the frame. Payloads under the size threshold, or
+that don't shrink, are sent raw behind a `None` header, so the receiver
+always knows how to decode regardless of the sender's settings.
+*/
+
+use crate::error::{BridgeError, NetworkError, Result};
+use crate::transport::TransportMetrics;
+use serde::{Deserialize, Serialize};
+
+/// Compression codec, also used as the frame header byte
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompressionAlgorithm {
+    #[default]
+    None,
+    Zstd,
+    Lz4,
+}
+
+impl CompressionAlgorithm {
+    fn header(self) -> u8 {
+        match self {
+            CompressionAlgorithm::None => 0x00,
+            CompressionAlgorithm::Zstd => 0x01,
+            CompressionAlgorithm::Lz4 => 0x02,
+        }
+    }
+
+    fn from_header(byte: u8) -> Result<Self> {
+        match byte {
+            0x00 => Ok(CompressionAlgorithm::None),
+            0x01 => Ok(CompressionAlgorithm::Zstd),
+            0x02 => Ok(CompressionAlgorithm::Lz4),
+            other => Err(invalid(format!("unknown compression header 0x{:02x}", other))),
+        }
+    }
+}
+
+/// Payload compression settings; both peers must enable framing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressionConfig {
+    /// Frame payloads with a compression header byte
+    pub enabled: bool,
+    /// Codec used for outgoing payloads
+    pub algorithm: CompressionAlgorithm,
+    /// Payloads smaller than this are sent uncompressed
+    pub threshold_bytes: usize,
+    pub zstd_level: i32,
+    /// Largest payload accepted after decompression
+    pub max_decompressed_bytes: usize,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            algorithm: CompressionAlgorithm::Zstd,
+            threshold_bytes: 1024,
+            zstd_level: 3,
+            max_decompressed_bytes: 1024 * 1024, // 1MB, matching the response read limit
+        }
+    }
+}
+
+/// Encodes and decodes stream payloads, recording wire bytes in the transport metrics
+pub struct PayloadCodec<'a> {
+    config: &'a CompressionConfig,
+    metrics: &'a TransportMetrics,
+}
+
+impl<'a> PayloadCodec<'a> {
+    pub fn new(config: &'a CompressionConfig, metrics: &'a TransportMetrics) -> Self {
+        Self { config, metrics }
+    }
+
+    /// Frame an outgoing payload
+    pub fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let frame = if self.config.enabled {
+            encode_frame(data, self.config)?
+        } else {
+            data.to_vec()
+        };
+        self.metrics.record_data_sent(frame.len());
+        Ok(frame)
+    }
+
+    /// Decode an incoming frame
+    pub fn decode(&self, frame: &[u8]) -> Result<Vec<u8>> {
+        self.metrics.record_data_received(frame.len());
+        if self.config.enabled {
+            decode_frame(frame, self.config.max_decompressed_bytes)
+        } else {
+            Ok(frame.to_vec())
+        }
+    }
+}
+
+fn encode_frame(data: &[u8], config: &CompressionConfig) -> Result<Vec<u8>> {
+    let compressed = match config.algorithm {
+        _ if data.len() < config.threshold_bytes => None,
+        CompressionAlgorithm::None => None,
+        CompressionAlgorithm::Zstd => Some(
+            zstd::bulk::compress(data, config.zstd_level)
+                .map_err(|e| invalid(format!("zstd compression failed: {}", e)))?,
+        ),
+        CompressionAlgorithm::Lz4 => Some(lz4_flex::compress_prepend_size(data)),
+    };
+
+    let (algorithm, body) = match compressed {
+        Some(body) if body.len() < data.len() => (config.algorithm, body),
+        _ => (CompressionAlgorithm::None, data.to_vec()),
+    };
+
+    let mut frame = Vec::with_capacity(body.len() + 1);
+    frame.push(algorithm.header());
+    frame.extend_from_slice(&body);
+    Ok(frame)
+}
+
+fn decode_frame(frame: &[u8], max_decompressed: usize) -> Result<Vec<u8>> {
+    let (&header, body) = frame.split_first().ok_or_else(|| invalid("empty frame".to_string()))?;
+
+    let data = match CompressionAlgorithm::from_header(header)? {
+        CompressionAlgorithm::None => body.to_vec(),
+        CompressionAlgorithm::Zstd => zstd::bulk::decompress(body, max_decompressed)
+            .map_err(|e| invalid(format!("zstd decompression failed: {}", e)))?,
+        CompressionAlgorithm::Lz4 => {
+            // Check the declared size before allocating for it
+            let declared = body.get(..4)
+                .map(|size| u32::from_le_bytes([size[0], size[1], size[2], size[3]]) as usize)
+                .ok_or_else(|| invalid("truncated lz4 frame".to_string()))?;
+            if declared > max_decompressed {
+                return Err(invalid(format!("lz4 payload of {} bytes exceeds limit", declared)));
+            }
+            lz4_flex::decompress_size_prepended(body)
+                .map_err(|e| invalid(format!("lz4 decompression failed: {}", e)))?
+        }
+    };
+
+    if data.len() > max_decompressed {
+        return Err(invalid(format!("payload of {} bytes exceeds limit", data.len())));
+    }
+    Ok(data)
+}
+
+fn invalid(message: String) -> BridgeError {
+    BridgeError::Network(NetworkError::InvalidPayload(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wire_bytes(config: &CompressionConfig, payload: &[u8]) -> u64 {
+        let metrics = TransportMetrics::new();
+        let codec = PayloadCodec::new(config, &metrics);
+
+        let frame = codec.encode(payload).unwrap();
+        assert_eq!(codec.decode(&frame).unwrap(), payload);
+        assert_eq!(metrics.bytes_received(), metrics.bytes_sent());
+        metrics.bytes_sent()
+    }
+
+    #[test]
+    fn test_compressible_payload_uses_fewer_wire_bytes() {
+        // A state proof full of repeated nodes compresses well
+        let payload: Vec<u8> = (0..64 * 1024).map(|i| (i % 16) as u8).collect();
+
+        let uncompressed = wire_bytes(&CompressionConfig::default(), &payload);
+        assert_eq!(uncompressed, payload.len() as u64);
+
+        for algorithm in [CompressionAlgorithm::Zstd, CompressionAlgorithm::Lz4] {
+            let config = CompressionConfig { enabled: true, algorithm, ..CompressionConfig::default() };
+            let compressed = wire_bytes(&config, &payload);
+            assert!(compressed < uncompressed / 10, "{:?} sent {} bytes", algorithm, compressed);
+        }
+    }
+
+    #[test]
+    fn test_small_payloads_sent_raw_and_bad_frames_rejected() {
+        let config = CompressionConfig { enabled: true, ..CompressionConfig::default() };
+        let metrics = TransportMetrics::new();
+        let codec = PayloadCodec::new(&config, &metrics);
+
+        let frame = codec.encode(b"ping").unwrap();
+        assert_eq!(frame, b"\x00ping");
+
+        assert!(codec.decode(&[]).is_err());
+        assert!(codec.decode(&[0x7f, 1, 2, 3]).is_err());
+
+        // An lz4 frame claiming a huge payload is refused up front
+        let mut bomb = vec![0x02];
+        bomb.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert!(codec.decode(&bomb).is_err());
+    }
+}
@@ -7,6 +7,8 @@ DNSSEC validation, and GhostChain CNS integration.
 
 use crate::error::{BridgeError, NetworkError, Result};
 use crate::transport::{QuicConnection, ClientConfig, SecurityConfig};
+use async_trait::async_trait;
+use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
@@ -17,9 +19,15 @@ use tracing::{debug, error, info, instrument, warn};
 /// DNS over QUIC client
 pub struct DnsOverQuic {
     config: DnsConfig,
-    resolvers: Vec<String>,
+    resolvers: Mutex<ResolverPool>,
     cache: Arc<RwLock<DnsCache>>,
-    quic_client: Arc<QuicClient>,
+    transport: Arc<dyn DnsTransport>,
+}
+
+/// Sends a single DNS query to a resolver
+#[async_trait]
+pub trait DnsTransport: Send + Sync {
+    async fn query(&self, resolver: &str, domain: &str, query_type: QueryType) -> Result<Vec<DnsRecord>>;
 }
 
 /// DNS configuration from transport module
@@ -141,6 +149,83 @@ impl DnsCache {
     }
 }
 
+/// Health of one configured resolver
+#[derive(Debug, Clone)]
+struct ResolverHealth {
+    endpoint: String,
+    consecutive_failures: u32,
+    unhealthy_until: Option<Instant>,
+}
+
+/// Round-robin resolver selection with failover past unhealthy resolvers
+struct ResolverPool {
+    resolvers: Vec<ResolverHealth>,
+    next: usize,
+    failure_threshold: u32,
+    retry_after: Duration,
+}
+
+impl ResolverPool {
+    fn new(endpoints: &[String], failure_threshold: u32, retry_after: Duration) -> Self {
+        Self {
+            resolvers: endpoints.iter()
+                .map(|endpoint| ResolverHealth {
+                    endpoint: endpoint.clone(),
+                    consecutive_failures: 0,
+                    unhealthy_until: None,
+                })
+                .collect(),
+            next: 0,
+            failure_threshold: failure_threshold.max(1),
+            retry_after,
+        }
+    }
+
+    /// Resolvers to try for one query: healthy ones starting at the
+    /// round-robin cursor, then unhealthy ones as a last resort
+    fn query_order(&mut self, now: Instant) -> Vec<String> {
+        let count = self.resolvers.len();
+        if count == 0 {
+            return Vec::new();
+        }
+        let start = self.next % count;
+        self.next = (start + 1) % count;
+
+        let (healthy, unhealthy): (Vec<&ResolverHealth>, Vec<&ResolverHealth>) = (0..count)
+            .map(|offset| &self.resolvers[(start + offset) % count])
+            .partition(|resolver| resolver.unhealthy_until.is_none_or(|until| now >= until));
+
+        healthy.into_iter()
+            .chain(unhealthy)
+            .map(|resolver| resolver.endpoint.clone())
+            .collect()
+    }
+
+    fn record_success(&mut self, endpoint: &str) {
+        if let Some(resolver) = self.resolvers.iter_mut().find(|r| r.endpoint == endpoint) {
+            resolver.consecutive_failures = 0;
+            resolver.unhealthy_until = None;
+        }
+    }
+
+    fn record_failure(&mut self, endpoint: &str, now: Instant) {
+        let (threshold, retry_after) = (self.failure_threshold, self.retry_after);
+        if let Some(resolver) = self.resolvers.iter_mut().find(|r| r.endpoint == endpoint) {
+            resolver.consecutive_failures += 1;
+            if resolver.consecutive_failures >= threshold {
+                resolver.unhealthy_until = Some(now + retry_after);
+            }
+        }
+    }
+
+    fn healthy_endpoints(&self, now: Instant) -> Vec<String> {
+        self.resolvers.iter()
+            .filter(|resolver| resolver.unhealthy_until.is_none_or(|until| now >= until))
+            .map(|resolver| resolver.endpoint.clone())
+            .collect()
+    }
+}
+
 // Placeholder for QuicClient (would be implemented with actual GQUIC)
 struct QuicClient {
     config: ClientConfig,
@@ -192,16 +277,30 @@ impl DnsOverQuic {
 
         let quic_client = Arc::new(QuicClient::new(client_config, security_config)?);
         let cache = Arc::new(RwLock::new(DnsCache::new(config.cache_size)));
+        let resolvers = ResolverPool::new(
+            &config.resolver_endpoints,
+            config.resolver_failure_threshold,
+            config.resolver_retry_after,
+        );
 
-        let dns_client = Self {
-            resolvers: config.resolver_endpoints.clone(),
+        info!("DNS over QUIC client initialized with {} resolvers", config.resolver_endpoints.len());
+        Ok(Self {
             config,
+            resolvers: Mutex::new(resolvers),
             cache,
-            quic_client,
-        };
+            transport: Arc::new(QuicDnsTransport { quic_client }),
+        })
+    }
+
+    /// Use a custom query transport (e.g. a stub in tests)
+    pub fn with_transport(mut self, transport: Arc<dyn DnsTransport>) -> Self {
+        self.transport = transport;
+        self
+    }
 
-        info!("DNS over QUIC client initialized with {} resolvers", dns_client.resolvers.len());
-        Ok(dns_client)
+    /// Resolvers currently considered healthy
+    pub fn healthy_resolvers(&self) -> Vec<String> {
+        self.resolvers.lock().healthy_endpoints(Instant::now())
     }
 
     /// Resolve A records for a domain
@@ -371,15 +470,18 @@ impl DnsOverQuic {
     async fn query_dns(&self, domain: &str, query_type: QueryType) -> Result<Vec<DnsRecord>> {
         debug!("Querying DNS for {} record type {:?}", domain, query_type);
 
-        // Try each resolver until one succeeds
-        for resolver in &self.resolvers {
-            match self.query_resolver(resolver, domain, query_type).await {
+        // Start at the next resolver in rotation and fail over until one succeeds
+        let order = self.resolvers.lock().query_order(Instant::now());
+        for resolver in &order {
+            match self.transport.query(resolver, domain, query_type).await {
                 Ok(records) => {
                     debug!("DNS query succeeded via resolver: {}", resolver);
+                    self.resolvers.lock().record_success(resolver);
                     return Ok(records);
                 }
                 Err(e) => {
                     warn!("DNS query failed via resolver {}: {}", resolver, e);
+                    self.resolvers.lock().record_failure(resolver, Instant::now());
                     continue;
                 }
             }
@@ -389,13 +491,16 @@ impl DnsOverQuic {
             duration_ms: self.config.query_timeout.as_millis() as u64,
         }))
     }
+}
 
-    async fn query_resolver(
-        &self,
-        resolver: &str,
-        domain: &str,
-        query_type: QueryType,
-    ) -> Result<Vec<DnsRecord>> {
+/// DNS queries over GQUIC
+struct QuicDnsTransport {
+    quic_client: Arc<QuicClient>,
+}
+
+#[async_trait]
+impl DnsTransport for QuicDnsTransport {
+    async fn query(&self, _resolver: &str, domain: &str, query_type: QueryType) -> Result<Vec<DnsRecord>> {
         // TODO: Implement actual DNS over QUIC query
         // This would involve:
         // 1. Connect to resolver via QUIC
@@ -475,10 +580,75 @@ mod tests {
             cache_ttl: Duration::from_secs(300),
             query_timeout: Duration::from_secs(5),
             enable_dnssec: true,
+            resolver_failure_threshold: 3,
+            resolver_retry_after: Duration::from_secs(30),
         };
 
         // This would fail in test environment without actual DNS setup
         let result = DnsOverQuic::new(config).await;
         assert!(result.is_ok() || result.is_err()); // Either is fine for structure test
     }
+
+    /// Answers from every resolver except the `down` ones, recording who was asked
+    struct StubTransport {
+        down: Vec<String>,
+        queried: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl DnsTransport for StubTransport {
+        async fn query(&self, resolver: &str, domain: &str, _query_type: QueryType) -> Result<Vec<DnsRecord>> {
+            self.queried.lock().push(resolver.to_string());
+            if self.down.iter().any(|r| r == resolver) {
+                return Err(BridgeError::Network(NetworkError::Timeout { duration_ms: 5_000 }));
+            }
+            Ok(vec![DnsRecord {
+                name: domain.to_string(),
+                record_type: QueryType::TXT,
+                ttl: 300,
+                data: DnsRecordData::TXT(resolver.to_string()),
+            }])
+        }
+    }
+
+    async fn client_with(down: &[&str]) -> (DnsOverQuic, Arc<StubTransport>) {
+        let config = DnsConfig {
+            resolver_endpoints: vec!["r1:853".to_string(), "r2:853".to_string(), "r3:853".to_string()],
+            resolver_failure_threshold: 1,
+            ..crate::transport::TransportConfig::default().dns
+        };
+        let transport = Arc::new(StubTransport {
+            down: down.iter().map(|r| r.to_string()).collect(),
+            queried: Mutex::new(Vec::new()),
+        });
+        let client = DnsOverQuic::new(config).await.unwrap().with_transport(transport.clone());
+        (client, transport)
+    }
+
+    #[tokio::test]
+    async fn test_query_fails_over_past_dead_resolver() {
+        let (client, transport) = client_with(&["r1:853"]).await;
+
+        assert_eq!(client.resolve_txt("ghostchain.io").await.unwrap(), vec!["r2:853"]);
+        assert_eq!(*transport.queried.lock(), vec!["r1:853", "r2:853"]);
+        assert_eq!(client.healthy_resolvers(), vec!["r2:853", "r3:853"]);
+
+        // The dead resolver is skipped from now on
+        transport.queried.lock().clear();
+        for _ in 0..3 {
+            client.resolve_txt("ghostchain.io").await.unwrap();
+        }
+        assert!(!transport.queried.lock().iter().any(|r| r == "r1:853"));
+    }
+
+    #[tokio::test]
+    async fn test_queries_spread_across_healthy_resolvers() {
+        let (client, _) = client_with(&[]).await;
+
+        let mut answered_by = Vec::new();
+        for _ in 0..6 {
+            answered_by.extend(client.resolve_txt("ghostchain.io").await.unwrap());
+        }
+        assert_eq!(answered_by, ["r1:853", "r2:853", "r3:853", "r1:853", "r2:853", "r3:853"]);
+    }
 }
\ No newline at end of file
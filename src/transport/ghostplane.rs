@@ -0,0 +1,351 @@
+/*!
+GhostPlane over QUIC
+
+When GhostPlane runs on another host, the batches that would cross the FFI
+boundary travel over QUIC instead. Those batches are large and carry user
+transfers, so the channel negotiates a compression codec with the remote
+GhostPlane when it opens and refuses to send anything unless the link
+reports a TLS cipher suite from the configured list. A link that cannot tell
+which suite its handshake settled on reports none, so the channel fails
+closed rather than assuming one.
+*/
+
+use crate::error::{BridgeError, NetworkError, Result};
+use crate::ffi::BatchResult;
+use crate::transport::{
+    CompressionAlgorithm, CompressionConfig, EndpointClass, GQuicTransport, PayloadCodec,
+    TrafficClass, TransportMetrics, WireEnvelope,
+};
+use crate::types::Transaction;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// TLS 1.3 cipher suites the QUIC handshake may settle on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CipherSuite {
+    Aes128GcmSha256,
+    Aes256GcmSha384,
+    ChaCha20Poly1305Sha256,
+}
+
+/// Compression and encryption settings for the GhostPlane channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GhostPlaneChannelConfig {
+    /// Codecs offered to the remote GhostPlane, most preferred first
+    pub compression: Vec<CompressionAlgorithm>,
+    /// Batches smaller than this are sent uncompressed
+    pub compression_threshold_bytes: usize,
+    pub zstd_level: i32,
+    /// Largest response accepted after decompression
+    pub max_decompressed_bytes: usize,
+    /// Refuse to open the channel without a common codec
+    pub require_compression: bool,
+    /// Cipher suites accepted from the TLS handshake
+    pub cipher_suites: Vec<CipherSuite>,
+    /// Refuse to send over a link without TLS protection
+    pub require_encryption: bool,
+}
+
+impl Default for GhostPlaneChannelConfig {
+    fn default() -> Self {
+        Self {
+            compression: vec![CompressionAlgorithm::Zstd, CompressionAlgorithm::Lz4],
+            compression_threshold_bytes: 1024,
+            zstd_level: 3,
+            max_decompressed_bytes: 16 * 1024 * 1024, // 16MB, a full batch
+            require_compression: true,
+            cipher_suites: vec![
+                CipherSuite::Aes256GcmSha384,
+                CipherSuite::ChaCha20Poly1305Sha256,
+                CipherSuite::Aes128GcmSha256,
+            ],
+            require_encryption: true,
+        }
+    }
+}
+
+/// Codec and cipher suite agreed with a remote GhostPlane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NegotiatedChannel {
+    pub compression: CompressionAlgorithm,
+    /// `None` only when encryption is not required and the link is plaintext
+    pub cipher_suite: Option<CipherSuite>,
+}
+
+impl GhostPlaneChannelConfig {
+    /// Agree on a codec and check the link's cipher suite
+    pub fn negotiate(
+        &self,
+        endpoint: &str,
+        peer_compression: &[CompressionAlgorithm],
+        cipher_suite: Option<CipherSuite>,
+    ) -> Result<NegotiatedChannel> {
+        let cipher_suite = match cipher_suite {
+            Some(suite) if self.cipher_suites.contains(&suite) => Some(suite),
+            Some(suite) if self.require_encryption => {
+                return Err(negotiation_failed(endpoint, format!("cipher suite {:?} is not allowed", suite)));
+            }
+            None if self.require_encryption => {
+                return Err(BridgeError::Network(NetworkError::UnencryptedChannel {
+                    endpoint: endpoint.to_string(),
+                }));
+            }
+            _ => None,
+        };
+
+        let compression = self.compression.iter()
+            .copied()
+            .filter(|algorithm| *algorithm != CompressionAlgorithm::None)
+            .find(|algorithm| peer_compression.contains(algorithm))
+            .unwrap_or(CompressionAlgorithm::None);
+        if compression == CompressionAlgorithm::None && self.require_compression {
+            return Err(negotiation_failed(endpoint, "no common compression codec".to_string()));
+        }
+
+        Ok(NegotiatedChannel { compression, cipher_suite })
+    }
+
+    fn codec_config(&self, compression: CompressionAlgorithm) -> CompressionConfig {
+        CompressionConfig {
+            enabled: true,
+            algorithm: compression,
+            threshold_bytes: self.compression_threshold_bytes,
+            zstd_level: self.zstd_level,
+            max_decompressed_bytes: self.max_decompressed_bytes,
+        }
+    }
+}
+
+/// Requests understood by a remote GhostPlane
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GhostPlaneRequest {
+    SubmitBatch { transactions: Vec<Transaction> },
+}
+
+/// Connection to a remote GhostPlane
+#[async_trait]
+pub trait GhostPlaneLink: Send + Sync {
+    fn endpoint(&self) -> &str;
+
+    /// Cipher suite the QUIC TLS handshake settled on; `None` for a plaintext link
+    fn cipher_suite(&self) -> Option<CipherSuite>;
+
+    /// Offer codecs to the remote, which answers with those it supports
+    async fn hello(&self, offered: &[CompressionAlgorithm]) -> Result<Vec<CompressionAlgorithm>>;
+
+    /// Send one framed request and return the framed response
+    async fn send(&self, frame: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Link over the bridge's QUIC transport, sent as settlement traffic
+pub struct QuicGhostPlaneLink {
+    transport: Arc<GQuicTransport>,
+    endpoint: String,
+}
+
+impl QuicGhostPlaneLink {
+    /// Link to `endpoint`, whose connections are kept open as a service's
+    pub fn new(transport: Arc<GQuicTransport>, endpoint: impl Into<String>) -> Self {
+        let endpoint = endpoint.into();
+        transport.set_endpoint_class(&endpoint, EndpointClass::Service);
+        Self { transport, endpoint }
+    }
+}
+
+#[async_trait]
+impl GhostPlaneLink for QuicGhostPlaneLink {
+    fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    fn cipher_suite(&self) -> Option<CipherSuite> {
+        // GQUIC does not expose the suite its TLS handshake negotiated, and
+        // guessing one would let the channel vouch for a cipher it never
+        // checked. Report none so an encryption requirement refuses the link.
+        None
+    }
+
+    async fn hello(&self, offered: &[CompressionAlgorithm]) -> Result<Vec<CompressionAlgorithm>> {
+        let payload = WireEnvelope::new(offered).encode()?;
+        let response = self.transport
+            .send_data_with_class(&self.endpoint, &payload, TrafficClass::Settlement)
+            .await?;
+        Ok(WireEnvelope::<Vec<CompressionAlgorithm>>::decode(&response)?.payload)
+    }
+
+    async fn send(&self, frame: &[u8]) -> Result<Vec<u8>> {
+        self.transport.send_data_with_class(&self.endpoint, frame, TrafficClass::Settlement).await
+    }
+}
+
+/// Negotiated channel to a remote GhostPlane
+pub struct GhostPlaneChannel {
+    link: Arc<dyn GhostPlaneLink>,
+    negotiated: NegotiatedChannel,
+    codec_config: CompressionConfig,
+    metrics: Arc<TransportMetrics>,
+}
+
+impl GhostPlaneChannel {
+    /// Negotiate compression and check encryption before any batch is sent
+    pub async fn open(link: Arc<dyn GhostPlaneLink>, config: &GhostPlaneChannelConfig) -> Result<Self> {
+        let peer_compression = link.hello(&config.compression).await?;
+        let negotiated = config.negotiate(link.endpoint(), &peer_compression, link.cipher_suite())?;
+        if negotiated.cipher_suite.is_none() {
+            warn!("GhostPlane channel to {} is not encrypted", link.endpoint());
+        }
+        debug!("GhostPlane channel to {} negotiated {:?}", link.endpoint(), negotiated);
+
+        Ok(Self {
+            codec_config: config.codec_config(negotiated.compression),
+            link,
+            negotiated,
+            metrics: Arc::new(TransportMetrics::new()),
+        })
+    }
+
+    /// Open a channel to the GhostPlane at `endpoint` over `transport`
+    pub async fn connect_quic(
+        transport: Arc<GQuicTransport>,
+        endpoint: impl Into<String>,
+        config: &GhostPlaneChannelConfig,
+    ) -> Result<Self> {
+        Self::open(Arc::new(QuicGhostPlaneLink::new(transport, endpoint)), config).await
+    }
+
+    pub fn negotiated(&self) -> NegotiatedChannel {
+        self.negotiated
+    }
+
+    /// Bytes sent and received on this channel
+    pub fn metrics(&self) -> &TransportMetrics {
+        &self.metrics
+    }
+
+    /// Submit a batch to the remote GhostPlane
+    pub async fn submit_batch(&self, transactions: &[Transaction]) -> Result<BatchResult> {
+        let request = GhostPlaneRequest::SubmitBatch { transactions: transactions.to_vec() };
+        let payload = WireEnvelope::new(&request).encode()?;
+
+        let codec = PayloadCodec::new(&self.codec_config, &self.metrics);
+        let response = self.link.send(&codec.encode(&payload)?).await?;
+        let response = WireEnvelope::<BatchResult>::decode(&codec.decode(&response)?)?;
+        if let Some(deprecation) = &response.deprecation {
+            warn!("GhostPlane {}: {}", self.link.endpoint(), deprecation);
+        }
+        Ok(response.payload)
+    }
+}
+
+fn negotiation_failed(endpoint: &str, reason: String) -> BridgeError {
+    BridgeError::Network(NetworkError::ChannelNegotiationFailed { endpoint: endpoint.to_string(), reason })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::fixtures::transfer;
+    use parking_lot::Mutex;
+
+    /// Remote GhostPlane behind a link reporting `cipher_suite`, keeping
+    /// every frame the channel handed it
+    struct RemoteLink {
+        cipher_suite: Option<CipherSuite>,
+        supported: Vec<CompressionAlgorithm>,
+        frames: Mutex<Vec<Vec<u8>>>,
+    }
+
+    impl RemoteLink {
+        fn new(cipher_suite: Option<CipherSuite>, supported: Vec<CompressionAlgorithm>) -> Self {
+            Self { cipher_suite, supported, frames: Mutex::new(Vec::new()) }
+        }
+    }
+
+    #[async_trait]
+    impl GhostPlaneLink for RemoteLink {
+        fn endpoint(&self) -> &str {
+            "ghostplane.remote:9090"
+        }
+
+        fn cipher_suite(&self) -> Option<CipherSuite> {
+            self.cipher_suite
+        }
+
+        async fn hello(&self, offered: &[CompressionAlgorithm]) -> Result<Vec<CompressionAlgorithm>> {
+            Ok(offered.iter().copied().filter(|algorithm| self.supported.contains(algorithm)).collect())
+        }
+
+        async fn send(&self, frame: &[u8]) -> Result<Vec<u8>> {
+            self.frames.lock().push(frame.to_vec());
+
+            // Remote side: decode the batch and acknowledge it with the same codec
+            let config = CompressionConfig { enabled: true, algorithm: self.supported[0], ..CompressionConfig::default() };
+            let metrics = TransportMetrics::new();
+            let codec = PayloadCodec::new(&config, &metrics);
+            let request = WireEnvelope::<GhostPlaneRequest>::decode(&codec.decode(frame)?)?;
+            let GhostPlaneRequest::SubmitBatch { transactions } = request.payload;
+            let result = BatchResult {
+                batch_hash: [1; 32],
+                state_root: [2; 32],
+                transaction_count: transactions.len() as u32,
+                gas_used: 21_000 * transactions.len() as u64,
+                success: true,
+            };
+            codec.encode(&WireEnvelope::new(result).encode()?)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_is_compressed_on_the_wire() {
+        let link = Arc::new(RemoteLink::new(
+            Some(CipherSuite::ChaCha20Poly1305Sha256),
+            vec![CompressionAlgorithm::Lz4],
+        ));
+        let channel = GhostPlaneChannel::open(link.clone(), &GhostPlaneChannelConfig::default()).await.unwrap();
+        assert_eq!(channel.negotiated(), NegotiatedChannel {
+            compression: CompressionAlgorithm::Lz4,
+            cipher_suite: Some(CipherSuite::ChaCha20Poly1305Sha256),
+        });
+
+        let batch: Vec<Transaction> = (0..200).map(|i| transfer(i as u8, i as u8 + 1, 10)).collect();
+        let result = channel.submit_batch(&batch).await.unwrap();
+        assert_eq!(result.transaction_count, 200);
+
+        // The frame handed to the link is far smaller than the serialized batch
+        let plaintext = WireEnvelope::new(&GhostPlaneRequest::SubmitBatch { transactions: batch.clone() }).encode().unwrap();
+        let frame = link.frames.lock()[0].clone();
+        assert!(frame.len() < plaintext.len() / 2, "frame of {} bytes for {} byte batch", frame.len(), plaintext.len());
+        assert_eq!(channel.metrics().bytes_sent(), frame.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_channel_refuses_plaintext_or_uncompressed_links() {
+        let config = GhostPlaneChannelConfig::default();
+
+        // A link that can't name its cipher suite is treated as plaintext
+        let unknown = Arc::new(RemoteLink::new(None, vec![CompressionAlgorithm::Zstd]));
+        assert!(matches!(
+            GhostPlaneChannel::open(unknown.clone(), &config).await,
+            Err(BridgeError::Network(NetworkError::UnencryptedChannel { .. }))
+        ));
+
+        let disallowed = GhostPlaneChannelConfig { cipher_suites: vec![CipherSuite::Aes256GcmSha384], ..config.clone() };
+        let link = Arc::new(RemoteLink::new(Some(CipherSuite::ChaCha20Poly1305Sha256), vec![CompressionAlgorithm::Zstd]));
+        assert!(matches!(
+            GhostPlaneChannel::open(link, &disallowed).await,
+            Err(BridgeError::Network(NetworkError::ChannelNegotiationFailed { .. }))
+        ));
+
+        let uncompressed = Arc::new(RemoteLink::new(Some(CipherSuite::ChaCha20Poly1305Sha256), vec![CompressionAlgorithm::None]));
+        assert!(matches!(
+            GhostPlaneChannel::open(uncompressed.clone(), &config).await,
+            Err(BridgeError::Network(NetworkError::ChannelNegotiationFailed { .. }))
+        ));
+
+        // Nothing was sent over a refused channel
+        assert!(unknown.frames.lock().is_empty());
+        assert!(uncompressed.frames.lock().is_empty());
+    }
+}
@@ -6,6 +6,7 @@ featuring connection pooling, DNS over QUIC, and mesh networking capabilities.
 */
 
 use crate::error::{BridgeError, NetworkError, Result};
+use crate::health::{Criticality, HealthPolicy, HealthState};
 use gquic::prelude::*;
 use std::collections::HashMap;
 use std::net::SocketAddr;
@@ -20,17 +21,50 @@ pub mod server;
 pub mod pool;
 pub mod dns;
 pub mod mesh;
+pub mod priority;
+pub mod compression;
+pub mod protocol;
+pub mod ghostplane;
 
 pub use client::QuicClient;
-pub use server::QuicServer;
+pub use server::{QuicServer, DrainReport};
 pub use pool::{ConnectionPool, PoolConfig};
 pub use dns::DnsOverQuic;
 pub use mesh::QuicMeshNetwork;
+pub use priority::{PriorityScheduler, SendSlot, StreamPriorityConfig, TrafficClass};
+pub use compression::{CompressionAlgorithm, CompressionConfig, PayloadCodec};
+pub use protocol::{NegotiatedVersion, ProtocolConfig, WireEnvelope, PROTOCOL_VERSION};
+pub use ghostplane::{CipherSuite, GhostPlaneChannel, GhostPlaneChannelConfig, GhostPlaneLink, QuicGhostPlaneLink};
+
+/// Read an HTTP response body, failing as soon as it exceeds `limit` bytes
+/// so a misbehaving endpoint cannot exhaust memory
+pub(crate) async fn read_bounded_body(mut response: reqwest::Response, endpoint: &str, limit: usize) -> Result<Vec<u8>> {
+    let too_large = || BridgeError::Network(NetworkError::ResponseTooLarge { endpoint: endpoint.to_string(), limit });
+    if response.content_length().is_some_and(|length| length > limit as u64) {
+        return Err(too_large());
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| BridgeError::Network(NetworkError::ConnectionFailed {
+        endpoint: endpoint.to_string(),
+        source: Box::new(e),
+    }))? {
+        if body.len() + chunk.len() > limit {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
 
 /// GQUIC transport manager for GhostBridge
 pub struct GQuicTransport {
     config: TransportConfig,
     client_pool: Arc<ConnectionPool>,
+    /// Send schedulers for shared connections, by endpoint
+    schedulers: Arc<dashmap::DashMap<String, Arc<PriorityScheduler>>>,
+    /// Endpoint classes; unlisted endpoints are treated as clients
+    endpoint_classes: Arc<dashmap::DashMap<String, EndpointClass>>,
     server: Option<QuicServer>,
     mesh_network: Arc<QuicMeshNetwork>,
     dns_client: Arc<DnsOverQuic>,
@@ -52,6 +86,65 @@ pub struct TransportConfig {
     pub mesh: MeshConfig,
     /// Security settings
     pub security: SecurityConfig,
+    /// Stream priorities for settlement vs query traffic
+    #[serde(default)]
+    pub stream_priority: StreamPriorityConfig,
+    /// Stream payload compression
+    #[serde(default)]
+    pub compression: CompressionConfig,
+    /// Compression and encryption for the channel to a remote GhostPlane
+    #[serde(default)]
+    pub ghostplane: GhostPlaneChannelConfig,
+    /// Idle timeouts by endpoint class
+    #[serde(default)]
+    pub idle_timeouts: IdleTimeoutConfig,
+    /// Which component failures make the transport unhealthy rather than degraded
+    #[serde(default = "TransportConfig::default_health_policy")]
+    pub health: HealthPolicy,
+}
+
+/// Kind of peer a connection is made to, which decides its idle policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum EndpointClass {
+    /// Long-lived connections to GhostChain services
+    Service,
+    /// Bursty, short-lived client connections
+    Client,
+    /// Connections to mesh peers
+    Mesh,
+}
+
+/// Idle timeout applied to connections of each endpoint class
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct IdleTimeoutConfig {
+    pub service: Duration,
+    pub client: Duration,
+    pub mesh: Duration,
+    /// Further endpoints to treat as services. GhostPlane links and proof
+    /// workers classify their own endpoints.
+    #[serde(default)]
+    pub service_endpoints: Vec<String>,
+}
+
+impl Default for IdleTimeoutConfig {
+    fn default() -> Self {
+        Self {
+            service: Duration::from_secs(900),
+            client: Duration::from_secs(60),
+            mesh: Duration::from_secs(300),
+            service_endpoints: Vec::new(),
+        }
+    }
+}
+
+impl IdleTimeoutConfig {
+    pub fn for_class(&self, class: EndpointClass) -> Duration {
+        match class {
+            EndpointClass::Service => self.service,
+            EndpointClass::Client => self.client,
+            EndpointClass::Mesh => self.mesh,
+        }
+    }
 }
 
 /// Server configuration
@@ -64,6 +157,9 @@ pub struct ServerConfig {
     pub max_idle_timeout: Duration,
     pub enable_0rtt: bool,
     pub enable_migration: bool,
+    /// Wire protocol versions served to clients
+    #[serde(default)]
+    pub protocol: ProtocolConfig,
 }
 
 /// Client configuration
@@ -86,6 +182,10 @@ pub struct DnsConfig {
     pub cache_ttl: Duration,
     pub query_timeout: Duration,
     pub enable_dnssec: bool,
+    /// Consecutive failures before a resolver is skipped
+    pub resolver_failure_threshold: u32,
+    /// How long an unhealthy resolver is skipped before it is retried
+    pub resolver_retry_after: Duration,
 }
 
 /// Mesh networking configuration
@@ -129,6 +229,7 @@ impl Default for TransportConfig {
                 max_idle_timeout: Duration::from_secs(300),
                 enable_0rtt: true,
                 enable_migration: true,
+                protocol: ProtocolConfig::default(),
             },
             client: ClientConfig {
                 default_server_name: "ghostbridge.local".to_string(),
@@ -149,6 +250,8 @@ impl Default for TransportConfig {
                 cache_ttl: Duration::from_secs(300),
                 query_timeout: Duration::from_secs(5),
                 enable_dnssec: true,
+                resolver_failure_threshold: 3,
+                resolver_retry_after: Duration::from_secs(30),
             },
             mesh: MeshConfig {
                 node_id: uuid::Uuid::new_v4().to_string(),
@@ -173,6 +276,29 @@ impl Default for TransportConfig {
                     "cross-chain".to_string(),
                 ],
             },
+            stream_priority: StreamPriorityConfig::default(),
+            compression: CompressionConfig::default(),
+            ghostplane: GhostPlaneChannelConfig::default(),
+            idle_timeouts: IdleTimeoutConfig::default(),
+            health: Self::default_health_policy(),
+        }
+    }
+}
+
+impl TransportConfig {
+    /// The connection pool and server are critical; DNS-over-QUIC and the
+    /// mesh only degrade the transport when down
+    pub fn default_health_policy() -> HealthPolicy {
+        HealthPolicy::new(Criticality::Critical)
+            .with_component("dns", Criticality::NonCritical)
+            .with_component("mesh", Criticality::NonCritical)
+    }
+
+    /// Client settings for a connection to an endpoint of `class`
+    pub fn client_config_for(&self, class: EndpointClass) -> ClientConfig {
+        ClientConfig {
+            max_idle_timeout: self.idle_timeouts.for_class(class),
+            ..self.client.clone()
         }
     }
 }
@@ -195,9 +321,19 @@ impl GQuicTransport {
         // Initialize metrics
         let metrics = Arc::new(TransportMetrics::new());
 
+        let endpoint_classes = dashmap::DashMap::new();
+        for endpoint in &config.mesh.discovery_endpoints {
+            endpoint_classes.insert(endpoint.clone(), EndpointClass::Mesh);
+        }
+        for endpoint in &config.idle_timeouts.service_endpoints {
+            endpoint_classes.insert(endpoint.clone(), EndpointClass::Service);
+        }
+
         let transport = Self {
             config,
             client_pool,
+            schedulers: Arc::new(dashmap::DashMap::new()),
+            endpoint_classes: Arc::new(endpoint_classes),
             server: None,
             mesh_network,
             dns_client,
@@ -213,7 +349,8 @@ impl GQuicTransport {
     pub async fn start_server(&mut self) -> Result<()> {
         info!("Starting GQUIC server on {}", self.config.server.bind_address);
 
-        let server = QuicServer::new(self.config.server.clone(), self.config.security.clone()).await?;
+        let server = QuicServer::new(self.config.server.clone(), self.config.security.clone()).await?
+            .with_stream_priority(self.config.stream_priority.clone());
 
         // Start server in background
         let server_handle = server.start().await?;
@@ -223,10 +360,31 @@ impl GQuicTransport {
         Ok(())
     }
 
+    /// Gracefully shut down the QUIC server, draining in-flight streams
+    #[instrument(skip(self))]
+    pub async fn shutdown_server(&mut self, timeout: Duration) -> Result<Option<DrainReport>> {
+        match self.server.take() {
+            Some(server) => Ok(Some(server.graceful_shutdown(timeout).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Set the class of an endpoint, which decides the idle timeout of
+    /// connections established to it
+    pub fn set_endpoint_class(&self, endpoint: &str, class: EndpointClass) {
+        self.endpoint_classes.insert(endpoint.to_string(), class);
+    }
+
+    /// Class of an endpoint, `Client` unless set otherwise
+    pub fn endpoint_class(&self, endpoint: &str) -> EndpointClass {
+        self.endpoint_classes.get(endpoint).map_or(EndpointClass::Client, |class| *class)
+    }
+
     /// Create a new client connection
     #[instrument(skip(self))]
     pub async fn connect(&self, endpoint: &str) -> Result<QuicConnection> {
-        debug!("Creating QUIC connection to {}", endpoint);
+        let class = self.endpoint_class(endpoint);
+        debug!("Creating QUIC connection to {} ({:?})", endpoint, class);
 
         // Try to get existing connection from pool first
         if let Some(conn) = self.client_pool.get_connection(endpoint).await {
@@ -234,12 +392,14 @@ impl GQuicTransport {
             return Ok(conn);
         }
 
-        // Create new connection
-        let client = QuicClient::new(self.config.client.clone(), self.config.security.clone())?;
+        // Create new connection with the class's idle timeout
+        let client_config = self.config.client_config_for(class);
+        let idle_timeout = client_config.max_idle_timeout;
+        let client = QuicClient::new(client_config, self.config.security.clone())?;
         let connection = client.connect(endpoint).await?;
 
         // Add to pool
-        self.client_pool.add_connection(endpoint.to_string(), connection.clone()).await;
+        self.client_pool.add_connection_with_idle_timeout(endpoint.to_string(), connection.clone(), idle_timeout).await;
 
         self.metrics.record_connection_created();
         debug!("New QUIC connection created to {}", endpoint);
@@ -249,22 +409,33 @@ impl GQuicTransport {
     /// Send data over QUIC with automatic connection management
     #[instrument(skip(self, data))]
     pub async fn send_data(&self, endpoint: &str, data: &[u8]) -> Result<Vec<u8>> {
+        self.send_data_with_class(endpoint, data, TrafficClass::Query).await
+    }
+
+    /// Send data on a stream prioritized for `class`; settlement and finality
+    /// traffic is sent ahead of queries sharing the connection
+    #[instrument(skip(self, data))]
+    pub async fn send_data_with_class(&self, endpoint: &str, data: &[u8], class: TrafficClass) -> Result<Vec<u8>> {
         let connection = self.connect(endpoint).await?;
+        let scheduler = self.schedulers
+            .entry(endpoint.to_string())
+            .or_insert_with(|| Arc::new(PriorityScheduler::new(self.config.stream_priority.clone())))
+            .clone();
+        let slot = scheduler.acquire(class).await;
 
         // Open bidirectional stream
         let mut stream = connection.open_bi().await?;
+        stream.set_priority(slot.priority())?;
 
-        // Send data
-        stream.write_all(data).await?;
+        // Send data, compressed if configured
+        let codec = PayloadCodec::new(&self.config.compression, &self.metrics);
+        stream.write_all(&codec.encode(data)?).await?;
         stream.finish().await?;
+        drop(slot);
 
         // Read response
         let response = stream.read_to_end(1024 * 1024).await?; // 1MB max
-
-        self.metrics.record_data_sent(data.len());
-        self.metrics.record_data_received(response.len());
-
-        Ok(response)
+        codec.decode(&response)
     }
 
     /// Broadcast data to multiple endpoints
@@ -352,10 +523,13 @@ impl GQuicTransport {
             .map(|s| s.is_healthy())
             .unwrap_or(true); // No server is OK
 
-        health.overall_healthy = health.pool_healthy &&
-                                health.mesh_healthy &&
-                                health.dns_healthy &&
-                                health.server_healthy;
+        health.status = self.config.health.aggregate([
+            ("pool", health.pool_healthy),
+            ("mesh", health.mesh_healthy),
+            ("dns", health.dns_healthy),
+            ("server", health.server_healthy),
+        ]);
+        health.overall_healthy = health.status == HealthState::Healthy;
 
         Ok(health)
     }
@@ -367,6 +541,8 @@ impl Clone for GQuicTransport {
         Self {
             config: self.config.clone(),
             client_pool: Arc::clone(&self.client_pool),
+            schedulers: Arc::clone(&self.schedulers),
+            endpoint_classes: Arc::clone(&self.endpoint_classes),
             server: None, // Server handle is not cloneable
             mesh_network: Arc::clone(&self.mesh_network),
             dns_client: Arc::clone(&self.dns_client),
@@ -406,7 +582,10 @@ pub struct TransportStats {
 /// Transport health status
 #[derive(Debug, Clone, Default)]
 pub struct TransportHealth {
+    /// True only when every component is healthy
     pub overall_healthy: bool,
+    /// Healthy, degraded by non-critical failures, or unhealthy
+    pub status: HealthState,
     pub pool_healthy: bool,
     pub mesh_healthy: bool,
     pub dns_healthy: bool,
@@ -490,4 +669,56 @@ mod tests {
         // This might fail in test environment without actual GQUIC, but structure should be correct
         assert!(result.is_ok() || result.is_err()); // Either outcome is fine for structure test
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_idle_timeout_follows_endpoint_class() {
+        let mut config = TransportConfig::default();
+        config.idle_timeouts = IdleTimeoutConfig {
+            service: Duration::from_secs(1800),
+            client: Duration::from_secs(15),
+            mesh: Duration::from_secs(120),
+            ..IdleTimeoutConfig::default()
+        };
+
+        let service = config.client_config_for(EndpointClass::Service);
+        let client = config.client_config_for(EndpointClass::Client);
+        let mesh = config.client_config_for(EndpointClass::Mesh);
+
+        assert_eq!(service.max_idle_timeout, Duration::from_secs(1800));
+        assert_eq!(client.max_idle_timeout, Duration::from_secs(15));
+        assert_eq!(mesh.max_idle_timeout, Duration::from_secs(120));
+        // Everything else comes from the shared client settings
+        assert_eq!(service.keep_alive_interval, config.client.keep_alive_interval);
+    }
+
+    #[tokio::test]
+    async fn test_bounded_body_refuses_oversized_responses() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).await;
+                // Chunked, so the size is only known while reading
+                let chunk = "x".repeat(1000);
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n{:x}\r\n{}\r\n0\r\n\r\n",
+                    chunk.len(), chunk,
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(read_bounded_body(response, &url, 1000).await.unwrap().len(), 1000);
+
+        let response = reqwest::get(&url).await.unwrap();
+        assert!(matches!(
+            read_bounded_body(response, &url, 999).await,
+            Err(BridgeError::Network(NetworkError::ResponseTooLarge { limit: 999, .. }))
+        ));
+    }
+}
@@ -45,13 +45,15 @@ struct PoolEntry {
     last_used: Arc<RwLock<Instant>>,
     use_count: Arc<parking_lot::Mutex<u64>>,
     is_healthy: Arc<parking_lot::Mutex<bool>>,
+    idle_timeout: Duration,
 }
 
 impl PoolEntry {
-    fn new(connection: QuicConnection) -> Self {
+    fn new(connection: QuicConnection, idle_timeout: Duration) -> Self {
         let now = Instant::now();
         Self {
             connection,
+            idle_timeout,
             created_at: now,
             last_used: Arc::new(RwLock::new(now)),
             use_count: Arc::new(parking_lot::Mutex::new(0)),
@@ -64,9 +66,9 @@ impl PoolEntry {
         *self.use_count.lock() += 1;
     }
 
-    async fn is_idle(&self, timeout: Duration) -> bool {
+    async fn is_idle(&self) -> bool {
         let last_used = *self.last_used.read().await;
-        last_used.elapsed() > timeout
+        last_used.elapsed() > self.idle_timeout
     }
 
     fn is_healthy(&self) -> bool {
@@ -110,7 +112,7 @@ impl ConnectionPool {
 
         // Find a healthy, non-idle connection
         for (index, entry) in pool.iter().enumerate() {
-            if entry.is_healthy() && !entry.is_idle(self.config.connection_idle_timeout).await {
+            if entry.is_healthy() && !entry.is_idle().await {
                 entry.mark_used().await;
                 debug!("Reusing existing connection for {}", endpoint);
                 return Some(entry.connection.clone());
@@ -120,7 +122,7 @@ impl ConnectionPool {
         // Remove unhealthy or idle connections
         pool.retain(|entry| {
             let is_healthy = entry.is_healthy();
-            let is_not_idle = !futures::executor::block_on(entry.is_idle(self.config.connection_idle_timeout));
+            let is_not_idle = !futures::executor::block_on(entry.is_idle());
             is_healthy && is_not_idle
         });
 
@@ -129,8 +131,13 @@ impl ConnectionPool {
     }
 
     /// Add a connection to the pool
-    #[instrument(skip(self, connection))]
     pub async fn add_connection(&self, endpoint: String, connection: QuicConnection) {
+        self.add_connection_with_idle_timeout(endpoint, connection, self.config.connection_idle_timeout).await
+    }
+
+    /// Add a connection that is dropped after `idle_timeout` without use
+    #[instrument(skip(self, connection))]
+    pub async fn add_connection_with_idle_timeout(&self, endpoint: String, connection: QuicConnection, idle_timeout: Duration) {
         debug!("Adding connection to pool for endpoint: {}", endpoint);
 
         let mut pool_entry = self.pools.entry(endpoint.clone()).or_insert_with(Vec::new);
@@ -150,7 +157,7 @@ impl ConnectionPool {
         }
 
         // Add new connection
-        let entry = PoolEntry::new(connection);
+        let entry = PoolEntry::new(connection, idle_timeout);
         pool.push(entry);
 
         debug!("Connection added to pool for {}", endpoint);
@@ -228,9 +235,7 @@ impl ConnectionPool {
 
             pool.retain(|entry| {
                 let is_healthy = entry.is_healthy();
-                let is_not_idle = !futures::executor::block_on(
-                    entry.is_idle(self.config.connection_idle_timeout)
-                );
+                let is_not_idle = !futures::executor::block_on(entry.is_idle());
                 is_healthy && is_not_idle
             });
 
@@ -249,7 +254,6 @@ impl ConnectionPool {
     fn start_cleanup_task(&mut self) {
         let pools = self.pools.clone();
         let cleanup_interval = self.config.cleanup_interval;
-        let connection_idle_timeout = self.config.connection_idle_timeout;
 
         let handle = tokio::spawn(async move {
             let mut interval = tokio::time::interval(cleanup_interval);
@@ -266,9 +270,7 @@ impl ConnectionPool {
 
                     pool.retain(|entry| {
                         let is_healthy = entry.is_healthy();
-                        let is_not_idle = !futures::executor::block_on(
-                            entry.is_idle(connection_idle_timeout)
-                        );
+                        let is_not_idle = !futures::executor::block_on(entry.is_idle());
                         is_healthy && is_not_idle
                     });
 
@@ -0,0 +1,257 @@
+/*!
+Stream priorities for shared QUIC connections
+
+Settlement and finality RPCs share connections with best-effort queries.
+Each stream is tagged with a `TrafficClass`; the class maps to a QUIC stream
+priority, and a per-connection `PriorityScheduler` hands out send slots to
+the highest-priority waiting stream first so critical traffic is not stuck
+behind bulk queries.
+*/
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+use tokio::sync::oneshot;
+
+/// Kind of traffic carried by a stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TrafficClass {
+    /// L1 settlement submissions
+    Settlement,
+    /// Finality and confirmation tracking
+    Finality,
+    /// Best-effort queries such as balance lookups
+    Query,
+}
+
+/// Priorities per traffic class; higher values are sent first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamPriorityConfig {
+    /// Streams on one connection that may send at once; the rest wait by priority
+    pub send_slots_per_connection: usize,
+    pub settlement_priority: i32,
+    pub finality_priority: i32,
+    pub query_priority: i32,
+}
+
+impl Default for StreamPriorityConfig {
+    fn default() -> Self {
+        Self {
+            send_slots_per_connection: 4,
+            settlement_priority: 100,
+            finality_priority: 50,
+            query_priority: 0,
+        }
+    }
+}
+
+impl StreamPriorityConfig {
+    /// QUIC stream priority for a traffic class
+    pub fn priority_of(&self, class: TrafficClass) -> i32 {
+        match class {
+            TrafficClass::Settlement => self.settlement_priority,
+            TrafficClass::Finality => self.finality_priority,
+            TrafficClass::Query => self.query_priority,
+        }
+    }
+}
+
+/// Stream waiting for a send slot
+struct Waiter {
+    priority: i32,
+    seq: u64,
+    grant: oneshot::Sender<()>,
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Waiter {}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Waiter {
+    // Highest priority first, then first come first served
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority.cmp(&other.priority).then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+struct SchedulerState {
+    available: usize,
+    next_seq: u64,
+    waiters: BinaryHeap<Waiter>,
+}
+
+/// Grants send slots on one connection in priority order
+pub struct PriorityScheduler {
+    config: StreamPriorityConfig,
+    state: Mutex<SchedulerState>,
+}
+
+/// Permission to send on a stream; the slot passes to the next waiter on drop
+pub struct SendSlot {
+    scheduler: Arc<PriorityScheduler>,
+    priority: i32,
+}
+
+/// Cleans up a waiter whose `acquire` is cancelled
+struct PendingGrant<'a> {
+    scheduler: &'a Arc<PriorityScheduler>,
+    receiver: Option<oneshot::Receiver<()>>,
+}
+
+impl PriorityScheduler {
+    pub fn new(config: StreamPriorityConfig) -> Self {
+        let available = config.send_slots_per_connection.max(1);
+        Self {
+            config,
+            state: Mutex::new(SchedulerState {
+                available,
+                next_seq: 0,
+                waiters: BinaryHeap::new(),
+            }),
+        }
+    }
+
+    /// Wait for a send slot for a stream of `class`
+    pub async fn acquire(self: &Arc<Self>, class: TrafficClass) -> SendSlot {
+        let priority = self.config.priority_of(class);
+        let receiver = {
+            let mut state = self.state.lock();
+            if state.available > 0 && state.waiters.is_empty() {
+                state.available -= 1;
+                return SendSlot { scheduler: self.clone(), priority };
+            }
+
+            let (grant, receiver) = oneshot::channel();
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            state.waiters.push(Waiter { priority, seq, grant });
+            receiver
+        };
+
+        let mut pending = PendingGrant { scheduler: self, receiver: Some(receiver) };
+        if let Some(receiver) = pending.receiver.as_mut() {
+            receiver.await.expect("scheduler outlives its waiters");
+        }
+        pending.receiver = None;
+        SendSlot { scheduler: self.clone(), priority }
+    }
+
+    /// Streams waiting for a send slot
+    pub fn waiting(&self) -> usize {
+        self.state.lock().waiters.len()
+    }
+
+    fn release(&self) {
+        let mut state = self.state.lock();
+        while let Some(waiter) = state.waiters.pop() {
+            // A failed send means the waiter gave up; try the next one
+            if waiter.grant.send(()).is_ok() {
+                return;
+            }
+        }
+        state.available += 1;
+    }
+}
+
+impl SendSlot {
+    /// QUIC priority to set on the stream while it sends
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+}
+
+impl Drop for SendSlot {
+    fn drop(&mut self) {
+        self.scheduler.release();
+    }
+}
+
+impl Drop for PendingGrant<'_> {
+    fn drop(&mut self) {
+        if let Some(mut receiver) = self.receiver.take() {
+            // Granted after the caller stopped waiting: hand the slot on
+            receiver.close();
+            if receiver.try_recv().is_ok() {
+                self.scheduler.release();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_settlement_stream_sent_ahead_of_queued_queries() {
+        let scheduler = Arc::new(PriorityScheduler::new(StreamPriorityConfig {
+            send_slots_per_connection: 1,
+            ..StreamPriorityConfig::default()
+        }));
+        let (sent_tx, mut sent_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        // A query occupies the only slot while more queries and then a settlement stream queue up
+        let busy = scheduler.acquire(TrafficClass::Query).await;
+        let mut streams = Vec::new();
+        for (name, class) in [
+            ("query-1", TrafficClass::Query),
+            ("query-2", TrafficClass::Query),
+            ("query-3", TrafficClass::Query),
+            ("settlement", TrafficClass::Settlement),
+        ] {
+            let scheduler = scheduler.clone();
+            let sent_tx = sent_tx.clone();
+            streams.push(tokio::spawn(async move {
+                let slot = scheduler.acquire(class).await;
+                sent_tx.send((name, slot.priority())).unwrap();
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }));
+            while scheduler.waiting() < streams.len() {
+                tokio::task::yield_now().await;
+            }
+        }
+
+        drop(busy);
+        for stream in streams {
+            stream.await.unwrap();
+        }
+
+        let mut order = Vec::new();
+        while let Ok(sent) = sent_rx.try_recv() {
+            order.push(sent);
+        }
+        assert_eq!(order, vec![("settlement", 100), ("query-1", 0), ("query-2", 0), ("query-3", 0)]);
+        assert_eq!(scheduler.waiting(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_waiter_does_not_leak_slot() {
+        let scheduler = Arc::new(PriorityScheduler::new(StreamPriorityConfig {
+            send_slots_per_connection: 1,
+            ..StreamPriorityConfig::default()
+        }));
+
+        let busy = scheduler.acquire(TrafficClass::Query).await;
+        let abandoned = tokio::time::timeout(Duration::from_millis(10), scheduler.acquire(TrafficClass::Finality)).await;
+        assert!(abandoned.is_err());
+        drop(busy);
+
+        // The slot is still available to later streams
+        tokio::time::timeout(Duration::from_secs(1), scheduler.acquire(TrafficClass::Query))
+            .await
+            .expect("slot was leaked");
+    }
+}
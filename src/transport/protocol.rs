@@ -0,0 +1,136 @@
+/*!
+Bridge wire protocol versioning
+
+Every request and response exchanged over QUIC is wrapped in a
+`WireEnvelope` carrying the sender's protocol version. The server serves any
+version from its minimum supported version up to the current one, flags
+older versions as deprecated in the response, and rejects anything older
+with an error telling the client which versions to upgrade to.
+*/
+
+use crate::error::{BridgeError, NetworkError, Result};
+use crate::telemetry::TraceContext;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Protocol version spoken by this build
+pub const PROTOCOL_VERSION: u16 = 2;
+
+/// Versions the server accepts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolConfig {
+    /// Oldest client version still served; older ones must upgrade
+    pub min_supported_version: u16,
+}
+
+impl Default for ProtocolConfig {
+    fn default() -> Self {
+        Self {
+            min_supported_version: 1,
+        }
+    }
+}
+
+/// Versioned frame around a request or response payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WireEnvelope<T> {
+    /// Absent on frames from version 1 clients, which predate the field
+    #[serde(default = "unversioned_frame")]
+    pub protocol_version: u16,
+    /// Set on responses to clients on a deprecated version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecation: Option<String>,
+    /// Sender's trace, continued by the receiver
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_context: Option<TraceContext>,
+    pub payload: T,
+}
+
+/// Version of a frame without a `protocol_version` field
+fn unversioned_frame() -> u16 {
+    1
+}
+
+impl<T> WireEnvelope<T> {
+    /// Envelope at the current protocol version, in the current span's trace
+    pub fn new(payload: T) -> Self {
+        Self {
+            protocol_version: PROTOCOL_VERSION,
+            deprecation: None,
+            trace_context: TraceContext::current(),
+            payload,
+        }
+    }
+}
+
+impl<T: Serialize> WireEnvelope<T> {
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(|e| BridgeError::Serialization(e.into()))
+    }
+}
+
+impl<T: DeserializeOwned> WireEnvelope<T> {
+    pub fn decode(frame: &[u8]) -> Result<Self> {
+        serde_json::from_slice(frame).map_err(|e| BridgeError::Serialization(e.into()))
+    }
+}
+
+/// Version agreed with a client
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegotiatedVersion {
+    /// Version responses are encoded with
+    pub version: u16,
+    /// Upgrade notice for clients on an older, still supported version
+    pub deprecation: Option<String>,
+    /// Client's trace; attach it to the span handling the request
+    pub trace_context: Option<TraceContext>,
+}
+
+impl NegotiatedVersion {
+    /// Wrap a response payload at the negotiated version
+    pub fn respond<T>(&self, payload: T) -> WireEnvelope<T> {
+        WireEnvelope {
+            protocol_version: self.version,
+            deprecation: self.deprecation.clone(),
+            trace_context: None,
+            payload,
+        }
+    }
+}
+
+impl ProtocolConfig {
+    /// Agree on a version with a client, rejecting unsupported ones
+    pub fn negotiate(&self, client_version: u16) -> Result<NegotiatedVersion> {
+        if client_version < self.min_supported_version {
+            return Err(BridgeError::Network(NetworkError::UnsupportedProtocolVersion {
+                version: client_version,
+                min_supported: self.min_supported_version,
+                current: PROTOCOL_VERSION,
+            }));
+        }
+
+        // Newer clients fall back to the version this server speaks
+        let version = client_version.min(PROTOCOL_VERSION);
+        let deprecation = (version < PROTOCOL_VERSION).then(|| format!(
+            "protocol version {} is deprecated; upgrade to version {}", version, PROTOCOL_VERSION
+        ));
+        Ok(NegotiatedVersion { version, deprecation, trace_context: None })
+    }
+
+    /// Decode a client request and negotiate its version
+    pub fn decode_request<T: DeserializeOwned>(&self, frame: &[u8]) -> Result<(NegotiatedVersion, T)> {
+        // Read the version first so an unsupported client gets the upgrade error
+        // rather than a decoding error for a payload shape it doesn't know
+        #[derive(Deserialize)]
+        struct VersionHeader {
+            #[serde(default = "unversioned_frame")]
+            protocol_version: u16,
+        }
+        let header: VersionHeader = serde_json::from_slice(frame)
+            .map_err(|e| BridgeError::Serialization(e.into()))?;
+        let negotiated = self.negotiate(header.protocol_version)?;
+
+        let request = WireEnvelope::<T>::decode(frame)?;
+        Ok((NegotiatedVersion { trace_context: request.trace_context, ..negotiated }, request.payload))
+    }
+}
@@ -4,30 +4,459 @@ QUIC server implementation
 High-performance QUIC server for accepting bridge connections.
 */
 
-use crate::error::{BridgeError, Result};
-use crate::transport::{ServerConfig, SecurityConfig};
-use tracing::{debug, instrument};
+use crate::error::{BridgeError, ConnectionClose, NetworkError, Result};
+use crate::transport::{
+    NegotiatedVersion, PriorityScheduler, ProtocolConfig, SendSlot, ServerConfig, SecurityConfig,
+    StreamPriorityConfig, TrafficClass,
+};
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tracing::{debug, info, instrument, warn};
 
 /// QUIC server wrapper
 pub struct QuicServer {
     config: ServerConfig,
     security: SecurityConfig,
+    stream_priority: StreamPriorityConfig,
+    state: Arc<ServerState>,
+}
+
+/// Shared accept/drain state for the server and its connections
+struct ServerState {
+    draining: AtomicBool,
+    next_connection_id: AtomicU64,
+    connections: Mutex<HashMap<u64, Arc<ConnectionState>>>,
+    in_flight_streams: AtomicUsize,
+    streams_finished: Notify,
+    protocol: ProtocolConfig,
+}
+
+/// Per-connection state
+struct ConnectionState {
+    in_flight_streams: AtomicUsize,
+    /// Set once the connection is closed; the first close wins
+    close: Mutex<Option<ConnectionClose>>,
+    last_activity: Mutex<Instant>,
+    /// Orders sends across the connection's streams by traffic class
+    scheduler: Arc<PriorityScheduler>,
+}
+
+impl ConnectionState {
+    fn close(&self, close: ConnectionClose) {
+        self.close.lock().get_or_insert(close);
+    }
+}
+
+/// Accepted server-side connection
+pub struct ServerConnection {
+    id: u64,
+    peer_address: SocketAddr,
+    connection: Arc<ConnectionState>,
+    server: Arc<ServerState>,
+}
+
+/// Guard for an in-flight request stream; the stream completes on drop
+pub struct StreamGuard {
+    class: TrafficClass,
+    connection: Arc<ConnectionState>,
+    server: Arc<ServerState>,
+}
+
+/// Outcome of a graceful shutdown
+#[derive(Debug, Clone, Default)]
+pub struct DrainReport {
+    /// Whether all in-flight streams finished before the timeout
+    pub drained: bool,
+    /// Streams still in flight when the remaining connections were closed
+    pub streams_aborted: usize,
+    /// Connections sent a GOAWAY and closed
+    pub connections_closed: usize,
 }
 
 impl QuicServer {
     pub async fn new(config: ServerConfig, security: SecurityConfig) -> Result<Self> {
-        Ok(Self { config, security })
+        let protocol = config.protocol.clone();
+        Ok(Self {
+            config,
+            security,
+            stream_priority: StreamPriorityConfig::default(),
+            state: Arc::new(ServerState {
+                draining: AtomicBool::new(false),
+                next_connection_id: AtomicU64::new(1),
+                connections: Mutex::new(HashMap::new()),
+                in_flight_streams: AtomicUsize::new(0),
+                streams_finished: Notify::new(),
+                protocol,
+            }),
+        })
+    }
+
+    /// Use custom stream priorities for accepted connections
+    pub fn with_stream_priority(mut self, stream_priority: StreamPriorityConfig) -> Self {
+        self.stream_priority = stream_priority;
+        self
     }
 
     #[instrument(skip(self))]
     pub async fn start(&self) -> Result<()> {
         debug!("Starting QUIC server on {}", self.config.bind_address);
-        
+
         // TODO: Implement actual GQUIC server
         Ok(())
     }
 
+    /// Accept a new incoming connection
+    pub fn accept_connection(&self, peer_address: SocketAddr) -> Result<ServerConnection> {
+        if self.is_draining() {
+            debug!("Refusing connection from {} while draining", peer_address);
+            return Err(BridgeError::Network(NetworkError::ServerDraining));
+        }
+
+        let id = self.state.next_connection_id.fetch_add(1, Ordering::SeqCst);
+        let connection = Arc::new(ConnectionState {
+            in_flight_streams: AtomicUsize::new(0),
+            close: Mutex::new(None),
+            last_activity: Mutex::new(Instant::now()),
+            scheduler: Arc::new(PriorityScheduler::new(self.stream_priority.clone())),
+        });
+        self.state.connections.lock().insert(id, connection.clone());
+
+        Ok(ServerConnection {
+            id,
+            peer_address,
+            connection,
+            server: self.state.clone(),
+        })
+    }
+
+    /// Stop accepting connections, wait for in-flight streams up to `timeout`,
+    /// then close all remaining connections with a GOAWAY
+    #[instrument(skip(self))]
+    pub async fn graceful_shutdown(&self, timeout: Duration) -> Result<DrainReport> {
+        info!("Draining QUIC server on {}", self.config.bind_address);
+        self.state.draining.store(true, Ordering::SeqCst);
+
+        let drained = tokio::time::timeout(timeout, async {
+            loop {
+                let notified = self.state.streams_finished.notified();
+                if self.state.in_flight_streams.load(Ordering::SeqCst) == 0 {
+                    break;
+                }
+                notified.await;
+            }
+        })
+        .await
+        .is_ok();
+
+        let streams_aborted = self.state.in_flight_streams.load(Ordering::SeqCst);
+        if !drained {
+            warn!("Drain timeout reached with {} streams in flight", streams_aborted);
+        }
+
+        let connections: Vec<_> = self.state.connections.lock().drain().collect();
+        for (_, connection) in &connections {
+            connection.close(ConnectionClose::application(0, "server shutting down", false));
+        }
+
+        let report = DrainReport {
+            drained,
+            streams_aborted,
+            connections_closed: connections.len(),
+        };
+
+        info!("QUIC server shut down: {:?}", report);
+        Ok(report)
+    }
+
+    /// Close connections idle for longer than the configured timeout
+    pub fn close_idle_connections(&self, now: Instant) -> usize {
+        let idle: Vec<_> = self.state.connections.lock()
+            .iter()
+            .filter(|(_, c)| c.close.lock().is_none())
+            .filter(|(_, c)| now.saturating_duration_since(*c.last_activity.lock()) >= self.config.max_idle_timeout)
+            .map(|(id, c)| (*id, c.clone()))
+            .collect();
+
+        for (id, connection) in &idle {
+            debug!("Closing idle connection {}", id);
+            connection.close(ConnectionClose::idle_timeout());
+        }
+        idle.len()
+    }
+
+    /// Whether the server is draining and refusing new connections
+    pub fn is_draining(&self) -> bool {
+        self.state.draining.load(Ordering::SeqCst)
+    }
+
+    /// Number of currently open connections
+    pub fn active_connections(&self) -> usize {
+        self.state.connections.lock().len()
+    }
+
     pub fn is_healthy(&self) -> bool {
-        true // TODO: Implement actual health check
+        !self.is_draining()
+    }
+}
+
+impl ServerConnection {
+    /// Connection identifier
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Remote peer address
+    pub fn peer_address(&self) -> SocketAddr {
+        self.peer_address
+    }
+
+    /// Begin handling a best-effort request stream on this connection
+    pub fn open_stream(&self) -> Result<StreamGuard> {
+        self.open_stream_with_class(TrafficClass::Query)
+    }
+
+    /// Begin handling a request stream carrying `class` traffic
+    pub fn open_stream_with_class(&self, class: TrafficClass) -> Result<StreamGuard> {
+        if let Some(close) = self.close_reason() {
+            return Err(BridgeError::Network(NetworkError::ConnectionClosed {
+                endpoint: self.peer_address.to_string(),
+                close,
+            }));
+        }
+
+        *self.connection.last_activity.lock() = Instant::now();
+
+        self.connection.in_flight_streams.fetch_add(1, Ordering::SeqCst);
+        self.server.in_flight_streams.fetch_add(1, Ordering::SeqCst);
+
+        Ok(StreamGuard {
+            class,
+            connection: self.connection.clone(),
+            server: self.server.clone(),
+        })
+    }
+
+    /// Decode a versioned request, rejecting clients on unsupported protocol versions.
+    /// Respond with `NegotiatedVersion::respond` so the client gets a version it speaks.
+    pub fn decode_request<T: DeserializeOwned>(&self, frame: &[u8]) -> Result<(NegotiatedVersion, T)> {
+        let (negotiated, request) = self.server.protocol.decode_request(frame).inspect_err(|e| {
+            warn!("Rejected request from {}: {}", self.peer_address, e);
+        })?;
+        if let Some(deprecation) = &negotiated.deprecation {
+            debug!("Client {} on deprecated protocol: {}", self.peer_address, deprecation);
+        }
+        Ok((negotiated, request))
+    }
+
+    /// Whether the connection has been closed (GOAWAY, idle timeout, or error)
+    pub fn is_closed(&self) -> bool {
+        self.connection.close.lock().is_some()
     }
-}
\ No newline at end of file
+
+    /// Why the connection was closed, if it has been
+    pub fn close_reason(&self) -> Option<ConnectionClose> {
+        self.connection.close.lock().clone()
+    }
+
+    /// Record that the connection was closed, e.g. from the QUIC connection error
+    pub fn close(&self, close: ConnectionClose) {
+        self.connection.close(close);
+    }
+
+    /// Streams currently in flight on this connection
+    pub fn in_flight_streams(&self) -> usize {
+        self.connection.in_flight_streams.load(Ordering::SeqCst)
+    }
+}
+
+impl StreamGuard {
+    /// Traffic class the stream was opened with
+    pub fn class(&self) -> TrafficClass {
+        self.class
+    }
+
+    /// Wait for this stream's turn to send, ahead of lower-priority streams
+    pub async fn send_slot(&self) -> SendSlot {
+        self.connection.scheduler.acquire(self.class).await
+    }
+}
+
+impl Drop for ServerConnection {
+    fn drop(&mut self) {
+        self.server.connections.lock().remove(&self.id);
+    }
+}
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        self.connection.in_flight_streams.fetch_sub(1, Ordering::SeqCst);
+        self.server.in_flight_streams.fetch_sub(1, Ordering::SeqCst);
+        self.server.streams_finished.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::TransportConfig;
+
+    async fn test_server() -> QuicServer {
+        let config = TransportConfig::default();
+        QuicServer::new(config.server, config.security).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_graceful_shutdown_drains_in_flight_requests() {
+        let server = Arc::new(test_server().await);
+        let peer: SocketAddr = "127.0.0.1:40000".parse().unwrap();
+
+        let connection = server.accept_connection(peer).unwrap();
+        let stream = connection.open_stream().unwrap();
+
+        let shutdown = {
+            let server = server.clone();
+            tokio::spawn(async move { server.graceful_shutdown(Duration::from_secs(5)).await })
+        };
+
+        // Wait for the drain to begin, then verify new connections are refused
+        while !server.is_draining() {
+            tokio::task::yield_now().await;
+        }
+        assert!(matches!(
+            server.accept_connection(peer),
+            Err(BridgeError::Network(NetworkError::ServerDraining))
+        ));
+
+        // The in-flight request completes during the drain
+        drop(stream);
+
+        let report = shutdown.await.unwrap().unwrap();
+        assert!(report.drained);
+        assert_eq!(report.streams_aborted, 0);
+        assert_eq!(report.connections_closed, 1);
+        assert!(connection.is_closed());
+    }
+
+    #[tokio::test]
+    async fn test_graceful_shutdown_timeout_closes_connections() {
+        let server = test_server().await;
+        let connection = server.accept_connection("127.0.0.1:40001".parse().unwrap()).unwrap();
+        let _stream = connection.open_stream().unwrap();
+
+        let report = server.graceful_shutdown(Duration::from_millis(10)).await.unwrap();
+        assert!(!report.drained);
+        assert_eq!(report.streams_aborted, 1);
+        assert!(connection.is_closed());
+        assert!(connection.open_stream().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_close_reasons_are_distinguishable() {
+        let server = test_server().await;
+        let idle = server.accept_connection("127.0.0.1:40002".parse().unwrap()).unwrap();
+        let closed = server.accept_connection("127.0.0.1:40003".parse().unwrap()).unwrap();
+
+        closed.close(ConnectionClose::application(0x2a, "client going away", true));
+        let later = Instant::now() + TransportConfig::default().server.max_idle_timeout;
+        assert_eq!(server.close_idle_connections(later), 1);
+
+        let close_of = |connection: &ServerConnection| match connection.open_stream() {
+            Err(BridgeError::Network(NetworkError::ConnectionClosed { close, .. })) => close,
+            other => panic!("expected a connection-closed error, got {:?}", other.err()),
+        };
+
+        let idle_close = close_of(&idle);
+        assert_eq!(idle_close, ConnectionClose::idle_timeout());
+        assert!(!idle_close.peer_initiated);
+        assert!(BridgeError::Network(NetworkError::ConnectionClosed {
+            endpoint: String::new(),
+            close: idle_close.clone(),
+        }).is_retryable());
+
+        let app_close = close_of(&closed);
+        assert_eq!(app_close.kind, crate::error::CloseKind::ApplicationClosed);
+        assert_eq!(app_close.code, Some(0x2a));
+        assert_eq!(app_close.reason, "client going away");
+        assert!(app_close.peer_initiated);
+
+        assert_eq!(idle_close.to_string(), "idle timeout (local)");
+        assert_eq!(app_close.to_string(), "application close (peer), code 0x2a: client going away");
+    }
+
+    #[tokio::test]
+    async fn test_settlement_stream_progresses_under_query_contention() {
+        let server = test_server().await.with_stream_priority(StreamPriorityConfig {
+            send_slots_per_connection: 1,
+            ..StreamPriorityConfig::default()
+        });
+        let connection = server.accept_connection("127.0.0.1:40004".parse().unwrap()).unwrap();
+
+        let queries: Vec<_> = (0..3).map(|_| connection.open_stream().unwrap()).collect();
+        let settlement = connection.open_stream_with_class(TrafficClass::Settlement).unwrap();
+        assert_eq!(settlement.class(), TrafficClass::Settlement);
+
+        // A query is mid-send; the rest of the queries queue before the settlement stream
+        let sending = queries[0].send_slot().await;
+        let (mut waiting_queries, waiting_settlement) = (
+            queries[1..].iter().map(|q| Box::pin(q.send_slot())).collect::<Vec<_>>(),
+            settlement.send_slot(),
+        );
+        for query in waiting_queries.iter_mut() {
+            assert!(futures::poll!(query).is_pending());
+        }
+        tokio::pin!(waiting_settlement);
+        assert!(futures::poll!(&mut waiting_settlement).is_pending());
+
+        drop(sending);
+        let slot = tokio::time::timeout(Duration::from_secs(1), &mut waiting_settlement)
+            .await
+            .expect("settlement stream was starved");
+        assert_eq!(slot.priority(), StreamPriorityConfig::default().settlement_priority);
+        for query in waiting_queries.iter_mut() {
+            assert!(futures::poll!(query).is_pending());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_older_protocol_served_and_unsupported_rejected() {
+        use crate::transport::{WireEnvelope, PROTOCOL_VERSION};
+
+        let server = test_server().await;
+        let connection = server.accept_connection("127.0.0.1:40003".parse().unwrap()).unwrap();
+        let request = |version: u16| {
+            let mut envelope = WireEnvelope::new("balance".to_string());
+            envelope.protocol_version = version;
+            envelope.encode().unwrap()
+        };
+
+        // Current clients are served without a notice
+        let (negotiated, payload) = connection.decode_request::<String>(&request(PROTOCOL_VERSION)).unwrap();
+        assert_eq!((negotiated.version, payload.as_str()), (PROTOCOL_VERSION, "balance"));
+        assert!(negotiated.deprecation.is_none());
+
+        // An older supported client is answered at its own version with a deprecation notice
+        let (negotiated, _) = connection.decode_request::<String>(&request(1)).unwrap();
+        let response = WireEnvelope::<u64>::decode(&negotiated.respond(42u64).encode().unwrap()).unwrap();
+        assert_eq!((response.protocol_version, response.payload), (1, 42));
+        assert!(response.deprecation.unwrap().contains("upgrade"));
+
+        // Clients older than the minimum are told to upgrade
+        let error = connection.decode_request::<String>(&request(0)).unwrap_err();
+        assert!(matches!(
+            error,
+            BridgeError::Network(NetworkError::UnsupportedProtocolVersion { version: 0, min_supported: 1, .. })
+        ));
+        assert!(error.to_string().contains("upgrade to version 1"), "{}", error);
+
+        // Version 1 frames carry no version field at all
+        let (negotiated, payload) = connection.decode_request::<String>(br#"{"payload":"balance"}"#).unwrap();
+        assert_eq!((negotiated.version, payload.as_str()), (1, "balance"));
+        assert!(negotiated.deprecation.is_some());
+    }
+}
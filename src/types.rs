@@ -55,6 +55,21 @@ pub enum Network {
     },
 }
 
+impl Network {
+    /// EVM-style chain id, if the network has one
+    pub fn chain_id(&self) -> Option<ChainId> {
+        match self {
+            Network::Ethereum { chain_id }
+            | Network::GhostChain { chain_id }
+            | Network::GhostPlane { chain_id }
+            | Network::Polygon { chain_id }
+            | Network::Arbitrum { chain_id }
+            | Network::Custom { chain_id, .. } => Some(chain_id.clone()),
+            Network::Bitcoin { .. } => None,
+        }
+    }
+}
+
 /// Bitcoin network types
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum BitcoinNetwork {
@@ -132,8 +147,9 @@ impl TokenAmount {
     }
 }
 
-/// 256-bit unsigned integer for large token amounts
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// 256-bit unsigned integer for large token amounts. Big-endian bytes, so
+/// the derived ordering is numeric ordering.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct U256(pub [u8; 32]);
 
 impl U256 {
@@ -158,11 +174,77 @@ impl U256 {
         ])
     }
 
+    /// Nearest `f64`, for ratios and display; exact up to 2^53
+    pub fn to_f64(&self) -> f64 {
+        self.0.iter().fold(0.0, |value, byte| value * 256.0 + *byte as f64)
+    }
+
     /// Check if zero
     pub fn is_zero(&self) -> bool {
         self.0.iter().all(|&b| b == 0)
     }
 
+    /// Full-width addition, `None` on overflow
+    pub fn checked_add(&self, other: &U256) -> Option<U256> {
+        let mut result = [0u8; 32];
+        let mut carry = 0u16;
+        for i in (0..32).rev() {
+            let sum = self.0[i] as u16 + other.0[i] as u16 + carry;
+            result[i] = sum as u8;
+            carry = sum >> 8;
+        }
+        (carry == 0).then_some(U256(result))
+    }
+
+    /// Full-width subtraction, `None` if `other` is larger
+    pub fn checked_sub(&self, other: &U256) -> Option<U256> {
+        let mut result = [0u8; 32];
+        let mut borrow = 0i16;
+        for i in (0..32).rev() {
+            let mut difference = self.0[i] as i16 - other.0[i] as i16 - borrow;
+            borrow = (difference < 0) as i16;
+            if difference < 0 {
+                difference += 256;
+            }
+            result[i] = difference as u8;
+        }
+        (borrow == 0).then_some(U256(result))
+    }
+
+    /// Full-width multiplication by a small factor, `None` on overflow
+    pub fn checked_mul_u64(&self, factor: u64) -> Option<U256> {
+        let mut result = [0u8; 32];
+        let mut carry: u128 = 0;
+        for i in (0..32).rev() {
+            carry += self.0[i] as u128 * factor as u128;
+            result[i] = carry as u8;
+            carry >>= 8;
+        }
+        (carry == 0).then_some(U256(result))
+    }
+
+    /// Full-width division by a small divisor, returning quotient and remainder
+    pub fn div_rem_u64(&self, divisor: u64) -> (U256, u64) {
+        assert!(divisor != 0, "division by zero");
+        let mut quotient = [0u8; 32];
+        let mut remainder: u128 = 0;
+        for i in 0..32 {
+            remainder = (remainder << 8) | self.0[i] as u128;
+            quotient[i] = (remainder / divisor as u128) as u8;
+            remainder %= divisor as u128;
+        }
+        (U256(quotient), remainder as u64)
+    }
+
+    /// `self * numerator / denominator` rounded down, `None` on overflow.
+    /// Exact at full width, since the full product is never formed.
+    pub fn mul_div_u64(&self, numerator: u64, denominator: u64) -> Option<U256> {
+        let (quotient, remainder) = self.div_rem_u64(denominator);
+        // remainder < denominator, so this is below numerator
+        let fraction = (remainder as u128 * numerator as u128 / denominator as u128) as u64;
+        quotient.checked_mul_u64(numerator)?.checked_add(&U256::from(fraction))
+    }
+
     /// Simple power operation (for small exponents)
     pub fn pow(&self, exp: U256) -> U256 {
         if exp.is_zero() {
@@ -243,7 +325,25 @@ impl std::ops::Rem for &U256 {
 
 impl std::fmt::Display for U256 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_u64())
+        // Peel off 19 decimal digits at a time so large values print in full
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+        let mut chunks = Vec::new();
+        let mut rest = self.clone();
+        loop {
+            let (quotient, remainder) = rest.div_rem_u64(CHUNK);
+            chunks.push(remainder);
+            if quotient.is_zero() {
+                break;
+            }
+            rest = quotient;
+        }
+
+        let mut chunks = chunks.into_iter().rev();
+        write!(f, "{}", chunks.next().unwrap_or(0))?;
+        for chunk in chunks {
+            write!(f, "{:019}", chunk)?;
+        }
+        Ok(())
     }
 }
 
@@ -281,6 +381,29 @@ impl Transaction {
         TransactionHash(hasher.finalize().into())
     }
 
+    /// Hash of the transaction's content, excluding its id and creation time,
+    /// so a resubmitted copy of the same transfer hashes identically
+    pub fn content_hash(&self) -> TransactionHash {
+        use sha2::{Sha256, Digest};
+
+        let mut hasher = Sha256::new();
+        hasher.update(&bincode::serialize(&self.from_chain).unwrap_or_default());
+        hasher.update(&bincode::serialize(&self.to_chain).unwrap_or_default());
+        hasher.update(&self.from_address.0);
+        hasher.update(&self.to_address.0);
+        hasher.update(&bincode::serialize(&self.amount).unwrap_or_default());
+        hasher.update(&bincode::serialize(&self.fee).unwrap_or_default());
+        hasher.update(&self.nonce.to_be_bytes());
+        hasher.update(&self.data);
+
+        TransactionHash(hasher.finalize().into())
+    }
+
+    /// Canonical, versioned encoding shared with other implementations
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        crate::canonical::encode(self)
+    }
+
     /// Convert to bytes for FFI
     pub fn to_bytes(&self) -> crate::error::Result<Vec<u8>> {
         bincode::serialize(self).map_err(Into::into)
@@ -302,6 +425,16 @@ pub struct MultiTokenFee {
 }
 
 impl MultiTokenFee {
+    /// No fee in any token
+    pub fn zero() -> Self {
+        Self {
+            gcc_fee: TokenAmount::new(TokenType::Gcc, U256::ZERO),
+            spirit_fee: TokenAmount::new(TokenType::Spirit, U256::ZERO),
+            mana_fee: TokenAmount::new(TokenType::Mana, U256::ZERO),
+            ghost_fee: TokenAmount::new(TokenType::Ghost, U256::ZERO),
+        }
+    }
+
     /// Calculate total fee value (simplified)
     pub fn total_value(&self) -> U256 {
         let gcc = &self.gcc_fee.amount;
@@ -364,6 +497,9 @@ pub struct Signature {
     pub r: U256,
     pub s: U256,
     pub v: u8,
+    /// Scheme the signature was produced with; checked against the signer's
+    /// registered key, never assumed
+    pub scheme: crate::security::SignatureScheme,
 }
 
 /// Transaction receipt
@@ -491,6 +627,47 @@ pub struct AssetInfo {
     pub supported_networks: Vec<Network>,
 }
 
+/// Fixtures shared by unit tests across the crate
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::*;
+    use crate::settlement::SettlementBatch;
+
+    /// GCC transfer on GhostPlane between the addresses filled with `from`
+    /// and `to`, paying no fee
+    pub fn transfer(from: u8, to: u8, amount: u64) -> Transaction {
+        Transaction {
+            id: Uuid::new_v4(),
+            from_chain: Network::GhostPlane { chain_id: ChainId::GHOSTPLANE },
+            to_chain: Network::GhostPlane { chain_id: ChainId::GHOSTPLANE },
+            from_address: Address([from; 20]),
+            to_address: Address([to; 20]),
+            amount: TokenAmount::new(TokenType::Gcc, U256::from(amount)),
+            fee: MultiTokenFee::zero(),
+            nonce: 0,
+            data: vec![],
+            signature: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Empty batch moving the state root from `[0; 32]` to `[1; 32]`
+    pub fn batch(batch_id: &str) -> SettlementBatch {
+        SettlementBatch {
+            batch_id: batch_id.to_string(),
+            transactions: vec![],
+            state_root: vec![1u8; 32],
+            previous_state_root: vec![0u8; 32],
+            block_number: 1,
+            merkle_proof: vec![],
+            zk_proof: None,
+            created_at: SystemTime::now(),
+            gas_used: 0,
+            fee_paid: TokenAmount::new(TokenType::Gcc, U256::ZERO),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -512,6 +689,14 @@ mod tests {
         assert_eq!((&a / &b).to_u64(), 2);
     }
 
+    #[test]
+    fn test_u256_display_is_full_width() {
+        assert_eq!(U256::ZERO.to_string(), "0");
+        assert_eq!(U256::from(10_000_000_000_000_000_000).to_string(), "10000000000000000000");
+        let two_pow_64 = U256::from(u64::MAX).checked_add(&U256::ONE).unwrap();
+        assert_eq!(two_pow_64.to_string(), "18446744073709551616");
+    }
+
     #[test]
     fn test_address_hex() {
         let addr = Address::from_hex("0x742d35Cc6634C0532925a3b8D431Df45C3f8D23B").unwrap();